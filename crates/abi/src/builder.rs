@@ -1,11 +1,19 @@
 use crate::elements::{
-    Component, Contract, Event, EventField, FuncInput, FuncOutput, FuncType, Function, JsonAbi,
-    ModuleAbis,
+    Component, Contract, DecodingManifest, Error, Event, EventField, FuncInput, FuncOutput,
+    FuncType, Function, InterfaceManifest, JsonAbi, ManifestError, ManifestEvent, ManifestFunction,
+    ManifestInterface, ManifestStruct, ManifestStructField, ModuleAbis, StateMutability,
 };
+use crate::utils::{event_topic, func_selector, ERC165_INTERFACE_ID};
 use crate::AbiError;
-use fe_analyzer::namespace::items::{ContractId, FunctionId, ModuleId};
+use fe_analyzer::namespace::items::{
+    walk_local_dependencies, ContractId, EventId, FunctionId, IngotId, Item, ModuleId, StructId,
+    TypeDef, SUPPORTS_INTERFACE,
+};
+use fe_analyzer::namespace::type_id::TypeId;
 use fe_analyzer::namespace::types;
 use fe_analyzer::AnalyzerDb;
+use fe_parser::ast;
+use indexmap::IndexSet;
 
 /// Parse a map of contract ABIs from the input `module`.
 pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> Result<ModuleAbis, AbiError> {
@@ -26,30 +34,14 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> Result<ModuleAbis, AbiEr
 }
 
 fn contract_def(db: &dyn AnalyzerDb, contract: ContractId) -> Contract {
-    let events = contract
-        .events(db)
-        .iter()
-        .map(|(name, eventid)| {
-            let attributes = eventid.typ(db);
-            Event {
-                name: name.to_string(),
-                typ: "event".to_string(),
-                fields: attributes
-                    .fields
-                    .iter()
-                    .map(|field| {
-                        let typ = field.typ.clone().expect("event field type error");
-                        EventField {
-                            name: field.name.to_string(),
-                            typ: typ.abi_json_name(),
-                            indexed: field.is_indexed,
-                            components: components(db, &typ),
-                        }
-                    })
-                    .collect(),
-                anonymous: false,
-            }
-        })
+    let events = contract_events(db, contract)
+        .into_iter()
+        .map(|event| event_def(db, &event.name(db), event))
+        .collect();
+
+    let errors = revert_errors(db, contract)
+        .into_iter()
+        .map(|struct_| error_def(db, struct_))
         .collect();
 
     let mut functions = contract
@@ -58,11 +50,367 @@ fn contract_def(db: &dyn AnalyzerDb, contract: ContractId) -> Contract {
         .map(|(name, func)| function_def(db, name, *func, FuncType::Function))
         .collect::<Vec<_>>();
 
+    if contract.supports_erc165(db) {
+        functions.push(supports_interface_def());
+    }
     if let Some(init_fn) = contract.init_function(db) {
         functions.push(function_def(db, "", init_fn, FuncType::Constructor));
     }
+    if let Some(receive_fn) = contract.receive_function(db) {
+        functions.push(function_def(db, "", receive_fn, FuncType::Receive));
+    }
+    if let Some(fallback_fn) = contract.fallback_function(db) {
+        functions.push(function_def(db, "", fallback_fn, FuncType::Fallback));
+    }
+
+    Contract {
+        events,
+        errors,
+        functions,
+    }
+}
+
+fn event_def(db: &dyn AnalyzerDb, name: &str, event: EventId) -> Event {
+    let attributes = event.typ(db);
+    Event {
+        name: name.to_string(),
+        typ: "event".to_string(),
+        fields: attributes
+            .fields
+            .iter()
+            .map(|field| {
+                let typ = field.typ.clone().expect("event field type error");
+                EventField {
+                    name: field.name.to_string(),
+                    typ: typ.abi_json_name(),
+                    indexed: field.is_indexed,
+                    components: components(db, &typ),
+                }
+            })
+            .collect(),
+        anonymous: attributes.anonymous,
+    }
+}
+
+/// Collects the events and errors of all contracts in the ingot, and all of
+/// its structs. An event or error that's shared by several contracts, e.g.
+/// because they use the same struct to revert, appears once.
+pub fn decoding_manifest(db: &dyn AnalyzerDb, ingot: IngotId) -> DecodingManifest {
+    let mut manifest = DecodingManifest::default();
+
+    for module in ingot.all_modules(db).iter() {
+        for contract in module.all_contracts(db).iter() {
+            let contract_name = contract.name(db).to_string();
+
+            for event_id in contract_events(db, *contract) {
+                let name = &event_id.name(db);
+                let event = event_def(db, name, event_id);
+                match manifest.events.iter_mut().find(|item| item.event == event) {
+                    Some(item) => item.contracts.push(contract_name.clone()),
+                    None => {
+                        let params = event_id
+                            .typ(db)
+                            .fields
+                            .iter()
+                            .map(|field| {
+                                selector_name(
+                                    db,
+                                    field.typ.as_ref().expect("event field type error"),
+                                )
+                            })
+                            .collect::<Vec<_>>();
+                        manifest.events.push(ManifestEvent {
+                            signature: signature(name, &params),
+                            topic: event_topic(name, &params),
+                            contracts: vec![contract_name.clone()],
+                            event,
+                        })
+                    }
+                }
+            }
+
+            for struct_ in revert_errors(db, *contract) {
+                let error = error_def(db, struct_);
+                match manifest.errors.iter_mut().find(|item| item.error == error) {
+                    Some(item) => item.contracts.push(contract_name.clone()),
+                    None => {
+                        let params = struct_field_types(db, struct_)
+                            .iter()
+                            .map(|typ| selector_name(db, typ))
+                            .collect::<Vec<_>>();
+                        manifest.errors.push(ManifestError {
+                            signature: signature(&error.name, &params),
+                            selector: func_selector(&error.name, &params),
+                            contracts: vec![contract_name.clone()],
+                            error,
+                        })
+                    }
+                }
+            }
+        }
+
+        for struct_ in module.all_structs(db).iter() {
+            let types = struct_field_types(db, *struct_);
+            if !types.iter().all(is_abi_encodable) {
+                continue;
+            }
+            manifest.structs.push(ManifestStruct {
+                name: struct_.name(db).to_string(),
+                module: module.file_path_relative_to_src_dir(db).to_string(),
+                type_id: TypeId::of(db, &types::Type::Struct(struct_.typ(db).as_ref().clone()))
+                    .to_string(),
+                fields: struct_
+                    .fields(db)
+                    .keys()
+                    .zip(types)
+                    .map(|(name, typ)| ManifestStructField {
+                        name: name.to_string(),
+                        typ: typ.abi_json_name(),
+                        internal_type: typ.to_string(),
+                    })
+                    .collect(),
+            })
+        }
+    }
+    manifest
+}
+
+/// Collects the interfaces that are declared in the modules of the ingot,
+/// with the signature and selector of each of their functions.
+pub fn interface_manifest(db: &dyn AnalyzerDb, ingot: IngotId) -> InterfaceManifest {
+    let mut manifest = InterfaceManifest::default();
+
+    for module in ingot.all_modules(db).iter() {
+        for item in module.all_items(db).iter() {
+            let interface = match item {
+                Item::Type(TypeDef::Contract(id)) if id.is_interface(db) => *id,
+                _ => continue,
+            };
+            let functions = interface
+                .public_functions(db)
+                .iter()
+                .map(|(name, func)| {
+                    let sig = func.signature(db);
+                    let params = selector_params(db, *func);
+                    let return_type = sig
+                        .return_type
+                        .as_ref()
+                        .expect("function return type error");
+                    ManifestFunction {
+                        function: function_def(db, name, *func, FuncType::Function),
+                        signature: signature(name, &params),
+                        selector: function_selector(db, *func),
+                        returns: if return_type.is_unit() {
+                            String::new()
+                        } else {
+                            selector_name(db, return_type)
+                        },
+                    }
+                })
+                .collect();
+
+            manifest.interfaces.push(ManifestInterface {
+                name: interface.name(db).to_string(),
+                module: module.file_path_relative_to_src_dir(db).to_string(),
+                type_id: TypeId::of(
+                    db,
+                    &types::Type::Contract(types::Contract::from_id(interface, db)),
+                )
+                .to_string(),
+                functions,
+            })
+        }
+    }
+    manifest
+}
+
+/// The selector of a public function of a contract or an interface, which is
+/// the one pinned with `#[selector(..)]` if there is one.
+pub fn function_selector(db: &dyn AnalyzerDb, function: FunctionId) -> String {
+    function.selector(db).to_string()
+}
+
+/// The ERC-165 identifiers of the interfaces that a contract with the
+/// `#[erc165]` attribute supports: ERC-165 itself, the interface made of the
+/// contract's public functions, and the interfaces that the contract is
+/// declared to implement. Each identifier is the XOR of the selectors of the
+/// functions of the interface. `0xffffffff` is never supported.
+pub fn erc165_interface_ids(db: &dyn AnalyzerDb, contract: ContractId) -> Vec<u32> {
+    let interface_id = |contract: ContractId| {
+        contract
+            .public_functions(db)
+            .values()
+            .fold(0, |id, function| id ^ function.selector(db).0)
+    };
+
+    let mut ids = IndexSet::new();
+    ids.insert(ERC165_INTERFACE_ID);
+    if !contract.public_functions(db).is_empty() {
+        ids.insert(interface_id(contract));
+    }
+    for interface in contract.implemented_interfaces(db) {
+        ids.insert(interface_id(interface));
+    }
+    ids.into_iter().filter(|id| *id != 0xffffffff).collect()
+}
+
+/// The generated `supportsInterface(bytes4)` function of ERC-165.
+fn supports_interface_def() -> Function {
+    Function {
+        name: SUPPORTS_INTERFACE.to_string(),
+        typ: FuncType::Function,
+        inputs: vec![FuncInput {
+            name: "interfaceId".to_string(),
+            typ: "bytes4".to_string(),
+            components: vec![],
+        }],
+        outputs: vec![FuncOutput {
+            name: "".to_string(),
+            typ: "bool".to_string(),
+            components: vec![],
+        }],
+        state_mutability: StateMutability::View,
+    }
+}
+
+fn selector_params(db: &dyn AnalyzerDb, function: FunctionId) -> Vec<String> {
+    function
+        .signature(db)
+        .params
+        .iter()
+        .map(|param| {
+            selector_name(
+                db,
+                param.typ.as_ref().expect("function parameter type error"),
+            )
+        })
+        .collect()
+}
+
+fn signature(name: &str, params: &[String]) -> String {
+    format!("{}({})", name, params.join(","))
+}
+
+fn struct_field_types(db: &dyn AnalyzerDb, struct_: StructId) -> Vec<types::FixedSize> {
+    struct_
+        .fields(db)
+        .values()
+        .map(|field| field.typ(db).expect("struct field type error"))
+        .collect()
+}
+
+fn is_abi_encodable(typ: &types::FixedSize) -> bool {
+    match typ {
+        types::FixedSize::Base(base) => *base != types::Base::Unit,
+        types::FixedSize::Tuple(types::Tuple { items }) => items.iter().all(is_abi_encodable),
+        types::FixedSize::Vec(_) | types::FixedSize::Function(_) => false,
+        _ => true,
+    }
+}
 
-    Contract { events, functions }
+/// The name of the type in a canonical signature, in which tuples are written
+/// as the list of their component types, e.g. `(uint256,bool)`.
+fn selector_name(db: &dyn AnalyzerDb, typ: &types::FixedSize) -> String {
+    let tuple = |types: Vec<types::FixedSize>| {
+        let names = types
+            .iter()
+            .map(|typ| selector_name(db, typ))
+            .collect::<Vec<_>>();
+        format!("({})", names.join(","))
+    };
+    match typ {
+        types::FixedSize::Struct(types::Struct { id, .. }) => tuple(struct_field_types(db, *id)),
+        types::FixedSize::Enum(types::Enum { id, .. }) => {
+            tuple(id.repr_fields(db).into_iter().map(|(_, typ)| typ).collect())
+        }
+        types::FixedSize::Tuple(types::Tuple { items }) => tuple(items.to_vec()),
+        _ => typ.abi_json_name(),
+    }
+}
+
+/// The events that are defined in the contract, followed by the module-level
+/// events that are emitted by functions reachable from the contract's public
+/// functions or its `__init__` function.
+fn contract_events(db: &dyn AnalyzerDb, contract: ContractId) -> IndexSet<EventId> {
+    let root = Item::Type(TypeDef::Contract(contract));
+    let mut events = contract
+        .events(db)
+        .values()
+        .copied()
+        .collect::<IndexSet<_>>();
+    let mut collect = |item| {
+        if let Item::Function(function) = item {
+            events.extend(
+                function
+                    .body(db)
+                    .emits
+                    .values()
+                    .filter(|event| event.data(db).contract.is_none()),
+            );
+        }
+    };
+
+    walk_local_dependencies(&contract.runtime_dependency_graph(db), root, &mut collect);
+    if let Some(init_fn) = contract.init_function(db) {
+        walk_local_dependencies(
+            &init_fn.dependency_graph(db),
+            Item::Function(init_fn),
+            &mut collect,
+        );
+    }
+    events
+}
+
+/// All structs that are used as `revert` errors by functions reachable from
+/// the contract's public functions or its `__init__` function.
+fn revert_errors(db: &dyn AnalyzerDb, contract: ContractId) -> IndexSet<StructId> {
+    let root = Item::Type(TypeDef::Contract(contract));
+    let mut errors = IndexSet::new();
+    let mut collect = |item| {
+        if let Item::Function(function) = item {
+            let body = function.body(db);
+            ast::for_each_stmt(&function.data(db).ast.kind.body, &mut |stmt| {
+                if let ast::FuncStmt::Revert { error: Some(node) } = stmt {
+                    if let Some(types::Type::Struct(types::Struct { id, .. })) =
+                        body.expressions.get(&node.id).map(|attr| &attr.typ)
+                    {
+                        errors.insert(*id);
+                    }
+                }
+            });
+        }
+    };
+
+    walk_local_dependencies(&contract.runtime_dependency_graph(db), root, &mut collect);
+    if let Some(init_fn) = contract.init_function(db) {
+        walk_local_dependencies(
+            &init_fn.dependency_graph(db),
+            Item::Function(init_fn),
+            &mut collect,
+        );
+    }
+    errors
+}
+
+fn error_def(db: &dyn AnalyzerDb, struct_: StructId) -> Error {
+    let inputs = struct_
+        .fields(db)
+        .iter()
+        .map(|(name, field)| {
+            let typ = field.typ(db).expect("struct field type error");
+            FuncInput {
+                name: name.to_string(),
+                typ: typ.abi_json_name(),
+                components: components(db, &typ),
+            }
+        })
+        .collect();
+
+    Error {
+        name: struct_.name(db).to_string(),
+        typ: "error".to_string(),
+        inputs,
+    }
 }
 
 fn function_def(db: &dyn AnalyzerDb, name: &str, fn_id: FunctionId, typ: FuncType) -> Function {
@@ -92,11 +440,22 @@ fn function_def(db: &dyn AnalyzerDb, name: &str, fn_id: FunctionId, typ: FuncTyp
         }]
     };
 
+    // Calls that transfer ether to a function that isn't `#[payable]` revert.
+    let state_mutability = match (&typ, fn_id.state_mutability(db)) {
+        _ if fn_id.is_payable(db) => StateMutability::Payable,
+        (FuncType::Constructor | FuncType::Fallback, _) | (_, types::StateMutability::Mutable) => {
+            StateMutability::Nonpayable
+        }
+        (_, types::StateMutability::View) => StateMutability::View,
+        (_, types::StateMutability::Pure) => StateMutability::Pure,
+    };
+
     Function {
         name: name.to_string(),
         typ,
         inputs,
         outputs,
+        state_mutability,
     }
 }
 
@@ -113,6 +472,14 @@ fn components(db: &dyn AnalyzerDb, typ: &types::FixedSize) -> Vec<Component> {
                     .abi_json_name(),
             })
             .collect(),
+        types::FixedSize::Enum(types::Enum { id, .. }) => id
+            .repr_fields(db)
+            .iter()
+            .map(|(name, typ)| Component {
+                name: name.to_string(),
+                typ: typ.abi_json_name(),
+            })
+            .collect(),
         types::FixedSize::Tuple(types::Tuple { items }) => items
             .iter()
             .enumerate()
@@ -128,6 +495,8 @@ fn components(db: &dyn AnalyzerDb, typ: &types::FixedSize) -> Vec<Component> {
 #[cfg(test)]
 mod tests {
     use crate::builder;
+    use crate::elements::{FuncType, StateMutability};
+    use crate::utils::{func_selector, ERC165_INTERFACE_ID};
     use fe_analyzer::namespace::items::ModuleId;
     use fe_analyzer::TestDb;
 
@@ -137,16 +506,26 @@ mod tests {
 pub fn add(x: u256, y: u256) -> u256:
   return x + y
 
+#[error]
+struct BadBar:
+  pub code: u256
+
+#[error]
+struct Unused:
+  pub code: u256
+
 contract Foo:
   event Food:
     idx barge: u256
   pub fn __init__(x: address):
     pass
   fn baz(x: address) -> u256:
-    add(10, 20)
+    let sum: u256 = add(10, 20)
     revert
   pub fn bar(x: u256) -> Array<u256, 10>:
-    revert"#;
+    revert BadBar(code: x)
+  fn qux():
+    revert Unused(code: 1)"#;
 
         let mut db = TestDb::default();
         let module = ModuleId::new_standalone(&mut db, "test_module", contract);
@@ -157,6 +536,11 @@ contract Foo:
         if let Some(abi) = abis.get("Foo") {
             // event
             assert_eq!(abi.events[0].name, "Food");
+            // errors reachable from public functions
+            assert_eq!(abi.errors.len(), 1);
+            assert_eq!(abi.errors[0].name, "BadBar");
+            assert_eq!(abi.errors[0].inputs[0].name, "code");
+            assert_eq!(abi.errors[0].inputs[0].typ, "uint256");
             // function count
             assert_eq!(abi.functions.len(), 2);
             // bar
@@ -166,8 +550,440 @@ contract Foo:
             // __init__ always comes after normal functions
             assert_eq!(abi.functions[1].name, "");
             assert_eq!(abi.functions[1].inputs[0].typ, "address",);
+            assert_eq!(
+                abi.functions[1].state_mutability,
+                StateMutability::Nonpayable
+            );
         } else {
             panic!("contract \"Foo\" not found in module")
         }
     }
+
+    #[test]
+    fn errors_reverted_in_nested_blocks() {
+        let src = r#"
+#[error]
+struct InMatch:
+  pub code: u256
+
+#[error]
+struct InIf:
+  pub code: u256
+
+contract Foo:
+  pub fn bar(x: u256):
+    match x:
+      0:
+        revert InMatch(code: x)
+      _:
+        if x > 10:
+          revert InIf(code: x)"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let abi = &builder::module(&db, module).expect("unable to build ABI")["Foo"];
+        let errors = abi
+            .errors
+            .iter()
+            .map(|error| error.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(errors, ["InMatch", "InIf"]);
+    }
+
+    #[test]
+    fn function_state_mutability() {
+        let src = r#"
+fn block_number() -> u256:
+  return block.number
+
+interface Other:
+  pub fn value(self) -> u256
+  pub fn bump(mut self)
+
+contract Counter:
+  count: u256
+  pub fn get(self) -> u256:
+    return self.count
+  pub fn own_address(self) -> address:
+    return self.address
+  pub fn ignores_self(self, x: u256) -> u256:
+    return x
+  pub fn increment(mut self):
+    self.count += 1
+  pub fn double(x: u256) -> u256:
+    return x * 2
+  pub fn sender() -> address:
+    return msg.sender
+  pub fn number() -> u256:
+    return block_number()
+  pub fn peek(other: address) -> u256:
+    return Other(other).value()
+  pub fn poke(other: address):
+    Other(other).bump()
+  #[payable]
+  pub fn deposit():
+    pass
+  pub fn yul_store(x: u256):
+    unsafe:
+      yul(a: x) {
+        sstore(0, a)
+      }
+  pub fn yul_load() -> u256:
+    unsafe:
+      yul() -> (a: u256) {
+        a := sload(0)
+      }
+      return a
+  pub fn yul_add(x: u256) -> u256:
+    unsafe:
+      yul(a: x) -> (b: u256) {
+        b := add(a, 1)
+      }
+      return b
+"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let abis = builder::module(&db, module).expect("unable to build ABI");
+
+        let mutability = abis["Counter"]
+            .functions
+            .iter()
+            .map(|func| (func.name.as_str(), func.state_mutability.clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            mutability,
+            vec![
+                ("get", StateMutability::View),
+                ("own_address", StateMutability::View),
+                ("ignores_self", StateMutability::Pure),
+                ("increment", StateMutability::Nonpayable),
+                ("double", StateMutability::Pure),
+                ("sender", StateMutability::View),
+                ("number", StateMutability::View),
+                ("peek", StateMutability::View),
+                ("poke", StateMutability::Nonpayable),
+                ("deposit", StateMutability::Payable),
+                ("yul_store", StateMutability::Nonpayable),
+                ("yul_load", StateMutability::View),
+                ("yul_add", StateMutability::Pure),
+            ]
+        );
+    }
+
+    #[test]
+    fn fallback_and_receive() {
+        let src = r#"
+contract Vault:
+  deposits: u256
+  pub fn balance(self) -> u256:
+    return self.deposits
+  pub fn __receive__(mut self):
+    self.deposits += msg.value
+  pub fn __fallback__():
+    revert
+"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let abis = builder::module(&db, module).expect("unable to build ABI");
+
+        let functions = abis["Vault"]
+            .functions
+            .iter()
+            .map(|func| {
+                (
+                    func.name.as_str(),
+                    func.typ.clone(),
+                    func.state_mutability.clone(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            functions,
+            vec![
+                ("balance", FuncType::Function, StateMutability::View),
+                ("", FuncType::Receive, StateMutability::Payable),
+                ("", FuncType::Fallback, StateMutability::Nonpayable),
+            ]
+        );
+    }
+
+    #[test]
+    fn erc165() {
+        let src = r#"
+interface Named:
+  pub fn name(self) -> u256
+
+impl Named for Token
+
+#[erc165]
+contract Token:
+  pub fn name(self) -> u256:
+    return 1
+  pub fn burn(mut self, amount: u256):
+    pass
+"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let abis = builder::module(&db, module).expect("unable to build ABI");
+
+        let supports_interface = &abis["Token"].functions[2];
+        assert_eq!(supports_interface.name, "supportsInterface");
+        assert_eq!(supports_interface.inputs[0].typ, "bytes4");
+        assert_eq!(supports_interface.outputs[0].typ, "bool");
+        assert_eq!(
+            format!("{:#010x}", ERC165_INTERFACE_ID),
+            func_selector("supportsInterface", &["bytes4".to_string()])
+        );
+
+        let selector = |name: &str, params: &[&str]| {
+            let params = params
+                .iter()
+                .map(|param| param.to_string())
+                .collect::<Vec<_>>();
+            u32::from_str_radix(&func_selector(name, &params)[2..], 16).unwrap()
+        };
+        let name = selector("name", &[]);
+        let burn = selector("burn", &["uint256"]);
+        let token = module.all_contracts(&db)[0];
+        assert_eq!(
+            builder::erc165_interface_ids(&db, token),
+            vec![ERC165_INTERFACE_ID, name ^ burn, name]
+        );
+    }
+
+    #[test]
+    fn module_abis_are_in_declaration_order() {
+        let src = r#"
+contract Zed:
+  pub fn zz():
+    pass
+  pub fn aa():
+    pass
+  pub fn mm():
+    pass
+
+contract Alpha:
+  pub fn b():
+    pass
+  pub fn a():
+    pass
+"#;
+
+        let build = || {
+            let mut db = TestDb::default();
+            let module = ModuleId::new_standalone(&mut db, "test_module", src);
+            fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+            crate::build(&db, module).expect("unable to build ABI")
+        };
+
+        let abis = build();
+        assert_eq!(abis.keys().collect::<Vec<_>>(), ["Zed", "Alpha"]);
+        for _ in 0..4 {
+            assert_eq!(build(), abis);
+        }
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        let abis = builder::module(&db, module).expect("unable to build ABI");
+        let names = |contract: &str| {
+            abis[contract]
+                .functions
+                .iter()
+                .map(|func| func.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("Zed"), ["zz", "aa", "mm"]);
+        assert_eq!(names("Alpha"), ["b", "a"]);
+    }
+
+    #[test]
+    fn module_level_events() {
+        let src = r#"
+event Transfer:
+  idx from: address
+  idx to: address
+  value: u256
+
+#[anonymous]
+event Ping:
+  idx a: u256
+  idx b: u256
+  idx c: u256
+  idx d: u256
+
+event Unused:
+  x: u256
+
+fn log_ping():
+  emit Ping(a: 1, b: 2, c: 3, d: 4)
+
+contract Foo:
+  event Approval:
+    idx owner: address
+  pub fn __init__():
+    log_ping()
+  pub fn transfer(to: address, value: u256):
+    emit Transfer(from: msg.sender, to, value)
+
+contract Bar:
+  pub fn send(to: address):
+    emit Transfer(from: msg.sender, to, value: 1)
+"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let abis = builder::module(&db, module).expect("unable to build ABI");
+
+        let events = |contract: &str| {
+            abis[contract]
+                .events
+                .iter()
+                .map(|event| (event.name.as_str(), event.anonymous))
+                .collect::<Vec<_>>()
+        };
+        // contract events come first, then the module-level events it emits
+        assert_eq!(
+            events("Foo"),
+            [("Approval", false), ("Transfer", false), ("Ping", true)]
+        );
+        assert_eq!(events("Bar"), [("Transfer", false)]);
+
+        let manifest = builder::decoding_manifest(&db, module.ingot(&db));
+        assert_eq!(
+            manifest.events[1].signature,
+            "Transfer(address,address,uint256)"
+        );
+        assert_eq!(manifest.events[1].contracts, ["Foo", "Bar"]);
+    }
+
+    #[test]
+    fn decoding_manifest() {
+        let src = r#"
+struct Point:
+  pub x: u256
+  pub y: u256
+
+#[error]
+struct NotOwner:
+  pub owner: address
+  pub point: Point
+
+contract Foo:
+  event Moved:
+    idx who: address
+    to: Point
+  pub fn move(p: Point):
+    revert NotOwner(owner: address(0), point: p)
+
+contract Bar:
+  event Moved:
+    idx who: address
+    to: Point
+  event Stopped:
+    flag: bool
+  pub fn stop():
+    revert NotOwner(owner: address(0), point: Point(x: 1, y: 2))
+"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let manifest = builder::decoding_manifest(&db, module.ingot(&db));
+
+        assert_eq!(manifest.events.len(), 2);
+        let moved = &manifest.events[0];
+        assert_eq!(moved.signature, "Moved(address,(uint256,uint256))");
+        assert_eq!(
+            moved.topic,
+            crate::utils::event_topic("Moved", &["address".into(), "(uint256,uint256)".into()])
+        );
+        assert_eq!(moved.contracts, ["Foo", "Bar"]);
+        assert_eq!(manifest.events[1].signature, "Stopped(bool)");
+        assert_eq!(manifest.events[1].contracts, ["Bar"]);
+
+        assert_eq!(manifest.errors.len(), 1);
+        let error = &manifest.errors[0];
+        assert_eq!(error.signature, "NotOwner(address,(uint256,uint256))");
+        // the same selector as Solidity's `error NotOwner(address owner, Point point)`
+        assert_eq!(error.selector, "0x4b7caea6");
+        assert_eq!(error.contracts, ["Foo", "Bar"]);
+
+        let structs = manifest
+            .structs
+            .iter()
+            .map(|struct_| struct_.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(structs, ["Point", "NotOwner"]);
+        assert_eq!(manifest.structs[1].fields[1].typ, "tuple");
+        assert_eq!(manifest.structs[1].fields[1].internal_type, "Point");
+        // The id is derived from the path and the fields of the struct.
+        assert_eq!(
+            manifest.structs[0].type_id,
+            fe_common::utils::keccak::partial(b"struct test_module::Point{x:u256,y:u256}", 16)
+        );
+
+        let json = manifest.json(false).unwrap();
+        assert!(json.contains(r#""name":"Moved","type":"event""#));
+    }
+
+    #[test]
+    fn interface_manifest() {
+        let src = r#"
+struct Point:
+  pub x: u256
+  pub y: u256
+
+interface Token:
+  pub fn transfer(mut self, to: address, amount: u256) -> bool
+  pub fn locate(self, owner: address) -> (Point, bool)
+  pub fn burn(mut self, ids: Array<u256, 2>)
+
+contract Foo:
+  pub fn noop():
+    pass
+"#;
+
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "test_module", src);
+        fe_analyzer::analyze_module(&db, module).expect("failed to analyze source");
+        let manifest = builder::interface_manifest(&db, module.ingot(&db));
+
+        assert_eq!(manifest.interfaces.len(), 1);
+        let token = &manifest.interfaces[0];
+        assert_eq!(token.name, "Token");
+        assert_eq!(token.module, "test_module");
+        let functions = token
+            .functions
+            .iter()
+            .map(|function| {
+                (
+                    function.signature.as_str(),
+                    function.selector.as_str(),
+                    function.returns.as_str(),
+                )
+            })
+            .collect::<Vec<_>>();
+        // The selectors are the same as Solidity's.
+        assert_eq!(
+            functions,
+            [
+                ("transfer(address,uint256)", "0xa9059cbb", "bool"),
+                ("locate(address)", "0x11afebb4", "((uint256,uint256),bool)"),
+                ("burn(uint256[2])", "0x90008227", ""),
+            ]
+        );
+        assert_eq!(token.functions[0].function.inputs[0].name, "to");
+
+        let json = manifest.json(false).unwrap();
+        assert!(json.contains(r#""name":"transfer","type":"function""#));
+    }
 }
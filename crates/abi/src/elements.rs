@@ -1,17 +1,21 @@
 use crate::errors::AbiError;
-use fe_analyzer::namespace::types::{Array, Base, FeString, FixedSize, Integer, Struct, Tuple};
+use fe_analyzer::namespace::types::{
+    Array, Base, Enum, FeString, FixedSize, Integer, Struct, Tuple,
+};
+use indexmap::IndexMap;
 use serde::ser::SerializeSeq;
 use serde::{Serialize, Serializer};
-use std::collections::HashMap;
 
-/// The ABIs for each contract in a Fe module.
-pub type ModuleAbis = HashMap<String, Contract>;
+/// The ABIs for each contract in a Fe module, in declaration order.
+pub type ModuleAbis = IndexMap<String, Contract>;
 
 /// All public interfaces of a Fe contract.
 #[derive(Debug, PartialEq, Clone)]
 pub struct Contract {
     /// All events defined in a contract.
     pub events: Vec<Event>,
+    /// All errors that may be used to revert a contract call.
+    pub errors: Vec<Error>,
     /// All public functions defined in a contract.
     pub functions: Vec<Function>,
 }
@@ -26,6 +30,7 @@ impl Contract {
     pub fn new() -> Self {
         Self {
             events: vec![],
+            errors: vec![],
             functions: vec![],
         }
     }
@@ -53,6 +58,10 @@ impl Serialize for Contract {
             seq.serialize_element(event)?;
         }
 
+        for error in self.errors.iter() {
+            seq.serialize_element(error)?;
+        }
+
         for function in self.functions.iter() {
             seq.serialize_element(function)?;
         }
@@ -83,8 +92,11 @@ impl JsonAbi for FixedSize {
             FixedSize::Base(base) => base.abi_json_name(),
             FixedSize::Tuple(tuple) => tuple.abi_json_name(),
             FixedSize::String(string) => string.abi_json_name(),
+            FixedSize::Vec(_) => panic!("vec type is not abi encodable"),
+            FixedSize::Function(_) => panic!("function type is not abi encodable"),
             FixedSize::Contract(_) => "address".to_string(),
             FixedSize::Struct(val) => val.abi_json_name(),
+            FixedSize::Enum(val) => val.abi_json_name(),
         }
     }
 }
@@ -92,18 +104,8 @@ impl JsonAbi for FixedSize {
 impl JsonAbi for Base {
     fn abi_json_name(&self) -> String {
         match self {
-            Base::Numeric(Integer::U256) => "uint256".to_string(),
-            Base::Numeric(Integer::U128) => "uint128".to_string(),
-            Base::Numeric(Integer::U64) => "uint64".to_string(),
-            Base::Numeric(Integer::U32) => "uint32".to_string(),
-            Base::Numeric(Integer::U16) => "uint16".to_string(),
-            Base::Numeric(Integer::U8) => "uint8".to_string(),
-            Base::Numeric(Integer::I256) => "int256".to_string(),
-            Base::Numeric(Integer::I128) => "int128".to_string(),
-            Base::Numeric(Integer::I64) => "int64".to_string(),
-            Base::Numeric(Integer::I32) => "int32".to_string(),
-            Base::Numeric(Integer::I16) => "int16".to_string(),
-            Base::Numeric(Integer::I8) => "int8".to_string(),
+            Base::Numeric(integer) if integer.is_signed() => format!("int{}", integer.bits()),
+            Base::Numeric(integer) => format!("uint{}", integer.bits()),
             Base::Address => "address".to_string(),
             Base::Bool => "bool".to_string(),
             Base::Unit => panic!("unit type is not abi encodable"),
@@ -127,6 +129,12 @@ impl JsonAbi for Struct {
     }
 }
 
+impl JsonAbi for Enum {
+    fn abi_json_name(&self) -> String {
+        "tuple".to_string()
+    }
+}
+
 impl JsonAbi for Tuple {
     fn abi_json_name(&self) -> String {
         "tuple".to_string()
@@ -171,6 +179,149 @@ pub struct EventField {
     pub components: Vec<Component>,
 }
 
+/// An error interface.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct Error {
+    /// The error's name.
+    pub name: String,
+    /// The type of an error (Always "error").
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// All error fields.
+    pub inputs: Vec<FuncInput>,
+}
+
+/// The events, errors and structs of all contracts in an ingot, which lets
+/// off-chain code decode the logs and revert data of any of the contracts.
+#[derive(Serialize, Debug, PartialEq, Clone, Default)]
+pub struct DecodingManifest {
+    /// All events, deduplicated across contracts.
+    pub events: Vec<ManifestEvent>,
+    /// All errors that may be used to revert a contract call, deduplicated
+    /// across contracts.
+    pub errors: Vec<ManifestError>,
+    /// All ABI encodable structs defined in the ingot.
+    pub structs: Vec<ManifestStruct>,
+}
+
+impl DecodingManifest {
+    /// Serialize the manifest into JSON.
+    pub fn json(&self, prettify: bool) -> Result<String, AbiError> {
+        match prettify {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        }
+        .map_err(|_| AbiError::SerializationFailed)
+    }
+}
+
+/// An event in a decoding manifest.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ManifestEvent {
+    #[serde(flatten)]
+    pub event: Event,
+    /// The canonical signature of the event, e.g. `Transfer(address,uint256)`.
+    pub signature: String,
+    /// The keccak256 hash of the signature, which is the first topic of the
+    /// event's logs unless the event is anonymous.
+    pub topic: String,
+    /// The names of the contracts that define or emit the event.
+    pub contracts: Vec<String>,
+}
+
+/// An error in a decoding manifest.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ManifestError {
+    #[serde(flatten)]
+    pub error: Error,
+    /// The canonical signature of the error, e.g. `NotOwner(address)`.
+    pub signature: String,
+    /// The first 4 bytes of the keccak256 hash of the signature, which
+    /// prefix the revert data.
+    pub selector: String,
+    /// The names of the contracts that may revert with the error.
+    pub contracts: Vec<String>,
+}
+
+/// A struct in a decoding manifest.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ManifestStruct {
+    /// The struct's name.
+    pub name: String,
+    /// The path of the module that defines the struct, relative to the
+    /// ingot's `src` directory.
+    pub module: String,
+    /// The content-based id of the struct type, which is the same in every
+    /// compilation of the ingot.
+    pub type_id: String,
+    /// All struct fields.
+    pub fields: Vec<ManifestStructField>,
+}
+
+/// A single field of a struct in a decoding manifest.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ManifestStructField {
+    /// The field's name.
+    pub name: String,
+    /// The field's type as it appears in the JSON ABI.
+    #[serde(rename = "type")]
+    pub typ: String,
+    /// The field's Fe type, e.g. `u8` or the name of a struct.
+    #[serde(rename = "internalType")]
+    pub internal_type: String,
+}
+
+/// The interfaces that are declared in an ingot, which describe the external
+/// contracts that its contracts call. Other tools can check that a deployed
+/// contract, e.g. one built from Solidity, actually implements them.
+#[derive(Serialize, Debug, PartialEq, Clone, Default)]
+pub struct InterfaceManifest {
+    /// All interfaces, in declaration order.
+    pub interfaces: Vec<ManifestInterface>,
+}
+
+impl InterfaceManifest {
+    /// Serialize the manifest into JSON.
+    pub fn json(&self, prettify: bool) -> Result<String, AbiError> {
+        match prettify {
+            true => serde_json::to_string_pretty(self),
+            false => serde_json::to_string(self),
+        }
+        .map_err(|_| AbiError::SerializationFailed)
+    }
+}
+
+/// An interface in an interface manifest.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ManifestInterface {
+    /// The interface's name.
+    pub name: String,
+    /// The path of the module that declares the interface, relative to the
+    /// ingot's `src` directory.
+    pub module: String,
+    /// The content-based id of the interface type, which is the same in every
+    /// compilation of the ingot.
+    pub type_id: String,
+    /// All functions of the interface.
+    pub functions: Vec<ManifestFunction>,
+}
+
+/// A function of an interface in an interface manifest.
+#[derive(Serialize, Debug, PartialEq, Clone)]
+pub struct ManifestFunction {
+    #[serde(flatten)]
+    pub function: Function,
+    /// The canonical signature of the function, e.g.
+    /// `transfer(address,uint256)`.
+    pub signature: String,
+    /// The first 4 bytes of the keccak256 hash of the signature, which
+    /// prefix the calldata.
+    pub selector: String,
+    /// The canonical name of the return type, e.g. `(uint256,bool)`, or an
+    /// empty string if the function doesn't return anything.
+    pub returns: String,
+}
+
 /// A function interface.
 #[derive(Serialize, Debug, PartialEq, Clone)]
 pub struct Function {
@@ -183,6 +334,9 @@ pub struct Function {
     pub inputs: Vec<FuncInput>,
     /// All function outputs.
     pub outputs: Vec<FuncOutput>,
+    /// Whether the function reads or modifies the state of the chain.
+    #[serde(rename = "stateMutability")]
+    pub state_mutability: StateMutability,
 }
 
 /// Component of an ABI tuple.
@@ -239,7 +393,6 @@ fn should_skip_components(components: &[Component]) -> bool {
 }
 
 /// The type of a public function.
-#[allow(dead_code)]
 #[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum FuncType {
@@ -250,7 +403,6 @@ pub enum FuncType {
 }
 
 /// The mutability of a public function.
-#[allow(dead_code)]
 #[derive(Serialize, Debug, PartialEq, Clone)]
 #[serde(rename_all = "lowercase")]
 pub enum StateMutability {
@@ -262,7 +414,10 @@ pub enum StateMutability {
 
 #[cfg(test)]
 mod tests {
-    use crate::elements::{Contract, Event, EventField, FuncInput, FuncOutput, FuncType, Function};
+    use crate::elements::{
+        Contract, Error, Event, EventField, FuncInput, FuncOutput, FuncType, Function,
+        StateMutability,
+    };
 
     #[test]
     fn contract_json() {
@@ -278,6 +433,15 @@ mod tests {
                 }],
                 anonymous: false,
             }],
+            errors: vec![Error {
+                name: "error_name".to_string(),
+                typ: "error".to_string(),
+                inputs: vec![FuncInput {
+                    name: "input_name".to_string(),
+                    typ: "uint256".to_string(),
+                    components: vec![],
+                }],
+            }],
             functions: vec![Function {
                 name: "function_name".to_string(),
                 typ: FuncType::Function,
@@ -291,6 +455,7 @@ mod tests {
                     typ: "uint256".to_string(),
                     components: vec![],
                 }],
+                state_mutability: StateMutability::View,
             }],
         };
 
@@ -309,11 +474,17 @@ mod tests {
                     ],
                     "anonymous":false
                 },
+                {
+                    "name":"error_name",
+                    "type":"error",
+                    "inputs":[{"name":"input_name","type":"uint256"}]
+                },
                 {
                     "name":"function_name",
                     "type":"function",
                     "inputs":[{"name":"input_name","type":"address"}],
-                    "outputs":[{"name":"output_name","type":"uint256"}]
+                    "outputs":[{"name":"output_name","type":"uint256"}],
+                    "stateMutability":"view"
                 }
             ]"#
             .split_whitespace()
@@ -1,8 +1,8 @@
 //! Fe to ABI builder.
 
-use fe_analyzer::namespace::items::ModuleId;
+use fe_analyzer::namespace::items::{ContractId, FunctionId, IngotId, ModuleId};
 use fe_analyzer::AnalyzerDb;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 mod builder;
 pub mod utils;
@@ -13,8 +13,9 @@ pub mod elements;
 mod errors;
 pub use errors::AbiError;
 
-/// A mapping of contract names and their ABIs.
-pub type NamedAbis = HashMap<ContractName, JsonAbi>;
+/// A mapping of contract names and their ABIs, in the order the contracts
+/// are declared in the module.
+pub type NamedAbis = IndexMap<ContractName, JsonAbi>;
 /// The ABI of a contract as a string.
 pub type JsonAbi = String;
 /// The name of a Fe contract.
@@ -23,7 +24,31 @@ pub type ContractName = String;
 /// Builds ABIs for each contract in the module.
 pub fn build(db: &dyn AnalyzerDb, module: ModuleId) -> Result<NamedAbis, AbiError> {
     builder::module(db, module)?
-        .drain()
+        .into_iter()
         .map(|(name, abi)| abi.json(true).map(|json| (name, json)))
         .collect::<Result<NamedAbis, _>>()
 }
+
+/// Builds a single JSON file with the events, errors and structs of every
+/// contract in the ingot, for decoding the logs and revert data of any of them.
+pub fn build_decoding_manifest(db: &dyn AnalyzerDb, ingot: IngotId) -> Result<String, AbiError> {
+    builder::decoding_manifest(db, ingot).json(true)
+}
+
+/// Builds a JSON file with the interfaces that are declared in the ingot, for
+/// checking that the external contracts they describe actually implement them.
+pub fn build_interface_manifest(db: &dyn AnalyzerDb, ingot: IngotId) -> Result<String, AbiError> {
+    builder::interface_manifest(db, ingot).json(true)
+}
+
+/// The 4 byte selector of a public function of a contract or an interface, as
+/// a hex string, e.g. `0xa9059cbb`.
+pub fn function_selector(db: &dyn AnalyzerDb, function: FunctionId) -> String {
+    builder::function_selector(db, function)
+}
+
+/// The ERC-165 identifiers of the interfaces that a contract with the
+/// `#[erc165]` attribute supports, including ERC-165 itself.
+pub fn erc165_interface_ids(db: &dyn AnalyzerDb, contract: ContractId) -> Vec<u32> {
+    builder::erc165_interface_ids(db, contract)
+}
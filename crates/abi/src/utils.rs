@@ -6,6 +6,10 @@ pub fn event_topic(name: &str, fields: &[String]) -> String {
     hash_signature(name, fields, 32)
 }
 
+/// The ERC-165 identifier of ERC-165 itself, i.e. the selector of
+/// `supportsInterface(bytes4)`.
+pub const ERC165_INTERFACE_ID: u32 = 0x01ffc9a7;
+
 /// Formats the name and params and calculates the 4 byte keccak256 value of the
 /// signature.
 pub fn func_selector(name: &str, params: &[String]) -> String {
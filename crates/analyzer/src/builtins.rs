@@ -1,4 +1,5 @@
-use crate::namespace::types::Base;
+use crate::namespace::types::{Base, StateMutability};
+use fe_parser::ast::{BinOperator, CompOperator};
 use strum::{AsRefStr, EnumIter, EnumString};
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, EnumString, AsRefStr)]
@@ -7,6 +8,12 @@ pub enum ValueMethod {
     Clone,
     ToMem,
     AbiEncode,
+    Concat,
+    Slice,
+    Push,
+    Pop,
+    Len,
+    ToString,
 }
 
 #[derive(
@@ -15,6 +22,11 @@ pub enum ValueMethod {
 #[strum(serialize_all = "snake_case")]
 pub enum GlobalFunction {
     Keccak256,
+    /// `abi_encode(value)`: the abi encoding of a value, as a `Vec<u8>`.
+    AbiEncode,
+    /// `abi_decode<T>(data)`: decodes a `Vec<u8>` into a value of type `T`,
+    /// and reverts if the data isn't a valid encoding of a `T`.
+    AbiDecode,
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, AsRefStr)]
@@ -22,15 +34,35 @@ pub enum GlobalFunction {
 pub enum ContractTypeMethod {
     Create,
     Create2,
+    /// The address at which `create2` deploys the contract, for a deployer
+    /// and a salt.
+    AddressOf,
 }
 
 impl ContractTypeMethod {
     pub fn arg_count(&self) -> usize {
         match self {
             ContractTypeMethod::Create => 1,
-            ContractTypeMethod::Create2 => 2,
+            ContractTypeMethod::Create2 | ContractTypeMethod::AddressOf => 2,
         }
     }
+
+    /// Returns true if the argument at `index` is an address, rather than a
+    /// number.
+    pub fn takes_address(&self, index: usize) -> bool {
+        matches!(self, ContractTypeMethod::AddressOf) && index == 0
+    }
+}
+
+/// Conversions between integer types, called as `u8::try_from(value)`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumString, AsRefStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum IntegerTypeMethod {
+    /// Reverts if the value doesn't fit in the target type.
+    TryFrom,
+    /// Truncates or sign-extends the value to the target type, like a cast,
+    /// but allows changing both the sign and the size at once.
+    UncheckedFrom,
 }
 
 #[derive(
@@ -80,6 +112,33 @@ pub enum ContractSelfField {
     Address,
 }
 
+/// The versions (hard forks) of the EVM that contracts can be compiled for.
+/// Each version has the instructions of the ones before it.
+#[derive(
+    Debug,
+    Default,
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumString,
+    AsRefStr,
+    EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum EvmVersion {
+    Istanbul,
+    Berlin,
+    #[default]
+    London,
+    Paris,
+    Shanghai,
+    Cancun,
+}
+
 /// The evm functions exposed by yul.
 #[allow(non_camel_case_types)]
 #[derive(
@@ -118,8 +177,11 @@ pub enum Intrinsic {
     __mload,          // (p)
     __mstore,         // (p, v) -> ()
     __mstore8,        // (p, v) -> ()
+    __mcopy,          // (t, f, s) -> ()
     __sload,          // (p)
     __sstore,         // (p, v) -> ()
+    __tload,          // (p)
+    __tstore,         // (p, v) -> ()
     __msize,          // ()
     __gas,            // ()
     __address,        // ()
@@ -161,7 +223,10 @@ pub enum Intrinsic {
     __timestamp,      // ()
     __number,         // ()
     __difficulty,     // ()
+    __prevrandao,     // ()
     __gaslimit,       // ()
+    __blobhash,       // (i)
+    __blobbasefee,    // ()
 }
 
 impl Intrinsic {
@@ -171,18 +236,18 @@ impl Intrinsic {
             __stop | __basefee | __origin | __gasprice | __coinbase | __timestamp | __number
             | __difficulty | __gaslimit | __pc | __msize | __gas | __address | __selfbalance
             | __caller | __callvalue | __calldatasize | __codesize | __returndatasize
-            | __invalid | __chainid => 0,
+            | __invalid | __chainid | __prevrandao | __blobbasefee => 0,
 
-            __not | __iszero | __pop | __mload | __balance | __sload | __calldataload
-            | __extcodesize | __extcodehash | __selfdestruct | __blockhash => 1,
+            __not | __iszero | __pop | __mload | __balance | __sload | __tload | __calldataload
+            | __extcodesize | __extcodehash | __selfdestruct | __blockhash | __blobhash => 1,
 
             __add | __sub | __mul | __div | __sdiv | __mod | __smod | __exp | __lt | __gt
             | __slt | __sgt | __eq | __and | __or | __xor | __byte | __shl | __shr | __sar
-            | __signextend | __keccak256 | __mstore | __mstore8 | __sstore | __return
-            | __revert | __log0 => 2,
+            | __signextend | __keccak256 | __mstore | __mstore8 | __sstore | __tstore
+            | __return | __revert | __log0 => 2,
 
-            __addmod | __mulmod | __calldatacopy | __codecopy | __returndatacopy | __create
-            | __log1 => 3,
+            __addmod | __mulmod | __mcopy | __calldatacopy | __codecopy | __returndatacopy
+            | __create | __log1 => 3,
             __extcodecopy | __create2 | __log2 => 4,
             __log3 => 5,
             __delegatecall | __staticcall | __log4 => 6,
@@ -190,13 +255,77 @@ impl Intrinsic {
         }
     }
 
+    /// Whether the instruction reads or modifies the state of the chain, e.g.
+    /// storage or the balances of accounts. Memory and calldata aren't part
+    /// of the state.
+    pub fn state_mutability(&self) -> StateMutability {
+        use Intrinsic::*;
+        match self {
+            __sstore | __tstore | __create | __create2 | __call | __callcode | __delegatecall
+            | __selfdestruct | __log0 | __log1 | __log2 | __log3 | __log4 => {
+                StateMutability::Mutable
+            }
+
+            __sload | __tload | __gas | __address | __balance | __selfbalance | __caller
+            | __callvalue | __extcodesize | __extcodecopy | __extcodehash | __staticcall
+            | __chainid | __basefee | __origin | __gasprice | __blockhash | __coinbase
+            | __timestamp | __number | __difficulty | __prevrandao | __gaslimit | __blobhash
+            | __blobbasefee => StateMutability::View,
+
+            _ => StateMutability::Pure,
+        }
+    }
+
+    /// The first EVM version that has the instruction.
+    pub fn min_evm_version(&self) -> EvmVersion {
+        use Intrinsic::*;
+        match self {
+            __basefee => EvmVersion::London,
+            __prevrandao => EvmVersion::Paris,
+            __mcopy | __tload | __tstore | __blobhash | __blobbasefee => EvmVersion::Cancun,
+            _ => EvmVersion::Istanbul,
+        }
+    }
+
     pub fn return_type(&self) -> Base {
         use Intrinsic::*;
         match self {
-            __stop | __pop | __mstore | __mstore8 | __sstore | __calldatacopy | __codecopy
-            | __extcodecopy | __returndatacopy | __return | __revert | __selfdestruct
-            | __invalid | __log0 | __log1 | __log2 | __log3 | __log4 => Base::Unit,
+            __stop | __pop | __mstore | __mstore8 | __mcopy | __sstore | __tstore
+            | __calldatacopy | __codecopy | __extcodecopy | __returndatacopy | __return
+            | __revert | __selfdestruct | __invalid | __log0 | __log1 | __log2 | __log3
+            | __log4 => Base::Unit,
             _ => Base::u256(),
         }
     }
 }
+
+/// The name of the struct function that overloads a binary operator, e.g.
+/// `__add__` for `+`.
+pub fn bin_operator_method(op: BinOperator) -> &'static str {
+    match op {
+        BinOperator::Add => "__add__",
+        BinOperator::Sub => "__sub__",
+        BinOperator::Mult => "__mul__",
+        BinOperator::Div => "__div__",
+        BinOperator::Mod => "__mod__",
+        BinOperator::Pow => "__pow__",
+        BinOperator::LShift => "__lshift__",
+        BinOperator::RShift => "__rshift__",
+        BinOperator::BitOr => "__or__",
+        BinOperator::BitXor => "__xor__",
+        BinOperator::BitAnd => "__and__",
+    }
+}
+
+/// The name of the struct function that overloads a comparison operator,
+/// e.g. `__eq__` for `==`.
+pub fn comp_operator_method(op: CompOperator) -> &'static str {
+    match op {
+        CompOperator::Eq => "__eq__",
+        CompOperator::NotEq => "__ne__",
+        CompOperator::Lt => "__lt__",
+        CompOperator::LtE => "__le__",
+        CompOperator::Gt => "__gt__",
+        CompOperator::GtE => "__ge__",
+    }
+}
@@ -1,9 +1,11 @@
-use crate::errors::{self, CannotMove, IncompleteItem, TypeError};
-use crate::namespace::items::{Class, ContractId, DiagnosticSink, EventId, FunctionId, Item};
-use crate::namespace::types::{FixedSize, SelfDecl, Type};
+use crate::errors::{self, CannotMove, ConstEvalError, IncompleteItem, TypeError};
+use crate::namespace::items::{
+    Class, ContractId, DiagnosticSink, EnumVariantId, EventId, FunctionId, Item,
+};
+use crate::namespace::types::{FixedSize, FunctionType, Integer, SelfDecl, StateMutability, Type};
 use crate::AnalyzerDb;
 use crate::{
-    builtins::{ContractTypeMethod, GlobalFunction, Intrinsic, ValueMethod},
+    builtins::{ContractTypeMethod, GlobalFunction, IntegerTypeMethod, Intrinsic, ValueMethod},
     namespace::scopes::BlockScopeType,
 };
 use fe_common::diagnostics::Diagnostic;
@@ -38,6 +40,9 @@ impl<T> Analysis<T> {
 pub trait AnalyzerContext {
     fn resolve_name(&self, name: &str) -> Result<Option<NamedThing>, IncompleteItem>;
     fn resolve_path(&mut self, path: &ast::Path) -> Option<NamedThing>;
+    /// Returns the type that a generic parameter of the enclosing struct or
+    /// function instance is bound to.
+    fn resolve_type_param(&self, name: &str) -> Option<FixedSize>;
     fn add_diagnostic(&mut self, diag: Diagnostic);
     fn db(&self) -> &dyn AnalyzerDb;
 
@@ -69,11 +74,12 @@ pub trait AnalyzerContext {
     /// Add evaluated constant value in a constant declaration to the context.
     fn add_constant(&self, name: &Node<ast::SmolStr>, expr: &Node<ast::Expr>, value: Constant);
 
-    /// Returns constant value from variable name.
+    /// Returns constant value from variable name, or the error that occurred
+    /// during the evaluation of the constant.
     fn constant_value_by_name(
         &self,
         name: &ast::SmolStr,
-    ) -> Result<Option<Constant>, IncompleteItem>;
+    ) -> Result<Option<Constant>, ConstEvalError>;
 
     /// Returns an item enclosing current context.
     ///
@@ -127,6 +133,33 @@ pub trait AnalyzerContext {
     /// to determine whether a context is in a function.
     fn add_call(&self, node: &Node<ast::Expr>, call_type: CallType);
 
+    /// Records that an expression is a reference to a function, which is used
+    /// as a value of a function type.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a context is not in a function. Use [`Self::is_in_function`]
+    /// to determine whether a context is in a function.
+    fn add_function_ref(&self, node: &Node<ast::Expr>, function: FunctionId);
+
+    /// Records that the index of an array subscript expression is known to be
+    /// within the bounds of the array.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a context is not in a function. Use [`Self::is_in_function`]
+    /// to determine whether a context is in a function.
+    fn add_in_bounds_index(&self, node: &Node<ast::Expr>);
+
+    /// Records that an expression reads the state of the chain without
+    /// calling a function, e.g. `block.number` or a field of the contract.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a context is not in a function. Use [`Self::is_in_function`]
+    /// to determine whether a context is in a function.
+    fn add_state_read(&self, node: &Node<ast::Expr>);
+
     /// Store string literal to the current context.
     ///
     /// # Panics
@@ -193,6 +226,12 @@ pub trait AnalyzerContext {
         ))
     }
 
+    /// Emit a warning. Warnings don't stop compilation, so no
+    /// [`DiagnosticVoucher`] is returned.
+    fn warning(&mut self, message: &str, labels: Vec<Label>, notes: Vec<String>) {
+        self.add_diagnostic(errors::warning(message, labels, notes))
+    }
+
     fn register_diag(&mut self, diag: Diagnostic) -> DiagnosticVoucher {
         self.add_diagnostic(diag);
         DiagnosticVoucher(PhantomData::default())
@@ -268,6 +307,10 @@ impl AnalyzerContext for TempContext {
         panic!("TempContext can't resolve names")
     }
 
+    fn resolve_type_param(&self, _name: &str) -> Option<FixedSize> {
+        None
+    }
+
     fn add_expression(&self, _node: &Node<ast::Expr>, _attributes: ExpressionAttributes) {
         panic!("TempContext can't store expression")
     }
@@ -287,7 +330,7 @@ impl AnalyzerContext for TempContext {
     fn constant_value_by_name(
         &self,
         _name: &ast::SmolStr,
-    ) -> Result<Option<Constant>, IncompleteItem> {
+    ) -> Result<Option<Constant>, ConstEvalError> {
         Ok(None)
     }
 
@@ -303,6 +346,18 @@ impl AnalyzerContext for TempContext {
         panic!("TempContext can't add call");
     }
 
+    fn add_function_ref(&self, _node: &Node<ast::Expr>, _function: FunctionId) {
+        panic!("TempContext can't add function reference");
+    }
+
+    fn add_in_bounds_index(&self, _node: &Node<ast::Expr>) {
+        panic!("TempContext can't add in bounds index");
+    }
+
+    fn add_state_read(&self, _node: &Node<ast::Expr>) {
+        panic!("TempContext can't add state read");
+    }
+
     fn add_string(&self, _str_lit: SmolStr) {
         panic!("TempContext can't store string literal")
     }
@@ -341,11 +396,15 @@ impl Location {
     /// assigned, returned, or passed.
     pub fn assign_location(typ: &FixedSize) -> Self {
         match typ {
-            FixedSize::Base(_) | FixedSize::Contract(_) => Location::Value,
+            FixedSize::Base(_)
+            | FixedSize::Contract(_)
+            | FixedSize::Vec(_)
+            | FixedSize::Function(_) => Location::Value,
             FixedSize::Array(_)
             | FixedSize::Tuple(_)
             | FixedSize::String(_)
-            | FixedSize::Struct(_) => Location::Memory,
+            | FixedSize::Struct(_)
+            | FixedSize::Enum(_) => Location::Memory,
         }
     }
 }
@@ -356,9 +415,35 @@ pub struct FunctionBody {
     pub emits: IndexMap<NodeId, EventId>,
     pub string_literals: IndexSet<SmolStr>, // for yulgen
 
-    // This is the id of the VarDecl TypeDesc node
+    // This is the id of the VarDecl TypeDesc node, or of the VarDecl target
+    // node if the declaration has no type description.
     pub var_decl_types: IndexMap<NodeId, FixedSize>,
     pub calls: IndexMap<NodeId, CallType>,
+    /// Name expressions that refer to a function, as a value of a function type.
+    pub function_refs: IndexMap<NodeId, FunctionId>,
+    /// Assignments to storage that are overwritten before they're read. The
+    /// stores are left out of the compiled code.
+    pub dead_stores: IndexSet<NodeId>,
+    /// Array subscripts whose index is known to be within the bounds of the
+    /// array. The bounds checks of these subscripts are left out.
+    pub in_bounds_indices: IndexSet<NodeId>,
+    /// Expressions that read the state of the chain without calling a
+    /// function, e.g. `block.number` or a field of the contract.
+    pub state_reads: IndexSet<NodeId>,
+    /// The state mutability of the instructions used by `yul` blocks, by the
+    /// id of the block's statement node.
+    pub yul_blocks: IndexMap<NodeId, StateMutability>,
+    /// The external calls made by `try` statements, by the id of the called
+    /// function's node. These calls don't revert when the callee reverts.
+    pub try_calls: IndexSet<NodeId>,
+    /// The errors caught by `catch` clauses, by the id of the clause's type
+    /// node.
+    pub catch_types: IndexMap<NodeId, FixedSize>,
+    /// The integer arithmetic that reverts on overflow only if the build
+    /// profile enables overflow checks, by the id of the operation's
+    /// expression (or augmented assignment) node. The operations in
+    /// `unchecked` blocks and `#[checked]` statements aren't included.
+    pub profile_checked_arithmetic: IndexMap<NodeId, Span>,
     pub spans: HashMap<NodeId, Span>,
 }
 
@@ -391,7 +476,7 @@ impl ExpressionAttributes {
     /// Adds a move to value, if it is in storage or memory.
     pub fn into_loaded(mut self) -> Result<Self, CannotMove> {
         match self.typ {
-            Type::Base(_) | Type::Contract(_) => {
+            Type::Base(_) | Type::Contract(_) | Type::Vec(_) | Type::Function(_) => {
                 if self.location != Location::Value {
                     self.move_location = Some(Location::Value);
                 }
@@ -431,12 +516,26 @@ pub enum CallType {
         typ: Type,
     },
 
-    // create, create2 (will be methods of the context struct soon)
+    // create, create2, address_of (will be methods of the context struct soon)
     BuiltinAssociatedFunction {
         contract: ContractId,
         function: ContractTypeMethod,
     },
 
+    // abi_encode(x), abi_decode<T>(data), and keccak256(x) of a value that's
+    // hashed by its abi encoding
+    BuiltinAbiFunction {
+        function: GlobalFunction,
+        typ: FixedSize,
+    },
+
+    // u8::try_from(x), u8::unchecked_from(x)
+    BuiltinIntegerConversion {
+        function: IntegerTypeMethod,
+        from: Integer,
+        to: Integer,
+    },
+
     // MyStruct.foo() (soon MyStruct::foo())
     AssociatedFunction {
         class: Class,
@@ -453,6 +552,10 @@ pub enum CallType {
     },
     Pure(FunctionId),
     TypeConstructor(Type),
+    EnumConstructor(EnumVariantId),
+
+    // op(x), where `op` is a value of a function type
+    FunctionValue(FunctionType),
 }
 
 impl CallType {
@@ -462,8 +565,12 @@ impl CallType {
             BuiltinFunction(_)
             | BuiltinValueMethod { .. }
             | TypeConstructor(_)
+            | EnumConstructor(_)
             | Intrinsic(_)
-            | BuiltinAssociatedFunction { .. } => None,
+            | BuiltinAssociatedFunction { .. }
+            | BuiltinAbiFunction { .. }
+            | BuiltinIntegerConversion { .. }
+            | FunctionValue(_) => None,
             AssociatedFunction { function: id, .. }
             | ValueMethod { method: id, .. }
             | External { function: id, .. }
@@ -477,11 +584,15 @@ impl CallType {
             CallType::Intrinsic(f) => f.as_ref().into(),
             CallType::BuiltinValueMethod { method, .. } => method.as_ref().into(),
             CallType::BuiltinAssociatedFunction { function, .. } => function.as_ref().into(),
+            CallType::BuiltinAbiFunction { function, .. } => function.as_ref().into(),
+            CallType::BuiltinIntegerConversion { function, .. } => function.as_ref().into(),
             CallType::AssociatedFunction { function: id, .. }
             | CallType::ValueMethod { method: id, .. }
             | CallType::External { function: id, .. }
             | CallType::Pure(id) => id.name(db),
             CallType::TypeConstructor(typ) => typ.name(),
+            CallType::EnumConstructor(variant) => variant.name(db),
+            CallType::FunctionValue(typ) => typ.to_string().into(),
         }
     }
 
@@ -500,8 +611,90 @@ impl fmt::Display for CallType {
     }
 }
 
+/// A call from a contract's code to the code of another account, i.e. a call
+/// of a function of another contract, the deployment of a contract, or a
+/// `call`-like intrinsic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExternalCall {
+    /// The function in which the call is made.
+    pub caller: FunctionId,
+    pub callee: ExternalCallee,
+    /// Whether the call can send ether to the callee. Calls of contract
+    /// functions always send zero.
+    pub transfers_value: bool,
+    /// Whether the call is made by a `try` statement, which catches the
+    /// reverts of the callee.
+    pub is_try: bool,
+    /// The span of the called expression, e.g. `token.transfer`.
+    pub span: Span,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExternalCallee {
+    /// A public function of a contract or an interface.
+    Function {
+        contract: ContractId,
+        function: FunctionId,
+    },
+    /// `Foo.create(value)` or `Foo.create2(value, salt)`.
+    Create {
+        contract: ContractId,
+        method: ContractTypeMethod,
+    },
+    /// `__call`, `__callcode`, `__delegatecall`, `__staticcall`, `__create`
+    /// or `__create2`.
+    Intrinsic(Intrinsic),
+}
+
+impl ExternalCall {
+    /// The external call made by a call expression of the given type, if any.
+    pub fn from_call_type(
+        caller: FunctionId,
+        call_type: &CallType,
+        is_try: bool,
+        span: Span,
+    ) -> Option<Self> {
+        let (callee, transfers_value) = match call_type {
+            CallType::External { contract, function } => (
+                ExternalCallee::Function {
+                    contract: *contract,
+                    function: *function,
+                },
+                false,
+            ),
+            CallType::BuiltinAssociatedFunction {
+                contract,
+                function: method @ (ContractTypeMethod::Create | ContractTypeMethod::Create2),
+            } => (
+                ExternalCallee::Create {
+                    contract: *contract,
+                    method: *method,
+                },
+                true,
+            ),
+            CallType::Intrinsic(
+                intrinsic @ (Intrinsic::__call
+                | Intrinsic::__callcode
+                | Intrinsic::__create
+                | Intrinsic::__create2),
+            ) => (ExternalCallee::Intrinsic(*intrinsic), true),
+            CallType::Intrinsic(
+                intrinsic @ (Intrinsic::__delegatecall | Intrinsic::__staticcall),
+            ) => (ExternalCallee::Intrinsic(*intrinsic), false),
+            _ => return None,
+        };
+        Some(ExternalCall {
+            caller,
+            callee,
+            transfers_value,
+            is_try,
+            span,
+        })
+    }
+}
+
 /// Represents constant value.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Constant {
     Int(BigInt),
     Bool(bool),
@@ -1,17 +1,21 @@
-use crate::context::{Analysis, Constant, FunctionBody};
+use crate::builtins::EvmVersion;
+use crate::context::{Analysis, Constant, ExternalCall, FunctionBody};
 use crate::errors::{ConstEvalError, TypeError};
+use crate::features::ModuleFeatures;
 use crate::namespace::items::{
-    self, ContractFieldId, ContractId, DepGraphWrapper, EventId, FunctionId, IngotId, Item,
-    ModuleConstantId, ModuleId, StructFieldId, StructId, TypeAliasId,
+    self, ContractFieldId, ContractId, DepGraphWrapper, EnumId, EnumVariantId, EventId, FunctionId,
+    IngotId, Item, ModuleConstantId, ModuleId, StructFieldId, StructId, TypeAliasId,
 };
 use crate::namespace::types;
 use fe_common::db::{SourceDb, SourceDbStorage, Upcast, UpcastMut};
+use fe_common::files::SourceFileId;
 use fe_common::Span;
 use fe_parser::ast;
+use fe_parser::node::Node;
 use indexmap::map::IndexMap;
 use smol_str::SmolStr;
 use std::rc::Rc;
-mod queries;
+pub(crate) mod queries;
 
 #[salsa::query_group(AnalyzerDbStorage)]
 pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb> {
@@ -26,6 +30,10 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
     #[salsa::interned]
     fn intern_struct_field(&self, data: Rc<items::StructField>) -> StructFieldId;
     #[salsa::interned]
+    fn intern_enum(&self, data: Rc<items::Enum>) -> EnumId;
+    #[salsa::interned]
+    fn intern_enum_variant(&self, data: Rc<items::EnumVariant>) -> EnumVariantId;
+    #[salsa::interned]
     fn intern_type_alias(&self, data: Rc<items::TypeAlias>) -> TypeAliasId;
     #[salsa::interned]
     fn intern_contract(&self, data: Rc<items::Contract>) -> ContractId;
@@ -51,29 +59,62 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
 
     #[salsa::invoke(queries::ingots::ingot_root_module)]
     fn ingot_root_module(&self, ingot: IngotId) -> Option<ModuleId>;
+    #[salsa::invoke(queries::ingots::ingot_global_items)]
+    fn ingot_global_items(&self, ingot: IngotId) -> Rc<IndexMap<SmolStr, Item>>;
+
+    // The values of constants that are defined at build time, e.g. with
+    // `fe --define WETH=0x...`, by constant name. Each value is stored in a
+    // file of its own, so that diagnostics can point at it.
+    // Set with `crate::define_constants`.
+    #[salsa::input]
+    fn constant_defines(&self) -> Rc<IndexMap<SmolStr, SourceFileId>>;
+    #[salsa::invoke(queries::module::constant_define_values)]
+    fn constant_define_values(&self) -> Analysis<Rc<IndexMap<SmolStr, Node<ast::Expr>>>>;
+
+    // The version of the EVM that the contracts are compiled for. Calling an
+    // instruction that it doesn't have is an error.
+    #[salsa::input]
+    fn evm_version(&self) -> EvmVersion;
 
     // Module
     #[salsa::invoke(queries::module::module_file_path)]
     fn module_file_path(&self, module: ModuleId) -> SmolStr;
     #[salsa::invoke(queries::module::module_parse)]
     fn module_parse(&self, module: ModuleId) -> Analysis<Rc<ast::Module>>;
+    #[salsa::invoke(queries::module::module_features)]
+    fn module_features(&self, module: ModuleId) -> Analysis<Rc<ModuleFeatures>>;
     #[salsa::invoke(queries::module::module_is_incomplete)]
     fn module_is_incomplete(&self, module: ModuleId) -> bool;
     #[salsa::invoke(queries::module::module_all_items)]
     fn module_all_items(&self, module: ModuleId) -> Rc<[Item]>;
     #[salsa::invoke(queries::module::module_item_map)]
     fn module_item_map(&self, module: ModuleId) -> Analysis<Rc<IndexMap<SmolStr, Item>>>;
+    #[salsa::invoke(queries::module::module_function_overloads)]
+    fn module_function_overloads(
+        &self,
+        module: ModuleId,
+    ) -> Analysis<Rc<IndexMap<SmolStr, Rc<[FunctionId]>>>>;
+    #[salsa::invoke(queries::module::module_internal_items)]
+    fn module_internal_items(&self, module: ModuleId) -> Rc<IndexMap<SmolStr, Item>>;
     #[salsa::invoke(queries::module::module_contracts)]
     fn module_contracts(&self, module: ModuleId) -> Rc<[ContractId]>;
+    #[salsa::invoke(queries::module::module_interface_impls)]
+    fn module_interface_impls(&self, module: ModuleId) -> Analysis<Rc<[(ContractId, ContractId)]>>;
     #[salsa::invoke(queries::module::module_structs)]
     fn module_structs(&self, module: ModuleId) -> Rc<[StructId]>;
     #[salsa::invoke(queries::module::module_constants)]
     fn module_constants(&self, module: ModuleId) -> Rc<Vec<ModuleConstantId>>;
+    #[salsa::invoke(queries::module::module_generic_instances)]
+    fn module_generic_instances(&self, module: ModuleId) -> Rc<[Item]>;
     #[salsa::invoke(queries::module::module_used_item_map)]
     fn module_used_item_map(
         &self,
         module: ModuleId,
     ) -> Analysis<Rc<IndexMap<SmolStr, (Span, Item)>>>;
+    #[salsa::invoke(queries::module::module_unused_imports)]
+    fn module_unused_imports(&self, module: ModuleId) -> Analysis<Rc<[SmolStr]>>;
+    #[salsa::invoke(queries::module::module_unused_functions)]
+    fn module_unused_functions(&self, module: ModuleId) -> Analysis<Rc<[FunctionId]>>;
     #[salsa::invoke(queries::module::module_parent_module)]
     fn module_parent_module(&self, module: ModuleId) -> Option<ModuleId>;
     #[salsa::invoke(queries::module::module_submodules)]
@@ -94,6 +135,8 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
     ) -> Analysis<Result<Constant, ConstEvalError>>;
 
     // Contract
+    #[salsa::invoke(queries::contracts::contract_ancestors)]
+    fn contract_ancestors(&self, id: ContractId) -> Analysis<Rc<[ContractId]>>;
     #[salsa::invoke(queries::contracts::contract_all_functions)]
     fn contract_all_functions(&self, id: ContractId) -> Rc<[FunctionId]>;
     #[salsa::invoke(queries::contracts::contract_function_map)]
@@ -104,6 +147,10 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
     fn contract_init_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
     #[salsa::invoke(queries::contracts::contract_call_function)]
     fn contract_call_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
+    #[salsa::invoke(queries::contracts::contract_fallback_function)]
+    fn contract_fallback_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
+    #[salsa::invoke(queries::contracts::contract_receive_function)]
+    fn contract_receive_function(&self, id: ContractId) -> Analysis<Option<FunctionId>>;
 
     #[salsa::invoke(queries::contracts::contract_all_events)]
     fn contract_all_events(&self, id: ContractId) -> Rc<[EventId]>;
@@ -128,8 +175,12 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
     #[salsa::cycle(queries::contracts::contract_runtime_dependency_graph_cycle)]
     #[salsa::invoke(queries::contracts::contract_runtime_dependency_graph)]
     fn contract_runtime_dependency_graph(&self, id: ContractId) -> DepGraphWrapper;
+    #[salsa::invoke(queries::contracts::contract_external_calls)]
+    fn contract_external_calls(&self, id: ContractId) -> Rc<[ExternalCall]>;
 
     // Function
+    #[salsa::invoke(queries::functions::function_generic_params)]
+    fn function_generic_params(&self, id: FunctionId) -> Analysis<Rc<[SmolStr]>>;
     #[salsa::invoke(queries::functions::function_signature)]
     fn function_signature(&self, id: FunctionId) -> Analysis<Rc<types::FunctionSignature>>;
     #[salsa::invoke(queries::functions::function_body)]
@@ -137,10 +188,14 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
     #[salsa::cycle(queries::functions::function_dependency_graph_cycle)]
     #[salsa::invoke(queries::functions::function_dependency_graph)]
     fn function_dependency_graph(&self, id: FunctionId) -> DepGraphWrapper;
+    #[salsa::invoke(queries::functions::function_state_mutability)]
+    fn function_state_mutability(&self, id: FunctionId) -> types::StateMutability;
 
     // Struct
     #[salsa::invoke(queries::structs::struct_type)]
     fn struct_type(&self, id: StructId) -> Rc<types::Struct>;
+    #[salsa::invoke(queries::structs::struct_generic_params)]
+    fn struct_generic_params(&self, id: StructId) -> Analysis<Rc<[SmolStr]>>;
     #[salsa::invoke(queries::structs::struct_all_fields)]
     fn struct_all_fields(&self, id: StructId) -> Rc<[StructFieldId]>;
     #[salsa::invoke(queries::structs::struct_field_map)]
@@ -157,6 +212,17 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
     #[salsa::invoke(queries::structs::struct_dependency_graph)]
     fn struct_dependency_graph(&self, id: StructId) -> DepGraphWrapper;
 
+    // Enum
+    #[salsa::invoke(queries::enums::enum_all_variants)]
+    fn enum_all_variants(&self, id: EnumId) -> Rc<[EnumVariantId]>;
+    #[salsa::invoke(queries::enums::enum_variant_map)]
+    fn enum_variant_map(&self, id: EnumId) -> Analysis<Rc<IndexMap<SmolStr, EnumVariantId>>>;
+    #[salsa::invoke(queries::enums::enum_variant_fields)]
+    fn enum_variant_fields(
+        &self,
+        id: EnumVariantId,
+    ) -> Analysis<Rc<[Result<types::FixedSize, TypeError>]>>;
+
     // Event
     #[salsa::invoke(queries::events::event_type)]
     fn event_type(&self, event: EventId) -> Analysis<Rc<types::Event>>;
@@ -168,12 +234,22 @@ pub trait AnalyzerDb: SourceDb + Upcast<dyn SourceDb> + UpcastMut<dyn SourceDb>
 }
 
 #[salsa::database(AnalyzerDbStorage, SourceDbStorage)]
-#[derive(Default)]
 pub struct TestDb {
     storage: salsa::Storage<TestDb>,
 }
 impl salsa::Database for TestDb {}
 
+impl Default for TestDb {
+    fn default() -> Self {
+        let mut db = TestDb {
+            storage: Default::default(),
+        };
+        db.set_constant_defines(Rc::new(IndexMap::new()));
+        db.set_evm_version(EvmVersion::default());
+        db
+    }
+}
+
 impl Upcast<dyn SourceDb> for TestDb {
     fn upcast(&self) -> &(dyn SourceDb + 'static) {
         &*self
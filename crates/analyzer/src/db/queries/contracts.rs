@@ -1,24 +1,189 @@
-use crate::context::{AnalyzerContext, NamedThing};
+use crate::context::{AnalyzerContext, ExternalCall, NamedThing};
+use crate::db::queries::functions::{is_special_contract_fn, loop_has_break};
 use crate::db::{Analysis, AnalyzerDb};
 use crate::errors;
 use crate::namespace::items::{
-    self, ContractFieldId, ContractId, DepGraph, DepGraphWrapper, DepLocality, EventId, FunctionId,
-    Item, TypeDef,
+    self, walk_local_dependencies, ContractFieldId, ContractId, DepGraph, DepGraphWrapper,
+    DepLocality, EventId, FunctionId, Item, TypeDef,
 };
 use crate::namespace::scopes::ItemScope;
-use crate::namespace::types::{self, Contract, Struct, Type};
+use crate::namespace::types::{self, Contract, Enum, Struct, Type};
 use crate::traversal::types::type_desc;
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{Diagnostic, Label};
 use fe_parser::ast;
+use fe_parser::node::Node;
 use indexmap::map::{Entry, IndexMap};
+use indexmap::IndexSet;
 use smol_str::SmolStr;
+use std::collections::BTreeSet;
 use std::rc::Rc;
 
-/// A `Vec` of every function defined in the contract, including duplicates and the init function.
+/// The contracts that the contract inherits from, directly or through its
+/// parents, in the order that their fields, events and functions are
+/// inherited: each contract comes after its own parents, and a contract that
+/// is inherited through several parents is listed once.
+pub fn contract_ancestors(db: &dyn AnalyzerDb, contract: ContractId) -> Analysis<Rc<[ContractId]>> {
+    let mut scope = ItemScope::new(db, contract.module(db));
+    let mut parents = IndexMap::<ContractId, &Node<SmolStr>>::new();
+
+    let data = contract.data(db);
+    for name in data.ast.kind.parents.iter() {
+        let parent = match resolve_parent(db, contract, name) {
+            Ok(parent) => parent,
+            Err(diag) => {
+                scope.register_diag(diag);
+                continue;
+            }
+        };
+        if parent == contract || inherits_from(db, parent, contract, &mut vec![]) {
+            scope.error(
+                &format!("contract `{}` inherits from itself", contract.name(db)),
+                name.span,
+                &format!("`{}` inherits from `{}`", name.kind, contract.name(db)),
+            );
+            continue;
+        }
+        match parents.entry(parent) {
+            Entry::Occupied(entry) => {
+                scope.duplicate_name_error(
+                    &format!(
+                        "duplicate parent contracts of `contract {}`",
+                        contract.name(db)
+                    ),
+                    &name.kind,
+                    entry.get().span,
+                    name.span,
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(name);
+            }
+        }
+    }
+
+    let mut ancestors = IndexSet::new();
+    for parent in parents.keys() {
+        collect_ancestors(db, *parent, &mut ancestors, &mut vec![contract]);
+    }
+
+    Analysis {
+        value: ancestors.into_iter().collect(),
+        diagnostics: scope.diagnostics.into(),
+    }
+}
+
+/// Resolves the name of a parent contract. A contract can only inherit from
+/// contracts that are defined in the same module.
+fn resolve_parent(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    name: &Node<SmolStr>,
+) -> Result<ContractId, Diagnostic> {
+    match contract.module(db).resolve_name(db, &name.kind) {
+        Ok(Some(Item::Type(TypeDef::Contract(parent)))) if parent.is_interface(db) => {
+            Err(errors::error(
+                format!("contract `{}` can't inherit from an interface", contract.name(db)),
+                name.span,
+                format!("`{}` is an interface", name.kind),
+            ))
+        }
+        Ok(Some(Item::Type(TypeDef::Contract(parent))))
+            if parent.module(db) != contract.module(db) =>
+        {
+            Err(errors::fancy_error(
+                format!(
+                    "contract `{}` can't inherit from a contract in another module",
+                    contract.name(db)
+                ),
+                vec![Label::primary(
+                    name.span,
+                    format!("`{}` is defined in another module", name.kind),
+                )],
+                vec!["Note: a contract can only inherit from contracts that are defined in the same module".into()],
+            ))
+        }
+        Ok(Some(Item::Type(TypeDef::Contract(parent)))) => Ok(parent),
+        Ok(Some(item)) => Err(errors::error(
+            format!("`{}` is not a contract", name.kind),
+            name.span,
+            format!("`{}` is a {}", name.kind, item.item_kind_display_name()),
+        )),
+        Ok(None) | Err(_) => Err(errors::error(
+            format!("undefined contract `{}`", name.kind),
+            name.span,
+            "undefined",
+        )),
+    }
+}
+
+/// The parents of the contract that can be resolved. Errors are reported with
+/// the contract itself.
+fn resolved_parents(db: &dyn AnalyzerDb, contract: ContractId) -> Vec<ContractId> {
+    contract
+        .data(db)
+        .ast
+        .kind
+        .parents
+        .iter()
+        .filter_map(|name| resolve_parent(db, contract, name).ok())
+        .collect()
+}
+
+/// Returns `true` if `contract` inherits from `ancestor`. `visiting` holds
+/// the contracts on the current path, so that other cycles are skipped.
+fn inherits_from(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    ancestor: ContractId,
+    visiting: &mut Vec<ContractId>,
+) -> bool {
+    if visiting.contains(&contract) {
+        return false;
+    }
+    visiting.push(contract);
+    let found = resolved_parents(db, contract)
+        .into_iter()
+        .any(|parent| parent == ancestor || inherits_from(db, parent, ancestor, visiting));
+    visiting.pop();
+    found
+}
+
+fn collect_ancestors(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    ancestors: &mut IndexSet<ContractId>,
+    visiting: &mut Vec<ContractId>,
+) {
+    if ancestors.contains(&contract) || visiting.contains(&contract) {
+        return;
+    }
+    visiting.push(contract);
+    for parent in resolved_parents(db, contract) {
+        collect_ancestors(db, parent, ancestors, visiting);
+    }
+    visiting.pop();
+    ancestors.insert(contract);
+}
+
+/// The definitions of the contract's ancestors, followed by the definition of
+/// the contract itself. The contract has the fields, events and functions of
+/// all of them, in this order.
+fn inherited_defs(db: &dyn AnalyzerDb, contract: ContractId) -> Vec<Rc<items::Contract>> {
+    contract
+        .ancestors(db)
+        .iter()
+        .chain([&contract])
+        .map(|id| id.data(db))
+        .collect()
+}
+
+/// A `Vec` of every function defined in the contract or inherited by it,
+/// including duplicates and the init function.
 pub fn contract_all_functions(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<[FunctionId]> {
     let module = contract.module(db);
-    let body = &contract.data(db).ast.kind.body;
-    body.iter()
+    inherited_defs(db, contract)
+        .iter()
+        .flat_map(|def| def.ast.kind.body.iter())
         .filter_map(|stmt| match stmt {
             ast::ContractStmt::Event(_) => None,
             ast::ContractStmt::Function(node) => {
@@ -26,6 +191,7 @@ pub fn contract_all_functions(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<[
                     ast: node.clone(),
                     module,
                     parent: Some(items::Class::Contract(contract)),
+                    generic_args: vec![],
                 })))
             }
         })
@@ -42,7 +208,7 @@ pub fn contract_function_map(
     for func in db.contract_all_functions(contract).iter() {
         let def = &func.data(db).ast;
         let def_name = def.name();
-        if def_name == "__init__" || def_name == "__call__" {
+        if is_special_contract_fn(def_name) {
             continue;
         }
 
@@ -148,6 +314,8 @@ pub fn contract_init_function(
                 ],
             ));
         }
+
+        diagnostics.extend(uninitialized_field_warnings(db, contract, *id));
     }
 
     Analysis {
@@ -156,6 +324,305 @@ pub fn contract_init_function(
     }
 }
 
+/// Warns about the contract fields that aren't assigned on every path through
+/// `__init__` that returns. Maps and arrays are usually filled in one item at a
+/// time later on, and are skipped, as are all the fields of a contract with the
+/// `#[zero_init]` attribute.
+fn uninitialized_field_warnings(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    init_fn: FunctionId,
+) -> Vec<Diagnostic> {
+    if contract.allows_zero_init(db) {
+        return vec![];
+    }
+    let assigned = match fields_assigned_by(db, contract, init_fn, &mut vec![]) {
+        Some(assigned) => assigned,
+        // `__init__` always reverts.
+        None => return vec![],
+    };
+
+    contract
+        .fields(db)
+        .iter()
+        .filter(|(name, field)| {
+            let field_ast = &field.data(db).ast.kind;
+            !assigned.contains(*name)
+                && field_ast.value.is_none()
+                && !matches!(field.typ(db), Ok(Type::Map(_) | Type::Array(_)) | Err(_))
+        })
+        .map(|(name, field)| {
+            errors::warning(
+                format!("contract field `{}` is not initialized in `__init__`", name),
+                vec![
+                    Label::primary(field.data(db).ast.span, format!("`{}` is declared here", name)),
+                    Label::secondary(
+                        init_fn.name_span(db),
+                        format!("`self.{}` isn't assigned on every path through `__init__`", name),
+                    ),
+                ],
+                vec![format!(
+                    "Hint: assign a value to `self.{}` in `__init__`, or add `#[zero_init]` to the contract if the field should start out as zero",
+                    name
+                )],
+            )
+        })
+        .collect()
+}
+
+/// Returns the contract fields that are assigned on every path through
+/// `function` that returns, or `None` if the function never returns. Fields
+/// assigned by the contract functions that are called with `self` count too.
+fn fields_assigned_by(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    function: FunctionId,
+    visiting: &mut Vec<FunctionId>,
+) -> Option<BTreeSet<SmolStr>> {
+    visiting.push(function);
+    let mut paths = FieldPaths {
+        db,
+        contract,
+        visiting,
+        returns: vec![],
+    };
+    let body = &function.data(db).ast.kind.body;
+    if let Some(assigned) = paths.block(body, BTreeSet::new()) {
+        paths.returns.push(assigned);
+    }
+    let returns = paths.returns;
+    visiting.pop();
+
+    returns
+        .into_iter()
+        .reduce(|first, other| first.intersection(&other).cloned().collect())
+}
+
+/// Tracks the contract fields that are assigned on the paths through a
+/// function body.
+struct FieldPaths<'a, 'b> {
+    db: &'a dyn AnalyzerDb,
+    contract: ContractId,
+    visiting: &'b mut Vec<FunctionId>,
+    /// The fields assigned on each path that ends with a `return`.
+    returns: Vec<BTreeSet<SmolStr>>,
+}
+
+impl FieldPaths<'_, '_> {
+    /// Returns the fields that are assigned after `block` on every path that
+    /// reaches its end, or `None` if no path does.
+    fn block(
+        &mut self,
+        block: &[Node<ast::FuncStmt>],
+        mut assigned: BTreeSet<SmolStr>,
+    ) -> Option<BTreeSet<SmolStr>> {
+        for stmt in block {
+            assigned = match &stmt.kind {
+                ast::FuncStmt::Return { value } => {
+                    if let Some(assigned) = self.exprs(value, assigned) {
+                        self.returns.push(assigned);
+                    }
+                    return None;
+                }
+                ast::FuncStmt::Revert { .. }
+                | ast::FuncStmt::Break { .. }
+                | ast::FuncStmt::Continue => return None,
+                ast::FuncStmt::Assign { target, value }
+                | ast::FuncStmt::AugAssign { target, value, .. } => {
+                    let mut assigned = self.exprs([value, target], assigned)?;
+                    if let Some(field) = assigned_field(target) {
+                        assigned.insert(field.clone());
+                    }
+                    assigned
+                }
+                ast::FuncStmt::VarDecl { value, .. } => self.exprs(value, assigned)?,
+                ast::FuncStmt::ConstantDecl { value, .. } | ast::FuncStmt::Expr { value } => {
+                    self.exprs([value], assigned)?
+                }
+                ast::FuncStmt::Assert { test, msg } => {
+                    self.exprs(std::iter::once(test).chain(msg), assigned)?
+                }
+                ast::FuncStmt::Emit { args, .. } => {
+                    self.exprs(args.kind.iter().map(|arg| &arg.kind.value), assigned)?
+                }
+                ast::FuncStmt::If {
+                    test,
+                    body,
+                    or_else,
+                } => {
+                    let assigned = self.exprs([test], assigned)?;
+                    let body = self.block(body, assigned.clone());
+                    let or_else = self.block(or_else, assigned);
+                    join(vec![body, or_else])?
+                }
+                ast::FuncStmt::Match { value, arms } => {
+                    let assigned = self.exprs([value], assigned)?;
+                    let arms = arms
+                        .iter()
+                        .map(|arm| self.block(&arm.kind.body, assigned.clone()))
+                        .collect();
+                    join(arms)?
+                }
+                ast::FuncStmt::Try {
+                    call,
+                    body,
+                    catches,
+                    ..
+                } => {
+                    let assigned = self.exprs([call], assigned)?;
+                    let paths = std::iter::once(body)
+                        .chain(catches.iter().map(|clause| &clause.kind.body))
+                        .map(|body| self.block(body, assigned.clone()))
+                        .collect();
+                    join(paths)?
+                }
+                // The body of these loops might not run at all.
+                ast::FuncStmt::For {
+                    iter: test, body, ..
+                }
+                | ast::FuncStmt::While { test, body } => {
+                    let assigned = self.exprs([test], assigned)?;
+                    self.block(body, assigned.clone());
+                    assigned
+                }
+                ast::FuncStmt::Loop { body, .. } => {
+                    self.block(body, assigned.clone());
+                    if !loop_has_break(body) {
+                        return None;
+                    }
+                    assigned
+                }
+                ast::FuncStmt::Unsafe(body)
+                | ast::FuncStmt::Unchecked(body)
+                | ast::FuncStmt::Checked(body) => self.block(body, assigned)?,
+                // Stores done by the Yul code itself aren't tracked.
+                ast::FuncStmt::Yul { inputs, .. } => {
+                    self.exprs(inputs.iter().map(|input| &input.kind.value), assigned)?
+                }
+                ast::FuncStmt::Pass => assigned,
+            };
+        }
+        Some(assigned)
+    }
+
+    /// Adds the fields assigned by the `self` functions called in `exprs`.
+    /// Returns `None` if one of the functions never returns.
+    fn exprs<'e>(
+        &mut self,
+        exprs: impl IntoIterator<Item = &'e Node<ast::Expr>>,
+        mut assigned: BTreeSet<SmolStr>,
+    ) -> Option<BTreeSet<SmolStr>> {
+        let mut method_names = vec![];
+        for expr in exprs {
+            self_method_calls(expr, &mut method_names);
+        }
+        for name in method_names {
+            let function = match self.contract.self_function(self.db, name) {
+                Some(function) if !self.visiting.contains(&function) => function,
+                _ => continue,
+            };
+            assigned.extend(fields_assigned_by(
+                self.db,
+                self.contract,
+                function,
+                self.visiting,
+            )?);
+        }
+        Some(assigned)
+    }
+}
+
+/// The fields assigned on every path that reaches the end of one of the given
+/// branches, or `None` if no path does.
+fn join(branches: Vec<Option<BTreeSet<SmolStr>>>) -> Option<BTreeSet<SmolStr>> {
+    branches
+        .into_iter()
+        .flatten()
+        .reduce(|first, other| first.intersection(&other).cloned().collect())
+}
+
+/// The contract field that is (partially) assigned by an assignment to
+/// `target`, e.g. `balances` for `self.balances[owner] = 10`.
+fn assigned_field(target: &Node<ast::Expr>) -> Option<&SmolStr> {
+    match &target.kind {
+        ast::Expr::Attribute { value, attr } => match &value.kind {
+            ast::Expr::Name(name) if name == "self" => Some(&attr.kind),
+            _ => assigned_field(value),
+        },
+        ast::Expr::Subscript { value, .. } => assigned_field(value),
+        _ => None,
+    }
+}
+
+/// Collects the names of the functions called on `self` in `expr`.
+fn self_method_calls<'a>(expr: &'a Node<ast::Expr>, names: &mut Vec<&'a str>) {
+    match &expr.kind {
+        ast::Expr::Call {
+            func, args, base, ..
+        } => {
+            if let ast::Expr::Attribute { value, attr } = &func.kind {
+                if matches!(&value.kind, ast::Expr::Name(name) if name == "self") {
+                    names.push(&attr.kind);
+                }
+            }
+            self_method_calls(func, names);
+            for arg in &args.kind {
+                self_method_calls(&arg.kind.value, names);
+            }
+            if let Some(base) = base {
+                self_method_calls(base, names);
+            }
+        }
+        ast::Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => {
+            for expr in [if_expr, test, else_expr] {
+                self_method_calls(expr, names);
+            }
+        }
+        ast::Expr::BoolOperation { left, right, .. }
+        | ast::Expr::BinOperation { left, right, .. }
+        | ast::Expr::CompOperation { left, right, .. } => {
+            self_method_calls(left, names);
+            self_method_calls(right, names);
+        }
+        ast::Expr::ChainedComparison { left, comparisons } => {
+            self_method_calls(left, names);
+            for (_, operand) in comparisons {
+                self_method_calls(operand, names);
+            }
+        }
+        ast::Expr::UnaryOperation { operand: value, .. } | ast::Expr::Attribute { value, .. } => {
+            self_method_calls(value, names)
+        }
+        ast::Expr::Subscript { value, index } => {
+            self_method_calls(value, names);
+            self_method_calls(index, names);
+        }
+        ast::Expr::List { elts, fill } => {
+            for elt in elts {
+                self_method_calls(elt, names);
+            }
+            if let Some(fill) = fill {
+                self_method_calls(fill, names);
+            }
+        }
+        ast::Expr::Tuple { elts } => {
+            for elt in elts {
+                self_method_calls(elt, names);
+            }
+        }
+        ast::Expr::Bool(_)
+        | ast::Expr::Name(_)
+        | ast::Expr::Path(_)
+        | ast::Expr::Num(_)
+        | ast::Expr::Str(_)
+        | ast::Expr::Unit => {}
+    }
+}
+
 pub fn contract_call_function(
     db: &dyn AnalyzerDb,
     contract: ContractId,
@@ -205,7 +672,9 @@ pub fn contract_call_function(
     if let Some((_id, init_span)) = first_def {
         for func in all_fns.iter() {
             let name = func.name(db);
-            if func.is_public(db) && name != "__init__" && name != "__call__" {
+            // Defining `__fallback__` or `__receive__` along with `__call__` is
+            // reported in `dispatcher_hook_function`.
+            if func.is_public(db) && !is_special_contract_fn(&name) {
                 diagnostics.push(errors::fancy_error(
                     "`pub` not allowed if `__call__` is defined",
                     vec![
@@ -227,10 +696,116 @@ pub fn contract_call_function(
     }
 }
 
-/// A `Vec` of all events defined within the contract, including those with duplicate names.
+pub fn contract_fallback_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Option<FunctionId>> {
+    dispatcher_hook_function(db, contract, "__fallback__")
+}
+
+pub fn contract_receive_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+) -> Analysis<Option<FunctionId>> {
+    dispatcher_hook_function(db, contract, "__receive__")
+}
+
+/// Finds the `__fallback__` or `__receive__` function of a contract. The
+/// generated call dispatcher calls `__receive__` when the calldata is empty,
+/// and `__fallback__` when the calldata doesn't select a public function.
+fn dispatcher_hook_function(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    name: &str,
+) -> Analysis<Option<FunctionId>> {
+    let all_fns = db.contract_all_functions(contract);
+    let mut hook_fns = all_fns.iter().filter_map(|func| {
+        let def = &func.data(db).ast;
+        (def.name() == name).then_some((func, def.span))
+    });
+
+    let mut diagnostics = vec![];
+
+    let first_def = hook_fns.next();
+    let dupe_labels = hook_fns
+        .map(|(_, dupe_span)| Label::secondary(dupe_span, format!("`{}` redefined here", name)))
+        .collect::<Vec<_>>();
+    if let (Some((_, first_span)), false) = (first_def, dupe_labels.is_empty()) {
+        let mut labels = vec![Label::primary(
+            first_span,
+            format!("`{}` first defined here", name),
+        )];
+        labels.extend(dupe_labels);
+        diagnostics.push(errors::fancy_error(
+            format!(
+                "`fn {}()` is defined multiple times in `contract {}`",
+                name,
+                contract.name(db),
+            ),
+            labels,
+            vec![],
+        ));
+    }
+
+    if let Some((id, span)) = first_def {
+        // Return type is checked in `queries::functions::function_signature`.
+        if !id.is_public(db) {
+            diagnostics.push(errors::fancy_error(
+                format!("`{}` function is not public", name),
+                vec![Label::primary(
+                    span,
+                    format!("`{}` function must be public", name),
+                )],
+                vec![
+                    "Hint: Add the `pub` modifier.".to_string(),
+                    format!("Example: `pub fn {}(self):`", name),
+                ],
+            ));
+        }
+
+        let def = &id.data(db).ast.kind;
+        if let Some(param) = def
+            .args
+            .iter()
+            .find(|arg| matches!(arg.kind, ast::FunctionArg::Regular(_)))
+        {
+            diagnostics.push(errors::fancy_error(
+                format!("`{}` function can't take parameters", name),
+                vec![Label::primary(param.span, "remove this parameter")],
+                vec![format!(
+                    "Note: `{}` is called by the contract's call dispatcher, which doesn't pass any arguments.",
+                    name
+                )],
+            ));
+        }
+
+        if let Some(call_fn) = contract.call_function(db) {
+            diagnostics.push(errors::fancy_error(
+                format!("`{}` can't be defined if `__call__` is defined", name),
+                vec![
+                    Label::primary(span, format!("`{}` is never called", name)),
+                    Label::secondary(call_fn.data(db).ast.span, "`__call__` defined here"),
+                ],
+                vec![format!(
+                    "Note: `{}` is called by the generated call dispatcher, which the `__call__` function replaces.",
+                    name
+                )],
+            ));
+        }
+    }
+
+    Analysis {
+        value: first_def.map(|(id, _span)| *id),
+        diagnostics: diagnostics.into(),
+    }
+}
+
+/// A `Vec` of all events defined within the contract or inherited by it,
+/// including those with duplicate names.
 pub fn contract_all_events(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<[EventId]> {
-    let body = &contract.data(db).ast.kind.body;
-    body.iter()
+    inherited_defs(db, contract)
+        .iter()
+        .flat_map(|def| def.ast.kind.body.iter())
         .filter_map(|stmt| match stmt {
             ast::ContractStmt::Function(_) => None,
             ast::ContractStmt::Event(node) => Some(db.intern_event(Rc::new(items::Event {
@@ -274,14 +849,12 @@ pub fn contract_event_map(
     }
 }
 
-/// All field ids, including those with duplicate names
+/// All field ids, including inherited fields and those with duplicate names.
+/// The fields of the ancestors come first.
 pub fn contract_all_fields(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<[ContractFieldId]> {
-    contract
-        .data(db)
-        .ast
-        .kind
-        .fields
+    inherited_defs(db, contract)
         .iter()
+        .flat_map(|def| def.ast.kind.fields.iter())
         .map(|node| {
             db.intern_contract_field(Rc::new(items::ContractField {
                 ast: node.clone(),
@@ -328,7 +901,34 @@ pub fn contract_field_type(
     field: ContractFieldId,
 ) -> Analysis<Result<types::Type, errors::TypeError>> {
     let mut scope = ItemScope::new(db, field.data(db).parent.module(db));
-    let typ = type_desc(&mut scope, &field.data(db).ast.kind.typ);
+    let type_node = &field.data(db).ast.kind.typ;
+    let typ = type_desc(&mut scope, type_node).and_then(|typ| {
+        // Map values are stored in storage too.
+        let mut value_type = &typ;
+        while let Type::Map(map) = value_type {
+            value_type = &map.value;
+        }
+        match value_type {
+            Type::Vec(_) => Err(errors::TypeError::new(scope.error(
+                "`Vec` type can't be stored in a contract field",
+                type_node.span,
+                "a `Vec` can only be used in memory",
+            ))),
+            _ if matches!(types::FixedSize::try_from(value_type.clone()), Ok(typ) if typ.contains_vec(db)) => {
+                Err(errors::TypeError::new(scope.error(
+                    "`Vec` type can't be stored in a contract field",
+                    type_node.span,
+                    "this type holds a `Vec`, which can only be used in memory",
+                )))
+            }
+            Type::Function(_) => Err(errors::TypeError::new(scope.error(
+                "function type can't be stored in a contract field",
+                type_node.span,
+                "a function value can only be used inside a function",
+            ))),
+            _ => Ok(typ),
+        }
+    });
 
     let node = &field.data(db).ast;
 
@@ -360,6 +960,7 @@ pub fn contract_dependency_graph(db: &dyn AnalyzerDb, contract: ContractId) -> D
             // We don't want
             Type::Contract(Contract { id, .. }) => Some(Item::Type(TypeDef::Contract(id))),
             Type::Struct(Struct { id, .. }) => Some(Item::Type(TypeDef::Struct(id))),
+            Type::Enum(Enum { id, .. }) => Some(Item::Type(TypeDef::Enum(id))),
             // TODO: when tuples can contain non-primitive items,
             // we'll have to depend on tuple element types
             _ => None,
@@ -394,14 +995,18 @@ pub fn contract_runtime_dependency_graph(
     contract: ContractId,
 ) -> DepGraphWrapper {
     // This is the dependency graph of the (as yet imaginary) `__call__` function, which
-    // dispatches to the contract's public functions. This should be used when compiling
-    // the runtime object for a contract.
+    // dispatches to the contract's public functions and to its `__fallback__` and
+    // `__receive__` functions. This should be used when compiling the runtime object for
+    // a contract.
 
     let root = Item::Type(TypeDef::Contract(contract));
     let pub_fns = contract
         .public_functions(db)
         .values()
-        .map(|fun| (root, Item::Function(*fun), DepLocality::Local))
+        .copied()
+        .chain(contract.fallback_function(db))
+        .chain(contract.receive_function(db))
+        .map(|fun| (root, Item::Function(fun), DepLocality::Local))
         .collect::<Vec<_>>();
 
     let mut graph = DepGraph::from_edges(pub_fns.iter());
@@ -421,3 +1026,50 @@ pub fn contract_runtime_dependency_graph_cycle(
 ) -> DepGraphWrapper {
     DepGraphWrapper(Rc::new(DepGraph::new()))
 }
+
+/// The external calls that can be made by the code of the contract, i.e. by
+/// its `__init__`, `__call__`, `__fallback__` and `__receive__` functions,
+/// its public functions, and the functions they call. The calls of each
+/// function are in source order.
+pub fn contract_external_calls(db: &dyn AnalyzerDb, contract: ContractId) -> Rc<[ExternalCall]> {
+    let mut functions = IndexSet::new();
+    let mut collect = |item| {
+        if let Item::Function(function) = item {
+            functions.insert(function);
+        }
+    };
+    walk_local_dependencies(
+        &contract.runtime_dependency_graph(db),
+        Item::Type(TypeDef::Contract(contract)),
+        &mut collect,
+    );
+    for function in contract
+        .init_function(db)
+        .into_iter()
+        .chain(contract.call_function(db))
+    {
+        walk_local_dependencies(
+            &function.dependency_graph(db),
+            Item::Function(function),
+            &mut collect,
+        );
+    }
+
+    functions
+        .into_iter()
+        .flat_map(|function| {
+            let body = function.body(db);
+            body.calls
+                .iter()
+                .filter_map(|(id, call_type)| {
+                    ExternalCall::from_call_type(
+                        function,
+                        call_type,
+                        body.try_calls.contains(id),
+                        body.spans[id],
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
@@ -0,0 +1,105 @@
+use crate::context::AnalyzerContext;
+use crate::db::Analysis;
+use crate::errors::TypeError;
+use crate::namespace::items::{EnumId, EnumVariant, EnumVariantId};
+use crate::namespace::scopes::ItemScope;
+use crate::namespace::types::FixedSize;
+use crate::traversal::types::type_desc;
+use crate::AnalyzerDb;
+use fe_common::diagnostics::Label;
+use indexmap::map::{Entry, IndexMap};
+use smol_str::SmolStr;
+use std::convert::TryInto;
+use std::rc::Rc;
+
+/// The maximum number of variants an enum may have, as the tag is a `u8`.
+const MAX_VARIANT_COUNT: usize = 256;
+
+pub fn enum_all_variants(db: &dyn AnalyzerDb, enum_: EnumId) -> Rc<[EnumVariantId]> {
+    enum_
+        .data(db)
+        .ast
+        .kind
+        .variants
+        .iter()
+        .enumerate()
+        .map(|(tag, node)| {
+            db.intern_enum_variant(Rc::new(EnumVariant {
+                ast: node.clone(),
+                parent: enum_,
+                tag,
+            }))
+        })
+        .collect()
+}
+
+pub fn enum_variant_map(
+    db: &dyn AnalyzerDb,
+    enum_: EnumId,
+) -> Analysis<Rc<IndexMap<SmolStr, EnumVariantId>>> {
+    let mut scope = ItemScope::new(db, enum_.module(db));
+    let mut variants = IndexMap::<SmolStr, EnumVariantId>::new();
+
+    let enum_name = enum_.name(db);
+    let all_variants = db.enum_all_variants(enum_);
+    for variant in all_variants.iter() {
+        let node = &variant.data(db).ast;
+
+        match variants.entry(node.name().into()) {
+            Entry::Occupied(entry) => {
+                scope.duplicate_name_error(
+                    &format!("duplicate variant names in `enum {}`", enum_name),
+                    entry.key(),
+                    entry.get().data(db).ast.span,
+                    node.span,
+                );
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(*variant);
+            }
+        }
+    }
+
+    if all_variants.len() > MAX_VARIANT_COUNT {
+        scope.fancy_error(
+            &format!("`enum {}` has too many variants", enum_name),
+            vec![Label::primary(
+                enum_.name_span(db),
+                format!("this enum has {} variants", all_variants.len()),
+            )],
+            vec![format!(
+                "Note: an enum can have at most {} variants",
+                MAX_VARIANT_COUNT
+            )],
+        );
+    }
+
+    Analysis::new(Rc::new(variants), scope.diagnostics.into())
+}
+
+pub fn enum_variant_fields(
+    db: &dyn AnalyzerDb,
+    variant: EnumVariantId,
+) -> Analysis<Rc<[Result<FixedSize, TypeError>]>> {
+    let variant_data = variant.data(db);
+    let mut scope = ItemScope::new(db, variant_data.parent.module(db));
+
+    let fields = variant_data
+        .ast
+        .kind
+        .fields
+        .iter()
+        .map(|typ| match type_desc(&mut scope, typ)?.try_into() {
+            Ok(FixedSize::Base(base)) if !FixedSize::Base(base).is_unit() => {
+                Ok(FixedSize::Base(base))
+            }
+            _ => Err(TypeError::new(scope.error(
+                "enum variant fields must be primitive types",
+                typ.span,
+                "this can't be used as an enum variant field",
+            ))),
+        })
+        .collect();
+
+    Analysis::new(fields, scope.diagnostics.into())
+}
@@ -22,11 +22,18 @@ pub fn event_type(db: &dyn AnalyzerDb, event: EventId) -> Analysis<Rc<types::Eve
     let mut scope = ItemScope::new(db, event.module(db));
 
     let ast::Event {
+        attributes: _,
         name: event_name,
         fields: field_nodes,
         pub_qual: _,
+        doc: _,
     } = &event.data(db).ast.kind;
 
+    // An anonymous event doesn't log its signature, which leaves room for
+    // one more indexed field.
+    let anonymous = event.is_anonymous(db);
+    let max_indexed = MAX_INDEXED_EVENT_FIELDS + anonymous as usize;
+
     let mut names = HashMap::new();
     let mut indexed_count = 0;
     let fields = field_nodes
@@ -40,6 +47,21 @@ pub fn event_type(db: &dyn AnalyzerDb, event: EventId) -> Analysis<Rc<types::Eve
             } = &field.kind;
 
             let typ = type_desc(&mut scope, typ_node).and_then(|typ| match typ.try_into() {
+                Ok(types::FixedSize::Vec(_)) => Err(TypeError::new(scope.error(
+                    "event field type can't be `Vec`",
+                    typ_node.span,
+                    "a `Vec` can't be logged",
+                ))),
+                Ok(typ) if typ.contains_vec(db) => Err(TypeError::new(scope.error(
+                    "event field type can't hold a `Vec`",
+                    typ_node.span,
+                    "this type holds a `Vec`, which can't be logged",
+                ))),
+                Ok(types::FixedSize::Function(_)) => Err(TypeError::new(scope.error(
+                    "event field type can't be a function type",
+                    typ_node.span,
+                    "a function value can't be logged",
+                ))),
                 Ok(typ) => Ok(typ),
                 Err(_) => Err(TypeError::new(scope.error(
                     "event field type must have a fixed size",
@@ -48,6 +70,28 @@ pub fn event_type(db: &dyn AnalyzerDb, event: EventId) -> Analysis<Rc<types::Eve
                 ))),
             });
 
+            // Indexed values are logged as topics, and a value of a reference
+            // type is logged as the hash of its ABI encoding. This isn't
+            // supported if the encoding has a dynamic size, except for a
+            // string or byte array on its own.
+            if let Ok(
+                field_type @ (types::FixedSize::Tuple(_)
+                | types::FixedSize::Struct(_)
+                | types::FixedSize::Enum(_)),
+            ) = &typ
+            {
+                if *is_idx && contains_dynamic_abi_type(db, field_type) {
+                    scope.fancy_error(
+                        "indexed event field can't contain a string or byte array",
+                        vec![Label::primary(
+                            typ_node.span,
+                            format!("`{}` contains a string or byte array", field_type),
+                        )],
+                        vec!["Note: a string or byte array can be indexed on its own, in which case the hash of its bytes is logged".into()],
+                    );
+                }
+            }
+
             // If we've already seen the max number of indexed fields,
             // ignore the `idx` qualifier on this one. We'll emit an error below.
             indexed_count += *is_idx as usize;
@@ -77,8 +121,8 @@ pub fn event_type(db: &dyn AnalyzerDb, event: EventId) -> Analysis<Rc<types::Eve
         })
         .collect();
 
-    if indexed_count > MAX_INDEXED_EVENT_FIELDS {
-        let excess_count = indexed_count - MAX_INDEXED_EVENT_FIELDS;
+    if indexed_count > max_indexed {
+        let excess_count = indexed_count - max_indexed;
 
         let mut labels = field_nodes
             .iter()
@@ -92,10 +136,17 @@ pub fn event_type(db: &dyn AnalyzerDb, event: EventId) -> Analysis<Rc<types::Eve
         labels.last_mut().unwrap().message = format!("{} indexed fields", indexed_count);
 
         scope.fancy_error(
-            &format!(
-                "more than three indexed fields in `event {}`",
-                event_name.kind
-            ),
+            &if anonymous {
+                format!(
+                    "more than four indexed fields in anonymous `event {}`",
+                    event_name.kind
+                )
+            } else {
+                format!(
+                    "more than three indexed fields in `event {}`",
+                    event_name.kind
+                )
+            },
             labels,
             vec![format!(
                 "Note: Remove the `idx` keyword from at least {} {}.",
@@ -109,7 +160,36 @@ pub fn event_type(db: &dyn AnalyzerDb, event: EventId) -> Analysis<Rc<types::Eve
         value: Rc::new(types::Event {
             name: event_name.kind.clone(),
             fields,
+            anonymous,
         }),
         diagnostics: scope.diagnostics.into(),
     }
 }
+
+/// Returns `true` if the ABI encoding of the type contains a string or a byte
+/// array, i.e. an `Array<u8, N>`.
+fn contains_dynamic_abi_type(db: &dyn AnalyzerDb, typ: &types::FixedSize) -> bool {
+    let is_dynamic = |typ: &Result<types::FixedSize, TypeError>| matches!(typ, Ok(typ) if contains_dynamic_abi_type(db, typ));
+    match typ {
+        types::FixedSize::String(_) => true,
+        types::FixedSize::Array(array) => array.inner == types::Base::Numeric(types::Integer::U8),
+        types::FixedSize::Tuple(tuple) => tuple
+            .items
+            .iter()
+            .any(|item| contains_dynamic_abi_type(db, item)),
+        types::FixedSize::Struct(struct_) => struct_
+            .id
+            .fields(db)
+            .values()
+            .any(|field| is_dynamic(&field.typ(db))),
+        types::FixedSize::Enum(enum_) => enum_
+            .id
+            .variants(db)
+            .values()
+            .any(|variant| variant.fields(db).iter().any(is_dynamic)),
+        types::FixedSize::Base(_)
+        | types::FixedSize::Vec(_)
+        | types::FixedSize::Contract(_)
+        | types::FixedSize::Function(_) => false,
+    }
+}
@@ -1,20 +1,59 @@
-use crate::context::{AnalyzerContext, CallType, FunctionBody};
+use crate::builtins::ContractTypeMethod;
+use crate::context::{AnalyzerContext, CallType, Constant, FunctionBody};
 use crate::db::{Analysis, AnalyzerDb};
 use crate::errors::TypeError;
 use crate::namespace::items::{
     Class, DepGraph, DepGraphWrapper, DepLocality, FunctionId, Item, TypeDef,
 };
 use crate::namespace::scopes::{BlockScope, BlockScopeType, FunctionScope, ItemScope};
-use crate::namespace::types::{self, Contract, FixedSize, SelfDecl, Struct, Type};
+use crate::namespace::types::{
+    self, Contract, Enum, FixedSize, SelfDecl, StateMutability, Struct, Type,
+};
+use crate::traversal::const_expr;
+use crate::traversal::dead_stores::dead_stores;
+use crate::traversal::definite_assignment::uninitialized_reads;
+use crate::traversal::expressions;
 use crate::traversal::functions::traverse_statements;
-use crate::traversal::types::type_desc;
-use fe_common::diagnostics::Label;
+use crate::traversal::overflow_checks::profile_checked_arithmetic;
+use crate::traversal::types::{generic_params, type_desc};
+use crate::traversal::unused_variables::unused_variables;
+use fe_common::diagnostics::{Diagnostic, Label};
 use fe_parser::ast;
 use fe_parser::node::Node;
 use if_chain::if_chain;
+use indexmap::IndexSet;
+use smol_str::SmolStr;
 use std::collections::HashMap;
 use std::rc::Rc;
 
+/// Check the generic parameters of a function definition, and return their
+/// names.
+pub fn function_generic_params(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+) -> Analysis<Rc<[SmolStr]>> {
+    let def = &function.data(db).ast.kind;
+    let mut scope = ItemScope::new(db, function.module(db));
+    let params = generic_params(
+        &mut scope,
+        &format!("function `{}`", def.name.kind),
+        &def.generic_params,
+    );
+
+    if let (Some(class), Some(first), Some(last)) = (
+        function.class(db),
+        def.generic_params.first(),
+        def.generic_params.last(),
+    ) {
+        scope.not_yet_implemented(
+            &format!("generic {} functions", class.kind()),
+            first.span + last.span,
+        );
+    }
+
+    Analysis::new(params, scope.diagnostics.into())
+}
+
 /// Gather context information for a function definition and check for type
 /// errors. Does not inspect the function body.
 pub fn function_signature(
@@ -24,7 +63,7 @@ pub fn function_signature(
     let node = &function.data(db).ast;
     let def = &node.kind;
 
-    let mut scope = ItemScope::new(db, function.module(db));
+    let mut scope = ItemScope::with_type_params(db, function.module(db), function.type_params(db));
     let fn_parent = function.class(db);
 
     if_chain! {
@@ -38,14 +77,106 @@ pub fn function_signature(
         }
     }
 
+    if function.is_interface_function(db) {
+        if function.pub_span(db).is_none() {
+            scope.fancy_error(
+                "interface functions must be public",
+                vec![Label::primary(def.name.span, "this function is not `pub`")],
+                vec![format!(
+                    "Hint: use `pub fn {}(..` to declare the function",
+                    def.name.kind
+                )],
+            );
+        }
+        if is_special_contract_fn(&def.name.kind) {
+            scope.error(
+                &format!("`{}` can't be declared in an interface", def.name.kind),
+                def.name.span,
+                "not allowed in an interface",
+            );
+        }
+    }
+
+    if let Some(init_only_span) = function.init_only_span(db) {
+        if !matches!(fn_parent, Some(Class::Contract(_))) {
+            scope.error(
+                "`#[init_only]` can only be used on contract functions",
+                init_only_span,
+                "this function isn't defined in a contract",
+            );
+        } else if let Some(pub_span) = function.pub_span(db) {
+            scope.fancy_error(
+                "`#[init_only]` functions can't be public",
+                vec![
+                    Label::primary(pub_span, "this function is `pub`"),
+                    Label::secondary(init_only_span, "the function is marked `#[init_only]`"),
+                ],
+                vec![
+                    "Note: public functions can be called by anyone after the contract is deployed."
+                        .into(),
+                ],
+            );
+        }
+    }
+
+    // The params and return values of public contract functions are abi encoded.
+    let is_abi_function = matches!(fn_parent, Some(Class::Contract(_))) && function.is_public(db);
+
+    if let Some(payable_span) = function.payable_span(db) {
+        if !matches!(fn_parent, Some(Class::Contract(_))) {
+            scope.error(
+                "`#[payable]` can only be used on contract functions",
+                payable_span,
+                "this function isn't defined in a contract",
+            );
+        } else if !is_abi_function {
+            scope.fancy_error(
+                "`#[payable]` functions must be public",
+                vec![
+                    Label::primary(def.name.span, "this function isn't `pub`"),
+                    Label::secondary(payable_span, "the function is marked `#[payable]`"),
+                ],
+                vec![
+                    "Note: only calls to public functions can transfer ether to the contract."
+                        .into(),
+                ],
+            );
+        }
+    }
+
+    if let Some(selector_span) = function.selector_span(db) {
+        if !is_abi_function || is_special_contract_fn(&def.name.kind) {
+            scope.fancy_error(
+                "`#[selector(..)]` can only be used on public contract functions",
+                vec![Label::primary(
+                    selector_span,
+                    "this attribute isn't allowed here",
+                )],
+                vec!["Note: only public contract functions are called by their selector.".into()],
+            );
+        } else if function.pinned_selector(db).is_none() {
+            scope.fancy_error(
+                "invalid selector",
+                vec![Label::primary(selector_span, "this isn't a valid selector")],
+                vec![
+                    "Note: a selector is 4 bytes, written as `0x` followed by 8 hex digits, e.g. `#[selector(0xa9059cbb)]`"
+                        .into(),
+                ],
+            );
+        }
+    }
+
     let mut self_decl = None;
     let mut names = HashMap::new();
+    // The name of the first parameter with a default value. The parameters
+    // that follow it must have default values too.
+    let mut first_default: Option<&Node<SmolStr>> = None;
     let params = def
         .args
         .iter()
         .enumerate()
         .filter_map(|(index, arg)| match &arg.kind {
-            ast::FunctionArg::Zelf => {
+            ast::FunctionArg::Zelf { mut_ } => {
                 if fn_parent.is_none() {
                     scope.error(
                         "`self` can only be used in contract or struct functions",
@@ -53,7 +184,11 @@ pub fn function_signature(
                         "not allowed in functions defined outside of a contract or struct",
                     );
                 } else {
-                    self_decl = Some(SelfDecl::Mutable);
+                    self_decl = Some(if mut_.is_some() {
+                        SelfDecl::Mutable
+                    } else {
+                        SelfDecl::Immutable
+                    });
                     if index != 0 {
                         scope.error(
                             "`self` is not the first parameter",
@@ -65,10 +200,31 @@ pub fn function_signature(
                 None
             }
             ast::FunctionArg::Regular(ast::RegularFunctionArg {
+                mut_,
                 name,
                 typ: typ_node,
+                default,
             }) => {
                 let typ = type_desc(&mut scope, typ_node).and_then(|typ| match typ.try_into() {
+                    Ok(FixedSize::Vec(_)) if is_abi_function => Err(TypeError::new(scope.error(
+                        "public contract function parameters can't be `Vec`s",
+                        typ_node.span,
+                        "a `Vec` can't be abi encoded",
+                    ))),
+                    Ok(typ) if is_abi_function && typ.contains_vec(db) => {
+                        Err(TypeError::new(scope.error(
+                            "public contract function parameters can't hold a `Vec`",
+                            typ_node.span,
+                            "this type holds a `Vec`, which can't be abi encoded",
+                        )))
+                    }
+                    Ok(FixedSize::Function(_)) if is_abi_function => {
+                        Err(TypeError::new(scope.error(
+                            "public contract function parameters can't be function types",
+                            typ_node.span,
+                            "a function value can't be abi encoded",
+                        )))
+                    }
                     Ok(typ) => Ok(typ),
                     Err(_) => Err(TypeError::new(scope.error(
                         "function parameter types must have fixed size",
@@ -97,9 +253,28 @@ pub fn function_signature(
                     None
                 } else {
                     names.insert(&name.kind, index);
+                    match (default, first_default) {
+                        (Some(_), None) => first_default = Some(name),
+                        (None, Some(first)) => {
+                            scope.fancy_error(
+                                "parameters without a default value can't follow parameters with one",
+                                vec![
+                                    Label::primary(name.span, format!("`{}` has no default value", name.kind)),
+                                    Label::secondary(first.span, format!("`{}` has a default value", first.kind)),
+                                ],
+                                vec!["Hint: move the parameters with default values to the end.".into()],
+                            );
+                        }
+                        _ => {}
+                    }
+                    let default = default
+                        .as_ref()
+                        .map(|value| param_default(&mut scope, value, &typ, is_abi_function));
                     Some(types::FunctionParam {
                         name: name.kind.clone(),
+                        is_mut: mut_.is_some(),
                         typ,
+                        default,
                     })
                 }
             }
@@ -111,8 +286,9 @@ pub fn function_signature(
         .as_ref()
         .map(|type_node| {
             let fn_name = &def.name.kind;
-            if fn_name == "__init__" || fn_name == "__call__" {
-                // `__init__` and `__call__` must not return any type other than `()`.
+            if is_special_contract_fn(fn_name) {
+                // `__init__`, `__call__`, `__fallback__` and `__receive__` must
+                // not return any type other than `()`.
                 if type_node.kind != ast::TypeDesc::Unit {
                     scope.fancy_error(
                         &format!("`{}` function has incorrect return type", fn_name),
@@ -126,7 +302,22 @@ pub fn function_signature(
                 Ok(FixedSize::unit())
             } else {
                 match type_desc(&mut scope, type_node)?.try_into() {
-                    Ok(FixedSize::Struct(val)) if val.id.has_complex_fields(db) && function.is_public(db) => {
+                    Ok(FixedSize::Vec(_)) if is_abi_function => Err(TypeError::new(scope.error(
+                        "public contract functions can't return a `Vec`",
+                        type_node.span,
+                        "a `Vec` can't be abi encoded",
+                    ))),
+                    Ok(typ) if is_abi_function && typ.contains_vec(db) => Err(TypeError::new(scope.error(
+                        "public contract functions can't return a value that holds a `Vec`",
+                        type_node.span,
+                        "this type holds a `Vec`, which can't be abi encoded",
+                    ))),
+                    Ok(FixedSize::Function(_)) if is_abi_function => Err(TypeError::new(scope.error(
+                        "public contract functions can't return a function type",
+                        type_node.span,
+                        "a function value can't be abi encoded",
+                    ))),
+                    Ok(FixedSize::Struct(val)) if val.id.has_complex_fields(db) && is_abi_function => {
                         scope.not_yet_implemented("structs with complex fields can't be returned from public functions yet", type_node.span);
                         Ok(FixedSize::Struct(val))
                     }
@@ -151,6 +342,55 @@ pub fn function_signature(
     }
 }
 
+/// `true` for the names of the contract functions that aren't part of the
+/// contract's interface: the constructor, a custom call dispatcher, and the
+/// functions that the generated call dispatcher falls back to.
+pub(crate) fn is_special_contract_fn(name: &str) -> bool {
+    matches!(
+        name,
+        "__init__" | "__call__" | "__fallback__" | "__receive__"
+    )
+}
+
+/// Checks the default value of a function parameter and evaluates it.
+/// Default values are filled in when calls are lowered, so they must be
+/// constants of a primitive type.
+fn param_default(
+    scope: &mut ItemScope,
+    value: &Node<ast::Expr>,
+    typ: &Result<FixedSize, TypeError>,
+    is_abi_function: bool,
+) -> Result<Constant, TypeError> {
+    if is_abi_function {
+        return Err(TypeError::new(scope.fancy_error(
+            "public contract functions can't have default parameter values",
+            vec![Label::primary(value.span, "default value of a public function")],
+            vec!["Note: calls to public contract functions are abi encoded, and must provide every argument.".into()],
+        )));
+    }
+    let typ = match typ {
+        Ok(FixedSize::Base(base)) => Type::Base(*base),
+        Ok(typ) => {
+            return Err(TypeError::new(scope.error(
+                "default parameter values must have a primitive type",
+                value.span,
+                &format!("this parameter has type `{}`", typ),
+            )));
+        }
+        Err(err) => return Err(err.clone()),
+    };
+    let attributes = expressions::assignable_expr(scope, value, Some(&typ))?;
+    if attributes.typ != typ {
+        return Err(TypeError::new(scope.type_error(
+            "type mismatch",
+            value.span,
+            &typ,
+            &attributes.typ,
+        )));
+    }
+    Ok(const_expr::eval_expr(scope, value)?)
+}
+
 /// Gather context information for a function body and check for type errors.
 pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<FunctionBody>> {
     let def = &function.data(db).ast.kind;
@@ -159,9 +399,12 @@ pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<F
     // If the return type is unit, explicit return or no return (implicit) is valid,
     // so no scanning is necessary.
     // If the return type is anything else, we need to ensure that all code paths
-    // return or revert.
+    // return or revert. Interface functions don't have a body.
     if let Ok(return_type) = &function.signature(db).return_type {
-        if !return_type.is_unit() && !all_paths_return_or_revert(&def.body) {
+        if !return_type.is_unit()
+            && !function.is_interface_function(db)
+            && !all_paths_return_or_revert(&def.body)
+        {
             scope.fancy_error(
                 "function body is missing a return or revert statement",
                 vec![
@@ -193,13 +436,67 @@ pub fn function_body(db: &dyn AnalyzerDb, function: FunctionId) -> Analysis<Rc<F
     // a type or fn used in this fn body, because of the `DiagnosticVoucher`
     // system. (See the definition of `FatalError`)
     let _ = traverse_statements(&mut block_scope, &def.body);
+
+    let dead_stores = dead_stores(&scope.body.borrow(), &def.body);
+    for store in dead_stores {
+        scope.warning(
+            &format!("value assigned to `{}` is never read", store.place),
+            vec![
+                Label::primary(store.span, "this value is overwritten before it's read"),
+                Label::secondary(store.overwritten_at, "overwritten here"),
+            ],
+            vec!["Note: the first assignment is removed from the compiled contract".into()],
+        );
+        scope.body.borrow_mut().dead_stores.insert(store.stmt);
+    }
+
+    let arithmetic = profile_checked_arithmetic(&scope.body.borrow(), &def.body);
+    scope
+        .body
+        .borrow_mut()
+        .profile_checked_arithmetic
+        .extend(arithmetic);
+
+    let uninitialized_reads = uninitialized_reads(&scope.body.borrow(), &def.body);
+    for read in uninitialized_reads {
+        scope.fancy_error(
+            &format!("`{}` might be read before it's assigned", read.name),
+            vec![
+                Label::primary(read.span, format!("`{}` might not have a value here", read.name)),
+                Label::secondary(read.declared_at, "declared here without a value"),
+            ],
+            vec![format!(
+                "Hint: assign `{}` a value on every path before it's read, or give it a value when it's declared, e.g. `let {}: {} = ...`",
+                read.name, read.name, read.typ
+            )],
+        );
+    }
+
+    // Variables whose uses have errors would be reported as unused.
+    let has_errors = scope.diagnostics.borrow().iter().any(Diagnostic::is_error);
+    let unused_variables = if has_errors {
+        vec![]
+    } else {
+        unused_variables(&def.body)
+    };
+    for var in unused_variables {
+        scope.warning(
+            &format!("unused variable `{}`", var.name),
+            vec![Label::primary(var.span, "this variable is never read")],
+            vec![format!(
+                "Hint: if this is intended, prefix the name with an underscore: `_{}`",
+                var.name
+            )],
+        );
+    }
+
     Analysis {
         value: Rc::new(scope.body.into_inner()),
         diagnostics: scope.diagnostics.into_inner().into(),
     }
 }
 
-fn all_paths_return_or_revert(block: &[Node<ast::FuncStmt>]) -> bool {
+pub(crate) fn all_paths_return_or_revert(block: &[Node<ast::FuncStmt>]) -> bool {
     for statement in block.iter().rev() {
         match &statement.kind {
             ast::FuncStmt::Return { .. } | ast::FuncStmt::Revert { .. } => return true,
@@ -214,11 +511,32 @@ fn all_paths_return_or_revert(block: &[Node<ast::FuncStmt>]) -> bool {
                     return true;
                 }
             }
-            ast::FuncStmt::Unsafe(body) => {
+            ast::FuncStmt::Unsafe(body)
+            | ast::FuncStmt::Unchecked(body)
+            | ast::FuncStmt::Checked(body) => {
                 if all_paths_return_or_revert(body) {
                     return true;
                 }
             }
+            // A loop that's never exited with `break` can only be left by
+            // returning or reverting.
+            ast::FuncStmt::Loop { body, .. } if !loop_has_break(body) => return true,
+            ast::FuncStmt::Match { arms, .. }
+                if arms
+                    .iter()
+                    .all(|arm| all_paths_return_or_revert(&arm.kind.body)) =>
+            {
+                return true
+            }
+            // A revert that isn't caught is propagated.
+            ast::FuncStmt::Try { body, catches, .. }
+                if all_paths_return_or_revert(body)
+                    && catches
+                        .iter()
+                        .all(|clause| all_paths_return_or_revert(&clause.kind.body)) =>
+            {
+                return true
+            }
             _ => {}
         }
     }
@@ -226,6 +544,26 @@ fn all_paths_return_or_revert(block: &[Node<ast::FuncStmt>]) -> bool {
     false
 }
 
+/// Returns `true` if `body` contains a `break` statement that exits the loop
+/// whose body it is. `break`s of nested loops are ignored.
+pub(crate) fn loop_has_break(body: &[Node<ast::FuncStmt>]) -> bool {
+    body.iter().any(|statement| match &statement.kind {
+        ast::FuncStmt::Break { .. } => true,
+        ast::FuncStmt::If { body, or_else, .. } => loop_has_break(body) || loop_has_break(or_else),
+        ast::FuncStmt::Unsafe(body)
+        | ast::FuncStmt::Unchecked(body)
+        | ast::FuncStmt::Checked(body) => loop_has_break(body),
+        ast::FuncStmt::Match { arms, .. } => arms.iter().any(|arm| loop_has_break(&arm.kind.body)),
+        ast::FuncStmt::Try { body, catches, .. } => {
+            loop_has_break(body)
+                || catches
+                    .iter()
+                    .any(|clause| loop_has_break(&clause.kind.body))
+        }
+        _ => false,
+    })
+}
+
 pub fn function_dependency_graph(db: &dyn AnalyzerDb, function: FunctionId) -> DepGraphWrapper {
     let root = Item::Function(function);
 
@@ -252,6 +590,9 @@ pub fn function_dependency_graph(db: &dyn AnalyzerDb, function: FunctionId) -> D
                 FixedSize::Struct(Struct { id, .. }) => {
                     Some((root, Item::Type(TypeDef::Struct(id)), DepLocality::Local))
                 }
+                FixedSize::Enum(Enum { id, .. }) => {
+                    Some((root, Item::Type(TypeDef::Enum(id)), DepLocality::Local))
+                }
                 _ => None,
             }),
     );
@@ -293,6 +634,13 @@ pub fn function_dependency_graph(db: &dyn AnalyzerDb, function: FunctionId) -> D
                 ));
             }
             CallType::TypeConstructor(_) => {}
+            CallType::EnumConstructor(variant) => {
+                directs.push((
+                    root,
+                    Item::Type(TypeDef::Enum(variant.enum_id(db))),
+                    DepLocality::Local,
+                ));
+            }
             CallType::BuiltinAssociatedFunction { contract, .. } => {
                 // create/create2 call. The contract type is "external" for dependency graph purposes.
                 directs.push((
@@ -303,10 +651,20 @@ pub fn function_dependency_graph(db: &dyn AnalyzerDb, function: FunctionId) -> D
             }
             // Builtin functions aren't part of the dependency graph yet.
             CallType::BuiltinFunction(_)
+            | CallType::BuiltinAbiFunction { .. }
             | CallType::Intrinsic(_)
-            | CallType::BuiltinValueMethod { .. } => {}
+            | CallType::BuiltinValueMethod { .. }
+            | CallType::BuiltinIntegerConversion { .. } => {}
+            // The functions that a function value can refer to are added
+            // where they're referenced.
+            CallType::FunctionValue(_) => {}
         }
     }
+    directs.extend(
+        body.function_refs
+            .values()
+            .map(|function| (root, Item::Function(*function), DepLocality::Local)),
+    );
 
     directs.extend(
         body.emits
@@ -322,6 +680,9 @@ pub fn function_dependency_graph(db: &dyn AnalyzerDb, function: FunctionId) -> D
         FixedSize::Struct(Struct { id, .. }) => {
             Some((root, Item::Type(TypeDef::Struct(*id)), DepLocality::Local))
         }
+        FixedSize::Enum(Enum { id, .. }) => {
+            Some((root, Item::Type(TypeDef::Enum(*id)), DepLocality::Local))
+        }
         _ => None,
     }));
 
@@ -334,6 +695,91 @@ pub fn function_dependency_graph(db: &dyn AnalyzerDb, function: FunctionId) -> D
     DepGraphWrapper(Rc::new(graph))
 }
 
+/// Derives whether a function reads or modifies the state of the chain from
+/// its `self` parameter, its body, and the functions that it calls, directly
+/// or indirectly. The functions that are referred to as values are assumed to
+/// be called.
+pub fn function_state_mutability(db: &dyn AnalyzerDb, function: FunctionId) -> StateMutability {
+    let mut mutability = StateMutability::Pure;
+    let mut visited = IndexSet::new();
+    let mut queue = vec![function];
+    while let Some(function) = queue.pop() {
+        if visited.insert(function) {
+            mutability = mutability.max(own_state_mutability(db, function, &mut queue));
+        }
+    }
+    mutability
+}
+
+/// The state mutability of a function, not counting the functions that it
+/// calls, which are added to `callees`.
+fn own_state_mutability(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+    callees: &mut Vec<FunctionId>,
+) -> StateMutability {
+    // The `self` of a struct is in memory, so only the `self` of a contract
+    // gives access to the state. A function that takes `self` reads the state
+    // if it accesses a field of the contract, which is recorded in its body,
+    // but the body of an interface function is unknown.
+    let mut mutability = match (function.class(db), function.signature(db).self_decl) {
+        (Some(Class::Contract(_)), Some(SelfDecl::Mutable)) => StateMutability::Mutable,
+        (Some(Class::Contract(contract)), Some(SelfDecl::Immutable))
+            if contract.is_interface(db) =>
+        {
+            StateMutability::View
+        }
+        _ => StateMutability::Pure,
+    };
+
+    let body = function.body(db);
+    if !body.emits.is_empty() {
+        mutability = StateMutability::Mutable;
+    } else if !body.state_reads.is_empty() {
+        mutability = mutability.max(StateMutability::View);
+    }
+    for yul_mutability in body.yul_blocks.values() {
+        mutability = mutability.max(*yul_mutability);
+    }
+
+    for calltype in body.calls.values() {
+        let call_mutability = match calltype {
+            CallType::Pure(function)
+            | CallType::AssociatedFunction { function, .. }
+            | CallType::ValueMethod {
+                method: function, ..
+            } => {
+                callees.push(*function);
+                StateMutability::Pure
+            }
+            // Calling another contract reads the state, e.g. its code, even
+            // if the function that's called is pure.
+            CallType::External { function, .. } => {
+                callees.push(*function);
+                StateMutability::View
+            }
+            CallType::Intrinsic(intrinsic) => intrinsic.state_mutability(),
+            // `address_of` only hashes the init code of the contract.
+            CallType::BuiltinAssociatedFunction {
+                function: ContractTypeMethod::AddressOf,
+                ..
+            } => StateMutability::Pure,
+            // create, create2
+            CallType::BuiltinAssociatedFunction { .. } => StateMutability::Mutable,
+            CallType::BuiltinFunction(_)
+            | CallType::BuiltinAbiFunction { .. }
+            | CallType::BuiltinValueMethod { .. }
+            | CallType::BuiltinIntegerConversion { .. }
+            | CallType::TypeConstructor(_)
+            | CallType::EnumConstructor(_)
+            | CallType::FunctionValue(_) => StateMutability::Pure,
+        };
+        mutability = mutability.max(call_mutability);
+    }
+    callees.extend(body.function_refs.values());
+    mutability
+}
+
 pub fn function_dependency_graph_cycle(
     _db: &dyn AnalyzerDb,
     _cycle: &[String],
@@ -1,5 +1,8 @@
-use crate::namespace::items::{IngotId, IngotMode, ModuleId};
+use crate::namespace::items::{std_prelude_items, IngotId, IngotMode, Item, ModuleId};
 use crate::AnalyzerDb;
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+use std::rc::Rc;
 
 pub fn ingot_root_module(db: &dyn AnalyzerDb, ingot: IngotId) -> Option<ModuleId> {
     let filename = match ingot.data(db).mode {
@@ -14,3 +17,17 @@ pub fn ingot_root_module(db: &dyn AnalyzerDb, ingot: IngotId) -> Option<ModuleId
         .find(|modid| modid.file_path_relative_to_src_dir(db) == filename)
         .copied()
 }
+
+pub fn ingot_global_items(db: &dyn AnalyzerDb, ingot: IngotId) -> Rc<IndexMap<SmolStr, Item>> {
+    let mut items = ingot
+        .external_ingots(db)
+        .iter()
+        .map(|(name, ingot)| (name.clone(), Item::Ingot(*ingot)))
+        .chain(std_prelude_items())
+        .collect::<IndexMap<_, _>>();
+
+    if ingot.data(db).mode != IngotMode::StandaloneModule {
+        items.insert("ingot".into(), Item::Ingot(ingot));
+    }
+    Rc::new(items)
+}
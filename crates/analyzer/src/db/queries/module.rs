@@ -1,21 +1,26 @@
-use crate::context::{Analysis, AnalyzerContext, Constant};
+use crate::context::{Analysis, AnalyzerContext, CallType, Constant};
 use crate::db::AnalyzerDb;
 use crate::errors::{self, ConstEvalError, TypeError};
+use crate::features::{Edition, Feature, ModuleFeatures};
 use crate::namespace::items::{
-    Contract, ContractId, Event, Function, Item, ModuleConstant, ModuleConstantId, ModuleId,
-    ModuleSource, Struct, StructId, TypeAlias, TypeDef,
+    Contract, ContractId, Enum, Event, Function, FunctionId, Item, ModuleConstant,
+    ModuleConstantId, ModuleId, ModuleSource, Struct, StructId, TypeAlias, TypeDef,
 };
 use crate::namespace::scopes::ItemScope;
 use crate::namespace::types::{self, Type};
+use crate::traversal::pragma::check_pragma_version;
 use crate::traversal::{const_expr, expressions, types::type_desc};
-use fe_common::diagnostics::Label;
+use fe_common::diagnostics::{Diagnostic, Label};
 use fe_common::files::Utf8Path;
 use fe_common::Span;
+use fe_parser::lexer::{Lexer, TokenKind};
 use fe_parser::{ast, node::Node};
-use indexmap::indexmap;
 use indexmap::map::{Entry, IndexMap};
+use indexmap::{indexmap, IndexSet};
 use smol_str::SmolStr;
 use std::rc::Rc;
+use std::str::FromStr;
+use strum::IntoEnumIterator;
 
 pub fn module_file_path(db: &dyn AnalyzerDb, module: ModuleId) -> SmolStr {
     let full_path = match &module.data(db).source {
@@ -47,6 +52,113 @@ pub fn module_parse(db: &dyn AnalyzerDb, module: ModuleId) -> Analysis<Rc<ast::M
     }
 }
 
+pub fn constant_define_values(
+    db: &dyn AnalyzerDb,
+) -> Analysis<Rc<IndexMap<SmolStr, Node<ast::Expr>>>> {
+    let mut diagnostics = vec![];
+    let values = db
+        .constant_defines()
+        .iter()
+        .filter_map(|(name, file)| {
+            let (value, diags) = fe_parser::parse_expr(*file, &file.content(db.upcast()));
+            diagnostics.extend(diags);
+            Some((name.clone(), value?))
+        })
+        .collect();
+    Analysis::new(Rc::new(values), diagnostics.into())
+}
+
+/// The edition and features selected with the module's `pragma` statements.
+pub fn module_features(db: &dyn AnalyzerDb, module: ModuleId) -> Analysis<Rc<ModuleFeatures>> {
+    let mut scope = ItemScope::new(db, module);
+    let mut features = ModuleFeatures::default();
+    let mut edition_span: Option<Span> = None;
+    let mut enabled = IndexMap::<Feature, Span>::new();
+
+    for stmt in module.ast(db).body.iter() {
+        let pragma = match stmt {
+            ast::ModuleStmt::Pragma(pragma) => pragma,
+            _ => continue,
+        };
+        match &pragma.kind {
+            ast::Pragma::Version { .. } => {
+                if let Some(diag) = check_pragma_version(pragma) {
+                    scope.add_diagnostic(diag);
+                }
+            }
+            ast::Pragma::Edition { edition } => {
+                if let Some(first_span) = edition_span {
+                    scope.fancy_error(
+                        "duplicate edition pragma",
+                        vec![
+                            Label::primary(pragma.span, "edition specified again here"),
+                            Label::secondary(first_span, "the edition is first specified here"),
+                        ],
+                        vec![],
+                    );
+                } else if let Ok(value) = Edition::from_str(&edition.kind) {
+                    features.edition = value;
+                    edition_span = Some(pragma.span);
+                } else {
+                    scope.fancy_error(
+                        &format!("unknown edition `{}`", edition.kind),
+                        vec![Label::primary(edition.span, "unknown edition")],
+                        vec![format!(
+                            "Note: the available editions are {}",
+                            Edition::iter()
+                                .map(|edition| edition.as_ref().to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )],
+                    );
+                }
+            }
+            ast::Pragma::Feature { features: names } => {
+                for name in names {
+                    if let Ok(feature) = Feature::from_str(&name.kind) {
+                        enabled.entry(feature).or_insert(name.span);
+                    } else {
+                        scope.fancy_error(
+                            &format!("unknown feature `{}`", name.kind),
+                            vec![Label::primary(name.span, "unknown feature")],
+                            vec![format!(
+                                "Note: the available features are {}",
+                                Feature::iter()
+                                    .map(|feature| format!("`{}`", feature.as_ref()))
+                                    .collect::<Vec<_>>()
+                                    .join(", ")
+                            )],
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    for (feature, span) in enabled {
+        if features.is_enabled(feature) {
+            scope.warning(
+                &format!("feature `{}` doesn't need to be enabled", feature.as_ref()),
+                vec![Label::primary(
+                    span,
+                    format!(
+                        "{} are stable in edition {}",
+                        feature.display_name(),
+                        features.edition.as_ref()
+                    ),
+                )],
+                vec![],
+            );
+        }
+        features.enabled.insert(feature);
+    }
+
+    Analysis {
+        value: Rc::new(features),
+        diagnostics: scope.diagnostics.into(),
+    }
+}
+
 pub fn module_is_incomplete(db: &dyn AnalyzerDb, module: ModuleId) -> bool {
     if matches!(module.data(db).source, ModuleSource::File(_)) {
         let ast = module.ast(db);
@@ -61,6 +173,11 @@ pub fn module_is_incomplete(db: &dyn AnalyzerDb, module: ModuleId) -> bool {
 
 pub fn module_all_items(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[Item]> {
     let body = &module.ast(db).body;
+    let defines = if module.receives_constant_defines(db) {
+        db.constant_define_values().value
+    } else {
+        Rc::new(IndexMap::new())
+    };
 
     body.iter()
         .filter_map(|stmt| match stmt {
@@ -75,29 +192,51 @@ pub fn module_all_items(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[Item]> {
                     name: node.name().into(),
                     ast: node.clone(),
                     module,
+                    is_interface: false,
+                })),
+            ))),
+            ast::ModuleStmt::Interface(node) => Some(Item::Type(TypeDef::Contract(
+                db.intern_contract(Rc::new(Contract {
+                    name: node.name().into(),
+                    ast: interface_contract_ast(node),
+                    module,
+                    is_interface: true,
                 })),
             ))),
             ast::ModuleStmt::Struct(node) => Some(Item::Type(TypeDef::Struct(db.intern_struct(
                 Rc::new(Struct {
                     ast: node.clone(),
                     module,
+                    generic_args: vec![],
                 }),
             )))),
-            ast::ModuleStmt::Constant(node) => Some(Item::Constant(db.intern_module_const(
-                Rc::new(ModuleConstant {
-                    ast: *node.clone(),
+            ast::ModuleStmt::Enum(node) => {
+                Some(Item::Type(TypeDef::Enum(db.intern_enum(Rc::new(Enum {
+                    ast: node.clone(),
                     module,
-                }),
-            ))),
+                })))))
+            }
+            ast::ModuleStmt::Constant(node) => {
+                let mut ast = *node.clone();
+                // A constant that's defined at build time gets the defined value.
+                if let Some(value) = defines.get(&ast.kind.name.kind) {
+                    ast.kind.value = value.clone();
+                }
+                Some(Item::Constant(db.intern_module_const(Rc::new(
+                    ModuleConstant { ast, module },
+                ))))
+            }
             ast::ModuleStmt::Function(node) => {
                 Some(Item::Function(db.intern_function(Rc::new(Function {
                     ast: node.clone(),
                     module,
                     parent: None,
+                    generic_args: vec![],
                 }))))
             }
             ast::ModuleStmt::Pragma(_) => None,
             ast::ModuleStmt::Use(_) => None,
+            ast::ModuleStmt::Impl(_) => None,
             ast::ModuleStmt::Event(node) => Some(Item::Event(db.intern_event(Rc::new(Event {
                 ast: node.clone(),
                 module,
@@ -108,6 +247,28 @@ pub fn module_all_items(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[Item]> {
         .collect()
 }
 
+pub fn module_internal_items(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<IndexMap<SmolStr, Item>> {
+    let global_items = module.global_items(db);
+    let defined_items = module.items(db);
+    Rc::new(
+        module
+            .submodules(db)
+            .iter()
+            .map(|id| (id.name(db), Item::Module(*id)))
+            .chain(
+                global_items
+                    .iter()
+                    .map(|(name, item)| (name.clone(), *item)),
+            )
+            .chain(
+                defined_items
+                    .iter()
+                    .map(|(name, item)| (name.clone(), *item)),
+            )
+            .collect(),
+    )
+}
+
 pub fn module_item_map(
     db: &dyn AnalyzerDb,
     module: ModuleId,
@@ -157,6 +318,10 @@ pub fn module_item_map(
         }
 
         match map.entry(item_name.clone()) {
+            // Functions can be overloaded. The overloads are checked in
+            // `module_function_overloads`, and the first one is in the map.
+            Entry::Occupied(entry)
+                if matches!((entry.get(), item), (Item::Function(_), Item::Function(_))) => {}
             Entry::Occupied(entry) => {
                 if let Some(entry_name_span) = entry.get().name_span(db) {
                     diagnostics.push(errors::duplicate_name_error(
@@ -206,17 +371,294 @@ pub fn module_item_map(
     )
 }
 
+/// The functions of the module that share their name with another function,
+/// grouped by name in declaration order. Overloads can't be generic, and
+/// must have different parameter types.
+pub fn module_function_overloads(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+) -> Analysis<Rc<IndexMap<SmolStr, Rc<[FunctionId]>>>> {
+    let mut functions = IndexMap::<SmolStr, Vec<FunctionId>>::new();
+    for item in module.all_items(db).iter() {
+        if let Item::Function(function) = item {
+            functions
+                .entry(function.name(db))
+                .or_default()
+                .push(*function);
+        }
+    }
+
+    // `None` if a parameter type is invalid.
+    let param_types = |function: &FunctionId| {
+        function
+            .signature(db)
+            .params
+            .iter()
+            .map(|param| param.typ.clone().ok())
+            .collect::<Option<Vec<_>>>()
+    };
+
+    let mut diagnostics = vec![];
+    let overloads = functions
+        .into_iter()
+        .filter(|(_, overloads)| overloads.len() > 1)
+        .map(|(name, overloads)| {
+            for (index, function) in overloads.iter().enumerate() {
+                if function.is_generic(db) {
+                    diagnostics.push(errors::fancy_error(
+                        format!("generic function `{}` can't be overloaded", name),
+                        vec![Label::primary(
+                            function.name_span(db),
+                            "this function is generic",
+                        )],
+                        vec![format!(
+                            "Note: `{}` is defined {} times in this module.",
+                            name,
+                            overloads.len()
+                        )],
+                    ));
+                    continue;
+                }
+                let types = match param_types(function) {
+                    Some(types) => types,
+                    None => continue,
+                };
+                if let Some(original) = overloads[..index].iter().find(|original| {
+                    !original.is_generic(db) && param_types(original).as_ref() == Some(&types)
+                }) {
+                    diagnostics.push(errors::duplicate_name_error(
+                        &format!(
+                            "a function named \"{}\" with the same parameter types has already been defined",
+                            name
+                        ),
+                        &name,
+                        original.name_span(db),
+                        function.name_span(db),
+                    ));
+                }
+            }
+            (name, overloads.into())
+        })
+        .collect();
+
+    Analysis::new(Rc::new(overloads), diagnostics.into())
+}
+
+/// An interface is represented as a contract without fields or events, whose
+/// functions have empty bodies.
+fn interface_contract_ast(node: &Node<ast::Interface>) -> Node<ast::Contract> {
+    Node::new(
+        ast::Contract {
+            attributes: vec![],
+            name: node.kind.name.clone(),
+            parents: vec![],
+            fields: vec![],
+            body: node
+                .kind
+                .functions
+                .iter()
+                .cloned()
+                .map(ast::ContractStmt::Function)
+                .collect(),
+            pub_qual: node.kind.pub_qual,
+            doc: node.kind.doc.clone(),
+        },
+        node.span,
+    )
+}
+
+/// The contracts that are defined in the module, excluding interfaces.
 pub fn module_contracts(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[ContractId]> {
     module
         .all_items(db)
         .iter()
         .filter_map(|item| match item {
-            Item::Type(TypeDef::Contract(id)) => Some(*id),
+            Item::Type(TypeDef::Contract(id)) if !id.is_interface(db) => Some(*id),
             _ => None,
         })
         .collect()
 }
 
+/// The `(contract, interface)` pairs of the `impl` statements in the module.
+/// Reports the contracts that don't implement every function of the interface
+/// with a matching signature.
+pub fn module_interface_impls(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+) -> Analysis<Rc<[(ContractId, ContractId)]>> {
+    let mut diagnostics = vec![];
+    let mut impls = vec![];
+    for stmt in &module.ast(db).body {
+        let node = match stmt {
+            ast::ModuleStmt::Impl(node) => node,
+            _ => continue,
+        };
+        let interface =
+            resolve_impl_contract(db, module, &node.kind.interface, true, &mut diagnostics);
+        let contract =
+            resolve_impl_contract(db, module, &node.kind.contract, false, &mut diagnostics);
+        if let (Some(interface), Some(contract)) = (interface, contract) {
+            check_interface_impl(db, contract, interface, node.span, &mut diagnostics);
+            impls.push((contract, interface));
+        }
+    }
+    Analysis::new(impls.into(), diagnostics.into())
+}
+
+/// Resolves the interface or contract named in an `impl` statement.
+fn resolve_impl_contract(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    name: &Node<SmolStr>,
+    expect_interface: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> Option<ContractId> {
+    let expected = if expect_interface {
+        "an interface"
+    } else {
+        "a contract"
+    };
+    let found = match module.resolve_name(db, &name.kind) {
+        Ok(Some(Item::Type(TypeDef::Contract(id)))) if id.is_interface(db) == expect_interface => {
+            return Some(id)
+        }
+        Ok(Some(Item::Type(TypeDef::Contract(id)))) if id.is_interface(db) => {
+            "an interface".to_string()
+        }
+        Ok(Some(Item::Type(TypeDef::Contract(_)))) => "a contract".to_string(),
+        Ok(Some(item)) => {
+            let kind = item.item_kind_display_name();
+            let article = if kind.starts_with(['a', 'e', 'i', 'o', 'u']) {
+                "an"
+            } else {
+                "a"
+            };
+            format!("{} {}", article, kind)
+        }
+        Ok(None) => {
+            diagnostics.push(errors::error(
+                format!("`{}` is not defined", name.kind),
+                name.span,
+                format!("expected {} named `{}`", expected, name.kind),
+            ));
+            return None;
+        }
+        // The module has a syntax error.
+        Err(_) => return None,
+    };
+    diagnostics.push(errors::error(
+        format!("`{}` is not {}", name.kind, expected),
+        name.span,
+        format!("expected {}, found {}", expected, found),
+    ));
+    None
+}
+
+/// Checks that `contract` has a public function matching each function of
+/// `interface`. The parameter and return types must be the same, and a function
+/// must take `self` if and only if the interface function does.
+fn check_interface_impl(
+    db: &dyn AnalyzerDb,
+    contract: ContractId,
+    interface: ContractId,
+    impl_span: Span,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let contract_name = contract.name(db);
+    let interface_name = interface.name(db);
+    let contract_functions = contract.functions(db);
+    for (name, interface_fn) in interface.functions(db).iter() {
+        let expected_sig = interface_fn.data(db).ast.kind.signature_string();
+        let function = match contract_functions.get(name) {
+            Some(function) => *function,
+            None => {
+                diagnostics.push(errors::fancy_error(
+                    format!(
+                        "`{}` doesn't implement `{}` of interface `{}`",
+                        contract_name, name, interface_name
+                    ),
+                    vec![
+                        Label::primary(impl_span, format!("`{}` is missing", name)),
+                        Label::secondary(
+                            interface_fn.name_span(db),
+                            format!("`{}` is declared here", name),
+                        ),
+                    ],
+                    vec![format!(
+                        "Hint: add `{}:` to contract `{}`",
+                        expected_sig, contract_name
+                    )],
+                ));
+                continue;
+            }
+        };
+
+        let expected = interface_fn.signature(db);
+        let actual = function.signature(db);
+        let mut notes = vec![];
+        if !function.is_public(db) {
+            notes.push("Note: the function isn't `pub`".to_string());
+        }
+        match (expected.self_decl, actual.self_decl) {
+            (Some(_), None) => notes.push("Note: the function doesn't take `self`".into()),
+            (None, Some(_)) => notes.push("Note: the function takes `self`".into()),
+            (Some(types::SelfDecl::Immutable), Some(types::SelfDecl::Mutable)) => notes.push(
+                "Note: the function takes `mut self`, but the interface function takes `self`"
+                    .into(),
+            ),
+            _ => {}
+        }
+        if expected.params.len() != actual.params.len() {
+            notes.push(format!(
+                "Note: the function takes {} parameters, but the interface function takes {}",
+                actual.params.len(),
+                expected.params.len()
+            ));
+        } else {
+            for (expected, actual) in expected.params.iter().zip(&actual.params) {
+                if let (Ok(expected_typ), Ok(actual_typ)) = (&expected.typ, &actual.typ) {
+                    if expected_typ != actual_typ {
+                        notes.push(format!(
+                            "Note: parameter `{}` has type `{}`, but the interface expects `{}`",
+                            actual.name, actual_typ, expected_typ
+                        ));
+                    }
+                }
+            }
+        }
+        if let (Ok(expected_typ), Ok(actual_typ)) = (&expected.return_type, &actual.return_type) {
+            if expected_typ != actual_typ {
+                notes.push(format!(
+                    "Note: the function returns `{}`, but the interface function returns `{}`",
+                    actual_typ, expected_typ
+                ));
+            }
+        }
+
+        if !notes.is_empty() {
+            notes.push(format!(
+                "Hint: the expected signature is `{}`",
+                expected_sig
+            ));
+            diagnostics.push(errors::fancy_error(
+                format!(
+                    "`{}.{}` doesn't match the function of interface `{}`",
+                    contract_name, name, interface_name
+                ),
+                vec![
+                    Label::primary(function.name_span(db), "doesn't match the interface"),
+                    Label::secondary(
+                        interface_fn.name_span(db),
+                        format!("`{}` is declared here", name),
+                    ),
+                    Label::secondary(impl_span, "required by this `impl`"),
+                ],
+                notes,
+            ));
+        }
+    }
+}
+
 pub fn module_structs(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[StructId]> {
     module
         .all_items(db)
@@ -235,6 +677,63 @@ pub fn module_structs(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[StructId]> {
         .collect()
 }
 
+/// Returns the instances of generic structs and functions that are used by the
+/// non-generic items of the module, including the instances that are used by
+/// other instances.
+pub fn module_generic_instances(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[Item]> {
+    let mut roots = vec![];
+    for item in module.all_items(db).iter() {
+        match item {
+            Item::Function(id) if !id.is_generic(db) => roots.push(*item),
+            Item::Type(TypeDef::Struct(id)) if !id.is_generic(db) => {
+                roots.push(*item);
+                roots.extend(
+                    db.struct_all_functions(*id)
+                        .iter()
+                        .map(|id| Item::Function(*id)),
+                );
+            }
+            Item::Type(TypeDef::Contract(id)) => {
+                roots.push(*item);
+                roots.extend(
+                    db.contract_all_functions(*id)
+                        .iter()
+                        .map(|id| Item::Function(*id)),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    let mut instances = IndexSet::new();
+    while let Some(root) = roots.pop() {
+        let graph = match root.dependency_graph(db) {
+            Some(graph) => graph,
+            None => continue,
+        };
+        for item in graph.nodes() {
+            match item {
+                Item::Function(id) if !id.generic_args(db).is_empty() && instances.insert(item) => {
+                    roots.push(item);
+                }
+                // All of the functions of a struct instance are lowered, not
+                // just the ones that are called.
+                Item::Type(TypeDef::Struct(id))
+                    if !id.generic_args(db).is_empty() && instances.insert(item) =>
+                {
+                    roots.extend(
+                        db.struct_all_functions(id)
+                            .iter()
+                            .map(|id| Item::Function(*id)),
+                    );
+                }
+                _ => {}
+            }
+        }
+    }
+    instances.into_iter().collect()
+}
+
 pub fn module_constants(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<Vec<ModuleConstantId>> {
     Rc::new(
         module
@@ -290,11 +789,11 @@ pub fn module_constant_type_cycle(
     constant: &ModuleConstantId,
 ) -> Analysis<Result<Type, TypeError>> {
     let mut context = ItemScope::new(db, constant.data(db).module);
-    let err = Err(TypeError::new(context.error(
-        "recursive constant value definition",
-        constant.data(db).ast.span,
-        "",
-    )));
+    let span = constant.data(db).ast.span;
+    let err = Err(
+        TypeError::new(context.error("recursive constant value definition", span, ""))
+            .with_cause(span, "this constant depends on its own value"),
+    );
 
     Analysis {
         value: err,
@@ -351,11 +850,12 @@ pub fn module_constant_value_cycle(
     constant: &ModuleConstantId,
 ) -> Analysis<Result<Constant, ConstEvalError>> {
     let mut context = ItemScope::new(db, constant.data(db).module);
-    let err = Err(ConstEvalError::new(context.error(
-        "recursive constant value definition",
-        constant.data(db).ast.span,
-        "",
-    )));
+    let span = constant.data(db).ast.span;
+    let err =
+        Err(
+            ConstEvalError::new(context.error("recursive constant value definition", span, ""))
+                .with_cause(span, "this constant depends on its own value"),
+        );
 
     Analysis {
         value: err,
@@ -373,51 +873,61 @@ pub fn module_used_item_map(
     let mut diagnostics = vec![];
     let body = &module.ast(db).body;
 
-    let items = body
+    // Items that are imported with a glob import are shadowed by the items that
+    // are defined in the module.
+    let defined_names = module
+        .all_items(db)
         .iter()
-        .fold(indexmap! {}, |mut accum, stmt| {
-            if let ast::ModuleStmt::Use(use_stmt) = stmt {
-                let items = resolve_use_tree(db, module, &use_stmt.kind.tree, true);
-                diagnostics.extend(items.diagnostics.iter().cloned());
-
-                for (name, (name_span, item)) in items.value.iter() {
-                    if let Some((other_name_span, other_item)) =
-                        accum.insert(name.clone(), (*name_span, *item))
-                    {
-                        diagnostics.push(errors::duplicate_name_error(
-                            &format!(
-                                "a {} with the same name has already been imported",
-                                other_item.item_kind_display_name()
-                            ),
-                            name,
-                            other_name_span,
-                            *name_span,
-                        ));
-                    }
-                }
-            }
+        .map(|item| item.name(db))
+        .chain(module.submodules(db).iter().map(|id| id.name(db)))
+        .collect::<IndexSet<_>>();
 
-            accum
-        })
+    let mut resolver = ImportResolver::new(db);
+    let mut used_items = vec![];
+    for stmt in body {
+        if let ast::ModuleStmt::Use(use_stmt) = stmt {
+            let items = resolve_use_tree(&mut resolver, module, None, &use_stmt.kind.tree);
+            diagnostics.extend(items.diagnostics.iter().cloned());
+            used_items.extend(
+                items
+                    .value
+                    .iter()
+                    .filter(|(name, used)| {
+                        !(used.is_glob
+                            && (defined_names.contains(*name) || global_items.contains_key(*name)))
+                    })
+                    .map(|(name, used)| (name.clone(), *used)),
+            );
+        }
+    }
+
+    let items = merge_used_items(used_items, &mut diagnostics)
         .into_iter()
-        .filter_map(|(name, (name_span, item))| {
-            if let Some(global_item) = global_items.get(&name) {
-                let other_kind = global_item.item_kind_display_name();
+        .filter_map(
+            |(
+                name,
+                UsedItem {
+                    name_span, item, ..
+                },
+            )| {
+                if let Some(global_item) = global_items.get(&name) {
+                    let other_kind = global_item.item_kind_display_name();
 
-                diagnostics.push(errors::error(
-                    &format!(
-                        "import name conflicts with the {} named \"{}\"",
-                        other_kind, name
-                    ),
-                    name_span,
-                    &format!("`{}` is already defined", name),
-                ));
+                    diagnostics.push(errors::error(
+                        &format!(
+                            "import name conflicts with the {} named \"{}\"",
+                            other_kind, name
+                        ),
+                        name_span,
+                        &format!("`{}` is already defined", name),
+                    ));
 
-                None
-            } else {
-                Some((name, (name_span, item)))
-            }
-        })
+                    None
+                } else {
+                    Some((name, (name_span, item)))
+                }
+            },
+        )
         .collect::<IndexMap<_, _>>();
 
     Analysis::new(Rc::new(items), diagnostics.into())
@@ -482,40 +992,289 @@ pub fn module_submodules(db: &dyn AnalyzerDb, module: ModuleId) -> Rc<[ModuleId]
     }
 }
 
-/// Resolve a use tree entirely. We set internal to true if the first path item is internal.
+/// Warns about the items that are imported by name by the `use` statements of
+/// the module, but never referred to. An imported name counts as used if it
+/// appears anywhere in the module outside of the `use` statements, or in
+/// another module of the ingot that might import the item through this
+/// module: in one of its `use` statements, or anywhere in it if it has a glob
+/// import.
+pub fn module_unused_imports(db: &dyn AnalyzerDb, module: ModuleId) -> Analysis<Rc<[SmolStr]>> {
+    let mut imported = IndexSet::new();
+    for stmt in &module.ast(db).body {
+        if let ast::ModuleStmt::Use(use_stmt) = stmt {
+            import_names(&use_stmt.kind.tree, &mut imported);
+        }
+    }
+    if imported.is_empty() {
+        return Analysis::new(Rc::new([]), Rc::new([]));
+    }
+
+    let mut referred = source_names(db, module);
+    for other in module.ingot(db).all_modules(db).iter() {
+        if *other == module {
+            continue;
+        }
+        let mut has_glob = false;
+        for stmt in &other.ast(db).body {
+            if let ast::ModuleStmt::Use(use_stmt) = stmt {
+                use_tree_segments(&use_stmt.kind.tree, &mut referred);
+                has_glob |= has_glob_import(&use_stmt.kind.tree);
+            }
+        }
+        if has_glob {
+            referred.extend(source_names(db, *other));
+        }
+    }
+
+    let used_items = module.used_items(db);
+    let mut diagnostics = vec![];
+    let mut unused = vec![];
+    for name in imported {
+        if referred.contains(&name) || name.starts_with('_') {
+            continue;
+        }
+        // Imports that can't be resolved are reported as errors.
+        if let Some((span, _)) = used_items.get(&name) {
+            diagnostics.push(errors::warning(
+                format!("unused import `{}`", name),
+                vec![Label::primary(
+                    *span,
+                    format!("`{}` is never used in this module", name),
+                )],
+                vec![],
+            ));
+            unused.push(name);
+        }
+    }
+    Analysis::new(unused.into(), diagnostics.into())
+}
+
+fn has_errors(db: &dyn AnalyzerDb, function: FunctionId) -> bool {
+    let signature = db.function_signature(function);
+    if function.is_generic(db) {
+        return signature.diagnostics.iter().any(Diagnostic::is_error);
+    }
+    signature
+        .diagnostics
+        .iter()
+        .chain(db.function_body(function).diagnostics.iter())
+        .any(Diagnostic::is_error)
+}
+
+/// The names in the source code of a module, outside of its `use` statements.
+fn source_names(db: &dyn AnalyzerDb, module: ModuleId) -> IndexSet<SmolStr> {
+    let file = match module.data(db).source {
+        ModuleSource::File(file) => file,
+        ModuleSource::Dir(_) | ModuleSource::Lowered { .. } => return IndexSet::new(),
+    };
+    let use_spans = module
+        .ast(db)
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            ast::ModuleStmt::Use(use_stmt) => Some(use_stmt.span),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let content = file.content(db.upcast());
+    Lexer::new(file, &content)
+        .filter(|token| {
+            token.kind == TokenKind::Name
+                && !use_spans
+                    .iter()
+                    .any(|span| span.start <= token.span.start && token.span.end <= span.end)
+        })
+        .map(|token| SmolStr::new(token.text))
+        .collect()
+}
+
+/// Collects the names of the path segments in a `use` tree.
+fn use_tree_segments(tree: &Node<ast::UseTree>, names: &mut IndexSet<SmolStr>) {
+    match &tree.kind {
+        ast::UseTree::Glob { prefix } => {
+            names.extend(prefix.segments.iter().map(|segment| segment.kind.clone()))
+        }
+        ast::UseTree::Simple { path, .. } => {
+            names.extend(path.segments.iter().map(|segment| segment.kind.clone()))
+        }
+        ast::UseTree::Nested { prefix, children } => {
+            names.extend(prefix.segments.iter().map(|segment| segment.kind.clone()));
+            for child in children {
+                use_tree_segments(child, names)
+            }
+        }
+    }
+}
+
+/// Warns about the private functions of the module, and of its contracts and
+/// structs, that are never called or used as a value. Functions whose names
+/// start with an underscore, and functions like `__init__` that are called
+/// implicitly, are ignored.
+///
+/// A function that's only called by other unused functions isn't reported,
+/// and neither is a function with errors, which might be the reason that it
+/// isn't called.
+pub fn module_unused_functions(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+) -> Analysis<Rc<[FunctionId]>> {
+    let mut functions = vec![];
+    for item in module.all_items(db).iter() {
+        match item {
+            Item::Function(id) => functions.push(*id),
+            Item::Type(TypeDef::Struct(id)) => {
+                functions.extend(db.struct_all_functions(*id).iter().copied())
+            }
+            Item::Type(TypeDef::Contract(id)) if !id.is_interface(db) => {
+                functions.extend(db.contract_all_functions(*id).iter().copied())
+            }
+            _ => {}
+        }
+    }
+
+    // Generic functions are only analyzed once they're instantiated.
+    let mut analyzed = functions
+        .iter()
+        .copied()
+        .filter(|id| !id.is_generic(db))
+        .collect::<Vec<_>>();
+    for item in db.module_generic_instances(module).iter() {
+        match item {
+            Item::Function(id) => analyzed.push(*id),
+            Item::Type(TypeDef::Struct(id)) => {
+                analyzed.extend(db.struct_all_functions(*id).iter().copied())
+            }
+            _ => {}
+        }
+    }
+
+    // The instances of a generic function share its AST node.
+    let mut used = IndexSet::new();
+    for id in analyzed {
+        let body = id.body(db);
+        for function in body
+            .calls
+            .values()
+            .filter_map(CallType::function)
+            .chain(body.function_refs.values().copied())
+        {
+            used.insert(function.data(db).ast.id);
+        }
+    }
+
+    let mut diagnostics = vec![];
+    let mut unused = vec![];
+    for id in functions {
+        let name = id.name(db);
+        if id.is_public(db)
+            || name.starts_with('_')
+            || used.contains(&id.data(db).ast.id)
+            || has_errors(db, id)
+        {
+            continue;
+        }
+        diagnostics.push(errors::warning(
+            format!("function `{}` is never used", name),
+            vec![Label::primary(
+                id.name_span(db),
+                "this private function is never called",
+            )],
+            vec![format!(
+                "Hint: if this is intended, prefix the name with an underscore: `_{}`",
+                name
+            )],
+        ));
+        unused.push(id);
+    }
+    Analysis::new(unused.into(), diagnostics.into())
+}
+
+/// An item that's imported by a `use` statement.
+#[derive(Clone, Copy)]
+struct UsedItem {
+    name_span: Span,
+    item: Item,
+    /// `true` if the item is imported with a glob import, e.g. `use foo::*`.
+    is_glob: bool,
+}
+
+/// Merges the items that are imported by a list of `use` trees. An item that's
+/// imported explicitly shadows an item with the same name that's imported with
+/// a glob import, and glob imports may import the same item more than once.
+/// Otherwise, two items with the same name are a conflict.
+fn merge_used_items(
+    mut items: Vec<(SmolStr, UsedItem)>,
+    diagnostics: &mut Vec<Diagnostic>,
+) -> IndexMap<SmolStr, UsedItem> {
+    // Explicit imports take precedence over glob imports, regardless of the
+    // order of the `use` statements.
+    items.sort_by_key(|(_, used)| used.is_glob);
+
+    let mut merged = IndexMap::<SmolStr, UsedItem>::new();
+    for (name, used) in items {
+        match merged.entry(name) {
+            Entry::Vacant(entry) => {
+                entry.insert(used);
+            }
+            Entry::Occupied(entry) => {
+                let other = entry.get();
+                if used.is_glob && (!other.is_glob || other.item == used.item) {
+                    continue;
+                }
+                diagnostics.push(errors::duplicate_name_error(
+                    &format!(
+                        "a {} with the same name has already been imported",
+                        other.item.item_kind_display_name()
+                    ),
+                    entry.key(),
+                    other.name_span,
+                    used.name_span,
+                ));
+            }
+        }
+    }
+    merged
+}
+
+/// Resolve a use tree entirely. `parent` is the item that the tree's path
+/// starts at, or `None` if the path starts with an internal item of `from`,
+/// the module that contains the `use` statement.
 ///
 /// e.g. `foo::bar::{baz::bing}`
 ///       ---        ---
-///        ^          ^ baz is not internal
+///        ^          ^ baz is looked up in `foo::bar`
 ///        foo is internal
 fn resolve_use_tree(
-    db: &dyn AnalyzerDb,
-    module: ModuleId,
+    resolver: &mut ImportResolver,
+    from: ModuleId,
+    parent: Option<Item>,
     tree: &Node<ast::UseTree>,
-    internal: bool,
-) -> Analysis<Rc<IndexMap<SmolStr, (Span, Item)>>> {
+) -> Analysis<Rc<IndexMap<SmolStr, UsedItem>>> {
+    let db = resolver.db;
     let mut diagnostics = vec![];
 
-    // Again, the path resolution method we use depends on whether or not the first item
-    // is internal.
-    let resolve_path = |module: ModuleId, db: &dyn AnalyzerDb, path: &ast::Path| {
-        if internal {
-            module.resolve_path_non_used_internal(db, path)
-        } else {
-            module.resolve_path(db, path)
-        }
-    };
-
     match &tree.kind {
         ast::UseTree::Glob { prefix } => {
-            let prefix_module = resolve_path(module, db, prefix);
+            let prefix_module = resolver.resolve_path(from, parent, prefix);
             diagnostics.extend(prefix_module.diagnostics.iter().cloned());
 
             let items = match prefix_module.value {
-                Some(Item::Module(module)) => module
-                    .items(db)
-                    .iter()
-                    .map(|(name, item)| (name.clone(), (tree.span, *item)))
+                // Items that aren't visible from `from` are left out.
+                Some(Item::Module(module)) => resolver
+                    .module_items(module)
+                    .into_iter()
+                    .filter(|(_, item)| {
+                        item.is_public(db) || from.can_access_private_items_of(db, module)
+                    })
+                    .map(|(name, item)| {
+                        let used = UsedItem {
+                            name_span: tree.span,
+                            item,
+                            is_glob: true,
+                        };
+                        (name, used)
+                    })
                     .collect(),
                 Some(item) => {
                     diagnostics.push(errors::error(
@@ -531,33 +1290,20 @@ fn resolve_use_tree(
             Analysis::new(items.into(), diagnostics.into())
         }
         ast::UseTree::Nested { prefix, children } => {
-            let prefix_module = resolve_path(module, db, prefix);
+            let prefix_module = resolver.resolve_path(from, parent, prefix);
             diagnostics.extend(prefix_module.diagnostics.iter().cloned());
 
             let items = match prefix_module.value {
                 Some(Item::Module(module)) => {
-                    children.iter().fold(indexmap! {}, |mut accum, node| {
-                        let child_items = resolve_use_tree(db, module, node, false);
-                        diagnostics.extend(child_items.diagnostics.iter().cloned());
-
-                        for (name, (name_span, item)) in child_items.value.iter() {
-                            if let Some((other_name_span, other_item)) =
-                                accum.insert(name.clone(), (*name_span, *item))
-                            {
-                                diagnostics.push(errors::duplicate_name_error(
-                                    &format!(
-                                        "a {} with the same name has already been imported",
-                                        other_item.item_kind_display_name()
-                                    ),
-                                    name,
-                                    other_name_span,
-                                    *name_span,
-                                ));
-                            }
-                        }
-
-                        accum
-                    })
+                    let mut child_items = vec![];
+                    for node in children {
+                        let items =
+                            resolve_use_tree(resolver, from, Some(Item::Module(module)), node);
+                        diagnostics.extend(items.diagnostics.iter().cloned());
+                        child_items
+                            .extend(items.value.iter().map(|(name, used)| (name.clone(), *used)));
+                    }
+                    merge_used_items(child_items, &mut diagnostics)
                 }
                 Some(item) => {
                     diagnostics.push(errors::error(
@@ -573,18 +1319,26 @@ fn resolve_use_tree(
             Analysis::new(items.into(), diagnostics.into())
         }
         ast::UseTree::Simple { path, rename } => {
-            let item = resolve_path(module, db, path);
+            let name = import_name(path, rename);
+            let item = resolver.resolve_import(from, parent, path, &name.kind);
 
             let items = match item.value {
+                Some(Item::EnumVariant(_)) => {
+                    diagnostics.extend(item.diagnostics.iter().cloned());
+                    diagnostics.push(errors::error(
+                        "enum variants can't be imported",
+                        path.segments.last().expect("path is empty").span,
+                        "refer to the variant by its path instead",
+                    ));
+                    return Analysis::new(Rc::new(indexmap! {}), diagnostics.into());
+                }
                 Some(item) => {
-                    let (item_name, item_name_span) = if let Some(name) = rename {
-                        (name.kind.clone(), name.span)
-                    } else {
-                        let name_segment_node = path.segments.last().expect("path is empty");
-                        (name_segment_node.kind.clone(), name_segment_node.span)
+                    let used = UsedItem {
+                        name_span: name.span,
+                        item,
+                        is_glob: false,
                     };
-
-                    indexmap! { item_name => (item_name_span, item) }
+                    indexmap! { name.kind.clone() => used }
                 }
                 None => indexmap! {},
             };
@@ -593,3 +1347,327 @@ fn resolve_use_tree(
         }
     }
 }
+
+/// The name that a simple `use` tree, e.g. `foo::Bar as Baz`, imports its
+/// item as.
+fn import_name<'a>(path: &'a ast::Path, rename: &'a Option<Node<SmolStr>>) -> &'a Node<SmolStr> {
+    rename
+        .as_ref()
+        .unwrap_or_else(|| path.segments.last().expect("path is empty"))
+}
+
+/// Collects the names that a `use` tree imports items as, not counting the
+/// items of glob imports.
+fn import_names(tree: &Node<ast::UseTree>, names: &mut IndexSet<SmolStr>) {
+    match &tree.kind {
+        ast::UseTree::Glob { .. } => {}
+        ast::UseTree::Simple { path, rename } => {
+            names.insert(import_name(path, rename).kind.clone());
+        }
+        ast::UseTree::Nested { children, .. } => {
+            for child in children {
+                import_names(child, names)
+            }
+        }
+    }
+}
+
+fn has_glob_import(tree: &Node<ast::UseTree>) -> bool {
+    match &tree.kind {
+        ast::UseTree::Glob { .. } => true,
+        ast::UseTree::Simple { .. } => false,
+        ast::UseTree::Nested { children, .. } => children.iter().any(has_glob_import),
+    }
+}
+
+/// Resolves the items that `use` statements import without building the used
+/// item maps of other modules, so that modules can import items from each
+/// other. When a name is looked up in a module, only the `use` statements of
+/// the module that might import that name are followed.
+struct ImportResolver<'a> {
+    db: &'a dyn AnalyzerDb,
+    /// The names whose imports are being resolved, with the module that
+    /// imports them. Coming across one of these again means that the name is
+    /// only imported in a cycle, and never defined.
+    imports: Vec<(ModuleId, SmolStr)>,
+    /// The names that are being searched for in the glob imports of a module.
+    glob_imports: Vec<(ModuleId, SmolStr)>,
+    /// The number of import cycles that have been come across.
+    cycles: usize,
+}
+
+impl<'a> ImportResolver<'a> {
+    fn new(db: &'a dyn AnalyzerDb) -> Self {
+        Self {
+            db,
+            imports: vec![],
+            glob_imports: vec![],
+            cycles: 0,
+        }
+    }
+
+    /// Resolves the path of a `use` tree of `module` that imports an item as
+    /// `name`.
+    fn resolve_import(
+        &mut self,
+        module: ModuleId,
+        parent: Option<Item>,
+        path: &ast::Path,
+        name: &SmolStr,
+    ) -> Analysis<Option<Item>> {
+        self.imports.push((module, name.clone()));
+        let item = self.resolve_path(module, parent, path);
+        self.imports.pop();
+        item
+    }
+
+    /// Resolves a path in a `use` statement of `module`. See
+    /// [`resolve_use_tree`] for the meaning of `parent`.
+    fn resolve_path(
+        &mut self,
+        module: ModuleId,
+        parent: Option<Item>,
+        path: &ast::Path,
+    ) -> Analysis<Option<Item>> {
+        let db = self.db;
+        let (first, segments) = match parent {
+            Some(parent) => (parent, &path.segments[..]),
+            None => {
+                let node = &path.segments[0];
+                let cycles = self.cycles;
+                let item = self
+                    .module_child(module, &node.kind)
+                    .or_else(|| module.global_items(db).get(&node.kind).copied());
+                match item {
+                    Some(item) => (item, &path.segments[1..]),
+                    None => {
+                        let diagnostic = self.unresolved(node, cycles);
+                        return Analysis::new(None, Rc::new([diagnostic]));
+                    }
+                }
+            }
+        };
+
+        first.resolve_path_segments_with(db, module, segments, |item, node| {
+            let cycles = self.cycles;
+            let child = match item {
+                Item::Ingot(ingot) => ingot
+                    .root_module(db)
+                    .and_then(|root| self.module_child(root, &node.kind)),
+                Item::Module(id) => self.module_child(id, &node.kind),
+                _ => item.lookup_child(db, &node.kind),
+            };
+            child.ok_or_else(|| self.unresolved(node, cycles))
+        })
+    }
+
+    /// The error for a path segment that couldn't be resolved. `cycles` is the
+    /// number of import cycles that had been come across before looking it
+    /// up.
+    fn unresolved(&self, node: &Node<SmolStr>, cycles: usize) -> Diagnostic {
+        if self.cycles == cycles {
+            return errors::error("unresolved path item", node.span, "not found");
+        }
+        errors::fancy_error(
+            "unresolved path item",
+            vec![Label::primary(
+                node.span,
+                format!("`{}` is imported in a cycle", node.kind),
+            )],
+            vec![format!(
+                "Note: `{}` must be defined in one of the modules that it's imported from",
+                node.kind
+            )],
+        )
+    }
+
+    /// The item named `name` in the item map of `module`.
+    ///
+    /// Submodules are looked up first, as in [`Item::resolve_path_segments`].
+    fn module_child(&mut self, module: ModuleId, name: &str) -> Option<Item> {
+        let db = self.db;
+        if let Some(submodule) = module
+            .submodules(db)
+            .iter()
+            .find(|submodule| submodule.name(db) == name)
+        {
+            return Some(Item::Module(*submodule));
+        }
+        // Items with the name of a global item aren't in the map.
+        if module.global_items(db).contains_key(name) {
+            return None;
+        }
+        if let Some(item) = self.explicit_import(module, name) {
+            return Some(item);
+        }
+        if let Some(item) = module
+            .all_items(db)
+            .iter()
+            .find(|item| item.name(db) == name)
+        {
+            return Some(*item);
+        }
+        // Glob imports are shadowed by the items that are defined in the module.
+        self.glob_import(module, name)
+    }
+
+    /// The item that `module` imports as `name` by name, i.e. not with a glob
+    /// import.
+    fn explicit_import(&mut self, module: ModuleId, name: &str) -> Option<Item> {
+        let key = (module, SmolStr::new(name));
+        if self.imports.contains(&key) {
+            self.cycles += 1;
+            return None;
+        }
+
+        self.imports.push(key);
+        let ast = module.ast(self.db);
+        let item = ast.body.iter().find_map(|stmt| match stmt {
+            ast::ModuleStmt::Use(use_stmt) => {
+                self.tree_import(module, None, &use_stmt.kind.tree, name)
+            }
+            _ => None,
+        });
+        self.imports.pop();
+        item
+    }
+
+    /// The item that a `use` tree of `module` imports as `name`, if any. Glob
+    /// imports aren't followed.
+    fn tree_import(
+        &mut self,
+        module: ModuleId,
+        parent: Option<Item>,
+        tree: &Node<ast::UseTree>,
+        name: &str,
+    ) -> Option<Item> {
+        let mut names = IndexSet::new();
+        import_names(tree, &mut names);
+        if !names.contains(name) {
+            return None;
+        }
+
+        match &tree.kind {
+            ast::UseTree::Glob { .. } => None,
+            // Enum variants can't be imported.
+            ast::UseTree::Simple { path, .. } => self
+                .resolve_path(module, parent, path)
+                .value
+                .filter(|item| !matches!(item, Item::EnumVariant(_))),
+            ast::UseTree::Nested { prefix, children } => {
+                match self.resolve_path(module, parent, prefix).value {
+                    Some(prefix @ Item::Module(_)) => children
+                        .iter()
+                        .find_map(|child| self.tree_import(module, Some(prefix), child, name)),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    /// The item that `module` imports as `name` with a glob import.
+    fn glob_import(&mut self, module: ModuleId, name: &str) -> Option<Item> {
+        let db = self.db;
+        let key = (module, SmolStr::new(name));
+        // Modules may import all of each other's items.
+        if self.glob_imports.contains(&key) {
+            return None;
+        }
+
+        self.glob_imports.push(key);
+        let mut item = None;
+        for imported in self.glob_modules(module) {
+            item = self.module_child(imported, name).filter(|item| {
+                item.is_public(db) || module.can_access_private_items_of(db, imported)
+            });
+            if item.is_some() {
+                break;
+            }
+        }
+        self.glob_imports.pop();
+        item
+    }
+
+    /// The modules that `module` imports all items of with glob imports.
+    fn glob_modules(&mut self, module: ModuleId) -> Vec<ModuleId> {
+        let mut modules = vec![];
+        for stmt in &module.ast(self.db).body {
+            if let ast::ModuleStmt::Use(use_stmt) = stmt {
+                self.tree_glob_modules(module, None, &use_stmt.kind.tree, &mut modules)
+            }
+        }
+        modules
+    }
+
+    fn tree_glob_modules(
+        &mut self,
+        module: ModuleId,
+        parent: Option<Item>,
+        tree: &Node<ast::UseTree>,
+        modules: &mut Vec<ModuleId>,
+    ) {
+        if !has_glob_import(tree) {
+            return;
+        }
+
+        match &tree.kind {
+            ast::UseTree::Glob { prefix } => {
+                if let Some(Item::Module(imported)) =
+                    self.resolve_path(module, parent, prefix).value
+                {
+                    modules.push(imported)
+                }
+            }
+            ast::UseTree::Nested { prefix, children } => {
+                if let Some(prefix @ Item::Module(_)) =
+                    self.resolve_path(module, parent, prefix).value
+                {
+                    for child in children {
+                        self.tree_glob_modules(module, Some(prefix), child, modules)
+                    }
+                }
+            }
+            ast::UseTree::Simple { .. } => {}
+        }
+    }
+
+    /// The items in the item map of `module`, found without building it.
+    fn module_items(&mut self, module: ModuleId) -> IndexMap<SmolStr, Item> {
+        let mut names = IndexSet::new();
+        self.item_names(module, &mut names, &mut vec![]);
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let item = self.module_child(module, &name)?;
+                Some((name, item))
+            })
+            .collect()
+    }
+
+    /// Collects the names of the items that are defined in `module` or might
+    /// be imported into it. Glob imports can form a cycle, so the modules
+    /// that have already been visited are skipped.
+    fn item_names(
+        &mut self,
+        module: ModuleId,
+        names: &mut IndexSet<SmolStr>,
+        visited: &mut Vec<ModuleId>,
+    ) {
+        if visited.contains(&module) {
+            return;
+        }
+        visited.push(module);
+
+        let db = self.db;
+        names.extend(module.all_items(db).iter().map(|item| item.name(db)));
+        names.extend(module.submodules(db).iter().map(|id| id.name(db)));
+        for stmt in &module.ast(db).body {
+            if let ast::ModuleStmt::Use(use_stmt) = stmt {
+                import_names(&use_stmt.kind.tree, names)
+            }
+        }
+        for imported in self.glob_modules(module) {
+            self.item_names(imported, names, visited)
+        }
+    }
+}
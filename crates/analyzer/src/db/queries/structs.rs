@@ -7,9 +7,10 @@ use crate::namespace::items::{
     StructFieldId, StructId, TypeDef,
 };
 use crate::namespace::scopes::ItemScope;
-use crate::namespace::types::{self, Contract, FixedSize, Struct};
-use crate::traversal::types::type_desc;
+use crate::namespace::types::{self, Contract, Enum, FixedSize, Struct};
+use crate::traversal::types::{generic_params, type_desc};
 use crate::AnalyzerDb;
+use fe_common::diagnostics::Label;
 use fe_parser::ast;
 use indexmap::map::{Entry, IndexMap};
 use smol_str::SmolStr;
@@ -21,9 +22,20 @@ pub fn struct_type(db: &dyn AnalyzerDb, struct_: StructId) -> Rc<types::Struct>
         name: struct_.name(db),
         id: struct_,
         field_count: struct_.fields(db).len(),
+        generic_args: struct_.generic_args(db),
     })
 }
 
+pub fn struct_generic_params(db: &dyn AnalyzerDb, struct_: StructId) -> Analysis<Rc<[SmolStr]>> {
+    let mut scope = ItemScope::new(db, struct_.module(db));
+    let params = generic_params(
+        &mut scope,
+        &format!("struct `{}`", struct_.name(db)),
+        &struct_.data(db).ast.kind.generic_params,
+    );
+    Analysis::new(params, scope.diagnostics.into())
+}
+
 pub fn struct_all_fields(db: &dyn AnalyzerDb, struct_: StructId) -> Rc<[StructFieldId]> {
     struct_
         .data(db)
@@ -75,7 +87,11 @@ pub fn struct_field_type(
 ) -> Analysis<Result<types::FixedSize, TypeError>> {
     let field_data = field.data(db);
 
-    let mut scope = ItemScope::new(db, field_data.parent.module(db));
+    let mut scope = ItemScope::with_type_params(
+        db,
+        field_data.parent.module(db),
+        field_data.parent.type_params(db),
+    );
 
     let ast::Field {
         is_pub: _,
@@ -83,6 +99,7 @@ pub fn struct_field_type(
         name: _,
         typ,
         value,
+        doc: _,
     } = &field_data.ast.kind;
 
     if *is_const {
@@ -100,6 +117,13 @@ pub fn struct_field_type(
                 );
                 Ok(FixedSize::Contract(contract))
             }
+            Ok(FixedSize::Function(function)) => {
+                scope.not_yet_implemented(
+                    "function types aren't yet supported as struct fields",
+                    field_data.ast.span,
+                );
+                Ok(FixedSize::Function(function))
+            }
             Ok(typ) => Ok(typ),
             Err(_) => Err(TypeError::new(scope.error(
                 "struct field type must have a fixed size",
@@ -110,6 +134,19 @@ pub fn struct_field_type(
         Err(err) => Err(err),
     };
 
+    if let Ok(field_type) = &typ {
+        if !field_type.is_base() && field_data.parent.is_packed(db) {
+            scope.fancy_error(
+                "fields of a `#[packed]` struct must have primitive types",
+                vec![Label::primary(
+                    field_data.ast.kind.typ.span,
+                    format!("this has type `{}`", field_type),
+                )],
+                vec!["Note: only primitive values can be packed into storage words".into()],
+            );
+        }
+    }
+
     Analysis::new(typ, scope.diagnostics.into())
 }
 
@@ -125,6 +162,7 @@ pub fn struct_all_functions(db: &dyn AnalyzerDb, struct_: StructId) -> Rc<[Funct
                 ast: node.clone(),
                 module: struct_data.module,
                 parent: Some(items::Class::Struct(struct_)),
+                generic_args: vec![],
             }))
         })
         .collect()
@@ -203,6 +241,9 @@ pub fn struct_dependency_graph(db: &dyn AnalyzerDb, struct_: StructId) -> DepGra
             FixedSize::Struct(Struct { id, .. }) => {
                 Some((root, Item::Type(TypeDef::Struct(id)), DepLocality::Local))
             }
+            FixedSize::Enum(Enum { id, .. }) => {
+                Some((root, Item::Type(TypeDef::Enum(id)), DepLocality::Local))
+            }
             _ => None,
         })
         .collect::<Vec<_>>();
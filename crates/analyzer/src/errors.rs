@@ -23,27 +23,85 @@ use std::fmt::Display;
 /// Please don't try to work around this restriction.
 ///
 /// Example: `TypeError::new(context.error("something is wrong", some_span, "this thing"))`
+///
+/// A `TypeError` can carry a chain of [`ErrorCause`]s, which point at the code
+/// that caused the error. Callers that receive the error from a query whose
+/// diagnostics aren't reported at their site (eg `ModuleConstantId::typ`) can
+/// render the chain with [`TypeError::cause_labels`].
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct TypeError(DiagnosticVoucher);
+pub struct TypeError {
+    voucher: DiagnosticVoucher,
+    causes: Vec<ErrorCause>,
+}
 impl TypeError {
     // `Clone` is required because these are stored in a salsa db.
     // Please don't clone these manually.
     pub fn new(voucher: DiagnosticVoucher) -> Self {
-        Self(voucher)
+        Self {
+            voucher,
+            causes: vec![],
+        }
+    }
+
+    /// Adds a cause to the end of the chain.
+    pub fn with_cause(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.causes.push(ErrorCause::new(span, message));
+        self
+    }
+
+    /// The span of the code that originally failed, if it's known.
+    pub fn span(&self) -> Option<Span> {
+        self.causes.first().map(|cause| cause.span)
+    }
+
+    /// The causes of the error, starting with the original failure.
+    pub fn causes(&self) -> &[ErrorCause] {
+        &self.causes
+    }
+
+    /// Secondary labels for the causes of the error.
+    pub fn cause_labels(&self) -> Vec<Label> {
+        cause_labels(&self.causes)
     }
 }
 
 impl From<FatalError> for TypeError {
     fn from(err: FatalError) -> Self {
-        Self(err.0)
+        Self::new(err.0)
     }
 }
 impl From<ConstEvalError> for TypeError {
     fn from(err: ConstEvalError) -> Self {
-        Self(err.0)
+        Self {
+            voucher: err.voucher,
+            causes: err.causes,
+        }
     }
 }
 
+/// A step in the chain of causes of a [`TypeError`] or [`ConstEvalError`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ErrorCause {
+    pub span: Span,
+    pub message: String,
+}
+
+impl ErrorCause {
+    pub fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+}
+
+fn cause_labels(causes: &[ErrorCause]) -> Vec<Label> {
+    causes
+        .iter()
+        .map(|cause| Label::secondary(cause.span, &cause.message))
+        .collect()
+}
+
 /// Error to be returned when otherwise no meaningful information can be returned.
 /// Can't be created unless a diagnostic has been emitted, and thus a [`DiagnosticVoucher`]
 /// has been obtained. (See comment on [`TypeError`])
@@ -60,7 +118,7 @@ impl FatalError {
 
 impl From<ConstEvalError> for FatalError {
     fn from(err: ConstEvalError) -> Self {
-        Self(err.0)
+        Self(err.voucher)
     }
 }
 
@@ -72,34 +130,71 @@ impl From<ConstEvalError> for FatalError {
 /// 3. zero division is detected during evaluation
 ///
 /// Can't be created unless a diagnostic has been emitted, and thus a [`DiagnosticVoucher`]
-/// has been obtained. (See comment on [`TypeError`])
+/// has been obtained. Like a [`TypeError`], it can carry a chain of [`ErrorCause`]s, so
+/// that an error in the evaluation of a constant can be reported where the constant is used.
 ///
 /// NOTE: `Clone` is required because these are stored in a salsa db.
 /// Please don't clone these manually.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-pub struct ConstEvalError(DiagnosticVoucher);
+pub struct ConstEvalError {
+    voucher: DiagnosticVoucher,
+    causes: Vec<ErrorCause>,
+}
 
 impl ConstEvalError {
     pub fn new(voucher: DiagnosticVoucher) -> Self {
-        Self(voucher)
+        Self {
+            voucher,
+            causes: vec![],
+        }
+    }
+
+    /// Adds a cause to the end of the chain.
+    pub fn with_cause(mut self, span: Span, message: impl Into<String>) -> Self {
+        self.causes.push(ErrorCause::new(span, message));
+        self
+    }
+
+    /// Prepends the causes of `err` to the chain.
+    pub fn caused_by(mut self, err: ConstEvalError) -> Self {
+        self.causes.splice(0..0, err.causes);
+        self
+    }
+
+    /// The span of the expression whose evaluation originally failed, if it's known.
+    pub fn span(&self) -> Option<Span> {
+        self.causes.first().map(|cause| cause.span)
+    }
+
+    /// The causes of the error, starting with the original failure.
+    pub fn causes(&self) -> &[ErrorCause] {
+        &self.causes
+    }
+
+    /// Secondary labels for the causes of the error.
+    pub fn cause_labels(&self) -> Vec<Label> {
+        cause_labels(&self.causes)
     }
 }
 
 impl From<TypeError> for ConstEvalError {
     fn from(err: TypeError) -> Self {
-        Self(err.0)
+        Self {
+            voucher: err.voucher,
+            causes: err.causes,
+        }
     }
 }
 
 impl From<FatalError> for ConstEvalError {
     fn from(err: FatalError) -> Self {
-        Self(err.0)
+        Self::new(err.0)
     }
 }
 
 impl From<IncompleteItem> for ConstEvalError {
     fn from(err: IncompleteItem) -> Self {
-        Self(err.0)
+        Self::new(err.0)
     }
 }
 
@@ -150,7 +245,7 @@ pub struct AnalyzerError(pub Vec<Diagnostic>);
 
 impl From<TypeError> for FatalError {
     fn from(err: TypeError) -> Self {
-        Self::new(err.0)
+        Self::new(err.voucher)
     }
 }
 
@@ -183,6 +278,17 @@ pub fn fancy_error(
     }
 }
 
+/// A diagnostic that doesn't prevent compilation, but points out
+/// code that is likely to be a mistake.
+pub fn warning(message: impl Into<String>, labels: Vec<Label>, notes: Vec<String>) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        message: message.into(),
+        labels,
+        notes,
+    }
+}
+
 pub fn type_error(
     message: impl Into<String>,
     span: Span,
@@ -0,0 +1,75 @@
+//! Language editions and experimental features, which are selected per module
+//! with `pragma edition <edition>` and `pragma feature(<feature>, ...)`.
+
+use std::collections::BTreeSet;
+use strum::{AsRefStr, EnumIter, EnumString};
+
+/// An edition of the language. A module that doesn't specify its edition uses
+/// the oldest one.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumString,
+    AsRefStr,
+    EnumIter,
+)]
+pub enum Edition {
+    #[default]
+    #[strum(serialize = "2021")]
+    Edition2021,
+    #[strum(serialize = "2024")]
+    Edition2024,
+}
+
+/// A language feature that has to be enabled with `pragma feature(..)`,
+/// unless it's stable in the edition of the module.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString, AsRefStr, EnumIter,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Feature {
+    Generics,
+    FunctionTypes,
+}
+
+impl Feature {
+    /// The first edition in which the feature is enabled without a pragma.
+    pub fn stable_since(&self) -> Option<Edition> {
+        match self {
+            Feature::Generics => Some(Edition::Edition2021),
+            Feature::FunctionTypes => Some(Edition::Edition2024),
+        }
+    }
+
+    /// The name of the feature in diagnostics, e.g. "function types are
+    /// experimental".
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Feature::Generics => "generics",
+            Feature::FunctionTypes => "function types",
+        }
+    }
+}
+
+/// The edition and the explicitly enabled features of a module.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ModuleFeatures {
+    pub edition: Edition,
+    pub enabled: BTreeSet<Feature>,
+}
+
+impl ModuleFeatures {
+    /// Returns `true` if the feature is stable in the module's edition or was
+    /// enabled with `pragma feature(..)`.
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.enabled.contains(&feature)
+            || matches!(feature.stable_since(), Some(edition) if edition <= self.edition)
+    }
+}
@@ -9,6 +9,7 @@ pub mod constants;
 pub mod context;
 pub mod db;
 pub mod errors;
+pub mod features;
 pub mod namespace;
 mod operations;
 mod traversal;
@@ -16,23 +17,54 @@ mod traversal;
 use crate::namespace::items::{IngotId, ModuleId};
 pub use db::{AnalyzerDb, TestDb};
 use fe_common::diagnostics::Diagnostic;
+use fe_common::{FileKind, SourceFileId};
+use indexmap::IndexMap;
+use std::rc::Rc;
 
+/// Returns all of the diagnostics of the ingot if any of them is an error.
+/// Warnings alone don't fail the analysis.
 pub fn analyze_ingot(db: &dyn AnalyzerDb, ingot_id: IngotId) -> Result<(), Vec<Diagnostic>> {
     let diagnostics = ingot_id.diagnostics(db);
 
-    if diagnostics.is_empty() {
+    if !diagnostics.iter().any(Diagnostic::is_error) {
         Ok(())
     } else {
         Err(diagnostics)
     }
 }
 
+/// Returns all of the diagnostics of the module if any of them is an error.
+/// Warnings alone don't fail the analysis.
 pub fn analyze_module(db: &dyn AnalyzerDb, module_id: ModuleId) -> Result<(), Vec<Diagnostic>> {
     let diagnostics = module_id.diagnostics(db);
 
-    if diagnostics.is_empty() {
+    if !diagnostics.iter().any(Diagnostic::is_error) {
         Ok(())
     } else {
         Err(diagnostics)
     }
 }
+
+/// Defines the values of constants at build time, e.g. for
+/// `fe --define WETH=0x...`. Each value is an expression that replaces the
+/// value of the constant with the same name in the root module of the compiled
+/// ingot, and is checked against the type of the constant. Naming a constant
+/// that doesn't exist is an error.
+pub fn define_constants(
+    db: &mut dyn AnalyzerDb,
+    defines: &[(impl AsRef<str>, impl AsRef<str>)],
+) {
+    let defines = defines
+        .iter()
+        .map(|(name, value)| {
+            let file = SourceFileId::new(
+                db.upcast_mut(),
+                FileKind::Local,
+                &format!("--define {}", name.as_ref()),
+                value.as_ref().trim().into(),
+            );
+            (name.as_ref().into(), file)
+        })
+        .collect::<IndexMap<_, _>>();
+    db.set_constant_defines(Rc::new(defines));
+}
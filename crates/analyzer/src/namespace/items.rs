@@ -1,20 +1,21 @@
 use crate::context;
 
-use crate::context::{Analysis, Constant};
+use crate::context::{Analysis, Constant, ExternalCall};
 use crate::errors::{self, IncompleteItem, TypeError};
+use crate::features::ModuleFeatures;
+use crate::namespace::selector::{canonical_signature, Selector};
 use crate::namespace::types::FixedSize;
 use crate::namespace::types::{self, GenericType};
-use crate::traversal::pragma::check_pragma_version;
 use crate::AnalyzerDb;
 use crate::{builtins, errors::ConstEvalError};
-use fe_common::diagnostics::Diagnostic;
+use fe_common::diagnostics::{Diagnostic, Label};
 use fe_common::files::{common_prefix, Utf8Path};
 use fe_common::{impl_intern_key, FileKind, SourceFileId};
 use fe_parser::ast;
 use fe_parser::node::{Node, Span};
+use indexmap::map::Entry;
 use indexmap::{indexmap, IndexMap, IndexSet};
 use smol_str::SmolStr;
-use std::ops::Deref;
 use std::rc::Rc;
 use strum::IntoEnumIterator;
 
@@ -34,6 +35,7 @@ pub enum Item {
     Event(EventId),
     Function(FunctionId),
     Constant(ModuleConstantId),
+    EnumVariant(EnumVariantId),
     // Needed until we can represent keccak256 as a FunctionId.
     // We can't represent keccak256's arg type yet.
     BuiltinFunction(builtins::GlobalFunction),
@@ -54,6 +56,7 @@ impl Item {
             Item::Intrinsic(id) => id.as_ref().into(),
             Item::Object(id) => id.as_ref().into(),
             Item::Constant(id) => id.name(db),
+            Item::EnumVariant(id) => id.name(db),
             Item::Ingot(id) => id.name(db),
             Item::Module(id) => id.name(db),
         }
@@ -66,6 +69,7 @@ impl Item {
             Item::Event(id) => Some(id.name_span(db)),
             Item::Function(id) => Some(id.name_span(db)),
             Item::Constant(id) => Some(id.name_span(db)),
+            Item::EnumVariant(id) => Some(id.name_span(db)),
             Item::BuiltinFunction(_)
             | Item::Intrinsic(_)
             | Item::Object(_)
@@ -74,6 +78,41 @@ impl Item {
         }
     }
 
+    /// The `///` doc comment of the item, if any. Lines are separated by `\n`.
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        match self {
+            Item::Type(id) => id.doc(db),
+            Item::Event(id) => id.doc(db),
+            Item::Function(id) => id.doc(db),
+            Item::Constant(id) => id.doc(db),
+            Item::GenericType(_)
+            | Item::EnumVariant(_)
+            | Item::BuiltinFunction(_)
+            | Item::Intrinsic(_)
+            | Item::Object(_)
+            | Item::Ingot(_)
+            | Item::Module(_) => None,
+        }
+    }
+
+    /// `true` if the item is declared with `pub`. Items that can't be declared
+    /// with `pub`, like modules and builtins, are always public.
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        match self {
+            Item::Type(id) => id.is_public(db),
+            Item::Event(id) => id.is_public(db),
+            Item::Function(id) => id.is_public(db),
+            Item::Constant(id) => id.is_public(db),
+            Item::GenericType(_)
+            | Item::EnumVariant(_)
+            | Item::BuiltinFunction(_)
+            | Item::Intrinsic(_)
+            | Item::Object(_)
+            | Item::Ingot(_)
+            | Item::Module(_) => true,
+        }
+    }
+
     pub fn is_builtin(&self) -> bool {
         match self {
             Item::Type(TypeDef::Primitive(_))
@@ -85,6 +124,7 @@ impl Item {
             | Item::Event(_)
             | Item::Function(_)
             | Item::Constant(_)
+            | Item::EnumVariant(_)
             | Item::Ingot(_)
             | Item::Module(_) => false,
         }
@@ -102,6 +142,7 @@ impl Item {
             Item::Intrinsic(_) => "intrinsic function",
             Item::Object(_) => "object",
             Item::Constant(_) => "constant",
+            Item::EnumVariant(_) => "enum variant",
             Item::Ingot(_) => "ingot",
             Item::Module(_) => "module",
         }
@@ -111,17 +152,46 @@ impl Item {
         match self {
             Item::Ingot(ingot) => ingot.items(db),
             Item::Module(module) => module.items(db),
+            Item::Type(TypeDef::Enum(id)) => Rc::new(
+                id.variants(db)
+                    .iter()
+                    .map(|(name, variant)| (name.clone(), Item::EnumVariant(*variant)))
+                    .collect(),
+            ),
+            // Functions of primitive types, like `u8::try_from`, are handled
+            // by the analyzer before paths are resolved.
+            Item::Type(TypeDef::Primitive(_)) => Rc::new(indexmap! {}),
             Item::Type(_) => todo!("cannot access items in types yet"),
             Item::GenericType(_)
             | Item::Event(_)
             | Item::Function(_)
             | Item::Constant(_)
+            | Item::EnumVariant(_)
             | Item::BuiltinFunction(_)
             | Item::Intrinsic(_)
             | Item::Object(_) => Rc::new(indexmap! {}),
         }
     }
 
+    /// The child item named `name`, i.e. `items(db).get(name)`, found without
+    /// building the whole item map of a module or enum.
+    pub fn lookup_child(&self, db: &dyn AnalyzerDb, name: &str) -> Option<Item> {
+        match self {
+            Item::Ingot(ingot) => ingot.root_module(db)?.lookup_child(db, name),
+            Item::Module(module) => module.lookup_child(db, name),
+            Item::Type(TypeDef::Enum(id)) => id.variant(db, name).map(Item::EnumVariant),
+            Item::Type(_)
+            | Item::GenericType(_)
+            | Item::Event(_)
+            | Item::Function(_)
+            | Item::Constant(_)
+            | Item::EnumVariant(_)
+            | Item::BuiltinFunction(_)
+            | Item::Intrinsic(_)
+            | Item::Object(_) => None,
+        }
+    }
+
     pub fn parent(&self, db: &dyn AnalyzerDb) -> Option<Item> {
         match self {
             Item::Type(id) => id.parent(db),
@@ -129,6 +199,7 @@ impl Item {
             Item::Event(id) => Some(id.parent(db)),
             Item::Function(id) => Some(id.parent(db)),
             Item::Constant(id) => Some(id.parent(db)),
+            Item::EnumVariant(id) => Some(id.parent(db)),
             Item::Module(id) => Some(id.parent(db)),
             Item::BuiltinFunction(_) | Item::Intrinsic(_) | Item::Object(_) | Item::Ingot(_) => {
                 None
@@ -162,33 +233,84 @@ impl Item {
         }
     }
 
+    /// Resolves the path `segments`, starting at this item. `from` is the
+    /// module that contains the path; items that aren't `pub` can only be
+    /// reached from within the module that defines them.
     pub fn resolve_path_segments(
         &self,
         db: &dyn AnalyzerDb,
+        from: ModuleId,
         segments: &[Node<SmolStr>],
+    ) -> Analysis<Option<Item>> {
+        self.resolve_path_segments_with(db, from, segments, |item, node| {
+            item.lookup_child(db, &node.kind)
+                .ok_or_else(|| errors::error("unresolved path item", node.span, "not found"))
+        })
+    }
+
+    /// Like [`Item::resolve_path_segments`], but the children that aren't
+    /// submodules are found with `lookup`, which returns the error to report
+    /// if there's no such child.
+    pub(crate) fn resolve_path_segments_with(
+        &self,
+        db: &dyn AnalyzerDb,
+        from: ModuleId,
+        segments: &[Node<SmolStr>],
+        mut lookup: impl FnMut(Item, &Node<SmolStr>) -> Result<Item, Diagnostic>,
     ) -> Analysis<Option<Item>> {
         let mut curr_item = *self;
+        let mut diagnostics = vec![];
 
         for node in segments {
-            curr_item = match curr_item.items(db).get(&node.kind) {
-                Some(item) => *item,
-                None => {
-                    return Analysis {
-                        value: None,
-                        diagnostics: Rc::new([errors::error(
-                            "unresolved path item",
-                            node.span,
-                            "not found",
-                        )]),
+            let parent_module = match curr_item {
+                Item::Module(id) => Some(id),
+                Item::Ingot(id) => id.root_module(db),
+                _ => None,
+            };
+            // Submodules are looked up first, so that following a path through
+            // modules doesn't depend on the `use` statements of those modules.
+            let submodule = parent_module.and_then(|id| {
+                id.submodules(db)
+                    .iter()
+                    .copied()
+                    .find(|submodule| submodule.name(db) == node.kind)
+            });
+            let item = match submodule {
+                Some(submodule) => Ok(Item::Module(submodule)),
+                None => lookup(curr_item, node),
+            };
+            curr_item = match item {
+                Ok(item) => item,
+                Err(diagnostic) => {
+                    diagnostics.push(diagnostic);
+                    return Analysis::new(None, diagnostics.into());
+                }
+            };
+
+            if let Some(parent_module) = parent_module {
+                if !curr_item.is_public(db) && !from.can_access_private_items_of(db, parent_module)
+                {
+                    let kind = curr_item.item_kind_display_name();
+                    let mut labels = vec![Label::primary(node.span, format!("private {}", kind))];
+                    if let Some(span) = curr_item.name_span(db) {
+                        labels.push(Label::secondary(
+                            span,
+                            format!("`{}` is defined here", node.kind),
+                        ));
                     }
+                    diagnostics.push(errors::fancy_error(
+                        format!("the {} `{}` is private", kind, node.kind),
+                        labels,
+                        vec![format!(
+                            "Hint: use `pub` to make `{}` visible from outside of its module",
+                            node.kind
+                        )],
+                    ));
                 }
             }
         }
 
-        Analysis {
-            value: Some(curr_item),
-            diagnostics: Rc::new([]),
-        }
+        Analysis::new(Some(curr_item), diagnostics.into())
     }
 
     /// Downcast utility function
@@ -204,7 +326,9 @@ impl Item {
             Item::Type(id) => id.sink_diagnostics(db, sink),
             Item::Event(id) => id.sink_diagnostics(db, sink),
             Item::Function(id) => id.sink_diagnostics(db, sink),
+            // Variant diagnostics are reported by the enum.
             Item::GenericType(_)
+            | Item::EnumVariant(_)
             | Item::BuiltinFunction(_)
             | Item::Intrinsic(_)
             | Item::Object(_) => {}
@@ -379,6 +503,11 @@ impl IngotId {
         db.ingot_external_ingots(*self)
     }
 
+    /// Returns the map of ingot deps, built-ins, and the ingot itself as "ingot".
+    pub fn global_items(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, Item>> {
+        db.ingot_global_items(*self)
+    }
+
     pub fn all_modules(&self, db: &dyn AnalyzerDb) -> Rc<[ModuleId]> {
         db.ingot_modules(*self)
     }
@@ -391,6 +520,15 @@ impl IngotId {
         self.data(db).name.clone()
     }
 
+    /// `true` if this is the standard library ingot, or its lowered version.
+    /// Any ingot can be named `std`, so this checks the kind of its files.
+    pub fn is_std(&self, db: &dyn AnalyzerDb) -> bool {
+        let ingot = self.data(db).original.unwrap_or(*self);
+        ingot.all_modules(db).iter().any(|module| {
+            matches!(module.data(db).source, ModuleSource::File(file) if file.kind(db.upcast()) == FileKind::Std)
+        })
+    }
+
     /// Returns the `main.fe`, or `lib.fe` module, depending on the ingot "mode" (IngotMode).
     pub fn root_module(&self, db: &dyn AnalyzerDb) -> Option<ModuleId> {
         db.ingot_root_module(*self)
@@ -523,76 +661,76 @@ impl ModuleId {
         self.data(db).ingot
     }
 
+    /// The edition and the enabled features of the module.
+    pub fn features(&self, db: &dyn AnalyzerDb) -> Rc<ModuleFeatures> {
+        db.module_features(*self).value
+    }
+
     pub fn is_incomplete(&self, db: &dyn AnalyzerDb) -> bool {
         db.module_is_incomplete(*self)
     }
 
-    /// Includes duplicate names
+    /// Includes duplicate names. Items are in declaration order.
     pub fn all_items(&self, db: &dyn AnalyzerDb) -> Rc<[Item]> {
         db.module_all_items(*self)
     }
 
-    /// Returns a map of the named items in the module
+    /// Returns a map of the named items in the module, in declaration order.
     pub fn items(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, Item>> {
         db.module_item_map(*self).value
     }
 
-    /// Returns a `name -> (name_span, external_item)` map for all `use` statements in a module.
-    pub fn used_items(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, (Span, Item)>> {
-        db.module_used_item_map(*self).value
+    /// The item named `name` in [`ModuleId::items`]. Unlike `items(db).get(name)`,
+    /// this doesn't build the item map, which checks all items of the module
+    /// for conflicts.
+    pub fn lookup_child(&self, db: &dyn AnalyzerDb, name: &str) -> Option<Item> {
+        // Used items take precedence over submodules, which take precedence
+        // over defined items, as in the item map.
+        if let Some((_, item)) = self.used_items(db).get(name) {
+            return Some(*item);
+        }
+        if let Some(submodule) = self
+            .submodules(db)
+            .iter()
+            .find(|submodule| submodule.name(db) == name)
+        {
+            return Some(Item::Module(*submodule));
+        }
+        // Items whose names conflict with a global item aren't in the map.
+        if self.global_items(db).contains_key(name) {
+            return None;
+        }
+        self.all_items(db)
+            .iter()
+            .find(|item| item.name(db) == name)
+            .copied()
     }
 
-    /// Returns all of the internal items, except for `use`d items. This is used when resolving
-    /// `use` statements, as it does not create a query cycle.
-    pub fn non_used_internal_items(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, Item> {
-        let global_items = self.global_items(db);
+    /// The position of the named item in [`ModuleId::items`].
+    pub fn item_index(&self, db: &dyn AnalyzerDb, name: &str) -> Option<usize> {
+        self.items(db).get_index_of(name)
+    }
 
-        self.submodules(db)
-            .iter()
-            .map(|module| (module.name(db), Item::Module(*module)))
-            .chain(global_items)
-            .collect()
+    /// Returns a `name -> (name_span, external_item)` map for all `use` statements in a module.
+    pub fn used_items(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, (Span, Item)>> {
+        db.module_used_item_map(*self).value
     }
 
     /// Returns all of the internal items. Internal items refers to the set of items visible when
     /// inside of a module.
-    pub fn internal_items(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, Item> {
-        let global_items = self.global_items(db);
-        let defined_items = self.items(db);
-        self.submodules(db)
-            .iter()
-            .map(|module| (module.name(db), Item::Module(*module)))
-            .chain(global_items)
-            .chain(defined_items.deref().clone())
-            .collect()
+    pub fn internal_items(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, Item>> {
+        db.module_internal_items(*self)
     }
 
-    /// Resolve a path that starts with an item defined in the module.
-    pub fn resolve_path(&self, db: &dyn AnalyzerDb, path: &ast::Path) -> Analysis<Option<Item>> {
-        Item::Module(*self).resolve_path_segments(db, &path.segments)
-    }
-
-    /// Resolve a path that starts with an internal item. We omit used items to avoid a query cycle.
-    pub fn resolve_path_non_used_internal(
+    /// Resolve a path that starts with an item defined in the module. `from` is
+    /// the module that contains the path.
+    pub fn resolve_path(
         &self,
         db: &dyn AnalyzerDb,
+        from: ModuleId,
         path: &ast::Path,
     ) -> Analysis<Option<Item>> {
-        let segments = &path.segments;
-        let first_segment = &segments[0];
-
-        if let Some(curr_item) = self.non_used_internal_items(db).get(&first_segment.kind) {
-            curr_item.resolve_path_segments(db, &segments[1..])
-        } else {
-            Analysis {
-                value: None,
-                diagnostics: Rc::new([errors::error(
-                    "unresolved path item",
-                    first_segment.span,
-                    "not found",
-                )]),
-            }
-        }
+        Item::Module(*self).resolve_path_segments(db, from, &path.segments)
     }
 
     /// Resolve a path that starts with an internal item.
@@ -605,7 +743,7 @@ impl ModuleId {
         let first_segment = &segments[0];
 
         if let Some(curr_item) = self.internal_items(db).get(&first_segment.kind) {
-            curr_item.resolve_path_segments(db, &segments[1..])
+            curr_item.resolve_path_segments(db, *self, &segments[1..])
         } else {
             Analysis {
                 value: None,
@@ -666,28 +804,35 @@ impl ModuleId {
         db.module_parent_module(*self)
     }
 
+    /// `true` if the items of `other` that aren't `pub` can be used in this
+    /// module, i.e. if this module is `other` or one of its submodules.
+    ///
+    /// Lowered modules can use any item, as lowering doesn't preserve `pub`.
+    pub fn can_access_private_items_of(&self, db: &dyn AnalyzerDb, other: ModuleId) -> bool {
+        if matches!(self.data(db).source, ModuleSource::Lowered { .. }) {
+            return true;
+        }
+        let mut module = Some(*self);
+        while let Some(id) = module {
+            if id == other {
+                return true;
+            }
+            module = id.parent_module(db);
+        }
+        false
+    }
+
     /// All contracts, including duplicates
     pub fn all_contracts(&self, db: &dyn AnalyzerDb) -> Rc<[ContractId]> {
         db.module_contracts(*self)
     }
 
-    /// Returns the map of ingot deps, built-ins, and the ingot itself as "ingot".
-    pub fn global_items(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, Item> {
-        let ingot = self.ingot(db);
-        let mut items = ingot
-            .external_ingots(db)
-            .iter()
-            .map(|(name, ingot)| (name.clone(), Item::Ingot(*ingot)))
-            .chain(std_prelude_items())
-            .collect::<IndexMap<_, _>>();
-
-        if ingot.data(db).mode != IngotMode::StandaloneModule {
-            items.insert("ingot".into(), Item::Ingot(ingot));
-        }
-        items
+    /// The global items of the module's ingot. See [`IngotId::global_items`].
+    pub fn global_items(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, Item>> {
+        db.ingot_global_items(self.ingot(db))
     }
 
-    /// All structs, including duplicatecrates/analyzer/src/db.rss
+    /// All structs, including duplicates
     pub fn all_structs(&self, db: &dyn AnalyzerDb) -> Rc<[StructId]> {
         db.module_structs(*self)
     }
@@ -697,6 +842,18 @@ impl ModuleId {
         db.module_constants(*self)
     }
 
+    /// Returns `true` if the constants that are defined at build time (see
+    /// [`crate::define_constants`]) override the constants of this module.
+    /// That's the case for the root module of the compiled ingot, but not for
+    /// libraries, or for lowered modules, which already have the values.
+    pub fn receives_constant_defines(&self, db: &dyn AnalyzerDb) -> bool {
+        let ingot = self.ingot(db);
+        let data = ingot.data(db);
+        data.mode != IngotMode::Lib
+            && data.original.is_none()
+            && ingot.root_module(db) == Some(*self)
+    }
+
     pub fn diagnostics(&self, db: &dyn AnalyzerDb) -> Vec<Diagnostic> {
         let mut diagnostics = vec![];
         self.sink_diagnostics(db, &mut diagnostics);
@@ -708,22 +865,55 @@ impl ModuleId {
         if let ModuleSource::File(_) = data.source {
             sink.push_all(db.module_parse(*self).diagnostics.iter())
         }
-        let ast = self.ast(db);
-        for stmt in &ast.body {
-            if let ast::ModuleStmt::Pragma(inner) = stmt {
-                if let Some(diag) = check_pragma_version(inner) {
-                    sink.push(&diag)
-                }
-            }
-        }
+        // errors for `pragma` statements
+        sink.push_all(db.module_features(*self).diagnostics.iter());
 
         // duplicate item name errors
         sink.push_all(db.module_item_map(*self).diagnostics.iter());
+        sink.push_all(db.module_function_overloads(*self).diagnostics.iter());
+
+        // errors for `impl` statements that the contract doesn't conform to
+        sink.push_all(db.module_interface_impls(*self).diagnostics.iter());
+
+        // errors for constants that are defined at build time
+        if self.receives_constant_defines(db) {
+            sink.push_all(db.constant_define_values().diagnostics.iter());
+            let constants = self.all_constants(db);
+            for (name, file) in db.constant_defines().iter() {
+                if !constants.iter().any(|constant| &constant.name(db) == name) {
+                    let len = file.content(db.upcast()).len();
+                    sink.push(&errors::error(
+                        format!("cannot define `{}`", name),
+                        Span::new(*file, 0, len),
+                        format!(
+                            "`{}` has no constant named `{}`",
+                            self.file_path_relative_to_src_dir(db),
+                            name
+                        ),
+                    ));
+                }
+            }
+        }
 
         // errors for each item
         self.all_items(db)
             .iter()
             .for_each(|id| id.sink_diagnostics(db, sink));
+
+        // errors for each instance of a generic struct or function
+        for item in db.module_generic_instances(*self).iter() {
+            match item {
+                Item::Function(id) => id.sink_instance_diagnostics(db, sink),
+                Item::Type(TypeDef::Struct(id)) => id.sink_instance_diagnostics(db, sink),
+                _ => {}
+            }
+        }
+
+        // warnings for unused imports and private functions
+        if let ModuleSource::File(_) = data.source {
+            sink.push_all(db.module_unused_imports(*self).diagnostics.iter());
+            sink.push_all(db.module_unused_functions(*self).diagnostics.iter());
+        }
     }
 }
 
@@ -763,6 +953,17 @@ impl ModuleConstantId {
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.pub_qual.is_some()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
 
     pub fn value(&self, db: &dyn AnalyzerDb) -> ast::Expr {
         self.data(db).ast.kind.value.kind.clone()
@@ -784,6 +985,7 @@ impl ModuleConstantId {
 pub enum TypeDef {
     Alias(TypeAliasId),
     Struct(StructId),
+    Enum(EnumId),
     Contract(ContractId),
     Primitive(types::Base),
 }
@@ -792,6 +994,7 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => id.name(db),
             TypeDef::Struct(id) => id.name(db),
+            TypeDef::Enum(id) => id.name(db),
             TypeDef::Contract(id) => id.name(db),
             TypeDef::Primitive(typ) => typ.name(),
         }
@@ -801,11 +1004,32 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => Some(id.name_span(db)),
             TypeDef::Struct(id) => Some(id.name_span(db)),
+            TypeDef::Enum(id) => Some(id.name_span(db)),
             TypeDef::Contract(id) => Some(id.name_span(db)),
             TypeDef::Primitive(_) => None,
         }
     }
 
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        match self {
+            TypeDef::Alias(id) => id.doc(db),
+            TypeDef::Struct(id) => id.doc(db),
+            TypeDef::Enum(id) => id.doc(db),
+            TypeDef::Contract(id) => id.doc(db),
+            TypeDef::Primitive(_) => None,
+        }
+    }
+
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        match self {
+            TypeDef::Alias(id) => id.is_public(db),
+            TypeDef::Struct(id) => id.is_public(db),
+            TypeDef::Enum(id) => id.is_public(db),
+            TypeDef::Contract(id) => id.is_public(db),
+            TypeDef::Primitive(_) => true,
+        }
+    }
+
     pub fn typ(&self, db: &dyn AnalyzerDb) -> Result<types::Type, TypeError> {
         match self {
             TypeDef::Alias(id) => id.typ(db),
@@ -813,7 +1037,9 @@ impl TypeDef {
                 id: *id,
                 name: id.name(db),
                 field_count: id.fields(db).len(), // for the EvmSized trait
+                generic_args: id.generic_args(db),
             })),
+            TypeDef::Enum(id) => Ok(types::Type::Enum(id.typ(db))),
             TypeDef::Contract(id) => Ok(types::Type::Contract(types::Contract {
                 id: *id,
                 name: id.name(db),
@@ -826,6 +1052,7 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => Some(id.parent(db)),
             TypeDef::Struct(id) => Some(id.parent(db)),
+            TypeDef::Enum(id) => Some(id.parent(db)),
             TypeDef::Contract(id) => Some(id.parent(db)),
             TypeDef::Primitive(_) => None,
         }
@@ -835,6 +1062,7 @@ impl TypeDef {
         match self {
             TypeDef::Alias(id) => id.sink_diagnostics(db, sink),
             TypeDef::Struct(id) => id.sink_diagnostics(db, sink),
+            TypeDef::Enum(id) => id.sink_diagnostics(db, sink),
             TypeDef::Contract(id) => id.sink_diagnostics(db, sink),
             TypeDef::Primitive(_) => {}
         }
@@ -859,11 +1087,22 @@ impl TypeAliasId {
         self.data(db).ast.span
     }
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
     }
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.pub_qual.is_some()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
     pub fn typ(&self, db: &dyn AnalyzerDb) -> Result<types::Type, TypeError> {
         db.type_alias_type(*self).value
     }
@@ -883,6 +1122,9 @@ pub struct Contract {
     pub name: SmolStr,
     pub ast: Node<ast::Contract>,
     pub module: ModuleId,
+    /// `true` for an `interface`, which only declares the functions of a
+    /// deployed contract. Interfaces aren't compiled.
+    pub is_interface: bool,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -896,20 +1138,82 @@ impl ContractId {
         self.data(db).ast.span
     }
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
     }
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.pub_qual.is_some()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
 
     pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
         self.data(db).module
     }
 
+    pub fn is_interface(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).is_interface
+    }
+
+    /// `true` if the contract has the `#[abi(packed_events)]` attribute, which
+    /// packs the data of the events that its functions emit, like
+    /// `abi.encodePacked` in Solidity, instead of ABI encoding it.
+    pub fn packs_event_data(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, ABI_PACKED_EVENTS).is_some()
+    }
+
+    /// `true` if the contract has the `#[erc165]` attribute, which generates a
+    /// `supportsInterface` function that reports the contract's own interface
+    /// and the interfaces it implements, as described by ERC-165.
+    pub fn supports_erc165(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, ERC165).is_some()
+    }
+
+    /// `true` if the contract has the `#[zero_init]` attribute, which lets its
+    /// fields start out as zero without a warning when `__init__` doesn't
+    /// assign them.
+    pub fn allows_zero_init(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, ZERO_INIT).is_some()
+    }
+
+    /// The interfaces that the contract is declared to implement with `impl`
+    /// statements, in any module of its ingot.
+    pub fn implemented_interfaces(&self, db: &dyn AnalyzerDb) -> Vec<ContractId> {
+        self.module(db)
+            .ingot(db)
+            .all_modules(db)
+            .iter()
+            .flat_map(|module| db.module_interface_impls(*module).value.to_vec())
+            .filter(|(contract, _)| contract == self)
+            .map(|(_, interface)| interface)
+            .collect()
+    }
+
+    /// The contracts that this contract inherits from, directly or
+    /// indirectly. Each contract comes after its own parents.
+    pub fn ancestors(&self, db: &dyn AnalyzerDb) -> Rc<[ContractId]> {
+        db.contract_ancestors(*self).value
+    }
+
+    /// Contract fields, in declaration order, starting with the fields that
+    /// are inherited. The storage layout of the contract follows this order.
     pub fn fields(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, ContractFieldId>> {
         db.contract_field_map(*self).value
     }
 
+    /// The position of the named field in [`ContractId::fields`].
+    pub fn field_index(&self, db: &dyn AnalyzerDb, name: &str) -> Option<usize> {
+        self.fields(db).get_index_of(name)
+    }
+
     pub fn field_type(
         &self,
         db: &dyn AnalyzerDb,
@@ -945,18 +1249,38 @@ impl ContractId {
         db.contract_call_function(*self).value
     }
 
-    /// User functions, public and not. Excludes `__init__` and `__call__`.
+    /// The `__fallback__` function, which the generated call dispatcher calls
+    /// if the calldata doesn't select a public function.
+    pub fn fallback_function(&self, db: &dyn AnalyzerDb) -> Option<FunctionId> {
+        db.contract_fallback_function(*self).value
+    }
+
+    /// The `__receive__` function, which the generated call dispatcher calls
+    /// if the calldata is empty.
+    pub fn receive_function(&self, db: &dyn AnalyzerDb) -> Option<FunctionId> {
+        db.contract_receive_function(*self).value
+    }
+
+    /// User functions, public and not, in declaration order. Excludes
+    /// `__init__`, `__call__`, `__fallback__` and `__receive__`.
     pub fn functions(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, FunctionId>> {
         db.contract_function_map(*self).value
     }
 
+    /// The position of the named function in [`ContractId::functions`].
+    pub fn function_index(&self, db: &dyn AnalyzerDb, name: &str) -> Option<usize> {
+        self.functions(db).get_index_of(name)
+    }
+
     /// Lookup a function by name. Searches all user functions, private or not.
-    /// Excludes `__init__` and `__call__`.
+    /// Excludes `__init__`, `__call__`, `__fallback__` and `__receive__`.
     pub fn function(&self, db: &dyn AnalyzerDb, name: &str) -> Option<FunctionId> {
         self.functions(db).get(name).copied()
     }
 
-    /// Excludes `__init__` and `__call__`.
+    /// Public user functions, in declaration order. The ABI and the call
+    /// dispatcher of the contract follow this order. Excludes `__init__`,
+    /// `__call__`, `__fallback__` and `__receive__`.
     pub fn public_functions(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, FunctionId>> {
         db.contract_public_function_map(*self)
     }
@@ -994,25 +1318,168 @@ impl ContractId {
         db.contract_runtime_dependency_graph(*self).0
     }
 
+    /// The calls to other contracts and accounts that the contract's code can
+    /// make, e.g. for audits and reentrancy checks.
+    pub fn external_calls(&self, db: &dyn AnalyzerDb) -> Rc<[ExternalCall]> {
+        db.contract_external_calls(*self)
+    }
+
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        db.contract_ancestors(*self).sink_diagnostics(sink);
+
+        let attributes = &self.data(db).ast.kind.attributes;
+        sink_attribute_diagnostics(
+            attributes,
+            &[ABI_STANDARD, ABI_PACKED_EVENTS, ERC165, ZERO_INIT],
+            sink,
+        );
+        let standard = attribute_span(attributes, ABI_STANDARD);
+        let packed = attribute_span(attributes, ABI_PACKED_EVENTS);
+        if let (Some(standard), Some(packed)) = (standard, packed) {
+            sink.push(&errors::fancy_error(
+                "conflicting ABI encodings",
+                vec![
+                    Label::primary(packed, "the event data is packed here"),
+                    Label::secondary(standard, "but the standard encoding is selected here"),
+                ],
+                vec!["Note: a contract can only use one ABI encoding".into()],
+            ));
+        }
+
+        // Calls are dispatched by selector, so the selectors of the public
+        // functions must be distinct.
+        let mut selectors = IndexMap::<Selector, FunctionId>::new();
+        for function in self.public_functions(db).values() {
+            let selector = function.selector(db);
+            match selectors.entry(selector) {
+                Entry::Occupied(entry) => {
+                    let (name, other) = (function.name(db), entry.get().name(db));
+                    sink.push(&errors::fancy_error(
+                        format!("functions `{}` and `{}` have the same selector", other, name),
+                        vec![
+                            Label::primary(
+                                function.name_span(db),
+                                format!("`{}` has the selector `{}`", name, selector),
+                            ),
+                            Label::secondary(
+                                entry.get().name_span(db),
+                                format!("`{}` has the same selector", other),
+                            ),
+                        ],
+                        vec![
+                            "Hint: rename one of the functions, or pin another selector with `#[selector(..)]`"
+                                .into(),
+                        ],
+                    ));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(*function);
+                }
+            }
+        }
+
+        if let Some(erc165) = attribute_span(attributes, ERC165) {
+            let supports_interface = Selector::of_signature("supportsInterface(bytes4)");
+            if let Some(function) = selectors
+                .get(&supports_interface)
+                .filter(|function| function.name(db) != SUPPORTS_INTERFACE)
+            {
+                sink.push(&errors::fancy_error(
+                    format!(
+                        "function `{}` has the same selector as `{}`",
+                        function.name(db),
+                        SUPPORTS_INTERFACE
+                    ),
+                    vec![
+                        Label::primary(
+                            function.name_span(db),
+                            format!(
+                                "`{}` has the selector `{}`",
+                                function.name(db),
+                                supports_interface
+                            ),
+                        ),
+                        Label::secondary(
+                            erc165,
+                            format!("`#[erc165]` generates `{}`", SUPPORTS_INTERFACE),
+                        ),
+                    ],
+                    vec!["Hint: pin another selector with `#[selector(..)]`".into()],
+                ));
+            }
+            if let Some(function) = self.public_functions(db).get(SUPPORTS_INTERFACE) {
+                sink.push(&errors::fancy_error(
+                    format!("`{}` is already defined", SUPPORTS_INTERFACE),
+                    vec![
+                        Label::primary(
+                            function.name_span(db),
+                            format!("`{}` is defined here", SUPPORTS_INTERFACE),
+                        ),
+                        Label::secondary(erc165, "but `#[erc165]` generates it"),
+                    ],
+                    vec![format!(
+                        "Hint: remove the `{}` function or the `#[erc165]` attribute",
+                        SUPPORTS_INTERFACE
+                    )],
+                ));
+            }
+        }
+
+        // The events are checked against the encoding of every contract that
+        // emits them, including the events emitted by inherited functions.
+        if let Some(packed) = packed {
+            let events = db
+                .contract_all_functions(*self)
+                .iter()
+                .flat_map(|function| {
+                    function
+                        .body(db)
+                        .emits
+                        .values()
+                        .copied()
+                        .collect::<Vec<_>>()
+                })
+                .collect::<IndexSet<_>>();
+            for event in events {
+                sink_packed_event_diagnostics(db, event, packed, sink);
+            }
+        }
+
+        // Inherited fields, events and functions are checked with the
+        // contract that defines them, so only their own items are checked here.
+        let own = &self.data(db).ast.kind;
+
         // fields
         db.contract_field_map(*self).sink_diagnostics(sink);
         db.contract_all_fields(*self)
             .iter()
+            .filter(|field| own.fields.contains(&field.data(db).ast))
             .for_each(|field| field.sink_diagnostics(db, sink));
 
         // events
         db.contract_event_map(*self).sink_diagnostics(sink);
         db.contract_all_events(*self)
             .iter()
+            .filter(|event| {
+                own.body.iter().any(
+                    |stmt| matches!(stmt, ast::ContractStmt::Event(node) if *node == event.data(db).ast),
+                )
+            })
             .for_each(|event| event.sink_diagnostics(db, sink));
 
         // functions
         db.contract_init_function(*self).sink_diagnostics(sink);
         db.contract_call_function(*self).sink_diagnostics(sink);
+        db.contract_fallback_function(*self).sink_diagnostics(sink);
+        db.contract_receive_function(*self).sink_diagnostics(sink);
         db.contract_function_map(*self).sink_diagnostics(sink);
         db.contract_all_functions(*self)
             .iter()
+            .filter(|id| {
+                own.body.iter().any(
+                    |stmt| matches!(stmt, ast::ContractStmt::Function(node) if *node == id.data(db).ast),
+                )
+            })
             .for_each(|id| id.sink_diagnostics(db, sink));
     }
 }
@@ -1028,7 +1495,15 @@ pub struct ContractFieldId(pub(crate) u32);
 impl_intern_key!(ContractFieldId);
 impl ContractFieldId {
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
     }
     pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<ContractField> {
         db.lookup_intern_contract_field(*self)
@@ -1046,6 +1521,9 @@ pub struct Function {
     pub ast: Node<ast::Function>,
     pub module: ModuleId,
     pub parent: Option<Class>,
+    /// The types that the generic parameters of the function are bound to.
+    /// Empty unless this is an instance of a generic function.
+    pub generic_args: Vec<FixedSize>,
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -1059,11 +1537,19 @@ impl FunctionId {
         self.data(db).ast.span
     }
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
     }
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
 
     // This should probably be scrapped in favor of `parent()`
     pub fn class(&self, db: &dyn AnalyzerDb) -> Option<Class> {
@@ -1080,6 +1566,49 @@ impl FunctionId {
         self.data(db).module
     }
 
+    pub fn generic_params(&self, db: &dyn AnalyzerDb) -> Rc<[SmolStr]> {
+        db.function_generic_params(*self).value
+    }
+    pub fn generic_args(&self, db: &dyn AnalyzerDb) -> Vec<FixedSize> {
+        self.data(db).generic_args.clone()
+    }
+
+    /// Returns `true` if the function, or the struct that it belongs to, has
+    /// generic parameters that aren't bound to any types. Such a function is
+    /// only analyzed once it's instantiated.
+    pub fn is_generic(&self, db: &dyn AnalyzerDb) -> bool {
+        (!self.generic_params(db).is_empty() && self.data(db).generic_args.is_empty())
+            || matches!(self.class(db), Some(Class::Struct(id)) if id.is_generic(db))
+    }
+
+    /// Returns the instance of this generic function with its generic
+    /// parameters bound to `args`.
+    pub fn instantiate(&self, db: &dyn AnalyzerDb, args: Vec<FixedSize>) -> FunctionId {
+        let data = self.data(db);
+        db.intern_function(Rc::new(Function {
+            ast: data.ast.clone(),
+            module: data.module,
+            parent: data.parent,
+            generic_args: args,
+        }))
+    }
+
+    /// The types that the generic parameters in scope of the function body are
+    /// bound to, including those of the struct that the function belongs to.
+    pub fn type_params(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, FixedSize> {
+        let mut params = match self.class(db) {
+            Some(Class::Struct(id)) => id.type_params(db),
+            _ => IndexMap::new(),
+        };
+        params.extend(
+            self.generic_params(db)
+                .iter()
+                .cloned()
+                .zip(self.generic_args(db)),
+        );
+        params
+    }
+
     pub fn takes_self(&self, db: &dyn AnalyzerDb) -> bool {
         self.signature(db).self_decl.is_some()
     }
@@ -1090,7 +1619,7 @@ impl FunctionId {
                 .kind
                 .args
                 .iter()
-                .find_map(|arg| matches!(arg.kind, ast::FunctionArg::Zelf).then(|| arg.span))
+                .find_map(|arg| matches!(arg.kind, ast::FunctionArg::Zelf { .. }).then(|| arg.span))
         } else {
             None
         }
@@ -1099,6 +1628,21 @@ impl FunctionId {
     pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
         self.pub_span(db).is_some()
     }
+    /// `true` if the function is declared in an interface, and so has no body.
+    pub fn is_interface_function(&self, db: &dyn AnalyzerDb) -> bool {
+        matches!(self.class(db), Some(Class::Contract(contract)) if contract.is_interface(db))
+    }
+    /// All functions of the module with the same name as this module-level
+    /// function, including this one, if the function is overloaded.
+    pub fn overloads(&self, db: &dyn AnalyzerDb) -> Option<Rc<[FunctionId]>> {
+        if self.class(db).is_some() {
+            return None;
+        }
+        db.module_function_overloads(self.module(db))
+            .value
+            .get(&self.name(db))
+            .cloned()
+    }
     pub fn is_constructor(&self, db: &dyn AnalyzerDb) -> bool {
         self.name(db) == "__init__"
     }
@@ -1111,6 +1655,53 @@ impl FunctionId {
     pub fn unsafe_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
         self.data(db).ast.kind.unsafe_
     }
+    /// `true` if the function has the `#[must_use]` attribute, which makes
+    /// discarding its return value an error.
+    pub fn is_must_use(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, MUST_USE).is_some()
+    }
+    /// `true` if the function has the `#[init_only]` attribute, which means
+    /// that it can only be called from `__init__` and other `#[init_only]`
+    /// functions.
+    pub fn is_init_only(&self, db: &dyn AnalyzerDb) -> bool {
+        self.init_only_span(db).is_some()
+    }
+    pub fn init_only_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        attribute_span(&self.data(db).ast.kind.attributes, INIT_ONLY)
+    }
+    /// `true` if the function has the `#[payable]` attribute, which lets a
+    /// public contract function accept calls that transfer ether. Calls to
+    /// other public functions revert if they transfer ether. `__receive__` is
+    /// always payable.
+    pub fn is_payable(&self, db: &dyn AnalyzerDb) -> bool {
+        self.payable_span(db).is_some()
+            || (self.name(db) == "__receive__"
+                && matches!(self.class(db), Some(Class::Contract(_))))
+    }
+    pub fn payable_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        attribute_span(&self.data(db).ast.kind.attributes, PAYABLE)
+    }
+    pub fn selector_span(&self, db: &dyn AnalyzerDb) -> Option<Span> {
+        attribute_span(&self.data(db).ast.kind.attributes, SELECTOR)
+    }
+    /// The selector that the function's `#[selector(..)]` attribute pins, if
+    /// it has the attribute and the value is valid.
+    pub fn pinned_selector(&self, db: &dyn AnalyzerDb) -> Option<Selector> {
+        self.data(db)
+            .ast
+            .kind
+            .attributes
+            .iter()
+            .find_map(|attr| selector_attribute_arg(&attr.kind))
+            .and_then(Selector::from_hex)
+    }
+    /// The 4-byte selector by which the function is called, if it's a public
+    /// contract or interface function: the value that the `#[selector(..)]`
+    /// attribute pins, or else the one derived from its canonical signature.
+    pub fn selector(&self, db: &dyn AnalyzerDb) -> Selector {
+        self.pinned_selector(db)
+            .unwrap_or_else(|| Selector::of_signature(&canonical_signature(db, *self)))
+    }
     pub fn signature(&self, db: &dyn AnalyzerDb) -> Rc<types::FunctionSignature> {
         db.function_signature(*self).value
     }
@@ -1120,7 +1711,28 @@ impl FunctionId {
     pub fn dependency_graph(&self, db: &dyn AnalyzerDb) -> Rc<DepGraph> {
         db.function_dependency_graph(*self).0
     }
+    /// Whether the function, or any function that it calls, reads or
+    /// modifies the state of the chain.
+    pub fn state_mutability(&self, db: &dyn AnalyzerDb) -> types::StateMutability {
+        db.function_state_mutability(*self)
+    }
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink_attribute_diagnostics(
+            &self.data(db).ast.kind.attributes,
+            FUNCTION_ATTRIBUTES,
+            sink,
+        );
+        sink.push_all(db.function_generic_params(*self).diagnostics.iter());
+        // Generic functions are checked when they're instantiated.
+        if !self.is_generic(db) {
+            self.sink_instance_diagnostics(db, sink);
+        }
+    }
+
+    /// Sinks the diagnostics of the function signature and body. Unlike
+    /// [`FunctionId::sink_diagnostics`], this is also used for the instances of
+    /// generic functions.
+    pub fn sink_instance_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
         sink.push_all(db.function_signature(*self).diagnostics.iter());
         sink.push_all(db.function_body(*self).diagnostics.iter());
     }
@@ -1174,6 +1786,9 @@ pub enum MemberFunction {
 pub struct Struct {
     pub ast: Node<ast::Struct>,
     pub module: ModuleId,
+    /// The types that the generic parameters of the struct are bound to.
+    /// Empty unless this is an instance of a generic struct.
+    pub generic_args: Vec<FixedSize>,
 }
 
 #[derive(Default, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
@@ -1187,17 +1802,81 @@ impl StructId {
         self.data(db).ast.span
     }
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
     }
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.pub_qual.is_some()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
     pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
         self.data(db).module
     }
     pub fn typ(&self, db: &dyn AnalyzerDb) -> Rc<types::Struct> {
         db.struct_type(*self)
     }
+    /// `true` if the struct has the `#[must_use]` attribute, which makes
+    /// discarding a value of the struct type an error.
+    pub fn is_must_use(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, MUST_USE).is_some()
+    }
+    /// `true` if the struct has the `#[error]` attribute, which lets it be
+    /// used as the error of a `revert` statement.
+    pub fn is_error(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, ERROR).is_some()
+    }
+    /// `true` if the struct has the `#[packed]` attribute, which packs its
+    /// fields into as few storage words as possible.
+    pub fn is_packed(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, PACKED).is_some()
+    }
+    /// `true` if the struct has the `#[repr(declared)]` attribute, which keeps
+    /// the fields of a `#[packed]` struct in declaration order in storage.
+    pub fn is_repr_declared(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, REPR_DECLARED).is_some()
+    }
+
+    pub fn generic_params(&self, db: &dyn AnalyzerDb) -> Rc<[SmolStr]> {
+        db.struct_generic_params(*self).value
+    }
+    pub fn generic_args(&self, db: &dyn AnalyzerDb) -> Vec<FixedSize> {
+        self.data(db).generic_args.clone()
+    }
+
+    /// Returns `true` if the struct has generic parameters that aren't bound to
+    /// any types. Such a struct is only analyzed once it's instantiated.
+    pub fn is_generic(&self, db: &dyn AnalyzerDb) -> bool {
+        !self.generic_params(db).is_empty() && self.data(db).generic_args.is_empty()
+    }
+
+    /// Returns the instance of this generic struct with its generic parameters
+    /// bound to `args`.
+    pub fn instantiate(&self, db: &dyn AnalyzerDb, args: Vec<FixedSize>) -> StructId {
+        let data = self.data(db);
+        db.intern_struct(Rc::new(Struct {
+            ast: data.ast.clone(),
+            module: data.module,
+            generic_args: args,
+        }))
+    }
+
+    /// The types that the generic parameters of the struct are bound to.
+    pub fn type_params(&self, db: &dyn AnalyzerDb) -> IndexMap<SmolStr, FixedSize> {
+        self.generic_params(db)
+            .iter()
+            .cloned()
+            .zip(self.generic_args(db))
+            .collect()
+    }
 
     pub fn has_private_field(&self, db: &dyn AnalyzerDb) -> bool {
         self.private_fields(db).iter().count() > 0
@@ -1228,12 +1907,18 @@ impl StructId {
             .any(|(name, _)| !self.is_base_type(db, name.as_str()))
     }
 
+    /// Struct fields, in declaration order. The memory layout and the ABI
+    /// encoding of the struct follow this order.
     pub fn fields(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, StructFieldId>> {
         db.struct_field_map(*self).value
     }
+    /// Struct functions, in declaration order.
     pub fn functions(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, FunctionId>> {
         db.struct_function_map(*self).value
     }
+    pub fn function_index(&self, db: &dyn AnalyzerDb, name: &str) -> Option<usize> {
+        self.functions(db).get_index_of(name)
+    }
     pub fn function(&self, db: &dyn AnalyzerDb, name: &str) -> Option<FunctionId> {
         self.functions(db).get(name).copied()
     }
@@ -1261,14 +1946,35 @@ impl StructId {
         db.struct_dependency_graph(*self).0
     }
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink_attribute_diagnostics(
+            &self.data(db).ast.kind.attributes,
+            &[MUST_USE, ERROR, PACKED, REPR_DECLARED],
+            sink,
+        );
+        sink.push_all(db.struct_generic_params(*self).diagnostics.iter());
         sink.push_all(db.struct_field_map(*self).diagnostics.iter());
+        // The field types of generic structs are checked when they're instantiated.
+        if !self.is_generic(db) {
+            db.struct_all_fields(*self)
+                .iter()
+                .for_each(|id| id.sink_diagnostics(db, sink));
+        }
+
+        db.struct_all_functions(*self)
+            .iter()
+            .for_each(|id| id.sink_diagnostics(db, sink));
+    }
+
+    /// Sinks the diagnostics of the field types and functions of an instance
+    /// of a generic struct.
+    pub fn sink_instance_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
         db.struct_all_fields(*self)
             .iter()
             .for_each(|id| id.sink_diagnostics(db, sink));
 
         db.struct_all_functions(*self)
             .iter()
-            .for_each(|id| id.sink_diagnostics(db, sink));
+            .for_each(|id| id.sink_instance_diagnostics(db, sink));
     }
 }
 
@@ -1283,7 +1989,15 @@ pub struct StructFieldId(pub(crate) u32);
 impl_intern_key!(StructFieldId);
 impl StructFieldId {
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
     }
     pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.span
@@ -1306,6 +2020,136 @@ impl StructFieldId {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Enum {
+    pub ast: Node<ast::Enum>,
+    pub module: ModuleId,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct EnumId(pub(crate) u32);
+impl_intern_key!(EnumId);
+impl EnumId {
+    pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<Enum> {
+        db.lookup_intern_enum(*self)
+    }
+    pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.span
+    }
+    pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
+        self.data(db).ast.kind.name.kind.clone()
+    }
+    pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.kind.name.span
+    }
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.pub_qual.is_some()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
+    pub fn module(&self, db: &dyn AnalyzerDb) -> ModuleId {
+        self.data(db).module
+    }
+    pub fn parent(&self, db: &dyn AnalyzerDb) -> Item {
+        Item::Module(self.data(db).module)
+    }
+    pub fn typ(&self, db: &dyn AnalyzerDb) -> types::Enum {
+        types::Enum::from_id(*self, db)
+    }
+    /// `true` if the enum has the `#[must_use]` attribute, which makes
+    /// discarding a value of the enum type an error.
+    pub fn is_must_use(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, MUST_USE).is_some()
+    }
+
+    pub fn variants(&self, db: &dyn AnalyzerDb) -> Rc<IndexMap<SmolStr, EnumVariantId>> {
+        db.enum_variant_map(*self).value
+    }
+    pub fn variant(&self, db: &dyn AnalyzerDb, name: &str) -> Option<EnumVariantId> {
+        self.variants(db).get(name).copied()
+    }
+
+    /// The fields of the struct that the enum is represented as: the `u8` tag
+    /// of the active variant, followed by the fields of every variant (in
+    /// declaration order). The fields of inactive variants are zeroed.
+    pub fn repr_fields(&self, db: &dyn AnalyzerDb) -> Vec<(SmolStr, FixedSize)> {
+        let mut fields = vec![(SmolStr::new("tag"), FixedSize::u8())];
+        for variant in self.variants(db).values() {
+            fields.extend(variant.fields(db).iter().enumerate().map(|(idx, typ)| {
+                (
+                    variant.repr_field_name(db, idx),
+                    typ.clone().expect("enum variant field type error"),
+                )
+            }));
+        }
+        fields
+    }
+
+    pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink_attribute_diagnostics(&self.data(db).ast.kind.attributes, &[MUST_USE], sink);
+        sink.push_all(db.enum_variant_map(*self).diagnostics.iter());
+        db.enum_all_variants(*self)
+            .iter()
+            .for_each(|id| id.sink_diagnostics(db, sink));
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct EnumVariant {
+    pub ast: Node<ast::Variant>,
+    pub parent: EnumId,
+    pub tag: usize,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct EnumVariantId(pub(crate) u32);
+impl_intern_key!(EnumVariantId);
+impl EnumVariantId {
+    pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<EnumVariant> {
+        db.lookup_intern_enum_variant(*self)
+    }
+    pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
+        self.data(db).ast.kind.name.kind.clone()
+    }
+    pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.kind.name.span
+    }
+    pub fn span(&self, db: &dyn AnalyzerDb) -> Span {
+        self.data(db).ast.span
+    }
+    pub fn parent(&self, db: &dyn AnalyzerDb) -> Item {
+        Item::Type(TypeDef::Enum(self.data(db).parent))
+    }
+    pub fn enum_id(&self, db: &dyn AnalyzerDb) -> EnumId {
+        self.data(db).parent
+    }
+    /// The value that identifies the variant at runtime.
+    pub fn tag(&self, db: &dyn AnalyzerDb) -> usize {
+        self.data(db).tag
+    }
+    pub fn fields(&self, db: &dyn AnalyzerDb) -> Rc<[Result<FixedSize, TypeError>]> {
+        db.enum_variant_fields(*self).value
+    }
+    pub fn is_unit(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.fields.is_empty()
+    }
+    /// The name of the field that holds the value of the variant field at
+    /// index `idx`, in the struct the enum is represented as.
+    pub fn repr_field_name(&self, db: &dyn AnalyzerDb, idx: usize) -> SmolStr {
+        format!("{}_{}", self.name(db), idx).into()
+    }
+
+    pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink.push_all(db.enum_variant_fields(*self).diagnostics.iter())
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Event {
     pub ast: Node<ast::Event>,
@@ -1319,11 +2163,22 @@ impl_intern_key!(EventId);
 
 impl EventId {
     pub fn name(&self, db: &dyn AnalyzerDb) -> SmolStr {
-        self.data(db).ast.name().into()
+        self.data(db).ast.kind.name.kind.clone()
     }
     pub fn name_span(&self, db: &dyn AnalyzerDb) -> Span {
         self.data(db).ast.kind.name.span
     }
+    pub fn is_public(&self, db: &dyn AnalyzerDb) -> bool {
+        self.data(db).ast.kind.pub_qual.is_some()
+    }
+    pub fn doc(&self, db: &dyn AnalyzerDb) -> Option<SmolStr> {
+        self.data(db)
+            .ast
+            .kind
+            .doc
+            .as_ref()
+            .map(|doc| doc.kind.clone())
+    }
     pub fn data(&self, db: &dyn AnalyzerDb) -> Rc<Event> {
         db.lookup_intern_event(*self)
     }
@@ -1340,11 +2195,182 @@ impl EventId {
             Item::Module(self.module(db))
         }
     }
+    /// `true` if the event has the `#[anonymous]` attribute, which leaves the
+    /// hash of its signature out of the topics of its logs.
+    pub fn is_anonymous(&self, db: &dyn AnalyzerDb) -> bool {
+        attribute_span(&self.data(db).ast.kind.attributes, ANONYMOUS).is_some()
+    }
     pub fn sink_diagnostics(&self, db: &dyn AnalyzerDb, sink: &mut impl DiagnosticSink) {
+        sink_attribute_diagnostics(&self.data(db).ast.kind.attributes, &[ANONYMOUS], sink);
         sink.push_all(db.event_type(*self).diagnostics.iter());
     }
 }
 
+/// The name of the attribute that makes discarding a value an error.
+const MUST_USE: &str = "must_use";
+/// The name of the attribute that restricts a function to the constructor.
+const INIT_ONLY: &str = "init_only";
+/// The name of the attribute that lets a function receive ether.
+const PAYABLE: &str = "payable";
+/// The name of the attribute that pins the selector of a public function,
+/// e.g. `#[selector(0xa9059cbb)]`.
+const SELECTOR: &str = "selector";
+
+/// The name of the attribute that declares a struct as an error type.
+const ERROR: &str = "error";
+/// The name of the attribute that packs the fields of a struct in storage.
+const PACKED: &str = "packed";
+/// The name of the attribute that stops the compiler from reordering the
+/// fields of a packed struct.
+const REPR_DECLARED: &str = "repr(declared)";
+
+/// The name of the attribute that leaves the signature topic out of the logs
+/// of an event.
+const ANONYMOUS: &str = "anonymous";
+
+/// The name of the attribute that selects the standard ABI encoding for the
+/// data of the events that a contract emits. This is the default.
+const ABI_STANDARD: &str = "abi(standard)";
+/// The name of the attribute that packs the data of the events that a
+/// contract emits.
+const ABI_PACKED_EVENTS: &str = "abi(packed_events)";
+/// The name of the attribute that generates an ERC-165 `supportsInterface`
+/// function for a contract.
+const ERC165: &str = "erc165";
+/// The name of the attribute that lets the fields of a contract start out as
+/// zero when `__init__` doesn't assign them.
+const ZERO_INIT: &str = "zero_init";
+/// The name of the function that the `#[erc165]` attribute generates.
+pub const SUPPORTS_INTERFACE: &str = "supportsInterface";
+
+/// The attributes that can be used on functions.
+const FUNCTION_ATTRIBUTES: &[&str] = &[MUST_USE, INIT_ONLY, PAYABLE, SELECTOR];
+
+fn attribute_span(attributes: &[Node<SmolStr>], name: &str) -> Option<Span> {
+    attributes
+        .iter()
+        .find(|attr| attribute_key(&attr.kind) == name)
+        .map(|attr| attr.span)
+}
+
+/// The name by which an attribute is allowed on an item. It's the name of the
+/// attribute, which includes its argument, except for `#[selector(..)]`,
+/// whose argument is a value.
+fn attribute_key(attr: &str) -> &str {
+    if selector_attribute_arg(attr).is_some() {
+        SELECTOR
+    } else {
+        attr
+    }
+}
+
+/// The argument of a `#[selector(..)]` attribute.
+fn selector_attribute_arg(attr: &str) -> Option<&str> {
+    attr.strip_prefix("selector(")?.strip_suffix(')')
+}
+
+/// Reports the attributes that the compiler doesn't know, and the ones that
+/// can't be used on the item.
+fn sink_attribute_diagnostics(
+    attributes: &[Node<SmolStr>],
+    allowed: &[&str],
+    sink: &mut impl DiagnosticSink,
+) {
+    for attr in attributes
+        .iter()
+        .filter(|attr| !allowed.contains(&attribute_key(&attr.kind)))
+    {
+        let diag = if FUNCTION_ATTRIBUTES.contains(&attribute_key(&attr.kind)) {
+            errors::error(
+                format!("`#[{}]` can only be used on functions", attr.kind),
+                attr.span,
+                "this attribute isn't allowed here",
+            )
+        } else if [ERROR, PACKED, REPR_DECLARED].contains(&attr.kind.as_str()) {
+            errors::error(
+                format!("`#[{}]` can only be used on structs", attr.kind),
+                attr.span,
+                "this attribute isn't allowed here",
+            )
+        } else if attr.kind == ANONYMOUS {
+            errors::error(
+                format!("`#[{}]` can only be used on events", attr.kind),
+                attr.span,
+                "this attribute isn't allowed here",
+            )
+        } else if [ABI_STANDARD, ABI_PACKED_EVENTS, ERC165, ZERO_INIT].contains(&attr.kind.as_str())
+        {
+            errors::error(
+                format!("`#[{}]` can only be used on contracts", attr.kind),
+                attr.span,
+                "this attribute isn't allowed here",
+            )
+        } else if attr.kind.starts_with("abi(") {
+            errors::fancy_error(
+                format!("unknown ABI encoding `{}`", attr.kind),
+                vec![Label::primary(attr.span, "this encoding isn't supported")],
+                vec![format!(
+                    "Note: the supported encodings are `{}` and `{}`",
+                    ABI_STANDARD, ABI_PACKED_EVENTS
+                )],
+            )
+        } else {
+            errors::error(
+                format!("unknown attribute `{}`", attr.kind),
+                attr.span,
+                "this attribute isn't supported",
+            )
+        };
+        sink.push(&diag);
+    }
+}
+
+/// Reports the fields of an event that can't be packed into the data of its
+/// logs, when it's emitted by a contract with the `#[abi(packed_events)]`
+/// attribute. Only numbers, booleans, addresses, strings and byte arrays can
+/// be packed, as the packed encoding of other values is ambiguous.
+fn sink_packed_event_diagnostics(
+    db: &dyn AnalyzerDb,
+    event: EventId,
+    attribute: Span,
+    sink: &mut impl DiagnosticSink,
+) {
+    let ast = &event.data(db).ast.kind;
+    for field in event
+        .typ(db)
+        .fields
+        .iter()
+        .filter(|field| !field.is_indexed)
+    {
+        let packable = match &field.typ {
+            Ok(FixedSize::Base(base)) => *base != types::Base::Unit,
+            Ok(FixedSize::Array(array)) => array.inner == types::Base::Numeric(types::Integer::U8),
+            Ok(FixedSize::String(_) | FixedSize::Contract(_)) | Err(_) => true,
+            Ok(_) => false,
+        };
+        let field_node = ast
+            .fields
+            .iter()
+            .find(|node| node.kind.name.kind == field.name);
+        if let (false, Some(field_node), Ok(typ)) = (packable, field_node, &field.typ) {
+            sink.push(&errors::fancy_error(
+                format!(
+                    "event `{}` can't be emitted with packed data",
+                    ast.name.kind
+                ),
+                vec![
+                    Label::primary(
+                        field_node.kind.typ.span,
+                        format!("a value of type `{}` can't be packed", typ),
+                    ),
+                    Label::secondary(attribute, "the event data is packed here"),
+                ],
+                vec!["Hint: make the field `idx`, or use the standard encoding".into()],
+            ));
+        }
+    }
+}
+
 pub trait DiagnosticSink {
     fn push(&mut self, diag: &Diagnostic);
     fn push_all<'a>(&mut self, iter: impl Iterator<Item = &'a Diagnostic>) {
@@ -1,3 +1,5 @@
 pub mod items;
 pub mod scopes;
+pub mod selector;
+pub mod type_id;
 pub mod types;
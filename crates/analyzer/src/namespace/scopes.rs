@@ -3,16 +3,17 @@
 use crate::context::{
     AnalyzerContext, CallType, Constant, ExpressionAttributes, FunctionBody, NamedThing,
 };
-use crate::errors::{AlreadyDefined, IncompleteItem, TypeError};
+use crate::errors::{AlreadyDefined, ConstEvalError, IncompleteItem, TypeError};
 use crate::namespace::items::Item;
 use crate::namespace::items::{Class, EventId, FunctionId, ModuleId};
-use crate::namespace::types::FixedSize;
+use crate::namespace::types::{FixedSize, StateMutability};
 use crate::AnalyzerDb;
 use fe_common::diagnostics::Diagnostic;
 use fe_common::Span;
 use fe_parser::{ast, node::NodeId};
 use fe_parser::{ast::Expr, node::Node};
 use indexmap::IndexMap;
+use smol_str::SmolStr;
 use std::cell::RefCell;
 use std::collections::BTreeMap;
 
@@ -21,14 +22,26 @@ use super::types::Type;
 pub struct ItemScope<'a> {
     db: &'a dyn AnalyzerDb,
     module: ModuleId,
+    type_params: IndexMap<SmolStr, FixedSize>,
     expressions: RefCell<IndexMap<NodeId, ExpressionAttributes>>,
     pub diagnostics: Vec<Diagnostic>,
 }
 impl<'a> ItemScope<'a> {
     pub fn new(db: &'a dyn AnalyzerDb, module: ModuleId) -> Self {
+        Self::with_type_params(db, module, IndexMap::new())
+    }
+
+    /// Creates a scope for analyzing an instance of a generic struct or
+    /// function, with its generic parameters bound to the given types.
+    pub fn with_type_params(
+        db: &'a dyn AnalyzerDb,
+        module: ModuleId,
+        type_params: IndexMap<SmolStr, FixedSize>,
+    ) -> Self {
         Self {
             db,
             module,
+            type_params,
             expressions: RefCell::new(IndexMap::default()),
             diagnostics: vec![],
         }
@@ -70,11 +83,9 @@ impl<'a> AnalyzerContext for ItemScope<'a> {
     fn constant_value_by_name(
         &self,
         name: &ast::SmolStr,
-    ) -> Result<Option<Constant>, IncompleteItem> {
+    ) -> Result<Option<Constant>, ConstEvalError> {
         if let Some(constant) = self.module.resolve_constant(self.db, name)? {
-            // It's ok to ignore an error.
-            // Diagnostics are already emitted when an error occurs.
-            Ok(constant.constant_value(self.db).ok())
+            constant.constant_value(self.db).map(Some)
         } else {
             Ok(None)
         }
@@ -92,6 +103,18 @@ impl<'a> AnalyzerContext for ItemScope<'a> {
         unreachable!("Can't call function outside of function")
     }
 
+    fn add_function_ref(&self, _node: &Node<ast::Expr>, _function: FunctionId) {
+        unreachable!("Can't refer to a function value outside of function")
+    }
+
+    fn add_in_bounds_index(&self, _node: &Node<ast::Expr>) {
+        unreachable!("Can't index an array outside of function")
+    }
+
+    fn add_state_read(&self, _node: &Node<ast::Expr>) {
+        unreachable!("Can't read the state outside of function")
+    }
+
     fn add_string(&self, _str_lit: ast::SmolStr) {
         unreachable!("Can't store string in the item scope")
     }
@@ -123,6 +146,10 @@ impl<'a> AnalyzerContext for ItemScope<'a> {
 
         item.value.map(NamedThing::Item)
     }
+
+    fn resolve_type_param(&self, name: &str) -> Option<FixedSize> {
+        self.type_params.get(name).cloned()
+    }
 }
 
 pub struct FunctionScope<'a> {
@@ -160,12 +187,14 @@ impl<'a> FunctionScope<'a> {
             .expect_none("emit statement attributes already exist");
     }
 
-    /// Attribute contextual information to a declaration node.
+    /// Attribute contextual information to a declaration node. The node is
+    /// the type description of the declaration, or its target if the type is
+    /// inferred from the declared value.
     ///
     /// # Panics
     ///
     /// Panics if an entry already exists for the node id.
-    pub fn add_declaration(&self, node: &Node<ast::TypeDesc>, typ: FixedSize) {
+    pub fn add_declaration<T>(&self, node: &Node<T>, typ: FixedSize) {
         self.add_node(node);
         self.body
             .borrow_mut()
@@ -174,6 +203,34 @@ impl<'a> FunctionScope<'a> {
             .expect_none("declaration attributes already exist");
     }
 
+    /// Records that the external call of a `try` statement doesn't revert
+    /// when the callee reverts. The node is the called function.
+    pub fn add_try_call(&self, node: &Node<ast::Expr>) {
+        self.body.borrow_mut().try_calls.insert(node.id);
+    }
+
+    /// Records the state mutability of the instructions used by a `yul` block.
+    pub fn add_yul_block(&self, node: &Node<ast::FuncStmt>, mutability: StateMutability) {
+        self.body
+            .borrow_mut()
+            .yul_blocks
+            .insert(node.id, mutability);
+    }
+
+    /// Attribute the caught error type to the type node of a `catch` clause.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an entry already exists for the node id.
+    pub fn add_catch_type(&self, node: &Node<ast::TypeDesc>, typ: FixedSize) {
+        self.add_node(node);
+        self.body
+            .borrow_mut()
+            .catch_types
+            .insert(node.id, typ)
+            .expect_none("catch clause attributes already exist");
+    }
+
     fn add_node<T>(&self, node: &Node<T>) {
         self.body.borrow_mut().spans.insert(node.id, node.span);
     }
@@ -227,7 +284,7 @@ impl<'a> AnalyzerContext for FunctionScope<'a> {
     fn constant_value_by_name(
         &self,
         _name: &ast::SmolStr,
-    ) -> Result<Option<Constant>, IncompleteItem> {
+    ) -> Result<Option<Constant>, ConstEvalError> {
         Ok(None)
     }
 
@@ -250,6 +307,23 @@ impl<'a> AnalyzerContext for FunctionScope<'a> {
             .expect_none("call attributes already exist");
     }
 
+    fn add_function_ref(&self, node: &Node<ast::Expr>, function: FunctionId) {
+        self.add_node(node);
+        self.body
+            .borrow_mut()
+            .function_refs
+            .insert(node.id, function)
+            .expect_none("function reference already exists");
+    }
+
+    fn add_in_bounds_index(&self, node: &Node<ast::Expr>) {
+        self.body.borrow_mut().in_bounds_indices.insert(node.id);
+    }
+
+    fn add_state_read(&self, node: &Node<ast::Expr>) {
+        self.body.borrow_mut().state_reads.insert(node.id);
+    }
+
     fn add_string(&self, s: ast::SmolStr) {
         self.body.borrow_mut().string_literals.insert(s);
     }
@@ -319,6 +393,10 @@ impl<'a> AnalyzerContext for FunctionScope<'a> {
 
         item.value.map(NamedThing::Item)
     }
+
+    fn resolve_type_param(&self, name: &str) -> Option<FixedSize> {
+        self.function.type_params(self.db).get(name).cloned()
+    }
 }
 
 pub struct BlockScope<'a, 'b> {
@@ -335,7 +413,13 @@ pub enum BlockScopeType {
     Function,
     IfElse,
     Loop,
+    /// The body of a `let x: u256 = loop:` statement, which is exited with a
+    /// value of the given type.
+    LoopWithValue(FixedSize),
     Unsafe,
+    /// An `unchecked` block, whose integer arithmetic wraps around on
+    /// overflow.
+    Unchecked,
 }
 
 impl AnalyzerContext for BlockScope<'_, '_> {
@@ -386,7 +470,7 @@ impl AnalyzerContext for BlockScope<'_, '_> {
     fn constant_value_by_name(
         &self,
         name: &ast::SmolStr,
-    ) -> Result<Option<Constant>, IncompleteItem> {
+    ) -> Result<Option<Constant>, ConstEvalError> {
         if let Some(constant) = self.constant_defs.borrow().get(name.as_str()) {
             Ok(Some(constant.clone()))
         } else if let Some(parent) = self.parent {
@@ -394,7 +478,7 @@ impl AnalyzerContext for BlockScope<'_, '_> {
         } else {
             match self.resolve_name(name)? {
                 Some(NamedThing::Item(Item::Constant(constant))) => {
-                    Ok(constant.constant_value(self.db()).ok())
+                    constant.constant_value(self.db()).map(Some)
                 }
                 _ => Ok(None),
             }
@@ -413,6 +497,18 @@ impl AnalyzerContext for BlockScope<'_, '_> {
         self.root.add_call(node, call_type)
     }
 
+    fn add_function_ref(&self, node: &Node<ast::Expr>, function: FunctionId) {
+        self.root.add_function_ref(node, function)
+    }
+
+    fn add_in_bounds_index(&self, node: &Node<ast::Expr>) {
+        self.root.add_in_bounds_index(node)
+    }
+
+    fn add_state_read(&self, node: &Node<ast::Expr>) {
+        self.root.add_state_read(node)
+    }
+
     fn add_string(&self, str_lit: ast::SmolStr) {
         self.root.add_string(str_lit)
     }
@@ -439,6 +535,10 @@ impl AnalyzerContext for BlockScope<'_, '_> {
         item.value.map(NamedThing::Item)
     }
 
+    fn resolve_type_param(&self, name: &str) -> Option<FixedSize> {
+        self.root.resolve_type_param(name)
+    }
+
     fn add_diagnostic(&mut self, diag: Diagnostic) {
         self.root.diagnostics.borrow_mut().push(diag)
     }
@@ -465,6 +565,14 @@ impl<'a, 'b> BlockScope<'a, 'b> {
         }
     }
 
+    /// The type of the innermost loop that the block is part of, if any.
+    pub fn innermost_loop(&self) -> Option<&BlockScopeType> {
+        match self.typ {
+            BlockScopeType::Loop | BlockScopeType::LoopWithValue(_) => Some(&self.typ),
+            _ => self.parent?.innermost_loop(),
+        }
+    }
+
     /// Add a variable to the block scope.
     pub fn add_var(
         &mut self,
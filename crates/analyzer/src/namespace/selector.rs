@@ -0,0 +1,151 @@
+//! The 4-byte selectors by which the public functions of contracts are called,
+//! as in the Solidity ABI.
+
+use crate::namespace::items::{FunctionId, StructId};
+use crate::namespace::types::{Base, FixedSize, Integer};
+use crate::AnalyzerDb;
+use fe_common::utils::keccak;
+use std::fmt;
+
+/// The first 4 bytes of the calldata of a call, which select the function
+/// that's called. It's the first 4 bytes of the keccak256 hash of the
+/// function's canonical signature (see [`canonical_signature`]), unless the
+/// function pins another value with the `#[selector(..)]` attribute.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Selector(pub u32);
+
+impl Selector {
+    pub fn of_signature(signature: &str) -> Self {
+        let hash = keccak::full_as_bytes(signature.as_bytes());
+        Selector(u32::from_be_bytes([hash[0], hash[1], hash[2], hash[3]]))
+    }
+
+    /// Parses the argument of a `#[selector(..)]` attribute, which is written
+    /// as `0x` followed by 8 hex digits.
+    pub fn from_hex(text: &str) -> Option<Self> {
+        let digits = text.strip_prefix("0x")?;
+        if digits.len() != 8 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        u32::from_str_radix(digits, 16).ok().map(Selector)
+    }
+}
+
+impl fmt::Display for Selector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x}", self.0)
+    }
+}
+
+/// The signature from which the selector of a function is derived: its name
+/// and the ABI types of its parameters, e.g. `transfer(address,uint256)`.
+/// Structs, enums and tuples are written as the list of their component types,
+/// e.g. `(uint256,bool)`.
+pub fn canonical_signature(db: &dyn AnalyzerDb, function: FunctionId) -> String {
+    let params = function
+        .signature(db)
+        .params
+        .iter()
+        .map(|param| match &param.typ {
+            Ok(typ) => abi_type_name(db, typ),
+            // Functions with parameter type errors aren't compiled.
+            Err(_) => "?".into(),
+        })
+        .collect::<Vec<_>>();
+    format!("{}({})", function.name(db), params.join(","))
+}
+
+/// The name of a type in a canonical signature.
+pub fn abi_type_name(db: &dyn AnalyzerDb, typ: &FixedSize) -> String {
+    let tuple = |types: Vec<FixedSize>| {
+        let names = types
+            .iter()
+            .map(|typ| abi_type_name(db, typ))
+            .collect::<Vec<_>>();
+        format!("({})", names.join(","))
+    };
+    match typ {
+        FixedSize::Base(Base::Numeric(integer)) if integer.is_signed() => {
+            format!("int{}", integer.bits())
+        }
+        FixedSize::Base(Base::Numeric(integer)) => format!("uint{}", integer.bits()),
+        FixedSize::Base(Base::Bool) => "bool".into(),
+        FixedSize::Base(Base::Address) | FixedSize::Contract(_) => "address".into(),
+        FixedSize::Base(Base::Unit) => "()".into(),
+        FixedSize::Array(array) if array.inner == Base::Numeric(Integer::U8) => "bytes".into(),
+        FixedSize::Array(array) => format!(
+            "{}[{}]",
+            abi_type_name(db, &FixedSize::Base(array.inner)),
+            array.size
+        ),
+        FixedSize::String(_) => "string".into(),
+        FixedSize::Tuple(tuple_) => tuple(tuple_.items.to_vec()),
+        FixedSize::Struct(struct_) => tuple(struct_field_types(db, struct_.id)),
+        FixedSize::Enum(enum_) => tuple(
+            enum_
+                .id
+                .repr_fields(db)
+                .into_iter()
+                .map(|(_, typ)| typ)
+                .collect(),
+        ),
+        // Not abi encodable, so public functions can't take them.
+        FixedSize::Vec(_) | FixedSize::Function(_) => "?".into(),
+    }
+}
+
+fn struct_field_types(db: &dyn AnalyzerDb, struct_: StructId) -> Vec<FixedSize> {
+    struct_
+        .fields(db)
+        .values()
+        .filter_map(|field| field.typ(db).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namespace::items::ModuleId;
+    use crate::TestDb;
+
+    #[test]
+    fn selectors() {
+        let src = "
+struct Point:
+  pub x: u256
+  pub y: i8
+
+contract Token:
+  pub fn transfer(to: address, amount: u256) -> bool:
+    return true
+  pub fn move(p: Point, data: Array<u8, 4>, ids: Array<u16, 2>) -> bool:
+    return true
+  #[selector(0x12345678)]
+  pub fn pinned():
+    pass
+";
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "token.fe", src);
+        let functions = module.all_contracts(&db)[0].public_functions(&db);
+
+        let transfer = functions["transfer"];
+        assert_eq!(
+            canonical_signature(&db, transfer),
+            "transfer(address,uint256)"
+        );
+        assert_eq!(transfer.selector(&db).to_string(), "0xa9059cbb");
+        assert_eq!(
+            canonical_signature(&db, functions["move"]),
+            "move((uint256,int8),bytes,uint16[2])"
+        );
+        assert_eq!(functions["pinned"].selector(&db), Selector(0x12345678));
+    }
+
+    #[test]
+    fn selector_from_hex() {
+        assert_eq!(Selector::from_hex("0xa9059cbb"), Some(Selector(0xa9059cbb)));
+        assert_eq!(Selector::from_hex("0xa9059c"), None);
+        assert_eq!(Selector::from_hex("a9059cbb"), None);
+        assert_eq!(Selector::from_hex("0xa9059cbg"), None);
+    }
+}
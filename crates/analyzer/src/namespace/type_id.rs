@@ -0,0 +1,243 @@
+//! Content-based identifiers of types, which can be compared across separate
+//! compilations.
+
+use crate::namespace::items::{ContractId, EnumId, Item, StructId, TypeDef};
+use crate::namespace::types::{FixedSize, Type};
+use crate::AnalyzerDb;
+use fe_common::utils::keccak;
+use std::fmt;
+
+/// An identifier of a type that's derived from its content, rather than from
+/// the order in which the compiler happens to intern its items. It's the same
+/// in every build of the ingot that defines the type, so the artifacts of
+/// separate compilations, e.g. of an ingot and of the ingots that depend on
+/// it, can be compared and linked.
+///
+/// The identifier is the first 16 bytes of the keccak256 hash of the
+/// canonical description of the type (see [`canonical_type_name`]).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct TypeId([u8; 16]);
+
+impl TypeId {
+    pub fn of(db: &dyn AnalyzerDb, typ: &Type) -> Self {
+        let hash = keccak::full_as_bytes(canonical_type_name(db, typ).as_bytes());
+        let mut id = [0; 16];
+        id.copy_from_slice(&hash[..16]);
+        TypeId(id)
+    }
+
+    pub fn of_fixed_size(db: &dyn AnalyzerDb, typ: &FixedSize) -> Self {
+        Self::of(db, &typ.clone().into())
+    }
+}
+
+impl fmt::Display for TypeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        self.0.iter().try_for_each(|byte| write!(f, "{:02x}", byte))
+    }
+}
+
+/// The canonical description of a type, from which its [`TypeId`] is derived.
+///
+/// Structs, enums and contracts are described by their path in their ingot
+/// and their content: the names and types of the fields of a struct (and
+/// whether it's `#[packed]`), the variants of an enum, and the public
+/// functions of a contract. The name of the ingot is left out, so a type has
+/// the same id whether its ingot is compiled on its own, or as a dependency.
+///
+/// e.g. `struct geometry::Point{x:u256,y:u256}`
+pub fn canonical_type_name(db: &dyn AnalyzerDb, typ: &Type) -> String {
+    Describer { db, stack: vec![] }.typ(typ)
+}
+
+struct Describer<'a> {
+    db: &'a dyn AnalyzerDb,
+    /// The structs and contracts that are being described. A type that refers
+    /// back to one of them is described by the path of the item alone.
+    stack: Vec<Item>,
+}
+
+impl Describer<'_> {
+    fn typ(&mut self, typ: &Type) -> String {
+        match typ {
+            Type::Base(base) => base.to_string(),
+            Type::Array(array) => format!("Array<{},{}>", array.inner, array.size),
+            Type::Map(map) => format!("Map<{},{}>", map.key, self.typ(&map.value)),
+            Type::Tuple(tuple) => format!("({})", self.fixed_sizes(tuple.items.iter())),
+            Type::String(string) => format!("String<{}>", string.max_size),
+            Type::Vec(vec) => format!("Vec<{}>", vec.inner),
+            Type::Contract(contract) | Type::SelfContract(contract) => self.contract(contract.id),
+            Type::Struct(struct_) => self.struct_(struct_.id),
+            Type::Enum(enum_) => self.enum_(enum_.id),
+            Type::Function(function) => format!(
+                "fn({})->{}",
+                self.fixed_sizes(function.params.iter()),
+                self.fixed_size(&function.return_type)
+            ),
+        }
+    }
+
+    fn fixed_size(&mut self, typ: &FixedSize) -> String {
+        self.typ(&typ.clone().into())
+    }
+
+    fn fixed_sizes<'b>(&mut self, types: impl Iterator<Item = &'b FixedSize>) -> String {
+        types
+            .map(|typ| self.fixed_size(typ))
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// The description of a field or parameter type that failed to resolve.
+    /// Such types don't make it into compiled artifacts.
+    fn fallible<E>(&mut self, typ: &Result<FixedSize, E>) -> String {
+        match typ {
+            Ok(typ) => self.fixed_size(typ),
+            Err(_) => "?".into(),
+        }
+    }
+
+    fn path(&self, item: Item) -> String {
+        item.path(self.db).join("::")
+    }
+
+    fn struct_(&mut self, struct_: StructId) -> String {
+        let item = Item::Type(TypeDef::Struct(struct_));
+        let mut name = format!("struct {}", self.path(item));
+        let args = struct_.generic_args(self.db);
+        if !args.is_empty() {
+            name = format!("{}<{}>", name, self.fixed_sizes(args.iter()));
+        }
+        if self.stack.contains(&item) {
+            return name;
+        }
+
+        self.stack.push(item);
+        let fields = struct_
+            .fields(self.db)
+            .iter()
+            .map(|(field_name, field)| {
+                format!("{}:{}", field_name, self.fallible(&field.typ(self.db)))
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        self.stack.pop();
+
+        // The attributes that change the storage layout of the struct.
+        let mut attributes = String::new();
+        if struct_.is_packed(self.db) {
+            attributes.push_str("#[packed]");
+        }
+        if struct_.is_repr_declared(self.db) {
+            attributes.push_str("#[repr(declared)]");
+        }
+        format!("{}{}{{{}}}", attributes, name, fields)
+    }
+
+    fn enum_(&mut self, enum_: EnumId) -> String {
+        let variants = enum_
+            .variants(self.db)
+            .iter()
+            .map(|(name, variant)| {
+                if variant.is_unit(self.db) {
+                    name.to_string()
+                } else {
+                    let fields = variant
+                        .fields(self.db)
+                        .iter()
+                        .map(|typ| self.fallible(typ))
+                        .collect::<Vec<_>>();
+                    format!("{}({})", name, fields.join(","))
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(",");
+        format!(
+            "enum {}{{{}}}",
+            self.path(Item::Type(TypeDef::Enum(enum_))),
+            variants
+        )
+    }
+
+    fn contract(&mut self, contract: ContractId) -> String {
+        let item = Item::Type(TypeDef::Contract(contract));
+        let name = format!("contract {}", self.path(item));
+        if self.stack.contains(&item) {
+            return name;
+        }
+
+        self.stack.push(item);
+        let functions = contract
+            .public_functions(self.db)
+            .iter()
+            .map(|(function_name, function)| {
+                let signature = function.signature(self.db);
+                let params = signature
+                    .params
+                    .iter()
+                    .map(|param| self.fallible(&param.typ))
+                    .collect::<Vec<_>>();
+                format!(
+                    "fn {}({})->{}",
+                    function_name,
+                    params.join(","),
+                    self.fallible(&signature.return_type)
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(";");
+        self.stack.pop();
+
+        format!("{}{{{}}}", name, functions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namespace::items::ModuleId;
+    use crate::namespace::types;
+    use crate::TestDb;
+
+    /// The canonical name and id of the named type in a module.
+    fn type_id(src: &str, name: &str) -> (String, TypeId) {
+        let mut db = TestDb::default();
+        let module = ModuleId::new_standalone(&mut db, "shapes.fe", src);
+        let typ = match module.items(&db)[name] {
+            Item::Type(TypeDef::Struct(id)) => Type::Struct(id.typ(&db).as_ref().clone()),
+            Item::Type(TypeDef::Contract(id)) => Type::Contract(types::Contract::from_id(id, &db)),
+            _ => panic!("`{}` isn't a struct or contract", name),
+        };
+        (canonical_type_name(&db, &typ), TypeId::of(&db, &typ))
+    }
+
+    #[test]
+    fn struct_id_depends_on_content() {
+        let (name, id) = type_id("struct Point:\n  pub x: u256\n  pub y: u256\n", "Point");
+        assert_eq!(name, "struct shapes::Point{x:u256,y:u256}");
+        assert_eq!(
+            id.to_string(),
+            keccak::partial(b"struct shapes::Point{x:u256,y:u256}", 16)
+        );
+
+        // Other items don't change the id.
+        let (_, other_id) = type_id(
+            "struct Line:\n  pub a: Point\n\nstruct Point:\n  pub x: u256\n  pub y: u256\n",
+            "Point",
+        );
+        assert_eq!(id, other_id);
+
+        let (_, changed_id) = type_id("struct Point:\n  pub x: u256\n  pub y: u128\n", "Point");
+        assert_ne!(id, changed_id);
+    }
+
+    #[test]
+    fn recursive_contract() {
+        let (name, _) = type_id(
+            "contract Foo:\n  pub fn me(self) -> Foo:\n    return Foo(address(0))\n",
+            "Foo",
+        );
+        assert_eq!(name, "contract shapes::Foo{fn me()->contract shapes::Foo}");
+    }
+}
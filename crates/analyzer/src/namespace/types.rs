@@ -1,9 +1,9 @@
+use crate::context::Constant;
 use crate::errors::{NotFixedSize, TypeError};
-use crate::namespace::items::{Class, ContractId, StructId};
+use crate::namespace::items::{Class, ContractId, EnumId, StructId};
 use crate::AnalyzerDb;
 
 use num_bigint::BigInt;
-use num_traits::ToPrimitive;
 use smol_str::SmolStr;
 use std::fmt;
 use std::str::FromStr;
@@ -39,12 +39,15 @@ pub enum Type {
     Map(Map),
     Tuple(Tuple),
     String(FeString),
+    Vec(FeVec),
     /// An "external" contract. Effectively just a `newtype`d address.
     Contract(Contract),
     /// The type of a contract while it's being executed. Ie. the type
     /// of `self` within a contract function.
     SelfContract(Contract),
     Struct(Struct),
+    Enum(Enum),
+    Function(FunctionType),
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -53,8 +56,11 @@ pub enum FixedSize {
     Array(Array),
     Tuple(Tuple),
     String(FeString),
+    Vec(FeVec),
     Contract(Contract),
     Struct(Struct),
+    Enum(Enum),
+    Function(FunctionType),
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -85,15 +91,67 @@ impl Base {
 #[strum(serialize_all = "snake_case")]
 pub enum Integer {
     U256,
+    U248,
+    U240,
+    U232,
+    U224,
+    U216,
+    U208,
+    U200,
+    U192,
+    U184,
+    U176,
+    U168,
+    U160,
+    U152,
+    U144,
+    U136,
     U128,
+    U120,
+    U112,
+    U104,
+    U96,
+    U88,
+    U80,
+    U72,
     U64,
+    U56,
+    U48,
+    U40,
     U32,
+    U24,
     U16,
     U8,
     I256,
+    I248,
+    I240,
+    I232,
+    I224,
+    I216,
+    I208,
+    I200,
+    I192,
+    I184,
+    I176,
+    I168,
+    I160,
+    I152,
+    I144,
+    I136,
     I128,
+    I120,
+    I112,
+    I104,
+    I96,
+    I88,
+    I80,
+    I72,
     I64,
+    I56,
+    I48,
+    I40,
     I32,
+    I24,
     I16,
     I8,
 }
@@ -106,6 +164,13 @@ pub struct Array {
     pub inner: Base,
 }
 
+/// A dynamically sized array in memory. A value of this type is a pointer to
+/// the length, the capacity, and the location of the items of the array.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FeVec {
+    pub inner: Base,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Map {
     pub key: Base,
@@ -122,6 +187,8 @@ pub struct Struct {
     pub name: SmolStr,
     pub id: StructId,
     pub field_count: usize,
+    /// The types that the generic parameters of the struct are bound to.
+    pub generic_args: Vec<FixedSize>,
 }
 impl Struct {
     pub fn from_id(id: StructId, db: &dyn AnalyzerDb) -> Self {
@@ -129,6 +196,21 @@ impl Struct {
             name: id.name(db),
             id,
             field_count: id.fields(db).len(),
+            generic_args: id.generic_args(db),
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct Enum {
+    pub name: SmolStr,
+    pub id: EnumId,
+}
+impl Enum {
+    pub fn from_id(id: EnumId, db: &dyn AnalyzerDb) -> Self {
+        Self {
+            name: id.name(db),
+            id,
         }
     }
 }
@@ -147,6 +229,15 @@ impl Contract {
     }
 }
 
+/// The type of a reference to a function, e.g. `fn(u256, u256) -> u256`. A
+/// value of this type identifies one of the functions that are compiled into
+/// the contract, and is called like the function itself.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    pub params: Vec<FixedSize>,
+    pub return_type: Box<FixedSize>,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
 pub struct FeString {
     pub max_size: usize,
@@ -161,13 +252,37 @@ pub struct FunctionSignature {
 
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
 pub enum SelfDecl {
+    /// `self`, whose fields can be read but not modified.
+    Immutable,
+    /// `mut self`
     Mutable,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct FunctionParam {
     pub name: SmolStr,
+    /// `true` if the parameter is declared `mut`, which allows the function
+    /// to modify the value that's passed in, e.g. the elements of an array.
+    pub is_mut: bool,
     pub typ: Result<FixedSize, TypeError>,
+    /// The value that's passed to the parameter if a call leaves it out. If
+    /// the default value in the declaration is invalid, the parameter can
+    /// still be left out; the error is reported at the declaration.
+    pub default: Option<Result<Constant, TypeError>>,
+}
+
+/// Whether a function reads or modifies the state of the chain, e.g. the
+/// storage of the contract. The variants are ordered from the most to the
+/// least restrictive.
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub enum StateMutability {
+    /// Neither reads nor modifies the state.
+    Pure,
+    /// Reads the state, but doesn't modify it.
+    View,
+    /// Might modify the state, e.g. by writing to storage or emitting an
+    /// event.
+    Mutable,
 }
 
 #[derive(
@@ -177,6 +292,7 @@ pub enum GenericType {
     Array,
     String,
     Map,
+    Vec,
 }
 
 impl GenericType {
@@ -209,6 +325,10 @@ impl GenericType {
                     kind: GenericParamKind::Int,
                 },
             ],
+            GenericType::Vec => vec![GenericParam {
+                name: "element type".into(),
+                kind: GenericParamKind::PrimitiveType,
+            }],
         }
     }
 
@@ -235,6 +355,12 @@ impl GenericType {
                 })),
                 _ => None,
             },
+            GenericType::Vec => match args {
+                [GenericArg::Type(element)] => Some(Type::Vec(FeVec {
+                    inner: element.as_primitive()?,
+                })),
+                _ => None,
+            },
         }
     }
 }
@@ -250,7 +376,7 @@ pub enum GenericParamKind {
 
     // Ideally these would be represented as trait constraints.
     PrimitiveType,
-    // FixedSizeType, // not needed yet
+    FixedSizeType,
     AnyType,
 }
 
@@ -263,31 +389,20 @@ pub enum GenericArg {
 impl Integer {
     /// Returns `true` if the integer is signed, otherwise `false`
     pub fn is_signed(&self) -> bool {
-        matches!(
-            self,
-            Integer::I256
-                | Integer::I128
-                | Integer::I64
-                | Integer::I32
-                | Integer::I16
-                | Integer::I8
-        )
+        self.as_ref().starts_with('i')
     }
 
+    /// Returns size of integer type in bytes.
     pub fn size(&self) -> usize {
-        match self {
-            Integer::U256 | Integer::I256 => 32,
-            Integer::U128 | Integer::I128 => 16,
-            Integer::U64 | Integer::I64 => 8,
-            Integer::U32 | Integer::I32 => 4,
-            Integer::U16 | Integer::I16 => 2,
-            Integer::U8 | Integer::I8 => 1,
-        }
+        self.bits() / 8
     }
 
     /// Returns size of integer type in bits.
     pub fn bits(&self) -> usize {
-        self.size() * 8
+        // The width is encoded in the type name, e.g. `u24` or `i136`.
+        self.as_ref()[1..]
+            .parse()
+            .expect("integer type name should end with its width")
     }
 
     /// Returns `true` if the integer is at least the same size (or larger) than
@@ -298,55 +413,24 @@ impl Integer {
 
     /// Returns `true` if `num` represents a number that fits the type
     pub fn fits(&self, num: BigInt) -> bool {
-        match self {
-            Integer::U8 => num.to_u8().is_some(),
-            Integer::U16 => num.to_u16().is_some(),
-            Integer::U32 => num.to_u32().is_some(),
-            Integer::U64 => num.to_u64().is_some(),
-            Integer::U128 => num.to_u128().is_some(),
-            Integer::I8 => num.to_i8().is_some(),
-            Integer::I16 => num.to_i16().is_some(),
-            Integer::I32 => num.to_i32().is_some(),
-            Integer::I64 => num.to_i64().is_some(),
-            Integer::I128 => num.to_i128().is_some(),
-            Integer::U256 => num >= u256_min() && num <= u256_max(),
-            Integer::I256 => num >= i256_min() && num <= i256_max(),
-        }
+        num >= self.min_value() && num <= self.max_value()
     }
 
     /// Returns max value of the integer type.
     pub fn max_value(&self) -> BigInt {
-        match self {
-            Integer::U256 => u256_max(),
-            Integer::U128 => u128::MAX.into(),
-            Integer::U64 => u64::MAX.into(),
-            Integer::U32 => u32::MAX.into(),
-            Integer::U16 => u16::MAX.into(),
-            Integer::U8 => u8::MAX.into(),
-            Integer::I256 => i256_max(),
-            Integer::I128 => i128::MAX.into(),
-            Integer::I64 => i64::MAX.into(),
-            Integer::I32 => i32::MAX.into(),
-            Integer::I16 => i16::MAX.into(),
-            Integer::I8 => i8::MAX.into(),
+        if self.is_signed() {
+            BigInt::from(2).pow(self.bits() as u32 - 1) - 1
+        } else {
+            BigInt::from(2).pow(self.bits() as u32) - 1
         }
     }
 
     /// Returns min value of the integer type.
     pub fn min_value(&self) -> BigInt {
-        match self {
-            Integer::U256 => u256_min(),
-            Integer::U128 => u128::MIN.into(),
-            Integer::U64 => u64::MIN.into(),
-            Integer::U32 => u32::MIN.into(),
-            Integer::U16 => u16::MIN.into(),
-            Integer::U8 => u8::MIN.into(),
-            Integer::I256 => i256_min(),
-            Integer::I128 => i128::MIN.into(),
-            Integer::I64 => i64::MIN.into(),
-            Integer::I32 => i32::MIN.into(),
-            Integer::I16 => i16::MIN.into(),
-            Integer::I8 => i8::MIN.into(),
+        if self.is_signed() {
+            -BigInt::from(2).pow(self.bits() as u32 - 1)
+        } else {
+            BigInt::from(0)
         }
     }
 }
@@ -355,6 +439,8 @@ impl Integer {
 pub struct Event {
     pub name: SmolStr,
     pub fields: Vec<EventField>,
+    /// `true` if the hash of the event signature isn't logged as a topic.
+    pub anonymous: bool,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
@@ -379,6 +465,18 @@ impl FunctionSignature {
     pub fn expect_return_type(&self) -> FixedSize {
         self.return_type.clone().expect("fn return type error")
     }
+
+    /// The type of a reference to the function.
+    pub fn function_type(&self) -> Result<FunctionType, TypeError> {
+        Ok(FunctionType {
+            params: self
+                .params
+                .iter()
+                .map(|param| param.typ.clone())
+                .collect::<Result<_, _>>()?,
+            return_type: Box::new(self.return_type.clone()?),
+        })
+    }
 }
 
 impl Type {
@@ -389,7 +487,10 @@ impl Type {
             Type::Map(inner) => inner.to_string().into(),
             Type::Tuple(inner) => inner.to_string().into(),
             Type::String(inner) => inner.to_string().into(),
+            Type::Vec(inner) => inner.to_string().into(),
             Type::Struct(inner) => inner.name.clone(),
+            Type::Enum(inner) => inner.name.clone(),
+            Type::Function(inner) => inner.to_string().into(),
             Type::Contract(inner) | Type::SelfContract(inner) => inner.name.clone(),
         }
     }
@@ -428,6 +529,10 @@ impl Type {
                 0 => Some(Type::Base(array.inner)),
                 _ => None,
             },
+            Type::Vec(vec) => match idx {
+                0 => Some(Type::Base(vec.inner)),
+                _ => None,
+            },
             _ => None,
         }
     }
@@ -520,8 +625,11 @@ impl From<FixedSize> for Type {
             FixedSize::Base(base) => Type::Base(base),
             FixedSize::Tuple(tuple) => Type::Tuple(tuple),
             FixedSize::String(string) => Type::String(string),
+            FixedSize::Vec(vec) => Type::Vec(vec),
             FixedSize::Contract(contract) => Type::Contract(contract),
             FixedSize::Struct(val) => Type::Struct(val),
+            FixedSize::Enum(val) => Type::Enum(val),
+            FixedSize::Function(val) => Type::Function(val),
         }
     }
 }
@@ -542,6 +650,22 @@ impl FixedSize {
         matches!(self, FixedSize::Base(_))
     }
 
+    /// Returns true if the type is a `Vec`, or a tuple or struct that holds a
+    /// `Vec`. Values of these types can only be kept in memory: they can't be
+    /// stored, logged or abi encoded.
+    pub fn contains_vec(&self, db: &dyn AnalyzerDb) -> bool {
+        match self {
+            FixedSize::Vec(_) => true,
+            FixedSize::Tuple(tuple) => tuple.items.iter().any(|item| item.contains_vec(db)),
+            FixedSize::Struct(struct_) => struct_
+                .id
+                .fields(db)
+                .values()
+                .any(|field| matches!(field.typ(db), Ok(typ) if typ.contains_vec(db))),
+            _ => false,
+        }
+    }
+
     /// Creates an instance of bool.
     pub fn bool() -> Self {
         FixedSize::Base(Base::Bool)
@@ -575,8 +699,11 @@ impl PartialEq<Type> for FixedSize {
             (FixedSize::Base(in1), Type::Base(in2)) => in1 == in2,
             (FixedSize::Tuple(in1), Type::Tuple(in2)) => in1 == in2,
             (FixedSize::String(in1), Type::String(in2)) => in1 == in2,
+            (FixedSize::Vec(in1), Type::Vec(in2)) => in1 == in2,
             (FixedSize::Contract(in1), Type::Contract(in2)) => in1 == in2,
             (FixedSize::Struct(in1), Type::Struct(in2)) => in1 == in2,
+            (FixedSize::Enum(in1), Type::Enum(in2)) => in1 == in2,
+            (FixedSize::Function(in1), Type::Function(in2)) => in1 == in2,
             _ => false,
         }
     }
@@ -603,7 +730,10 @@ impl TryFrom<Type> for FixedSize {
             Type::Base(base) => Ok(FixedSize::Base(base)),
             Type::Tuple(tuple) => Ok(FixedSize::Tuple(tuple)),
             Type::String(string) => Ok(FixedSize::String(string)),
+            Type::Vec(vec) => Ok(FixedSize::Vec(vec)),
             Type::Struct(val) => Ok(FixedSize::Struct(val)),
+            Type::Enum(val) => Ok(FixedSize::Enum(val)),
+            Type::Function(val) => Ok(FixedSize::Function(val)),
             Type::Contract(contract) => Ok(FixedSize::Contract(contract)),
             Type::Map(_) | Type::SelfContract(_) => Err(NotFixedSize),
         }
@@ -623,8 +753,11 @@ impl SafeNames for FixedSize {
             FixedSize::Base(base) => base.lower_snake(),
             FixedSize::Tuple(tuple) => tuple.lower_snake(),
             FixedSize::String(string) => string.lower_snake(),
+            FixedSize::Vec(vec) => vec.lower_snake(),
             FixedSize::Contract(contract) => contract.lower_snake(),
             FixedSize::Struct(val) => val.lower_snake(),
+            FixedSize::Enum(val) => val.lower_snake(),
+            FixedSize::Function(val) => val.lower_snake(),
         }
     }
 }
@@ -632,18 +765,7 @@ impl SafeNames for FixedSize {
 impl SafeNames for Base {
     fn lower_snake(&self) -> String {
         match self {
-            Base::Numeric(Integer::U256) => "u256".to_string(),
-            Base::Numeric(Integer::U128) => "u128".to_string(),
-            Base::Numeric(Integer::U64) => "u64".to_string(),
-            Base::Numeric(Integer::U32) => "u32".to_string(),
-            Base::Numeric(Integer::U16) => "u16".to_string(),
-            Base::Numeric(Integer::U8) => "u8".to_string(),
-            Base::Numeric(Integer::I256) => "i256".to_string(),
-            Base::Numeric(Integer::I128) => "i128".to_string(),
-            Base::Numeric(Integer::I64) => "i64".to_string(),
-            Base::Numeric(Integer::I32) => "i32".to_string(),
-            Base::Numeric(Integer::I16) => "i16".to_string(),
-            Base::Numeric(Integer::I8) => "i8".to_string(),
+            Base::Numeric(integer) => integer.as_ref().to_string(),
             Base::Address => "address".to_string(),
             Base::Bool => "bool".to_string(),
             Base::Unit => "unit".to_string(),
@@ -659,7 +781,23 @@ impl SafeNames for Array {
 
 impl SafeNames for Struct {
     fn lower_snake(&self) -> String {
-        format!("struct_{}", self.name)
+        if self.generic_args.is_empty() {
+            format!("struct_{}", self.name)
+        } else {
+            // As with tuples, the trailing `_` denotes the end of the generic args.
+            let arg_names = self
+                .generic_args
+                .iter()
+                .map(SafeNames::lower_snake)
+                .collect::<Vec<String>>();
+            format!("struct_{}${}_", self.name, arg_names.join("_"))
+        }
+    }
+}
+
+impl SafeNames for Enum {
+    fn lower_snake(&self) -> String {
+        format!("enum_{}", self.name)
     }
 }
 
@@ -693,6 +831,29 @@ impl SafeNames for FeString {
     }
 }
 
+impl SafeNames for FeVec {
+    fn lower_snake(&self) -> String {
+        format!("vec_{}", self.inner.lower_snake())
+    }
+}
+
+impl SafeNames for FunctionType {
+    fn lower_snake(&self) -> String {
+        let param_names = self
+            .params
+            .iter()
+            .map(SafeNames::lower_snake)
+            .collect::<Vec<String>>();
+
+        // As with tuples, the trailing `_` denotes the end of the type.
+        format!(
+            "fn_{}_to_{}_",
+            param_names.join("_"),
+            self.return_type.lower_snake()
+        )
+    }
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -701,9 +862,12 @@ impl fmt::Display for Type {
             Type::Map(inner) => inner.fmt(f),
             Type::Tuple(inner) => inner.fmt(f),
             Type::String(inner) => inner.fmt(f),
+            Type::Vec(inner) => inner.fmt(f),
             Type::Contract(inner) => inner.fmt(f),
             Type::SelfContract(inner) => inner.fmt(f),
             Type::Struct(inner) => inner.fmt(f),
+            Type::Enum(inner) => inner.fmt(f),
+            Type::Function(inner) => inner.fmt(f),
         }
     }
 }
@@ -715,8 +879,11 @@ impl fmt::Display for FixedSize {
             FixedSize::Array(inner) => inner.fmt(f),
             FixedSize::Tuple(inner) => inner.fmt(f),
             FixedSize::String(inner) => inner.fmt(f),
+            FixedSize::Vec(inner) => inner.fmt(f),
             FixedSize::Contract(inner) => inner.fmt(f),
             FixedSize::Struct(inner) => inner.fmt(f),
+            FixedSize::Enum(inner) => inner.fmt(f),
+            FixedSize::Function(inner) => inner.fmt(f),
         }
     }
 }
@@ -735,21 +902,7 @@ impl fmt::Display for Base {
 
 impl fmt::Display for Integer {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let name = match self {
-            Integer::U256 => "u256",
-            Integer::U128 => "u128",
-            Integer::U64 => "u64",
-            Integer::U32 => "u32",
-            Integer::U16 => "u16",
-            Integer::U8 => "u8",
-            Integer::I256 => "i256",
-            Integer::I128 => "i128",
-            Integer::I64 => "i64",
-            Integer::I32 => "i32",
-            Integer::I16 => "i16",
-            Integer::I8 => "i8",
-        };
-        write!(f, "{}", name)
+        write!(f, "{}", self.as_ref())
     }
 }
 
@@ -783,6 +936,28 @@ impl fmt::Display for FeString {
     }
 }
 
+impl fmt::Display for FeVec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Vec<{}>", self.inner)
+    }
+}
+
+impl fmt::Display for FunctionType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "fn(")?;
+        let mut delim = "";
+        for param in &self.params {
+            write!(f, "{}{}", delim, param)?;
+            delim = ", ";
+        }
+        write!(f, ")")?;
+        if !self.return_type.is_unit() {
+            write!(f, " -> {}", self.return_type)?;
+        }
+        Ok(())
+    }
+}
+
 impl fmt::Display for Contract {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.name)
@@ -798,7 +973,17 @@ impl fmt::Debug for Contract {
 
 impl fmt::Display for Struct {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.name)
+        write!(f, "{}", self.name)?;
+        if !self.generic_args.is_empty() {
+            write!(f, "<")?;
+            let mut delim = "";
+            for arg in &self.generic_args {
+                write!(f, "{}{}", delim, arg)?;
+                delim = ", ";
+            }
+            write!(f, ">")?;
+        }
+        Ok(())
     }
 }
 impl fmt::Debug for Struct {
@@ -810,6 +995,17 @@ impl fmt::Debug for Struct {
     }
 }
 
+impl fmt::Display for Enum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+impl fmt::Debug for Enum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Enum").field("name", &self.name).finish()
+    }
+}
+
 impl FromStr for Base {
     type Err = strum::ParseError;
 
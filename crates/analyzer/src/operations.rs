@@ -1,5 +1,5 @@
 use crate::errors::{BinaryOperationError, IndexingError};
-use crate::namespace::types::{Array, Base, Map, Type, U256};
+use crate::namespace::types::{Array, Base, FeVec, Map, Type, U256};
 
 use fe_parser::ast as fe;
 
@@ -10,12 +10,15 @@ pub fn index(value: Type, index: Type) -> Result<Type, IndexingError> {
     match value {
         Type::Array(array) => index_array(array, index),
         Type::Map(map) => index_map(map, index),
+        Type::Vec(vec) => index_vec(vec, index),
         Type::Base(_)
         | Type::Tuple(_)
         | Type::String(_)
         | Type::Contract(_)
         | Type::SelfContract(_)
-        | Type::Struct(_) => Err(IndexingError::NotSubscriptable),
+        | Type::Struct(_)
+        | Type::Enum(_)
+        | Type::Function(_) => Err(IndexingError::NotSubscriptable),
     }
 }
 
@@ -27,6 +30,14 @@ fn index_array(array: Array, index: Type) -> Result<Type, IndexingError> {
     Ok(Type::Base(array.inner))
 }
 
+fn index_vec(vec: FeVec, index: Type) -> Result<Type, IndexingError> {
+    if index != Type::Base(U256) {
+        return Err(IndexingError::WrongIndexType);
+    }
+
+    Ok(Type::Base(vec.inner))
+}
+
 fn index_map(map: Map, index: Type) -> Result<Type, IndexingError> {
     if index != Type::Base(map.key) {
         return Err(IndexingError::WrongIndexType);
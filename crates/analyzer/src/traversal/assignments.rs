@@ -1,10 +1,14 @@
+use crate::builtins::bin_operator_method;
 use crate::context::{AnalyzerContext, Location, NamedThing};
 use crate::errors::FatalError;
 use crate::namespace::scopes::BlockScope;
+use crate::namespace::types::Type;
 use crate::operations;
 use crate::traversal::expressions;
 use crate::traversal::utils::add_bin_operations_errors;
 use fe_common::diagnostics::Label;
+use fe_common::utils::humanize::pluralize_conditionally;
+use fe_common::Span;
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
 
@@ -13,6 +17,11 @@ use fe_parser::node::Node;
 /// e.g. `foo[42] = "bar"`, `self.foo[42] = "bar"`, `foo = 42`
 pub fn assign(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::Assign { target, value } = &stmt.kind {
+        if let fe::Expr::Tuple { .. } = &target.kind {
+            let value_attributes = expressions::assignable_expr(scope, value, None)?;
+            return tuple_assign_target(scope, target, &value_attributes.typ, value.span);
+        }
+
         let target_attributes = expressions::expr(scope, target, None)?;
 
         let value_attributes = expressions::expr(scope, value, Some(&target_attributes.typ))?;
@@ -62,11 +71,69 @@ pub fn assign(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), F
     unreachable!()
 }
 
+/// Checks an item of a tuple destructuring assignment, eg. `(x, y) = pair`,
+/// where `typ` is the type of the value that's assigned to the item.
+fn tuple_assign_target(
+    scope: &mut BlockScope,
+    target: &Node<fe::Expr>,
+    typ: &Type,
+    value_span: Span,
+) -> Result<(), FatalError> {
+    if let fe::Expr::Tuple { elts } = &target.kind {
+        return match typ {
+            Type::Tuple(tuple) if tuple.items.len() == elts.len() => {
+                for (elt, item_type) in elts.iter().zip(tuple.items.iter()) {
+                    tuple_assign_target(scope, elt, &item_type.clone().into(), value_span)?;
+                }
+                Ok(())
+            }
+            _ => Err(FatalError::new(scope.fancy_error(
+                "mismatched types",
+                vec![
+                    Label::primary(
+                        target.span,
+                        format!(
+                            "this tuple has {} {}",
+                            elts.len(),
+                            pluralize_conditionally("item", elts.len())
+                        ),
+                    ),
+                    Label::secondary(value_span, format!("this value has type `{}`", typ)),
+                ],
+                vec![],
+            ))),
+        };
+    }
+
+    let target_attributes = expressions::expr(scope, target, Some(typ))?;
+    check_assign_target(scope, target)?;
+    if target_attributes.typ != *typ {
+        scope.fancy_error(
+            "mismatched types",
+            vec![
+                Label::primary(
+                    target.span,
+                    format!("this variable has type `{}`", target_attributes.typ),
+                ),
+                Label::secondary(
+                    value_span,
+                    format!("this value has incompatible type `{}`", typ),
+                ),
+            ],
+            vec![],
+        );
+    }
+    Ok(())
+}
+
 fn check_assign_target(scope: &mut BlockScope, expr: &Node<fe::Expr>) -> Result<(), FatalError> {
     use fe::Expr::*;
 
     match &expr.kind {
-        Attribute { .. } | Subscript { .. } => Ok(()),
+        Attribute { value, .. } | Subscript { value, .. } => {
+            expressions::check_mutable(scope, value, expr.span);
+            Ok(())
+        }
         Tuple { elts } => {
             for elt in elts {
                 check_assign_target(scope, elt)?;
@@ -103,6 +170,28 @@ pub fn aug_assign(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(
     if let fe::FuncStmt::AugAssign { target, op, value } = &stmt.kind {
         check_assign_target(scope, target)?;
         let target_attributes = expressions::expr(scope, target, None)?;
+        if let Type::Struct(struct_) = &target_attributes.typ {
+            let typ = target_attributes.typ.clone();
+            let attributes = expressions::expr_operator_overload(
+                scope,
+                struct_.id,
+                target,
+                target_attributes,
+                format!("{}=", op.kind),
+                op.span,
+                bin_operator_method(op.kind),
+                value,
+            )?;
+            if attributes.typ != typ {
+                scope.type_error(
+                    &format!("`{}=` can't be applied to type `{}`", op.kind, typ),
+                    stmt.span,
+                    &typ,
+                    &attributes.typ,
+                );
+            }
+            return Ok(());
+        }
         let value_attributes = expressions::expr(scope, value, Some(&target_attributes.typ))?;
 
         if let Err(err) = operations::bin(&target_attributes.typ, &op.kind, &value_attributes.typ) {
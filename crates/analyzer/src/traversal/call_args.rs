@@ -1,7 +1,7 @@
-use crate::context::{AnalyzerContext, DiagnosticVoucher};
+use crate::context::{AnalyzerContext, DiagnosticVoucher, ExpressionAttributes, Location};
 use crate::errors::{FatalError, TypeError};
 use crate::namespace::types::{EventField, FixedSize, FunctionParam};
-use crate::traversal::expressions::assignable_expr;
+use crate::traversal::expressions::{assignable_expr, check_mutable};
 use fe_common::{diagnostics::Label, utils::humanize::pluralize_conditionally};
 use fe_common::{Span, Spanned};
 use fe_parser::ast as fe;
@@ -11,6 +11,14 @@ use smol_str::SmolStr;
 pub trait LabeledParameter {
     fn label(&self) -> Option<&str>;
     fn typ(&self) -> Result<FixedSize, TypeError>;
+    /// `true` if the argument may be modified in place.
+    fn is_mut(&self) -> bool {
+        false
+    }
+    /// `true` if the argument can be left out.
+    fn has_default(&self) -> bool {
+        false
+    }
 }
 
 impl LabeledParameter for FunctionParam {
@@ -20,6 +28,12 @@ impl LabeledParameter for FunctionParam {
     fn typ(&self) -> Result<FixedSize, TypeError> {
         self.typ.clone()
     }
+    fn is_mut(&self) -> bool {
+        self.is_mut
+    }
+    fn has_default(&self) -> bool {
+        self.default.is_some()
+    }
 }
 
 impl LabeledParameter for EventField {
@@ -40,6 +54,16 @@ impl LabeledParameter for EventField {
 //     }
 // }
 
+// The parameters of a function type are unnamed.
+impl LabeledParameter for FixedSize {
+    fn label(&self) -> Option<&str> {
+        None
+    }
+    fn typ(&self) -> Result<FixedSize, TypeError> {
+        Ok(self.clone())
+    }
+}
+
 impl LabeledParameter for (SmolStr, Result<FixedSize, TypeError>) {
     fn label(&self) -> Option<&str> {
         Some(&self.0)
@@ -55,14 +79,161 @@ pub fn validate_named_args(
     name_span: Span,
     args: &Node<Vec<Node<fe::CallArg>>>,
     params: &[impl LabeledParameter],
-    label_policy: LabelPolicy,
 ) -> Result<(), FatalError> {
     validate_arg_count(context, name, name_span, args, params.len(), "argument");
-    validate_arg_labels(context, args, params, label_policy);
-    validate_arg_types(context, name, args, params)?;
+    validate_arg_labels(context, args, params);
+    validate_arg_types(context, name, args, params, 0..args.kind.len())?;
+    Ok(())
+}
+
+/// Validates the arguments of a function call. Unlike the arguments of a
+/// struct constructor or an event, labeled function arguments can be given
+/// in any order, and arguments with a default value can be left out.
+pub fn validate_fn_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    name_span: Span,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+) -> Result<(), FatalError> {
+    let param_indices = validate_fn_arg_count(context, name, name_span, args, params);
+    warn_unlabeled_same_typed_args(context, name, args, params, &param_indices);
+    validate_arg_types(context, name, args, params, param_indices)?;
     Ok(())
 }
 
+/// Validates the arguments of a call like [`validate_fn_args`], where some of
+/// the arguments have already been checked without an expected type.
+/// `arg_attrs` holds the attributes of those arguments, and `None` for the
+/// arguments that are checked here.
+pub fn validate_checked_fn_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    name_span: Span,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+    arg_attrs: Vec<Option<ExpressionAttributes>>,
+) -> Result<(), FatalError> {
+    let param_indices = validate_fn_arg_count(context, name, name_span, args, params);
+    warn_unlabeled_same_typed_args(context, name, args, params, &param_indices);
+    check_arg_types(context, name, args, params, param_indices, arg_attrs)
+}
+
+/// Checks the number of arguments of a function call, and returns the index
+/// of the parameter that each argument is passed to. See
+/// [`fn_arg_param_indices`].
+pub fn validate_fn_arg_count(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    name_span: Span,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+) -> Vec<usize> {
+    let has_defaults = params.iter().any(LabeledParameter::has_default);
+    if !has_defaults || args.kind.len() > params.len() {
+        validate_arg_count(context, name, name_span, args, params.len(), "argument");
+    }
+    let param_indices = fn_arg_param_indices(context, args, params);
+    if has_defaults && args.kind.len() <= params.len() {
+        validate_missing_args(context, name, args, params, &param_indices);
+    }
+    param_indices
+}
+
+/// Reports the parameters without a default value that a function call
+/// doesn't provide an argument for.
+fn validate_missing_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+    param_indices: &[usize],
+) {
+    let missing = params
+        .iter()
+        .enumerate()
+        .filter(|(index, param)| !param.has_default() && !param_indices.contains(index))
+        .map(|(_, param)| format!("`{}`", param.label().unwrap_or_default()))
+        .collect::<Vec<_>>();
+    if !missing.is_empty() {
+        context.fancy_error(
+            &format!(
+                "missing {} {} in call to `{}`",
+                pluralize_conditionally("argument", missing.len()),
+                missing.join(", "),
+                name
+            ),
+            vec![Label::primary(
+                args.span,
+                format!(
+                    "{} {} missing",
+                    missing.join(", "),
+                    pluralize_conditionally(("is", "are"), missing.len())
+                ),
+            )],
+            vec![
+                "Note: only the arguments of parameters with a default value can be left out."
+                    .into(),
+            ],
+        );
+    }
+}
+
+/// Function calls whose arguments are passed to at least this many parameters
+/// of the same type should label those arguments, as they're easy to mix up.
+const MIN_SAME_TYPED_PARAMS: usize = 3;
+
+/// Warns about unlabeled arguments that are passed to one of
+/// [`MIN_SAME_TYPED_PARAMS`] or more parameters of the same type. Arguments
+/// that are variables with the same name as the parameter don't need a label.
+fn warn_unlabeled_same_typed_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+    param_indices: &[usize],
+) {
+    let mut labels = vec![];
+    let mut same_typed: Option<(FixedSize, usize)> = None;
+    for (arg, index) in args.kind.iter().zip(param_indices) {
+        let (label, typ) = match params.get(*index) {
+            Some(param) => match (param.label(), param.typ()) {
+                (Some(label), Ok(typ)) => (label, typ),
+                _ => continue,
+            },
+            None => continue,
+        };
+        let arg_val = &arg.kind.value;
+        if arg.kind.label.is_some()
+            || matches!(&arg_val.kind, fe::Expr::Name(var_name) if var_name == label)
+        {
+            continue;
+        }
+        let count = params
+            .iter()
+            .filter(|param| matches!(param.typ(), Ok(other) if other == typ))
+            .count();
+        if count >= MIN_SAME_TYPED_PARAMS {
+            labels.push(Label::primary(
+                Span::new(arg_val.span.file_id, arg_val.span.start, arg_val.span.start),
+                format!("add `{}:` here", label),
+            ));
+            same_typed.get_or_insert((typ, count));
+        }
+    }
+
+    if let Some((typ, count)) = same_typed {
+        context.warning(
+            &format!("unlabeled arguments in call to `{}`", name),
+            labels,
+            vec![format!(
+                "Note: `{}` has {} parameters of type `{}`, so its arguments should be labeled to make it clear which is which.",
+                name, count, typ
+            )],
+        );
+    }
+}
+
 pub fn validate_arg_count(
     context: &mut dyn AnalyzerContext,
     name: &str,
@@ -111,21 +282,20 @@ pub fn validate_arg_count(
     }
 }
 
-pub enum LabelPolicy {
-    AllowAnyUnlabeled,
-    AllowUnlabeledIfNameEqual,
-}
-
+/// Validates the labels of arguments that are provided in order. A label can
+/// be left out if the argument is a variable with the same name as the
+/// parameter, or if no other parameter has the same type, so that the
+/// argument can't be mistaken for another one.
 pub fn validate_arg_labels(
     context: &mut dyn AnalyzerContext,
     args: &Node<Vec<Node<fe::CallArg>>>,
     params: &[impl LabeledParameter],
-    label_policy: LabelPolicy,
 ) {
-    for (expected_label, arg) in params
+    for (index, (expected_label, arg)) in params
         .iter()
         .map(LabeledParameter::label)
         .zip(args.kind.iter())
+        .enumerate()
     {
         let arg_val = &arg.kind.value;
         match (expected_label, &arg.kind.label) {
@@ -152,17 +322,23 @@ pub fn validate_arg_labels(
             (Some(expected_label), None) => match &arg_val.kind {
                 fe::Expr::Name(var_name) if var_name == expected_label => {}
                 _ => {
-                    if let LabelPolicy::AllowUnlabeledIfNameEqual = label_policy {
+                    if let Some(other) = same_typed_param(params, index) {
                         context.fancy_error(
                             "missing argument label",
                             vec![Label::primary(
                                 Span::new(arg_val.span.file_id, arg_val.span.start, arg_val.span.start),
                                 format!("add `{}:` here", expected_label),
                             )],
-                            vec![format!(
-                                "Note: this label is optional if the argument is a variable named `{}`.",
-                                expected_label
-                            )],
+                            vec![
+                                format!(
+                                    "Note: `{}` has the same type as `{}`, so the argument must be labeled.",
+                                    expected_label, other
+                                ),
+                                format!(
+                                    "Note: this label is optional if the argument is a variable named `{}`.",
+                                    expected_label
+                                ),
+                            ],
                         );
                     }
                 }
@@ -179,16 +355,249 @@ pub fn validate_arg_labels(
     }
 }
 
+/// Returns the label of another parameter with the same type as the
+/// parameter at `index`, if there is one.
+fn same_typed_param<P: LabeledParameter>(params: &[P], index: usize) -> Option<&str> {
+    let typ = params[index].typ().ok()?;
+    params
+        .iter()
+        .enumerate()
+        .find(|(other, param)| {
+            *other != index && matches!(param.typ(), Ok(other_typ) if other_typ == typ)
+        })
+        .and_then(|(_, param)| param.label())
+}
+
+/// Returns the index of the parameter that each argument of a function call
+/// is passed to. An unlabeled argument is passed to the parameter at its
+/// position, and a labeled argument to the parameter with the same name.
+pub fn fn_arg_param_indices(
+    context: &mut dyn AnalyzerContext,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+) -> Vec<usize> {
+    let mut indices = Vec::with_capacity(args.kind.len());
+    let mut reordered_label: Option<&Node<SmolStr>> = None;
+    for (position, arg) in args.kind.iter().enumerate() {
+        let index = match &arg.kind.label {
+            Some(label) => match param_index(params, &label.kind) {
+                Some(index) => {
+                    if index != position && reordered_label.is_none() {
+                        reordered_label = Some(label);
+                    }
+                    index
+                }
+                // The type of an argument with an unknown label is checked
+                // against the parameter at its position.
+                None => {
+                    if let Some(expected_label) =
+                        params.get(position).and_then(LabeledParameter::label)
+                    {
+                        context.error(
+                            "argument label mismatch",
+                            label.span,
+                            &format!("expected `{}`", expected_label),
+                        );
+                    }
+                    indices.push(position);
+                    continue;
+                }
+            },
+            None => {
+                if let Some(reordered_label) = reordered_label {
+                    context.fancy_error(
+                        "missing argument label",
+                        vec![
+                            Label::primary(arg.kind.value.span, "this argument must be labeled"),
+                            Label::secondary(
+                                reordered_label.span,
+                                "because this argument is out of order",
+                            ),
+                        ],
+                        vec!["Note: unlabeled arguments must be provided in order, before any argument that is out of order.".into()],
+                    );
+                    indices.push(position);
+                    continue;
+                }
+                position
+            }
+        };
+
+        if let Some(previous) = indices.iter().position(|prev| *prev == index) {
+            if let Some(label) = params.get(index).and_then(LabeledParameter::label) {
+                context.fancy_error(
+                    &format!("argument `{}` is provided more than once", label),
+                    vec![
+                        Label::primary(arg.span, format!("`{}` is provided again here", label)),
+                        Label::secondary(args.kind[previous].span, "first provided here"),
+                    ],
+                    vec![],
+                );
+            }
+        }
+        indices.push(index);
+    }
+    indices
+}
+
+/// Returns the index of the parameter that each argument of a function call
+/// is passed to, like [`fn_arg_param_indices`], or `None` if the arguments
+/// can't be passed to the parameters: if a label doesn't name a parameter, if
+/// a parameter is given more than one argument, or if a parameter without a
+/// default value isn't given one. Nothing is reported.
+pub fn fn_args_fit_params(
+    args: &[Node<fe::CallArg>],
+    params: &[impl LabeledParameter],
+) -> Option<Vec<usize>> {
+    let mut indices = Vec::with_capacity(args.len());
+    for (position, arg) in args.iter().enumerate() {
+        let index = match &arg.kind.label {
+            Some(label) => param_index(params, &label.kind)?,
+            None => position,
+        };
+        if index >= params.len() || indices.contains(&index) {
+            return None;
+        }
+        indices.push(index);
+    }
+    params
+        .iter()
+        .enumerate()
+        .all(|(index, param)| param.has_default() || indices.contains(&index))
+        .then_some(indices)
+}
+
+/// Validates the arguments of a struct update expression like
+/// `Point(x: 1, ..p)`. Any field can be left out, but the given fields must be
+/// labeled and provided in order.
+pub fn validate_struct_update_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+) -> Result<(), FatalError> {
+    let mut indices = Vec::with_capacity(args.kind.len());
+    let mut previous: Option<(usize, &Node<fe::CallArg>, &str)> = None;
+    for arg in &args.kind {
+        let (label, label_span) = match (&arg.kind.label, &arg.kind.value.kind) {
+            (Some(label), _) => (label.kind.as_str(), label.span),
+            (None, fe::Expr::Name(var_name)) => (var_name.as_str(), arg.kind.value.span),
+            (None, _) => {
+                let arg_val = &arg.kind.value;
+                context.fancy_error(
+                    "missing argument label",
+                    vec![Label::primary(
+                        Span::new(arg_val.span.file_id, arg_val.span.start, arg_val.span.start),
+                        "add a field label here",
+                    )],
+                    vec!["Note: the label is optional if the argument is a variable with the same name as the field.".into()],
+                );
+                indices.push(usize::MAX);
+                continue;
+            }
+        };
+
+        let index = match param_index(params, label) {
+            Some(index) => index,
+            None => {
+                context.error(
+                    &format!("`{}` has no field `{}`", name, label),
+                    label_span,
+                    "unknown field",
+                );
+                indices.push(usize::MAX);
+                continue;
+            }
+        };
+        if let Some((previous_index, previous_arg, previous_label)) = previous {
+            if index == previous_index {
+                context.fancy_error(
+                    &format!("argument `{}` is provided more than once", label),
+                    vec![
+                        Label::primary(arg.span, format!("`{}` is provided again here", label)),
+                        Label::secondary(previous_arg.span, "first provided here"),
+                    ],
+                    vec![],
+                );
+            } else if index < previous_index {
+                context.fancy_error(
+                    "argument label mismatch",
+                    vec![
+                        Label::primary(label_span, format!("`{}` is provided out of order", label)),
+                        Label::secondary(
+                            previous_arg.span,
+                            format!("`{}` comes after `{}` in `{}`", previous_label, label, name),
+                        ),
+                    ],
+                    vec!["Note: arguments must be provided in order.".into()],
+                );
+            }
+        }
+        previous = Some((index, arg, label));
+        indices.push(index);
+    }
+    validate_arg_types(context, name, args, params, indices)
+}
+
+/// Returns the argument of a function call that is passed to the parameter
+/// at `index`. See [`fn_arg_param_indices`].
+pub fn fn_arg_for_param<'a>(
+    args: &'a [Node<fe::CallArg>],
+    params: &[impl LabeledParameter],
+    index: usize,
+) -> Option<&'a Node<fe::CallArg>> {
+    args.iter().enumerate().find_map(|(position, arg)| {
+        let arg_index = match &arg.kind.label {
+            Some(label) => param_index(params, &label.kind).unwrap_or(position),
+            None => position,
+        };
+        if arg_index == index {
+            Some(arg)
+        } else {
+            None
+        }
+    })
+}
+
+fn param_index(params: &[impl LabeledParameter], label: &str) -> Option<usize> {
+    params.iter().position(|param| param.label() == Some(label))
+}
+
+/// Checks the types of the arguments, which are passed to the parameters at
+/// `param_indices`.
 pub fn validate_arg_types(
     context: &mut dyn AnalyzerContext,
     name: &str,
     args: &Node<Vec<Node<fe::CallArg>>>,
     params: &[impl LabeledParameter],
+    param_indices: impl IntoIterator<Item = usize>,
+) -> Result<(), FatalError> {
+    let arg_attrs = args.kind.iter().map(|_| None).collect();
+    check_arg_types(context, name, args, params, param_indices, arg_attrs)
+}
+
+fn check_arg_types(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    params: &[impl LabeledParameter],
+    param_indices: impl IntoIterator<Item = usize>,
+    arg_attrs: Vec<Option<ExpressionAttributes>>,
 ) -> Result<(), FatalError> {
-    for (index, (param, arg)) in params.iter().zip(args.kind.iter()).enumerate() {
+    for ((index, arg), attrs) in param_indices
+        .into_iter()
+        .zip(args.kind.iter())
+        .zip(arg_attrs)
+    {
+        let param = match params.get(index) {
+            Some(param) => param,
+            None => continue,
+        };
         let param_type = param.typ()?;
-        let val_attrs =
-            assignable_expr(context, &arg.kind.value, Some(&param_type.clone().into()))?;
+        let val_attrs = match attrs {
+            Some(attrs) => attrs,
+            None => assignable_expr(context, &arg.kind.value, Some(&param_type.clone().into()))?,
+        };
         if param_type != val_attrs.typ {
             let msg = if let Some(label) = param.label() {
                 format!("incorrect type for `{}` argument `{}`", name, label)
@@ -200,6 +609,14 @@ pub fn validate_arg_types(
             };
             context.type_error(&msg, arg.kind.value.span, &param_type, &val_attrs.typ);
         }
+        // Values in memory and `Vec`s are passed by reference, so the
+        // function can modify the argument.
+        if param.is_mut()
+            && (val_attrs.final_location() == Location::Memory
+                || matches!(param_type, FixedSize::Vec(_)))
+        {
+            check_mutable(context, &arg.kind.value, arg.span);
+        }
     }
     Ok(())
 }
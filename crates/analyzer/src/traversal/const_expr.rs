@@ -1,15 +1,18 @@
 //! This module provides evaluator for constant expression to resolve const generics.
 
-use num_bigint::BigInt;
+use num_bigint::{BigInt, Sign};
 use num_traits::{One, ToPrimitive, Zero};
 
 use crate::{
-    context::{AnalyzerContext, Constant},
+    builtins::GlobalFunction,
+    context::{AnalyzerContext, Constant, NamedThing},
     errors::ConstEvalError,
+    namespace::items::Item,
     namespace::types::{self, Base, Type},
 };
 
-use fe_common::{numeric, Span};
+use fe_common::diagnostics::Label;
+use fe_common::{numeric, utils::keccak, Span};
 use fe_parser::{
     ast::{self, BinOperator, BoolOperator, CompOperator, UnaryOperator},
     node::Node,
@@ -37,10 +40,14 @@ pub(crate) fn eval_expr(
         ast::Expr::BinOperation { left, op, right } => eval_bin_op(context, left, op, right, &typ),
         ast::Expr::UnaryOperation { op, operand } => eval_unary_op(context, op, operand),
         ast::Expr::CompOperation { left, op, right } => eval_comp_op(context, left, op, right),
+        ast::Expr::ChainedComparison { left, comparisons } => {
+            eval_comp_chain(context, left, comparisons)
+        }
         ast::Expr::Bool(val) => Ok(Constant::Bool(*val)),
-        ast::Expr::Name(name) => match context.constant_value_by_name(name)? {
-            Some(const_value) => Ok(const_value),
-            _ => Err(not_const_error(context, expr.span)),
+        ast::Expr::Name(name) => match context.constant_value_by_name(name) {
+            Ok(Some(const_value)) => Ok(const_value),
+            Ok(None) => Err(not_const_error(context, expr.span)),
+            Err(err) => Err(constant_error(context, name, expr.span, err)),
         },
 
         ast::Expr::Num(num) => {
@@ -52,17 +59,48 @@ pub(crate) fn eval_expr(
 
         ast::Expr::Str(s) => Ok(Constant::Str(s.clone())),
 
+        ast::Expr::Call { func, args, .. } => eval_call(context, func, args, expr.span),
+
         // TODO: Need to evaluate attribute getter, constant constructor and const fn call.
         ast::Expr::Subscript { .. }
         | ast::Expr::Path(_)
         | ast::Expr::Attribute { .. }
-        | ast::Expr::Call { .. }
         | ast::Expr::List { .. }
         | ast::Expr::Tuple { .. }
         | ast::Expr::Unit => Err(not_const_error(context, expr.span)),
     }
 }
 
+/// Evaluates a function call. Only `keccak256` of a string literal can be
+/// evaluated at compile time.
+fn eval_call(
+    context: &mut dyn AnalyzerContext,
+    func: &Node<ast::Expr>,
+    args: &Node<Vec<Node<ast::CallArg>>>,
+    span: Span,
+) -> Result<Constant, ConstEvalError> {
+    let is_keccak = match &func.kind {
+        ast::Expr::Name(name) => matches!(
+            context.resolve_name(name)?,
+            Some(NamedThing::Item(Item::BuiltinFunction(
+                GlobalFunction::Keccak256
+            )))
+        ),
+        _ => false,
+    };
+
+    match args.kind.as_slice() {
+        [arg] if is_keccak => match eval_expr(context, &arg.kind.value)? {
+            Constant::Str(s) => Ok(Constant::Int(BigInt::from_bytes_be(
+                Sign::Plus,
+                &keccak::full_as_bytes(s.as_bytes()),
+            ))),
+            _ => Err(not_const_error(context, span)),
+        },
+        _ => Err(not_const_error(context, span)),
+    }
+}
+
 /// Evaluates ternary expression.
 fn eval_ternary(
     context: &mut dyn AnalyzerContext,
@@ -223,8 +261,28 @@ fn eval_comp_op(
     rhs: &Node<ast::Expr>,
 ) -> Result<Constant, ConstEvalError> {
     let (lhs, rhs) = (eval_expr(context, lhs)?, eval_expr(context, rhs)?);
+    Ok(Constant::Bool(compare(&lhs, op, &rhs)))
+}
 
-    let res = match (lhs, rhs) {
+/// Evaluates chained comparisons like `a < b < c`.
+fn eval_comp_chain(
+    context: &mut dyn AnalyzerContext,
+    lhs: &Node<ast::Expr>,
+    comparisons: &[(Node<ast::CompOperator>, Node<ast::Expr>)],
+) -> Result<Constant, ConstEvalError> {
+    // In constant evaluation, we don't apply short circuit property for safety.
+    let mut lhs = eval_expr(context, lhs)?;
+    let mut res = true;
+    for (op, rhs) in comparisons {
+        let rhs = eval_expr(context, rhs)?;
+        res &= compare(&lhs, op, &rhs);
+        lhs = rhs;
+    }
+    Ok(Constant::Bool(res))
+}
+
+fn compare(lhs: &Constant, op: &Node<ast::CompOperator>, rhs: &Constant) -> bool {
+    match (lhs, rhs) {
         (Constant::Int(lhs), Constant::Int(rhs)) => match op.kind {
             CompOperator::Eq => lhs == rhs,
             CompOperator::NotEq => lhs != rhs,
@@ -244,9 +302,7 @@ fn eval_comp_op(
         },
 
         _ => panic!("arguments of comp op have invalid type"),
-    };
-
-    Ok(Constant::Bool(res))
+    }
 }
 
 impl Constant {
@@ -308,28 +364,71 @@ impl Constant {
     }
 }
 
+/// Reports that the value of the constant `name`, which is used at `span`,
+/// can't be evaluated, with the causes of the failure. The diagnostics of the
+/// evaluation of a module constant are only emitted where its value is needed.
+///
+/// An error without causes is returned as is, because its diagnostic has
+/// already been emitted, eg for an invalid type of the constant.
+pub(crate) fn constant_error(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    span: Span,
+    err: ConstEvalError,
+) -> ConstEvalError {
+    if err.causes().is_empty() {
+        return err;
+    }
+
+    let mut labels = vec![Label::primary(
+        span,
+        format!("the value of `{}` is required to be constant here", name),
+    )];
+    labels.extend(err.cause_labels());
+    let voucher = context.fancy_error(
+        &format!("the value of constant `{}` can't be evaluated", name),
+        labels,
+        vec![],
+    );
+    ConstEvalError::new(voucher)
+        .caused_by(err)
+        .with_cause(span, format!("`{}` is used here", name))
+}
+
 fn not_const_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalError {
-    ConstEvalError::new(context.error(
+    eval_error(
+        context,
         "expression is not a constant",
         span,
         "expression is required to be constant here",
-    ))
+    )
 }
 
 fn overflow_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalError {
-    ConstEvalError::new(context.error(
+    eval_error(
+        context,
         "overflow error",
         span,
         "overflow occurred during constant evaluation",
-    ))
+    )
 }
 
 fn zero_division_error(context: &mut dyn AnalyzerContext, span: Span) -> ConstEvalError {
-    ConstEvalError::new(context.error(
+    eval_error(
+        context,
         "zero division error",
         span,
         "zero division occurred during constant evaluation",
-    ))
+    )
+}
+
+fn eval_error(
+    context: &mut dyn AnalyzerContext,
+    message: &str,
+    span: Span,
+    label: &str,
+) -> ConstEvalError {
+    ConstEvalError::new(context.error(message, span, label)).with_cause(span, label)
 }
 
 /// Returns integer types embedded in `typ`.
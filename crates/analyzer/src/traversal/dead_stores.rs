@@ -0,0 +1,336 @@
+use crate::context::{CallType, FunctionBody, Location};
+use fe_common::Span;
+use fe_parser::ast;
+use fe_parser::node::{Node, NodeId};
+use smol_str::SmolStr;
+
+/// A store to storage that is overwritten before it can be observed.
+pub struct DeadStore {
+    /// The assignment statement whose store is dead.
+    pub stmt: NodeId,
+    pub span: Span,
+    /// The assignment that overwrites the stored value.
+    pub overwritten_at: Span,
+    /// The assigned place, e.g. `self.balances[0]`.
+    pub place: String,
+}
+
+/// Finds the assignments to storage in `block` that are overwritten before the
+/// stored value is read.
+///
+/// Only straight-line code is considered: the stores that are pending when a
+/// statement with a nested block, a call that might access storage, or a
+/// statement that leaves the block is reached are assumed to be read.
+pub fn dead_stores(body: &FunctionBody, block: &[Node<ast::FuncStmt>]) -> Vec<DeadStore> {
+    let mut finder = DeadStoreFinder {
+        body,
+        pending: vec![],
+        dead: vec![],
+    };
+    finder.block(block);
+    finder.dead
+}
+
+/// A step in the path to a storage place, starting at `self`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Segment {
+    Field(SmolStr),
+    /// An index given by a literal, e.g. `[0]` or `[true]`.
+    Index(SmolStr),
+}
+
+struct PendingStore {
+    place: Vec<Segment>,
+    stmt: NodeId,
+    span: Span,
+    target: String,
+}
+
+struct DeadStoreFinder<'a> {
+    body: &'a FunctionBody,
+    /// The stores of the current block that haven't been read yet.
+    pending: Vec<PendingStore>,
+    dead: Vec<DeadStore>,
+}
+
+impl DeadStoreFinder<'_> {
+    fn block(&mut self, block: &[Node<ast::FuncStmt>]) {
+        let outer = std::mem::take(&mut self.pending);
+        for stmt in block {
+            self.stmt(stmt);
+        }
+        self.pending = outer;
+    }
+
+    fn stmt(&mut self, stmt: &Node<ast::FuncStmt>) {
+        match &stmt.kind {
+            ast::FuncStmt::Assign { target, value } => {
+                self.expr(value);
+                match self.storage_place(target) {
+                    Some(place) => self.store(stmt, target, place),
+                    None => self.expr(target),
+                }
+            }
+            ast::FuncStmt::AugAssign { target, value, .. } => {
+                self.expr(value);
+                self.expr(target);
+            }
+            ast::FuncStmt::VarDecl { value, .. } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+            }
+            ast::FuncStmt::ConstantDecl { value, .. } | ast::FuncStmt::Expr { value } => {
+                self.expr(value)
+            }
+            ast::FuncStmt::Assert { test, msg } => {
+                self.expr(test);
+                if let Some(msg) = msg {
+                    self.expr(msg)
+                }
+            }
+            ast::FuncStmt::Emit { args, .. } => {
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+            }
+            // Yul code might read anything.
+            ast::FuncStmt::Yul { inputs, .. } => {
+                for input in inputs {
+                    self.expr(&input.kind.value)
+                }
+                self.pending.clear()
+            }
+            ast::FuncStmt::Return { .. }
+            | ast::FuncStmt::Revert { .. }
+            | ast::FuncStmt::Break { .. }
+            | ast::FuncStmt::Continue => self.pending.clear(),
+            ast::FuncStmt::For { body, .. }
+            | ast::FuncStmt::While { body, .. }
+            | ast::FuncStmt::Loop { body, .. }
+            | ast::FuncStmt::Unsafe(body)
+            | ast::FuncStmt::Unchecked(body) => {
+                self.pending.clear();
+                self.block(body);
+            }
+            ast::FuncStmt::Checked(body) => {
+                for stmt in body {
+                    self.stmt(stmt)
+                }
+            }
+            ast::FuncStmt::If { body, or_else, .. } => {
+                self.pending.clear();
+                self.block(body);
+                self.block(or_else);
+            }
+            ast::FuncStmt::Match { arms, .. } => {
+                self.pending.clear();
+                for arm in arms {
+                    self.block(&arm.kind.body);
+                }
+            }
+            ast::FuncStmt::Try {
+                call,
+                body,
+                catches,
+                ..
+            } => {
+                self.expr(call);
+                self.pending.clear();
+                self.block(body);
+                for clause in catches {
+                    self.block(&clause.kind.body);
+                }
+            }
+            ast::FuncStmt::Pass => {}
+        }
+    }
+
+    /// Records a store to `place`, which makes the pending stores to `place`
+    /// (or to a part of it) dead.
+    fn store(&mut self, stmt: &Node<ast::FuncStmt>, target: &Node<ast::Expr>, place: Vec<Segment>) {
+        let (overwritten, pending) = std::mem::take(&mut self.pending)
+            .into_iter()
+            .partition(|store| store.place.starts_with(&place));
+        self.pending = pending;
+
+        for store in overwritten {
+            self.dead.push(DeadStore {
+                stmt: store.stmt,
+                span: store.span,
+                overwritten_at: stmt.span,
+                place: store.target,
+            })
+        }
+        self.pending.push(PendingStore {
+            place,
+            stmt: stmt.id,
+            span: stmt.span,
+            target: target.kind.to_string(),
+        })
+    }
+
+    /// Forgets the pending stores that might be read by `expr`.
+    fn expr(&mut self, expr: &Node<ast::Expr>) {
+        if let Some((place, _)) = read_place(expr) {
+            self.pending.retain(|store| {
+                !store.place.starts_with(&place) && !place.starts_with(&store.place)
+            });
+            self.indices(expr);
+            return;
+        }
+
+        match &expr.kind {
+            ast::Expr::Call {
+                func, args, base, ..
+            } => {
+                if self.call_may_access_storage(func) {
+                    self.pending.clear()
+                }
+                match &func.kind {
+                    // The method's receiver is read, e.g. `self.point` in
+                    // `self.point.to_mem()`.
+                    ast::Expr::Attribute { value, .. } => self.expr(value),
+                    _ => self.expr(func),
+                }
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+                if let Some(base) = base {
+                    self.expr(base)
+                }
+            }
+            ast::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                for expr in [if_expr, test, else_expr] {
+                    self.expr(expr)
+                }
+            }
+            ast::Expr::BoolOperation { left, right, .. }
+            | ast::Expr::BinOperation { left, right, .. }
+            | ast::Expr::CompOperation { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            ast::Expr::ChainedComparison { left, comparisons } => {
+                self.expr(left);
+                for (_, operand) in comparisons {
+                    self.expr(operand)
+                }
+            }
+            ast::Expr::UnaryOperation { operand: value, .. }
+            | ast::Expr::Attribute { value, .. } => self.expr(value),
+            ast::Expr::Subscript { value, index } => {
+                self.expr(value);
+                self.expr(index);
+            }
+            ast::Expr::List { elts, fill } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+                if let Some(fill) = fill {
+                    self.expr(fill)
+                }
+            }
+            ast::Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+            }
+            ast::Expr::Bool(_)
+            | ast::Expr::Name(_)
+            | ast::Expr::Path(_)
+            | ast::Expr::Num(_)
+            | ast::Expr::Str(_)
+            | ast::Expr::Unit => {}
+        }
+    }
+
+    /// Visits the indices in a path, e.g. `i` in `self.balances[i]`.
+    fn indices(&mut self, expr: &Node<ast::Expr>) {
+        match &expr.kind {
+            ast::Expr::Attribute { value, .. } => self.indices(value),
+            ast::Expr::Subscript { value, index } => {
+                self.indices(value);
+                self.expr(index);
+            }
+            _ => {}
+        }
+    }
+
+    /// Calls of functions (and intrinsics) might read storage, directly or by
+    /// calling back into the contract.
+    fn call_may_access_storage(&self, func: &Node<ast::Expr>) -> bool {
+        match self.body.calls.get(&func.id) {
+            Some(
+                CallType::BuiltinFunction(_)
+                | CallType::BuiltinAbiFunction { .. }
+                | CallType::BuiltinValueMethod { .. }
+                | CallType::BuiltinIntegerConversion { .. }
+                | CallType::TypeConstructor(_)
+                | CallType::EnumConstructor(_),
+            ) => false,
+            Some(_) | None => true,
+        }
+    }
+
+    /// The path of a storage place that's assigned to with `target`, if the
+    /// place is known exactly.
+    fn storage_place(&self, target: &Node<ast::Expr>) -> Option<Vec<Segment>> {
+        let is_storage = matches!(
+            self.body.expressions.get(&target.id),
+            Some(attributes) if matches!(attributes.location, Location::Storage { .. })
+        );
+        if !is_storage {
+            return None;
+        }
+        exact_place(target).filter(|place| !place.is_empty())
+    }
+}
+
+fn exact_place(expr: &Node<ast::Expr>) -> Option<Vec<Segment>> {
+    match &expr.kind {
+        ast::Expr::Name(name) if name == "self" => Some(vec![]),
+        ast::Expr::Attribute { value, attr } => {
+            let mut place = exact_place(value)?;
+            place.push(Segment::Field(attr.kind.clone()));
+            Some(place)
+        }
+        ast::Expr::Subscript { value, index } => {
+            let index = match &index.kind {
+                ast::Expr::Num(num) => num.clone(),
+                ast::Expr::Bool(true) => "true".into(),
+                ast::Expr::Bool(false) => "false".into(),
+                _ => return None,
+            };
+            let mut place = exact_place(value)?;
+            place.push(Segment::Index(index));
+            Some(place)
+        }
+        _ => None,
+    }
+}
+
+/// The path of the place in `self` that's read by `expr`, if any, and whether
+/// the path was cut off. Paths are cut off at the first subscript, since
+/// different indices might refer to the same item (e.g. `[1]` and `[0x1]`).
+fn read_place(expr: &Node<ast::Expr>) -> Option<(Vec<Segment>, bool)> {
+    match &expr.kind {
+        ast::Expr::Name(name) if name == "self" => Some((vec![], false)),
+        ast::Expr::Attribute { value, attr } => {
+            let (mut place, is_cut) = read_place(value)?;
+            if !is_cut {
+                place.push(Segment::Field(attr.kind.clone()));
+            }
+            Some((place, is_cut))
+        }
+        ast::Expr::Subscript { value, .. } => {
+            let (place, _) = read_place(value)?;
+            Some((place, true))
+        }
+        _ => None,
+    }
+}
@@ -1,7 +1,7 @@
 use crate::context::AnalyzerContext;
 use crate::errors::FatalError;
 use crate::namespace::scopes::BlockScope;
-use crate::namespace::types::FixedSize;
+use crate::namespace::types::{Base, FixedSize};
 use crate::traversal::{const_expr, expressions, types};
 use fe_common::diagnostics::Label;
 use fe_common::utils::humanize::pluralize_conditionally;
@@ -11,6 +11,10 @@ use fe_parser::node::Node;
 /// Gather context information for var declarations and check for type errors.
 pub fn var_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::VarDecl { target, typ, value } = &stmt.kind {
+        let typ = match typ {
+            Some(typ) => typ,
+            None => return untyped_var_decl(scope, target, value),
+        };
         let declared_type = match FixedSize::try_from(types::type_desc(scope, typ)?) {
             Ok(typ) => typ,
             Err(_) => {
@@ -45,6 +49,64 @@ pub fn var_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
     unreachable!()
 }
 
+/// A declaration without a type description, eg. `let x = foo()` or
+/// `let (x, y) = pair`, whose type is inferred from the value.
+fn untyped_var_decl(
+    scope: &mut BlockScope,
+    target: &Node<fe::VarDeclTarget>,
+    value: &Option<Node<fe::Expr>>,
+) -> Result<(), FatalError> {
+    let value = value
+        .as_ref()
+        .expect("variable declaration without a type or a value");
+    let value_type = expressions::assignable_expr(scope, value, None)?.typ;
+    let declared_type = match FixedSize::try_from(value_type) {
+        Ok(typ) if matches!(target.kind, fe::VarDeclTarget::Name(_)) => typ,
+        Ok(typ @ FixedSize::Tuple(_)) => typ,
+        Ok(typ) => {
+            return Err(FatalError::new(scope.fancy_error(
+                "invalid declaration",
+                vec![
+                    Label::primary(target.span, "this is a tuple declaration"),
+                    Label::secondary(value.span, format!("this has type `{}`", typ)),
+                ],
+                vec!["Note: only a tuple value can be destructured".into()],
+            )))
+        }
+        Err(_) => {
+            return Err(FatalError::new(scope.error(
+                "invalid declaration",
+                value.span,
+                "this value can't be assigned to a variable",
+            )))
+        }
+    };
+
+    if has_uninferred_type(&declared_type) {
+        return Err(FatalError::new(scope.fancy_error(
+            "type annotation needed",
+            vec![Label::primary(
+                value.span,
+                format!("the type `{}` can't be fully inferred", declared_type),
+            )],
+            vec!["Hint: give the declaration a type, e.g. `let x: Array<u256, 0> = []`".into()],
+        )));
+    }
+
+    scope.root.add_declaration(target, declared_type.clone());
+    add_var(scope, target, declared_type)
+}
+
+/// `true` if the type includes the element type of an empty array literal,
+/// which is only known if the declaration has a type description.
+fn has_uninferred_type(typ: &FixedSize) -> bool {
+    match typ {
+        FixedSize::Array(array) => array.inner == Base::Unit,
+        FixedSize::Tuple(tuple) => tuple.items.iter().any(has_uninferred_type),
+        _ => false,
+    }
+}
+
 pub fn const_decl(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::ConstantDecl { name, typ, value } = &stmt.kind {
         let declared_type = match FixedSize::try_from(types::type_desc(scope, typ)?) {
@@ -0,0 +1,333 @@
+use crate::context::FunctionBody;
+use crate::namespace::types::FixedSize;
+use fe_common::Span;
+use fe_parser::ast;
+use fe_parser::node::Node;
+use indexmap::{IndexMap, IndexSet};
+use smol_str::SmolStr;
+
+/// A read of a local variable that might not have been given a value yet.
+pub struct UninitializedRead {
+    pub name: SmolStr,
+    pub span: Span,
+    /// The declaration of the variable, which doesn't give it a value.
+    pub declared_at: Span,
+    pub typ: FixedSize,
+}
+
+/// Finds the reads of local variables in `block` that aren't preceded by an
+/// assignment to the variable on every path that leads to them.
+///
+/// Only variables of a primitive, contract or enum type are checked. Arrays,
+/// structs and other data in memory start out zeroed, and are usually filled
+/// in one element or field at a time.
+pub fn uninitialized_reads(
+    body: &FunctionBody,
+    block: &[Node<ast::FuncStmt>],
+) -> Vec<UninitializedRead> {
+    let mut checker = Checker {
+        body,
+        declared: IndexMap::new(),
+        unassigned: Some(IndexSet::new()),
+        breaks: vec![],
+        reads: vec![],
+    };
+    checker.block(block);
+    checker.reads
+}
+
+/// The variables that might not have been assigned yet at some point of the
+/// function, or `None` if that point can't be reached.
+type Unassigned = Option<IndexSet<SmolStr>>;
+
+/// The variables that are unassigned on either of two paths that come
+/// together.
+fn join(left: Unassigned, right: Unassigned) -> Unassigned {
+    match (left, right) {
+        (Some(mut left), Some(right)) => {
+            left.extend(right);
+            Some(left)
+        }
+        (left, None) => left,
+        (None, right) => right,
+    }
+}
+
+struct Checker<'a> {
+    body: &'a FunctionBody,
+    /// The checked variables that are in scope, with their declaration.
+    declared: IndexMap<SmolStr, (Span, FixedSize)>,
+    unassigned: Unassigned,
+    /// For each enclosing loop, the variables that are unassigned on the
+    /// `break` statements that have been seen so far.
+    breaks: Vec<Unassigned>,
+    reads: Vec<UninitializedRead>,
+}
+
+impl Checker<'_> {
+    fn block(&mut self, block: &[Node<ast::FuncStmt>]) {
+        let declared = self.declared.len();
+        for stmt in block {
+            self.stmt(stmt)
+        }
+        // The variables that are declared in the block go out of scope.
+        self.declared.truncate(declared);
+        self.forget_out_of_scope();
+    }
+
+    fn forget_out_of_scope(&mut self) {
+        let declared = &self.declared;
+        if let Some(unassigned) = &mut self.unassigned {
+            unassigned.retain(|name| declared.contains_key(name))
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Node<ast::FuncStmt>) {
+        match &stmt.kind {
+            ast::FuncStmt::VarDecl { target, typ, value } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+                if let (ast::VarDeclTarget::Name(name), None) = (&target.kind, value) {
+                    self.declare(name, target, typ.as_ref())
+                }
+            }
+            ast::FuncStmt::Assign { target, value } => {
+                self.expr(value);
+                self.assign(target);
+            }
+            ast::FuncStmt::AugAssign { target, value, .. } => {
+                self.expr(value);
+                self.expr(target);
+                self.assign(target);
+            }
+            ast::FuncStmt::ConstantDecl { value, .. } | ast::FuncStmt::Expr { value } => {
+                self.expr(value)
+            }
+            ast::FuncStmt::Assert { test, msg } => {
+                self.expr(test);
+                if let Some(msg) = msg {
+                    self.expr(msg)
+                }
+            }
+            ast::FuncStmt::Emit { args, .. } => {
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+            }
+            ast::FuncStmt::Yul { inputs, .. } => {
+                for input in inputs {
+                    self.expr(&input.kind.value)
+                }
+            }
+            ast::FuncStmt::Return { value } | ast::FuncStmt::Break { value } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+                let unassigned = self.unassigned.take();
+                if let (ast::FuncStmt::Break { .. }, Some(breaks)) =
+                    (&stmt.kind, self.breaks.last_mut())
+                {
+                    *breaks = join(breaks.take(), unassigned)
+                }
+            }
+            ast::FuncStmt::Revert { error } => {
+                if let Some(error) = error {
+                    self.expr(error)
+                }
+                self.unassigned = None
+            }
+            ast::FuncStmt::Continue => self.unassigned = None,
+            ast::FuncStmt::For { iter, body, .. } => {
+                self.expr(iter);
+                self.loop_body(body, true);
+            }
+            ast::FuncStmt::While { test, body } => {
+                self.expr(test);
+                self.loop_body(body, true);
+            }
+            // A `loop` can only be left with a `break`.
+            ast::FuncStmt::Loop { body, .. } => self.loop_body(body, false),
+            ast::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(test);
+                let entry = self.unassigned.clone();
+                self.block(body);
+                let after_body = std::mem::replace(&mut self.unassigned, entry);
+                self.block(or_else);
+                self.unassigned = join(after_body, self.unassigned.take());
+            }
+            // Matches are exhaustive, so one of the arms is always taken.
+            ast::FuncStmt::Match { value, arms } => {
+                self.expr(value);
+                let entry = self.unassigned.take();
+                for arm in arms {
+                    let after_arms = std::mem::replace(&mut self.unassigned, entry.clone());
+                    self.block(&arm.kind.body);
+                    self.unassigned = join(after_arms, self.unassigned.take());
+                }
+            }
+            // If no clause catches the revert of the call, the revert is
+            // propagated, so the body or one of the clauses is taken.
+            ast::FuncStmt::Try {
+                call,
+                body,
+                catches,
+                ..
+            } => {
+                self.expr(call);
+                let entry = self.unassigned.take();
+                let bodies = std::iter::once(body).chain(catches.iter().map(|c| &c.kind.body));
+                for body in bodies {
+                    let after_bodies = std::mem::replace(&mut self.unassigned, entry.clone());
+                    self.block(body);
+                    self.unassigned = join(after_bodies, self.unassigned.take());
+                }
+            }
+            ast::FuncStmt::Unsafe(body) | ast::FuncStmt::Unchecked(body) => self.block(body),
+            ast::FuncStmt::Checked(body) => {
+                for stmt in body {
+                    self.stmt(stmt)
+                }
+            }
+            ast::FuncStmt::Pass => {}
+        }
+    }
+
+    fn declare(
+        &mut self,
+        name: &SmolStr,
+        target: &Node<ast::VarDeclTarget>,
+        typ: Option<&Node<ast::TypeDesc>>,
+    ) {
+        let id = typ.map_or(target.id, |typ| typ.id);
+        if let Some(typ @ (FixedSize::Base(_) | FixedSize::Contract(_) | FixedSize::Enum(_))) =
+            self.body.var_decl_types.get(&id)
+        {
+            if typ.is_unit() {
+                return;
+            }
+            self.declared
+                .insert(name.clone(), (target.span, typ.clone()));
+            if let Some(unassigned) = &mut self.unassigned {
+                unassigned.insert(name.clone());
+            }
+        }
+    }
+
+    /// The body of a loop is checked once, as the variables that are
+    /// unassigned when the loop is entered are the most that can be
+    /// unassigned on any iteration. `may_skip` is `true` if the loop can end
+    /// without a `break`.
+    fn loop_body(&mut self, body: &[Node<ast::FuncStmt>], may_skip: bool) {
+        let entry = self.unassigned.clone();
+        self.breaks.push(None);
+        self.block(body);
+        let breaks = self.breaks.pop().flatten();
+
+        self.unassigned = if may_skip {
+            join(entry, breaks)
+        } else {
+            breaks
+        };
+        // A `break` might be in a block whose variables are out of scope now.
+        self.forget_out_of_scope();
+    }
+
+    fn assign(&mut self, target: &Node<ast::Expr>) {
+        match &target.kind {
+            ast::Expr::Name(name) => {
+                if let Some(unassigned) = &mut self.unassigned {
+                    unassigned.shift_remove(name);
+                }
+            }
+            ast::Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.assign(elt)
+                }
+            }
+            _ => self.expr(target),
+        }
+    }
+
+    fn expr(&mut self, expr: &Node<ast::Expr>) {
+        match &expr.kind {
+            ast::Expr::Name(name) => {
+                // Each variable is only reported once per path, so it's
+                // considered to be assigned after the first read.
+                let unassigned = match &mut self.unassigned {
+                    Some(unassigned) => unassigned.shift_remove(name),
+                    None => false,
+                };
+                if unassigned {
+                    let (declared_at, typ) = self.declared[name].clone();
+                    self.reads.push(UninitializedRead {
+                        name: name.clone(),
+                        span: expr.span,
+                        declared_at,
+                        typ,
+                    })
+                }
+            }
+            ast::Expr::Call {
+                func, args, base, ..
+            } => {
+                self.expr(func);
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+                if let Some(base) = base {
+                    self.expr(base)
+                }
+            }
+            ast::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                for expr in [test, if_expr, else_expr] {
+                    self.expr(expr)
+                }
+            }
+            ast::Expr::BoolOperation { left, right, .. }
+            | ast::Expr::BinOperation { left, right, .. }
+            | ast::Expr::CompOperation { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            ast::Expr::ChainedComparison { left, comparisons } => {
+                self.expr(left);
+                for (_, operand) in comparisons {
+                    self.expr(operand)
+                }
+            }
+            ast::Expr::UnaryOperation { operand: value, .. }
+            | ast::Expr::Attribute { value, .. } => self.expr(value),
+            ast::Expr::Subscript { value, index } => {
+                self.expr(value);
+                self.expr(index);
+            }
+            ast::Expr::List { elts, fill } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+                if let Some(fill) = fill {
+                    self.expr(fill)
+                }
+            }
+            ast::Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+            }
+            ast::Expr::Bool(_)
+            | ast::Expr::Path(_)
+            | ast::Expr::Num(_)
+            | ast::Expr::Str(_)
+            | ast::Expr::Unit => {}
+        }
+    }
+}
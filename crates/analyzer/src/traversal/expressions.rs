@@ -1,28 +1,43 @@
 use crate::context::{AnalyzerContext, CallType, ExpressionAttributes, Location, NamedThing};
-use crate::errors::{FatalError, IndexingError, NotFixedSize};
-use crate::namespace::items::{Class, FunctionId, Item};
+use crate::db::queries::functions::all_paths_return_or_revert;
+use crate::errors::{FatalError, IndexingError, NotFixedSize, TypeError};
+use crate::features::Feature;
+use crate::namespace::items::{Class, EnumVariantId, FunctionId, Item, StructId};
 use crate::namespace::scopes::BlockScopeType;
 use crate::namespace::types::{
-    Array, Base, Contract, FeString, Integer, Struct, Tuple, Type, TypeDowncast, U256,
+    Array, Base, Contract, FeString, FeVec, FixedSize, GenericParam, GenericParamKind, Integer,
+    SelfDecl, Struct, Tuple, Type, TypeDowncast, U256,
 };
 use crate::operations;
-use crate::traversal::call_args::{validate_arg_count, validate_named_args, LabelPolicy};
-use crate::traversal::types::apply_generic_type_args;
+use crate::traversal::call_args::{
+    fn_arg_for_param, fn_args_fit_params, validate_arg_count, validate_checked_fn_args,
+    validate_fn_arg_count, validate_fn_args, validate_named_args, validate_struct_update_args,
+};
+use crate::traversal::types::{
+    apply_generic_struct_args, apply_generic_type_args, check_generic_args, context_module,
+    fixed_size_args, type_desc,
+};
+use crate::traversal::pragma::check_feature;
 use crate::traversal::utils::{add_bin_operations_errors, types_to_fixed_sizes};
+use crate::AnalyzerDb;
 use crate::{
     builtins::{
-        BlockField, ChainField, ContractSelfField, ContractTypeMethod, GlobalFunction,
-        GlobalObject, Intrinsic, MsgField, TxField, ValueMethod,
+        bin_operator_method, comp_operator_method, BlockField, ChainField, ContractSelfField,
+        ContractTypeMethod, EvmVersion, GlobalFunction, GlobalObject, IntegerTypeMethod, Intrinsic,
+        MsgField, TxField, ValueMethod,
     },
     namespace::items::TypeDef,
 };
 use fe_common::diagnostics::Label;
+use fe_common::utils::humanize::pluralize_conditionally;
 use fe_common::{numeric, Span};
 use fe_parser::ast as fe;
 use fe_parser::ast::UnaryOperator;
 use fe_parser::node::Node;
 use num_bigint::BigInt;
+use num_traits::Zero;
 use smol_str::SmolStr;
+use std::collections::HashMap;
 use std::ops::RangeInclusive;
 use std::str::FromStr;
 use vec1::Vec1;
@@ -40,17 +55,19 @@ pub fn expr(
         fe::Expr::Bool(_) => expr_bool(exp),
         fe::Expr::Subscript { .. } => expr_subscript(context, exp),
         fe::Expr::Attribute { .. } => expr_attribute(context, exp),
-        fe::Expr::Ternary { .. } => expr_ternary(context, exp),
+        fe::Expr::Ternary { .. } => expr_ternary(context, exp, expected_type),
         fe::Expr::BoolOperation { .. } => expr_bool_operation(context, exp),
         fe::Expr::BinOperation { .. } => expr_bin_operation(context, exp, expected_type.as_int()),
         fe::Expr::UnaryOperation { .. } => expr_unary_operation(context, exp, expected_type),
         fe::Expr::CompOperation { .. } => expr_comp_operation(context, exp),
+        fe::Expr::ChainedComparison { .. } => expr_chained_comparison(context, exp),
         fe::Expr::Call {
             func,
             generic_args,
             args,
-        } => expr_call(context, func, generic_args, args),
-        fe::Expr::List { elts } => expr_list(context, elts, expected_type.as_array()),
+            base,
+        } => expr_call(context, func, generic_args, args, base.as_deref()),
+        fe::Expr::List { .. } => expr_list(context, exp, expected_type.as_array()),
         fe::Expr::Tuple { .. } => expr_tuple(context, exp, expected_type.as_tuple()),
         fe::Expr::Str(_) => expr_str(context, exp, expected_type.as_string()),
         fe::Expr::Unit => Ok(ExpressionAttributes::new(Type::unit(), Location::Value)),
@@ -62,23 +79,16 @@ pub fn expr(
 
 pub fn expr_list(
     context: &mut dyn AnalyzerContext,
-    elts: &[Node<fe::Expr>],
+    exp: &Node<fe::Expr>,
     expected_type: Option<&Array>,
 ) -> Result<ExpressionAttributes, FatalError> {
-    if elts.is_empty() {
-        return Ok(ExpressionAttributes {
-            typ: Type::Array(Array {
-                size: 0,
-                inner: expected_type.map_or(Base::Unit, |arr| arr.inner),
-            }),
-            location: Location::Memory,
-            move_location: None,
-            const_value: None,
-        });
-    }
+    let (elts, fill) = match &exp.kind {
+        fe::Expr::List { elts, fill } => (elts, fill.as_deref()),
+        _ => unreachable!(),
+    };
 
-    let inner_type = if let Some(expected) = expected_type {
-        for elt in elts {
+    let (inner_type, size) = if let Some(expected) = expected_type {
+        for elt in elts.iter().chain(fill) {
             let element_attributes =
                 assignable_expr(context, elt, Some(&Type::Base(expected.inner)))?;
             if element_attributes.typ != Type::Base(expected.inner) {
@@ -90,7 +100,26 @@ pub fn expr_list(
                 );
             }
         }
-        expected.inner
+        // The literal is given the expected length even if it has the wrong
+        // number of elements, so that the mismatch is only reported once.
+        if elts.len() > expected.size || (fill.is_none() && elts.len() < expected.size) {
+            array_length_error(context, exp.span, elts, expected);
+        }
+        (expected.inner, expected.size)
+    } else if let Some(fill) = fill {
+        return Err(FatalError::new(context.fancy_error(
+            "the length of an array with a fill value can't be inferred",
+            vec![Label::primary(
+                fill.span,
+                "the remaining elements are set to this value",
+            )],
+            vec![
+                    "Hint: give the array a type, e.g. `let values: Array<u256, 10> = [1, 2, ..0]`"
+                        .into(),
+                ],
+        )));
+    } else if elts.is_empty() {
+        (Base::Unit, 0)
     } else {
         let first_attr = assignable_expr(context, &elts[0], None)?;
         let inner = match first_attr.typ {
@@ -125,13 +154,13 @@ pub fn expr_list(
                 );
             }
         }
-        inner
+        (inner, elts.len())
     };
 
     // TODO: Right now we are only supporting Base type arrays
     // Potentially we can support tuples as well.
     let array_typ = Array {
-        size: elts.len(),
+        size,
         inner: inner_type,
     };
 
@@ -143,6 +172,67 @@ pub fn expr_list(
     })
 }
 
+/// The longest array for which a literal that's too short is suggested to be
+/// padded with zeros, in addition to being given a fill value.
+const MAX_PADDED_LITERAL_SIZE: usize = 16;
+
+/// Reports an array literal that has more or fewer elements than the
+/// expected array type.
+fn array_length_error(
+    context: &mut dyn AnalyzerContext,
+    span: Span,
+    elts: &[Node<fe::Expr>],
+    expected: &Array,
+) {
+    let given = elts.iter().map(|elt| elt.kind.to_string());
+    let zero = match expected.inner {
+        Base::Numeric(_) => "0",
+        Base::Bool => "false",
+        Base::Address => "address(0)",
+        Base::Unit => "()",
+    };
+    let mut notes = vec![];
+    if elts.len() < expected.size {
+        if expected.size <= MAX_PADDED_LITERAL_SIZE {
+            let padded = given
+                .clone()
+                .chain((elts.len()..expected.size).map(|_| zero.to_string()))
+                .collect::<Vec<_>>();
+            notes.push(format!(
+                "Hint: pad the literal to {} elements: `[{}]`",
+                expected.size,
+                padded.join(", ")
+            ));
+        }
+        let filled = given
+            .chain(std::iter::once(format!("..{}", zero)))
+            .collect::<Vec<_>>();
+        notes.push(format!(
+            "Hint: {}set the remaining elements to a fill value: `[{}]`",
+            if notes.is_empty() { "" } else { "or " },
+            filled.join(", ")
+        ));
+    }
+    context.fancy_error(
+        &format!(
+            "array literal has {} {}, but `{}` has {}",
+            elts.len(),
+            pluralize_conditionally("element", elts.len()),
+            expected,
+            expected.size
+        ),
+        vec![Label::primary(
+            span,
+            format!(
+                "expected {} {}",
+                expected.size,
+                pluralize_conditionally("element", expected.size)
+            ),
+        )],
+        notes,
+    );
+}
+
 /// Gather context information for expressions and check for type errors.
 ///
 /// Also ensures that the expression is on the stack.
@@ -151,7 +241,16 @@ pub fn value_expr(
     exp: &Node<fe::Expr>,
     expected_type: Option<&Type>,
 ) -> Result<ExpressionAttributes, FatalError> {
-    let original_attributes = expr(context, exp, expected_type)?;
+    let attributes = expr(context, exp, expected_type)?;
+    move_to_stack(context, exp, attributes)
+}
+
+/// Moves the value of an already traversed expression onto the stack.
+fn move_to_stack(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+    original_attributes: ExpressionAttributes,
+) -> Result<ExpressionAttributes, FatalError> {
     let attributes = original_attributes.clone().into_loaded().map_err(|_| {
         FatalError::new(context.fancy_error(
             "can't move value onto stack",
@@ -175,17 +274,27 @@ pub fn assignable_expr(
     context: &mut dyn AnalyzerContext,
     exp: &Node<fe::Expr>,
     expected_type: Option<&Type>,
+) -> Result<ExpressionAttributes, FatalError> {
+    let attributes = expr(context, exp, expected_type)?;
+    move_to_assignment_location(context, exp, attributes)
+}
+
+/// Moves the value of an already traversed expression to the assignment
+/// location of its type.
+fn move_to_assignment_location(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+    mut attributes: ExpressionAttributes,
 ) -> Result<ExpressionAttributes, FatalError> {
     use Type::*;
 
-    let mut attributes = expr(context, exp, expected_type)?;
     match &attributes.typ {
-        Base(_) | Contract(_) => {
+        Base(_) | Contract(_) | Vec(_) | Function(_) => {
             if attributes.location != Location::Value {
                 attributes.move_location = Some(Location::Value);
             }
         }
-        Array(_) | Tuple(_) | String(_) | Struct(_) => {
+        Array(_) | Tuple(_) | String(_) | Struct(_) | Enum(_) => {
             if attributes.final_location() != Location::Memory {
                 context.fancy_error(
                     "value must be copied to memory",
@@ -341,6 +450,9 @@ fn expr_named_thing(
                 )))
             }
         }
+        Some(NamedThing::Item(Item::Function(function))) => {
+            expr_function_ref(context, exp, function)
+        }
         Some(NamedThing::Item(Item::Constant(id))) => {
             let typ = id
                 .typ(context.db())?
@@ -349,6 +461,32 @@ fn expr_named_thing(
             let location = Location::assign_location(&typ);
             Ok(ExpressionAttributes::new(typ.into(), location))
         }
+        Some(NamedThing::Item(Item::EnumVariant(variant))) => {
+            if !variant.is_unit(context.db()) {
+                let field_count = variant.fields(context.db()).len();
+                context.fancy_error(
+                    &format!("`{}` is missing its field values", exp.kind),
+                    vec![Label::primary(
+                        exp.span,
+                        format!(
+                            "`{}` has {} {}",
+                            exp.kind,
+                            field_count,
+                            pluralize_conditionally("field", field_count)
+                        ),
+                    )],
+                    vec![format!(
+                        "Hint: construct the variant by calling it, e.g. `{}(..)`",
+                        exp.kind
+                    )],
+                );
+            }
+            let enum_id = variant.enum_id(context.db());
+            Ok(ExpressionAttributes::new(
+                Type::Enum(enum_id.typ(context.db())),
+                Location::Memory,
+            ))
+        }
         Some(item) => {
             let item_kind = item.item_kind_display_name();
             let diag = if let Some(def_span) = item.name_span(context.db()) {
@@ -400,6 +538,54 @@ fn expr_named_thing(
     }
 }
 
+/// Maps the name of a function that's used as a value, e.g. `double` in
+/// `let op: fn(u256) -> u256 = double`.
+fn expr_function_ref(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+    function: FunctionId,
+) -> Result<ExpressionAttributes, FatalError> {
+    let name = function.name(context.db());
+    check_for_call_to_special_fns(context, &name, exp.span)?;
+
+    let kind = if function.is_generic(context.db()) {
+        Some("generic")
+    } else if function.overloads(context.db()).is_some() {
+        Some("overloaded")
+    } else if function.is_unsafe(context.db()) {
+        Some("unsafe")
+    } else {
+        None
+    };
+    if let Some(kind) = kind {
+        let name_span = function.name_span(context.db());
+        return Err(FatalError::new(context.fancy_error(
+            &format!("{} function `{}` can't be used as a value", kind, name),
+            vec![
+                Label::primary(exp.span, "used here as a value"),
+                Label::secondary(name_span, format!("`{}` is defined here", name)),
+            ],
+            vec![],
+        )));
+    }
+    if !context.is_in_function() {
+        return Err(FatalError::new(context.error(
+            "function reference outside function",
+            exp.span,
+            "a function can only be used as a value inside a function",
+        )));
+    }
+    check_init_only_call(context, function, exp.span);
+    check_feature(context, Feature::FunctionTypes, exp.span);
+
+    let typ = function.signature(context.db()).function_type()?;
+    context.add_function_ref(exp, function);
+    Ok(ExpressionAttributes::new(
+        Type::Function(typ),
+        Location::Value,
+    ))
+}
+
 fn expr_str(
     context: &mut dyn AnalyzerContext,
     exp: &Node<fe::Expr>,
@@ -499,7 +685,7 @@ fn expr_subscript(
                     return Err(FatalError::new(context.fancy_error(
                         &format!("`{}` type is not subscriptable", value_attributes.typ),
                         vec![Label::primary(value.span, "unsubscriptable type")],
-                        vec!["Note: Only arrays and maps are subscriptable".into()],
+                        vec!["Note: Only arrays, vecs and maps are subscriptable".into()],
                     )));
                 }
                 Err(IndexingError::WrongIndexType) => {
@@ -515,9 +701,17 @@ fn expr_subscript(
                 Ok(val) => val,
             };
 
+        if let Type::Array(array) = &value_attributes.typ {
+            if context.is_in_function() && is_in_bounds(index, array.size) {
+                context.add_in_bounds_index(exp);
+            }
+        }
+
         let location = match value_attributes.location {
             Location::Storage { .. } => Location::Storage { nonce: None },
             Location::Memory => Location::Memory,
+            // a vec value points to its items in memory
+            Location::Value if matches!(value_attributes.typ, Type::Vec(_)) => Location::Memory,
             // neither maps or arrays can be stored as values, so this is unreachable
             Location::Value => unreachable!(),
         };
@@ -528,6 +722,27 @@ fn expr_subscript(
     unreachable!()
 }
 
+/// Returns `true` if the value of an array index is known to be less than the
+/// size of the array: if it's a number literal that's less than the size, or
+/// the remainder of a division by a number literal that's at most the size
+/// (e.g. `i % 4`). Arrays are indexed with `u256` values, so the remainder
+/// can't be negative.
+fn is_in_bounds(index: &Node<fe::Expr>, size: usize) -> bool {
+    let size = BigInt::from(size);
+    let literal = |expr: &Node<fe::Expr>| match &expr.kind {
+        fe::Expr::Num(num) => numeric::Literal::new(num).parse::<BigInt>().ok(),
+        _ => None,
+    };
+
+    match &index.kind {
+        fe::Expr::Num(_) => matches!(literal(index), Some(value) if value < size),
+        fe::Expr::BinOperation { op, right, .. } if op.kind == fe::BinOperator::Mod => {
+            matches!(literal(right), Some(divisor) if divisor <= size)
+        }
+        _ => false,
+    }
+}
+
 fn expr_attribute(
     context: &mut dyn AnalyzerContext,
     exp: &Node<fe::Expr>,
@@ -543,7 +758,17 @@ fn expr_attribute(
     // checking. This will all go away when the `Context` struct is ready.
 
     if let fe::Expr::Name(name) = &target.kind {
-        match GlobalObject::from_str(name) {
+        let object = GlobalObject::from_str(name);
+        // Apart from `msg.sig`, the fields of the global objects depend on the
+        // state of the chain.
+        if object.is_ok()
+            && !(object == Ok(GlobalObject::Msg)
+                && matches!(MsgField::from_str(&field.kind), Ok(MsgField::Sig)))
+            && context.is_in_function()
+        {
+            context.add_state_read(exp)
+        }
+        match object {
             Ok(GlobalObject::Block) => {
                 return match BlockField::from_str(&field.kind) {
                     Ok(BlockField::Coinbase) => base_type(Base::Address),
@@ -577,7 +802,13 @@ fn expr_attribute(
             Ok(GlobalObject::Msg) => {
                 return match MsgField::from_str(&field.kind) {
                     Ok(MsgField::Sender) => base_type(Base::Address),
-                    Ok(MsgField::Sig | MsgField::Value) => base_type(U256),
+                    Ok(MsgField::Sig) => base_type(U256),
+                    Ok(MsgField::Value) => {
+                        if context.is_in_function() {
+                            check_value_is_payable(context, exp);
+                        }
+                        base_type(U256)
+                    }
                     Err(_) => {
                         Err(FatalError::new(context.fancy_error(
                             "Not a `msg` field",
@@ -615,6 +846,10 @@ fn expr_attribute(
     let attrs = expr(context, target, None)?;
     return match attrs.typ {
         Type::SelfContract(contract) => {
+            // Both the address and the fields of the contract are part of the
+            // state.
+            context.add_state_read(exp);
+
             // Check built-in `.address` field first. (This will go away soon.)
             if let Ok(ContractSelfField::Address) = ContractSelfField::from_str(&field.kind) {
                 return Ok(ExpressionAttributes::new(
@@ -733,7 +968,20 @@ fn expr_bin_operation(
             _ => (expected_type.map(Type::int), expected_type.map(Type::int)),
         };
 
-        let left_attributes = value_expr(context, left, left_expected.as_ref())?;
+        let left_attributes = expr(context, left, left_expected.as_ref())?;
+        if let Type::Struct(struct_) = &left_attributes.typ {
+            return expr_operator_overload(
+                context,
+                struct_.id,
+                left,
+                left_attributes,
+                op.kind.to_string(),
+                op.span,
+                bin_operator_method(op.kind),
+                right,
+            );
+        }
+        let left_attributes = move_to_stack(context, left, left_attributes)?;
         let right_attributes = value_expr(context, right, right_expected.as_ref())?;
 
         let typ = match operations::bin(&left_attributes.typ, &op.kind, &right_attributes.typ) {
@@ -826,27 +1074,52 @@ fn expr_call(
     func: &Node<fe::Expr>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    base: Option<&Node<fe::Expr>>,
 ) -> Result<ExpressionAttributes, FatalError> {
     let (attributes, call_type) = match &func.kind {
-        fe::Expr::Name(name) => expr_call_name(context, name, func, generic_args, args)?,
-        fe::Expr::Path(path) => expr_call_path(context, path, func, generic_args, args)?,
+        fe::Expr::Name(name) if is_function_variable(context, name) => {
+            let func_attributes = expr(context, func, None)?;
+            expr_call_function_value(context, func, func_attributes, generic_args, args)?
+        }
+        fe::Expr::Name(name) => expr_call_name(context, name, func, generic_args, args, base)?,
+        fe::Expr::Path(path) => expr_call_path(context, path, func, generic_args, args, base)?,
         fe::Expr::Attribute { value, attr } => {
             // TODO: err if there are generic args
             expr_call_method(context, value, attr, generic_args, args)?
         }
         _ => {
             let expression = expr(context, func, None)?;
-            return Err(FatalError::new(context.fancy_error(
-                &format!("`{}` type is not callable", expression.typ),
-                vec![Label::primary(
-                    func.span,
-                    format!("this has type `{}`", expression.typ),
-                )],
-                vec![],
-            )));
+            if let Type::Function(_) = expression.typ {
+                expr_call_function_value(context, func, expression, generic_args, args)?
+            } else {
+                return Err(FatalError::new(context.fancy_error(
+                    &format!("`{}` type is not callable", expression.typ),
+                    vec![Label::primary(
+                        func.span,
+                        format!("this has type `{}`", expression.typ),
+                    )],
+                    vec![],
+                )));
+            }
         }
     };
 
+    if let Some(base) = base {
+        if !matches!(call_type, CallType::TypeConstructor(Type::Struct(_))) {
+            context.fancy_error(
+                "struct update syntax can only be used to construct a struct",
+                vec![
+                    Label::primary(base.span, "this can't be used here"),
+                    Label::secondary(
+                        func.span,
+                        format!("`{}` isn't a struct", call_type.function_name(context.db())),
+                    ),
+                ],
+                vec![],
+            );
+        }
+    }
+
     if !context.inherits_type(BlockScopeType::Unsafe) && call_type.is_unsafe(context.db()) {
         let mut labels = vec![Label::primary(func.span, "call to unsafe function")];
         let fn_name = call_type.function_name(context.db());
@@ -867,7 +1140,7 @@ fn expr_call(
 
     if context.is_in_function() {
         context.add_call(func, call_type);
-    } else {
+    } else if !is_const_call(&call_type, args) {
         context.error(
             "calling function outside function",
             func.span,
@@ -878,15 +1151,91 @@ fn expr_call(
     Ok(attributes)
 }
 
+/// Returns `true` if the name refers to a variable of a function type.
+fn is_function_variable(context: &dyn AnalyzerContext, name: &str) -> bool {
+    matches!(
+        context.resolve_name(name),
+        Ok(Some(NamedThing::Variable {
+            typ: Ok(FixedSize::Function(_)),
+            ..
+        }))
+    )
+}
+
+/// Calls a value of a function type, e.g. `op(x, y)` where `op` has the type
+/// `fn(u256, u256) -> u256`.
+fn expr_call_function_value(
+    context: &mut dyn AnalyzerContext,
+    func: &Node<fe::Expr>,
+    func_attributes: ExpressionAttributes,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    let typ = match func_attributes.typ {
+        Type::Function(typ) => typ,
+        _ => unreachable!(),
+    };
+    if let Some(generic_args) = generic_args {
+        context.fancy_error(
+            &format!("`{}` type is not generic", typ),
+            vec![Label::primary(
+                generic_args.span,
+                "unexpected generic argument list",
+            )],
+            vec![],
+        );
+    }
+    for index in 0..args.kind.len() {
+        expect_no_label_on_arg(context, args, index);
+    }
+
+    let name = func.kind.to_string();
+    validate_fn_args(context, &name, func.span, args, &typ.params)?;
+
+    let return_type = (*typ.return_type).clone();
+    let return_location = Location::assign_location(&return_type);
+    Ok((
+        ExpressionAttributes::new(return_type.into(), return_location),
+        CallType::FunctionValue(typ),
+    ))
+}
+
+/// Returns true if the call can be evaluated at compile time, which is only
+/// the case for `keccak256` of a string literal.
+fn is_const_call(call_type: &CallType, args: &Node<Vec<Node<fe::CallArg>>>) -> bool {
+    matches!(
+        call_type,
+        CallType::BuiltinFunction(GlobalFunction::Keccak256)
+    ) && matches!(
+        args.kind.as_slice(),
+        [arg] if matches!(arg.kind.value.kind, fe::Expr::Str(_))
+    )
+}
+
 fn expr_call_name<T: std::fmt::Display>(
     context: &mut dyn AnalyzerContext,
     name: &str,
     func: &Node<T>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    base: Option<&Node<fe::Expr>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     check_for_call_to_special_fns(context, name, func.span)?;
 
+    if let Some(typ) = context.resolve_type_param(name) {
+        if let Some(args) = generic_args {
+            context.fancy_error(
+                &format!("`{}` type is not generic", name),
+                vec![Label::primary(
+                    args.span,
+                    "unexpected generic argument list",
+                )],
+                vec![],
+            );
+        }
+        return expr_call_type_constructor(context, typ.into(), func.span, args, base);
+    }
+
     let named_thing = context.resolve_name(name)?.ok_or_else(|| {
         // Check for call to a fn in the current class that takes self.
         if context.is_in_function() {
@@ -929,7 +1278,7 @@ fn expr_call_name<T: std::fmt::Display>(
         }
     })?;
 
-    expr_call_named_thing(context, named_thing, func, generic_args, args)
+    expr_call_named_thing(context, named_thing, func, generic_args, args, base)
 }
 
 fn expr_call_path<T: std::fmt::Display>(
@@ -938,7 +1287,23 @@ fn expr_call_path<T: std::fmt::Display>(
     func: &Node<T>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    base: Option<&Node<fe::Expr>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    if let [typ, function] = path.segments.as_slice() {
+        if let Ok(Some(NamedThing::Item(Item::Type(TypeDef::Primitive(Base::Numeric(integer)))))) =
+            context.resolve_name(&typ.kind)
+        {
+            return expr_call_integer_conversion(
+                context,
+                integer,
+                func.span,
+                function,
+                generic_args,
+                args,
+            );
+        }
+    }
+
     let named_thing = context.resolve_path(path).ok_or_else(|| {
         FatalError::new(context.error(
             &format!("`{}` is not defined", func.kind),
@@ -947,7 +1312,59 @@ fn expr_call_path<T: std::fmt::Display>(
         ))
     })?;
 
-    expr_call_named_thing(context, named_thing, func, generic_args, args)
+    expr_call_named_thing(context, named_thing, func, generic_args, args, base)
+}
+
+fn expr_call_integer_conversion(
+    context: &mut dyn AnalyzerContext,
+    to: Integer,
+    name_span: Span,
+    function: &Node<SmolStr>,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    let name = format!("{}::{}", to, function.kind);
+    let function = IntegerTypeMethod::from_str(&function.kind).map_err(|_| {
+        FatalError::new(context.fancy_error(
+            &format!("`{}` is not defined", name),
+            vec![Label::primary(function.span, "undefined function")],
+            vec![format!(
+                "Hint: integers can be converted with `{to}::try_from(..)` or `{to}::unchecked_from(..)`",
+                to = to
+            )],
+        ))
+    })?;
+
+    if let Some(args) = generic_args {
+        context.error(
+            &format!("`{}` function does not expect generic arguments", name),
+            args.span,
+            "unexpected generic argument list",
+        );
+    }
+
+    if let Some(diag) = validate_arg_count(context, &name, name_span, args, 1, "argument") {
+        return Err(FatalError::new(diag));
+    }
+    expect_no_label_on_arg(context, args, 0);
+
+    let arg = &args.kind[0];
+    let arg_attr = assignable_expr(context, &arg.kind.value, None)?;
+    let from = match arg_attr.typ {
+        Type::Base(Base::Numeric(from)) => from,
+        _ => {
+            return Err(FatalError::new(context.error(
+                "type mismatch",
+                arg.span,
+                &format!("expected a numeric type but was `{}`", arg_attr.typ),
+            )))
+        }
+    };
+
+    Ok((
+        ExpressionAttributes::new(Type::Base(Base::Numeric(to)), Location::Value),
+        CallType::BuiltinIntegerConversion { function, from, to },
+    ))
 }
 
 fn expr_call_named_thing<T: std::fmt::Display>(
@@ -956,17 +1373,34 @@ fn expr_call_named_thing<T: std::fmt::Display>(
     func: &Node<T>,
     generic_args: &Option<Node<Vec<fe::GenericArg>>>,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    base: Option<&Node<fe::Expr>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     match named_thing {
         NamedThing::Item(Item::BuiltinFunction(function)) => {
             expr_call_builtin_function(context, function, func.span, generic_args, args)
         }
         NamedThing::Item(Item::Intrinsic(function)) => {
+            check_evm_version(
+                context,
+                function.as_ref(),
+                function.min_evm_version(),
+                func.span,
+            );
             expr_call_intrinsic(context, function, func.span, generic_args, args)
         }
         NamedThing::Item(Item::Function(function)) => {
+            if let Some(overloads) = function.overloads(context.db()) {
+                return expr_call_overloaded(context, &overloads, func.span, generic_args, args);
+            }
+            check_init_only_call(context, function, func.span);
+            check_std_function_evm_version(context, function, func.span);
             expr_call_pure(context, function, generic_args, args)
         }
+        NamedThing::Item(Item::Type(TypeDef::Struct(id))) if id.is_generic(context.db()) => {
+            let concrete_type =
+                apply_generic_struct_args(context, id, func.span, generic_args.as_ref())?;
+            expr_call_type_constructor(context, concrete_type, func.span, args, base)
+        }
         NamedThing::Item(Item::Type(id)) => {
             if let Some(args) = generic_args {
                 context.fancy_error(
@@ -979,12 +1413,15 @@ fn expr_call_named_thing<T: std::fmt::Display>(
                 );
             }
             let typ = id.typ(context.db())?;
-            expr_call_type_constructor(context, typ, func.span, args)
+            expr_call_type_constructor(context, typ, func.span, args, base)
         }
         NamedThing::Item(Item::GenericType(generic)) => {
             let concrete_type =
                 apply_generic_type_args(context, generic, func.span, generic_args.as_ref())?;
-            expr_call_type_constructor(context, concrete_type, func.span, args)
+            expr_call_type_constructor(context, concrete_type, func.span, args, base)
+        }
+        NamedThing::Item(Item::EnumVariant(variant)) => {
+            expr_call_enum_constructor(context, variant, func, generic_args, args)
         }
 
         // Nothing else is callable (for now at least)
@@ -1062,17 +1499,37 @@ fn expr_call_builtin_function(
     args: &Node<Vec<Node<fe::CallArg>>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if let Some(args) = generic_args {
-        context.error(
-            &format!(
-                "`{}` function does not expect generic arguments",
-                function.as_ref()
-            ),
-            args.span,
-            "unexpected generic argument list",
-        );
+        if function != GlobalFunction::AbiDecode {
+            context.error(
+                &format!(
+                    "`{}` function does not expect generic arguments",
+                    function.as_ref()
+                ),
+                args.span,
+                "unexpected generic argument list",
+            );
+        }
     }
 
-    let argument_attributes = expr_call_args(context, args)?;
+    let argument_attributes = match (function, args.kind.as_slice()) {
+        // The hash of a string literal is computed at compile time, so it's
+        // allowed in module constants, where string literals aren't otherwise.
+        (GlobalFunction::Keccak256, [arg]) if !context.is_in_function() => {
+            if let fe::Expr::Str(string) = &arg.kind.value.kind {
+                let attributes = ExpressionAttributes::new(
+                    Type::String(FeString {
+                        max_size: string.len(),
+                    }),
+                    Location::Memory,
+                );
+                context.add_expression(&arg.kind.value, attributes.clone());
+                vec![attributes]
+            } else {
+                expr_call_args(context, args)?
+            }
+        }
+        _ => expr_call_args(context, args)?,
+    };
 
     let attrs = match function {
         GlobalFunction::Keccak256 => {
@@ -1080,43 +1537,150 @@ fn expr_call_builtin_function(
             expect_no_label_on_arg(context, args, 0);
 
             if let Some(arg_typ) = argument_attributes.first().map(|attr| &attr.typ) {
-                if !matches!(
-                    arg_typ,
+                let is_const_string =
+                    matches!(arg_typ, Type::String(_)) && !context.is_in_function();
+                match arg_typ {
                     Type::Array(Array {
                         inner: Base::Numeric(Integer::U8),
                         ..
-                    })
-                ) {
-                    context.fancy_error(
-                        &format!(
-                            "`{}` can not be used as an argument to `{}`",
-                            arg_typ,
-                            function.as_ref(),
-                        ),
-                        vec![Label::primary(args.span, "wrong type")],
-                        vec![format!(
-                            "Note: `{}` expects a byte array argument",
-                            function.as_ref()
-                        )],
-                    );
+                    }) => {}
+                    _ if is_const_string => {}
+                    // Other compound values are hashed by their abi encoding,
+                    // like `keccak256(abi.encode(value))` in Solidity.
+                    Type::Array(_) | Type::Tuple(_) | Type::Struct(_) => {
+                        let span = args.kind[0].kind.value.span;
+                        let typ = abi_coding_type(context, function, arg_typ, span)?;
+                        return Ok((
+                            ExpressionAttributes::new(Type::Base(U256), Location::Value),
+                            CallType::BuiltinAbiFunction { function, typ },
+                        ));
+                    }
+                    _ => {
+                        context.fancy_error(
+                            &format!(
+                                "`{}` can not be used as an argument to `{}`",
+                                arg_typ,
+                                function.as_ref(),
+                            ),
+                            vec![Label::primary(args.span, "wrong type")],
+                            vec![format!(
+                                "Note: `{}` expects a byte array, or an array, tuple or struct to hash the abi encoding of",
+                                function.as_ref()
+                            )],
+                        );
+                    }
                 }
             };
             ExpressionAttributes::new(Type::Base(U256), Location::Value)
         }
+        GlobalFunction::AbiEncode => {
+            if let Some(voucher) =
+                validate_arg_count(context, function.as_ref(), name_span, args, 1, "argument")
+            {
+                return Err(FatalError::new(voucher));
+            }
+            expect_no_label_on_arg(context, args, 0);
+
+            // Values in storage are rejected by `expr_call_args`.
+            let value = &args.kind[0].kind.value;
+            let value_type = &argument_attributes[0].typ;
+            let typ = abi_coding_type(context, function, value_type, value.span)?;
+            return Ok((
+                ExpressionAttributes::new(
+                    Type::Vec(FeVec {
+                        inner: Base::Numeric(Integer::U8),
+                    }),
+                    Location::Value,
+                ),
+                CallType::BuiltinAbiFunction { function, typ },
+            ));
+        }
+        GlobalFunction::AbiDecode => {
+            let type_node =
+                match generic_args.as_ref().map(|args| args.kind.as_slice()) {
+                    Some([fe::GenericArg::TypeDesc(type_node)]) => type_node,
+                    Some(_) | None => return Err(FatalError::new(context.fancy_error(
+                        "`abi_decode` expects the type of the decoded value as a generic argument",
+                        vec![Label::primary(name_span, "missing type")],
+                        vec!["Example: `abi_decode<(u256, bool)>(data)`".into()],
+                    ))),
+                };
+            let typ = type_desc(context, type_node)?;
+            let typ = abi_coding_type(context, function, &typ, type_node.span)?;
+            if let Some(voucher) =
+                validate_arg_count(context, function.as_ref(), name_span, args, 1, "argument")
+            {
+                return Err(FatalError::new(voucher));
+            }
+            expect_no_label_on_arg(context, args, 0);
+
+            let data = &args.kind[0].kind.value;
+            match &argument_attributes[0].typ {
+                Type::Vec(FeVec {
+                    inner: Base::Numeric(Integer::U8),
+                }) => {}
+                data_typ => {
+                    context.type_error(
+                        &format!("incorrect type for `{}` argument", function.as_ref()),
+                        data.span,
+                        &Type::Vec(FeVec {
+                            inner: Base::Numeric(Integer::U8),
+                        }),
+                        data_typ,
+                    );
+                }
+            }
+            let return_type = Type::from(typ.clone());
+            let location = Location::assign_location(&typ);
+            return Ok((
+                ExpressionAttributes::new(return_type, location),
+                CallType::BuiltinAbiFunction { function, typ },
+            ));
+        }
     };
     Ok((attrs, CallType::BuiltinFunction(function)))
 }
 
-fn expr_call_intrinsic(
+/// Checks that values of `typ` can be abi encoded and decoded by
+/// `abi_encode` and `abi_decode`, or hashed by `keccak256`.
+fn abi_coding_type(
     context: &mut dyn AnalyzerContext,
-    function: Intrinsic,
-    name_span: Span,
-    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
-    args: &Node<Vec<Node<fe::CallArg>>>,
-) -> Result<(ExpressionAttributes, CallType), FatalError> {
-    if let Some(args) = generic_args {
-        context.error(
-            &format!(
+    function: GlobalFunction,
+    typ: &Type,
+    span: Span,
+) -> Result<FixedSize, FatalError> {
+    match FixedSize::try_from(typ.clone()) {
+        Ok(typ) if typ.contains_vec(context.db()) => Err(FatalError::new(context.error(
+            &format!("`{}` doesn't support values of type `{}`", function.as_ref(), typ),
+            span,
+            "this type holds a `Vec`, which can't be abi encoded",
+        ))),
+        Ok(
+            typ @ (FixedSize::Base(Base::Numeric(_) | Base::Bool | Base::Address)
+            | FixedSize::Array(_)
+            | FixedSize::Tuple(_)
+            | FixedSize::String(_)
+            | FixedSize::Contract(_)
+            | FixedSize::Struct(_)),
+        ) => Ok(typ),
+        _ => Err(FatalError::new(context.fancy_error(
+            &format!("`{}` doesn't support values of type `{}`", function.as_ref(), typ),
+            vec![Label::primary(span, "this type can't be abi encoded")],
+            vec!["Note: numbers, booleans, addresses, arrays, strings, tuples, structs and contracts can be abi encoded".into()],
+        ))),
+    }
+}
+
+fn expr_call_intrinsic(
+    context: &mut dyn AnalyzerContext,
+    function: Intrinsic,
+    name_span: Span,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    if let Some(args) = generic_args {
+        context.error(
+            &format!(
                 "`{}` function does not expect generic arguments",
                 function.as_ref()
             ),
@@ -1159,6 +1723,56 @@ fn expr_call_pure(
     args: &Node<Vec<Node<fe::CallArg>>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     let fn_name = function.name(context.db());
+    let name_span = function.name_span(context.db());
+    let function = if function.is_generic(context.db()) {
+        match generic_args {
+            Some(generic_args) => {
+                let function = instantiate_generic_function(context, function, generic_args)?;
+                let sig = function.signature(context.db());
+                validate_fn_args(context, &fn_name, name_span, args, &sig.params)?;
+                function
+            }
+            None => infer_generic_function_call(context, function, args)?,
+        }
+    } else {
+        if let Some(args) = generic_args {
+            context.fancy_error(
+                &format!("`{}` function is not generic", fn_name),
+                vec![Label::primary(
+                    args.span,
+                    "unexpected generic argument list",
+                )],
+                vec![],
+            );
+        }
+        let sig = function.signature(context.db());
+        validate_fn_args(context, &fn_name, name_span, args, &sig.params)?;
+        function
+    };
+    let sig = function.signature(context.db());
+
+    check_value_transfer_recipient(context, function, args);
+
+    let return_type = sig.return_type.clone()?;
+    let return_location = Location::assign_location(&return_type);
+    Ok((
+        ExpressionAttributes::new(return_type.into(), return_location),
+        CallType::Pure(function),
+    ))
+}
+
+/// Checks a call to an overloaded function, and chooses the overload whose
+/// parameters the arguments can be passed to. The arguments are checked
+/// before an overload is chosen, so they can't take their type from the
+/// parameters, except for integer literals, which are checked afterwards.
+fn expr_call_overloaded(
+    context: &mut dyn AnalyzerContext,
+    overloads: &[FunctionId],
+    func_span: Span,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    let fn_name = overloads[0].name(context.db());
     if let Some(args) = generic_args {
         context.fancy_error(
             &format!("`{}` function is not generic", fn_name),
@@ -1170,16 +1784,75 @@ fn expr_call_pure(
         );
     }
 
-    let sig = function.signature(context.db());
+    let mut arg_attrs = vec![];
+    for arg in &args.kind {
+        arg_attrs.push(if is_int_literal(&arg.kind.value) {
+            None
+        } else {
+            Some(assignable_expr(context, &arg.kind.value, None)?)
+        });
+    }
+
+    let db = context.db();
+    let matching = overloads
+        .iter()
+        .copied()
+        .filter(|function| {
+            !function.is_generic(db) && overload_accepts_args(db, *function, args, &arg_attrs)
+        })
+        .collect::<Vec<_>>();
+    let function = match matching.as_slice() {
+        [function] => *function,
+        _ => {
+            let arg_types = args
+                .kind
+                .iter()
+                .zip(&arg_attrs)
+                .map(|(arg, attrs)| match attrs {
+                    Some(attrs) => attrs.typ.to_string(),
+                    None => format!("{{integer}} {}", arg.kind.value.kind),
+                })
+                .collect::<Vec<_>>();
+            let candidates = if matching.is_empty() {
+                overloads
+            } else {
+                &matching
+            };
+            let mut notes = candidates
+                .iter()
+                .map(|function| format!("Note: candidate: `{}`", overload_signature(db, *function)))
+                .collect::<Vec<_>>();
+            let (message, label) = if matching.is_empty() {
+                (
+                    format!("no overload of `{}` matches the arguments", fn_name),
+                    "no overload takes these arguments",
+                )
+            } else {
+                notes.push(
+                    "Hint: convert integer literals to the parameter type, e.g. `u256(1)`".into(),
+                );
+                (
+                    format!("ambiguous call to overloaded function `{}`", fn_name),
+                    "more than one overload takes these arguments",
+                )
+            };
+            return Err(FatalError::new(context.fancy_error(
+                &message,
+                vec![
+                    Label::primary(func_span, label),
+                    Label::secondary(args.span, format!("({})", arg_types.join(", "))),
+                ],
+                notes,
+            )));
+        }
+    };
+
+    check_init_only_call(context, function, func_span);
+    check_std_function_evm_version(context, function, func_span);
     let name_span = function.name_span(context.db());
-    validate_named_args(
-        context,
-        &fn_name,
-        name_span,
-        args,
-        &sig.params,
-        LabelPolicy::AllowAnyUnlabeled,
-    )?;
+    let sig = function.signature(context.db());
+    validate_checked_fn_args(context, &fn_name, name_span, args, &sig.params, arg_attrs)?;
+    check_value_transfer_recipient(context, function, args);
 
     let return_type = sig.return_type.clone()?;
     let return_location = Location::assign_location(&return_type);
@@ -1189,15 +1862,479 @@ fn expr_call_pure(
     ))
 }
 
+/// Returns `true` if the arguments of a call can be passed to the parameters
+/// of `function`. `arg_attrs` holds the attributes of the arguments, or
+/// `None` for an integer literal, which can be passed to any parameter of an
+/// integer type that its value fits in.
+fn overload_accepts_args(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    arg_attrs: &[Option<ExpressionAttributes>],
+) -> bool {
+    let sig = function.signature(db);
+    let param_indices = match fn_args_fit_params(&args.kind, &sig.params) {
+        Some(indices) => indices,
+        None => return false,
+    };
+    param_indices
+        .iter()
+        .zip(args.kind.iter().zip(arg_attrs))
+        .all(
+            |(index, (arg, attrs))| match (&sig.params[*index].typ, attrs) {
+                (Ok(param_type), Some(attrs)) => *param_type == attrs.typ,
+                (Ok(FixedSize::Base(Base::Numeric(integer))), None) => {
+                    match int_literal_value(&arg.kind.value) {
+                        Some(value) => integer.fits(value),
+                        None => true,
+                    }
+                }
+                _ => false,
+            },
+        )
+}
+
+/// The signature of an overloaded function, as it's shown in diagnostics,
+/// e.g. `min(a: u256, b: u256) -> u256`.
+fn overload_signature(db: &dyn AnalyzerDb, function: FunctionId) -> String {
+    let sig = function.signature(db);
+    let params = sig
+        .params
+        .iter()
+        .map(|param| match &param.typ {
+            Ok(typ) => format!("{}: {}", param.name, typ),
+            Err(_) => param.name.to_string(),
+        })
+        .collect::<Vec<_>>();
+    match &sig.return_type {
+        Ok(typ) if !typ.is_unit() => {
+            format!("{}({}) -> {}", function.name(db), params.join(", "), typ)
+        }
+        _ => format!("{}({})", function.name(db), params.join(", ")),
+    }
+}
+
+/// Returns the instance of a generic function with the explicitly given generic
+/// args, e.g. `make_pair<u8, bool>(..)`.
+fn instantiate_generic_function(
+    context: &mut dyn AnalyzerContext,
+    function: FunctionId,
+    generic_args: &Node<Vec<fe::GenericArg>>,
+) -> Result<FunctionId, FatalError> {
+    check_generic_function_module(context, function, generic_args.span)?;
+    let db = context.db();
+    let fn_name = function.name(db);
+    let params = function
+        .generic_params(db)
+        .iter()
+        .map(|name| GenericParam {
+            name: format!("type parameter `{}`", name).into(),
+            kind: GenericParamKind::FixedSizeType,
+        })
+        .collect::<Vec<_>>();
+    let args = check_generic_args(
+        context,
+        &fn_name,
+        &params,
+        generic_args.span,
+        Some(generic_args),
+    )?;
+    Ok(function.instantiate(context.db(), fixed_size_args(args)))
+}
+
+/// Checks the args of a call to a generic function that has no explicit
+/// generic args, and returns the instance of the function with the generic
+/// args that are inferred from the types of the call args.
+fn infer_generic_function_call(
+    context: &mut dyn AnalyzerContext,
+    function: FunctionId,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<FunctionId, FatalError> {
+    check_generic_function_module(context, function, args.span)?;
+    let db = context.db();
+    let fn_name = function.name(db);
+    let name_span = function.name_span(db);
+    let generic_params = function.generic_params(db);
+    let param_descs = function
+        .data(db)
+        .ast
+        .kind
+        .args
+        .iter()
+        .filter_map(|arg| match &arg.kind {
+            fe::FunctionArg::Regular(arg) => Some(arg.typ.clone()),
+            fe::FunctionArg::Zelf { .. } => None,
+        })
+        .collect::<Vec<_>>();
+    let generic_sig = function.signature(context.db());
+    let param_indices =
+        validate_fn_arg_count(context, &fn_name, name_span, args, &generic_sig.params);
+
+    // The args whose parameter types mention a generic parameter are checked
+    // first, in order, to infer the generic args.
+    let mut bindings = HashMap::<SmolStr, FixedSize>::new();
+    let mut arg_types = vec![None; args.kind.len()];
+    for (index, (param_index, arg)) in param_indices.iter().zip(args.kind.iter()).enumerate() {
+        let desc = match param_descs.get(*param_index) {
+            Some(desc) if mentions_generic_param(&desc.kind, &generic_params) => desc,
+            _ => continue,
+        };
+        let expected_type = match &desc.kind {
+            fe::TypeDesc::Base { base } => bindings.get(base).cloned().map(Type::from),
+            _ => None,
+        };
+        let typ = assignable_expr(context, &arg.kind.value, expected_type.as_ref())?.typ;
+        infer_generic_args(&desc.kind, &typ, &generic_params, &mut bindings);
+        arg_types[index] = Some(typ);
+    }
+
+    let mut generic_args = vec![];
+    for param in generic_params.iter() {
+        match bindings.get(param) {
+            Some(typ) => generic_args.push(typ.clone()),
+            None => {
+                return Err(FatalError::new(context.fancy_error(
+                    &format!(
+                        "can't infer the type of generic parameter `{}` of `{}`",
+                        param, fn_name
+                    ),
+                    vec![Label::primary(args.span, "")],
+                    vec![format!(
+                        "Hint: provide the generic arguments explicitly, e.g. `{}<{}>(..)`",
+                        fn_name,
+                        vec!["u256"; generic_params.len()].join(", ")
+                    )],
+                )))
+            }
+        }
+    }
+
+    let instance = function.instantiate(context.db(), generic_args);
+    let sig = instance.signature(context.db());
+    for (index, (param_index, arg)) in param_indices.iter().zip(args.kind.iter()).enumerate() {
+        let param = match sig.params.get(*param_index) {
+            Some(param) => param,
+            None => continue,
+        };
+        let param_type = param.typ.clone()?;
+        let arg_type = match arg_types[index].take() {
+            Some(typ) => typ,
+            None => {
+                assignable_expr(context, &arg.kind.value, Some(&param_type.clone().into()))?.typ
+            }
+        };
+        if param_type != arg_type {
+            context.type_error(
+                &format!("incorrect type for `{}` argument `{}`", fn_name, param.name),
+                arg.kind.value.span,
+                &param_type,
+                &arg_type,
+            );
+        }
+    }
+    Ok(instance)
+}
+
+fn check_generic_function_module(
+    context: &mut dyn AnalyzerContext,
+    function: FunctionId,
+    span: Span,
+) -> Result<(), FatalError> {
+    if context_module(context) == Some(function.module(context.db())) {
+        Ok(())
+    } else {
+        Err(FatalError::new(context.not_yet_implemented(
+            "calling generic functions that are defined in another module",
+            span,
+        )))
+    }
+}
+
+/// Returns `true` if the type description refers to any of the generic
+/// parameters.
+fn mentions_generic_param(desc: &fe::TypeDesc, params: &[SmolStr]) -> bool {
+    match desc {
+        fe::TypeDesc::Base { base } => params.contains(base),
+        fe::TypeDesc::Generic { args, .. } => args.kind.iter().any(|arg| match arg {
+            fe::GenericArg::TypeDesc(desc) => mentions_generic_param(&desc.kind, params),
+            fe::GenericArg::Int(_) | fe::GenericArg::ConstExpr(_) => false,
+        }),
+        fe::TypeDesc::Tuple { items } => items
+            .iter()
+            .any(|item| mentions_generic_param(&item.kind, params)),
+        fe::TypeDesc::Function {
+            params: param_descs,
+            return_type,
+        } => param_descs
+            .iter()
+            .chain(return_type.as_deref())
+            .any(|desc| mentions_generic_param(&desc.kind, params)),
+        fe::TypeDesc::Path(_) | fe::TypeDesc::Unit => false,
+    }
+}
+
+/// Binds the generic parameters that appear in a parameter type description
+/// to the matching parts of the type of the argument. Parameters that are
+/// already bound are left as they are; a mismatch is reported when the
+/// argument types are checked against the instantiated function signature.
+fn infer_generic_args(
+    desc: &fe::TypeDesc,
+    typ: &Type,
+    params: &[SmolStr],
+    bindings: &mut HashMap<SmolStr, FixedSize>,
+) {
+    match (desc, typ) {
+        (fe::TypeDesc::Base { base }, _) if params.contains(base) => {
+            if let Ok(typ) = FixedSize::try_from(typ.clone()) {
+                bindings.entry(base.clone()).or_insert(typ);
+            }
+        }
+        (fe::TypeDesc::Generic { base, args }, Type::Struct(struct_))
+            if base.kind == struct_.name =>
+        {
+            for (arg, typ) in args.kind.iter().zip(struct_.generic_args.iter()) {
+                if let fe::GenericArg::TypeDesc(desc) = arg {
+                    infer_generic_args(&desc.kind, &typ.clone().into(), params, bindings);
+                }
+            }
+        }
+        (fe::TypeDesc::Generic { args, .. }, Type::Array(array)) => {
+            if let Some(fe::GenericArg::TypeDesc(desc)) = args.kind.first() {
+                infer_generic_args(&desc.kind, &Type::Base(array.inner), params, bindings);
+            }
+        }
+        (fe::TypeDesc::Tuple { items }, Type::Tuple(tuple)) => {
+            for (desc, typ) in items.iter().zip(tuple.items.iter()) {
+                infer_generic_args(&desc.kind, &typ.clone().into(), params, bindings);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// If `function` is a std lib function that sends value to an address, returns
+/// the index of the recipient argument and the index of the value argument.
+fn value_transfer_params(db: &dyn AnalyzerDb, function: FunctionId) -> Option<(usize, usize)> {
+    let module = function.module(db);
+    if !module.ingot(db).is_std(db) {
+        return None;
+    }
+    match (module.name(db).as_str(), function.name(db).as_str()) {
+        ("lib", "send_value") => Some((0, 1)),
+        ("evm", "call") | ("evm", "call_code") => Some((1, 2)),
+        ("evm", "raw_call") => Some((0, 1)),
+        _ => None,
+    }
+}
+
+/// Warns if value is sent to an address that's passed into a public function
+/// and isn't checked (by an `assert`, or an `if` that returns or reverts) on
+/// every path to the send. Anyone can call a public function, so such an address
+/// is entirely under the control of the caller.
+fn check_value_transfer_recipient(
+    context: &mut dyn AnalyzerContext,
+    function: FunctionId,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) {
+    let (recipient_idx, value_idx) = match value_transfer_params(context.db(), function) {
+        Some(indices) => indices,
+        None => return,
+    };
+    if !context.is_in_function() {
+        return;
+    }
+    let db = context.db();
+    let caller = context.parent_function();
+    if caller.module(db).ingot(db).is_std(db) || !caller.is_public(db) {
+        return;
+    }
+
+    let params = &function.signature(db).params;
+    // Sending zero value is just a call.
+    if let Some(fe::Expr::Num(num)) =
+        fn_arg_for_param(&args.kind, params, value_idx).map(|arg| &arg.kind.value.kind)
+    {
+        if matches!(numeric::Literal::new(num).parse::<BigInt>(), Ok(num) if num.is_zero()) {
+            return;
+        }
+    }
+    let recipient = match fn_arg_for_param(&args.kind, params, recipient_idx) {
+        Some(arg) => &arg.kind.value,
+        None => return,
+    };
+    let name = match &recipient.kind {
+        fe::Expr::Name(name) => name,
+        _ => return,
+    };
+
+    let caller_data = caller.data(db);
+    let param_span = caller_data
+        .ast
+        .kind
+        .args
+        .iter()
+        .find_map(|arg| match &arg.kind {
+            fe::FunctionArg::Regular(param) if &param.name.kind == name => Some(param.name.span),
+            _ => None,
+        });
+    let param_span = match param_span {
+        Some(span) => span,
+        None => return,
+    };
+
+    if !name_checked_before(&caller_data.ast.kind.body, name, recipient.span) {
+        context.warning(
+            "value is sent to an unvalidated address",
+            vec![
+                Label::primary(
+                    recipient.span,
+                    format!("`{}` hasn't been checked before sending value to it", name),
+                ),
+                Label::secondary(
+                    param_span,
+                    format!(
+                        "`{}` is a parameter of public function `{}`",
+                        name,
+                        caller_data.ast.name()
+                    ),
+                ),
+            ],
+            vec![format!(
+                "Hint: check that `{}` is a valid recipient (eg. `assert self.is_payee[{}]`) before sending value to it.",
+                name, name
+            )],
+        );
+    }
+}
+
+/// Returns `true` if `name` is checked on every path to the expression at
+/// `target` in `stmts`, before the expression is evaluated: a statement that
+/// checks it dominates the expression.
+fn name_checked_before(stmts: &[Node<fe::FuncStmt>], name: &str, target: Span) -> bool {
+    for stmt in stmts {
+        if !span_contains(stmt.span, target) {
+            if stmt_checks_name(&stmt.kind, name) {
+                return true;
+            }
+            continue;
+        }
+
+        let in_block = |stmts: &[Node<fe::FuncStmt>]| {
+            stmts
+                .iter()
+                .any(|stmt| span_contains(stmt.span, target))
+                .then(|| name_checked_before(stmts, name, target))
+        };
+        return match &stmt.kind {
+            fe::FuncStmt::If { body, or_else, .. } => in_block(body)
+                .or_else(|| in_block(or_else))
+                .unwrap_or(false),
+            fe::FuncStmt::Match { arms, .. } => arms
+                .iter()
+                .find_map(|arm| in_block(&arm.kind.body))
+                .unwrap_or(false),
+            fe::FuncStmt::For { body, .. }
+            | fe::FuncStmt::While { body, .. }
+            | fe::FuncStmt::Loop { body, .. }
+            | fe::FuncStmt::Unsafe(body)
+            | fe::FuncStmt::Unchecked(body)
+            | fe::FuncStmt::Checked(body) => in_block(body).unwrap_or(false),
+            fe::FuncStmt::Try { body, catches, .. } => in_block(body)
+                .or_else(|| {
+                    catches
+                        .iter()
+                        .find_map(|clause| in_block(&clause.kind.body))
+                })
+                .unwrap_or(false),
+            _ => false,
+        };
+    }
+    false
+}
+
+/// Returns `true` if `stmt` stops the execution unless `name` passes a check:
+/// it's an `assert` on `name`, or an `if` on `name` with a branch that always
+/// returns or reverts.
+fn stmt_checks_name(stmt: &fe::FuncStmt, name: &str) -> bool {
+    match stmt {
+        fe::FuncStmt::Assert { test, .. } => expr_uses_name(test, name),
+        fe::FuncStmt::If {
+            test,
+            body,
+            or_else,
+        } => {
+            expr_uses_name(test, name)
+                && (all_paths_return_or_revert(body) || all_paths_return_or_revert(or_else))
+        }
+        fe::FuncStmt::Unsafe(body)
+        | fe::FuncStmt::Unchecked(body)
+        | fe::FuncStmt::Checked(body) => body.iter().any(|stmt| stmt_checks_name(&stmt.kind, name)),
+        _ => false,
+    }
+}
+
+fn span_contains(outer: Span, inner: Span) -> bool {
+    outer.start <= inner.start && inner.end <= outer.end
+}
+
+fn expr_uses_name(expr: &Node<fe::Expr>, name: &str) -> bool {
+    match &expr.kind {
+        fe::Expr::Name(other) => other == name,
+        fe::Expr::Ternary {
+            if_expr,
+            test,
+            else_expr,
+        } => {
+            expr_uses_name(if_expr, name)
+                || expr_uses_name(test, name)
+                || expr_uses_name(else_expr, name)
+        }
+        fe::Expr::BoolOperation { left, right, .. }
+        | fe::Expr::BinOperation { left, right, .. }
+        | fe::Expr::CompOperation { left, right, .. } => {
+            expr_uses_name(left, name) || expr_uses_name(right, name)
+        }
+        fe::Expr::ChainedComparison { left, comparisons } => {
+            expr_uses_name(left, name)
+                || comparisons
+                    .iter()
+                    .any(|(_, operand)| expr_uses_name(operand, name))
+        }
+        fe::Expr::UnaryOperation { operand, .. } => expr_uses_name(operand, name),
+        fe::Expr::Attribute { value, .. } => expr_uses_name(value, name),
+        fe::Expr::Subscript { value, index } => {
+            expr_uses_name(value, name) || expr_uses_name(index, name)
+        }
+        fe::Expr::Call { func, args, .. } => {
+            expr_uses_name(func, name)
+                || args
+                    .kind
+                    .iter()
+                    .any(|arg| expr_uses_name(&arg.kind.value, name))
+        }
+        fe::Expr::List { elts, fill } => {
+            elts.iter().any(|elt| expr_uses_name(elt, name))
+                || matches!(fill, Some(fill) if expr_uses_name(fill, name))
+        }
+        fe::Expr::Tuple { elts } => elts.iter().any(|elt| expr_uses_name(elt, name)),
+        fe::Expr::Bool(_)
+        | fe::Expr::Path(_)
+        | fe::Expr::Num(_)
+        | fe::Expr::Str(_)
+        | fe::Expr::Unit => false,
+    }
+}
+
 fn expr_call_type_constructor(
     context: &mut dyn AnalyzerContext,
     typ: Type,
     name_span: Span,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    base: Option<&Node<fe::Expr>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     match typ {
         Type::Struct(struct_type) => {
-            return expr_call_struct_constructor(context, name_span, struct_type, args)
+            return expr_call_struct_constructor(context, name_span, struct_type, args, base)
         }
         Type::Base(Base::Bool) => {
             return Err(FatalError::new(context.error(
@@ -1213,6 +2350,16 @@ fn expr_call_type_constructor(
                 "",
             )))
         }
+        Type::Enum(enum_) => {
+            return Err(FatalError::new(context.fancy_error(
+                &format!("`{}` type is not callable", enum_.name),
+                vec![Label::primary(name_span, "")],
+                vec![format!(
+                    "Hint: values of an enum type are constructed using one of its variants, e.g. `{}::Variant(..)`",
+                    enum_.name
+                )],
+            )))
+        }
         Type::Array(_) => {
             return Err(FatalError::new(context.error(
                 "`Array` type is not callable",
@@ -1220,6 +2367,44 @@ fn expr_call_type_constructor(
                 "",
             )))
         }
+        Type::Function(_) => {
+            return Err(FatalError::new(context.fancy_error(
+                &format!("`{}` type is not callable", typ),
+                vec![Label::primary(name_span, "")],
+                vec!["Hint: a value of a function type is the name of a function, e.g. `let op: fn(u256) -> u256 = double`".into()],
+            )))
+        }
+        Type::Vec(vec) => {
+            // `Vec<u8>(s)` copies the bytes of the string `s` into a new vec.
+            if let [arg] = args.kind.as_slice() {
+                expect_no_label_on_arg(context, args, 0);
+                let arg_attrs = assignable_expr(context, &arg.kind.value, None)?;
+                if vec.inner != Base::Numeric(Integer::U8)
+                    || !matches!(arg_attrs.typ, Type::String(_))
+                {
+                    context.fancy_error(
+                        &format!(
+                            "`{}` can't be created from a value of type `{}`",
+                            typ, arg_attrs.typ
+                        ),
+                        vec![Label::primary(
+                            arg.kind.value.span,
+                            format!("this has type `{}`", arg_attrs.typ),
+                        )],
+                        vec![
+                            "Note: only a `Vec<u8>` can be created from a value, which must be a string"
+                                .into(),
+                        ],
+                    );
+                }
+            } else {
+                validate_arg_count(context, &format!("{}", typ), name_span, args, 0, "argument");
+            }
+            return Ok((
+                ExpressionAttributes::new(typ.clone(), Location::Value),
+                CallType::TypeConstructor(typ),
+            ));
+        }
         _ => {}
     }
 
@@ -1230,8 +2415,13 @@ fn expr_call_type_constructor(
     let expr_attrs = match &typ {
         Type::String(string_type) => {
             if let Some(arg) = args.kind.first() {
-                assignable_expr(context, &arg.kind.value, None)?;
-                validate_str_literal_fits_type(context, &arg.kind.value, string_type);
+                let arg_attrs = assignable_expr(context, &arg.kind.value, None)?;
+                // The bytes of a `Vec<u8>` are copied into the string. The
+                // call reverts if there are more than the max size.
+                let u8 = Base::Numeric(Integer::U8);
+                if !matches!(arg_attrs.typ, Type::Vec(vec) if vec.inner == u8) {
+                    validate_str_literal_fits_type(context, &arg.kind.value, string_type);
+                }
             }
             ExpressionAttributes::new(typ.clone(), Location::Memory)
         }
@@ -1298,7 +2488,10 @@ fn expr_call_type_constructor(
         Type::Tuple(_) => unreachable!(),         // rejected in expr_call_type
         Type::Struct(_) => unreachable!(),        // handled above
         Type::Map(_) => unreachable!(),           // handled above
+        Type::Enum(_) => unreachable!(),          // handled above
         Type::Array(_) => unreachable!(),         // handled above
+        Type::Vec(_) => unreachable!(),           // handled above
+        Type::Function(_) => unreachable!(),      // handled above
         Type::SelfContract(_) => unreachable!(),  /* unnameable; contract names all become
                                                     * Type::Contract */
     };
@@ -1310,6 +2503,7 @@ fn expr_call_struct_constructor(
     name_span: Span,
     struct_: Struct,
     args: &Node<Vec<Node<fe::CallArg>>>,
+    base: Option<&Node<fe::Expr>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
     if struct_.id.has_private_field(context.db()) && !context.root_item().is_struct(&struct_.id) {
         let labels = struct_
@@ -1344,14 +2538,10 @@ fn expr_call_struct_constructor(
         .iter()
         .map(|(name, field)| (name.clone(), field.typ(db)))
         .collect::<Vec<_>>();
-    validate_named_args(
-        context,
-        &struct_.name,
-        name_span,
-        args,
-        &fields,
-        LabelPolicy::AllowUnlabeledIfNameEqual,
-    )?;
+    match base {
+        Some(base) => validate_struct_update(context, &struct_, args, base, &fields)?,
+        None => validate_named_args(context, &struct_.name, name_span, args, &fields)?,
+    }
 
     Ok((
         ExpressionAttributes::new(Type::Struct(struct_.clone()), Location::Memory),
@@ -1359,6 +2549,105 @@ fn expr_call_struct_constructor(
     ))
 }
 
+/// Validates a struct update expression like `Point(x: 1, ..p)`, which takes
+/// the fields that aren't given from the base struct `p`.
+fn validate_struct_update(
+    context: &mut dyn AnalyzerContext,
+    struct_: &Struct,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+    base: &Node<fe::Expr>,
+    fields: &[(SmolStr, Result<FixedSize, TypeError>)],
+) -> Result<(), FatalError> {
+    validate_struct_update_args(context, &struct_.name, args, fields)?;
+
+    let struct_type = Type::Struct(struct_.clone());
+    let base_attrs = assignable_expr(context, base, Some(&struct_type))?;
+    if base_attrs.typ != struct_type {
+        context.type_error(
+            "incorrect type for the base of the struct update",
+            base.span,
+            &struct_type,
+            &base_attrs.typ,
+        );
+    } else if args.kind.len() == fields.len() {
+        context.warning(
+            "the base of the struct update is never used",
+            vec![Label::primary(
+                base.span,
+                format!("every field of `{}` is already given", struct_.name),
+            )],
+            vec![format!("Hint: remove `..{}`", base.kind)],
+        );
+    }
+    Ok(())
+}
+
+fn expr_call_enum_constructor<T: std::fmt::Display>(
+    context: &mut dyn AnalyzerContext,
+    variant: EnumVariantId,
+    func: &Node<T>,
+    generic_args: &Option<Node<Vec<fe::GenericArg>>>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
+    if let Some(args) = generic_args {
+        context.fancy_error(
+            &format!("`{}` enum variant is not generic", func.kind),
+            vec![Label::primary(
+                args.span,
+                "unexpected generic argument list",
+            )],
+            vec![],
+        );
+    }
+
+    let db = context.db();
+    if variant.is_unit(db) {
+        return Err(FatalError::new(context.fancy_error(
+            &format!("`{}` is not callable", func.kind),
+            vec![Label::primary(
+                func.span,
+                format!("`{}` is an enum variant without fields", func.kind),
+            )],
+            vec![format!(
+                "Hint: use the variant without parentheses: `{}`",
+                func.kind
+            )],
+        )));
+    }
+
+    let enum_type = variant.enum_id(db).typ(db);
+    let fields = variant
+        .fields(db)
+        .iter()
+        .enumerate()
+        .map(|(idx, typ)| (variant.repr_field_name(db, idx), typ.clone()))
+        .collect::<Vec<_>>();
+
+    let name = func.kind.to_string();
+    validate_arg_count(context, &name, func.span, args, fields.len(), "argument");
+    for idx in 0..args.kind.len() {
+        expect_no_label_on_arg(context, args, idx);
+    }
+    for (field, arg) in fields.iter().zip(args.kind.iter()) {
+        let field_type = field.1.clone()?;
+        let arg_attrs =
+            assignable_expr(context, &arg.kind.value, Some(&field_type.clone().into()))?;
+        if field_type != arg_attrs.typ {
+            context.type_error(
+                &format!("incorrect type for `{}` argument", name),
+                arg.kind.value.span,
+                &field_type,
+                &arg_attrs.typ,
+            );
+        }
+    }
+
+    Ok((
+        ExpressionAttributes::new(Type::Enum(enum_type), Location::Memory),
+        CallType::EnumConstructor(variant),
+    ))
+}
+
 fn expr_call_method(
     context: &mut dyn AnalyzerContext,
     target: &Node<fe::Expr>,
@@ -1399,7 +2688,17 @@ fn expr_call_method(
     }
 
     let target_attributes = expr(context, target, None)?;
+    expr_call_value_method(context, target, target_attributes, field, args)
+}
 
+/// Checks a method call on an already traversed value.
+fn expr_call_value_method(
+    context: &mut dyn AnalyzerContext,
+    target: &Node<fe::Expr>,
+    target_attributes: ExpressionAttributes,
+    field: &Node<SmolStr>,
+    args: &Node<Vec<Node<fe::CallArg>>>,
+) -> Result<(ExpressionAttributes, CallType), FatalError> {
     // Check built-in methods.
     if let Ok(method) = ValueMethod::from_str(&field.kind) {
         return expr_call_builtin_value_method(
@@ -1420,6 +2719,7 @@ fn expr_call_method(
         }
         if let Some(method) = class.function(context.db(), &field.kind) {
             let is_self = is_self_value(target);
+            check_init_only_call(context, method, field.span);
 
             if is_self && !method.takes_self(context.db()) {
                 context.fancy_error(
@@ -1450,14 +2750,12 @@ fn expr_call_method(
             }
 
             let sig = method.signature(context.db());
-            validate_named_args(
-                context,
-                &field.kind,
-                field.span,
-                args,
-                &sig.params,
-                LabelPolicy::AllowAnyUnlabeled,
-            )?;
+            validate_fn_args(context, &field.kind, field.span, args, &sig.params)?;
+            if sig.self_decl == Some(SelfDecl::Mutable)
+                && (is_self || matches!(class, Class::Struct(_)))
+            {
+                check_mutable(context, target, target.span + field.span);
+            }
 
             let calltype = match class {
                 Class::Contract(contract) => {
@@ -1530,15 +2828,32 @@ fn expr_call_builtin_value_method(
     method_name: &Node<SmolStr>,
     args: &Node<Vec<Node<fe::CallArg>>>,
 ) -> Result<(ExpressionAttributes, CallType), FatalError> {
-    // for now all of these functions expect 0 arguments
-    validate_arg_count(
+    let arg_count = match method {
+        ValueMethod::Concat | ValueMethod::Push => 1,
+        ValueMethod::Slice => 2,
+        ValueMethod::Clone
+        | ValueMethod::ToMem
+        | ValueMethod::AbiEncode
+        | ValueMethod::Pop
+        | ValueMethod::Len
+        | ValueMethod::ToString => 0,
+    };
+    if let Some(diag) = validate_arg_count(
         context,
         &method_name.kind,
         method_name.span,
         args,
-        0,
+        arg_count,
         "argument",
-    );
+    ) {
+        if arg_count > 0 {
+            return Err(FatalError::new(diag));
+        }
+    }
+
+    if matches!(method, ValueMethod::Push | ValueMethod::Pop) {
+        check_mutable(context, value, value.span + method_name.span);
+    }
 
     let calltype = CallType::BuiltinValueMethod {
         method,
@@ -1546,6 +2861,13 @@ fn expr_call_builtin_value_method(
     };
     match method {
         ValueMethod::Clone => {
+            if let Type::Vec(_) = value_attrs.typ {
+                // The items of the vec are copied, rather than the pointer.
+                return Ok((
+                    ExpressionAttributes::new(value_attrs.typ, Location::Value),
+                    calltype,
+                ));
+            }
             match value_attrs.location {
                 Location::Storage { .. } => {
                     context.fancy_error(
@@ -1604,6 +2926,13 @@ fn expr_call_builtin_value_method(
             Ok((value_attrs.into_cloned(), calltype))
         }
         ValueMethod::AbiEncode => match &value_attrs.typ {
+            typ if matches!(FixedSize::try_from(typ.clone()), Ok(typ) if typ.contains_vec(context.db())) => {
+                Err(FatalError::new(context.error(
+                    &format!("value of type `{}` does not support `abi_encode()`", typ),
+                    value.span,
+                    "this value holds a `Vec`, which can't be abi encoded",
+                )))
+            }
             Type::Struct(struct_) => {
                 if value_attrs.final_location() != Location::Memory {
                     context.fancy_error(
@@ -1663,6 +2992,160 @@ fn expr_call_builtin_value_method(
                 ],
             ))),
         },
+        ValueMethod::Concat | ValueMethod::Slice => {
+            let string = match &value_attrs.typ {
+                Type::String(string) => *string,
+                _ => {
+                    return Err(FatalError::new(context.fancy_error(
+                        &format!(
+                            "value of type `{}` does not support `{}()`",
+                            value_attrs.typ, method_name.kind
+                        ),
+                        vec![Label::primary(value.span, "this value is not a string")],
+                        vec![],
+                    )))
+                }
+            };
+            let value_attrs = move_to_assignment_location(context, value, value_attrs)?;
+            let max_size = if method == ValueMethod::Concat {
+                string.max_size + string_concat_arg(context, &args.kind[0])?.max_size
+            } else {
+                string_slice_max_size(context, &string, &args.kind[0], &args.kind[1])?
+            };
+            let calltype = CallType::BuiltinValueMethod {
+                method,
+                typ: value_attrs.typ,
+            };
+            Ok((
+                ExpressionAttributes::new(Type::String(FeString { max_size }), Location::Memory),
+                calltype,
+            ))
+        }
+        ValueMethod::Push | ValueMethod::Pop | ValueMethod::Len => {
+            let vec = match &value_attrs.typ {
+                Type::Vec(vec) => *vec,
+                _ => {
+                    return Err(FatalError::new(context.fancy_error(
+                        &format!(
+                            "value of type `{}` does not support `{}()`",
+                            value_attrs.typ, method_name.kind
+                        ),
+                        vec![Label::primary(value.span, "this value is not a `Vec`")],
+                        vec![],
+                    )))
+                }
+            };
+            let typ = match method {
+                ValueMethod::Push => {
+                    let item_type = Type::Base(vec.inner);
+                    let arg = &args.kind[0].kind.value;
+                    let arg_attrs = value_expr(context, arg, Some(&item_type))?;
+                    if arg_attrs.typ != item_type {
+                        context.type_error(
+                            "incorrect type for `push` argument",
+                            arg.span,
+                            &item_type,
+                            &arg_attrs.typ,
+                        );
+                    }
+                    Type::unit()
+                }
+                ValueMethod::Pop => Type::Base(vec.inner),
+                _ => Type::Base(U256),
+            };
+            Ok((ExpressionAttributes::new(typ, Location::Value), calltype))
+        }
+        ValueMethod::ToString => match value_attrs.typ {
+            // The string can hold the decimal digits of any value of the
+            // type, with a minus sign if it's negative.
+            Type::Base(Base::Numeric(integer)) => {
+                move_to_assignment_location(context, value, value_attrs)?;
+                let max_size = [integer.min_value(), integer.max_value()]
+                    .iter()
+                    .map(|value| value.to_string().len())
+                    .max()
+                    .expect("no values");
+                Ok((
+                    ExpressionAttributes::new(
+                        Type::String(FeString { max_size }),
+                        Location::Memory,
+                    ),
+                    calltype,
+                ))
+            }
+            _ => Err(FatalError::new(context.fancy_error(
+                &format!(
+                    "value of type `{}` does not support `to_string()`",
+                    value_attrs.typ
+                ),
+                vec![Label::primary(value.span, "this value is not an integer")],
+                vec!["Hint: an integer can be converted to its decimal digits, e.g. `\"balance: \".concat(balance.to_string())`".into()],
+            ))),
+        },
+    }
+}
+
+/// Checks that the argument of `concat()` is a string in memory, and returns
+/// its type.
+fn string_concat_arg(
+    context: &mut dyn AnalyzerContext,
+    arg: &Node<fe::CallArg>,
+) -> Result<FeString, FatalError> {
+    let arg_attrs = assignable_expr(context, &arg.kind.value, None)?;
+    match arg_attrs.typ {
+        Type::String(string) => Ok(string),
+        typ => Err(FatalError::new(context.type_error(
+            "incorrect type for `concat` argument",
+            arg.kind.value.span,
+            &"String",
+            &typ,
+        ))),
+    }
+}
+
+/// Checks the `start` and `end` arguments of `slice()`, and returns the max
+/// size of the slice. If both arguments are number literals, the max size is
+/// the length of the slice, rather than the max size of the string.
+fn string_slice_max_size(
+    context: &mut dyn AnalyzerContext,
+    string: &FeString,
+    start: &Node<fe::CallArg>,
+    end: &Node<fe::CallArg>,
+) -> Result<usize, FatalError> {
+    let u256 = Type::int(Integer::U256);
+    for arg in [start, end] {
+        let arg_attrs = value_expr(context, &arg.kind.value, Some(&u256))?;
+        if arg_attrs.typ != u256 {
+            context.type_error(
+                "incorrect type for `slice` argument",
+                arg.kind.value.span,
+                &u256,
+                &arg_attrs.typ,
+            );
+        }
+    }
+
+    if let (fe::Expr::Num(start_num), fe::Expr::Num(end_num)) =
+        (&start.kind.value.kind, &end.kind.value.kind)
+    {
+        let (start_num, end_num) = (to_bigint(start_num), to_bigint(end_num));
+        if start_num > end_num || end_num > BigInt::from(string.max_size) {
+            return Err(FatalError::new(context.fancy_error(
+                "string slice is out of bounds",
+                vec![Label::primary(
+                    start.span + end.span,
+                    format!(
+                        "the slice `{}..{}` doesn't fit in a string with max size {}",
+                        start_num, end_num, string.max_size
+                    ),
+                )],
+                vec![],
+            )));
+        }
+        let len = end_num - start_num;
+        Ok(usize::try_from(len).expect("slice length is at most the string max size"))
+    } else {
+        Ok(string.max_size)
     }
 }
 
@@ -1688,9 +3171,23 @@ fn expr_call_type_attribute(
         let class_name = class.name(context.db());
 
         if let Class::Contract(contract) = class {
-            // Check for Foo.create/create2 (this will go away when the context object is
-            // ready)
+            // Check for Foo.create/create2/address_of (this will go away when the context
+            // object is ready)
             if let Ok(function) = ContractTypeMethod::from_str(&field.kind) {
+                if contract.is_interface(context.db()) {
+                    context.fancy_error(
+                        &format!(
+                            "`{}.{}(...)` can't be called on an interface",
+                            &class_name,
+                            function.as_ref()
+                        ),
+                        vec![Label::primary(field.span, "Contract creation")],
+                        vec![format!(
+                            "Note: `{}` is an interface, which has no bytecode that could be deployed",
+                            &class_name
+                        )],
+                    );
+                }
                 if context.root_item() == Item::Type(TypeDef::Contract(contract)) {
                     context.fancy_error(
                         &format!("`{contract}.{}(...)` called within `{contract}` creates an illegal circular dependency", function.as_ref(), contract=&class_name),
@@ -1709,7 +3206,15 @@ fn expr_call_type_attribute(
 
                 for i in 0..arg_count {
                     if let Some(attrs) = arg_attributes.get(i) {
-                        if !matches!(&attrs.typ, Type::Base(Base::Numeric(_))) {
+                        let (is_expected, expected) = if function.takes_address(i) {
+                            (attrs.typ == Type::Base(Base::Address), "an address")
+                        } else {
+                            (
+                                matches!(&attrs.typ, Type::Base(Base::Numeric(_))),
+                                "a number",
+                            )
+                        };
+                        if !is_expected {
                             context.fancy_error(
                                 &format!(
                                     "incorrect type for argument to `{}.{}`",
@@ -1718,15 +3223,22 @@ fn expr_call_type_attribute(
                                 ),
                                 vec![Label::primary(
                                     args.kind[i].span,
-                                    format!("this has type `{}`; expected a number", &attrs.typ),
+                                    format!(
+                                        "this has type `{}`; expected {}",
+                                        &attrs.typ, expected
+                                    ),
                                 )],
                                 vec![],
                             );
                         }
                     }
                 }
+                let return_type = match function {
+                    ContractTypeMethod::AddressOf => Type::Base(Base::Address),
+                    ContractTypeMethod::Create | ContractTypeMethod::Create2 => typ,
+                };
                 return Ok((
-                    ExpressionAttributes::new(typ, Location::Value),
+                    ExpressionAttributes::new(return_type, Location::Value),
                     CallType::BuiltinAssociatedFunction { contract, function },
                 ));
             }
@@ -1846,13 +3358,20 @@ fn check_for_call_to_special_fns(
     name: &str,
     span: Span,
 ) -> Result<(), FatalError> {
-    if name == "__init__" || name == "__call__" {
-        let label = if name == "__init__" {
-            "Note: `__init__` is the constructor function, and can't be called at runtime."
-        } else {
+    if matches!(
+        name,
+        "__init__" | "__call__" | "__fallback__" | "__receive__"
+    ) {
+        let label = match name {
+            "__init__" => {
+                "Note: `__init__` is the constructor function, and can't be called at runtime."
+            }
             // TODO: add a hint label explaining how to call contracts directly
             // with `Context` (not yet supported).
-            "Note: `__call__` is not part of the contract's interface, and can't be called."
+            "__call__" => {
+                "Note: `__call__` is not part of the contract's interface, and can't be called."
+            }
+            _ => "Note: this function is only called by the contract's call dispatcher.",
         };
         Err(FatalError::new(context.fancy_error(
             &format!("`{}()` is not directly callable", name),
@@ -1864,6 +3383,99 @@ fn check_for_call_to_special_fns(
     }
 }
 
+/// Reports a call to an `#[init_only]` function from anywhere but `__init__`
+/// and other `#[init_only]` functions.
+fn check_init_only_call(context: &mut dyn AnalyzerContext, function: FunctionId, span: Span) {
+    let db = context.db();
+    let init_only_span = match function.init_only_span(db) {
+        Some(span) => span,
+        None => return,
+    };
+    if context.is_in_function() {
+        let caller = context.parent_function();
+        if caller.is_constructor(db) || caller.is_init_only(db) {
+            return;
+        }
+    }
+
+    let name = function.name(db);
+    context.fancy_error(
+        &format!("`{}` can only be called from `__init__`", name),
+        vec![
+            Label::primary(span, "called outside of `__init__`"),
+            Label::secondary(init_only_span, format!("`{}` is marked `#[init_only]`", name)),
+        ],
+        vec![format!(
+            "Note: `#[init_only]` functions can only be called from `__init__` and other `#[init_only]` functions, so that `{}` can't run after the contract is deployed.",
+            name
+        )],
+    );
+}
+
+/// Reports a call of an instruction that the target EVM version doesn't have.
+/// The std lib can call any instruction; calling the std lib function is
+/// reported instead (see [`check_std_function_evm_version`]).
+fn check_evm_version(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    required: EvmVersion,
+    span: Span,
+) {
+    let db = context.db();
+    let target = db.evm_version();
+    if required <= target || is_in_std(context) {
+        return;
+    }
+    context.fancy_error(
+        &format!(
+            "`{}` isn't available in the `{}` EVM version",
+            name,
+            target.as_ref()
+        ),
+        vec![Label::primary(
+            span,
+            format!("needs the `{}` EVM version or later", required.as_ref()),
+        )],
+        vec![format!(
+            "Hint: compile for a later EVM version, e.g. with `--evm-version {}`.",
+            required.as_ref()
+        )],
+    );
+}
+
+/// Reports a call of a std lib function that calls an instruction that the
+/// target EVM version doesn't have, e.g. `std::evm::tload`.
+fn check_std_function_evm_version(
+    context: &mut dyn AnalyzerContext,
+    function: FunctionId,
+    span: Span,
+) {
+    let db = context.db();
+    // The bodies of std lib functions don't depend on the bodies of other
+    // functions, so analyzing them here can't form a cycle.
+    if !function.module(db).ingot(db).is_std(db) || function.is_generic(db) || is_in_std(context) {
+        return;
+    }
+    let required = function
+        .body(db)
+        .calls
+        .values()
+        .filter_map(|call| match call {
+            CallType::Intrinsic(intrinsic) => Some(intrinsic.min_evm_version()),
+            _ => None,
+        })
+        .max();
+    if let Some(required) = required {
+        let name = function.name(db);
+        check_evm_version(context, &name, required, span);
+    }
+}
+
+fn is_in_std(context: &dyn AnalyzerContext) -> bool {
+    let db = context.db();
+    context.is_in_function() && context.parent_function().module(db).ingot(db).is_std(db)
+}
+
 fn validate_numeric_literal_fits_type(
     context: &mut dyn AnalyzerContext,
     num: BigInt,
@@ -1897,7 +3509,11 @@ fn validate_str_literal_fits_type(
             );
         }
     } else {
-        context.error("type mismatch", arg_val.span, "expected a string literal");
+        context.error(
+            "type mismatch",
+            arg_val.span,
+            "expected a string literal or a `Vec<u8>`",
+        );
     }
 }
 
@@ -1906,24 +3522,56 @@ fn expr_comp_operation(
     exp: &Node<fe::Expr>,
 ) -> Result<ExpressionAttributes, FatalError> {
     if let fe::Expr::CompOperation { left, op, right } = &exp.kind {
-        // comparison operands should be moved to the stack
-        let left_attr = value_expr(context, left, None)?;
-        let right_attr = value_expr(context, right, Some(&left_attr.typ))?;
+        // An integer literal on the left takes the type of the right operand,
+        // e.g. in `0 < x`.
+        if is_int_literal(left) && !is_int_literal(right) {
+            let right_attr = comparison_operand(context, right, None)?;
+            let left_attr = comparison_operand(context, left, Some(&right_attr.typ))?;
+            check_comparison_operands(context, op, left, &left_attr.typ, right, &right_attr.typ);
+            return Ok(ExpressionAttributes::new(
+                Type::Base(Base::Bool),
+                Location::Value,
+            ));
+        }
 
-        if left_attr.typ != right_attr.typ {
-            context.fancy_error(
-                &format!("`{}` operands must have the same type", op.kind),
-                vec![
-                    Label::primary(left.span, format!("this has type `{}`", left_attr.typ)),
-                    Label::secondary(
-                        right.span,
-                        format!("this has incompatible type `{}`", right_attr.typ),
-                    ),
-                ],
-                vec![],
-            );
+        let left_attr = expr(context, left, None)?;
+        if let Type::Struct(struct_) = &left_attr.typ {
+            let attributes = expr_operator_overload(
+                context,
+                struct_.id,
+                left,
+                left_attr,
+                op.kind.to_string(),
+                op.span,
+                comp_operator_method(op.kind),
+                right,
+            )?;
+            if attributes.typ != Type::Base(Base::Bool) {
+                context.fancy_error(
+                    &format!("`{}` must return `bool`", comp_operator_method(op.kind)),
+                    vec![Label::primary(
+                        exp.span,
+                        format!("this has type `{}`", attributes.typ),
+                    )],
+                    vec![],
+                );
+            }
+            return Ok(ExpressionAttributes::new(
+                Type::Base(Base::Bool),
+                Location::Value,
+            ));
         }
 
+        // comparison operands should be moved to the stack, except for strings,
+        // which are compared in memory
+        let left_attr = if let Type::String(_) = left_attr.typ {
+            move_to_assignment_location(context, left, left_attr)?
+        } else {
+            move_to_stack(context, left, left_attr)?
+        };
+        let right_attr = comparison_operand(context, right, Some(&left_attr.typ))?;
+        check_comparison_operands(context, op, left, &left_attr.typ, right, &right_attr.typ);
+
         // for now we assume these are the only possible attributes
         return Ok(ExpressionAttributes::new(
             Type::Base(Base::Bool),
@@ -1934,9 +3582,188 @@ fn expr_comp_operation(
     unreachable!()
 }
 
+fn expr_chained_comparison(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+) -> Result<ExpressionAttributes, FatalError> {
+    if let fe::Expr::ChainedComparison { left, comparisons } = &exp.kind {
+        let operands = std::iter::once(left.as_ref())
+            .chain(comparisons.iter().map(|(_, operand)| operand))
+            .collect::<Vec<_>>();
+
+        // The operands that aren't integer literals are checked first. A literal
+        // takes the type of the closest of them, preferring the one on its
+        // left, so that e.g. `0 < x <= 10` can be used if `x` is a `u8`.
+        let mut types = vec![None; operands.len()];
+        for (operand, typ) in operands.iter().zip(types.iter_mut()) {
+            if !is_int_literal(operand) {
+                *typ = Some(chained_comparison_operand(context, operand, None)?);
+            }
+        }
+        for idx in 0..operands.len() {
+            if types[idx].is_none() {
+                let expected_type = types[..idx]
+                    .iter()
+                    .rev()
+                    .chain(&types[idx + 1..])
+                    .find_map(Option::as_ref)
+                    .cloned();
+                types[idx] = Some(chained_comparison_operand(
+                    context,
+                    operands[idx],
+                    expected_type.as_ref(),
+                )?);
+            }
+        }
+        let types = types.into_iter().flatten().collect::<Vec<_>>();
+
+        for (idx, (op, _)) in comparisons.iter().enumerate() {
+            check_comparison_operands(
+                context,
+                op,
+                operands[idx],
+                &types[idx],
+                operands[idx + 1],
+                &types[idx + 1],
+            );
+        }
+
+        return Ok(ExpressionAttributes::new(
+            Type::Base(Base::Bool),
+            Location::Value,
+        ));
+    }
+
+    unreachable!()
+}
+
+/// Checks an operand of a chained comparison, and returns its type. Operands
+/// can't be structs, since their comparison operators would need to be
+/// called on values that are compared twice.
+fn chained_comparison_operand(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+    expected_type: Option<&Type>,
+) -> Result<Type, FatalError> {
+    let attributes = expr(context, exp, expected_type)?;
+    if let Type::Struct(_) = &attributes.typ {
+        return Err(FatalError::new(context.fancy_error(
+            "chained comparisons can't be applied to structs",
+            vec![Label::primary(
+                exp.span,
+                format!("this has type `{}`", attributes.typ),
+            )],
+            vec!["Hint: compare the values separately, e.g. `a < b and b < c`".into()],
+        )));
+    }
+    Ok(move_comparison_operand(context, exp, attributes)?.typ)
+}
+
+fn check_comparison_operands(
+    context: &mut dyn AnalyzerContext,
+    op: &Node<fe::CompOperator>,
+    left: &Node<fe::Expr>,
+    left_typ: &Type,
+    right: &Node<fe::Expr>,
+    right_typ: &Type,
+) {
+    // Strings of different max sizes can be compared.
+    let same_type = match (left_typ, right_typ) {
+        (Type::String(_), Type::String(_)) => true,
+        (left_typ, right_typ) => left_typ == right_typ,
+    };
+    if !same_type {
+        context.fancy_error(
+            &format!("`{}` operands must have the same type", op.kind),
+            vec![
+                Label::primary(left.span, format!("this has type `{}`", left_typ)),
+                Label::secondary(
+                    right.span,
+                    format!("this has incompatible type `{}`", right_typ),
+                ),
+            ],
+            vec![],
+        );
+    }
+}
+
+/// Checks an operation whose left operand is a struct. The struct overloads
+/// the operator with a method like `__add__`, which is called with the right
+/// operand as its argument.
+#[allow(clippy::too_many_arguments)]
+pub fn expr_operator_overload(
+    context: &mut dyn AnalyzerContext,
+    struct_: StructId,
+    left: &Node<fe::Expr>,
+    left_attributes: ExpressionAttributes,
+    op: String,
+    op_span: Span,
+    method_name: &str,
+    right: &Node<fe::Expr>,
+) -> Result<ExpressionAttributes, FatalError> {
+    let overloaded = matches!(
+        struct_.function(context.db(), method_name),
+        Some(method) if method.takes_self(context.db())
+    );
+    if !overloaded {
+        return Err(FatalError::new(context.fancy_error(
+            &format!(
+                "`{}` can't be applied to type `{}`",
+                op, left_attributes.typ
+            ),
+            vec![Label::primary(
+                left.span,
+                format!("this has type `{}`", left_attributes.typ),
+            )],
+            vec![format!(
+                "Hint: `{}` can overload `{}` by defining `pub fn {}(self, other: ..)`",
+                left_attributes.typ, op, method_name
+            )],
+        )));
+    }
+
+    let method = Node::new(SmolStr::new(method_name), op_span);
+    let args = Node::new(
+        vec![Node::new(
+            fe::CallArg {
+                label: None,
+                value: right.clone(),
+            },
+            right.span,
+        )],
+        right.span,
+    );
+    let (attributes, _) = expr_call_value_method(context, left, left_attributes, &method, &args)?;
+    Ok(attributes)
+}
+
+fn comparison_operand(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+    expected_type: Option<&Type>,
+) -> Result<ExpressionAttributes, FatalError> {
+    let attributes = expr(context, exp, expected_type)?;
+    move_comparison_operand(context, exp, attributes)
+}
+
+/// Comparison operands are moved to the stack, except for strings, which are
+/// compared in memory.
+fn move_comparison_operand(
+    context: &mut dyn AnalyzerContext,
+    exp: &Node<fe::Expr>,
+    attributes: ExpressionAttributes,
+) -> Result<ExpressionAttributes, FatalError> {
+    if let Type::String(_) = attributes.typ {
+        move_to_assignment_location(context, exp, attributes)
+    } else {
+        move_to_stack(context, exp, attributes)
+    }
+}
+
 fn expr_ternary(
     context: &mut dyn AnalyzerContext,
     exp: &Node<fe::Expr>,
+    expected_type: Option<&Type>,
 ) -> Result<ExpressionAttributes, FatalError> {
     if let fe::Expr::Ternary {
         if_expr,
@@ -1951,9 +3778,24 @@ fn expr_ternary(
         // If, for example, one of the expressions is stored in memory and the other is
         // stored in storage, it's necessary that we move them to the same location.
         // This could be memory or the stack, depending on the type.
-        let if_expr_attributes = assignable_expr(context, if_expr, None)?;
-        let else_expr_attributes =
-            assignable_expr(context, else_expr, Some(&if_expr_attributes.typ))?;
+        //
+        // Both values get the expected type. Without one, an integer literal
+        // gets the type of the other value, e.g. `u8` in `0 if c else x`.
+        let (if_expr_attributes, else_expr_attributes) =
+            if expected_type.is_none() && is_int_literal(if_expr) && !is_int_literal(else_expr) {
+                let else_expr_attributes = assignable_expr(context, else_expr, None)?;
+                let if_expr_attributes =
+                    assignable_expr(context, if_expr, Some(&else_expr_attributes.typ))?;
+                (if_expr_attributes, else_expr_attributes)
+            } else {
+                let if_expr_attributes = assignable_expr(context, if_expr, expected_type)?;
+                let else_expr_attributes = assignable_expr(
+                    context,
+                    else_expr,
+                    expected_type.or(Some(&if_expr_attributes.typ)),
+                )?;
+                (if_expr_attributes, else_expr_attributes)
+            };
 
         // Make sure the `test_attributes` is a boolean type.
         if Type::Base(Base::Bool) != test_attributes.typ {
@@ -2015,12 +3857,95 @@ fn expr_bool_operation(
 ///
 /// # Panics
 /// Panics if `num` contains invalid digit.
-fn to_bigint(num: &str) -> BigInt {
+pub(crate) fn to_bigint(num: &str) -> BigInt {
     numeric::Literal::new(num)
         .parse::<BigInt>()
         .expect("the numeric literal contains a invalid digit")
 }
 
+/// The value of an integer literal like `1` or `-1`.
+fn int_literal_value(expr: &Node<fe::Expr>) -> Option<BigInt> {
+    match &expr.kind {
+        fe::Expr::Num(num) => Some(to_bigint(num)),
+        fe::Expr::UnaryOperation { op, operand } if op.kind == fe::UnaryOperator::USub => {
+            int_literal_value(operand).map(|value| -value)
+        }
+        _ => None,
+    }
+}
+
+/// Returns `true` for an integer literal like `1` or `-1`, the type of which
+/// depends on where it's used.
+fn is_int_literal(expr: &Node<fe::Expr>) -> bool {
+    match &expr.kind {
+        fe::Expr::Num(_) => true,
+        fe::Expr::UnaryOperation { op, operand } => {
+            matches!(op.kind, fe::UnaryOperator::USub | fe::UnaryOperator::Invert)
+                && is_int_literal(operand)
+        }
+        _ => false,
+    }
+}
+
+/// Checks that `value` may be modified in place, e.g. by assigning to one of
+/// its fields. `self` and the parameters of the function, and anything that
+/// they contain, can only be modified if they're declared `mut`. `span` is
+/// the span of the modification.
+pub fn check_mutable(context: &mut dyn AnalyzerContext, value: &Node<fe::Expr>, span: Span) {
+    let name = match &value.kind {
+        fe::Expr::Attribute { value, .. } | fe::Expr::Subscript { value, .. } => {
+            return check_mutable(context, value, span)
+        }
+        fe::Expr::Name(name) => name,
+        _ => return,
+    };
+    // Local variables can't shadow parameters, so a name that matches a
+    // parameter refers to it.
+    let function = context.parent_function();
+    if let Some(param) = function
+        .data(context.db())
+        .ast
+        .kind
+        .args
+        .iter()
+        .find(|arg| arg.name() == name && arg.mut_span().is_none())
+    {
+        context.fancy_error(
+            &format!("`{}` is not mutable", name),
+            vec![
+                Label::primary(span, format!("`{}` is modified here", name)),
+                Label::secondary(param.span, "declared without `mut`"),
+            ],
+            vec![format!(
+                "Hint: declare it as `mut {}` to allow the function to modify it",
+                param.kind
+            )],
+        );
+    }
+}
+
+/// Reports a read of `msg.value` in a public contract function that isn't
+/// `#[payable]`. Calls that transfer ether to such a function revert, so the
+/// value would always be zero.
+fn check_value_is_payable(context: &mut dyn AnalyzerContext, exp: &Node<fe::Expr>) {
+    let function = context.parent_function();
+    let db = context.db();
+    if matches!(function.class(db), Some(Class::Contract(_)))
+        && function.is_public(db)
+        && !function.is_payable(db)
+    {
+        let name = function.name(db);
+        context.fancy_error(
+            "`msg.value` can't be read in a public function that isn't `#[payable]`",
+            vec![Label::primary(exp.span, "this is always zero")],
+            vec![format!(
+                "Hint: mark `{}` as `#[payable]` to allow calls to it to transfer ether",
+                name
+            )],
+        );
+    }
+}
+
 fn is_self_value(expr: &Node<fe::Expr>) -> bool {
     if let fe::Expr::Name(name) = &expr.kind {
         name == "self"
@@ -1,12 +1,20 @@
-use crate::context::{AnalyzerContext, ExpressionAttributes, Location, NamedThing};
+use crate::builtins::Intrinsic;
+use crate::context::{AnalyzerContext, CallType, ExpressionAttributes, Location, NamedThing};
 use crate::errors::FatalError;
 use crate::namespace::items::Item;
 use crate::namespace::scopes::{BlockScope, BlockScopeType};
-use crate::namespace::types::{Base, FixedSize, Type};
-use crate::traversal::call_args::LabelPolicy;
-use crate::traversal::{assignments, call_args, declarations, expressions};
+use crate::namespace::types::{Base, FixedSize, StateMutability, Type};
+use crate::traversal::{assignments, call_args, declarations, expressions, types};
+use fe_common::diagnostics::Label;
+use fe_common::utils::humanize::pluralize_conditionally;
+use fe_common::Span;
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
+use num_bigint::BigInt;
+use smol_str::SmolStr;
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::str::FromStr;
 
 pub fn traverse_statements(
     scope: &mut BlockScope,
@@ -29,19 +37,100 @@ fn func_stmt(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fa
         AugAssign { .. } => assignments::aug_assign(scope, stmt),
         For { .. } => for_loop(scope, stmt),
         While { .. } => while_loop(scope, stmt),
+        Loop { .. } => loop_statement(scope, stmt),
         If { .. } => if_statement(scope, stmt),
+        Match { .. } => match_statement(scope, stmt),
+        Try { .. } => try_statement(scope, stmt),
         Unsafe { .. } => unsafe_block(scope, stmt),
+        Unchecked { .. } => unchecked_block(scope, stmt),
+        // `#[checked]` only changes the code that's generated for the
+        // statement's arithmetic.
+        Checked(body) => traverse_statements(scope, body),
+        Yul { .. } => yul_block(scope, stmt),
         Assert { .. } => assert(scope, stmt),
-        Expr { value } => expressions::expr(scope, value, None).map(|_| ()),
+        Expr { .. } => expr_statement(scope, stmt),
         Pass => Ok(()),
         Revert { .. } => revert(scope, stmt),
-        Break | Continue => {
-            loop_flow_statement(scope, stmt);
-            Ok(())
+        Break { .. } | Continue => loop_flow_statement(scope, stmt),
+    }
+}
+
+/// Analyzes an expression statement, and warns if the value of the
+/// expression is discarded.
+fn expr_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    if let fe::FuncStmt::Expr { value } = &stmt.kind {
+        let errors_before = error_count(scope);
+        let typ = expressions::expr(scope, value, None)?.typ;
+        // An expression with errors doesn't need another diagnostic.
+        if typ.is_unit() || error_count(scope) != errors_before {
+            return Ok(());
         }
+
+        let label = Label::primary(
+            value.span,
+            format!("this value of type `{}` is discarded", typ),
+        );
+        let hint = format!(
+            "Hint: if this is intended, assign the value to a variable, e.g. `let _unused: {} = ...`",
+            typ
+        );
+        if let Some(note) = must_use_note(scope, value, &typ) {
+            scope.fancy_error(
+                "unused value that must be used",
+                vec![label],
+                vec![note, hint],
+            );
+        } else {
+            scope.warning("unused value", vec![label], vec![hint]);
+        }
+        Ok(())
+    } else {
+        unreachable!()
     }
 }
 
+/// Explains why the value of an expression statement must be used, if the
+/// called function or the type of the value has the `#[must_use]` attribute.
+fn must_use_note(scope: &BlockScope, value: &Node<fe::Expr>, typ: &Type) -> Option<String> {
+    let db = scope.db();
+    if let fe::Expr::Call { func, .. } = &value.kind {
+        let function = scope
+            .root
+            .body
+            .borrow()
+            .calls
+            .get(&func.id)
+            .and_then(|call_type| call_type.function());
+        if let Some(function) = function.filter(|function| function.is_must_use(db)) {
+            return Some(format!(
+                "Note: the function `{}` is marked `#[must_use]`",
+                function.name(db)
+            ));
+        }
+    }
+    match typ {
+        Type::Struct(struct_) if struct_.id.is_must_use(db) => Some(format!(
+            "Note: the struct `{}` is marked `#[must_use]`",
+            struct_.name
+        )),
+        Type::Enum(enum_) if enum_.id.is_must_use(db) => Some(format!(
+            "Note: the enum `{}` is marked `#[must_use]`",
+            enum_.name
+        )),
+        _ => None,
+    }
+}
+
+fn error_count(scope: &BlockScope) -> usize {
+    scope
+        .root
+        .diagnostics
+        .borrow()
+        .iter()
+        .filter(|diag| diag.is_error())
+        .count()
+}
+
 fn for_loop(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     match &stmt.kind {
         fe::FuncStmt::For { target, iter, body } => {
@@ -69,22 +158,60 @@ fn for_loop(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fat
     }
 }
 
-fn loop_flow_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) {
-    if !scope.inherits_type(BlockScopeType::Loop) {
-        let stmt_name = match stmt.kind {
-            fe::FuncStmt::Continue => "continue",
-            fe::FuncStmt::Break => "break",
-            _ => unreachable!(),
-        };
-        scope.error(
-            &format!("`{}` outside of a loop", stmt_name),
-            stmt.span,
-            &format!(
-                "`{}` can only be used inside of a `for` or `while` loop",
-                stmt_name
-            ),
-        );
+fn loop_flow_statement(
+    scope: &mut BlockScope,
+    stmt: &Node<fe::FuncStmt>,
+) -> Result<(), FatalError> {
+    let stmt_name = match stmt.kind {
+        fe::FuncStmt::Continue => "continue",
+        fe::FuncStmt::Break { .. } => "break",
+        _ => unreachable!(),
+    };
+    let loop_type = match scope.innermost_loop() {
+        Some(loop_type) => loop_type.clone(),
+        None => {
+            scope.error(
+                &format!("`{}` outside of a loop", stmt_name),
+                stmt.span,
+                &format!(
+                    "`{}` can only be used inside of a `for`, `while` or `loop` statement",
+                    stmt_name
+                ),
+            );
+            return Ok(());
+        }
+    };
+
+    if let fe::FuncStmt::Break { value } = &stmt.kind {
+        match (loop_type, value) {
+            (BlockScopeType::LoopWithValue(expected), Some(value)) => {
+                let actual =
+                    expressions::assignable_expr(scope, value, Some(&expected.clone().into()))?.typ;
+                if expected != actual {
+                    scope.type_error("type mismatch", value.span, &expected, &actual);
+                }
+            }
+            (BlockScopeType::LoopWithValue(expected), None) => {
+                scope.error(
+                    "`break` without a value",
+                    stmt.span,
+                    &format!(
+                        "this `loop` must be exited with a value of type `{}`",
+                        expected
+                    ),
+                );
+            }
+            (_, Some(value)) => {
+                scope.fancy_error(
+                    "`break` with a value in a loop that doesn't produce a value",
+                    vec![Label::primary(value.span, "unexpected value")],
+                    vec!["Note: only a `loop` whose value is assigned to a variable, eg. `let x: u256 = loop:`, can be exited with a value".into()],
+                );
+            }
+            (_, None) => {}
+        }
     }
+    Ok(())
 }
 
 fn if_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
@@ -112,6 +239,396 @@ fn if_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
     }
 }
 
+/// The values matched by a `match` arm pattern that doesn't match every value.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum PatternKey {
+    Variant(usize),
+    Bool(bool),
+    Num(BigInt),
+}
+
+fn match_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    match &stmt.kind {
+        fe::FuncStmt::Match { value, arms } => {
+            let value_type = expressions::assignable_expr(scope, value, None)?.typ;
+            if !matches!(
+                value_type,
+                Type::Enum(_) | Type::Base(Base::Bool) | Type::Base(Base::Numeric(_))
+            ) {
+                return Err(FatalError::new(scope.fancy_error(
+                    "invalid `match` value type",
+                    vec![Label::primary(
+                        value.span,
+                        format!("this has type `{}`", value_type),
+                    )],
+                    vec!["Note: only enums, booleans and integers can be matched".into()],
+                )));
+            }
+
+            let mut covered = HashSet::new();
+            let mut exhausted = false;
+            for arm in arms {
+                let pattern = &arm.kind.pattern;
+                let mut arm_scope = scope.new_child(BlockScopeType::IfElse);
+                let key = match_pattern(&mut arm_scope, pattern, &value_type)?;
+
+                let is_covered = match &key {
+                    Some(key) => covered.contains(key),
+                    None => false,
+                };
+                if exhausted || is_covered {
+                    arm_scope.warning(
+                        "unreachable pattern",
+                        vec![Label::primary(
+                            pattern.span,
+                            "this pattern is covered by a previous arm",
+                        )],
+                        vec![],
+                    );
+                }
+                match key {
+                    Some(key) => {
+                        covered.insert(key);
+                    }
+                    None => exhausted = true,
+                }
+
+                traverse_statements(&mut arm_scope, &arm.kind.body)?;
+            }
+
+            if !exhausted {
+                let missing = missing_patterns(scope, &value_type, &covered);
+                if !missing.is_empty() {
+                    let missing = missing.join(", ");
+                    scope.fancy_error(
+                        &format!("non-exhaustive patterns: {} not covered", missing),
+                        vec![Label::primary(
+                            value.span,
+                            format!("{} not covered", missing),
+                        )],
+                        vec![
+                            "Hint: add an arm for each missing pattern, or an arm with the wildcard pattern `_`"
+                                .into(),
+                        ],
+                    );
+                }
+            }
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Checks that a `try` statement makes an external call, and that its `catch`
+/// clauses catch error structs or `Error(string)` revert reasons.
+fn try_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    if let fe::FuncStmt::Try {
+        call,
+        binding,
+        body,
+        catches,
+    } = &stmt.kind
+    {
+        let return_type = expressions::expr(scope, call, None)?.typ;
+        let func = match &call.kind {
+            fe::Expr::Call { func, .. } => Some(func),
+            _ => None,
+        };
+        let is_external_call = match func {
+            Some(func) => matches!(
+                scope.root.body.borrow().calls.get(&func.id),
+                Some(CallType::External { .. })
+            ),
+            None => false,
+        };
+        match func {
+            Some(func) if is_external_call => scope.root.add_try_call(func),
+            _ => {
+                return Err(FatalError::new(scope.fancy_error(
+                    "`try` requires a call to another contract",
+                    vec![Label::primary(
+                        call.span,
+                        "this isn't a call of a function of another contract",
+                    )],
+                    vec![
+                        "Note: only calls to other contracts can revert without reverting the caller"
+                            .into(),
+                    ],
+                )))
+            }
+        }
+
+        let mut body_scope = scope.new_child(BlockScopeType::IfElse);
+        if let Some(binding) = binding {
+            if return_type.is_unit() {
+                body_scope.error(
+                    "`try` binds the value of a call that doesn't return a value",
+                    binding.span,
+                    "there's no value to bind",
+                );
+            } else if let Ok(typ) = FixedSize::try_from(return_type) {
+                // add_var emits a msg on err; we can ignore the Result.
+                let _ = body_scope.add_var(&binding.kind, typ, false, binding.span);
+            }
+        }
+        traverse_statements(&mut body_scope, body)?;
+
+        let mut caught = HashSet::new();
+        let mut exhausted = false;
+        for clause in catches {
+            let mut clause_scope = scope.new_child(BlockScopeType::IfElse);
+            if exhausted {
+                clause_scope.warning(
+                    "unreachable `catch` clause",
+                    vec![Label::primary(
+                        clause.span,
+                        "every revert is caught by a previous `catch:` clause",
+                    )],
+                    vec![],
+                );
+            }
+            match &clause.kind.error {
+                Some((typ, name)) => {
+                    let error = types::type_desc(&mut clause_scope, typ)?;
+                    if check_catch_type(&mut clause_scope, typ, &error) {
+                        let error =
+                            FixedSize::try_from(error).expect("caught error must be fixed size");
+                        if !exhausted && !caught.insert(error.clone()) {
+                            clause_scope.warning(
+                                "unreachable `catch` clause",
+                                vec![Label::primary(
+                                    typ.span,
+                                    format!("`{}` is caught by a previous clause", error),
+                                )],
+                                vec![],
+                            );
+                        }
+                        scope.root.add_catch_type(typ, error.clone());
+                        let _ = clause_scope.add_var(&name.kind, error, false, name.span);
+                    }
+                }
+                None => exhausted = true,
+            }
+            traverse_statements(&mut clause_scope, &clause.kind.body)?;
+        }
+        return Ok(());
+    }
+
+    unreachable!()
+}
+
+/// Checks that a `catch` clause can catch errors of type `typ`: an `#[error]`
+/// struct, or a string, which catches `Error(string)` revert reasons.
+fn check_catch_type(scope: &mut BlockScope, typ_desc: &Node<fe::TypeDesc>, typ: &Type) -> bool {
+    match typ {
+        Type::String(_) => true,
+        Type::Struct(struct_) if !struct_.id.is_error(scope.db()) => {
+            scope.fancy_error(
+                &format!("`{}` isn't an error type", struct_.name),
+                vec![
+                    Label::primary(typ_desc.span, "this can't be caught"),
+                    Label::secondary(
+                        struct_.id.name_span(scope.db()),
+                        "this struct isn't marked `#[error]`",
+                    ),
+                ],
+                vec![format!(
+                    "Hint: add `#[error]` to the definition of `{}`",
+                    struct_.name
+                )],
+            );
+            false
+        }
+        Type::Struct(struct_) if FixedSize::Struct(struct_.clone()).contains_vec(scope.db()) => {
+            scope.error(
+                "`catch` error can't hold a `Vec`",
+                typ_desc.span,
+                "this error holds a `Vec`, which can't be abi decoded",
+            );
+            false
+        }
+        Type::Struct(_) => true,
+        _ => {
+            scope.fancy_error(
+                "invalid `catch` type",
+                vec![Label::primary(
+                    typ_desc.span,
+                    format!("this has type `{}`", typ),
+                )],
+                vec![
+                    "Note: a `catch` clause catches an `#[error]` struct, or the reason of an `Error(string)` revert as a `String<N>`"
+                        .into(),
+                ],
+            );
+            false
+        }
+    }
+}
+
+/// Checks that a `match` arm pattern can match a value of type `typ`, and adds
+/// the variables bound by the pattern to `scope`. Returns `None` if the pattern
+/// matches any value.
+fn match_pattern(
+    scope: &mut BlockScope,
+    pattern: &Node<fe::Pattern>,
+    typ: &Type,
+) -> Result<Option<PatternKey>, FatalError> {
+    match &pattern.kind {
+        fe::Pattern::Wildcard => Ok(None),
+        fe::Pattern::Binding(name) => {
+            let typ = FixedSize::try_from(typ.clone()).expect("match value must be fixed size");
+            // add_var emits a msg on err; we can ignore the Result.
+            let _ = scope.add_var(name, typ, false, pattern.span);
+            Ok(None)
+        }
+        fe::Pattern::Bool(val) => match typ {
+            Type::Base(Base::Bool) => Ok(Some(PatternKey::Bool(*val))),
+            _ => Err(pattern_type_error(scope, pattern.span, typ)),
+        },
+        fe::Pattern::Num(num) => match typ {
+            Type::Base(Base::Numeric(integer)) => {
+                let val = match num.strip_prefix('-') {
+                    Some(num) => -expressions::to_bigint(num),
+                    None => expressions::to_bigint(num),
+                };
+                if !integer.fits(val.clone()) {
+                    scope.error(
+                        &format!("literal out of range for `{}`", integer),
+                        pattern.span,
+                        &format!("does not fit into type `{}`", integer),
+                    );
+                }
+                Ok(Some(PatternKey::Num(val)))
+            }
+            _ => Err(pattern_type_error(scope, pattern.span, typ)),
+        },
+        fe::Pattern::Variant { path, fields } => {
+            let enum_ = match typ {
+                Type::Enum(enum_) => enum_,
+                _ => return Err(pattern_type_error(scope, pattern.span, typ)),
+            };
+            let variant = match scope.resolve_path(path) {
+                Some(NamedThing::Item(Item::EnumVariant(variant)))
+                    if variant.enum_id(scope.db()) == enum_.id =>
+                {
+                    variant
+                }
+                Some(NamedThing::Item(Item::EnumVariant(_))) => {
+                    return Err(pattern_type_error(scope, pattern.span, typ))
+                }
+                _ => {
+                    return Err(FatalError::new(scope.error(
+                        &format!("expected a variant of `enum {}`", enum_.name),
+                        pattern.span,
+                        &format!("`{}` is not an enum variant", path),
+                    )))
+                }
+            };
+
+            let field_types = variant.fields(scope.db());
+            match fields {
+                None if field_types.is_empty() => {}
+                None => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!("`{}` is missing its field patterns", path),
+                        vec![Label::primary(
+                            pattern.span,
+                            format!(
+                                "`{}` has {} {}",
+                                path,
+                                field_types.len(),
+                                pluralize_conditionally("field", field_types.len())
+                            ),
+                        )],
+                        vec![format!(
+                            "Hint: use `_` to ignore a field, e.g. `{}({})`",
+                            path,
+                            vec!["_"; field_types.len()].join(", ")
+                        )],
+                    )))
+                }
+                Some(_) if field_types.is_empty() => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!("`{}` has no fields", path),
+                        vec![Label::primary(pattern.span, "unexpected field patterns")],
+                        vec![format!("Hint: remove the parentheses, e.g. `{}`", path)],
+                    )))
+                }
+                Some(field_patterns) if field_patterns.len() != field_types.len() => {
+                    return Err(FatalError::new(scope.fancy_error(
+                        &format!("wrong number of field patterns for `{}`", path),
+                        vec![Label::primary(
+                            pattern.span,
+                            format!(
+                                "expected {} {}, found {}",
+                                field_types.len(),
+                                pluralize_conditionally("field", field_types.len()),
+                                field_patterns.len()
+                            ),
+                        )],
+                        vec![],
+                    )))
+                }
+                Some(field_patterns) => {
+                    for (field_pattern, field_type) in field_patterns.iter().zip(field_types.iter())
+                    {
+                        match &field_pattern.kind {
+                            fe::Pattern::Wildcard => {}
+                            fe::Pattern::Binding(name) => {
+                                let _ = scope.add_var(
+                                    name,
+                                    field_type.clone()?,
+                                    false,
+                                    field_pattern.span,
+                                );
+                            }
+                            _ => {
+                                return Err(FatalError::new(scope.not_yet_implemented(
+                                    "nested `match` patterns",
+                                    field_pattern.span,
+                                )))
+                            }
+                        }
+                    }
+                }
+            }
+            Ok(Some(PatternKey::Variant(variant.tag(scope.db()))))
+        }
+    }
+}
+
+fn pattern_type_error(scope: &mut BlockScope, span: Span, typ: &Type) -> FatalError {
+    FatalError::new(scope.fancy_error(
+        "mismatched types in `match` pattern",
+        vec![Label::primary(
+            span,
+            format!("this pattern can't match a value of type `{}`", typ),
+        )],
+        vec![],
+    ))
+}
+
+/// The patterns that must be added to a `match` statement on a value of
+/// type `typ` to make it exhaustive, if no catch-all pattern is used.
+fn missing_patterns(scope: &BlockScope, typ: &Type, covered: &HashSet<PatternKey>) -> Vec<String> {
+    let db = scope.db();
+    match typ {
+        Type::Enum(enum_) => enum_
+            .id
+            .variants(db)
+            .values()
+            .filter(|variant| !covered.contains(&PatternKey::Variant(variant.tag(db))))
+            .map(|variant| format!("`{}::{}`", enum_.name, variant.name(db)))
+            .collect(),
+        Type::Base(Base::Bool) => [true, false]
+            .iter()
+            .filter(|val| !covered.contains(&PatternKey::Bool(**val)))
+            .map(|val| format!("`{}`", val))
+            .collect(),
+        _ => vec!["`_`".into()],
+    }
+}
+
 fn unsafe_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     match &stmt.kind {
         fe::FuncStmt::Unsafe(body) => {
@@ -128,6 +645,138 @@ fn unsafe_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(),
     }
 }
 
+fn unchecked_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    match &stmt.kind {
+        fe::FuncStmt::Unchecked(body) => {
+            if scope.inherits_type(BlockScopeType::Unchecked) {
+                scope.error(
+                    "unnecessary `unchecked` block",
+                    stmt.span,
+                    "this `unchecked` block is nested inside another `unchecked` block",
+                );
+            }
+            traverse_statements(&mut scope.new_child(BlockScopeType::Unchecked), body)
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Checks the inputs and outputs of a `yul` block, and declares its outputs
+/// as variables. The Yul code itself isn't checked, but the instructions that
+/// it uses are recorded for the state mutability of the function.
+fn yul_block(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    if let fe::FuncStmt::Yul {
+        inputs,
+        outputs,
+        code,
+    } = &stmt.kind
+    {
+        scope
+            .root
+            .add_yul_block(stmt, yul_state_mutability(&code.kind));
+
+        if !scope.inherits_type(BlockScopeType::Unsafe) {
+            scope.fancy_error(
+                "inline Yul can only be used in an unsafe function or block",
+                vec![Label::primary(stmt.span, "`yul` block used here")],
+                vec!["Hint: put this `yul` block in an `unsafe` block".into()],
+            );
+        }
+
+        let mut names: Vec<&Node<SmolStr>> = vec![];
+        for name in inputs
+            .iter()
+            .map(|input| &input.kind.name)
+            .chain(outputs.iter().map(|output| &output.kind.name))
+        {
+            if let Some(original) = names.iter().find(|original| original.kind == name.kind) {
+                scope.duplicate_name_error(
+                    &format!("duplicate `yul` block binding `{}`", name.kind),
+                    &name.kind,
+                    original.span,
+                    name.span,
+                );
+            } else {
+                names.push(name);
+            }
+        }
+
+        for input in inputs {
+            let value = &input.kind.value;
+            // An error in one input doesn't stop the others from being checked.
+            let typ = match expressions::assignable_expr(scope, value, None) {
+                Ok(attributes) => attributes.typ,
+                Err(_) => continue,
+            };
+            if !matches!(
+                typ,
+                Type::Base(Base::Numeric(_) | Base::Bool | Base::Address)
+                    | Type::Contract(_)
+                    | Type::Array(_)
+                    | Type::Tuple(_)
+                    | Type::String(_)
+                    | Type::Struct(_)
+                    | Type::Vec(_)
+            ) {
+                scope.fancy_error(
+                    "invalid `yul` block input",
+                    vec![Label::primary(
+                        value.span,
+                        format!("this has type `{}`", typ),
+                    )],
+                    vec!["Note: inputs must be of a primitive type, or data in memory (which is passed as a pointer)".into()],
+                );
+            }
+        }
+
+        for output in outputs {
+            let fe::YulOutput { name, typ } = &output.kind;
+            let output_type = match types::type_desc(scope, typ)? {
+                Type::Base(base @ (Base::Numeric(_) | Base::Bool | Base::Address)) => {
+                    FixedSize::Base(base)
+                }
+                other => {
+                    scope.fancy_error(
+                        "invalid `yul` block output",
+                        vec![Label::primary(
+                            typ.span,
+                            format!("this has type `{}`", other),
+                        )],
+                        vec!["Note: outputs must be of a primitive type".into()],
+                    );
+                    continue;
+                }
+            };
+            scope.root.add_declaration(typ, output_type.clone());
+            // this logs a message on err, so it's safe to ignore here.
+            let _ = scope.add_var(&name.kind, output_type, false, name.span);
+        }
+        return Ok(());
+    }
+
+    unreachable!()
+}
+
+/// The state mutability of the instructions called in a piece of Yul code.
+fn yul_state_mutability(code: &str) -> StateMutability {
+    let mut mutability = StateMutability::Pure;
+    let mut rest = code;
+    while let Some(start) = rest.find(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        rest = &rest[start..];
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            .unwrap_or(rest.len());
+        let (name, after) = rest.split_at(end);
+        if after.trim_start().starts_with('(') {
+            if let Ok(intrinsic) = Intrinsic::from_str(&format!("__{}", name)) {
+                mutability = mutability.max(intrinsic.state_mutability());
+            }
+        }
+        rest = after;
+    }
+    mutability
+}
+
 fn while_loop(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     match &stmt.kind {
         fe::FuncStmt::While { test, body } => {
@@ -148,6 +797,41 @@ fn while_loop(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), F
     }
 }
 
+fn loop_statement(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
+    match &stmt.kind {
+        fe::FuncStmt::Loop { result: None, body } => {
+            traverse_statements(&mut scope.new_child(BlockScopeType::Loop), body)
+        }
+        fe::FuncStmt::Loop {
+            result: Some((name, typ)),
+            body,
+        } => {
+            let declared_type = match FixedSize::try_from(types::type_desc(scope, typ)?) {
+                Ok(typ) => typ,
+                Err(_) => {
+                    return Err(FatalError::new(scope.error(
+                        "invalid variable type",
+                        typ.span,
+                        "`Map` type can only be used as a contract field",
+                    )));
+                }
+            };
+
+            traverse_statements(
+                &mut scope.new_child(BlockScopeType::LoopWithValue(declared_type.clone())),
+                body,
+            )?;
+
+            // The variable is only in scope after the loop.
+            scope.root.add_declaration(typ, declared_type.clone());
+            // add_var emits a msg on err; we can ignore the Result.
+            let _ = scope.add_var(&name.kind, declared_type, false, name.span);
+            Ok(())
+        }
+        _ => unreachable!(),
+    }
+}
+
 fn emit(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalError> {
     if let fe::FuncStmt::Emit { name, args } = &stmt.kind {
         match scope.resolve_name(&name.kind)? {
@@ -161,14 +845,7 @@ fn emit(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), FatalEr
             Some(NamedThing::Item(Item::Event(event))) => {
                 scope.root.add_emit(stmt, event);
                 let params = event.typ(scope.db()).fields.clone();
-                call_args::validate_named_args(
-                    scope,
-                    &name.kind,
-                    name.span,
-                    args,
-                    &params,
-                    LabelPolicy::AllowUnlabeledIfNameEqual,
-                )?;
+                call_args::validate_named_args(scope, &name.kind, name.span, args, &params)?;
             }
             Some(named_thing) => {
                 scope.error(
@@ -220,15 +897,39 @@ fn revert(scope: &mut BlockScope, stmt: &Node<fe::FuncStmt>) -> Result<(), Fatal
     if let fe::FuncStmt::Revert { error } = &stmt.kind {
         if let Some(error_expr) = error {
             let error_attributes = expressions::assignable_expr(scope, error_expr, None)?;
-            if !matches!(error_attributes.typ, Type::Struct(_)) {
-                scope.error(
-                    "`revert` error must be a struct",
-                    error_expr.span,
-                    &format!(
-                        "this has type `{}`; expected a struct",
-                        error_attributes.typ
-                    ),
-                );
+            match &error_attributes.typ {
+                Type::Struct(struct_) => {
+                    if !struct_.id.is_error(scope.db()) {
+                        scope.fancy_error(
+                            &format!("`{}` isn't an error type", struct_.name),
+                            vec![
+                                Label::primary(error_expr.span, "this can't be used as an error"),
+                                Label::secondary(
+                                    struct_.id.name_span(scope.db()),
+                                    "this struct isn't marked `#[error]`",
+                                ),
+                            ],
+                            vec![format!(
+                                "Hint: add `#[error]` to the definition of `{}`",
+                                struct_.name
+                            )],
+                        );
+                    }
+                    if FixedSize::Struct(struct_.clone()).contains_vec(scope.db()) {
+                        scope.error(
+                            "`revert` error can't hold a `Vec`",
+                            error_expr.span,
+                            "this error holds a `Vec`, which can't be abi encoded",
+                        );
+                    }
+                }
+                typ => {
+                    scope.error(
+                        "`revert` error must be a struct",
+                        error_expr.span,
+                        &format!("this has type `{}`; expected a struct", typ),
+                    );
+                }
             }
         }
 
@@ -3,7 +3,11 @@ pub mod pragma;
 pub mod types;
 
 pub(crate) mod const_expr;
+pub(crate) mod dead_stores;
+pub(crate) mod definite_assignment;
 pub(crate) mod expressions;
+pub(crate) mod overflow_checks;
+pub(crate) mod unused_variables;
 
 mod assignments;
 mod call_args;
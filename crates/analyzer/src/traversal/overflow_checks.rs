@@ -0,0 +1,235 @@
+use crate::context::FunctionBody;
+use crate::namespace::types::{Base, Type};
+use fe_common::Span;
+use fe_parser::ast;
+use fe_parser::node::{Node, NodeId};
+
+/// Finds the integer arithmetic in `block` that reverts on overflow only if
+/// the build profile enables overflow checks, i.e. the additions,
+/// subtractions, multiplications, exponentiations and negations that aren't
+/// in an `unchecked` block or a `#[checked]` statement. Division and modulo
+/// are always checked, and the values of constants are computed at compile
+/// time.
+///
+/// The operations are returned by the id of their expression node, or of
+/// their statement node for augmented assignments like `x += 1`.
+pub fn profile_checked_arithmetic(
+    body: &FunctionBody,
+    block: &[Node<ast::FuncStmt>],
+) -> Vec<(NodeId, Span)> {
+    let mut finder = ArithmeticFinder {
+        body,
+        explicit: false,
+        found: vec![],
+    };
+    finder.block(block);
+    finder.found
+}
+
+struct ArithmeticFinder<'a> {
+    body: &'a FunctionBody,
+    /// `true` in `unchecked` blocks and `#[checked]` statements, where the
+    /// overflow checks don't depend on the build profile.
+    explicit: bool,
+    found: Vec<(NodeId, Span)>,
+}
+
+impl ArithmeticFinder<'_> {
+    fn block(&mut self, block: &[Node<ast::FuncStmt>]) {
+        for stmt in block {
+            self.stmt(stmt)
+        }
+    }
+
+    fn explicit_block(&mut self, block: &[Node<ast::FuncStmt>]) {
+        let explicit = std::mem::replace(&mut self.explicit, true);
+        self.block(block);
+        self.explicit = explicit;
+    }
+
+    fn stmt(&mut self, stmt: &Node<ast::FuncStmt>) {
+        match &stmt.kind {
+            ast::FuncStmt::AugAssign { target, op, value } => {
+                if is_checked_operator(op.kind) && self.is_integer(target) {
+                    self.record(stmt.id, stmt.span)
+                }
+                self.expr(target);
+                self.expr(value);
+            }
+            ast::FuncStmt::Assign { target, value } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            ast::FuncStmt::VarDecl { value, .. }
+            | ast::FuncStmt::Return { value }
+            | ast::FuncStmt::Break { value } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+            }
+            ast::FuncStmt::Revert { error } => {
+                if let Some(error) = error {
+                    self.expr(error)
+                }
+            }
+            ast::FuncStmt::Expr { value } => self.expr(value),
+            ast::FuncStmt::Assert { test, msg } => {
+                self.expr(test);
+                if let Some(msg) = msg {
+                    self.expr(msg)
+                }
+            }
+            ast::FuncStmt::Emit { args, .. } => {
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+            }
+            ast::FuncStmt::For { iter, body, .. } => {
+                self.expr(iter);
+                self.block(body);
+            }
+            ast::FuncStmt::While { test, body } => {
+                self.expr(test);
+                self.block(body);
+            }
+            ast::FuncStmt::Loop { body, .. } | ast::FuncStmt::Unsafe(body) => self.block(body),
+            ast::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(test);
+                self.block(body);
+                self.block(or_else);
+            }
+            ast::FuncStmt::Match { value, arms } => {
+                self.expr(value);
+                for arm in arms {
+                    self.block(&arm.kind.body);
+                }
+            }
+            ast::FuncStmt::Try {
+                call,
+                body,
+                catches,
+                ..
+            } => {
+                self.expr(call);
+                self.block(body);
+                for clause in catches {
+                    self.block(&clause.kind.body);
+                }
+            }
+            ast::FuncStmt::Unchecked(body) | ast::FuncStmt::Checked(body) => {
+                self.explicit_block(body)
+            }
+            ast::FuncStmt::Yul { inputs, .. } => {
+                for input in inputs {
+                    self.expr(&input.kind.value)
+                }
+            }
+            ast::FuncStmt::ConstantDecl { .. } | ast::FuncStmt::Pass | ast::FuncStmt::Continue => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Node<ast::Expr>) {
+        match &expr.kind {
+            ast::Expr::BinOperation { left, op, right } => {
+                if is_checked_operator(op.kind) && self.is_integer(left) {
+                    self.record(expr.id, expr.span)
+                }
+                self.expr(left);
+                self.expr(right);
+            }
+            ast::Expr::UnaryOperation { op, operand } => {
+                // The negations of literals are checked at compile time.
+                if op.kind == ast::UnaryOperator::USub
+                    && !matches!(operand.kind, ast::Expr::Num(_))
+                    && self.is_integer(operand)
+                {
+                    self.record(expr.id, expr.span)
+                }
+                self.expr(operand);
+            }
+            ast::Expr::Call {
+                func, args, base, ..
+            } => {
+                self.expr(func);
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+                if let Some(base) = base {
+                    self.expr(base)
+                }
+            }
+            ast::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                for expr in [if_expr, test, else_expr] {
+                    self.expr(expr)
+                }
+            }
+            ast::Expr::BoolOperation { left, right, .. }
+            | ast::Expr::CompOperation { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            ast::Expr::ChainedComparison { left, comparisons } => {
+                self.expr(left);
+                for (_, operand) in comparisons {
+                    self.expr(operand)
+                }
+            }
+            ast::Expr::Attribute { value, .. } => self.expr(value),
+            ast::Expr::Subscript { value, index } => {
+                self.expr(value);
+                self.expr(index);
+            }
+            ast::Expr::List { elts, fill } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+                if let Some(fill) = fill {
+                    self.expr(fill)
+                }
+            }
+            ast::Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+            }
+            ast::Expr::Bool(_)
+            | ast::Expr::Name(_)
+            | ast::Expr::Path(_)
+            | ast::Expr::Num(_)
+            | ast::Expr::Str(_)
+            | ast::Expr::Unit => {}
+        }
+    }
+
+    fn record(&mut self, id: NodeId, span: Span) {
+        if !self.explicit {
+            self.found.push((id, span))
+        }
+    }
+
+    fn is_integer(&self, expr: &Node<ast::Expr>) -> bool {
+        matches!(
+            self.body.expressions.get(&expr.id),
+            Some(attributes) if matches!(attributes.typ, Type::Base(Base::Numeric(_)))
+        )
+    }
+}
+
+/// Whether the operator reverts on overflow, if overflow checks are enabled.
+fn is_checked_operator(op: ast::BinOperator) -> bool {
+    matches!(
+        op,
+        ast::BinOperator::Add
+            | ast::BinOperator::Sub
+            | ast::BinOperator::Mult
+            | ast::BinOperator::Pow
+    )
+}
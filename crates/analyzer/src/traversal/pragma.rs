@@ -1,11 +1,20 @@
+use crate::context::AnalyzerContext;
 use crate::errors;
+use crate::features::Feature;
+use crate::namespace::items::Item;
 use fe_common::diagnostics::{Diagnostic, Label};
+use fe_common::Span;
 use fe_parser::ast;
 use fe_parser::node::Node;
 use semver::{Version, VersionReq};
 
 pub fn check_pragma_version(stmt: &Node<ast::Pragma>) -> Option<Diagnostic> {
-    let version_requirement = &stmt.kind.version_requirement;
+    let version_requirement = match &stmt.kind {
+        ast::Pragma::Version {
+            version_requirement,
+        } => version_requirement,
+        _ => return None,
+    };
     // This can't fail because the parser already validated it
     let requirement =
         VersionReq::parse(&version_requirement.kind).expect("Invalid version requirement");
@@ -31,3 +40,36 @@ pub fn check_pragma_version(stmt: &Node<ast::Pragma>) -> Option<Diagnostic> {
         ))
     }
 }
+
+/// Emits an error if the feature isn't enabled in the module of the context.
+pub fn check_feature(context: &mut dyn AnalyzerContext, feature: Feature, span: Span) {
+    let mut item = context.root_item();
+    let module = loop {
+        match item {
+            Item::Module(module) => break module,
+            _ => item = item.parent(context.db()).expect("item has no module"),
+        }
+    };
+    if module.features(context.db()).is_enabled(feature) {
+        return;
+    }
+
+    let mut notes = vec![format!(
+        "Hint: add `pragma feature({})` to the module to enable {}",
+        feature.as_ref(),
+        feature.display_name()
+    )];
+    if let Some(edition) = feature.stable_since() {
+        notes.push(format!(
+            "Note: {} are stable in edition {}, which is selected with `pragma edition {}`",
+            feature.display_name(),
+            edition.as_ref(),
+            edition.as_ref()
+        ));
+    }
+    context.fancy_error(
+        &format!("{} are experimental", feature.display_name()),
+        vec![Label::primary(span, "used here")],
+        notes,
+    );
+}
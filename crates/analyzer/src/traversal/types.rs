@@ -1,13 +1,20 @@
 use crate::context::{AnalyzerContext, Constant, NamedThing};
 use crate::errors::TypeError;
-use crate::namespace::items::Item;
-use crate::namespace::types::{FixedSize, GenericArg, GenericParamKind, GenericType, Tuple, Type};
+use crate::features::Feature;
+use crate::namespace::items::{Item, ModuleId, StructId, TypeDef};
+use crate::namespace::types::{
+    FixedSize, FunctionType, GenericArg, GenericParam, GenericParamKind, GenericType, Tuple, Type,
+};
 use crate::traversal::call_args::validate_arg_count;
+use crate::traversal::pragma::check_feature;
 use fe_common::diagnostics::Label;
 use fe_common::utils::humanize::pluralize_conditionally;
 use fe_common::Spanned;
 use fe_parser::ast;
 use fe_parser::node::{Node, Span};
+use smol_str::SmolStr;
+use std::collections::HashMap;
+use std::rc::Rc;
 use vec1::Vec1;
 
 pub fn apply_generic_type_args(
@@ -16,14 +23,81 @@ pub fn apply_generic_type_args(
     name_span: Span,
     args: Option<&Node<Vec<ast::GenericArg>>>,
 ) -> Result<Type, TypeError> {
-    let params = generic.params();
+    let concrete_args =
+        check_generic_args(context, &generic.name(), &generic.params(), name_span, args)?;
+    Ok(generic
+        .apply(&concrete_args)
+        .expect("failed to construct generic type after checking args"))
+}
+
+/// Returns the type of the instance of a generic struct with the given generic
+/// args.
+pub fn apply_generic_struct_args(
+    context: &mut dyn AnalyzerContext,
+    struct_: StructId,
+    name_span: Span,
+    args: Option<&Node<Vec<ast::GenericArg>>>,
+) -> Result<Type, TypeError> {
+    if context_module(context) != Some(struct_.module(context.db())) {
+        return Err(TypeError::new(context.not_yet_implemented(
+            "using generic structs that are defined in another module",
+            name_span,
+        )));
+    }
+    let db = context.db();
+    let params = struct_
+        .generic_params(db)
+        .iter()
+        .map(|name| GenericParam {
+            name: format!("type parameter `{}`", name).into(),
+            kind: GenericParamKind::FixedSizeType,
+        })
+        .collect::<Vec<_>>();
+    let name = struct_.name(db);
+    let concrete_args = check_generic_args(context, &name, &params, name_span, args)?;
+    let instance = struct_.instantiate(context.db(), fixed_size_args(concrete_args));
+    Ok(Type::Struct(instance.typ(context.db()).as_ref().clone()))
+}
 
+/// Converts the checked args of a generic item whose parameters are all
+/// [`GenericParamKind::FixedSizeType`] to types.
+pub fn fixed_size_args(args: Vec<GenericArg>) -> Vec<FixedSize> {
+    args.into_iter()
+        .map(|arg| match arg {
+            GenericArg::Type(typ) => {
+                FixedSize::try_from(typ).expect("generic arg type must have a fixed size")
+            }
+            GenericArg::Int(_) => panic!("generic arg must be a type"),
+        })
+        .collect()
+}
+
+/// The module that encloses the item being analyzed.
+pub fn context_module(context: &dyn AnalyzerContext) -> Option<ModuleId> {
+    let db = context.db();
+    match context.root_item() {
+        Item::Module(id) => Some(id),
+        Item::Type(TypeDef::Struct(id)) => Some(id.module(db)),
+        Item::Type(TypeDef::Contract(id)) => Some(id.module(db)),
+        _ => None,
+    }
+}
+
+/// Checks the generic args of a generic type or function against its generic
+/// parameters.
+pub fn check_generic_args(
+    context: &mut dyn AnalyzerContext,
+    name: &str,
+    params: &[GenericParam],
+    name_span: Span,
+    args: Option<&Node<Vec<ast::GenericArg>>>,
+) -> Result<Vec<GenericArg>, TypeError> {
     let args = args.ok_or_else(|| {
         TypeError::new(context.fancy_error(
             &format!(
                 "missing generic {} for type `{}`",
                 pluralize_conditionally("argument", params.len()),
-                generic.name()
+                name
             ),
             vec![Label::primary(
                 name_span,
@@ -33,13 +107,13 @@ pub fn apply_generic_type_args(
                     pluralize_conditionally("argument", params.len())
                 ),
             )],
-            vec![friendly_generic_arg_example_string(generic)],
+            vec![friendly_generic_arg_example_string(name, params)],
         ))
     })?;
 
     if let Some(diag) = validate_arg_count(
         context,
-        &generic.name(),
+        name,
         name_span,
         args,
         params.len(),
@@ -48,7 +122,7 @@ pub fn apply_generic_type_args(
         return Err(TypeError::new(diag));
     }
 
-    let concrete_args = params
+    params
         .iter()
         .zip(args.kind.iter())
         .map(|(param, arg)| match (param.kind, arg) {
@@ -56,12 +130,28 @@ pub fn apply_generic_type_args(
                 Ok(GenericArg::Int(int_node.kind))
             }
 
-            (GenericParamKind::Int, ast::GenericArg::TypeDesc(_)) => {
-                Err(TypeError::new(context.fancy_error(
-                    &format!("`{}` {} must be an integer", generic.name(), param.name),
-                    vec![Label::primary(arg.span(), "expected an integer")],
-                    vec![],
-                )))
+            (GenericParamKind::Int, ast::GenericArg::TypeDesc(type_node)) => {
+                // An integer constant can be given by name, eg. `Array<u8, SIZE>`.
+                let value = match &type_node.kind {
+                    ast::TypeDesc::Base { base } => {
+                        context.constant_value_by_name(base).map_err(|err| {
+                            super::const_expr::constant_error(context, base, type_node.span, err)
+                        })?
+                    }
+                    _ => None,
+                };
+
+                match value.and_then(|value| match value {
+                    Constant::Int(val) => usize::try_from(val).ok(),
+                    Constant::Bool(_) | Constant::Str(_) => None,
+                }) {
+                    Some(val) => Ok(GenericArg::Int(val)),
+                    None => Err(TypeError::new(context.fancy_error(
+                        &format!("`{}` {} must be an integer", name, param.name),
+                        vec![Label::primary(arg.span(), "expected an integer")],
+                        vec![],
+                    ))),
+                }
             }
 
             (GenericParamKind::Int, ast::GenericArg::ConstExpr(expr)) => {
@@ -84,48 +174,87 @@ pub fn apply_generic_type_args(
                 match type_desc(context, type_node)? {
                     Type::Base(base) => Ok(GenericArg::Type(Type::Base(base))),
                     typ => Err(TypeError::new(context.error(
-                        &format!(
-                            "`{}` {} must be a primitive type",
-                            generic.name(),
-                            param.name
-                        ),
+                        &format!("`{}` {} must be a primitive type", name, param.name),
                         type_node.span,
                         &format!("this has type `{}`; expected a primitive type", typ),
                     ))),
                 }
             }
 
+            (GenericParamKind::FixedSizeType, ast::GenericArg::TypeDesc(type_node)) => {
+                let typ = type_desc(context, type_node)?;
+                if FixedSize::try_from(typ.clone()).is_ok() {
+                    Ok(GenericArg::Type(typ))
+                } else {
+                    Err(TypeError::new(context.error(
+                        &format!("`{}` {} must have a fixed size", name, param.name),
+                        type_node.span,
+                        &format!("this has type `{}`; expected a type with a fixed size", typ),
+                    )))
+                }
+            }
+
             (GenericParamKind::AnyType, ast::GenericArg::TypeDesc(type_node)) => {
                 Ok(GenericArg::Type(type_desc(context, type_node)?))
             }
 
             (
-                GenericParamKind::PrimitiveType | GenericParamKind::AnyType,
+                GenericParamKind::PrimitiveType
+                | GenericParamKind::FixedSizeType
+                | GenericParamKind::AnyType,
                 ast::GenericArg::Int(_) | ast::GenericArg::ConstExpr(_),
             ) => Err(TypeError::new(context.fancy_error(
-                &format!("`{}` {} must be a type", generic.name(), param.name),
+                &format!("`{}` {} must be a type", name, param.name),
                 vec![Label::primary(arg.span(), "expected a type name")],
                 vec![],
             ))),
         })
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(generic
-        .apply(&concrete_args)
-        .expect("failed to construct generic type after checking args"))
+        .collect()
 }
 
-fn friendly_generic_arg_example_string(generic: GenericType) -> String {
-    let example_args = generic
-        .params()
+fn friendly_generic_arg_example_string(name: &str, params: &[GenericParam]) -> String {
+    let example_args = params
         .iter()
         .map(|param| match param.kind {
             GenericParamKind::Int => "32",
             GenericParamKind::PrimitiveType => "u64",
+            GenericParamKind::FixedSizeType => "u256",
             GenericParamKind::AnyType => "String<32>",
         })
         .collect::<Vec<&'static str>>();
 
-    format!("Example: `{}<{}>`", generic.name(), example_args.join(", "))
+    format!("Example: `{}<{}>`", name, example_args.join(", "))
+}
+
+/// Checks the generic parameter list of a struct or function definition, and
+/// returns the names of the parameters.
+pub fn generic_params(
+    context: &mut dyn AnalyzerContext,
+    item_desc: &str,
+    params: &[Node<SmolStr>],
+) -> Rc<[SmolStr]> {
+    let mut names = HashMap::<&SmolStr, Span>::new();
+    for param in params {
+        if let Ok(Some(named_item)) = context.resolve_name(&param.kind) {
+            context.name_conflict_error(
+                "generic parameter",
+                &param.kind,
+                &named_item,
+                named_item.name_span(context.db()),
+                param.span,
+            );
+        } else if let Some(original) = names.get(&param.kind) {
+            context.duplicate_name_error(
+                &format!("duplicate generic parameter names in {}", item_desc),
+                &param.kind,
+                *original,
+                param.span,
+            );
+        } else {
+            names.insert(&param.kind, param.span);
+        }
+    }
+    params.iter().map(|param| param.kind.clone()).collect()
 }
 
 pub fn resolve_concrete_type_name<T: std::fmt::Display>(
@@ -155,6 +284,9 @@ pub fn resolve_concrete_type_named_thing<T: std::fmt::Display>(
     generic_args: Option<&Node<Vec<ast::GenericArg>>>,
 ) -> Result<Type, TypeError> {
     match named_thing {
+        Some(NamedThing::Item(Item::Type(TypeDef::Struct(id)))) if id.is_generic(context.db()) => {
+            apply_generic_struct_args(context, id, base_desc.span, generic_args)
+        }
         Some(NamedThing::Item(Item::Type(id))) => {
             if let Some(args) = generic_args {
                 context.fancy_error(
@@ -214,7 +346,10 @@ pub fn type_desc(
     desc: &Node<ast::TypeDesc>,
 ) -> Result<Type, TypeError> {
     match &desc.kind {
-        ast::TypeDesc::Base { base } => resolve_concrete_type_name(context, base, desc, None),
+        ast::TypeDesc::Base { base } => match context.resolve_type_param(base) {
+            Some(typ) => Ok(typ.into()),
+            None => resolve_concrete_type_name(context, base, desc, None),
+        },
         ast::TypeDesc::Path(path) => resolve_concrete_type_path(context, path, desc, None),
         // generic will need to allow for paths too
         ast::TypeDesc::Generic { base, args } => {
@@ -224,6 +359,9 @@ pub fn type_desc(
             let types = items
                 .iter()
                 .map(|typ| match FixedSize::try_from(type_desc(context, typ)?) {
+                    Ok(FixedSize::Function(_)) => Err(TypeError::new(
+                        context.not_yet_implemented("function types in tuples", typ.span),
+                    )),
                     Ok(typ) => Ok(typ),
                     Err(_) => Err(TypeError::new(context.error(
                         "tuple elements must have fixed size",
@@ -236,6 +374,39 @@ pub fn type_desc(
                 items: Vec1::try_from_vec(types).expect("tuple is empty"),
             }))
         }
+        ast::TypeDesc::Function {
+            params,
+            return_type,
+        } => {
+            check_feature(context, Feature::FunctionTypes, desc.span);
+            let params = params
+                .iter()
+                .map(|param| fn_type_item(context, param))
+                .collect::<Result<Vec<_>, _>>()?;
+            let return_type = match return_type {
+                Some(desc) => fn_type_item(context, desc)?,
+                None => FixedSize::unit(),
+            };
+            Ok(Type::Function(FunctionType {
+                params,
+                return_type: Box::new(return_type),
+            }))
+        }
         ast::TypeDesc::Unit => Ok(Type::unit()),
     }
 }
+
+/// Maps the type description of a parameter or the return value of a
+/// function type.
+fn fn_type_item(
+    context: &mut dyn AnalyzerContext,
+    desc: &Node<ast::TypeDesc>,
+) -> Result<FixedSize, TypeError> {
+    FixedSize::try_from(type_desc(context, desc)?).map_err(|_| {
+        TypeError::new(context.error(
+            "function type parameters and return values must have fixed size",
+            desc.span,
+            "this type doesn't have a fixed size",
+        ))
+    })
+}
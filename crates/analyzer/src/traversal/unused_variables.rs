@@ -0,0 +1,293 @@
+use fe_common::Span;
+use fe_parser::ast;
+use fe_parser::node::Node;
+use indexmap::IndexMap;
+use smol_str::SmolStr;
+
+/// A local variable whose value is never read.
+pub struct UnusedVariable {
+    pub name: SmolStr,
+    /// The declaration of the variable.
+    pub span: Span,
+}
+
+/// Finds the local variables, local constants and match bindings in `block`
+/// that are never read. Assigning a new value to a variable doesn't count as
+/// reading it. Names that start with an underscore are ignored.
+pub fn unused_variables(block: &[Node<ast::FuncStmt>]) -> Vec<UnusedVariable> {
+    let mut finder = Finder {
+        scopes: vec![],
+        unused: vec![],
+    };
+    finder.block(block, &[]);
+    finder.unused.sort_by_key(|var| var.span.start);
+    finder.unused
+}
+
+struct Finder {
+    /// The variables of each enclosing block, with their declaration and
+    /// whether they've been read.
+    scopes: Vec<IndexMap<SmolStr, (Span, bool)>>,
+    unused: Vec<UnusedVariable>,
+}
+
+impl Finder {
+    /// Checks a block, whose scope starts out with the given variables.
+    fn block(&mut self, block: &[Node<ast::FuncStmt>], vars: &[(&SmolStr, Span)]) {
+        self.scopes.push(IndexMap::new());
+        for (name, span) in vars {
+            self.declare(name, *span)
+        }
+        for stmt in block {
+            self.stmt(stmt)
+        }
+        let scope = self.scopes.pop().expect("no scope");
+        self.unused.extend(
+            scope
+                .into_iter()
+                .filter(|(_, (_, is_read))| !is_read)
+                .map(|(name, (span, _))| UnusedVariable { name, span }),
+        );
+    }
+
+    fn declare(&mut self, name: &SmolStr, span: Span) {
+        if !name.starts_with('_') {
+            let scope = self.scopes.last_mut().expect("no scope");
+            scope.insert(name.clone(), (span, false));
+        }
+    }
+
+    fn declare_target(&mut self, target: &Node<ast::VarDeclTarget>) {
+        match &target.kind {
+            ast::VarDeclTarget::Name(name) => self.declare(name, target.span),
+            ast::VarDeclTarget::Tuple(elts) => {
+                for elt in elts {
+                    self.declare_target(elt)
+                }
+            }
+        }
+    }
+
+    /// The names that a match pattern binds, with their spans.
+    fn bindings<'a>(pattern: &'a Node<ast::Pattern>, names: &mut Vec<(&'a SmolStr, Span)>) {
+        match &pattern.kind {
+            ast::Pattern::Binding(name) => names.push((name, pattern.span)),
+            ast::Pattern::Variant {
+                fields: Some(fields),
+                ..
+            } => {
+                for field in fields {
+                    Self::bindings(field, names)
+                }
+            }
+            ast::Pattern::Variant { fields: None, .. }
+            | ast::Pattern::Wildcard
+            | ast::Pattern::Bool(_)
+            | ast::Pattern::Num(_) => {}
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Node<ast::FuncStmt>) {
+        match &stmt.kind {
+            ast::FuncStmt::VarDecl { target, value, .. } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+                self.declare_target(target);
+            }
+            ast::FuncStmt::ConstantDecl { name, value, .. } => {
+                self.expr(value);
+                self.declare(&name.kind, name.span);
+            }
+            ast::FuncStmt::Assign { target, value } => {
+                self.expr(value);
+                self.assign(target);
+            }
+            ast::FuncStmt::AugAssign { target, value, .. } => {
+                self.expr(value);
+                self.expr(target);
+            }
+            ast::FuncStmt::Expr { value } => self.expr(value),
+            ast::FuncStmt::Assert { test, msg } => {
+                self.expr(test);
+                if let Some(msg) = msg {
+                    self.expr(msg)
+                }
+            }
+            ast::FuncStmt::Emit { args, .. } => {
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+            }
+            ast::FuncStmt::Yul {
+                inputs, outputs, ..
+            } => {
+                for input in inputs {
+                    self.expr(&input.kind.value)
+                }
+                for output in outputs {
+                    self.declare(&output.kind.name.kind, output.kind.name.span)
+                }
+            }
+            ast::FuncStmt::Return { value } | ast::FuncStmt::Break { value } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+            }
+            ast::FuncStmt::Revert { error } => {
+                if let Some(error) = error {
+                    self.expr(error)
+                }
+            }
+            ast::FuncStmt::For { target, iter, body } => {
+                self.expr(iter);
+                self.block(body, &[(&target.kind, target.span)]);
+            }
+            ast::FuncStmt::While { test, body } => {
+                self.expr(test);
+                self.block(body, &[]);
+            }
+            // The result of a loop is declared after the loop.
+            ast::FuncStmt::Loop { result, body } => {
+                self.block(body, &[]);
+                if let Some((name, _)) = result {
+                    self.declare(&name.kind, name.span)
+                }
+            }
+            ast::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(test);
+                self.block(body, &[]);
+                self.block(or_else, &[]);
+            }
+            ast::FuncStmt::Match { value, arms } => {
+                self.expr(value);
+                for arm in arms {
+                    let mut bindings = vec![];
+                    Self::bindings(&arm.kind.pattern, &mut bindings);
+                    self.block(&arm.kind.body, &bindings);
+                }
+            }
+            ast::FuncStmt::Try {
+                call,
+                binding,
+                body,
+                catches,
+            } => {
+                self.expr(call);
+                let bindings = binding
+                    .iter()
+                    .map(|name| (&name.kind, name.span))
+                    .collect::<Vec<_>>();
+                self.block(body, &bindings);
+                for clause in catches {
+                    let bindings = clause
+                        .kind
+                        .error
+                        .iter()
+                        .map(|(_, name)| (&name.kind, name.span))
+                        .collect::<Vec<_>>();
+                    self.block(&clause.kind.body, &bindings);
+                }
+            }
+            ast::FuncStmt::Unsafe(body) | ast::FuncStmt::Unchecked(body) => self.block(body, &[]),
+            // The statement isn't a block, so its declarations stay in scope.
+            ast::FuncStmt::Checked(body) => {
+                for stmt in body {
+                    self.stmt(stmt)
+                }
+            }
+            ast::FuncStmt::Pass | ast::FuncStmt::Continue => {}
+        }
+    }
+
+    /// Visits the target of an assignment. Assigning to a variable doesn't
+    /// read it, but assigning to an element or field of it does.
+    fn assign(&mut self, target: &Node<ast::Expr>) {
+        match &target.kind {
+            ast::Expr::Name(_) => {}
+            ast::Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.assign(elt)
+                }
+            }
+            _ => self.expr(target),
+        }
+    }
+
+    fn read(&mut self, name: &SmolStr) {
+        if let Some((_, is_read)) = self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            *is_read = true
+        }
+    }
+
+    fn expr(&mut self, expr: &Node<ast::Expr>) {
+        match &expr.kind {
+            ast::Expr::Name(name) => self.read(name),
+            ast::Expr::Call {
+                func, args, base, ..
+            } => {
+                self.expr(func);
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+                if let Some(base) = base {
+                    self.expr(base)
+                }
+            }
+            ast::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                for expr in [test, if_expr, else_expr] {
+                    self.expr(expr)
+                }
+            }
+            ast::Expr::BoolOperation { left, right, .. }
+            | ast::Expr::BinOperation { left, right, .. }
+            | ast::Expr::CompOperation { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            ast::Expr::ChainedComparison { left, comparisons } => {
+                self.expr(left);
+                for (_, operand) in comparisons {
+                    self.expr(operand)
+                }
+            }
+            ast::Expr::UnaryOperation { operand: value, .. }
+            | ast::Expr::Attribute { value, .. } => self.expr(value),
+            ast::Expr::Subscript { value, index } => {
+                self.expr(value);
+                self.expr(index);
+            }
+            ast::Expr::List { elts, fill } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+                if let Some(fill) = fill {
+                    self.expr(fill)
+                }
+            }
+            ast::Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+            }
+            ast::Expr::Bool(_)
+            | ast::Expr::Path(_)
+            | ast::Expr::Num(_)
+            | ast::Expr::Str(_)
+            | ast::Expr::Unit => {}
+        }
+    }
+}
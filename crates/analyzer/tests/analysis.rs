@@ -1,6 +1,9 @@
+use fe_analyzer::builtins::EvmVersion;
+use fe_analyzer::context::ExternalCallee;
 use fe_analyzer::namespace::items::{self, IngotId, IngotMode, Item, ModuleId, TypeDef};
 use fe_analyzer::namespace::types::{Event, FixedSize};
 use fe_analyzer::{AnalyzerDb, TestDb};
+use fe_common::db::SourceDb;
 use fe_common::diagnostics::{diagnostics_string, print_diagnostics, Diagnostic, Label, Severity};
 use fe_common::files::{FileKind, Utf8Path};
 use fe_parser::node::{NodeId, Span};
@@ -30,6 +33,20 @@ fn analyze_std_lib() {
     }
 }
 
+#[test]
+fn evm_version() {
+    let mut db = TestDb::default();
+    db.set_evm_version(EvmVersion::Cancun);
+    let src = test_files::fixture("compile_errors/evm_version.fe");
+    let module = ModuleId::new_standalone(&mut db, "evm_version.fe", src);
+
+    let diags = module.diagnostics(&db);
+    if !diags.is_empty() {
+        print_diagnostics(&db, &diags);
+        panic!("instructions of the target EVM version are rejected")
+    }
+}
+
 #[test]
 fn ingot_files_to_modules() {
     let mut db = TestDb::default();
@@ -72,6 +89,391 @@ fn ingot_files_to_modules() {
     assert_eq!(d.name(&db), "d");
 }
 
+#[test]
+fn items_are_in_declaration_order() {
+    let src = "
+struct Zed:
+    pub z: u256
+    pub a: u8
+
+    pub fn zz(self):
+        pass
+
+    pub fn aa(self):
+        pass
+
+contract Bar:
+    y: u256
+    b: bool
+    x: address
+
+    pub fn second():
+        pass
+
+    pub fn first():
+        pass
+
+const ALPHA: u256 = 1
+";
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "order.fe", src);
+
+    let names = module.items(&db).keys().cloned().collect::<Vec<_>>();
+    assert_eq!(names, ["Zed", "Bar", "ALPHA"]);
+    assert_eq!(module.item_index(&db, "Bar"), Some(1));
+    assert_eq!(module.item_index(&db, "ALPHA"), Some(2));
+    assert_eq!(module.item_index(&db, "Missing"), None);
+
+    let contract = module.all_contracts(&db)[0];
+    let fields = contract.fields(&db).keys().cloned().collect::<Vec<_>>();
+    assert_eq!(fields, ["y", "b", "x"]);
+    assert_eq!(contract.field_index(&db, "x"), Some(2));
+    assert_eq!(contract.field_type(&db, "b").unwrap().1, 1);
+    let functions = contract.functions(&db).keys().cloned().collect::<Vec<_>>();
+    assert_eq!(functions, ["second", "first"]);
+    assert_eq!(contract.function_index(&db, "first"), Some(1));
+
+    let struct_ = module.all_structs(&db)[0];
+    let fields = struct_.fields(&db).keys().cloned().collect::<Vec<_>>();
+    assert_eq!(fields, ["z", "a"]);
+    assert_eq!(struct_.field_index(&db, "a"), Some(1));
+    assert_eq!(struct_.function_index(&db, "zz"), Some(0));
+    assert_eq!(struct_.function_index(&db, "aa"), Some(1));
+}
+
+#[test]
+fn dependency_graph_survives_unrelated_edit() {
+    let src = "
+contract Foo:
+    pub fn get() -> u256:
+        return double(1)
+
+fn double(x: u256) -> u256:
+    return x * 2
+
+contract Bar:
+    pub fn bar() -> u256:
+        return 1
+";
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "edit.fe", src);
+    let foo = module.all_contracts(&db)[0];
+    let graph = foo.runtime_dependency_graph(&db);
+    let bar = module.all_contracts(&db)[1];
+
+    let file = match module.data(&db).source {
+        items::ModuleSource::File(file) => file,
+        _ => unreachable!(),
+    };
+    db.set_file_content(file, src.replace("return 1", "return 100").into());
+
+    // `Foo` and its dependencies are defined before the edit, so they keep
+    // their ids, and the graph isn't rebuilt.
+    assert_eq!(module.all_contracts(&db)[0], foo);
+    assert_ne!(module.all_contracts(&db)[1], bar);
+    assert!(Rc::ptr_eq(&foo.runtime_dependency_graph(&db), &graph));
+}
+
+/// The diagnostics of a module after a series of edits are the same as the
+/// diagnostics of the edited source analyzed from scratch. Edits that only
+/// move items, like added comments and blank lines, change the spans of the
+/// items, which must not be taken from the items before the edit.
+#[test]
+fn diagnostics_survive_edits() {
+    let src = "
+contract Foo:
+    pub fn get() -> u256:
+        return double(true)
+
+fn double(x: u256) -> u256:
+    return x * 2
+
+# The last item.
+contract Bar:
+    pub fn bar() -> u256:
+        return false
+";
+    let versions = [
+        src.to_string(),
+        // unchanged
+        src.to_string(),
+        // comments and whitespace only
+        format!("# A new comment.\n\n{}", src),
+        src.replace("# The last item.", "# The end item."),
+        src.replace("# The last item.", "# The very last item."),
+        // an item in the middle of the module
+        src.replace("x * 2", "x * 20"),
+        src.replace("x * 2", "x * 3"),
+        src.to_string(),
+    ];
+
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "edit.fe", &versions[0]);
+    let file = match module.data(&db).source {
+        items::ModuleSource::File(file) => file,
+        _ => unreachable!(),
+    };
+    for version in &versions {
+        db.set_file_content(file, version.as_str().into());
+        let diags = diagnostics_string(&db, &module.diagnostics(&db));
+
+        let mut fresh_db = TestDb::default();
+        let fresh_module = ModuleId::new_standalone(&mut fresh_db, "edit.fe", version);
+        let expected = diagnostics_string(&fresh_db, &fresh_module.diagnostics(&fresh_db));
+        assert_eq!(diags, expected, "diagnostics after edit to:\n{}", version);
+    }
+}
+
+#[test]
+fn lookup_child_matches_item_map() {
+    let mut db = TestDb::default();
+    let ingot = IngotId::from_files(
+        &mut db,
+        "lookup",
+        IngotMode::Lib,
+        FileKind::Local,
+        &[
+            (
+                "lib.fe",
+                "use foo::Bar\nuse foo::Baz as Qux\nstruct Point:\n    pub x: u8\nstruct Point:\n    pub y: u8\nstruct u8:\n    pub z: u8\nenum Color:\n    Red\n    Blue\n",
+            ),
+            ("foo.fe", "pub struct Bar:\n    pub x: u8\npub struct Baz:\n    pub x: u8\n"),
+        ],
+        indexmap! {},
+    );
+    let lib = ingot.root_module(&db).unwrap();
+
+    let items = lib.items(&db);
+    for name in [
+        "Bar", "Qux", "Point", "Color", "foo", "u8", "Baz", "Missing",
+    ] {
+        assert_eq!(
+            lib.lookup_child(&db, name),
+            items.get(name).copied(),
+            "{}",
+            name
+        );
+        assert_eq!(
+            Item::Ingot(ingot).lookup_child(&db, name),
+            items.get(name).copied()
+        );
+    }
+
+    let color = items["Color"];
+    let variants = color.items(&db);
+    for name in ["Red", "Blue", "Green"] {
+        assert_eq!(color.lookup_child(&db, name), variants.get(name).copied());
+    }
+}
+
+#[test]
+fn mutual_imports() {
+    let mut db = TestDb::default();
+    let ingot = IngotId::from_files(
+        &mut db,
+        "mutual",
+        IngotMode::Lib,
+        FileKind::Local,
+        &[
+            (
+                "lib.fe",
+                "use foo::*\nuse ingot::bar::{Baz, Qux as Renamed}\npub struct Bar:\n    pub x: u8\n",
+            ),
+            (
+                "foo.fe",
+                "use ingot::Bar\nuse ingot::bar::*\npub struct Foo:\n    pub x: u8\n",
+            ),
+            (
+                "bar.fe",
+                "use ingot::foo::*\npub struct Baz:\n    pub x: u8\npub struct Qux:\n    pub x: u8\n",
+            ),
+        ],
+        indexmap! {},
+    );
+    // The imports are unused, which is only a warning.
+    assert!(!ingot.diagnostics(&db).iter().any(Diagnostic::is_error));
+
+    let lib = ingot.root_module(&db).unwrap();
+    let lib_items = lib.items(&db);
+    let foo_items = lib_items["foo"].items(&db);
+    let bar_items = lib_items["bar"].items(&db);
+
+    // `foo` and `bar` import all of each other's items.
+    assert_eq!(foo_items["Baz"], bar_items["Baz"]);
+    assert_eq!(bar_items["Foo"], foo_items["Foo"]);
+    // `bar` gets `Bar` from `lib` through the glob import of `foo`.
+    assert_eq!(bar_items["Bar"], lib_items["Bar"]);
+    assert_eq!(lib_items["Foo"], foo_items["Foo"]);
+    assert_eq!(lib_items["Renamed"], bar_items["Qux"]);
+    assert_eq!(lib_items["Qux"], bar_items["Qux"]);
+}
+
+#[test]
+fn doc_comments() {
+    let src = "
+/// A point on the plane.
+struct Point:
+    /// The horizontal position.
+    pub x: u256
+    pub y: u256
+
+/// Holds the origin.
+///
+/// Isn't deployed.
+contract Origin:
+    /// The origin.
+    point: Point
+
+    /// Sets the origin.
+    pub fn set(mut self, x: u256, y: u256):
+        self.point = Point(x, y)
+
+/// The maximum value of `x`.
+const MAX_X: u256 = 100
+
+/// A wide unsigned integer.
+type Wide = u256
+";
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "docs.fe", src);
+    assert!(module.diagnostics(&db).is_empty());
+
+    let doc = |name: &str| module.items(&db)[name].doc(&db);
+    assert_eq!(doc("Point").as_deref(), Some("A point on the plane."));
+    assert_eq!(
+        doc("Origin").as_deref(),
+        Some("Holds the origin.\n\nIsn't deployed.")
+    );
+    assert_eq!(doc("MAX_X").as_deref(), Some("The maximum value of `x`."));
+    assert_eq!(doc("Wide").as_deref(), Some("A wide unsigned integer."));
+
+    let struct_ = module.all_structs(&db)[0];
+    let fields = struct_.fields(&db);
+    assert_eq!(
+        fields["x"].doc(&db).as_deref(),
+        Some("The horizontal position.")
+    );
+    assert_eq!(fields["y"].doc(&db), None);
+
+    let contract = module.all_contracts(&db)[0];
+    assert_eq!(
+        contract.fields(&db)["point"].doc(&db).as_deref(),
+        Some("The origin.")
+    );
+    assert_eq!(
+        contract.functions(&db)["set"].doc(&db).as_deref(),
+        Some("Sets the origin.")
+    );
+}
+
+#[test]
+fn in_bounds_indices() {
+    let src = "
+contract Foo:
+    pub fn literal(values: Array<u256, 4>) -> u256:
+        return values[3]
+
+    pub fn literal_out_of_bounds(values: Array<u256, 4>) -> u256:
+        return values[4]
+
+    pub fn remainder(values: Array<u256, 4>, i: u256) -> u256:
+        return values[i % 4]
+
+    pub fn large_remainder(values: Array<u256, 4>, i: u256) -> u256:
+        return values[i % 5]
+
+    pub fn variable(values: Array<u256, 4>, i: u256) -> u256:
+        return values[i]
+";
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "indices.fe", src);
+    assert!(module.diagnostics(&db).is_empty());
+
+    let contract = module.all_contracts(&db)[0];
+    let in_bounds = contract
+        .functions(&db)
+        .iter()
+        .filter(|(_, function)| !function.body(&db).in_bounds_indices.is_empty())
+        .map(|(name, _)| name.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(in_bounds, ["literal", "remainder"]);
+}
+
+#[test]
+fn external_calls() {
+    let src = "
+interface Token:
+    pub fn transfer(mut self, to: address, amount: u256) -> bool
+
+contract Child:
+    pub fn ping() -> u256:
+        return 1
+
+contract Vault:
+    pub fn withdraw(token: Token, to: address, amount: u256) -> bool:
+        try token.transfer(to, amount) as success:
+            return success
+        catch:
+            return false
+
+    pub fn refund():
+        std::send_value(address(0), 1)
+
+    pub fn deploy() -> address:
+        return address(Child.create(0))
+";
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "vault.fe", src);
+    if !module.diagnostics(&db).is_empty() {
+        print_diagnostics(&db, &module.diagnostics(&db));
+        panic!("failed to analyze vault.fe")
+    }
+
+    let vault = module.all_contracts(&db)[1];
+    let calls = vault
+        .external_calls(&db)
+        .iter()
+        .map(|call| {
+            let callee = match &call.callee {
+                ExternalCallee::Function { contract, function } => {
+                    format!("{}.{}", contract.name(&db), function.name(&db))
+                }
+                ExternalCallee::Create { contract, method } => {
+                    format!("{}.{}", contract.name(&db), method.as_ref())
+                }
+                ExternalCallee::Intrinsic(intrinsic) => intrinsic.as_ref().to_string(),
+            };
+            let code = &db.file_content(call.span.file_id)[call.span.start..call.span.end];
+            (
+                call.caller.name(&db).to_string(),
+                callee,
+                call.transfers_value,
+                call.is_try,
+                code.to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let call = |caller: &str, callee: &str, transfers_value, is_try, code: &str| {
+        (
+            caller.to_string(),
+            callee.to_string(),
+            transfers_value,
+            is_try,
+            code.to_string(),
+        )
+    };
+    assert_eq!(
+        calls,
+        [
+            call("withdraw", "Token.transfer", false, true, "token.transfer"),
+            call("deploy", "Child.create", true, false, "Child.create"),
+            // `std::send_value` sends the ether with `std::evm::call`.
+            call("call", "__call", true, false, "__call"),
+        ]
+    );
+}
+
 macro_rules! test_analysis {
     ($name:ident, $path:expr) => {
         #[test]
@@ -83,7 +485,7 @@ macro_rules! test_analysis {
             let module = ModuleId::new_standalone(&mut db, file_name, test_files::fixture($path));
 
             let diags = module.diagnostics(&db);
-            if !diags.is_empty() {
+            if diags.iter().any(Diagnostic::is_error) {
                 print_diagnostics(&db, &diags);
                 panic!("analysis failed")
             }
@@ -121,7 +523,7 @@ macro_rules! test_analysis_ingot {
             );
 
             let diags = ingot.diagnostics(&db);
-            if !diags.is_empty() {
+            if diags.iter().any(Diagnostic::is_error) {
                 print_diagnostics(&db, &diags);
                 panic!("analysis failed")
             }
@@ -200,10 +602,15 @@ test_analysis! { create2_contract, "features/create2_contract.fe"}
 test_analysis! { create_contract, "features/create_contract.fe"}
 test_analysis! { create_contract_from_init, "features/create_contract_from_init.fe"}
 test_analysis! { empty, "features/empty.fe"}
+test_analysis! { enums, "features/enums.fe"}
 test_analysis! { events, "features/events.fe"}
+test_analysis! { generics, "features/generics.fe"}
+test_analysis! { match_stmt, "features/match.fe"}
 test_analysis! { module_level_events, "features/module_level_events.fe"}
 test_analysis! { external_contract, "features/external_contract.fe"}
+test_analysis! { interface, "features/interface.fe"}
 test_analysis! { for_loop_with_break, "features/for_loop_with_break.fe"}
+test_analysis! { loop_stmt, "features/loop.fe"}
 test_analysis! { for_loop_with_continue, "features/for_loop_with_continue.fe"}
 test_analysis! { for_loop_with_static_array, "features/for_loop_with_static_array.fe"}
 test_analysis! { if_statement, "features/if_statement.fe"}
@@ -217,6 +624,7 @@ test_analysis! { nested_map, "features/nested_map.fe"}
 test_analysis! { numeric_sizes, "features/numeric_sizes.fe"}
 test_analysis! { ownable, "features/ownable.fe"}
 test_analysis! { pure_fn_standalone, "features/pure_fn_standalone.fe"}
+test_analysis! { reordered_call_args, "features/reordered_call_args.fe"}
 test_analysis! { return_addition_i256, "features/return_addition_i256.fe"}
 test_analysis! { return_addition_u128, "features/return_addition_u128.fe"}
 test_analysis! { return_addition_u256, "features/return_addition_u256.fe"}
@@ -271,12 +679,20 @@ test_analysis! { return_u256_from_called_fn, "features/return_u256_from_called_f
 test_analysis! { return_u256_from_called_fn_with_args, "features/return_u256_from_called_fn_with_args.fe"}
 test_analysis! { revert, "features/revert.fe"}
 test_analysis! { self_address, "features/self_address.fe"}
+test_analysis! { permit, "features/permit.fe"}
 test_analysis! { send_value, "features/send_value.fe"}
 test_analysis! { balances, "features/balances.fe"}
 test_analysis! { sized_vals_in_sto, "features/sized_vals_in_sto.fe"}
 test_analysis! { strings, "features/strings.fe"}
+test_analysis! { string_operations, "features/string_operations.fe"}
+test_analysis! { vec, "features/vec.fe"}
+test_analysis! { function_values, "features/function_values.fe"}
+test_analysis! { init_only, "features/init_only.fe"}
 test_analysis! { structs, "features/structs.fe"}
 test_analysis! { struct_fns, "features/struct_fns.fe"}
+test_analysis! { packed_struct, "features/packed_struct.fe"}
+test_analysis! { integer_conversions, "features/integer_conversions.fe"}
+test_analysis! { operator_overloading, "features/operator_overloading.fe"}
 test_analysis! { ternary_expression, "features/ternary_expression.fe"}
 test_analysis! { two_contracts, "features/two_contracts.fe"}
 test_analysis! { u8_u8_map, "features/u8_u8_map.fe"}
@@ -292,6 +708,7 @@ test_analysis! { while_loop_with_continue, "features/while_loop_with_continue.fe
 test_analysis! { abi_encoding_stress, "stress/abi_encoding_stress.fe"}
 test_analysis! { data_copying_stress, "stress/data_copying_stress.fe"}
 test_analysis! { tuple_stress, "stress/tuple_stress.fe"}
+test_analysis! { tuple_destructuring, "features/tuple_destructuring.fe"}
 test_analysis! { type_aliases, "features/type_aliases.fe"}
 test_analysis! { const_generics, "features/const_generics.fe" }
 test_analysis! { const_local, "features/const_local.fe" }
@@ -302,6 +719,8 @@ fn build_snapshot(db: &dyn AnalyzerDb, module: items::ModuleId) -> String {
     let diagnostics = module
         .all_items(db)
         .iter()
+        .filter(|item| !is_generic_template(db, item))
+        .chain(db.module_generic_instances(module).iter())
         .flat_map(|item| match item {
             Item::Type(TypeDef::Alias(alias)) => vec![build_display_diagnostic(
                 alias.data(db).ast.span,
@@ -322,6 +741,23 @@ fn build_snapshot(db: &dyn AnalyzerDb, module: items::ModuleId) -> String {
                     .collect(),
             ]
             .concat(),
+            Item::Type(TypeDef::Enum(enum_)) => label_in_non_overlapping_groups(
+                &enum_
+                    .variants(db)
+                    .values()
+                    .flat_map(|variant| {
+                        variant
+                            .data(db)
+                            .ast
+                            .kind
+                            .fields
+                            .iter()
+                            .zip(variant.fields(db).iter())
+                            .map(|(node, typ)| (node.span, typ.clone().unwrap()))
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>(),
+            ),
             Item::Type(TypeDef::Contract(contract)) => [
                 label_in_non_overlapping_groups(
                     &contract
@@ -354,12 +790,23 @@ fn build_snapshot(db: &dyn AnalyzerDb, module: items::ModuleId) -> String {
             | Item::Ingot(_)
             | Item::Module(_)
             | Item::Object(_) => vec![],
+            // Variants are labeled with their enum.
+            Item::EnumVariant(_) => vec![],
         })
         .collect::<Vec<_>>();
 
     diagnostics_string(db.upcast(), &diagnostics)
 }
 
+/// Generic templates can't be typed on their own; their instances are labeled instead.
+fn is_generic_template(db: &dyn AnalyzerDb, item: &Item) -> bool {
+    match item {
+        Item::Type(TypeDef::Struct(struct_)) => struct_.is_generic(db),
+        Item::Function(function) => function.is_generic(db),
+        _ => false,
+    }
+}
+
 fn new_diagnostic(labels: Vec<Label>) -> Diagnostic {
     Diagnostic {
         severity: Severity::Note,
@@ -9,14 +9,23 @@ use insta::assert_snapshot;
 use wasm_bindgen_test::wasm_bindgen_test;
 
 fn error_string(path: &str, src: &str) -> String {
-    let mut db = TestDb::default();
-    let module = ModuleId::new_standalone(&mut db, path, src);
+    error_string_in(&mut TestDb::default(), path, src)
+}
+
+fn error_string_in(db: &mut TestDb, path: &str, src: &str) -> String {
+    let module = ModuleId::new_standalone(db, path, src);
 
-    let diags = module.diagnostics(&db);
+    let diags = module.diagnostics(db);
     if diags.is_empty() {
         panic!("expected analysis to fail with an error")
     }
-    diagnostics_string(&db, &diags)
+    diagnostics_string(db, &diags)
+}
+
+fn error_string_with_defines(path: &str, defines: &[(&str, &str)]) -> String {
+    let mut db = TestDb::default();
+    fe_analyzer::define_constants(&mut db, defines);
+    error_string_in(&mut db, path, test_files::fixture(path))
 }
 
 fn error_string_ingot(path: &str) -> String {
@@ -100,13 +109,15 @@ test_stmt! { array_non_primitive, "let x: Array<(u8, u8), 10>" }
 test_stmt! { array_mixed_types, "let x: Array<u16, 3> = [1, address(0), \"hi\"]" }
 test_stmt! { array_size_mismatch, "let x: Array<u8, 3> = []\nlet y: Array<u8, 3> = [1, 2]" }
 test_stmt! { array_constructor_call, "u8[3]([1, 2, 3])" }
+test_stmt! { array_non_int_const_size, "const FLAG: bool = true\nlet x: Array<u8, FLAG>" }
+test_stmt! { array_negative_const_size, "const SIZE: i8 = -1\nlet x: Array<u8, SIZE>" }
 test_stmt! { assert_reason_not_string, "assert true, 1" }
 test_stmt! { assign_int, "5 = 6" }
 test_stmt! { assign_call, "self.f() = 10" }
 test_stmt! { assign_type_mismatch, "let x: u256 = 10\nx = address(0)" }
 test_stmt! { aug_assign_non_numeric, "let a: u256 = 1\nlet b: bool = true\na += b" }
 test_stmt! { binary_op_add_uints, "let a: u256 = 1\nlet b: u8 = 2\na + b" }
-test_stmt! { binary_op_lshift_bool, "let a: bool = true\nlet b: i256\na << b" }
+test_stmt! { binary_op_lshift_bool, "let a: bool = true\nlet b: i256 = 1\na << b" }
 test_stmt! { binary_op_lshift_with_int, "let a: u256 = 1\nlet b: i256 = 2\na << b" }
 test_stmt! { binary_op_pow_int, "let a: u256 = 1\nlet b: i256 = 2\na ** b" }
 test_stmt! { binary_op_boolean_mismatch1, "10 and true" }
@@ -122,12 +133,15 @@ test_stmt! { call_keccak_without_parameter, "keccak256()" }
 test_stmt! { call_keccak_with_wrong_type, "keccak256(true)" }
 test_stmt! { call_keccak_with_2_args, "keccak256(1, 2)" }
 test_stmt! { call_keccak_with_generic_args, "keccak256<10>(1)" }
+test_stmt! { call_keccak_with_vec_in_tuple, "keccak256((1, Vec<u8>()))" }
 test_stmt! { cast_address_to_u64, "u64(address(0))" }
 
 test_stmt! { call_balance_of_without_parameter, "std::evm::balance_of()" }
 test_stmt! { call_balance_of_with_wrong_type, "std::evm::balance_of(true)" }
 test_stmt! { call_balance_of_with_2_args, "std::evm::balance_of(address(0), 2)" }
 test_stmt! { call_balance_with_arg, "std::evm::balance(address(0))" }
+test_stmt! { call_tstore_outside_unsafe, "std::evm::tstore(0, 1)" }
+test_stmt! { call_mcopy_intrinsic_with_2_args, "unsafe:\n  __mcopy(0, 32)" }
 test_stmt! { call_send_value_without_parameter, "std::send_value()" }
 test_stmt! { call_send_value_with_1_arg, "std::send_value(address(0))" }
 test_stmt! { call_send_value_with_3_args, "std::send_value(address(0), 0, 0)" }
@@ -180,14 +194,15 @@ test_stmt! { overflow_u64_pos, "u64(18446744073709551616)" }
 test_stmt! { overflow_u8_neg, "u8(-1)" }
 test_stmt! { overflow_u8_pos, "u8(256)" }
 test_stmt! { overflow_u8_assignment, "let x: u8 = 260" }
-test_stmt! { pow_with_signed_exponent, "let base: i128\nlet xp: i128\nbase ** exp" }
+test_stmt! { pow_with_signed_exponent, "let base: i128 = 2\nlet xp: i128 = 3\nbase ** exp" }
 // Exponent can be unsigned but needs to be same size or smaller
-test_stmt! { pow_with_wrong_capacity, "let base: i128\nlet exp: u256\nbase ** exp" }
+test_stmt! { pow_with_wrong_capacity, "let base: i128 = 2\nlet exp: u256 = 3\nbase ** exp" }
 test_stmt! { shadow_builtin_type_with_var, "let u8: u8 = 10" }
 test_stmt! { shadow_builtin_fn_with_var, "let keccak256: u8 = 10" }
 test_file! { shadow_builtin_type }
 test_file! { shadow_builtin_function }
 test_file! { self_misuse }
+test_file! { mutability }
 test_stmt! { string_capacity_mismatch, "String<3>(\"too long\")" }
 test_stmt! { string_non_int_type_arg, "let x: String<u8>" }
 test_stmt! { string_no_type_arg_list, "let x: String" }
@@ -200,11 +215,16 @@ test_stmt! { string_constructor_no_type_args, "String<>()" }
 test_stmt! { string_constructor_two_int_type_args, "String<1, 2>()" }
 test_stmt! { string_constructor_two_type_args, "String<1, u8>()" }
 test_stmt! { ternary_type_mismatch, "10 if 100 else true" }
-test_stmt! { change_sign_and_size_in_cast, "let x: i8\nlet y: u16 = u16(x)" }
-test_stmt! { change_sign_and_type_in_cast, "let x: bool\nlet y: u16 = u16(x)" }
+test_stmt! { ternary_literal_type_mismatch, "let x: u8 = 1\nlet y: u16 = 0 if true else x" }
+test_stmt! { chained_comparison_type_mismatch, "let x: u8 = 1\nlet y: u16 = 2\n0 < x <= y < 10" }
+test_stmt! { untyped_var_decl_empty_array, "let (x, y) = (1, [])" }
+test_stmt! { untyped_var_decl_type_mismatch, "let x = 1\nlet y: u8 = x" }
+test_stmt! { change_sign_and_size_in_cast, "let x: i8 = 1\nlet y: u16 = u16(x)" }
+test_stmt! { change_sign_and_type_in_cast, "let x: bool = true\nlet y: u16 = u16(x)" }
 test_stmt! { type_constructor_arg_count, "let x: u8 = u8(1, 10)" }
 test_stmt! { unary_minus_on_bool, "let x: bool = true\n-x" }
 test_stmt! { unary_not_on_int, "let x: u256 = 10\nnot x" }
+test_stmt! { unchecked_nesting, "let x: u8 = 1\nunchecked:\n  x += 1\n  if x > 1:\n    unchecked:\n      x += 1" }
 test_stmt! { undefined_generic_type, "let x: foobar<u256> = 10" }
 test_stmt! { undefined_name, "let x: u16 = y\nlet z: u16 = y" }
 test_stmt! { undefined_type, "let x: foobar = 10" }
@@ -221,6 +241,7 @@ test_file! { bad_tuple_attr3 }
 test_file! { call_builtin_object }
 test_file! { call_create_with_wrong_type }
 test_file! { call_create2_with_wrong_type }
+test_file! { call_address_of_with_wrong_type }
 test_file! { call_event_with_wrong_types }
 test_file! { call_undefined_function_on_external_contract }
 test_file! { call_undefined_function_on_memory_struct }
@@ -245,11 +266,26 @@ test_file! { duplicate_typedef_in_module }
 test_file! { duplicate_var_in_child_scope }
 test_file! { duplicate_var_in_contract_method }
 test_file! { duplicate_var_in_for_loop }
+test_file! { enum_variant_misuse }
+test_file! { enum_variant_non_primitive_field }
+test_file! { generic_args_mismatch }
+test_file! { generic_instance_errors }
+test_file! { generic_params_invalid }
+test_file! { match_invalid_pattern }
+test_file! { match_non_exhaustive }
 test_file! { emit_bad_args }
 test_file! { external_call_type_error }
 test_file! { external_call_wrong_number_of_params }
 test_file! { indexed_event }
+test_file! { indexed_event_field_types }
+test_file! { anonymous_event }
+test_file! { array_literal_length }
+test_file! { contract_inheritance }
+test_file! { default_params }
+test_file! { fallback_receive }
+test_file! { function_overloading }
 test_file! { invalid_compiler_version }
+test_file! { pragma_features }
 test_file! { invalid_block_field }
 test_file! { invalid_chain_field }
 test_file! { invalid_contract_field }
@@ -264,6 +300,22 @@ test_file! { issue_451 }
 test_file! { mislabeled_call_args }
 test_file! { mislabeled_call_args_self }
 test_file! { mislabeled_call_args_external_contract_call }
+test_file! { reordered_call_args }
+test_file! { unused_value }
+test_file! { must_use }
+test_file! { string_operations_errors }
+test_file! { vec_errors }
+test_file! { function_value_errors }
+test_file! { uninitialized_fields }
+test_file! { uninitialized_vars }
+test_file! { unused_items }
+test_file! { init_only }
+test_file! { payable }
+test_file! { packed_struct }
+test_file! { integer_conversion_errors }
+test_file! { operator_overloading }
+test_file! { dead_stores }
+test_file! { inline_yul_errors }
 test_file! { mismatch_return_type }
 test_file! { missing_return }
 test_file! { missing_return_in_else }
@@ -271,6 +323,8 @@ test_file! { missing_return_after_if }
 test_file! { module_const_unknown_type }
 test_file! { module_const_non_base_type }
 test_file! { module_const_call }
+test_file! { module_const_cycle }
+test_file! { module_const_eval_error }
 test_file! { needs_mem_copy }
 test_file! { not_callable }
 test_file! { not_in_scope }
@@ -285,11 +339,16 @@ test_file! { return_type_undefined }
 test_file! { return_complex_struct }
 test_file! { return_type_not_fixedsize }
 test_file! { undefined_type_param }
+test_file! { path_into_type }
+test_file! { evm_version }
+test_file! { send_value_to_unvalidated_address }
+test_file! { send_value_to_address_checked_late }
 
 test_file! { strict_boolean_if_else }
 test_file! { struct_private_constructor }
 test_file! { struct_call_bad_args }
 test_file! { struct_call_without_kw_args }
+test_file! { struct_update_errors }
 test_file! { non_pub_init }
 test_file! { init_wrong_return_type }
 test_file! { init_duplicate_def }
@@ -299,12 +358,22 @@ test_file! { call_wrong_return_type }
 test_file! { call_duplicate_def }
 test_file! { call_call_on_self }
 test_file! { call_call_on_external_contract }
+test_file! { interface_errors }
+test_file! { interface_impl_errors }
+test_file! { loop_errors }
+test_file! { tuple_destructuring_errors }
 test_file! { call_with_pub_fns }
 test_file! { abi_encode_u256 }
 test_file! { abi_encode_from_storage }
 test_file! { assert_sto_msg_no_copy }
 test_file! { for_loop_sto_iter_no_copy }
 test_file! { revert_sto_error_no_copy }
+test_file! { revert_not_error }
+test_file! { try_catch_errors }
+test_file! { abi_builtins_errors }
+test_file! { packed_events_errors }
+test_file! { erc165_errors }
+test_file! { selector_errors }
 
 test_file! { call_to_mut_fn_without_self }
 test_file! { call_to_pure_fn_on_self }
@@ -313,6 +382,27 @@ test_file! { self_not_first }
 test_file! { self_in_standalone_fn }
 test_file! { unsafe_misuse }
 test_file! { unsafe_nesting }
+test_file! { unlabeled_same_typed_args }
+
+#[test]
+#[wasm_bindgen_test]
+fn constant_defines() {
+    let defines = [
+        ("WETH", "true"),
+        ("FEE", "1 +"),
+        ("LIMIT", "256"),
+        ("MISSING", "1"),
+    ];
+    let errors = error_string_with_defines("compile_errors/constant_defines.fe", &defines);
+    if cfg!(target_arch = "wasm32") {
+        fe_common::assert_snapshot_wasm!("snapshots/errors__constant_defines.snap", errors);
+    } else {
+        assert_snapshot!(errors);
+    }
+}
 
 test_ingot! { bad_ingot }
 test_ingot! { mainless_ingot }
+test_ingot! { private_items_ingot }
+test_ingot! { glob_conflict_ingot }
+test_ingot! { import_cycle_ingot }
@@ -1,4 +1,5 @@
 use crate::files::{File, SourceFileId, Utf8Path};
+use crate::line_index::LineIndex;
 use codespan_reporting as cs;
 use salsa;
 use smol_str::SmolStr;
@@ -24,6 +25,9 @@ pub trait SourceDb {
     #[salsa::invoke(file_line_starts_query)]
     fn file_line_starts(&self, file: SourceFileId) -> Rc<[usize]>;
 
+    #[salsa::invoke(file_line_index_query)]
+    fn file_line_index(&self, file: SourceFileId) -> Rc<LineIndex>;
+
     #[salsa::invoke(file_name_query)]
     fn file_name(&self, file: SourceFileId) -> SmolStr;
 }
@@ -32,6 +36,10 @@ fn file_line_starts_query(db: &dyn SourceDb, file: SourceFileId) -> Rc<[usize]>
     cs::files::line_starts(&file.content(db)).collect()
 }
 
+fn file_line_index_query(db: &dyn SourceDb, file: SourceFileId) -> Rc<LineIndex> {
+    Rc::new(LineIndex::new(&file.content(db)))
+}
+
 fn file_name_query(db: &dyn SourceDb, file: SourceFileId) -> SmolStr {
     let path = db.lookup_intern_file(file).path;
     Utf8Path::new(path.as_str())
@@ -34,6 +34,12 @@ impl Diagnostic {
             notes: vec![],
         }
     }
+
+    /// Returns `true` if the diagnostic should prevent compilation
+    /// (ie. it isn't just a warning).
+    pub fn is_error(&self) -> bool {
+        self.severity >= Severity::Error
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -88,32 +94,118 @@ impl Label {
     }
 }
 
+/// How the diagnostics color their output.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum ColorMode {
+    Always,
+    Never,
+    /// Color the output if the terminal supports it.
+    Auto,
+}
+impl From<ColorMode> for ColorChoice {
+    fn from(other: ColorMode) -> ColorChoice {
+        match other {
+            ColorMode::Always => ColorChoice::Always,
+            ColorMode::Never => ColorChoice::Never,
+            ColorMode::Auto => ColorChoice::Auto,
+        }
+    }
+}
+
+/// The layout of a printed diagnostic.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub enum DiagnosticStyle {
+    /// The message, followed by the labelled source code and the notes.
+    Rich,
+    /// One line per diagnostic, starting with the `path:line:column` of its
+    /// primary label, e.g. `foo.fe:3:9: error: mismatched types`. The
+    /// notes and the other labels are omitted.
+    Short,
+}
+
+/// Options for printing diagnostics. The default is the rich style, colored
+/// if the terminal supports it.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct DiagnosticsConfig {
+    pub color: ColorMode,
+    pub style: DiagnosticStyle,
+    /// Draw the source code borders and the label underlines with ASCII
+    /// characters only.
+    pub ascii: bool,
+}
+impl Default for DiagnosticsConfig {
+    fn default() -> Self {
+        Self {
+            color: ColorMode::Auto,
+            style: DiagnosticStyle::Rich,
+            ascii: false,
+        }
+    }
+}
+impl DiagnosticsConfig {
+    fn term_config(&self) -> term::Config {
+        term::Config {
+            display_style: match self.style {
+                DiagnosticStyle::Rich => term::DisplayStyle::Rich,
+                DiagnosticStyle::Short => term::DisplayStyle::Short,
+            },
+            chars: if self.ascii {
+                term::Chars::ascii()
+            } else {
+                term::Chars::default()
+            },
+            ..term::Config::default()
+        }
+    }
+}
+
 /// Print the given diagnostics to stderr.
 pub fn print_diagnostics(db: &dyn SourceDb, diagnostics: &[Diagnostic]) {
-    let writer = BufferWriter::stderr(ColorChoice::Auto);
-    let mut buffer = writer.buffer();
-    let config = term::Config::default();
-    let files = SourceDbWrapper(db);
+    print_diagnostics_with_config(db, diagnostics, &DiagnosticsConfig::default())
+}
 
-    for diag in diagnostics {
-        term::emit(&mut buffer, &config, &files, &diag.clone().into_cs()).unwrap();
-    }
-    // If we use `writer` here, the output won't be captured by rust's test system.
-    eprintln!("{}", std::str::from_utf8(buffer.as_slice()).unwrap());
+/// Print the given diagnostics to stderr, as specified by `config`.
+pub fn print_diagnostics_with_config(
+    db: &dyn SourceDb,
+    diagnostics: &[Diagnostic],
+    config: &DiagnosticsConfig,
+) {
+    let output = render_diagnostics(db, diagnostics, config, config.color.into());
+    // If we use a `BufferWriter` here, the output won't be captured by rust's test system.
+    eprintln!("{}", output);
 }
 
 /// Format the given diagnostics as a string.
 pub fn diagnostics_string(db: &dyn SourceDb, diagnostics: &[Diagnostic]) -> String {
-    let writer = BufferWriter::stderr(ColorChoice::Never);
+    diagnostics_string_with_config(db, diagnostics, &DiagnosticsConfig::default())
+}
+
+/// Format the given diagnostics as a string, as specified by `config`. The
+/// string is never colored.
+pub fn diagnostics_string_with_config(
+    db: &dyn SourceDb,
+    diagnostics: &[Diagnostic],
+    config: &DiagnosticsConfig,
+) -> String {
+    render_diagnostics(db, diagnostics, config, ColorChoice::Never)
+}
+
+fn render_diagnostics(
+    db: &dyn SourceDb,
+    diagnostics: &[Diagnostic],
+    config: &DiagnosticsConfig,
+    color: ColorChoice,
+) -> String {
+    let writer = BufferWriter::stderr(color);
     let mut buffer = writer.buffer();
-    let config = term::Config::default();
+    let term_config = config.term_config();
     let files = SourceDbWrapper(db);
 
     for diag in diagnostics {
-        term::emit(&mut buffer, &config, &files, &diag.clone().into_cs())
+        term::emit(&mut buffer, &term_config, &files, &diag.clone().into_cs())
             .expect("failed to emit diagnostic");
     }
-    std::str::from_utf8(buffer.as_slice()).unwrap().to_string()
+    String::from_utf8(buffer.into_inner()).expect("diagnostics aren't valid utf-8")
 }
 
 struct SourceDbWrapper<'a>(pub &'a dyn SourceDb);
@@ -143,3 +235,50 @@ impl<'a> codespan_reporting::files::Files<'_> for SourceDbWrapper<'a> {
             })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::TestDb;
+
+    fn diagnostics(db: &mut TestDb) -> Vec<Diagnostic> {
+        let file = SourceFileId::new_local(db, "foo.fe", "let x: u8 = true\n".into());
+        vec![Diagnostic {
+            severity: Severity::Error,
+            message: "type mismatch".into(),
+            labels: vec![
+                Label::primary(Span::new(file, 12, 16), "this has type `bool`"),
+                Label::secondary(Span::new(file, 7, 9), "expected `u8`"),
+            ],
+            notes: vec!["a note".into()],
+        }]
+    }
+
+    #[test]
+    fn test_short_style() {
+        let mut db = TestDb::default();
+        let diags = diagnostics(&mut db);
+        let config = DiagnosticsConfig {
+            style: DiagnosticStyle::Short,
+            ..DiagnosticsConfig::default()
+        };
+        assert_eq!(
+            diagnostics_string_with_config(&db, &diags, &config),
+            "foo.fe:1:13: error: type mismatch\n"
+        );
+    }
+
+    #[test]
+    fn test_ascii() {
+        let mut db = TestDb::default();
+        let diags = diagnostics(&mut db);
+        let config = DiagnosticsConfig {
+            ascii: true,
+            ..DiagnosticsConfig::default()
+        };
+        let output = diagnostics_string_with_config(&db, &diags, &config);
+        assert!(output.is_ascii(), "{}", output);
+        assert!(output.contains("--> foo.fe:1:13"), "{}", output);
+        assert!(!diagnostics_string(&db, &diags).is_ascii());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::db::SourceDb;
+use crate::line_index::{LineCol, LineIndex};
 pub use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 pub use fe_library::include_dir;
 use std::ops::Range;
@@ -63,7 +64,7 @@ impl_intern_key!(SourceFileId);
 
 impl SourceFileId {
     pub fn new_local(db: &mut dyn SourceDb, path: &str, content: Rc<str>) -> Self {
-        Self::new(db, FileKind::Std, path, content)
+        Self::new(db, FileKind::Local, path, content)
     }
 
     pub fn new_std(db: &mut dyn SourceDb, path: &str, content: Rc<str>) -> Self {
@@ -83,6 +84,10 @@ impl SourceFileId {
         db.lookup_intern_file(*self).path
     }
 
+    pub fn kind(&self, db: &dyn SourceDb) -> FileKind {
+        db.lookup_intern_file(*self).kind
+    }
+
     pub fn content(&self, db: &dyn SourceDb) -> Rc<str> {
         db.file_content(*self)
     }
@@ -106,6 +111,27 @@ impl SourceFileId {
         })
     }
 
+    /// Maps between byte offsets and line/column positions in the file.
+    pub fn line_index_map(&self, db: &dyn SourceDb) -> Rc<LineIndex> {
+        db.file_line_index(*self)
+    }
+
+    /// The zero-based line and byte column of `byte_index`.
+    pub fn line_col(&self, db: &dyn SourceDb, byte_index: usize) -> LineCol {
+        self.line_index_map(db).line_col(byte_index)
+    }
+
+    /// The zero-based line and UTF-16 column of `byte_index`.
+    pub fn line_col_utf16(&self, db: &dyn SourceDb, byte_index: usize) -> LineCol {
+        self.line_index_map(db).line_col_utf16(byte_index)
+    }
+
+    /// The byte offset of a zero-based line and UTF-16 column, if the
+    /// position is in the file.
+    pub fn byte_index_utf16(&self, db: &dyn SourceDb, line_col: LineCol) -> Option<usize> {
+        self.line_index_map(db).offset_utf16(line_col)
+    }
+
     pub fn dummy_file() -> Self {
         // Used by lowering::ZeroSpanNode, unit tests, and benchmarks
         Self(u32::MAX)
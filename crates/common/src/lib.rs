@@ -1,12 +1,14 @@
 pub mod db;
 pub mod diagnostics;
 pub mod files;
+pub mod line_index;
 pub mod numeric;
 pub mod panic;
 mod span;
 pub mod utils;
 
 pub use files::{File, FileKind, SourceFileId};
+pub use line_index::{LineCol, LineIndex};
 pub use span::{Span, Spanned};
 
 #[macro_export]
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+
+/// A zero-based line and column position in a source file. Depending on the
+/// function that returns it, the column is either counted in bytes or in
+/// UTF-16 code units (the unit used by the language server protocol).
+#[derive(Debug, PartialEq, Eq, Hash, Copy, Clone, PartialOrd, Ord)]
+pub struct LineCol {
+    pub line: usize,
+    pub col: usize,
+}
+
+impl LineCol {
+    pub fn new(line: usize, col: usize) -> Self {
+        Self { line, col }
+    }
+}
+
+/// A character of a line that is longer than one byte, and thus has a
+/// different length in UTF-8 and UTF-16.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+struct WideChar {
+    /// Byte column of the start of the character.
+    col: usize,
+    len_utf8: usize,
+    len_utf16: usize,
+}
+
+/// Maps between byte offsets and line/column positions in a source file.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LineIndex {
+    /// Byte offsets of the starts of the lines. The first line starts at 0.
+    line_starts: Vec<usize>,
+    /// The multi-byte characters of each line that contains any.
+    wide_chars: BTreeMap<usize, Vec<WideChar>>,
+    len: usize,
+}
+
+impl LineIndex {
+    pub fn new(text: &str) -> Self {
+        let mut line_starts = vec![0];
+        let mut wide_chars = BTreeMap::<usize, Vec<WideChar>>::new();
+        for (offset, c) in text.char_indices() {
+            if c == '\n' {
+                line_starts.push(offset + 1);
+            } else if c.len_utf8() > 1 {
+                let line_start = *line_starts.last().unwrap();
+                wide_chars
+                    .entry(line_starts.len() - 1)
+                    .or_default()
+                    .push(WideChar {
+                        col: offset - line_start,
+                        len_utf8: c.len_utf8(),
+                        len_utf16: c.len_utf16(),
+                    });
+            }
+        }
+
+        Self {
+            line_starts,
+            wide_chars,
+            len: text.len(),
+        }
+    }
+
+    /// The number of lines. A trailing newline starts an (empty) last line.
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    /// The line and byte column of `offset`.
+    ///
+    /// # Panics
+    /// Panics if `offset` is beyond the end of the file.
+    pub fn line_col(&self, offset: usize) -> LineCol {
+        assert!(
+            offset <= self.len,
+            "offset {} is beyond the end of the file ({} bytes)",
+            offset,
+            self.len
+        );
+        let line = self
+            .line_starts
+            .binary_search(&offset)
+            .unwrap_or_else(|next_line| next_line - 1);
+        LineCol::new(line, offset - self.line_starts[line])
+    }
+
+    /// The byte offset of a line and byte column. Returns `None` if the
+    /// position isn't in the file. A column past the end of its line is
+    /// also rejected, except for the column right after the last character.
+    pub fn offset(&self, line_col: LineCol) -> Option<usize> {
+        let start = *self.line_starts.get(line_col.line)?;
+        let end = self
+            .line_starts
+            .get(line_col.line + 1)
+            .map_or(self.len, |next_start| next_start - 1);
+        let offset = start + line_col.col;
+        if offset <= end {
+            Some(offset)
+        } else {
+            None
+        }
+    }
+
+    /// Converts a byte column to a UTF-16 column. A column inside of a
+    /// multi-byte character isn't converted exactly.
+    pub fn to_utf16(&self, line_col: LineCol) -> LineCol {
+        let col = self
+            .wide_chars_before(line_col)
+            .fold(line_col.col, |col, c| col - c.len_utf8 + c.len_utf16);
+        LineCol::new(line_col.line, col)
+    }
+
+    /// Converts a UTF-16 column to a byte column.
+    pub fn to_utf8(&self, line_col: LineCol) -> LineCol {
+        let mut col = line_col.col;
+        for c in self.wide_chars.get(&line_col.line).into_iter().flatten() {
+            // `col` is the byte column the UTF-16 column would have if the
+            // remaining characters were all one byte long.
+            if c.col >= col {
+                break;
+            }
+            col = col + c.len_utf8 - c.len_utf16;
+        }
+        LineCol::new(line_col.line, col)
+    }
+
+    /// The line and UTF-16 column of `offset`.
+    ///
+    /// # Panics
+    /// Panics if `offset` is beyond the end of the file.
+    pub fn line_col_utf16(&self, offset: usize) -> LineCol {
+        self.to_utf16(self.line_col(offset))
+    }
+
+    /// The byte offset of a line and UTF-16 column. Returns `None` if the
+    /// position isn't in the file.
+    pub fn offset_utf16(&self, line_col: LineCol) -> Option<usize> {
+        self.offset(self.to_utf8(line_col))
+    }
+
+    fn wide_chars_before(&self, line_col: LineCol) -> impl Iterator<Item = &WideChar> {
+        self.wide_chars
+            .get(&line_col.line)
+            .into_iter()
+            .flatten()
+            .take_while(move |c| c.col + c.len_utf8 <= line_col.col)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let index = LineIndex::new("fn f():\n    pass\n\nx");
+        assert_eq!(index.line_count(), 4);
+        assert_eq!(index.line_col(0), LineCol::new(0, 0));
+        assert_eq!(index.line_col(7), LineCol::new(0, 7));
+        assert_eq!(index.line_col(8), LineCol::new(1, 0));
+        assert_eq!(index.line_col(12), LineCol::new(1, 4));
+        assert_eq!(index.line_col(17), LineCol::new(2, 0));
+        assert_eq!(index.line_col(18), LineCol::new(3, 0));
+        assert_eq!(index.line_col(19), LineCol::new(3, 1));
+
+        for offset in 0..=19 {
+            assert_eq!(index.offset(index.line_col(offset)), Some(offset));
+        }
+        assert_eq!(index.offset(LineCol::new(0, 8)), None);
+        assert_eq!(index.offset(LineCol::new(3, 2)), None);
+        assert_eq!(index.offset(LineCol::new(4, 0)), None);
+    }
+
+    #[test]
+    fn test_utf16() {
+        // `é` is 2 bytes and 1 UTF-16 unit, `𝔽` is 4 bytes and 2 UTF-16 units.
+        let text = "# é𝔽x\nlet y: u8 = 1 # 𝔽";
+        let index = LineIndex::new(text);
+
+        let x = text.find('x').unwrap();
+        assert_eq!(index.line_col(x), LineCol::new(0, 8));
+        assert_eq!(index.line_col_utf16(x), LineCol::new(0, 5));
+        assert_eq!(index.offset_utf16(LineCol::new(0, 5)), Some(x));
+
+        let y = text.find('y').unwrap();
+        assert_eq!(index.line_col_utf16(y), LineCol::new(1, 4));
+        assert_eq!(index.offset_utf16(LineCol::new(1, 4)), Some(y));
+
+        assert_eq!(index.line_col_utf16(text.len()), LineCol::new(1, 18));
+        assert_eq!(index.offset_utf16(LineCol::new(1, 18)), Some(text.len()));
+
+        for (offset, _) in text.char_indices() {
+            assert_eq!(
+                index.offset_utf16(index.line_col_utf16(offset)),
+                Some(offset)
+            );
+        }
+    }
+}
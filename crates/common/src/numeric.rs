@@ -1,3 +1,5 @@
+use std::fmt;
+
 /// A type that represents the radix of a numeric literal.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Radix {
@@ -19,15 +21,46 @@ impl Radix {
     }
 }
 
+/// The largest number of zeros that the exponent of a literal may add, e.g.
+/// `1e77`. Any larger number doesn't fit in 256 bits.
+const MAX_SCALE: usize = 77;
+
 /// A helper type to interpret a numeric literal represented by string.
+///
+/// The digits may be separated by `_`, e.g. `1_000_000`. Decimal literals may
+/// use scientific notation, e.g. `1e18` or `2.5e18`, as long as the number is
+/// an integer.
 #[derive(Debug, Clone)]
 pub struct Literal<'a> {
-    /// The number part of the string.
+    /// The number part of the string, without the exponent.
     num: &'a str,
+    /// The exponent of a decimal literal in scientific notation.
+    exponent: Option<&'a str>,
     /// The radix of the literal.
     radix: Radix,
 }
 
+/// The reason why a numeric literal can't be interpreted as an integer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LiteralError {
+    /// The literal contains a digit that isn't valid in its radix.
+    InvalidDigit,
+    /// The literal has a fractional part, e.g. `1.5` or `1.25e1`.
+    Fractional,
+    /// The exponent of the literal is too large, e.g. `1e100`.
+    ExponentTooLarge,
+}
+
+impl fmt::Display for LiteralError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LiteralError::InvalidDigit => write!(f, "the number contains an invalid digit"),
+            LiteralError::Fractional => write!(f, "the number isn't an integer"),
+            LiteralError::ExponentTooLarge => write!(f, "the exponent of the number is too large"),
+        }
+    }
+}
+
 impl<'a> Literal<'a> {
     pub fn new(src: &'a str) -> Self {
         debug_assert!(!src.is_empty());
@@ -43,15 +76,63 @@ impl<'a> Literal<'a> {
             }
         };
 
+        let num = &src[prefix.map_or(0, str::len)..];
+        let (num, exponent) = match num.find(['e', 'E']) {
+            Some(idx) if radix == Radix::Decimal => (&num[..idx], Some(&num[idx + 1..])),
+            _ => (num, None),
+        };
+
         Self {
-            num: &src[prefix.map_or(0, str::len)..],
+            num,
+            exponent,
             radix,
         }
     }
 
     /// Parse the numeric literal to `T`.
-    pub fn parse<T: num_traits::Num>(&self) -> Result<T, T::FromStrRadixErr> {
-        T::from_str_radix(self.num, self.radix.as_num())
+    pub fn parse<T: num_traits::Num>(&self) -> Result<T, LiteralError> {
+        T::from_str_radix(&self.digits()?, self.radix.as_num())
+            .map_err(|_| LiteralError::InvalidDigit)
+    }
+
+    /// Checks that the literal is an integer, without parsing it.
+    pub fn validate(&self) -> Result<(), LiteralError> {
+        self.digits().map(|_| ())
+    }
+
+    /// The digits of the integer, without separators, a decimal point or an
+    /// exponent. E.g. `2.5e3` has the digits `2500`.
+    fn digits(&self) -> Result<String, LiteralError> {
+        let (int, fraction) = match self.num.split_once('.') {
+            Some((int, fraction)) => (int, fraction),
+            None => (self.num, ""),
+        };
+        let fraction = fraction.replace('_', "");
+        let exponent = match self.exponent {
+            Some(exponent) => exponent
+                .replace('_', "")
+                .parse::<usize>()
+                .map_err(|_| LiteralError::ExponentTooLarge)?,
+            None => 0,
+        };
+
+        let mut digits = int.replace('_', "");
+        if exponent >= fraction.len() {
+            let scale = exponent - fraction.len();
+            if scale > MAX_SCALE {
+                return Err(LiteralError::ExponentTooLarge);
+            }
+            digits.push_str(&fraction);
+            digits.push_str(&"0".repeat(scale));
+        } else {
+            // The digits after the exponent must be zeros, e.g. `1.50e1`.
+            let (kept, dropped) = fraction.split_at(exponent);
+            if dropped.chars().any(|c| c != '0') {
+                return Err(LiteralError::Fractional);
+            }
+            digits.push_str(kept);
+        }
+        Ok(digits)
     }
 
     /// Returns radix of the numeric literal.
@@ -77,4 +158,37 @@ mod tests {
         // Invalid radix is treated as `Decimal`.
         assert_eq!(Literal::new("0D15").radix(), Radix::Decimal);
     }
+
+    #[test]
+    fn test_parse() {
+        for (src, expected) in [
+            ("1_000_000", 1_000_000),
+            ("0xff_ff", 0xffff),
+            ("0o7_7", 0o77),
+            ("0b1010_1010", 0b1010_1010),
+            ("1e18", 1_000_000_000_000_000_000),
+            ("1E3", 1000),
+            ("2.5e3", 2500),
+            ("1_0.0_5e1_0", 100_500_000_000),
+            ("1.50e1", 15),
+            ("1.0", 1),
+            ("0x1e3", 0x1e3),
+        ] {
+            assert_eq!(Literal::new(src).parse::<u128>(), Ok(expected), "{}", src);
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (src, error) in [
+            ("1.5", LiteralError::Fractional),
+            ("1.25e1", LiteralError::Fractional),
+            ("1e78", LiteralError::ExponentTooLarge),
+            ("1e99999999999999999999", LiteralError::ExponentTooLarge),
+            ("0b12", LiteralError::InvalidDigit),
+        ] {
+            assert_eq!(Literal::new(src).parse::<u128>(), Err(error), "{}", src);
+        }
+        assert_eq!(Literal::new("1e77").validate(), Ok(()));
+    }
 }
@@ -0,0 +1,140 @@
+//! Reports of the code that an audit should review: the integer arithmetic
+//! that doesn't revert on overflow in the selected build profile, and the
+//! calls to other contracts.
+
+use crate::{Db, YulgenDb};
+use fe_analyzer::context::ExternalCallee;
+use fe_analyzer::namespace::items::{ContractId, FunctionId, IngotId, Item, TypeDef};
+use fe_common::Span;
+use indexmap::IndexSet;
+use serde_json::{json, Value};
+
+/// The integer operations of the ingot whose overflow checks are left out
+/// because the build profile disables them, i.e. the ones that aren't in an
+/// `unchecked` block or a `#[checked]` statement. The list is empty if the
+/// profile enables overflow checks.
+///
+/// Each operation has the path of its function, the file it's in, its span
+/// (with the zero-based line and column at which it starts) and its source
+/// code. Generic functions are left out.
+pub fn unchecked_arithmetic(db: &Db, ingot: IngotId) -> Value {
+    if db.overflow_checks() {
+        return json!([]);
+    }
+
+    let operations = ingot_functions(db, ingot)
+        .into_iter()
+        .filter(|function| !function.is_generic(db))
+        .flat_map(|function| {
+            let path = Item::Function(function).path(db).join("::");
+            let body = function.body(db);
+            body.profile_checked_arithmetic
+                .values()
+                .map(|span| {
+                    let mut operation = located(db, *span);
+                    operation["function"] = path.clone().into();
+                    operation
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    operations.into()
+}
+
+/// The external calls that the contracts of the ingot can make, by contract
+/// (see [`fe_analyzer::namespace::items::ContractId::external_calls`]).
+///
+/// Each call has the path of the function that makes it, its callee, whether
+/// it can send ether, whether it's made by a `try` statement, and the file,
+/// span and source code of the called expression. The callee of a contract
+/// function call has the interface or contract, the function name and its
+/// selector; the callee of a deployment has the deployed contract and the
+/// method (`create` or `create2`); and the callee of an intrinsic has its
+/// name.
+pub fn external_calls(db: &Db, ingot: IngotId) -> Value {
+    let mut contracts = serde_json::Map::new();
+    for module in ingot.all_modules(db).iter() {
+        for contract in module.all_contracts(db).iter() {
+            if contract.is_interface(db) {
+                continue;
+            }
+            let calls = contract
+                .external_calls(db)
+                .iter()
+                .map(|call| {
+                    let callee = match &call.callee {
+                        ExternalCallee::Function { contract, function } => json!({
+                            "kind": "function",
+                            "contract": contract_path(db, *contract),
+                            "function": function.name(db).as_str(),
+                            "selector": fe_abi::function_selector(db, *function),
+                        }),
+                        ExternalCallee::Create { contract, method } => json!({
+                            "kind": method.as_ref(),
+                            "contract": contract_path(db, *contract),
+                        }),
+                        ExternalCallee::Intrinsic(intrinsic) => json!({
+                            "kind": "intrinsic",
+                            "function": intrinsic.as_ref(),
+                        }),
+                    };
+                    let mut site = located(db, call.span);
+                    site["function"] = Item::Function(call.caller).path(db).join("::").into();
+                    site["callee"] = callee;
+                    site["transfers_value"] = call.transfers_value.into();
+                    site["is_try"] = call.is_try.into();
+                    site
+                })
+                .collect::<Vec<_>>();
+            contracts.insert(contract_path(db, *contract), calls.into());
+        }
+    }
+    contracts.into()
+}
+
+fn contract_path(db: &Db, contract: ContractId) -> String {
+    Item::Type(TypeDef::Contract(contract)).path(db).join("::")
+}
+
+/// The file, span (with the zero-based line and column at which it starts)
+/// and source code of a span.
+fn located(db: &Db, span: Span) -> Value {
+    let content = span.file_id.content(db);
+    let start = span.file_id.line_col(db, span.start);
+    json!({
+        "file": span.file_id.path(db).as_str(),
+        "span": {
+            "start": span.start,
+            "end": span.end,
+            "line": start.line,
+            "column": start.col,
+        },
+        "code": &content[span.start..span.end],
+    })
+}
+
+/// The functions that are defined in the ingot, including the functions of
+/// its contracts and structs.
+fn ingot_functions(db: &Db, ingot: IngotId) -> IndexSet<FunctionId> {
+    let mut functions = IndexSet::new();
+    for module in ingot.all_modules(db).iter() {
+        for item in module.all_items(db).iter() {
+            match item {
+                Item::Function(function) => {
+                    functions.insert(*function);
+                }
+                Item::Type(TypeDef::Contract(contract)) if !contract.is_interface(db) => {
+                    functions.extend(contract.functions(db).values().copied());
+                    functions.extend(contract.init_function(db));
+                    functions.extend(contract.fallback_function(db));
+                    functions.extend(contract.receive_function(db));
+                }
+                Item::Type(TypeDef::Struct(struct_)) => {
+                    functions.extend(struct_.functions(db).values().copied());
+                }
+                _ => {}
+            }
+        }
+    }
+    functions
+}
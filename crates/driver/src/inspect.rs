@@ -0,0 +1,184 @@
+//! Describes the modules of an ingot and the items they define as JSON, so
+//! that build tools and docs sites can navigate the structure of a project
+//! without parsing Fe.
+
+use crate::Db;
+use fe_analyzer::namespace::items::{
+    ContractId, EnumId, FunctionId, IngotId, IngotMode, Item, ModuleId, ModuleSource, StructId,
+    TypeDef,
+};
+use fe_common::files::FileKind;
+use fe_common::Span;
+use indexmap::indexmap;
+use serde_json::{json, Value};
+
+/// The module tree of a single file.
+pub fn single_file_tree(db: &mut Db, path: &str, src: &str) -> Value {
+    let module = ModuleId::new_standalone(db, path, src);
+    ingot_tree(db, module.ingot(db))
+}
+
+/// The module tree of the ingot that consists of `files`.
+pub fn ingot_files_tree(
+    db: &mut Db,
+    name: &str,
+    files: &[(impl AsRef<str>, impl AsRef<str>)],
+) -> Value {
+    let std = IngotId::std_lib(db);
+    let ingot = IngotId::from_files(
+        db,
+        name,
+        IngotMode::Main,
+        FileKind::Local,
+        files,
+        indexmap! { "std".into() => std },
+    );
+    ingot_tree(db, ingot)
+}
+
+/// Describes an ingot as a tree of modules, which list their items and
+/// submodules. Items that can't be parsed are left out.
+///
+/// A span gives the byte offsets of the name of an item in the file of its
+/// module, and the zero-based line and column at which the name starts.
+pub fn ingot_tree(db: &Db, ingot: IngotId) -> Value {
+    let modules = ingot
+        .all_modules(db)
+        .iter()
+        .filter(|module| module.parent_module(db).is_none())
+        .map(|module| module_tree(db, *module))
+        .collect::<Vec<_>>();
+
+    json!({
+        "name": ingot.name(db).as_str(),
+        "modules": modules,
+    })
+}
+
+fn module_tree(db: &Db, module: ModuleId) -> Value {
+    let file = match &module.data(db).source {
+        ModuleSource::File(file) => Some(file.path(db).to_string()),
+        ModuleSource::Dir(_) | ModuleSource::Lowered { .. } => None,
+    };
+    let items = module
+        .all_items(db)
+        .iter()
+        .filter_map(|item| item_tree(db, *item))
+        .collect::<Vec<_>>();
+    let submodules = module
+        .submodules(db)
+        .iter()
+        .map(|submodule| module_tree(db, *submodule))
+        .collect::<Vec<_>>();
+
+    json!({
+        "name": module.name(db).as_str(),
+        "path": module.file_path_relative_to_src_dir(db).as_str(),
+        "file": file,
+        "items": items,
+        "submodules": submodules,
+    })
+}
+
+fn item_tree(db: &Db, item: Item) -> Option<Value> {
+    let (kind, members) = match item {
+        Item::Type(TypeDef::Contract(id)) if id.is_interface(db) => {
+            ("interface", contract_members(db, id))
+        }
+        Item::Type(TypeDef::Contract(id)) => ("contract", contract_members(db, id)),
+        Item::Type(TypeDef::Struct(id)) => ("struct", struct_members(db, id)),
+        Item::Type(TypeDef::Enum(id)) => ("enum", enum_members(db, id)),
+        Item::Type(TypeDef::Alias(_)) => ("type alias", vec![]),
+        Item::Function(_) => ("function", vec![]),
+        Item::Event(_) => ("event", vec![]),
+        Item::Constant(_) => ("constant", vec![]),
+        _ => return None,
+    };
+
+    let mut tree = member(
+        db,
+        kind,
+        &item.name(db),
+        item.is_public(db),
+        item.name_span(db)?,
+    );
+    if !members.is_empty() {
+        tree["members"] = members.into();
+    }
+    Some(tree)
+}
+
+/// The fields and the functions of a contract, including `__init__`,
+/// `__fallback__` and `__receive__`.
+fn contract_members(db: &Db, contract: ContractId) -> Vec<Value> {
+    let fields = contract.fields(db);
+    let fields = fields.values().map(|field| {
+        let name = &field.data(db).ast.kind.name;
+        member(db, "field", &name.kind, false, name.span)
+    });
+    let functions = contract
+        .functions(db)
+        .values()
+        .copied()
+        .chain(contract.init_function(db))
+        .chain(contract.fallback_function(db))
+        .chain(contract.receive_function(db))
+        .map(|function| function_member(db, function))
+        .collect::<Vec<_>>();
+    fields.chain(functions).collect()
+}
+
+fn struct_members(db: &Db, struct_: StructId) -> Vec<Value> {
+    let fields = struct_.fields(db);
+    let fields = fields.values().map(|field| {
+        let name = &field.data(db).ast.kind.name;
+        member(db, "field", &name.kind, field.is_public(db), name.span)
+    });
+    let functions = struct_
+        .functions(db)
+        .values()
+        .map(|function| function_member(db, *function))
+        .collect::<Vec<_>>();
+    fields.chain(functions).collect()
+}
+
+fn enum_members(db: &Db, enum_: EnumId) -> Vec<Value> {
+    enum_
+        .variants(db)
+        .values()
+        .map(|variant| {
+            member(
+                db,
+                "variant",
+                &variant.name(db),
+                true,
+                variant.name_span(db),
+            )
+        })
+        .collect()
+}
+
+fn function_member(db: &Db, function: FunctionId) -> Value {
+    member(
+        db,
+        "function",
+        &function.name(db),
+        function.is_public(db),
+        function.name_span(db),
+    )
+}
+
+fn member(db: &Db, kind: &str, name: &str, public: bool, span: Span) -> Value {
+    let start = span.file_id.line_col(db, span.start);
+    json!({
+        "kind": kind,
+        "name": name,
+        "public": public,
+        "span": {
+            "start": span.start,
+            "end": span.end,
+            "line": start.line,
+            "column": start.col,
+        },
+    })
+}
@@ -1,29 +1,62 @@
 #![allow(unused_imports, dead_code)]
 
 use fe_analyzer::context::Analysis;
-use fe_analyzer::namespace::items::{IngotId, IngotMode, ModuleId};
-use fe_analyzer::AnalyzerDb;
+pub use fe_analyzer::builtins::EvmVersion;
+pub use fe_analyzer::define_constants;
+use fe_analyzer::namespace::items::{ContractId, IngotId, IngotMode, ModuleId, StructId};
+use fe_analyzer::namespace::type_id::TypeId;
+use fe_analyzer::namespace::types::{self, Type};
+pub use fe_analyzer::AnalyzerDb;
 use fe_common::diagnostics::{print_diagnostics, Diagnostic};
 use fe_common::files::{FileKind, SourceFileId};
 use fe_parser::ast::SmolStr;
-pub use fe_yulgen::Db;
-use fe_yulgen::YulgenDb;
+pub use fe_yulgen::types::AbiDecoding;
+use fe_yulgen::types::EvmSized;
+pub use fe_yulgen::{Db, YulgenDb};
 use indexmap::{indexmap, IndexMap};
 #[cfg(feature = "solc-backend")]
 use serde_json::Value;
 use std::ops::Deref;
 use std::path::Path;
 
+pub mod audit;
+pub mod inspect;
+pub mod manifest;
+
 /// The artifacts of a compiled module.
 pub struct CompiledModule {
     pub src_ast: String,
     pub lowered_ast: String,
     pub contracts: IndexMap<String, CompiledContract>,
+    /// The events, errors and structs of all contracts in the ingot as JSON,
+    /// for decoding their logs and revert data off-chain.
+    pub decoding_manifest: String,
+    /// The interfaces that are declared in the ingot as JSON, with the
+    /// selector of each of their functions, for checking that the contracts
+    /// they're used with implement them.
+    pub interface_manifest: String,
+    /// The integer arithmetic that doesn't revert on overflow because the
+    /// build profile disables overflow checks, as JSON (see
+    /// [`audit::unchecked_arithmetic`]).
+    pub unchecked_arithmetic: String,
+    /// The calls to other contracts that the contracts of the ingot can make,
+    /// as JSON (see [`audit::external_calls`]).
+    pub external_calls: String,
+    /// The source code of a mock contract for each interface, by mock name.
+    pub mocks: IndexMap<String, String>,
+    /// Diagnostics that didn't prevent compilation.
+    pub warnings: Vec<Diagnostic>,
 }
 
 /// The artifacts of a compiled contract.
 pub struct CompiledContract {
     pub json_abi: String,
+    /// The storage location of each contract field as JSON, including the
+    /// byte offsets of the fields of `#[packed]` structs.
+    pub storage_layout: String,
+    /// The content-based id of the contract type, which is the same in every
+    /// compilation of the ingot.
+    pub type_id: String,
     pub yul: String,
     #[cfg(feature = "solc-backend")]
     pub bytecode: String,
@@ -32,20 +65,49 @@ pub struct CompiledContract {
 #[derive(Debug)]
 pub struct CompileError(pub Vec<Diagnostic>);
 
+/// A step of the compilation, reported to the `on_event` callback of the
+/// `*_with_events` functions as soon as it's done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileEvent {
+    /// A module was analyzed. `path` is relative to the `src` directory of
+    /// the ingot.
+    ModuleAnalyzed {
+        path: String,
+        errors: usize,
+        warnings: usize,
+    },
+    /// A contract was compiled to Yul, and to bytecode if it was requested.
+    ContractCompiled { name: String },
+}
+
 pub fn compile_single_file(
     db: &mut Db,
     path: &str,
     src: &str,
     with_bytecode: bool,
     optimize: bool,
+) -> Result<CompiledModule, CompileError> {
+    compile_single_file_with_events(db, path, src, with_bytecode, optimize, &mut |_| {})
+}
+
+/// Like [`compile_single_file`], but reports the progress of the compilation
+/// to `on_event`.
+pub fn compile_single_file_with_events(
+    db: &mut Db,
+    path: &str,
+    src: &str,
+    with_bytecode: bool,
+    optimize: bool,
+    on_event: &mut dyn FnMut(CompileEvent),
 ) -> Result<CompiledModule, CompileError> {
     let module = ModuleId::new_standalone(db, path, src);
 
     let diags = module.diagnostics(db);
-    if diags.is_empty() {
-        compile_module_id(db, module, with_bytecode, optimize)
-    } else {
+    on_event(module_analyzed(db, module, &diags));
+    if diags.iter().any(Diagnostic::is_error) {
         Err(CompileError(diags))
+    } else {
+        compile_module_id(db, module, diags, with_bytecode, optimize, on_event)
     }
 }
 
@@ -59,6 +121,19 @@ pub fn compile_ingot(
     files: &[(impl AsRef<str>, impl AsRef<str>)],
     with_bytecode: bool,
     optimize: bool,
+) -> Result<CompiledModule, CompileError> {
+    compile_ingot_with_events(db, name, files, with_bytecode, optimize, &mut |_| {})
+}
+
+/// Like [`compile_ingot`], but reports the progress of the compilation to
+/// `on_event`.
+pub fn compile_ingot_with_events(
+    db: &mut Db,
+    name: &str,
+    files: &[(impl AsRef<str>, impl AsRef<str>)],
+    with_bytecode: bool,
+    optimize: bool,
+    on_event: &mut dyn FnMut(CompileEvent),
 ) -> Result<CompiledModule, CompileError> {
     let std = IngotId::std_lib(db);
     let ingot = IngotId::from_files(
@@ -70,31 +145,82 @@ pub fn compile_ingot(
         indexmap! { "std".into() => std },
     );
 
+    for module in ingot.all_modules(db).iter() {
+        on_event(module_analyzed(db, *module, &module.diagnostics(db)));
+    }
+
     let mut diags = ingot.diagnostics(db);
     ingot.sink_external_ingot_diagnostics(db, &mut diags);
-    if !diags.is_empty() {
+    if diags.iter().any(Diagnostic::is_error) {
         return Err(CompileError(diags));
     }
     let main_module = ingot
         .root_module(db)
         .expect("missing root module, with no diagnostic");
-    compile_module_id(db, main_module, with_bytecode, optimize)
+    compile_module_id(db, main_module, diags, with_bytecode, optimize, on_event)
+}
+
+fn module_analyzed(db: &Db, module: ModuleId, diags: &[Diagnostic]) -> CompileEvent {
+    let errors = diags.iter().filter(|diag| diag.is_error()).count();
+    CompileEvent::ModuleAnalyzed {
+        path: module.file_path_relative_to_src_dir(db).to_string(),
+        errors,
+        warnings: diags.len() - errors,
+    }
 }
 
 fn compile_module_id(
     db: &mut Db,
     module_id: ModuleId,
+    warnings: Vec<Diagnostic>,
     _with_bytecode: bool,
     _optimize: bool,
+    on_event: &mut dyn FnMut(CompileEvent),
 ) -> Result<CompiledModule, CompileError> {
     // build abi
     let json_abis = fe_abi::build(db, module_id).expect("failed to generate abi");
+    let decoding_manifest = fe_abi::build_decoding_manifest(db, module_id.ingot(db))
+        .expect("failed to generate decoding manifest");
+    let interface_manifest = fe_abi::build_interface_manifest(db, module_id.ingot(db))
+        .expect("failed to generate interface manifest");
+    let unchecked_arithmetic =
+        serde_json::to_string_pretty(&audit::unchecked_arithmetic(db, module_id.ingot(db)))
+            .expect("failed to serialize the unchecked arithmetic");
+    let external_calls =
+        serde_json::to_string_pretty(&audit::external_calls(db, module_id.ingot(db)))
+            .expect("failed to serialize the external calls");
+    let storage_layouts = module_id
+        .all_contracts(db)
+        .iter()
+        .map(|contract| (contract.name(db).to_string(), storage_layout(db, *contract)))
+        .collect::<IndexMap<_, _>>();
+    let type_ids = module_id
+        .all_contracts(db)
+        .iter()
+        .map(|contract| {
+            let typ = Type::Contract(types::Contract::from_id(*contract, db));
+            (
+                contract.name(db).to_string(),
+                TypeId::of(db, &typ).to_string(),
+            )
+        })
+        .collect::<IndexMap<_, _>>();
+
+    // generate mock contracts for the interfaces
+    let mocks = fe_lowering::mocks::module_interface_mocks(db, module_id)
+        .into_iter()
+        .map(|mock| (mock.kind.name.kind.to_string(), mock.kind.to_string()))
+        .collect();
 
     // lower the AST
     let lowered_module_id = fe_lowering::lower_main_module(db, module_id);
     let lowered_ast = format!("{:#?}", &lowered_module_id.ast(db));
 
-    if !lowered_module_id.diagnostics(db).is_empty() {
+    if lowered_module_id
+        .diagnostics(db)
+        .iter()
+        .any(Diagnostic::is_error)
+    {
         eprintln!("Error: Analysis of lowered module resulted in the following errors:");
         print_diagnostics(db, &lowered_module_id.diagnostics(db));
         panic!("Lowered module has errors. Unfortunately, this is a bug in the Fe compiler.")
@@ -114,10 +240,13 @@ fn compile_module_id(
     let contracts = json_abis
         .keys()
         .map(|name| {
+            on_event(CompileEvent::ContractCompiled { name: name.clone() });
             (
                 name.clone(),
                 CompiledContract {
                     json_abi: json_abis[name].clone(),
+                    storage_layout: storage_layouts[name].clone(),
+                    type_id: type_ids[name].clone(),
                     yul: yul_contracts[name].clone(),
                     #[cfg(feature = "solc-backend")]
                     bytecode: if _with_bytecode {
@@ -134,9 +263,71 @@ fn compile_module_id(
         src_ast: format!("{:?}", module_id.ast(db)),
         lowered_ast,
         contracts,
+        decoding_manifest,
+        interface_manifest,
+        unchecked_arithmetic,
+        external_calls,
+        mocks,
+        warnings,
     })
 }
 
+/// Describes where the fields of a contract are stored. The value of a field
+/// with nonce `n` starts at the word `keccak256(n)` (with the last byte set to
+/// zero), and a map with nonce `n` hashes its keys with `n`. The `layout` of a
+/// `#[packed]` struct lists its fields in storage order, since the compiler
+/// may reorder them to take fewer storage words. The `type_id` of a field
+/// identifies its type across compilations, so the layouts of two builds can be
+/// checked for compatibility.
+fn storage_layout(db: &Db, contract: ContractId) -> String {
+    let fields = contract
+        .fields(db)
+        .values()
+        .enumerate()
+        .map(|(nonce, field)| {
+            let typ = field.typ(db).expect("contract field type error");
+            let mut json = serde_json::json!({
+                "name": field.name(db).as_str(),
+                "type": typ.to_string(),
+                "type_id": TypeId::of(db, &typ).to_string(),
+                "nonce": nonce,
+            });
+            if let Type::Struct(struct_) = &typ {
+                if struct_.id.is_packed(db) {
+                    json["layout"] = packed_struct_layout(db, struct_.id);
+                }
+            }
+            json
+        })
+        .collect::<Vec<_>>();
+    serde_json::to_string_pretty(&fields).expect("failed to serialize storage layout")
+}
+
+fn packed_struct_layout(db: &Db, struct_: StructId) -> serde_json::Value {
+    let offsets = db.struct_packed_offsets(struct_);
+    let mut fields = struct_
+        .fields(db)
+        .iter()
+        .zip(offsets.iter())
+        .map(|((name, field), offset)| {
+            let typ = field.typ(db).expect("struct field type error");
+            (*offset, name.clone(), typ)
+        })
+        .collect::<Vec<_>>();
+    fields.sort_by_key(|(offset, ..)| *offset);
+    fields
+        .into_iter()
+        .map(|(offset, name, typ)| {
+            serde_json::json!({
+                "name": name.as_str(),
+                "type": typ.to_string(),
+                "offset": offset,
+                "size": typ.size(),
+            })
+        })
+        .collect()
+}
+
 fn compile_yul(
     _contracts: impl Iterator<Item = (impl AsRef<str>, impl AsRef<str>)>,
     _optimize: bool,
@@ -0,0 +1,450 @@
+//! The `fe.toml` manifest of an ingot.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// The name of the manifest file in the root directory of an ingot.
+pub const MANIFEST_FILE_NAME: &str = "fe.toml";
+
+/// The placeholder for the contract name in artifact file names.
+const CONTRACT_PLACEHOLDER: &str = "{contract}";
+
+/// The settings of an ingot. Every section of the manifest is optional.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    #[serde(default)]
+    pub output: OutputConfig,
+    #[serde(default)]
+    pub profile: ProfileConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+}
+
+impl Manifest {
+    /// Parses the content of a manifest file.
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let manifest: Manifest = toml::from_str(content).map_err(|err| err.to_string())?;
+        manifest.output.validate()?;
+        manifest.network.validate()?;
+        Ok(manifest)
+    }
+}
+
+/// Where the artifacts of the compiled contracts are written, relative to the
+/// output directory. The file names may contain `{contract}`, which is
+/// replaced by the name of the contract.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    pub layout: OutputLayout,
+    pub abi: String,
+    pub bytecode: String,
+    pub yul: String,
+    pub storage_layout: String,
+    pub metadata: String,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            layout: OutputLayout::default(),
+            abi: "{contract}_abi.json".into(),
+            bytecode: "{contract}.bin".into(),
+            yul: "{contract}_ir.yul".into(),
+            storage_layout: "{contract}_storage_layout.json".into(),
+            metadata: "{contract}_metadata.json".into(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputLayout {
+    /// The artifacts of each contract are written to a directory named after
+    /// the contract, e.g. `output/Foo/Foo_abi.json`.
+    #[default]
+    Nested,
+    /// The artifacts of all contracts are written to the output directory
+    /// itself, e.g. `output/Foo_abi.json`.
+    Flat,
+}
+
+/// An artifact of a compiled contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Artifact {
+    Abi,
+    Bytecode,
+    Yul,
+    StorageLayout,
+    Metadata,
+}
+
+impl OutputConfig {
+    /// The path of an artifact of the named contract, relative to the output
+    /// directory.
+    pub fn artifact_path(&self, contract: &str, artifact: Artifact) -> PathBuf {
+        let file_name = self
+            .file_name_pattern(artifact)
+            .replace(CONTRACT_PLACEHOLDER, contract);
+        match self.layout {
+            OutputLayout::Nested => Path::new(contract).join(file_name),
+            OutputLayout::Flat => file_name.into(),
+        }
+    }
+
+    fn file_name_pattern(&self, artifact: Artifact) -> &str {
+        match artifact {
+            Artifact::Abi => &self.abi,
+            Artifact::Bytecode => &self.bytecode,
+            Artifact::Yul => &self.yul,
+            Artifact::StorageLayout => &self.storage_layout,
+            Artifact::Metadata => &self.metadata,
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        let patterns = [
+            ("abi", &self.abi),
+            ("bytecode", &self.bytecode),
+            ("yul", &self.yul),
+            ("storage_layout", &self.storage_layout),
+            ("metadata", &self.metadata),
+        ];
+        for (key, pattern) in patterns {
+            if pattern.is_empty() || pattern.contains(&['/', '\\'][..]) {
+                return Err(format!(
+                    "`output.{}` must be a file name, without a directory",
+                    key
+                ));
+            }
+            if self.layout == OutputLayout::Flat && !pattern.contains(CONTRACT_PLACEHOLDER) {
+                return Err(format!(
+                    "`output.{}` must contain `{}` when the output layout is `flat`, so that the artifacts of different contracts don't overwrite each other",
+                    key, CONTRACT_PLACEHOLDER
+                ));
+            }
+        }
+
+        let mut names = patterns
+            .iter()
+            .map(|(_, pattern)| pattern)
+            .collect::<Vec<_>>();
+        names.sort();
+        names.dedup();
+        if names.len() != patterns.len() {
+            return Err("the artifacts in `output` must have different file names".into());
+        }
+        Ok(())
+    }
+}
+
+/// A build profile, which selects the settings in a `[profile.<name>]`
+/// section of the manifest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfileKind {
+    /// Used by default.
+    Debug,
+    /// Used with `fe --release`.
+    Release,
+}
+
+impl ProfileKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ProfileKind::Debug => "debug",
+            ProfileKind::Release => "release",
+        }
+    }
+}
+
+/// The `[profile.debug]` and `[profile.release]` sections of the manifest.
+/// Settings that aren't given keep the defaults of the profile.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProfileConfig {
+    debug: ProfileSettings,
+    release: ProfileSettings,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+struct ProfileSettings {
+    optimize: Option<bool>,
+    overflow_checks: Option<bool>,
+    strip_revert_strings: Option<bool>,
+    metadata: Option<bool>,
+}
+
+impl ProfileConfig {
+    /// The settings of a profile, with the defaults filled in.
+    pub fn profile(&self, kind: ProfileKind) -> Profile {
+        let (defaults, settings) = match kind {
+            ProfileKind::Debug => (Profile::default(), &self.debug),
+            ProfileKind::Release => (
+                Profile {
+                    metadata: true,
+                    ..Profile::default()
+                },
+                &self.release,
+            ),
+        };
+        Profile {
+            optimize: settings.optimize.unwrap_or(defaults.optimize),
+            overflow_checks: settings.overflow_checks.unwrap_or(defaults.overflow_checks),
+            strip_revert_strings: settings
+                .strip_revert_strings
+                .unwrap_or(defaults.strip_revert_strings),
+            metadata: settings.metadata.unwrap_or(defaults.metadata),
+        }
+    }
+}
+
+/// The settings that a build profile controls.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq, Eq)]
+pub struct Profile {
+    /// Whether the Yul optimizer is enabled.
+    pub optimize: bool,
+    /// Whether integer arithmetic reverts when it overflows. Without the
+    /// checks, the result wraps around.
+    pub overflow_checks: bool,
+    /// Whether failing `assert` statements revert without their message.
+    pub strip_revert_strings: bool,
+    /// Whether a metadata file with the compiler version and these settings
+    /// is written for each contract.
+    pub metadata: bool,
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            optimize: true,
+            overflow_checks: true,
+            strip_revert_strings: false,
+            metadata: false,
+        }
+    }
+}
+
+/// The `[network.<name>]` sections of the manifest. Each one gives the values
+/// of constants in the root module, which are used when building with
+/// `fe --network <name>`, e.g.
+///
+/// ```toml
+/// [network.mainnet]
+/// WETH = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+/// FEE_BPS = 30
+/// ```
+///
+/// A string is the source of a Fe expression, so that numbers that don't fit
+/// into a TOML integer can be given in hex.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(transparent)]
+pub struct NetworkConfig(BTreeMap<String, BTreeMap<String, toml::Value>>);
+
+impl NetworkConfig {
+    /// The values of the constants of the named network, as `(name, value)`
+    /// pairs like the arguments of `--define`.
+    pub fn defines(&self, network: &str) -> Result<Vec<(String, String)>, String> {
+        let constants = self.0.get(network).ok_or_else(|| {
+            format!(
+                "the manifest has no `[network.{}]` section{}",
+                network,
+                self.known_networks_note()
+            )
+        })?;
+        Ok(constants
+            .iter()
+            .map(|(name, value)| {
+                let value = constant_source(value).expect("invalid constant value");
+                (name.clone(), value)
+            })
+            .collect())
+    }
+
+    fn known_networks_note(&self) -> String {
+        if self.0.is_empty() {
+            String::new()
+        } else {
+            let names = self.0.keys().map(String::as_str).collect::<Vec<_>>();
+            format!(" (the known networks are {})", names.join(", "))
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        for (network, constants) in &self.0 {
+            for (name, value) in constants {
+                if constant_source(value).is_none() {
+                    return Err(format!(
+                        "`network.{}.{}` must be an integer, a boolean, or a string with the source of an expression",
+                        network, name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The Fe source of the value of a constant in a `[network.<name>]` section.
+fn constant_source(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::Integer(value) => Some(value.to_string()),
+        toml::Value::Boolean(value) => Some(value.to_string()),
+        toml::Value::String(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_layout() {
+        let manifest = Manifest::parse("").unwrap();
+        assert_eq!(manifest, Manifest::default());
+        assert_eq!(
+            manifest.output.artifact_path("Foo", Artifact::Abi),
+            PathBuf::from("Foo").join("Foo_abi.json")
+        );
+    }
+
+    #[test]
+    fn flat_layout() {
+        let manifest = Manifest::parse(
+            r#"
+[output]
+layout = "flat"
+bytecode = "{contract}.hex"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.output.artifact_path("Foo", Artifact::Bytecode),
+            PathBuf::from("Foo.hex")
+        );
+        assert_eq!(
+            manifest.output.artifact_path("Foo", Artifact::Yul),
+            PathBuf::from("Foo_ir.yul")
+        );
+    }
+
+    #[test]
+    fn nested_layout_with_plain_names() {
+        let manifest = Manifest::parse(
+            r#"
+[output]
+abi = "abi.json"
+bytecode = "bin"
+yul = "ir.yul"
+storage_layout = "storage.json"
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.output.artifact_path("Foo", Artifact::Bytecode),
+            PathBuf::from("Foo").join("bin")
+        );
+    }
+
+    #[test]
+    fn profiles() {
+        let manifest = Manifest::parse(
+            r#"
+[profile.debug]
+optimize = false
+
+[profile.release]
+overflow_checks = false
+strip_revert_strings = true
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.profile.profile(ProfileKind::Debug),
+            Profile {
+                optimize: false,
+                ..Profile::default()
+            }
+        );
+        assert_eq!(
+            manifest.profile.profile(ProfileKind::Release),
+            Profile {
+                optimize: true,
+                overflow_checks: false,
+                strip_revert_strings: true,
+                metadata: true,
+            }
+        );
+        assert_eq!(
+            Manifest::default().profile.profile(ProfileKind::Debug),
+            Profile::default()
+        );
+    }
+
+    #[test]
+    fn networks() {
+        let manifest = Manifest::parse(
+            r#"
+[network.mainnet]
+WETH = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"
+FEE_BPS = 30
+FEES_ENABLED = true
+
+[network.goerli]
+FEE_BPS = 0
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            manifest.network.defines("mainnet").unwrap(),
+            vec![
+                ("FEES_ENABLED".to_string(), "true".to_string()),
+                ("FEE_BPS".to_string(), "30".to_string()),
+                (
+                    "WETH".to_string(),
+                    "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2".to_string()
+                ),
+            ]
+        );
+        assert_eq!(
+            manifest.network.defines("sepolia").unwrap_err(),
+            "the manifest has no `[network.sepolia]` section (the known networks are goerli, mainnet)"
+        );
+    }
+
+    #[test]
+    fn invalid_manifests() {
+        for (content, error) in [
+            (
+                "[output]\nlayout = \"flat\"\nabi = \"abi.json\"",
+                "`output.abi` must contain `{contract}` when the output layout is `flat`",
+            ),
+            (
+                "[output]\nyul = \"yul/{contract}.yul\"",
+                "`output.yul` must be a file name, without a directory",
+            ),
+            (
+                "[output]\nabi = \"{contract}\"\nyul = \"{contract}\"",
+                "the artifacts in `output` must have different file names",
+            ),
+            ("[output]\nlayout = \"tree\"", "unknown variant `tree`"),
+            ("[outputs]", "unknown field `outputs`"),
+            ("[profile.staging]", "unknown field `staging`"),
+            (
+                "[profile.release]\noptimize = 3",
+                "invalid type: integer `3`",
+            ),
+            (
+                "[network.mainnet]\nFEE = 0.3",
+                "`network.mainnet.FEE` must be an integer, a boolean, or a string",
+            ),
+            ("network = 1", "invalid type: integer `1`"),
+        ] {
+            let err = Manifest::parse(content).unwrap_err();
+            assert!(err.contains(error), "unexpected error: {}", err);
+        }
+    }
+}
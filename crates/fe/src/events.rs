@@ -0,0 +1,60 @@
+//! Machine-readable progress events, printed with `--log-json`.
+//!
+//! Each event is a JSON object on its own line of stdout, with an `event`
+//! field naming its kind:
+//!
+//! - `{"event": "module_analyzed", "path": "main.fe", "errors": 0, "warnings": 1}`
+//! - `{"event": "contract_compiled", "name": "Foo"}`
+//! - `{"event": "artifact_written", "path": "output/Foo/Foo_abi.json"}`
+
+use std::path::Path;
+
+use fe_driver::CompileEvent;
+use serde_json::{json, Value};
+
+/// Prints progress events, if they were requested.
+pub struct EventLog {
+    enabled: bool,
+}
+
+impl EventLog {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn compile_event(&self, event: CompileEvent) {
+        self.emit(match event {
+            CompileEvent::ModuleAnalyzed {
+                path,
+                errors,
+                warnings,
+            } => json!({
+                "event": "module_analyzed",
+                "path": path,
+                "errors": errors,
+                "warnings": warnings,
+            }),
+            CompileEvent::ContractCompiled { name } => json!({
+                "event": "contract_compiled",
+                "name": name,
+            }),
+        })
+    }
+
+    pub fn artifact_written(&self, path: &Path) {
+        self.emit(json!({
+            "event": "artifact_written",
+            "path": path.to_string_lossy(),
+        }))
+    }
+
+    fn emit(&self, event: Value) {
+        if self.enabled {
+            println!("{}", event)
+        }
+    }
+}
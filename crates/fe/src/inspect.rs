@@ -0,0 +1,84 @@
+//! `fe inspect modules`: prints the modules of a file or an ingot and the
+//! items they define, as JSON or as an indented tree.
+
+use clap::ArgMatches;
+use fe_driver::Db;
+use serde_json::Value;
+use std::path::Path;
+
+pub fn inspect(matches: &ArgMatches) {
+    if let Some(matches) = matches.subcommand_matches("modules") {
+        modules(matches);
+    }
+}
+
+fn modules(matches: &ArgMatches) {
+    let input_path = matches.value_of("input").unwrap();
+    let mut db = Db::default();
+
+    let tree = if Path::new(input_path).is_file() {
+        let content = match std::fs::read_to_string(input_path) {
+            Err(err) => {
+                eprintln!("Failed to load file: `{}`. Error: {}", input_path, err);
+                std::process::exit(1)
+            }
+            Ok(content) => content,
+        };
+        fe_driver::inspect::single_file_tree(&mut db, input_path, &content)
+    } else {
+        if !Path::new(input_path).exists() {
+            eprintln!("Input directory does not exist: `{}`.", input_path);
+            std::process::exit(1)
+        }
+        let files = match crate::load_files_from_dir(input_path) {
+            Ok(files) => files,
+            Err(err) => {
+                eprintln!("Failed to load project files. Error: {}", err);
+                std::process::exit(1)
+            }
+        };
+        fe_driver::inspect::ingot_files_tree(&mut db, "main", &files)
+    };
+
+    if matches.is_present("json") {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&tree).expect("failed to serialize module tree")
+        );
+    } else {
+        for module in tree["modules"].as_array().into_iter().flatten() {
+            print_module(module, 0);
+        }
+    }
+}
+
+fn print_module(module: &Value, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let name = module["name"].as_str().unwrap_or("");
+    match module["file"].as_str() {
+        Some(file) => println!("{}mod {} ({})", indent, name, file),
+        None => println!("{}mod {}", indent, name),
+    }
+    for item in module["items"].as_array().into_iter().flatten() {
+        print_item(item, depth + 1);
+    }
+    for submodule in module["submodules"].as_array().into_iter().flatten() {
+        print_module(submodule, depth + 1);
+    }
+}
+
+fn print_item(item: &Value, depth: usize) {
+    println!(
+        "{}{}{} {} ({}:{})",
+        "  ".repeat(depth),
+        if item["public"] == true { "pub " } else { "" },
+        item["kind"].as_str().unwrap_or(""),
+        item["name"].as_str().unwrap_or(""),
+        // Spans are zero-based, but editors count lines and columns from 1.
+        item["span"]["line"].as_u64().unwrap_or(0) + 1,
+        item["span"]["column"].as_u64().unwrap_or(0) + 1,
+    );
+    for member in item["members"].as_array().into_iter().flatten() {
+        print_item(member, depth + 1);
+    }
+}
@@ -4,14 +4,26 @@ use std::fs;
 use std::io::{Error, Write};
 use std::path::Path;
 
-use clap::{arg_enum, values_t, App, Arg};
+use clap::{arg_enum, values_t, App, AppSettings, Arg, ArgMatches, SubCommand};
 
-use fe_common::diagnostics::print_diagnostics;
+use fe_common::diagnostics::{
+    print_diagnostics_with_config, ColorMode, Diagnostic, DiagnosticStyle, DiagnosticsConfig,
+    Severity,
+};
 use fe_common::files::SourceFileId;
 use fe_common::panic::install_panic_hook;
-use fe_driver::{CompiledModule, Db};
+use fe_driver::manifest::{
+    Artifact, Manifest, OutputConfig, Profile, ProfileKind, MANIFEST_FILE_NAME,
+};
+use fe_driver::{AbiDecoding, AnalyzerDb, CompiledModule, Db, YulgenDb};
 use walkdir::WalkDir;
 
+use events::EventLog;
+
+mod events;
+mod inspect;
+mod run;
+
 const DEFAULT_OUTPUT_DIR_NAME: &str = "output";
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -22,7 +34,13 @@ arg_enum! {
         Ast,
         LoweredAst,
         Bytecode,
+        DecodingManifest,
+        ExternalCalls,
+        Interfaces,
+        Mocks,
+        StorageLayout,
         Tokens,
+        UncheckedArithmetic,
         Yul,
     }
 }
@@ -33,6 +51,7 @@ pub fn main() {
     let matches = App::new("Fe")
         .version(VERSION)
         .about("Compiler for the Fe language")
+        .setting(AppSettings::SubcommandsNegateReqs)
         .arg(
             Arg::with_name("input")
                 .help("The input source file to use e.g erc20.fe")
@@ -52,7 +71,20 @@ pub fn main() {
                 .short("e")
                 .long("emit")
                 .help("Comma separated compile targets e.g. -e=bytecode,yul")
-                .possible_values(&["abi", "bytecode", "ast", "tokens", "yul", "loweredAst"])
+                .possible_values(&[
+                    "abi",
+                    "bytecode",
+                    "ast",
+                    "tokens",
+                    "yul",
+                    "loweredAst",
+                    "mocks",
+                    "storageLayout",
+                    "decodingManifest",
+                    "interfaces",
+                    "uncheckedArithmetic",
+                    "externalCalls",
+                ])
                 .default_value("abi,bytecode")
                 .use_delimiter(true)
                 .takes_value(true),
@@ -71,12 +103,150 @@ pub fn main() {
                 .use_delimiter(false)
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("release")
+                .long("release")
+                .help("Build with the `release` profile of the ingot's manifest, instead of the `debug` profile"),
+        )
+        .arg(
+            Arg::with_name("abi-decoding")
+                .long("abi-decoding")
+                .help("How strictly the arguments of public functions are validated. `lenient` cleans dirty high bits and ignores trailing calldata instead of reverting")
+                .possible_values(&["strict", "lenient"])
+                .default_value("strict")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("evm-version")
+                .long("evm-version")
+                .help("The EVM version (hard fork) to compile for. Calling an instruction that it doesn't have is an error")
+                .possible_values(&["istanbul", "berlin", "london", "paris", "shanghai", "cancun"])
+                .default_value("london")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("define")
+                .long("define")
+                .help("Sets the value of a constant in the root module e.g. --define FEE=30. Without a value, e.g. --define FEE, the value is read from the environment variable of the same name")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("network")
+                .long("network")
+                .help("Sets the constants in the root module to the values in the `[network.<name>]` section of the ingot's manifest. `--define` takes precedence over these values")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("color")
+                .long("color")
+                .help("Whether to color the diagnostics")
+                .possible_values(&["always", "never", "auto"])
+                .default_value("auto")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("ascii")
+                .long("ascii")
+                .help("Draw the diagnostics with ASCII characters only")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("error-format")
+                .long("error-format")
+                .help("The format of the diagnostics. `short` prints one `path:line:column: message` line per diagnostic")
+                .possible_values(&["human", "short"])
+                .default_value("human")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("deny-warnings")
+                .long("deny-warnings")
+                .help("Treat warnings as errors, and fail the compilation if there are any")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("log-json")
+                .long("log-json")
+                .help("Print progress events as JSON lines on stdout, instead of the summary"),
+        )
+        .subcommand(
+            SubCommand::with_name("run")
+                .about("Deploys a contract into an in-memory EVM and calls one of its public functions")
+                .arg(
+                    Arg::with_name("input")
+                        .help("The input source file to use e.g erc20.fe")
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("function")
+                        .help("The name of the public function to call")
+                        .index(2)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("args")
+                        .help("The arguments of the function call e.g. 26 true 0x1000000000000000000000000000000000000001")
+                        .index(3)
+                        .multiple(true),
+                )
+                .arg(
+                    Arg::with_name("contract")
+                        .short("c")
+                        .long("contract")
+                        .help("The contract to deploy, if the module has more than one")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("optimize")
+                        .long("optimize")
+                        .help("Enables the Yul optimizer")
+                        .possible_values(&["true", "false"])
+                        .default_value("true")
+                        .use_delimiter(false)
+                        .takes_value(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Prints information about a project without compiling it")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("modules")
+                        .about("Prints the modules of a file or an ingot and the items they define, with their spans")
+                        .arg(
+                            Arg::with_name("input")
+                                .help("The input source file or ingot directory e.g erc20.fe")
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("json")
+                                .long("json")
+                                .help("Print the module tree as JSON"),
+                        ),
+                ),
+        )
         .get_matches();
 
+    if let Some(matches) = matches.subcommand_matches("run") {
+        run::run(matches);
+        return;
+    }
+    if let Some(matches) = matches.subcommand_matches("inspect") {
+        inspect::inspect(matches);
+        return;
+    }
+
+    let diagnostics_config = diagnostics_config(&matches);
     let input_path = matches.value_of("input").unwrap();
     let output_dir = matches.value_of("output-dir").unwrap();
     let overwrite = matches.is_present("overwrite");
-    let optimize = matches.value_of("optimize") == Some("true");
+    let log = EventLog::new(matches.is_present("log-json"));
     let targets =
         values_t!(matches.values_of("emit"), CompilationTarget).unwrap_or_else(|e| e.exit());
     let with_bytecode = targets.contains(&CompilationTarget::Bytecode);
@@ -85,7 +255,42 @@ pub fn main() {
         eprintln!("Warning: bytecode output requires 'solc-backend' feature. Try `cargo build --release --features solc-backend`. Skipping.");
     }
 
+    let manifest = load_manifest(input_path);
+    let profile_kind = if matches.is_present("release") {
+        ProfileKind::Release
+    } else {
+        ProfileKind::Debug
+    };
+    let mut profile = manifest.profile.profile(profile_kind);
+    // An explicit `--optimize` overrides the profile.
+    if matches.occurrences_of("optimize") > 0 {
+        profile.optimize = matches.value_of("optimize") == Some("true");
+    }
+    let abi_decoding = matches.value_of("abi-decoding").unwrap();
+    let evm_version = matches.value_of("evm-version").unwrap();
+    let network = matches.value_of("network");
+    let metadata = profile
+        .metadata
+        .then(|| contract_metadata(profile_kind, &profile, abi_decoding, evm_version, network));
+
+    let mut defines = match network {
+        Some(network) => manifest.network.defines(network).unwrap_or_else(|err| {
+            eprintln!("Failed to select network `{}`. Error: {}", network, err);
+            std::process::exit(1)
+        }),
+        None => vec![],
+    };
+    defines.extend(constant_defines(&matches));
+
     let mut db = Db::default();
+    db.set_abi_decoding(match abi_decoding {
+        "lenient" => AbiDecoding::Lenient,
+        _ => AbiDecoding::Strict,
+    });
+    db.set_overflow_checks(profile.overflow_checks);
+    db.set_strip_revert_strings(profile.strip_revert_strings);
+    db.set_evm_version(evm_version.parse().expect("invalid evm version"));
+    fe_driver::define_constants(&mut db, &defines);
 
     let (content, compiled_module) = if Path::new(input_path).is_file() {
         let content = match std::fs::read_to_string(input_path) {
@@ -96,17 +301,18 @@ pub fn main() {
             Ok(content) => content,
         };
 
-        let compiled_module = match fe_driver::compile_single_file(
+        let compiled_module = match fe_driver::compile_single_file_with_events(
             &mut db,
             input_path,
             &content,
             with_bytecode,
-            optimize,
+            profile.optimize,
+            &mut |event| log.compile_event(event),
         ) {
             Ok(module) => module,
             Err(error) => {
                 eprintln!("Unable to compile {}.", input_path);
-                print_diagnostics(&db, &error.0);
+                print_diagnostics_with_config(&db, &error.0, &diagnostics_config);
                 std::process::exit(1)
             }
         };
@@ -125,17 +331,18 @@ pub fn main() {
             }
         };
 
-        let compiled_module = match fe_driver::compile_ingot(
+        let compiled_module = match fe_driver::compile_ingot_with_events(
             &mut db,
             "main", // TODO: real ingot name
             &files,
             with_bytecode,
-            optimize,
+            profile.optimize,
+            &mut |event| log.compile_event(event),
         ) {
             Ok(module) => module,
             Err(error) => {
                 eprintln!("Unable to compile {}.", input_path);
-                print_diagnostics(&db, &error.0);
+                print_diagnostics_with_config(&db, &error.0, &diagnostics_config);
                 std::process::exit(1)
             }
         };
@@ -144,7 +351,25 @@ pub fn main() {
         ("".to_string(), compiled_module)
     };
 
-    match write_compiled_module(compiled_module, &content, &targets, output_dir, overwrite) {
+    report_warnings(
+        &db,
+        &matches,
+        input_path,
+        &compiled_module.warnings,
+        &diagnostics_config,
+    );
+
+    match write_compiled_module(
+        compiled_module,
+        &content,
+        &targets,
+        output_dir,
+        &manifest.output,
+        metadata.as_ref(),
+        overwrite,
+        &log,
+    ) {
+        Ok(_) if log.is_enabled() => {}
         Ok(_) => println!("Compiled {}. Outputs in `{}`", input_path, output_dir),
         Err(err) => {
             eprintln!(
@@ -156,6 +381,129 @@ pub fn main() {
     }
 }
 
+/// Prints the warnings of a compiled module. With `--deny-warnings`, the
+/// warnings are printed as errors, and the process exits.
+fn report_warnings(
+    db: &Db,
+    matches: &ArgMatches,
+    input_path: &str,
+    warnings: &[Diagnostic],
+    config: &DiagnosticsConfig,
+) {
+    if warnings.is_empty() {
+        return;
+    }
+    if !matches.is_present("deny-warnings") {
+        print_diagnostics_with_config(db, warnings, config);
+        return;
+    }
+
+    let errors = warnings
+        .iter()
+        .cloned()
+        .map(|warning| Diagnostic {
+            severity: Severity::Error,
+            ..warning
+        })
+        .collect::<Vec<_>>();
+    eprintln!("Unable to compile {}.", input_path);
+    print_diagnostics_with_config(db, &errors, config);
+    eprintln!("Note: warnings are treated as errors because of `--deny-warnings`");
+    std::process::exit(1)
+}
+
+/// Reads the options for printing diagnostics from the command-line arguments.
+fn diagnostics_config(matches: &ArgMatches) -> DiagnosticsConfig {
+    DiagnosticsConfig {
+        color: match matches.value_of("color") {
+            Some("always") => ColorMode::Always,
+            Some("never") => ColorMode::Never,
+            _ => ColorMode::Auto,
+        },
+        style: match matches.value_of("error-format") {
+            Some("short") => DiagnosticStyle::Short,
+            _ => DiagnosticStyle::Rich,
+        },
+        ascii: matches.is_present("ascii"),
+    }
+}
+
+/// Reads the manifest of the ingot at `input_path`. A single file, or an ingot
+/// without a manifest, gets the default settings.
+fn load_manifest(input_path: &str) -> Manifest {
+    let path = Path::new(input_path).join(MANIFEST_FILE_NAME);
+    if !path.is_file() {
+        return Manifest::default();
+    }
+    let result = fs::read_to_string(&path)
+        .map_err(ioerr_to_string)
+        .and_then(|content| Manifest::parse(&content));
+    match result {
+        Ok(manifest) => manifest,
+        Err(err) => {
+            eprintln!(
+                "Failed to load manifest `{}`. Error: {}",
+                path.display(),
+                err
+            );
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Reads the `--define NAME=VALUE` arguments. A `--define NAME` argument
+/// takes the value of the environment variable `NAME`.
+fn constant_defines(matches: &ArgMatches) -> Vec<(String, String)> {
+    let args = matches.values_of("define").into_iter().flatten();
+    args.map(|arg| {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name.trim(), value.to_string()),
+            None => match std::env::var(arg.trim()) {
+                Ok(value) => (arg.trim(), value),
+                Err(_) => {
+                    eprintln!(
+                        "Failed to define `{}`. Error: the environment variable `{}` isn't set",
+                        arg, arg
+                    );
+                    std::process::exit(1)
+                }
+            },
+        };
+        if name.is_empty() {
+            eprintln!(
+                "Failed to define `{}`. Error: the name of the constant is missing",
+                arg
+            );
+            std::process::exit(1)
+        }
+        (name.to_string(), value)
+    })
+    .collect()
+}
+
+/// The metadata that's written for each contract when the profile asks for it,
+/// without the name of the contract.
+fn contract_metadata(
+    profile_kind: ProfileKind,
+    profile: &Profile,
+    abi_decoding: &str,
+    evm_version: &str,
+    network: Option<&str>,
+) -> serde_json::Value {
+    let mut settings = serde_json::to_value(profile).expect("failed to serialize profile");
+    settings["abi_decoding"] = abi_decoding.into();
+    settings["evm_version"] = evm_version.into();
+    settings["network"] = network.into();
+    serde_json::json!({
+        "compiler": {
+            "name": "fe",
+            "version": VERSION,
+        },
+        "profile": profile_kind.name(),
+        "settings": settings,
+    })
+}
+
 fn load_files_from_dir(dir_path: &str) -> Result<Vec<(String, String)>, std::io::Error> {
     WalkDir::new(dir_path)
         .into_iter()
@@ -173,7 +521,10 @@ fn write_compiled_module(
     file_content: &str,
     targets: &[CompilationTarget],
     output_dir: &str,
+    output_config: &OutputConfig,
+    metadata: Option<&serde_json::Value>,
     overwrite: bool,
+    log: &EventLog,
 ) -> Result<(), String> {
     let output_dir = Path::new(output_dir);
     if output_dir.is_file() {
@@ -190,11 +541,15 @@ fn write_compiled_module(
     fs::create_dir_all(output_dir).map_err(ioerr_to_string)?;
 
     if targets.contains(&CompilationTarget::Ast) {
-        write_output(&output_dir.join("module.ast"), &module.src_ast)?;
+        write_output(&output_dir.join("module.ast"), &module.src_ast, log)?;
     }
 
     if targets.contains(&CompilationTarget::LoweredAst) {
-        write_output(&output_dir.join("lowered_module.ast"), &module.lowered_ast)?;
+        write_output(
+            &output_dir.join("lowered_module.ast"),
+            &module.lowered_ast,
+            log,
+        )?;
     }
 
     if targets.contains(&CompilationTarget::Tokens) {
@@ -202,34 +557,90 @@ fn write_compiled_module(
             let lexer = fe_parser::lexer::Lexer::new(SourceFileId::dummy_file(), file_content);
             lexer.collect::<Vec<_>>()
         };
-        write_output(&output_dir.join("module.tokens"), &format!("{:#?}", tokens))?;
+        write_output(
+            &output_dir.join("module.tokens"),
+            &format!("{:#?}", tokens),
+            log,
+        )?;
+    }
+
+    if targets.contains(&CompilationTarget::DecodingManifest) {
+        write_output(
+            &output_dir.join("decoding_manifest.json"),
+            &module.decoding_manifest,
+            log,
+        )?;
+    }
+
+    if targets.contains(&CompilationTarget::Interfaces) {
+        write_output(
+            &output_dir.join("interfaces.json"),
+            &module.interface_manifest,
+            log,
+        )?;
+    }
+
+    if targets.contains(&CompilationTarget::UncheckedArithmetic) {
+        write_output(
+            &output_dir.join("unchecked_arithmetic.json"),
+            &module.unchecked_arithmetic,
+            log,
+        )?;
+    }
+
+    if targets.contains(&CompilationTarget::ExternalCalls) {
+        write_output(
+            &output_dir.join("external_calls.json"),
+            &module.external_calls,
+            log,
+        )?;
+    }
+
+    if targets.contains(&CompilationTarget::Mocks) {
+        for (name, mock) in &module.mocks {
+            write_output(&output_dir.join(format!("{}.fe", name)), mock, log)?;
+        }
     }
 
     for (name, contract) in module.contracts.drain(0..) {
-        let contract_output_dir = output_dir.join(&name);
-        fs::create_dir_all(&contract_output_dir).map_err(ioerr_to_string)?;
+        let contract_metadata = metadata.map(|metadata| {
+            let mut metadata = metadata.clone();
+            metadata["contract"] = name.as_str().into();
+            metadata["type_id"] = contract.type_id.as_str().into();
+            serde_json::to_string_pretty(&metadata).expect("failed to serialize metadata")
+        });
 
+        let mut artifacts = vec![];
         if targets.contains(&CompilationTarget::Abi) {
-            let file_name = format!("{}_abi.json", &name);
-            write_output(&contract_output_dir.join(file_name), &contract.json_abi)?;
+            artifacts.push((Artifact::Abi, &contract.json_abi));
+        }
+        if targets.contains(&CompilationTarget::StorageLayout) {
+            artifacts.push((Artifact::StorageLayout, &contract.storage_layout));
         }
-
         if targets.contains(&CompilationTarget::Yul) {
-            let file_name = format!("{}_ir.yul", &name);
-            write_output(&contract_output_dir.join(file_name), &contract.yul)?;
+            artifacts.push((Artifact::Yul, &contract.yul));
         }
-
         #[cfg(feature = "solc-backend")]
         if targets.contains(&CompilationTarget::Bytecode) {
-            let file_name = format!("{}.bin", &name);
-            write_output(&contract_output_dir.join(file_name), &contract.bytecode)?;
+            artifacts.push((Artifact::Bytecode, &contract.bytecode));
+        }
+        if let Some(content) = &contract_metadata {
+            artifacts.push((Artifact::Metadata, content));
+        }
+
+        for (artifact, content) in artifacts {
+            let path = output_dir.join(output_config.artifact_path(&name, artifact));
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).map_err(ioerr_to_string)?;
+            }
+            write_output(&path, content, log)?;
         }
     }
 
     Ok(())
 }
 
-fn write_output(path: &Path, content: &str) -> Result<(), String> {
+fn write_output(path: &Path, content: &str, log: &EventLog) -> Result<(), String> {
     let mut file = fs::OpenOptions::new()
         .write(true)
         .create(true)
@@ -238,6 +649,7 @@ fn write_output(path: &Path, content: &str) -> Result<(), String> {
         .map_err(ioerr_to_string)?;
     file.write_all(content.as_bytes())
         .map_err(ioerr_to_string)?;
+    log.artifact_written(path);
     Ok(())
 }
 
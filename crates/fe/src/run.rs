@@ -0,0 +1,274 @@
+//! `fe run`: compiles a single module, deploys one of its contracts into an
+//! in-memory EVM, and calls one of the contract's public functions.
+
+use clap::ArgMatches;
+
+#[cfg(not(feature = "solc-backend"))]
+pub fn run(_matches: &ArgMatches) {
+    eprintln!("Error: `fe run` requires the 'solc-backend' feature. Try `cargo build --release --features solc-backend`.");
+    std::process::exit(1)
+}
+
+#[cfg(feature = "solc-backend")]
+pub fn run(matches: &ArgMatches) {
+    use fe_common::diagnostics::print_diagnostics_with_config;
+    use fe_driver::Db;
+    use std::path::Path;
+
+    let input_path = matches.value_of("input").unwrap();
+    let function_name = matches.value_of("function").unwrap();
+    let args = matches
+        .values_of("args")
+        .map(|values| values.collect::<Vec<_>>())
+        .unwrap_or_default();
+    let optimize = matches.value_of("optimize") == Some("true");
+    let diagnostics_config = crate::diagnostics_config(matches);
+
+    if !Path::new(input_path).is_file() {
+        eprintln!(
+            "Input file does not exist: `{}`. `fe run` only supports single-file modules.",
+            input_path
+        );
+        std::process::exit(1)
+    }
+    let content = match std::fs::read_to_string(input_path) {
+        Err(err) => {
+            eprintln!("Failed to load file: `{}`. Error: {}", input_path, err);
+            std::process::exit(1)
+        }
+        Ok(content) => content,
+    };
+
+    let mut db = Db::default();
+    let compiled_module =
+        match fe_driver::compile_single_file(&mut db, input_path, &content, true, optimize) {
+            Ok(module) => module,
+            Err(error) => {
+                eprintln!("Unable to compile {}.", input_path);
+                print_diagnostics_with_config(&db, &error.0, &diagnostics_config);
+                std::process::exit(1)
+            }
+        };
+
+    crate::report_warnings(
+        &db,
+        matches,
+        input_path,
+        &compiled_module.warnings,
+        &diagnostics_config,
+    );
+
+    let (contract_name, contract) =
+        match select_contract(&compiled_module, matches.value_of("contract")) {
+            Ok(contract) => contract,
+            Err(err) => {
+                eprintln!("Error: {}", err);
+                std::process::exit(1)
+            }
+        };
+
+    match execution::call_function(&contract.bytecode, &contract.json_abi, function_name, &args) {
+        Ok(outputs) => {
+            for output in outputs {
+                println!("{}", output)
+            }
+        }
+        Err(err) => {
+            eprintln!("Error: `{}.{}` {}", contract_name, function_name, err);
+            std::process::exit(1)
+        }
+    }
+}
+
+/// Returns the contract with the given name, or the only contract of the
+/// module if no name is given.
+#[cfg(feature = "solc-backend")]
+fn select_contract<'a>(
+    module: &'a fe_driver::CompiledModule,
+    name: Option<&str>,
+) -> Result<(&'a str, &'a fe_driver::CompiledContract), String> {
+    let names = || {
+        module
+            .contracts
+            .keys()
+            .map(|name| format!("`{}`", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    match name {
+        Some(name) => module
+            .contracts
+            .get_full(name)
+            .map(|(_, name, contract)| (name.as_str(), contract))
+            .ok_or_else(|| {
+                format!(
+                    "the module has no contract named `{}`. Contracts: {}",
+                    name,
+                    names()
+                )
+            }),
+        None if module.contracts.len() == 1 => {
+            let (name, contract) = module.contracts.get_index(0).unwrap();
+            Ok((name.as_str(), contract))
+        }
+        None if module.contracts.is_empty() => Err("the module has no contracts".to_string()),
+        None => Err(format!(
+            "the module has more than one contract, choose one with `--contract`. Contracts: {}",
+            names()
+        )),
+    }
+}
+
+#[cfg(feature = "solc-backend")]
+mod execution {
+    use ethabi::token::{LenientTokenizer, Tokenizer};
+    use ethabi::{ParamType, Token};
+    use evm::backend::{MemoryBackend, MemoryVicinity};
+    use evm::executor::{MemoryStackState, StackExecutor, StackSubstateMetadata};
+    use evm_runtime::{ExitReason, Handler};
+    use primitive_types::{H160, U256};
+    use std::collections::BTreeMap;
+
+    /// The address that deploys the contract and calls the function.
+    const CALLER: H160 = H160([
+        0x10, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x01,
+    ]);
+
+    /// Deploys the contract and calls `function_name` with the given arguments,
+    /// which are parsed according to the function's ABI. Returns the decoded
+    /// return values.
+    pub fn call_function(
+        bytecode: &str,
+        json_abi: &str,
+        function_name: &str,
+        args: &[&str],
+    ) -> Result<Vec<Token>, String> {
+        let abi = load_abi(json_abi)?;
+        if matches!(&abi.constructor, Some(constructor) if !constructor.inputs.is_empty()) {
+            return Err(
+                "can't be run, because the contract's `__init__` function takes arguments"
+                    .to_string(),
+            );
+        }
+
+        let function = abi
+            .function(function_name)
+            .map_err(|_| "is not a public function of the contract".to_string())?;
+        if function.inputs.len() != args.len() {
+            return Err(format!(
+                "expects {} argument(s), but {} were provided",
+                function.inputs.len(),
+                args.len()
+            ));
+        }
+        let tokens = function
+            .inputs
+            .iter()
+            .zip(args.iter().copied())
+            .map(|(param, arg)| {
+                // The tokenizer expects hex values without a `0x` prefix.
+                let value = match param.kind {
+                    ParamType::Address | ParamType::Bytes | ParamType::FixedBytes(_) => {
+                        arg.strip_prefix("0x").unwrap_or(arg)
+                    }
+                    _ => arg,
+                };
+                LenientTokenizer::tokenize(&param.kind, value).map_err(|err| {
+                    format!(
+                        "argument `{}` expects a value of type `{}`, but `{}` is invalid: {}",
+                        param.name, param.kind, arg, err
+                    )
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let input = function
+            .encode_input(&tokens)
+            .map_err(|err| format!("arguments could not be encoded: {}", err))?;
+        let bytecode =
+            hex::decode(bytecode).map_err(|err| format!("has invalid bytecode: {}", err))?;
+
+        let vicinity = MemoryVicinity {
+            gas_price: U256::zero(),
+            origin: CALLER,
+            chain_id: U256::one(),
+            block_hashes: Vec::new(),
+            block_number: U256::zero(),
+            block_coinbase: H160::zero(),
+            block_timestamp: U256::zero(),
+            block_difficulty: U256::zero(),
+            block_gas_limit: U256::MAX,
+        };
+        let backend = MemoryBackend::new(&vicinity, BTreeMap::new());
+        let config = evm::Config::istanbul();
+        let state = MemoryStackState::new(StackSubstateMetadata::new(u64::MAX, &config), &backend);
+        let mut executor = StackExecutor::new(state, &config);
+
+        let address = match executor.create(
+            CALLER,
+            evm_runtime::CreateScheme::Legacy { caller: CALLER },
+            U256::zero(),
+            bytecode,
+            None,
+        ) {
+            evm::Capture::Exit((ExitReason::Succeed(_), Some(address), _)) => address,
+            evm::Capture::Exit((reason, _, output)) => {
+                return Err(format!(
+                    "could not be called, because deploying the contract failed: {}",
+                    describe_failure(&reason, &output)
+                ))
+            }
+            evm::Capture::Trap(_) => unreachable!(),
+        };
+
+        let context = evm::Context {
+            address,
+            caller: CALLER,
+            apparent_value: U256::zero(),
+        };
+        match executor.call(address, None, input, None, false, context) {
+            evm::Capture::Exit((ExitReason::Succeed(_), output)) => function
+                .decode_output(&output)
+                .map_err(|err| format!("returned data that could not be decoded: {}", err)),
+            evm::Capture::Exit((reason, output)) => {
+                Err(format!("failed: {}", describe_failure(&reason, &output)))
+            }
+            evm::Capture::Trap(_) => unreachable!(),
+        }
+    }
+
+    /// Load a JSON ABI, skipping `error` entries, which ethabi 14 doesn't understand.
+    fn load_abi(json: &str) -> Result<ethabi::Contract, String> {
+        let mut entries: Vec<serde_json::Value> =
+            serde_json::from_str(json).map_err(|err| format!("has an invalid ABI: {}", err))?;
+        entries.retain(|entry| entry["type"] != "error");
+        ethabi::Contract::load(serde_json::to_string(&entries).unwrap().as_bytes())
+            .map_err(|err| format!("has an invalid ABI: {}", err))
+    }
+
+    /// Describes why a call didn't succeed. `Error(string)` and `Panic(uint256)`
+    /// revert data is decoded, any other revert data is printed as hex.
+    fn describe_failure(reason: &ExitReason, output: &[u8]) -> String {
+        if !matches!(reason, ExitReason::Revert(_)) {
+            return format!("{:?}", reason);
+        }
+
+        let decode = |selector: [u8; 4], kind: ParamType| {
+            if output.len() < 4 || output[..4] != selector {
+                return None;
+            }
+            ethabi::decode(&[kind], &output[4..]).ok()?.pop()
+        };
+        if let Some(Token::String(reason)) = decode([0x08, 0xc3, 0x79, 0xa0], ParamType::String) {
+            format!("reverted with reason \"{}\"", reason)
+        } else if let Some(Token::Uint(code)) =
+            decode([0x4e, 0x48, 0x7b, 0x71], ParamType::Uint(256))
+        {
+            format!("panicked with code {:#x}", code)
+        } else if output.is_empty() {
+            "reverted".to_string()
+        } else {
+            format!("reverted with data 0x{}", hex::encode(output))
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use fe_analyzer::namespace::types::FixedSize;
-use fe_parser::ast::{BoolOperator, CallArg, Expr, FuncStmt, UnaryOperator, VarDeclTarget};
+use fe_parser::ast::{
+    BoolOperator, CallArg, CatchClause, Expr, FuncStmt, UnaryOperator, VarDeclTarget, YulInput,
+};
 use fe_parser::node::{Node, NodeId};
 
 use crate::names;
@@ -90,6 +92,8 @@ where
                     error: error.map(|val| map_ast_node(val.into(), map_fn).as_expr()),
                 },
                 FuncStmt::Unsafe(body) => FuncStmt::Unsafe(map_body(body, map_fn)),
+                FuncStmt::Unchecked(body) => FuncStmt::Unchecked(map_body(body, map_fn)),
+                FuncStmt::Checked(body) => FuncStmt::Checked(map_body(body, map_fn)),
                 FuncStmt::VarDecl { target, typ, value } => FuncStmt::VarDecl {
                     target,
                     typ,
@@ -104,8 +108,47 @@ where
                     test: map_ast_node(test.into(), map_fn).as_expr(),
                     body: map_body(body, map_fn),
                 },
+                FuncStmt::Loop { result, body } => FuncStmt::Loop {
+                    result,
+                    body: map_body(body, map_fn),
+                },
+                FuncStmt::Break { value } => FuncStmt::Break {
+                    value: value.map(|val| map_ast_node(val.into(), map_fn).as_expr()),
+                },
+                FuncStmt::Yul {
+                    inputs,
+                    outputs,
+                    code,
+                } => FuncStmt::Yul {
+                    inputs: inputs
+                        .into_iter()
+                        .map(|input| {
+                            Node::new(
+                                YulInput {
+                                    name: input.kind.name,
+                                    value: map_ast_node(input.kind.value.into(), map_fn).as_expr(),
+                                },
+                                input.span,
+                            )
+                        })
+                        .collect(),
+                    outputs,
+                    code,
+                },
+                FuncStmt::Try {
+                    call,
+                    binding,
+                    body,
+                    catches,
+                } => FuncStmt::Try {
+                    call: map_ast_node(call.into(), map_fn).as_expr(),
+                    binding,
+                    body: map_body(body, map_fn),
+                    catches: map_catch_clauses(catches, |body| map_body(body, map_fn)),
+                },
+                FuncStmt::Match { .. } => panic!("Match should be lowered"),
                 // See comment below for why no catch all should be used here
-                FuncStmt::Pass | FuncStmt::Break | FuncStmt::Continue => stmt.kind,
+                FuncStmt::Pass | FuncStmt::Continue => stmt.kind,
             }
             .into_traceable_node(stmt.original_id);
 
@@ -131,21 +174,31 @@ where
                     args,
                     func,
                     generic_args,
+                    base,
                 } => Expr::Call {
                     args: map_call_args(args, map_fn),
                     func: Box::new(map_ast_node((*func).into(), map_fn).as_expr()),
                     generic_args,
+                    base: base.map(|base| Box::new(map_ast_node((*base).into(), map_fn).as_expr())),
                 },
                 Expr::CompOperation { left, op, right } => Expr::CompOperation {
                     left: Box::new(map_ast_node((*left).into(), map_fn).as_expr()),
                     right: Box::new(map_ast_node((*right).into(), map_fn).as_expr()),
                     op,
                 },
-                Expr::List { elts } => Expr::List {
+                Expr::ChainedComparison { left, comparisons } => Expr::ChainedComparison {
+                    left: Box::new(map_ast_node((*left).into(), map_fn).as_expr()),
+                    comparisons: comparisons
+                        .into_iter()
+                        .map(|(op, operand)| (op, map_ast_node(operand.into(), map_fn).as_expr()))
+                        .collect(),
+                },
+                Expr::List { elts, fill } => Expr::List {
                     elts: elts
                         .into_iter()
                         .map(|val| map_ast_node(val.into(), map_fn).as_expr())
                         .collect(),
+                    fill: fill.map(|fill| Box::new(map_ast_node((*fill).into(), map_fn).as_expr())),
                 },
                 Expr::Subscript { value, index } => Expr::Subscript {
                     value: Box::new(map_ast_node((*value).into(), map_fn).as_expr()),
@@ -336,10 +389,27 @@ pub fn inject_before_expression(
                     )
                 }
             }
+            FuncStmt::Loop { result, body } => transformed_body.push(
+                FuncStmt::Loop {
+                    result,
+                    body: inject_before_expression(&body, expression, injection),
+                }
+                .into_traceable_node(stmt.original_id),
+            ),
             FuncStmt::Unsafe(body) => transformed_body.push(
                 FuncStmt::Unsafe(inject_before_expression(&body, expression, injection))
                     .into_traceable_node(stmt.original_id),
             ),
+            FuncStmt::Unchecked(body) => transformed_body.push(
+                FuncStmt::Unchecked(inject_before_expression(&body, expression, injection))
+                    .into_traceable_node(stmt.original_id),
+            ),
+            // The injected statements keep the overflow checks of the
+            // `#[checked]` statement.
+            FuncStmt::Checked(body) => transformed_body.push(
+                FuncStmt::Checked(inject_before_expression(&body, expression, injection))
+                    .into_traceable_node(stmt.original_id),
+            ),
             // The following statements contain no further sub statements, only expressions.
             // At this point it doesn't matter how deeply nested our expression is found because
             // expressions can not contain statements.
@@ -362,7 +432,9 @@ pub fn inject_before_expression(
                     stmt,
                 );
             }
-            FuncStmt::VarDecl { value, .. } | FuncStmt::Return { value } => {
+            FuncStmt::VarDecl { value, .. }
+            | FuncStmt::Return { value }
+            | FuncStmt::Break { value } => {
                 if let Some(val) = value {
                     inject_or_add_current(
                         &mut transformed_body,
@@ -426,14 +498,66 @@ pub fn inject_before_expression(
                     transformed_body.push(stmt.clone())
                 }
             }
-            FuncStmt::Break | FuncStmt::Continue | FuncStmt::Pass => {
-                transformed_body.push(stmt.clone())
+            FuncStmt::Yul { inputs, .. } => {
+                if inputs
+                    .iter()
+                    .any(|input| contains_node(&input.kind.value, expression))
+                {
+                    transformed_body = [&transformed_body, injection].concat();
+                }
+                transformed_body.push(stmt.clone());
             }
+            FuncStmt::Try {
+                call,
+                binding,
+                body,
+                catches,
+            } => {
+                if !inject_if_contains_target(
+                    &mut transformed_body,
+                    injection_and_current_stmt,
+                    &[&call],
+                    expression,
+                ) {
+                    transformed_body.push(
+                        FuncStmt::Try {
+                            call,
+                            binding,
+                            body: inject_before_expression(&body, expression, injection),
+                            catches: map_catch_clauses(catches, |body| {
+                                inject_before_expression(&body, expression, injection)
+                            }),
+                        }
+                        .into_traceable_node(stmt.original_id),
+                    );
+                }
+            }
+            FuncStmt::Continue | FuncStmt::Pass => transformed_body.push(stmt.clone()),
+            FuncStmt::Match { .. } => panic!("Match should be lowered"),
         }
     }
     transformed_body
 }
 
+/// Maps the bodies of the `catch` clauses of a `try` statement.
+pub fn map_catch_clauses<F>(catches: Vec<Node<CatchClause>>, mut map_fn: F) -> Vec<Node<CatchClause>>
+where
+    F: FnMut(Vec<Node<FuncStmt>>) -> Vec<Node<FuncStmt>>,
+{
+    catches
+        .into_iter()
+        .map(|clause| {
+            Node::new(
+                CatchClause {
+                    error: clause.kind.error,
+                    body: map_fn(clause.kind.body),
+                },
+                clause.span,
+            )
+        })
+        .collect()
+}
+
 /// Turns a ternary expression into a set of statements resembling an if/else block with equal
 /// functionality. Expects the type and variable result name to be provided as parameters.
 pub fn ternary_to_if(
@@ -449,7 +573,7 @@ pub fn ternary_to_if(
     {
         let mut stmts = vec![FuncStmt::VarDecl {
             target: VarDeclTarget::Name(result_name.into()).into_node(),
-            typ: names::fixed_size_type_desc(&ternary_type).into_node(),
+            typ: Some(names::fixed_size_type_desc(&ternary_type).into_node()),
             value: None,
         }
         .into_node()];
@@ -487,6 +611,150 @@ pub fn ternary_to_if(
     unreachable!()
 }
 
+/// Turns the statements that declare or assign a variable with the value of a
+/// ternary expression, or return one, into if/else blocks that assign or return
+/// each value directly. Unlike `ternary_to_if`, this doesn't need a temporary
+/// variable for the result.
+///
+/// e.g. `let x: u256 = a if c else b` becomes:
+/// ```fe
+/// let x: u256
+/// if c:
+///     x = a
+/// else:
+///     x = b
+/// ```
+pub fn ternary_assignments_to_if(nodes: &[Node<FuncStmt>]) -> Vec<Node<FuncStmt>> {
+    nodes.iter().flat_map(ternary_assignment_to_if).collect()
+}
+
+fn ternary_assignment_to_if(node: &Node<FuncStmt>) -> Vec<Node<FuncStmt>> {
+    let lowered = match &node.kind {
+        FuncStmt::VarDecl {
+            target,
+            typ,
+            value: Some(value),
+        } => match &target.kind {
+            VarDeclTarget::Name(name) if is_ternary(value) => {
+                let decl = FuncStmt::VarDecl {
+                    target: target.clone(),
+                    typ: typ.clone(),
+                    value: None,
+                };
+                let assign = |value| FuncStmt::Assign {
+                    target: Expr::Name(name.clone()).into_node(),
+                    value,
+                };
+                return vec![decl.into_node(), ternary_branches(value, &assign)];
+            }
+            _ => None,
+        },
+        FuncStmt::Assign { target, value }
+            if matches!(target.kind, Expr::Name(_)) && is_ternary(value) =>
+        {
+            let assign = |value| FuncStmt::Assign {
+                target: target.kind.clone().into_traceable_node(target.original_id),
+                value,
+            };
+            Some(ternary_branches(value, &assign))
+        }
+        FuncStmt::Return { value: Some(value) } if is_ternary(value) => {
+            let ret = |value| FuncStmt::Return { value: Some(value) };
+            Some(ternary_branches(value, &ret))
+        }
+        FuncStmt::If {
+            test,
+            body,
+            or_else,
+        } => Some(
+            FuncStmt::If {
+                test: test.clone(),
+                body: ternary_assignments_to_if(body),
+                or_else: ternary_assignments_to_if(or_else),
+            }
+            .into_node(),
+        ),
+        FuncStmt::For { target, iter, body } => Some(
+            FuncStmt::For {
+                target: target.clone(),
+                iter: iter.clone(),
+                body: ternary_assignments_to_if(body),
+            }
+            .into_node(),
+        ),
+        FuncStmt::While { test, body } => Some(
+            FuncStmt::While {
+                test: test.clone(),
+                body: ternary_assignments_to_if(body),
+            }
+            .into_node(),
+        ),
+        FuncStmt::Loop { result, body } => Some(
+            FuncStmt::Loop {
+                result: result.clone(),
+                body: ternary_assignments_to_if(body),
+            }
+            .into_node(),
+        ),
+        FuncStmt::Unsafe(body) => {
+            Some(FuncStmt::Unsafe(ternary_assignments_to_if(body)).into_node())
+        }
+        FuncStmt::Unchecked(body) => {
+            Some(FuncStmt::Unchecked(ternary_assignments_to_if(body)).into_node())
+        }
+        FuncStmt::Checked(body) => {
+            Some(FuncStmt::Checked(ternary_assignments_to_if(body)).into_node())
+        }
+        FuncStmt::Try {
+            call,
+            binding,
+            body,
+            catches,
+        } => Some(
+            FuncStmt::Try {
+                call: call.clone(),
+                binding: binding.clone(),
+                body: ternary_assignments_to_if(body),
+                catches: map_catch_clauses(catches.clone(), |body| {
+                    ternary_assignments_to_if(&body)
+                }),
+            }
+            .into_node(),
+        ),
+        _ => None,
+    };
+    vec![lowered.unwrap_or_else(|| node.clone())]
+}
+
+fn is_ternary(expr: &Node<Expr>) -> bool {
+    matches!(expr.kind, Expr::Ternary { .. })
+}
+
+/// Turns `a if c else b` into an if/else block with the statement made by
+/// `branch` for each value. Nested ternary expressions are turned into nested
+/// blocks.
+fn ternary_branches(expr: &Node<Expr>, branch: &dyn Fn(Node<Expr>) -> FuncStmt) -> Node<FuncStmt> {
+    if let Expr::Ternary {
+        if_expr,
+        test,
+        else_expr,
+    } = &expr.kind
+    {
+        let branch = |value: &Node<Expr>| {
+            let value = value.kind.clone().into_traceable_node(value.original_id);
+            ternary_assignment_to_if(&branch(value).into_node())
+        };
+        return FuncStmt::If {
+            test: test.kind.clone().into_traceable_node(test.original_id),
+            body: branch(if_expr),
+            or_else: branch(else_expr),
+        }
+        .into_node();
+    }
+
+    unreachable!()
+}
+
 /// Turns a boolean expression into a set of statements resembling an if/else block with equal
 /// functionality. Expects the type and variable result name to be provided as parameters.
 pub fn boolean_expr_to_if(
@@ -506,7 +774,7 @@ pub fn boolean_expr_to_if(
 
                 let mut stmts = vec![FuncStmt::VarDecl {
                     target: VarDeclTarget::Name(result_name.into()).into_node(),
-                    typ: names::fixed_size_type_desc(&expr_type).into_node(),
+                    typ: Some(names::fixed_size_type_desc(&expr_type).into_node()),
                     value: Some(Expr::Bool(false).into_node()),
                 }
                 .into_node()];
@@ -534,7 +802,7 @@ pub fn boolean_expr_to_if(
                 //     res = right
                 let mut stmts = vec![FuncStmt::VarDecl {
                     target: VarDeclTarget::Name(result_name.into()).into_node(),
-                    typ: names::fixed_size_type_desc(&expr_type).into_node(),
+                    typ: Some(names::fixed_size_type_desc(&expr_type).into_node()),
                     value: Some(Expr::Bool(true).into_node()),
                 }
                 .into_node()];
@@ -566,44 +834,19 @@ pub fn boolean_expr_to_if(
     unreachable!()
 }
 
-/// Returns a vector of expressions with all ternary expressions that are
-/// contained within the given function statement. The last expression
-/// in the list is the outermost ternary expression found in the statement.
-pub fn get_all_ternary_expressions(node: &Node<FuncStmt>) -> Vec<Node<Expr>> {
-    let mut terns = vec![];
-    map_ast_node(node.clone().into(), &mut |exp| {
-        if let StmtOrExpr::Expr(expr) = &exp {
-            if let Expr::Ternary { .. } = expr.kind {
-                terns.push(expr.clone())
-            }
-        }
-
-        exp
-    });
-
-    terns
-}
-
-/// For a given set of nodes returns the first set of ternary expressions that can be found.
-/// The last expression in the list is the outermost ternary expression found in the statement.
-pub fn get_first_ternary_expressions(nodes: &[Node<FuncStmt>]) -> Vec<Node<Expr>> {
-    for node in nodes {
-        let result = get_all_ternary_expressions(node);
-        if !result.is_empty() {
-            return result;
-        }
-    }
-    vec![]
-}
-
-/// Returns a vector of expressions with all boolean expressions that are
-/// contained within the given function statement. The last expression
-/// in the list is the outermost boolean expression found in the statement.
-pub fn get_all_boolean_expressions(node: &Node<FuncStmt>) -> Vec<Node<Expr>> {
+/// Returns a vector of the ternary expressions, boolean operations and chained
+/// comparisons that are contained within the given function statement. These
+/// are lowered to if/else blocks, since some of their operands might not be
+/// evaluated. The last expression in the list is the outermost one found in
+/// the statement.
+pub fn get_all_conditional_expressions(node: &Node<FuncStmt>) -> Vec<Node<Expr>> {
     let mut expressions = vec![];
     map_ast_node(node.clone().into(), &mut |exp| {
         if let StmtOrExpr::Expr(expr) = &exp {
-            if let Expr::BoolOperation { .. } = expr.kind {
+            if let Expr::Ternary { .. }
+            | Expr::BoolOperation { .. }
+            | Expr::ChainedComparison { .. } = expr.kind
+            {
                 expressions.push(expr.clone())
             }
         }
@@ -614,11 +857,11 @@ pub fn get_all_boolean_expressions(node: &Node<FuncStmt>) -> Vec<Node<Expr>> {
     expressions
 }
 
-/// For a given set of nodes returns the first set of boolean expressions that can be found.
-/// The last expression in the list is the outermost boolean expression found in the statement.
-pub fn get_first_boolean_expressions(nodes: &[Node<FuncStmt>]) -> Vec<Node<Expr>> {
+/// For a given set of nodes returns the first set of conditional expressions that can be found.
+/// The last expression in the list is the outermost expression found in the statement.
+pub fn get_first_conditional_expressions(nodes: &[Node<FuncStmt>]) -> Vec<Node<Expr>> {
     for node in nodes {
-        let result = get_all_boolean_expressions(node);
+        let result = get_all_conditional_expressions(node);
         if !result.is_empty() {
             return result;
         }
@@ -649,7 +892,7 @@ pub fn replace_node_with_name_expression(
 #[cfg(test)]
 mod tests {
 
-    use crate::ast_utils::get_first_ternary_expressions;
+    use crate::ast_utils::get_first_conditional_expressions;
     use crate::ast_utils::inject_before_expression;
     use crate::ast_utils::map_ast_node;
     use crate::ast_utils::replace_node_with_name_expression;
@@ -864,6 +1107,7 @@ else:
             .into_node()]
             .into_node(),
             generic_args: None,
+            base: None,
         }
         .into_node();
 
@@ -876,7 +1120,7 @@ else:
             "foo(1 if true else 1 if true else 2)"
         );
 
-        let all_ternary = get_first_ternary_expressions(&original_body);
+        let all_ternary = get_first_conditional_expressions(&original_body);
 
         assert_eq!(all_ternary.len(), 2);
 
@@ -930,7 +1174,7 @@ else:
 foo(outer)"
         );
 
-        let remaining_terns = get_first_ternary_expressions(&new_body);
+        let remaining_terns = get_first_conditional_expressions(&new_body);
         let last_ternary = remaining_terns.last().unwrap();
         let transformed_inner = ternary_to_if(ternary_type, last_ternary, "inner");
 
@@ -1,7 +1,8 @@
 use fe_analyzer::context::{ExpressionAttributes, FunctionBody};
-use fe_analyzer::namespace::items::{FunctionId, ModuleId};
-use fe_analyzer::namespace::types::{Array, FixedSize, Tuple};
+use fe_analyzer::namespace::items::{FunctionId, ModuleId, StructId};
+use fe_analyzer::namespace::types::{Array, FixedSize, Struct, Tuple};
 use fe_analyzer::AnalyzerDb;
+use fe_parser::ast::SmolStr;
 use fe_parser::node::NodeId;
 use indexmap::IndexSet;
 use std::rc::Rc;
@@ -10,11 +11,22 @@ pub struct ModuleContext<'db> {
     pub db: &'db dyn AnalyzerDb,
     pub module: ModuleId,
 
-    /// List expressions that are used in the module
-    pub list_expressions: IndexSet<Array>,
+    /// List expressions that are used in the module, as the array type and,
+    /// for a list with a fill value, the number of elements that are given
+    pub list_expressions: IndexSet<(Array, Option<usize>)>,
 
     /// Tuples that are used in the module
     pub tuples: IndexSet<Tuple>,
+
+    /// Instances of generic structs that are used in the module
+    pub struct_instances: IndexSet<StructId>,
+
+    /// Instances of generic functions that are called in the module
+    pub function_instances: IndexSet<FunctionId>,
+
+    /// Struct update expressions that are used in the module, as the struct
+    /// and the names of the given fields
+    pub struct_updates: IndexSet<(Struct, Vec<SmolStr>)>,
 }
 
 impl<'db> ModuleContext<'db> {
@@ -24,6 +36,9 @@ impl<'db> ModuleContext<'db> {
             module,
             list_expressions: IndexSet::new(),
             tuples: IndexSet::new(),
+            struct_instances: IndexSet::new(),
+            function_instances: IndexSet::new(),
+            struct_updates: IndexSet::new(),
         }
     }
 }
@@ -35,6 +50,14 @@ pub struct FnContext<'a, 'db> {
 
     /// Holds fresh id for [`FnContext::make_unique_name`]
     fresh_id: u64,
+
+    /// The result variables of the loops enclosing the statement that is being
+    /// lowered, innermost last. `None` for loops that don't produce a value.
+    pub loop_results: Vec<Option<SmolStr>>,
+
+    /// The paths of the storage values that are read into variables before
+    /// the enclosing loops, and the names of the variables.
+    pub hoisted_reads: Vec<(Vec<SmolStr>, SmolStr)>,
 }
 
 impl<'a, 'db> FnContext<'a, 'db> {
@@ -44,6 +67,8 @@ impl<'a, 'db> FnContext<'a, 'db> {
             body,
             id,
             fresh_id: 0,
+            loop_results: vec![],
+            hoisted_reads: vec![],
         }
     }
 
@@ -69,9 +94,23 @@ impl<'a, 'db> FnContext<'a, 'db> {
         self.body.var_decl_types.get(&node_id.into())
     }
 
+    /// The name of the variable that holds the storage value at `path`, if
+    /// it's read before an enclosing loop.
+    pub fn hoisted_read(&self, path: &[SmolStr]) -> Option<&SmolStr> {
+        self.hoisted_reads
+            .iter()
+            .find(|(hoisted, _)| hoisted == path)
+            .map(|(_, name)| name)
+    }
+
     pub fn const_decl_type<T: Into<NodeId>>(&self, node_id: T) -> Option<&FixedSize> {
         self.body.var_decl_types.get(&node_id.into())
     }
+
+    /// The error caught by a `catch` clause, by the id of its type node.
+    pub fn catch_type<T: Into<NodeId>>(&self, node_id: T) -> Option<&FixedSize> {
+        self.body.catch_types.get(&node_id.into())
+    }
 }
 
 impl<'a, 'db> AsMut<ModuleContext<'db>> for FnContext<'a, 'db> {
@@ -1,7 +1,9 @@
+use fe_analyzer::builtins::EvmVersion;
 use fe_analyzer::namespace::items::ModuleId;
 use fe_analyzer::AnalyzerDb;
 use fe_common::db::{SourceDb, SourceDbStorage, Upcast, UpcastMut};
 use fe_parser::ast;
+use indexmap::IndexMap;
 use std::rc::Rc;
 
 mod queries;
@@ -13,12 +15,22 @@ pub trait LoweringDb: AnalyzerDb + Upcast<dyn AnalyzerDb> {
 }
 
 #[salsa::database(SourceDbStorage, fe_analyzer::db::AnalyzerDbStorage, LoweringDbStorage)]
-#[derive(Default)]
 pub struct TestDb {
     storage: salsa::Storage<TestDb>,
 }
 impl salsa::Database for TestDb {}
 
+impl Default for TestDb {
+    fn default() -> Self {
+        let mut db = TestDb {
+            storage: Default::default(),
+        };
+        db.set_constant_defines(Rc::new(IndexMap::new()));
+        db.set_evm_version(EvmVersion::default());
+        db
+    }
+}
+
 impl Upcast<dyn AnalyzerDb> for TestDb {
     fn upcast(&self) -> &(dyn AnalyzerDb + 'static) {
         &*self
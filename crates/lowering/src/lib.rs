@@ -5,7 +5,9 @@ use fe_analyzer::namespace::items::{Ingot, IngotId, Module, ModuleId, ModuleSour
 mod ast_utils;
 mod context;
 pub mod db;
+mod loop_invariants;
 mod mappers;
+pub mod mocks;
 mod names;
 mod utils;
 
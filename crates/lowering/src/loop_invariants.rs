@@ -0,0 +1,303 @@
+//! Finds the storage values that a loop reads but doesn't write, so that they
+//! can be read once before the loop instead of on every iteration.
+
+use fe_analyzer::builtins::ValueMethod;
+use fe_analyzer::context::{CallType, FunctionBody, Location};
+use fe_analyzer::namespace::types::{Base, Type};
+use fe_parser::ast::{Expr, FuncStmt, SmolStr};
+use fe_parser::node::Node;
+
+/// A read of a storage value of a base type, e.g. `self.config.fee`.
+pub struct StorageRead {
+    /// The fields on the path to the value, starting at `self`.
+    pub path: Vec<SmolStr>,
+    pub typ: Base,
+}
+
+/// Returns the storage values that are read by a loop with the given test and
+/// body, but aren't written in it, in the order of their first read.
+///
+/// Nothing is returned if the loop calls a function that might access storage
+/// (directly, or by calling back into the contract) or contains a `yul` block.
+pub fn invariant_storage_reads(
+    body: &FunctionBody,
+    test: Option<&Node<Expr>>,
+    stmts: &[Node<FuncStmt>],
+) -> Vec<StorageRead> {
+    let mut scanner = LoopScanner {
+        body,
+        reads: vec![],
+        writes: vec![],
+        is_opaque: false,
+    };
+    if let Some(test) = test {
+        scanner.expr(test);
+    }
+    scanner.stmts(stmts);
+
+    if scanner.is_opaque {
+        return vec![];
+    }
+    let writes = scanner.writes;
+    scanner
+        .reads
+        .into_iter()
+        .filter(|read| {
+            !writes
+                .iter()
+                .any(|write| read.path.starts_with(write) || write.starts_with(&read.path))
+        })
+        .collect()
+}
+
+/// The fields on the path of an expression like `self.config.fee`. Paths that
+/// go through a subscript aren't returned.
+pub fn self_path(expr: &Node<Expr>) -> Option<Vec<SmolStr>> {
+    match &expr.kind {
+        Expr::Name(name) if name == "self" => Some(vec![]),
+        Expr::Attribute { value, attr } => {
+            let mut path = self_path(value)?;
+            path.push(attr.kind.clone());
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+/// The fields on the path of the place that's written by assigning to
+/// `target`, up to the first subscript. Writing to `self.balances[owner]` may
+/// change any value in `self.balances`.
+fn written_path(target: &Node<Expr>) -> Option<Vec<SmolStr>> {
+    match &target.kind {
+        Expr::Attribute { value, .. } | Expr::Subscript { value, .. }
+            if self_path(target).is_none() =>
+        {
+            written_path(value)
+        }
+        _ => self_path(target),
+    }
+}
+
+struct LoopScanner<'a> {
+    body: &'a FunctionBody,
+    reads: Vec<StorageRead>,
+    /// The paths of the storage places that are written in the loop.
+    writes: Vec<Vec<SmolStr>>,
+    /// `true` if the loop might access storage in a way that isn't tracked.
+    is_opaque: bool,
+}
+
+impl LoopScanner<'_> {
+    fn stmts(&mut self, stmts: &[Node<FuncStmt>]) {
+        for stmt in stmts {
+            self.stmt(stmt)
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Node<FuncStmt>) {
+        match &stmt.kind {
+            FuncStmt::Assign { target, value } | FuncStmt::AugAssign { target, value, .. } => {
+                self.target(target);
+                self.expr(value);
+            }
+            FuncStmt::VarDecl { value, .. } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+            }
+            FuncStmt::ConstantDecl { value, .. } | FuncStmt::Expr { value } => self.expr(value),
+            FuncStmt::Return { value } | FuncStmt::Break { value } => {
+                if let Some(value) = value {
+                    self.expr(value)
+                }
+            }
+            FuncStmt::Revert { error } => {
+                if let Some(error) = error {
+                    self.expr(error)
+                }
+            }
+            FuncStmt::Assert { test, msg } => {
+                self.expr(test);
+                if let Some(msg) = msg {
+                    self.expr(msg)
+                }
+            }
+            FuncStmt::Emit { args, .. } => {
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+            }
+            FuncStmt::For { iter, body, .. } => {
+                self.expr(iter);
+                self.stmts(body);
+            }
+            FuncStmt::While { test, body } => {
+                self.expr(test);
+                self.stmts(body);
+            }
+            FuncStmt::Loop { body, .. }
+            | FuncStmt::Unsafe(body)
+            | FuncStmt::Unchecked(body)
+            | FuncStmt::Checked(body) => self.stmts(body),
+            FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                self.expr(test);
+                self.stmts(body);
+                self.stmts(or_else);
+            }
+            FuncStmt::Match { value, arms } => {
+                self.expr(value);
+                for arm in arms {
+                    self.stmts(&arm.kind.body);
+                }
+            }
+            FuncStmt::Try {
+                call,
+                body,
+                catches,
+                ..
+            } => {
+                self.expr(call);
+                self.stmts(body);
+                for clause in catches {
+                    self.stmts(&clause.kind.body);
+                }
+            }
+            // Yul code might read or write anything.
+            FuncStmt::Yul { .. } => self.is_opaque = true,
+            FuncStmt::Pass | FuncStmt::Continue => {}
+        }
+    }
+
+    /// Records the place that's written by an assignment to `target`.
+    fn target(&mut self, target: &Node<Expr>) {
+        match &target.kind {
+            Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.target(elt)
+                }
+            }
+            _ => {
+                if let Some(path) = written_path(target) {
+                    self.writes.push(path)
+                }
+                self.indices(target)
+            }
+        }
+    }
+
+    /// Visits the indices in a path, e.g. `owner` in `self.balances[owner]`.
+    fn indices(&mut self, expr: &Node<Expr>) {
+        match &expr.kind {
+            Expr::Attribute { value, .. } => self.indices(value),
+            Expr::Subscript { value, index } => {
+                self.indices(value);
+                self.expr(index);
+            }
+            _ => {}
+        }
+    }
+
+    fn expr(&mut self, expr: &Node<Expr>) {
+        if let (Some(path), Some(attributes)) =
+            (self_path(expr), self.body.expressions.get(&expr.id))
+        {
+            if let (Type::Base(typ), Location::Storage { .. }) =
+                (&attributes.typ, &attributes.location)
+            {
+                if *typ != Base::Unit && !self.reads.iter().any(|read| read.path == path) {
+                    self.reads.push(StorageRead { path, typ: *typ })
+                }
+                return;
+            }
+        }
+
+        match &expr.kind {
+            Expr::Call {
+                func, args, base, ..
+            } => {
+                match self.body.calls.get(&func.id) {
+                    Some(CallType::BuiltinValueMethod {
+                        method: ValueMethod::Push | ValueMethod::Pop,
+                        ..
+                    }) => {
+                        if let Expr::Attribute { value, .. } = &func.kind {
+                            self.target(value)
+                        }
+                    }
+                    Some(
+                        CallType::BuiltinFunction(_)
+                        | CallType::BuiltinAbiFunction { .. }
+                        | CallType::BuiltinValueMethod { .. }
+                        | CallType::BuiltinIntegerConversion { .. }
+                        | CallType::TypeConstructor(_)
+                        | CallType::EnumConstructor(_),
+                    ) => {}
+                    _ => self.is_opaque = true,
+                }
+                match &func.kind {
+                    // The method's receiver is read, e.g. `self.items` in
+                    // `self.items.to_mem()`.
+                    Expr::Attribute { value, .. } => self.expr(value),
+                    _ => self.expr(func),
+                }
+                for arg in &args.kind {
+                    self.expr(&arg.kind.value)
+                }
+                if let Some(base) = base {
+                    self.expr(base)
+                }
+            }
+            Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                for expr in [if_expr, test, else_expr] {
+                    self.expr(expr)
+                }
+            }
+            Expr::BoolOperation { left, right, .. }
+            | Expr::BinOperation { left, right, .. }
+            | Expr::CompOperation { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::ChainedComparison { left, comparisons } => {
+                self.expr(left);
+                for (_, operand) in comparisons {
+                    self.expr(operand)
+                }
+            }
+            Expr::UnaryOperation { operand: value, .. } | Expr::Attribute { value, .. } => {
+                self.expr(value)
+            }
+            Expr::Subscript { value, index } => {
+                self.expr(value);
+                self.expr(index);
+            }
+            Expr::List { elts, fill } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+                if let Some(fill) = fill {
+                    self.expr(fill)
+                }
+            }
+            Expr::Tuple { elts } => {
+                for elt in elts {
+                    self.expr(elt)
+                }
+            }
+            Expr::Bool(_)
+            | Expr::Name(_)
+            | Expr::Path(_)
+            | Expr::Num(_)
+            | Expr::Str(_)
+            | Expr::Unit => {}
+        }
+    }
+}
@@ -31,19 +31,51 @@ pub fn contract_def(context: &mut ModuleContext, contract: ContractId) -> Node<a
         )));
     }
 
-    if let Some(call_fn) = contract.call_function(db) {
+    let special_fns = [
+        contract.call_function(db),
+        contract.fallback_function(db),
+        contract.receive_function(db),
+    ];
+    for special_fn in special_fns.into_iter().flatten() {
         functions.push(ast::ContractStmt::Function(functions::func_def(
-            context, call_fn,
+            context, special_fn,
         )));
     }
 
     let node = &contract.data(context.db).ast;
     Node::new(
         ast::Contract {
+            attributes: node.kind.attributes.clone(),
             name: node.kind.name.clone(),
+            parents: vec![],
             fields,
             body: [events, functions].concat(),
             pub_qual: None,
+            doc: None,
+        },
+        node.span,
+    )
+}
+
+/// Lowers an interface definition. Only the function signatures are lowered.
+pub fn interface_def(context: &mut ModuleContext, interface: ContractId) -> Node<ast::Interface> {
+    let functions = interface
+        .functions(context.db)
+        .values()
+        .map(|function| {
+            let mut node = functions::func_def(context, *function);
+            node.kind.body = vec![];
+            node
+        })
+        .collect();
+
+    let node = &interface.data(context.db).ast;
+    Node::new(
+        ast::Interface {
+            name: node.kind.name.clone(),
+            functions,
+            pub_qual: None,
+            doc: None,
         },
         node.span,
     )
@@ -59,6 +91,7 @@ fn contract_field(context: &mut ModuleContext, field: ContractFieldId) -> Node<a
             name: node.kind.name.clone(),
             typ: types::type_desc(context, node.kind.typ.clone(), &typ),
             value: node.kind.value.clone(),
+            doc: None,
         },
         node.span,
     )
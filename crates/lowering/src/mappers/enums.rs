@@ -0,0 +1,42 @@
+use crate::context::ModuleContext;
+use crate::names;
+use crate::utils::ZeroSpanNode;
+use fe_analyzer::namespace::items::EnumId;
+use fe_parser::ast;
+use fe_parser::node::Node;
+
+/// Lowers an enum to a struct holding the tag of the active variant and the
+/// fields of every variant.
+pub fn enum_def(context: &mut ModuleContext, enum_: EnumId) -> Node<ast::Struct> {
+    let db = context.db;
+
+    let fields = enum_
+        .repr_fields(db)
+        .into_iter()
+        .map(|(name, typ)| {
+            ast::Field {
+                is_pub: true,
+                is_const: false,
+                name: name.into_node(),
+                typ: names::fixed_size_type_desc(&typ).into_node(),
+                value: None,
+                doc: None,
+            }
+            .into_node()
+        })
+        .collect();
+
+    let node = &enum_.data(db).ast;
+    Node::new(
+        ast::Struct {
+            attributes: vec![],
+            name: node.kind.name.clone(),
+            generic_params: vec![],
+            fields,
+            functions: vec![],
+            pub_qual: None,
+            doc: None,
+        },
+        node.span,
+    )
+}
@@ -34,9 +34,11 @@ pub fn event_def(context: &mut ModuleContext, event: EventId) -> Node<ast::Event
     let node = &event.data(context.db).ast;
     Node::new(
         ast::Event {
+            attributes: node.kind.attributes.clone(),
             name: node.kind.name.clone(),
             fields,
             pub_qual: None,
+            doc: None,
         },
         node.span,
     )
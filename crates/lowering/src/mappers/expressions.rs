@@ -1,9 +1,16 @@
 use crate::context::FnContext;
-use crate::names::{list_expr_generator_fn_name, tuple_struct_name};
+use crate::loop_invariants::self_path;
+use crate::mappers::types::concrete_type_desc;
+use crate::names::{
+    generic_instance_name, list_expr_generator_fn_name, list_fill_expr_generator_fn_name,
+    overload_name, struct_name, struct_update_fn_name, tuple_struct_name,
+};
 use crate::utils::ZeroSpanNode;
-use fe_analyzer::context::Constant;
-use fe_analyzer::namespace::items::Item;
-use fe_analyzer::namespace::types::{Type, TypeDowncast};
+use fe_analyzer::builtins::{bin_operator_method, comp_operator_method, GlobalFunction};
+use fe_analyzer::context::{CallType, Constant};
+use fe_analyzer::namespace::items::{EnumVariantId, FunctionId, Item};
+use fe_analyzer::namespace::types::{Base, FixedSize, Type, TypeDowncast};
+use fe_common::Span;
 use fe_parser::ast::{self as fe};
 use fe_parser::node::Node;
 
@@ -20,22 +27,19 @@ pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
         .expression_attributes(exp.id)
         .and_then(|attr| attr.const_value.as_ref())
     {
-        let literal = match const_value {
-            Constant::Int(val) => fe::Expr::Num(val.to_string().into()),
-            Constant::Bool(val) => fe::Expr::Bool(*val),
-            Constant::Str(val) => fe::Expr::Str(val.clone()),
-        };
+        return Node::new(constant_literal(const_value), exp.span);
+    }
 
-        return Node::new(literal, exp.span);
+    // Storage values that are read before the enclosing loop are replaced by
+    // the variables they're read into.
+    if let Some(name) = self_path(&exp).and_then(|path| context.hoisted_read(&path)) {
+        return Node::with_original_id(fe::Expr::Name(name.clone()), span, exp.original_id);
     }
 
     let lowered_kind = match exp.kind {
         fe::Expr::Name(_) => expr_name(context, exp),
-        fe::Expr::Path(_)
-        | fe::Expr::Num(_)
-        | fe::Expr::Bool(_)
-        | fe::Expr::Str(_)
-        | fe::Expr::Unit => exp.kind,
+        fe::Expr::Path(_) => expr_path(context, exp),
+        fe::Expr::Num(_) | fe::Expr::Bool(_) | fe::Expr::Str(_) | fe::Expr::Unit => exp.kind,
         fe::Expr::Subscript { value, index } => fe::Expr::Subscript {
             value: boxed_expr(context, value),
             index: boxed_expr(context, index),
@@ -58,6 +62,15 @@ pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
             op,
             right: boxed_expr(context, right),
         },
+        fe::Expr::BinOperation { left, op, right } if is_struct(context, &left) => {
+            operator_method_call(
+                context,
+                *left,
+                bin_operator_method(op.kind),
+                op.span,
+                *right,
+            )
+        }
         fe::Expr::BinOperation { left, op, right } => fe::Expr::BinOperation {
             left: boxed_expr(context, left),
             op,
@@ -67,20 +80,141 @@ pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
             op,
             operand: boxed_expr(context, operand),
         },
+        fe::Expr::CompOperation { left, op, right } if is_struct(context, &left) => {
+            operator_method_call(
+                context,
+                *left,
+                comp_operator_method(op.kind),
+                op.span,
+                *right,
+            )
+        }
         fe::Expr::CompOperation { left, op, right } => fe::Expr::CompOperation {
             left: boxed_expr(context, left),
             op,
             right: boxed_expr(context, right),
         },
+        fe::Expr::ChainedComparison { left, comparisons } => fe::Expr::ChainedComparison {
+            left: boxed_expr(context, left),
+            comparisons: comparisons
+                .into_iter()
+                .map(|(op, operand)| (op, expr(context, operand)))
+                .collect(),
+        },
         fe::Expr::Call {
             func,
-            generic_args,
             args,
-        } => fe::Expr::Call {
-            func: boxed_expr(context, func),
+            base: Some(base),
+            ..
+        } => struct_update(context, func, args, base),
+        fe::Expr::Call {
+            func,
             generic_args,
-            args: call_args(context, args),
-        },
+            args,
+            base: None,
+        } => {
+            let args = match context
+                .body
+                .calls
+                .get(&func.id)
+                .and_then(CallType::function)
+            {
+                Some(function) => args_in_param_order(context, function, args),
+                None => args,
+            };
+            match context.body.calls.get(&func.id) {
+                Some(CallType::EnumConstructor(variant)) => {
+                    let variant = *variant;
+                    let field_values = args
+                        .kind
+                        .into_iter()
+                        .map(|arg| expr(context, arg.kind.value))
+                        .collect();
+                    enum_variant_value(context, &func, variant, field_values)
+                }
+                Some(CallType::Pure(function))
+                    if !function.generic_args(context.db()).is_empty() =>
+                {
+                    let function = *function;
+                    context.module.function_instances.insert(function);
+                    let name = generic_instance_name(
+                        &function.name(context.db()),
+                        &function.generic_args(context.db()),
+                    );
+                    fe::Expr::Call {
+                        func: Box::new(Node::new(fe::Expr::Name(name), func.span)),
+                        generic_args: None,
+                        args: call_args(context, args),
+                        base: None,
+                    }
+                }
+                Some(CallType::Pure(function)) if function.overloads(context.db()).is_some() => {
+                    let function = *function;
+                    let name = overload_name(
+                        &function.name(context.db()),
+                        &function.signature(context.db()).param_types(),
+                    );
+                    // A function in another module is called by its path.
+                    let func_kind = match func.kind {
+                        fe::Expr::Path(mut path) => {
+                            let last = path.segments.last_mut().expect("empty path");
+                            last.kind = name;
+                            fe::Expr::Path(path)
+                        }
+                        _ => fe::Expr::Name(name),
+                    };
+                    fe::Expr::Call {
+                        func: Box::new(Node::new(func_kind, func.span)),
+                        generic_args: None,
+                        args: call_args(context, args),
+                        base: None,
+                    }
+                }
+                Some(CallType::TypeConstructor(typ))
+                    if is_generic_type_constructor(context, typ, &func.kind) =>
+                {
+                    let typ = FixedSize::try_from(typ.clone()).expect("expected fixed size type");
+                    let (name, generic_args) = match concrete_type_desc(context.module, &typ) {
+                        fe::TypeDesc::Base { base } => (base, None),
+                        fe::TypeDesc::Generic { base, args } => (base.kind, Some(args)),
+                        _ => unreachable!("type constructor of a type without a name"),
+                    };
+                    fe::Expr::Call {
+                        func: Box::new(Node::new(fe::Expr::Name(name), func.span)),
+                        generic_args,
+                        args: call_args(context, args),
+                        base: None,
+                    }
+                }
+                // The type of the decoded value can be a tuple, which is
+                // replaced by its struct.
+                Some(CallType::BuiltinAbiFunction {
+                    function: GlobalFunction::AbiDecode,
+                    typ,
+                }) => {
+                    let typ = typ.clone();
+                    let generic_args = generic_args.map(|args| {
+                        let type_desc = concrete_type_desc(context.module, &typ);
+                        Node::new(
+                            vec![fe::GenericArg::TypeDesc(Node::new(type_desc, args.span))],
+                            args.span,
+                        )
+                    });
+                    fe::Expr::Call {
+                        func: boxed_expr(context, func),
+                        generic_args,
+                        args: call_args(context, args),
+                        base: None,
+                    }
+                }
+                _ => fe::Expr::Call {
+                    func: boxed_expr(context, func),
+                    generic_args,
+                    args: call_args(context, args),
+                    base: None,
+                },
+            }
+        }
         fe::Expr::List { .. } => expr_list(context, exp),
         fe::Expr::Tuple { .. } => expr_tuple(context, exp),
     };
@@ -88,6 +222,82 @@ pub fn expr(context: &mut FnContext, exp: Node<fe::Expr>) -> Node<fe::Expr> {
     Node::with_original_id(lowered_kind, span, original_exp.original_id)
 }
 
+/// Lowers a struct update expression like `Point(x: 1, ..p)` to a call of a
+/// generated function that builds the struct from the given fields and the
+/// base struct. This way the base is evaluated only once, after the fields.
+#[allow(clippy::boxed_local)]
+fn struct_update(
+    context: &mut FnContext,
+    func: Box<Node<fe::Expr>>,
+    args: Node<Vec<Node<fe::CallArg>>>,
+    base: Box<Node<fe::Expr>>,
+) -> fe::Expr {
+    let struct_ = match context.body.calls.get(&func.id) {
+        Some(CallType::TypeConstructor(Type::Struct(struct_))) => struct_.clone(),
+        _ => unreachable!("struct update of a type that isn't a struct"),
+    };
+    let fields = args
+        .kind
+        .iter()
+        .map(|arg| match (&arg.kind.label, &arg.kind.value.kind) {
+            (Some(label), _) => label.kind.clone(),
+            (None, fe::Expr::Name(name)) => name.clone(),
+            _ => unreachable!("unlabeled field in struct update"),
+        })
+        .collect::<Vec<_>>();
+    let fn_name = struct_update_fn_name(&struct_, &fields);
+    // The constructor of a struct with private fields can only be called by
+    // the struct's own functions, so the generated function is added to it.
+    let update_fn = if struct_.id.has_private_field(context.db()) {
+        fe::Expr::Attribute {
+            value: Box::new(Node::new(fe::Expr::Name(struct_name(&struct_)), func.span)),
+            attr: Node::new(fn_name, func.span),
+        }
+    } else {
+        fe::Expr::Name(fn_name)
+    };
+    context.module.struct_updates.insert((struct_, fields));
+
+    let mut lowered_args = args
+        .kind
+        .into_iter()
+        .map(|arg| {
+            Node::new(
+                fe::CallArg {
+                    label: None,
+                    value: expr(context, arg.kind.value),
+                },
+                arg.span,
+            )
+        })
+        .collect::<Vec<_>>();
+    let base_span = base.span;
+    lowered_args.push(Node::new(
+        fe::CallArg {
+            label: None,
+            value: expr(context, *base),
+        },
+        base_span,
+    ));
+
+    fe::Expr::Call {
+        func: Box::new(Node::new(update_fn, func.span)),
+        generic_args: None,
+        args: Node::new(lowered_args, args.span),
+        base: None,
+    }
+}
+
+/// Returns `true` if the type constructor call constructs an instance of a
+/// generic struct, or if it's called via the name of a generic parameter.
+fn is_generic_type_constructor(context: &FnContext, typ: &Type, func: &fe::Expr) -> bool {
+    match (typ, func) {
+        (Type::Struct(struct_), _) if !struct_.generic_args.is_empty() => true,
+        (_, fe::Expr::Name(name)) => context.id.type_params(context.db()).contains_key(name),
+        _ => false,
+    }
+}
+
 /// Lowers and optional expression.
 pub fn optional_expr(
     context: &mut FnContext,
@@ -102,6 +312,50 @@ pub fn boxed_expr(context: &mut FnContext, exp: Box<Node<fe::Expr>>) -> Box<Node
     Box::new(expr(context, *exp))
 }
 
+/// Sorts the arguments of a function call into the order of the function's
+/// parameters, as labeled arguments can be given in any order. The default
+/// values of the parameters that the call leaves out are filled in.
+fn args_in_param_order(
+    context: &FnContext,
+    function: FunctionId,
+    args: Node<Vec<Node<fe::CallArg>>>,
+) -> Node<Vec<Node<fe::CallArg>>> {
+    let sig = function.signature(context.db());
+    let mut indexed_args = args
+        .kind
+        .into_iter()
+        .enumerate()
+        .map(|(position, arg)| {
+            let index = arg
+                .kind
+                .label
+                .as_ref()
+                .and_then(|label| sig.params.iter().position(|param| param.name == label.kind))
+                .unwrap_or(position);
+            (index, arg)
+        })
+        .collect::<Vec<_>>();
+    for (index, param) in sig.params.iter().enumerate() {
+        if let Some(Ok(default)) = &param.default {
+            if !indexed_args
+                .iter()
+                .any(|(arg_index, _)| *arg_index == index)
+            {
+                let arg = fe::CallArg {
+                    label: Some(Node::new(param.name.clone(), args.span)),
+                    value: Node::new(constant_literal(default), args.span),
+                };
+                indexed_args.push((index, Node::new(arg, args.span)));
+            }
+        }
+    }
+    indexed_args.sort_by_key(|(index, _)| *index);
+    Node::new(
+        indexed_args.into_iter().map(|(_, arg)| arg).collect(),
+        args.span,
+    )
+}
+
 /// Lowers call arguments
 pub fn call_args(
     context: &mut FnContext,
@@ -137,12 +391,169 @@ fn expr_name(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
                 val.is_base_type(db),
                 "Should have been rejected at first analyzer pass"
             );
-            val.value(db)
+            // Module constants are replaced by their value, so that references
+            // to other constants and compile time calls don't reach codegen.
+            match val.constant_value(db) {
+                Ok(value) => constant_literal(&value),
+                Err(_) => val.value(db),
+            }
         }
         _ => exp.kind,
     }
 }
 
+fn constant_literal(value: &Constant) -> fe::Expr {
+    match value {
+        // Number literals can't be negative, so negative values are negated
+        // literals.
+        Constant::Int(val) => match val.to_string().strip_prefix('-') {
+            Some(magnitude) => fe::Expr::UnaryOperation {
+                op: fe::UnaryOperator::USub.into_node(),
+                operand: Box::new(fe::Expr::Num(magnitude.into()).into_node()),
+            },
+            None => fe::Expr::Num(val.to_string().into()),
+        },
+        Constant::Bool(val) => fe::Expr::Bool(*val),
+        Constant::Str(val) => fe::Expr::Str(val.clone()),
+    }
+}
+
+/// Returns true if the expression has a struct type. Operations on structs
+/// are lowered to calls of the methods that overload the operators.
+pub fn is_struct(context: &FnContext, exp: &Node<fe::Expr>) -> bool {
+    matches!(
+        context.expression_attributes(exp.id).map(|attr| &attr.typ),
+        Some(Type::Struct(_))
+    )
+}
+
+/// Lowers an operation on a struct to a call of the method that overloads the
+/// operator, e.g. `a + b` to `a.__add__(b)`.
+pub fn operator_method_call(
+    context: &mut FnContext,
+    left: Node<fe::Expr>,
+    method: &str,
+    op_span: Span,
+    right: Node<fe::Expr>,
+) -> fe::Expr {
+    let right = expr(context, right);
+    let right_span = right.span;
+    fe::Expr::Call {
+        func: Box::new(Node::new(
+            fe::Expr::Attribute {
+                value: Box::new(expr(context, left)),
+                attr: Node::new(method.into(), op_span),
+            },
+            op_span,
+        )),
+        generic_args: None,
+        base: None,
+        args: Node::new(
+            vec![Node::new(
+                fe::CallArg {
+                    label: None,
+                    value: right,
+                },
+                right_span,
+            )],
+            right_span,
+        ),
+    }
+}
+
+fn expr_path(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
+    let path = match &exp.kind {
+        fe::Expr::Path(path) => path,
+        _ => unreachable!(),
+    };
+
+    let db = context.db();
+    match context.id.module(db).resolve_path_internal(db, path).value {
+        Some(Item::EnumVariant(variant)) => enum_variant_value(context, &exp, variant, vec![]),
+        _ => exp.kind,
+    }
+}
+
+/// Builds a constructor call of the struct that the variant's enum is lowered
+/// to. `variant_path` is the path used to refer to the variant, and
+/// `field_values` are the (lowered) values of the variant's fields.
+fn enum_variant_value(
+    context: &mut FnContext,
+    variant_path: &Node<fe::Expr>,
+    variant: EnumVariantId,
+    field_values: Vec<Node<fe::Expr>>,
+) -> fe::Expr {
+    let db = context.db();
+    let span = variant_path.span;
+
+    // The enum is referred to by the variant path, minus the variant name.
+    let mut segments = match &variant_path.kind {
+        fe::Expr::Path(path) => path.segments.clone(),
+        _ => unreachable!("enum variants are always referred to by path"),
+    };
+    segments.pop();
+    let func = if segments.len() == 1 {
+        fe::Expr::Name(segments.remove(0).kind)
+    } else {
+        fe::Expr::Path(fe::Path { segments })
+    };
+
+    let enum_id = variant.enum_id(db);
+    let tag = fe::Expr::Num(variant.tag(db).to_string().into()).into_node();
+    let mut values = vec![("tag".into(), tag)];
+    let mut field_values = field_values.into_iter();
+    for other in enum_id.variants(db).values() {
+        for (idx, typ) in other.fields(db).iter().enumerate() {
+            let value = if *other == variant {
+                field_values
+                    .next()
+                    .expect("missing enum variant field value")
+            } else {
+                zero_value(typ.as_ref().expect("enum variant field type error")).into_node()
+            };
+            values.push((other.repr_field_name(db, idx), value));
+        }
+    }
+
+    let args = values
+        .into_iter()
+        .map(|(name, value)| {
+            fe::CallArg {
+                label: Some(Node::new(name, span)),
+                value,
+            }
+            .into_node()
+        })
+        .collect::<Vec<_>>();
+
+    fe::Expr::Call {
+        func: Box::new(Node::new(func, span)),
+        generic_args: None,
+        base: None,
+        args: Node::new(args, span),
+    }
+}
+
+/// The value that the fields of inactive enum variants are set to.
+fn zero_value(typ: &FixedSize) -> fe::Expr {
+    match typ {
+        FixedSize::Base(Base::Bool) => fe::Expr::Bool(false),
+        FixedSize::Base(Base::Address) => fe::Expr::Call {
+            func: fe::Expr::Name("address".into()).into_boxed_node(),
+            generic_args: None,
+            base: None,
+            args: vec![fe::CallArg {
+                label: None,
+                value: fe::Expr::Num("0".into()).into_node(),
+            }
+            .into_node()]
+            .into_node(),
+        },
+        FixedSize::Base(Base::Numeric(_)) => fe::Expr::Num("0".into()),
+        _ => unreachable!("enum variant fields are primitive"),
+    }
+}
+
 fn expr_tuple(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
     let typ = context
         .expression_attributes(&exp)
@@ -182,6 +593,7 @@ fn expr_tuple(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
     fe::Expr::Call {
         func: Box::new(Node::new(fe::Expr::Name(struct_name), exp.span)),
         generic_args: None,
+        base: None,
         args,
     }
 }
@@ -193,12 +605,21 @@ fn expr_list(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
 
     if let Type::Array(array) = &attributes.typ {
         let array = array.clone();
-        let fn_name = list_expr_generator_fn_name(&array);
-        context.module.list_expressions.insert(array);
 
-        if let fe::Expr::List { elts } = exp.kind {
+        if let fe::Expr::List { elts, fill } = exp.kind {
+            let given = elts.len();
+            let fn_name = match fill {
+                Some(_) => list_fill_expr_generator_fn_name(&array, given),
+                None => list_expr_generator_fn_name(&array),
+            };
+            context
+                .module
+                .list_expressions
+                .insert((array, fill.as_ref().map(|_| given)));
+
             let args = elts
                 .into_iter()
+                .chain(fill.map(|fill| *fill))
                 .map(|list_val| {
                     fe::CallArg {
                         label: None,
@@ -213,6 +634,7 @@ fn expr_list(context: &mut FnContext, exp: Node<fe::Expr>) -> fe::Expr {
             return fe::Expr::Call {
                 func: fe::Expr::Name(fn_name).into_boxed_node(),
                 generic_args: None,
+                base: None,
                 args,
             };
         }
@@ -1,14 +1,15 @@
 use crate::ast_utils::{
-    boolean_expr_to_if, get_first_boolean_expressions, get_first_ternary_expressions,
-};
-use crate::ast_utils::{
-    inject_before_expression, replace_node_with_name_expression, ternary_to_if,
+    boolean_expr_to_if, get_first_conditional_expressions, inject_before_expression,
+    replace_node_with_name_expression, ternary_assignments_to_if, ternary_to_if,
 };
 use crate::context::{FnContext, ModuleContext};
+use crate::loop_invariants::{invariant_storage_reads, StorageRead};
 use crate::mappers::expressions;
 use crate::mappers::types;
+use crate::names;
 use crate::utils::ZeroSpanNode;
-use fe_analyzer::namespace::items::FunctionId;
+use fe_analyzer::builtins::bin_operator_method;
+use fe_analyzer::namespace::items::{FunctionId, Item};
 use fe_analyzer::namespace::types::{Base, Type};
 use fe_analyzer::namespace::types::{FixedSize, TypeDowncast};
 use fe_parser::ast::{self as fe, Expr, FuncStmt, RegularFunctionArg, SmolStr};
@@ -18,12 +19,15 @@ use fe_parser::node::Node;
 pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::Function> {
     let node = &function.data(context.db).ast;
     let fe::Function {
+        attributes,
         pub_,
         unsafe_,
         name,
+        generic_params: _,
         args,
         return_type: return_type_node,
         body,
+        doc: _,
     } = &node.kind;
 
     let signature = function.signature(context.db);
@@ -49,20 +53,10 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         lowered_body
     };
 
-    let lowered_body = lower_iteratively(
-        &mut fn_ctx,
-        lowered_body,
-        "ternary_result",
-        &get_first_ternary_expressions,
-        &ternary_to_if,
-    );
-    let lowered_body = lower_iteratively(
-        &mut fn_ctx,
-        lowered_body,
-        "boolean_expr_result",
-        &get_first_boolean_expressions,
-        &boolean_expr_to_if,
-    );
+    // Ternary expressions that are assigned or returned are turned into if/else
+    // blocks directly. The others need a temporary variable for their result.
+    let lowered_body = ternary_assignments_to_if(&lowered_body);
+    let lowered_body = lower_conditional_exprs(&mut fn_ctx, lowered_body);
 
     let param_types = {
         let params = &signature.params;
@@ -70,7 +64,7 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         if matches!(
             args.first(),
             Some(Node {
-                kind: fe::FunctionArg::Zelf,
+                kind: fe::FunctionArg::Zelf { .. },
                 ..
             })
         ) {
@@ -90,8 +84,11 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         .map(|(pnode, ptype)| {
             if let fe::FunctionArg::Regular(regular) = &pnode.kind {
                 fe::FunctionArg::Regular(RegularFunctionArg {
+                    mut_: regular.mut_,
                     name: regular.name.clone(),
                     typ: types::type_desc(fn_ctx.module, regular.typ.clone(), &ptype),
+                    // Calls are lowered with a value for every parameter.
+                    default: None,
                 })
                 .into_node()
             } else {
@@ -107,57 +104,181 @@ pub fn func_def(context: &mut ModuleContext, function: FunctionId) -> Node<fe::F
         .map(|type_desc| types::type_desc(fn_ctx.module, type_desc, &return_type.clone().into()))
         .unwrap_or_else(|| fe::TypeDesc::Unit.into_node());
 
+    let generic_args = function.generic_args(fn_ctx.db());
+    let name = if !generic_args.is_empty() {
+        Node::new(
+            names::generic_instance_name(&name.kind, &generic_args),
+            name.span,
+        )
+    } else if function.overloads(fn_ctx.db()).is_some() {
+        Node::new(
+            names::overload_name(&name.kind, &signature.param_types()),
+            name.span,
+        )
+    } else {
+        name.clone()
+    };
+
     let lowered_function = fe::Function {
+        attributes: attributes.clone(),
         pub_: *pub_,
         unsafe_: *unsafe_,
-        name: name.clone(),
+        name,
+        generic_params: vec![],
         args,
         return_type: Some(lowered_return_type),
         body: lowered_body,
+        doc: None,
     };
 
     Node::new(lowered_function, node.span)
 }
 
-fn lower_iteratively(
+/// Lowers the ternary expressions, boolean operations and chained comparisons
+/// to if/else blocks that assign their result to a variable. The outermost
+/// expression of a statement is lowered first, so that the operands which
+/// might not be evaluated end up in the blocks, e.g. `f()` in
+/// `a and (b if f() else c)`.
+fn lower_conditional_exprs(
     context: &mut FnContext,
     statements: Vec<Node<FuncStmt>>,
-    result_name: &str,
-    getter_fn: &dyn Fn(&[Node<FuncStmt>]) -> Vec<Node<Expr>>,
-    mapper_fn: &dyn Fn(FixedSize, &Node<Expr>, &str) -> Vec<Node<FuncStmt>>,
 ) -> Vec<Node<FuncStmt>> {
     let mut current_statements = statements;
 
-    loop {
-        let expressions = getter_fn(&current_statements);
+    while let Some(current_expression) =
+        get_first_conditional_expressions(&current_statements).pop()
+    {
+        let expr_attr = context
+            .expression_attributes(current_expression.original_id)
+            .expect("missing attributes");
 
-        if let Some(current_expression) = expressions.last() {
-            let expr_attr = context
-                .expression_attributes(current_expression.original_id)
-                .expect("missing attributes");
+        let expression_type = FixedSize::try_from(expr_attr.typ.clone()).expect("Not a fixed size");
 
-            let expression_type =
-                FixedSize::try_from(expr_attr.typ.clone()).expect("Not a fixed size");
+        let (unique_name, generated_statements) = match &current_expression.kind {
+            Expr::Ternary { .. } => {
+                let unique_name = context.make_unique_name("ternary_result");
+                let stmts = ternary_to_if(expression_type, &current_expression, &unique_name);
+                (unique_name, stmts)
+            }
+            Expr::BoolOperation { .. } => {
+                let unique_name = context.make_unique_name("boolean_expr_result");
+                let stmts = boolean_expr_to_if(expression_type, &current_expression, &unique_name);
+                (unique_name, stmts)
+            }
+            Expr::ChainedComparison { .. } => {
+                let unique_name = context.make_unique_name("comparison_result");
+                let stmts = chained_comparison_to_if(context, &current_expression, &unique_name);
+                (unique_name, stmts)
+            }
+            _ => unreachable!(),
+        };
+        current_statements = inject_before_expression(
+            &current_statements,
+            current_expression.original_id,
+            &generated_statements,
+        );
+        current_statements = replace_node_with_name_expression(
+            &current_statements,
+            current_expression.original_id,
+            &unique_name,
+        );
+    }
 
-            let unique_name = context.make_unique_name(result_name);
-            let generated_statements =
-                mapper_fn(expression_type.clone(), current_expression, &unique_name);
-            current_statements = inject_before_expression(
-                &current_statements,
-                current_expression.original_id,
-                &generated_statements,
-            );
-            current_statements = replace_node_with_name_expression(
-                &current_statements,
-                current_expression.original_id,
-                &unique_name,
-            );
+    current_statements
+}
+
+/// Turns a chained comparison like `a < b < c` into nested if blocks that
+/// assign the result to a variable named `result_name`:
+///
+/// ```fe
+/// let result: bool = false
+/// let $comparison_operand_0: u256 = a
+/// let $comparison_operand_1: u256 = b
+/// if $comparison_operand_0 < $comparison_operand_1:
+///     result = $comparison_operand_1 < c
+/// ```
+///
+/// Operands that aren't names or literals are stored in variables, so that
+/// they're evaluated once, and in order.
+fn chained_comparison_to_if(
+    context: &mut FnContext,
+    expr: &Node<Expr>,
+    result_name: &str,
+) -> Vec<Node<FuncStmt>> {
+    let (left, comparisons) = match &expr.kind {
+        Expr::ChainedComparison { left, comparisons } => (left, comparisons),
+        _ => unreachable!(),
+    };
+
+    let mut stmts = vec![FuncStmt::VarDecl {
+        target: fe::VarDeclTarget::Name(result_name.into()).into_node(),
+        typ: Some(names::fixed_size_type_desc(&FixedSize::bool()).into_node()),
+        value: Some(Expr::Bool(false).into_node()),
+    }
+    .into_node()];
+
+    // The comparisons are built from the last one, which assigns the result,
+    // to the first one. Each operand but the last one is evaluated before the
+    // comparison it's the left side of.
+    let mut operands = vec![left.as_ref().clone()];
+    operands.extend(comparisons.iter().map(|(_, operand)| operand.clone()));
+    let mut values = vec![];
+    let mut decls = vec![];
+    for operand in &operands[..operands.len() - 1] {
+        if matches!(operand.kind, Expr::Name(_) | Expr::Num(_) | Expr::Bool(_)) {
+            values.push(operand.clone());
+            decls.push(None);
         } else {
-            break;
+            let name = context.make_unique_name("comparison_operand");
+            let typ = context
+                .expression_attributes(operand.original_id)
+                .expect("missing attributes")
+                .typ
+                .clone();
+            let typ = FixedSize::try_from(typ).expect("Not a fixed size");
+            decls.push(Some(
+                FuncStmt::VarDecl {
+                    target: fe::VarDeclTarget::Name(name.as_str().into()).into_node(),
+                    typ: Some(names::fixed_size_type_desc(&typ).into_node()),
+                    value: Some(operand.clone()),
+                }
+                .into_node(),
+            ));
+            values.push(Expr::Name(name.into()).into_node());
         }
     }
+    values.push(operands[operands.len() - 1].clone());
 
-    current_statements
+    let compare = |idx: usize| {
+        let (op, _) = &comparisons[idx];
+        Expr::CompOperation {
+            left: Box::new(values[idx].clone()),
+            op: op.clone(),
+            right: Box::new(values[idx + 1].clone()),
+        }
+        .into_node()
+    };
+    let last = comparisons.len() - 1;
+    let mut body = vec![FuncStmt::Assign {
+        target: Expr::Name(result_name.into()).into_node(),
+        value: compare(last),
+    }
+    .into_node()];
+    for idx in (0..last).rev() {
+        let mut block = decls[idx + 1].clone().into_iter().collect::<Vec<_>>();
+        block.push(
+            FuncStmt::If {
+                test: compare(idx),
+                body,
+                or_else: vec![],
+            }
+            .into_node(),
+        );
+        body = block;
+    }
+    stmts.extend(decls[0].clone());
+    stmts.extend(body);
+    stmts
 }
 
 fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::FuncStmt>> {
@@ -165,15 +286,17 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
         fe::FuncStmt::Return { value } => stmt_return(context, value),
         fe::FuncStmt::VarDecl { target, typ, value } => {
             let var_type = context
-                .var_decl_type(typ.id)
+                .var_decl_type(typ.as_ref().map_or(target.id, |typ| typ.id))
                 .expect("missing var decl type")
-                .clone()
-                .into();
+                .clone();
+            // The type of an untyped tuple destructuring is inferred by the analyzer.
+            let typ = typ.unwrap_or_else(|| Node::new(source_type_desc(&var_type), target.span));
+            let var_type = var_type.into();
 
             match target.kind {
                 fe::VarDeclTarget::Name(_) => vec![fe::FuncStmt::VarDecl {
                     target,
-                    typ: types::type_desc(context.module, typ, &var_type),
+                    typ: Some(types::type_desc(context.module, typ, &var_type)),
                     value: expressions::optional_expr(context, value),
                 }],
                 fe::VarDeclTarget::Tuple(_) => {
@@ -194,10 +317,13 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
                 value: expressions::expr(context, value),
             }]
         }
-        fe::FuncStmt::Assign { target, value } => vec![fe::FuncStmt::Assign {
-            target: expressions::expr(context, target),
-            value: expressions::expr(context, value),
-        }],
+        fe::FuncStmt::Assign { target, value } => match target.kind {
+            fe::Expr::Tuple { .. } => lower_tuple_assignment(context, target, value, stmt.span),
+            _ => vec![fe::FuncStmt::Assign {
+                target: expressions::expr(context, target),
+                value: expressions::expr(context, value),
+            }],
+        },
         fe::FuncStmt::Emit { name, args } => vec![fe::FuncStmt::Emit {
             name,
             args: expressions::call_args(context, args),
@@ -205,15 +331,44 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
         fe::FuncStmt::AugAssign { target, op, value } => {
             stmt_aug_assign(context, target, op, value)
         }
-        fe::FuncStmt::For { target, iter, body } => vec![fe::FuncStmt::For {
-            target,
-            iter: expressions::expr(context, iter),
-            body: multiple_stmts(context, body),
-        }],
-        fe::FuncStmt::While { test, body } => vec![fe::FuncStmt::While {
-            test: expressions::expr(context, test),
-            body: multiple_stmts(context, body),
-        }],
+        fe::FuncStmt::For { target, iter, body } => {
+            let iter = expressions::expr(context, iter);
+            let reads = invariant_storage_reads(&context.body, None, &body);
+            hoist_storage_reads(context, reads, |context| {
+                vec![fe::FuncStmt::For {
+                    target,
+                    iter,
+                    body: loop_body(context, None, body),
+                }]
+            })
+        }
+        fe::FuncStmt::While { test, body } => {
+            let reads = invariant_storage_reads(&context.body, Some(&test), &body);
+            hoist_storage_reads(context, reads, |context| {
+                vec![fe::FuncStmt::While {
+                    test: expressions::expr(context, test),
+                    body: loop_body(context, None, body),
+                }]
+            })
+        }
+        fe::FuncStmt::Loop { result: None, body } => {
+            let reads = invariant_storage_reads(&context.body, None, &body);
+            hoist_storage_reads(context, reads, |context| {
+                vec![fe::FuncStmt::Loop {
+                    result: None,
+                    body: loop_body(context, None, body),
+                }]
+            })
+        }
+        fe::FuncStmt::Loop {
+            result: Some((name, typ)),
+            body,
+        } => {
+            let reads = invariant_storage_reads(&context.body, None, &body);
+            hoist_storage_reads(context, reads, |context| {
+                stmt_loop_decl(context, name, typ, body)
+            })
+        }
         fe::FuncStmt::If {
             test,
             body,
@@ -223,7 +378,79 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
             body: multiple_stmts(context, body),
             or_else: multiple_stmts(context, or_else),
         }],
+        fe::FuncStmt::Match { value, arms } => stmt_match(context, value, arms),
+        fe::FuncStmt::Try {
+            call,
+            binding,
+            body,
+            catches,
+        } => vec![fe::FuncStmt::Try {
+            call: expressions::expr(context, call),
+            binding,
+            body: multiple_stmts(context, body),
+            catches: catches
+                .into_iter()
+                .map(|clause| {
+                    let error = clause.kind.error.map(|(typ, name)| {
+                        let error_type = context
+                            .catch_type(typ.id)
+                            .expect("missing catch type")
+                            .clone()
+                            .into();
+                        (types::type_desc(context.module, typ, &error_type), name)
+                    });
+                    Node::new(
+                        fe::CatchClause {
+                            error,
+                            body: multiple_stmts(context, clause.kind.body),
+                        },
+                        clause.span,
+                    )
+                })
+                .collect(),
+        }],
         fe::FuncStmt::Unsafe(body) => vec![fe::FuncStmt::Unsafe(multiple_stmts(context, body))],
+        fe::FuncStmt::Unchecked(body) => {
+            vec![fe::FuncStmt::Unchecked(multiple_stmts(context, body))]
+        }
+        fe::FuncStmt::Checked(body) => vec![fe::FuncStmt::Checked(multiple_stmts(context, body))],
+        fe::FuncStmt::Yul {
+            inputs,
+            outputs,
+            code,
+        } => vec![fe::FuncStmt::Yul {
+            inputs: inputs
+                .into_iter()
+                .map(|input| {
+                    Node::new(
+                        fe::YulInput {
+                            name: input.kind.name,
+                            value: expressions::expr(context, input.kind.value),
+                        },
+                        input.span,
+                    )
+                })
+                .collect(),
+            outputs: outputs
+                .into_iter()
+                .map(|output| {
+                    let typ = output.kind.typ;
+                    let output_type = context
+                        .var_decl_type(typ.id)
+                        .expect("missing yul output type")
+                        .clone()
+                        .into();
+                    Node::new(
+                        fe::YulOutput {
+                            name: output.kind.name,
+                            typ: types::type_desc(context.module, typ, &output_type),
+                        },
+                        output.span,
+                    )
+                })
+                .collect(),
+            code,
+        }],
         fe::FuncStmt::Assert { test, msg } => vec![fe::FuncStmt::Assert {
             test: expressions::expr(context, test),
             msg: expressions::optional_expr(context, msg),
@@ -231,7 +458,8 @@ fn func_stmt(context: &mut FnContext, stmt: Node<fe::FuncStmt>) -> Vec<Node<fe::
         fe::FuncStmt::Expr { value } => vec![fe::FuncStmt::Expr {
             value: expressions::expr(context, value),
         }],
-        fe::FuncStmt::Pass | fe::FuncStmt::Break | fe::FuncStmt::Continue => vec![stmt.kind],
+        fe::FuncStmt::Break { value } => stmt_break(context, value),
+        fe::FuncStmt::Pass | fe::FuncStmt::Continue => vec![stmt.kind],
         fe::FuncStmt::Revert { error } => vec![fe::FuncStmt::Revert {
             error: error.map(|expr| expressions::expr(context, expr)),
         }],
@@ -255,6 +483,137 @@ fn multiple_stmts(
         .concat()
 }
 
+/// Lowers the body of a loop. `result` is the variable that the value of the
+/// loop is assigned to, if the loop produces a value.
+fn loop_body(
+    context: &mut FnContext,
+    result: Option<SmolStr>,
+    body: Vec<Node<fe::FuncStmt>>,
+) -> Vec<Node<fe::FuncStmt>> {
+    context.loop_results.push(result);
+    let body = multiple_stmts(context, body);
+    context.loop_results.pop();
+    body
+}
+
+/// Lowers a loop with `lower_loop`, after reading the given storage values
+/// into variables. The variables are used in place of the storage values in
+/// the loop, so that the values are read once instead of on every iteration.
+///
+/// e.g.
+/// ```fe
+/// while i < self.count:
+///     i += self.step
+/// ```
+/// will be lowered to
+/// ```fe
+/// let $count_0: u256 = self.count
+/// let $step_1: u256 = self.step
+/// while i < $count_0:
+///     i = i + $step_1
+/// ```
+fn hoist_storage_reads(
+    context: &mut FnContext,
+    reads: Vec<StorageRead>,
+    lower_loop: impl FnOnce(&mut FnContext) -> Vec<fe::FuncStmt>,
+) -> Vec<fe::FuncStmt> {
+    let outer_reads = context.hoisted_reads.len();
+    let mut stmts = vec![];
+    for read in reads {
+        // Values that are read before an enclosing loop are already hoisted.
+        if context.hoisted_read(&read.path).is_some() {
+            continue;
+        }
+        let name: SmolStr = context.make_unique_name(&read.path.join("_")).into();
+        let value =
+            read.path
+                .iter()
+                .fold(fe::Expr::Name("self".into()).into_node(), |value, field| {
+                    fe::Expr::Attribute {
+                        value: Box::new(value),
+                        attr: field.clone().into_node(),
+                    }
+                    .into_node()
+                });
+        stmts.push(fe::FuncStmt::VarDecl {
+            target: fe::VarDeclTarget::Name(name.clone()).into_node(),
+            typ: Some(names::fixed_size_type_desc(&FixedSize::Base(read.typ)).into_node()),
+            value: Some(value),
+        });
+        context.hoisted_reads.push((read.path, name));
+    }
+    stmts.extend(lower_loop(context));
+    context.hoisted_reads.truncate(outer_reads);
+    stmts
+}
+
+/// Lowers a `loop` that produces a value to a loop that assigns the value to a
+/// temporary variable before it exits.
+///
+/// e.g.
+/// ```fe
+/// let x: u256 = loop:
+///     if i > 10:
+///         break i
+///     i += 1
+/// ```
+/// will be lowered to
+/// ```fe
+/// let $loop_result_0: u256
+/// loop:
+///     if i > 10:
+///         $loop_result_0 = i
+///         break
+///     i = i + 1
+/// let x: u256 = $loop_result_0
+/// ```
+fn stmt_loop_decl(
+    context: &mut FnContext,
+    name: Node<SmolStr>,
+    typ: Node<fe::TypeDesc>,
+    body: Vec<Node<fe::FuncStmt>>,
+) -> Vec<fe::FuncStmt> {
+    let var_type = context
+        .var_decl_type(typ.id)
+        .expect("missing var decl type")
+        .clone();
+
+    let result: SmolStr = context.make_unique_name("loop_result").into();
+    let result_decl = fe::FuncStmt::VarDecl {
+        target: fe::VarDeclTarget::Name(result.clone()).into_node(),
+        typ: Some(names::fixed_size_type_desc(&var_type).into_node()),
+        value: None,
+    };
+    let body = loop_body(context, Some(result.clone()), body);
+    let decl = fe::FuncStmt::VarDecl {
+        target: Node::new(fe::VarDeclTarget::Name(name.kind), name.span),
+        typ: Some(types::type_desc(context.module, typ, &var_type.into())),
+        value: Some(fe::Expr::Name(result).into_node()),
+    };
+
+    vec![result_decl, fe::FuncStmt::Loop { result: None, body }, decl]
+}
+
+/// Lowers a `break` statement. The value of a `break` is assigned to the
+/// result variable of the loop before the loop is exited.
+fn stmt_break(context: &mut FnContext, value: Option<Node<fe::Expr>>) -> Vec<fe::FuncStmt> {
+    let mut stmts = vec![];
+    if let Some(value) = value {
+        let result = context
+            .loop_results
+            .last()
+            .cloned()
+            .flatten()
+            .expect("`break` with a value in a loop that doesn't produce a value");
+        stmts.push(fe::FuncStmt::Assign {
+            target: fe::Expr::Name(result).into_node(),
+            value: expressions::expr(context, value),
+        });
+    }
+    stmts.push(fe::FuncStmt::Break { value: None });
+    stmts
+}
+
 fn stmt_aug_assign(
     context: &mut FnContext,
     target: Node<fe::Expr>,
@@ -262,6 +621,22 @@ fn stmt_aug_assign(
     value: Node<fe::Expr>,
 ) -> Vec<fe::FuncStmt> {
     let original_value_span = value.span;
+    if expressions::is_struct(context, &target) {
+        let lowered_target = expressions::expr(context, target.clone());
+        let new_value = expressions::operator_method_call(
+            context,
+            target,
+            bin_operator_method(op.kind),
+            op.span,
+            value,
+        );
+        // the new statement is: `target = target.__<op>__(value)`.
+        return vec![fe::FuncStmt::Assign {
+            target: lowered_target,
+            value: Node::new(new_value, original_value_span),
+        }];
+    }
+
     let lowered_target = expressions::expr(context, target.clone());
     let lowered_lh_value = expressions::expr(context, target);
     let lowered_rh_value = expressions::expr(context, value);
@@ -295,6 +670,169 @@ fn stmt_return(context: &mut FnContext, value: Option<Node<fe::Expr>>) -> Vec<fe
     }
 }
 
+/// Lowers a `match` statement to an `if`/`else` chain.
+///
+/// e.g.
+/// ```fe
+/// match shape:
+///     Shape::Circle(radius):
+///         return radius
+///     _:
+///         return 0
+/// ```
+/// will be lowered to
+/// ```fe
+/// let $match_0: Shape = shape
+/// if $match_0.tag == 0:
+///     let radius: u256 = $match_0.Circle_0
+///     return radius
+/// else:
+///     return 0
+/// ```
+///
+/// The analyzer ensures that the arms are exhaustive, so the last arm (or the
+/// first arm that matches any value) is lowered to an unconditional `else`
+/// block, and the arms following it are dropped.
+fn stmt_match(
+    context: &mut FnContext,
+    value: Node<fe::Expr>,
+    arms: Vec<Node<fe::MatchArm>>,
+) -> Vec<fe::FuncStmt> {
+    let value_type = FixedSize::try_from(
+        context
+            .expression_attributes(&value)
+            .expect("missing attributes")
+            .typ
+            .clone(),
+    )
+    .expect("match value must be fixed size");
+
+    let match_value: SmolStr = context.make_unique_name("match").into();
+    let decl = fe::FuncStmt::VarDecl {
+        target: fe::VarDeclTarget::Name(match_value.clone()).into_node(),
+        typ: Some(names::fixed_size_type_desc(&value_type).into_node()),
+        value: Some(expressions::expr(context, value)),
+    };
+
+    let mut lowered_arms = vec![];
+    for arm in arms {
+        let (test, body) = match_arm(context, &match_value, &value_type, arm);
+        let is_catch_all = test.is_none();
+        lowered_arms.push((test, body));
+        if is_catch_all {
+            break;
+        }
+    }
+
+    let (_, mut or_else) = lowered_arms.pop().expect("match statement without arms");
+    while let Some((test, body)) = lowered_arms.pop() {
+        or_else = vec![fe::FuncStmt::If {
+            test: test.expect("catch-all arm must be the last lowered arm"),
+            body,
+            or_else,
+        }
+        .into_node()];
+    }
+
+    let mut stmts = vec![decl];
+    stmts.extend(or_else.into_iter().map(|stmt| stmt.kind));
+    stmts
+}
+
+/// Lowers a `match` arm to the condition under which the arm is taken, and the
+/// body of the arm, which begins with the declarations of the variables bound
+/// by its pattern. The condition is `None` if the pattern matches any value.
+fn match_arm(
+    context: &mut FnContext,
+    match_value: &str,
+    value_type: &FixedSize,
+    arm: Node<fe::MatchArm>,
+) -> (Option<Node<fe::Expr>>, Vec<Node<fe::FuncStmt>>) {
+    let fe::MatchArm { pattern, body } = arm.kind;
+    let value = || fe::Expr::Name(match_value.into()).into_node();
+    let is_equal = |left: Node<fe::Expr>, right: fe::Expr| {
+        fe::Expr::CompOperation {
+            left: Box::new(left),
+            op: fe::CompOperator::Eq.into_node(),
+            right: right.into_boxed_node(),
+        }
+        .into_node()
+    };
+
+    let mut stmts = vec![];
+    let test = match pattern.kind {
+        fe::Pattern::Wildcard => None,
+        fe::Pattern::Binding(name) => {
+            stmts.push(
+                fe::FuncStmt::VarDecl {
+                    target: Node::new(fe::VarDeclTarget::Name(name), pattern.span),
+                    typ: Some(names::fixed_size_type_desc(value_type).into_node()),
+                    value: Some(value()),
+                }
+                .into_node(),
+            );
+            None
+        }
+        fe::Pattern::Bool(val) => Some(is_equal(value(), fe::Expr::Bool(val))),
+        fe::Pattern::Num(num) => {
+            let num = match num.strip_prefix('-') {
+                Some(num) => fe::Expr::UnaryOperation {
+                    op: fe::UnaryOperator::USub.into_node(),
+                    operand: fe::Expr::Num(num.into()).into_boxed_node(),
+                },
+                None => fe::Expr::Num(num),
+            };
+            Some(is_equal(value(), num))
+        }
+        fe::Pattern::Variant { path, fields } => {
+            let db = context.db();
+            let variant = match context.id.module(db).resolve_path_internal(db, &path).value {
+                Some(Item::EnumVariant(variant)) => variant,
+                _ => panic!("match pattern is not an enum variant"),
+            };
+
+            let field_types = variant.fields(db);
+            for (idx, (field, typ)) in fields
+                .unwrap_or_default()
+                .into_iter()
+                .zip(field_types.iter())
+                .enumerate()
+            {
+                if let fe::Pattern::Binding(name) = field.kind {
+                    let typ = typ.as_ref().expect("enum variant field type error");
+                    stmts.push(
+                        fe::FuncStmt::VarDecl {
+                            target: Node::new(fe::VarDeclTarget::Name(name), field.span),
+                            typ: Some(names::fixed_size_type_desc(typ).into_node()),
+                            value: Some(
+                                fe::Expr::Attribute {
+                                    value: value().into(),
+                                    attr: variant.repr_field_name(db, idx).into_node(),
+                                }
+                                .into_node(),
+                            ),
+                        }
+                        .into_node(),
+                    );
+                }
+            }
+
+            let tag = fe::Expr::Attribute {
+                value: value().into(),
+                attr: SmolStr::new("tag").into_node(),
+            }
+            .into_node();
+            Some(is_equal(
+                tag,
+                fe::Expr::Num(variant.tag(db).to_string().into()),
+            ))
+        }
+    };
+
+    stmts.extend(multiple_stmts(context, body));
+    (test, stmts)
+}
+
 fn is_last_statement_return(stmts: &[Node<fe::FuncStmt>]) -> bool {
     if let Some(stmt) = stmts.last() {
         matches!(stmt.kind, fe::FuncStmt::Return { .. })
@@ -303,6 +841,23 @@ fn is_last_statement_return(stmts: &[Node<fe::FuncStmt>]) -> bool {
     }
 }
 
+/// The description of a type as it would be written in source code, i.e. with
+/// tuple types described by their items.
+fn source_type_desc(typ: &FixedSize) -> fe::TypeDesc {
+    match typ {
+        FixedSize::Tuple(tuple) => fe::TypeDesc::Tuple {
+            items: tuple
+                .items
+                .iter()
+                .map(|item| source_type_desc(item).into_node())
+                .collect::<Vec<_>>()
+                .try_into()
+                .expect("tuple is empty"),
+        },
+        _ => names::fixed_size_type_desc(typ),
+    }
+}
+
 /// Lowers tuple desctructuring.
 ///
 /// e.g.
@@ -327,7 +882,7 @@ fn lower_tuple_destructuring(
     let tmp_tuple: SmolStr = context.make_unique_name("tmp_tuple").into();
     stmts.push(fe::FuncStmt::VarDecl {
         target: Node::new(fe::VarDeclTarget::Name(tmp_tuple.clone()), span),
-        typ: types::type_desc(context.module, type_desc.clone(), typ),
+        typ: Some(types::type_desc(context.module, type_desc.clone(), typ)),
         value: expressions::optional_expr(context, value),
     });
 
@@ -354,18 +909,10 @@ fn declare_tuple_items(
 ) {
     match target.kind {
         fe::VarDeclTarget::Name(_) => {
-            let mut value = fe::Expr::Name(tmp_tuple.into()).into_node();
-            for index in indices.iter() {
-                value = fe::Expr::Attribute {
-                    value: value.into(),
-                    attr: SmolStr::new(format!("item{}", index)).into_node(),
-                }
-                .into_node();
-            }
-
+            let value = tuple_item_expr(tmp_tuple, indices);
             stmts.push(fe::FuncStmt::VarDecl {
                 target,
-                typ: types::type_desc(context.module, type_desc, typ),
+                typ: Some(types::type_desc(context.module, type_desc, typ)),
                 value: expressions::optional_expr(context, Some(value)),
             });
         }
@@ -400,3 +947,81 @@ fn declare_tuple_items(
         }
     }
 }
+
+/// Lowers an assignment to a tuple of targets.
+///
+/// e.g.
+/// ```fe
+/// (x, y) = (y, x)
+/// ```
+/// will be lowered to
+/// ```fe
+/// let $tmp_tuple_0: $tuple_u256_u256_ = (y, x)
+/// x = $tmp_tuple_0.item0
+/// y = $tmp_tuple_0.item1
+/// ```
+/// The value is stored before any target is assigned, so a target can be used
+/// in the value.
+fn lower_tuple_assignment(
+    context: &mut FnContext,
+    target: Node<fe::Expr>,
+    value: Node<fe::Expr>,
+    span: fe_common::Span,
+) -> Vec<fe::FuncStmt> {
+    let typ = FixedSize::try_from(
+        context
+            .expression_attributes(&value)
+            .expect("missing attributes")
+            .typ
+            .clone(),
+    )
+    .expect("tuple assignment value must be fixed size");
+
+    let tmp_tuple: SmolStr = context.make_unique_name("tmp_tuple").into();
+    let mut stmts = vec![fe::FuncStmt::VarDecl {
+        target: Node::new(fe::VarDeclTarget::Name(tmp_tuple.clone()), span),
+        typ: Some(Node::new(
+            types::concrete_type_desc(context.module, &typ),
+            span,
+        )),
+        value: Some(expressions::expr(context, value)),
+    }];
+    assign_tuple_items(context, target, &tmp_tuple, &mut vec![], &mut stmts);
+    stmts
+}
+
+fn assign_tuple_items(
+    context: &mut FnContext,
+    target: Node<fe::Expr>,
+    tmp_tuple: &str,
+    indices: &mut Vec<usize>,
+    stmts: &mut Vec<fe::FuncStmt>,
+) {
+    match target.kind {
+        fe::Expr::Tuple { elts } => {
+            for (index, target) in elts.into_iter().enumerate() {
+                indices.push(index);
+                assign_tuple_items(context, target, tmp_tuple, indices, stmts);
+                indices.pop().unwrap();
+            }
+        }
+        _ => stmts.push(fe::FuncStmt::Assign {
+            target: expressions::expr(context, target),
+            value: tuple_item_expr(tmp_tuple, indices),
+        }),
+    }
+}
+
+/// The expression of the item of `tmp_tuple` at the path of `indices`,
+/// e.g. `$tmp_tuple_0.item1.item0` for the indices `[1, 0]`.
+fn tuple_item_expr(tmp_tuple: &str, indices: &[usize]) -> Node<fe::Expr> {
+    let mut value = fe::Expr::Name(tmp_tuple.into()).into_node();
+    for index in indices.iter() {
+        value = fe::Expr::Attribute {
+            value: value.into(),
+            attr: SmolStr::new(format!("item{}", index)).into_node(),
+        }
+        .into_node();
+    }
+    value
+}
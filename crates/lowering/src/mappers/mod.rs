@@ -1,4 +1,5 @@
 mod contracts;
+mod enums;
 mod events;
 mod expressions;
 mod functions;
@@ -1,10 +1,11 @@
 use crate::context::ModuleContext;
-use crate::mappers::{contracts, events, functions, structs, types};
+use crate::mappers::{contracts, enums, events, functions, structs, types};
 use crate::names;
 use crate::utils::ZeroSpanNode;
 use fe_analyzer::namespace::items::{Item, ModuleId, TypeDef};
-use fe_analyzer::namespace::types::{Array, Base, FixedSize, Tuple};
+use fe_analyzer::namespace::types::{Array, Base, FixedSize, Struct, Tuple};
 use fe_analyzer::AnalyzerDb;
+use fe_common::{SourceFileId, Span};
 use fe_parser::ast::{self, SmolStr};
 use fe_parser::node::Node;
 
@@ -17,7 +18,15 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
         .body
         .iter()
         .filter_map(|stmt| match stmt {
-            ast::ModuleStmt::Pragma(_) | ast::ModuleStmt::Use(_) => Some(stmt.clone()),
+            // `impl` statements are kept for the analysis of the lowered
+            // module, e.g. to report the interfaces that a contract supports.
+            ast::ModuleStmt::Pragma(_) | ast::ModuleStmt::Impl(_) => Some(stmt.clone()),
+            ast::ModuleStmt::Use(use_stmt) => Some(ast::ModuleStmt::Use(Node::new(
+                ast::Use {
+                    tree: use_tree(db, module, &use_stmt.kind.tree),
+                },
+                use_stmt.span,
+            ))),
             _ => None,
         })
         .collect::<Vec<_>>();
@@ -36,20 +45,28 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
                             &id.typ(db).expect("type alias error"),
                         ),
                         pub_qual: None,
+                        doc: None,
                     },
                     id.span(db),
                 )))
             }
+            // Generic structs are lowered once for each of their instances.
+            TypeDef::Struct(id) if id.is_generic(db) => None,
             TypeDef::Struct(id) => Some(ast::ModuleStmt::Struct(structs::struct_def(
                 &mut context,
                 *id,
             ))),
+            TypeDef::Enum(id) => Some(ast::ModuleStmt::Struct(enums::enum_def(&mut context, *id))),
+            TypeDef::Contract(id) if id.is_interface(db) => Some(ast::ModuleStmt::Interface(
+                contracts::interface_def(&mut context, *id),
+            )),
             TypeDef::Contract(id) => Some(ast::ModuleStmt::Contract(contracts::contract_def(
                 &mut context,
                 *id,
             ))),
             TypeDef::Primitive(_) => unreachable!(),
         },
+        Item::Function(id) if id.is_generic(db) => None,
         Item::Function(id) => Some(ast::ModuleStmt::Function(functions::func_def(
             &mut context,
             *id,
@@ -61,6 +78,8 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
             unreachable!("special built-in stuff")
         }
 
+        Item::EnumVariant(_) => unreachable!("enum variants are lowered with their enum"),
+
         Item::Constant(constant) => Some(ast::ModuleStmt::Constant(
             constant.data(db).ast.clone().into(),
         )),
@@ -69,6 +88,61 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
         Item::Module(_) => unreachable!("modules cannot be defined in modules (at least not yet)"),
     }));
 
+    // Instances of generic structs and functions are lowered to regular
+    // definitions. Lowering an instance can require further instances.
+    // The functions that struct update expressions are lowered to are
+    // generated along with them, as their types can require instances too.
+    let mut generic_instance_defs = vec![];
+    let mut struct_update_defs = vec![];
+    let (mut struct_count, mut function_count, mut update_count) = (0, 0, 0);
+    loop {
+        if let Some(id) = context.struct_instances.get_index(struct_count).copied() {
+            struct_count += 1;
+            generic_instance_defs.push(ast::ModuleStmt::Struct(structs::struct_def(
+                &mut context,
+                id,
+            )));
+        } else if let Some(id) = context
+            .function_instances
+            .get_index(function_count)
+            .copied()
+        {
+            function_count += 1;
+            generic_instance_defs.push(ast::ModuleStmt::Function(functions::func_def(
+                &mut context,
+                id,
+            )));
+        } else if let Some((struct_, fields)) =
+            context.struct_updates.get_index(update_count).cloned()
+        {
+            update_count += 1;
+            let def = struct_update_fn_def(&mut context, &struct_, &fields).into_node();
+            struct_update_defs.push((struct_, def));
+        } else {
+            break;
+        }
+    }
+
+    // The constructor of a struct with private fields can only be called by
+    // the struct itself, so these functions are added to the struct.
+    let mut func_defs_from_struct_updates = vec![];
+    for (struct_, def) in struct_update_defs {
+        if !struct_.id.has_private_field(db) {
+            func_defs_from_struct_updates.push(ast::ModuleStmt::Function(def));
+            continue;
+        }
+        let name = names::struct_name(&struct_);
+        let struct_def = lowered_body
+            .iter_mut()
+            .chain(generic_instance_defs.iter_mut())
+            .find_map(|stmt| match stmt {
+                ast::ModuleStmt::Struct(node) if node.kind.name.kind == name => Some(node),
+                _ => None,
+            })
+            .expect("missing struct definition");
+        struct_def.kind.functions.push(def);
+    }
+
     let struct_defs_from_tuples = context
         .tuples
         .iter()
@@ -78,14 +152,18 @@ pub fn module(db: &dyn AnalyzerDb, module: ModuleId) -> ast::Module {
     let func_defs_from_list_expr = context
         .list_expressions
         .iter()
-        .map(|expr| ast::ModuleStmt::Function(list_expr_to_fn_def(expr).into_node()))
+        .map(|(array, fill_from)| {
+            ast::ModuleStmt::Function(list_expr_to_fn_def(array, *fill_from).into_node())
+        })
         .collect::<Vec<_>>();
 
     ast::Module {
         body: [
             struct_defs_from_tuples,
             func_defs_from_list_expr,
+            func_defs_from_struct_updates,
             lowered_body,
+            generic_instance_defs,
         ]
         .concat(),
     }
@@ -102,10 +180,13 @@ fn build_tuple_struct(tuple: &Tuple) -> ast::Struct {
         .collect();
 
     ast::Struct {
+        attributes: vec![],
         name: names::tuple_struct_name(tuple).into_node(),
+        generic_params: vec![],
         fields,
         functions: vec![],
         pub_qual: None,
+        doc: None,
     }
 }
 
@@ -116,6 +197,7 @@ fn build_struct_field(name: String, type_desc: ast::TypeDesc) -> ast::Field {
         name: SmolStr::new(name).into_node(),
         typ: type_desc.into_node(),
         value: None,
+        doc: None,
     }
 }
 
@@ -143,38 +225,152 @@ fn build_type_desc(typ: &FixedSize) -> ast::TypeDesc {
             base: SmolStr::new("String").into_node(),
             args: vec![ast::GenericArg::Int(string.max_size.into_node())].into_node(),
         },
+        FixedSize::Vec(vec) => ast::TypeDesc::Generic {
+            base: SmolStr::new("Vec").into_node(),
+            args: vec![ast::GenericArg::TypeDesc(
+                ast::TypeDesc::Base {
+                    base: vec.inner.name(),
+                }
+                .into_node(),
+            )]
+            .into_node(),
+        },
         FixedSize::Contract(contract) => ast::TypeDesc::Base {
             base: contract.name.clone(),
         },
         FixedSize::Struct(strukt) => ast::TypeDesc::Base {
-            base: strukt.name.clone(),
+            base: names::struct_name(strukt),
         },
+        FixedSize::Enum(enum_) => ast::TypeDesc::Base {
+            base: enum_.name.clone(),
+        },
+        FixedSize::Function(_) => names::fixed_size_type_desc(typ),
     }
 }
 
-fn list_expr_to_fn_def(array: &Array) -> ast::Function {
+/// Builds the function that a struct update expression giving the fields
+/// `fields` is lowered to. It takes the given fields and the base struct, and
+/// copies the other fields from the base:
+///
+/// `pub fn $update_Point$x(x: u256, $base: Point) -> Point:
+///     return Point(x: x, y: $base.y)`
+fn struct_update_fn_def(
+    context: &mut ModuleContext,
+    struct_: &Struct,
+    fields: &[SmolStr],
+) -> ast::Function {
+    let db = context.db;
+    let base_name = SmolStr::new("$base");
+    let struct_desc = types::concrete_type_desc(context, &FixedSize::Struct(struct_.clone()));
+
+    let mut args = vec![];
+    let mut field_values = vec![];
+    for (name, field) in struct_.id.fields(db).iter() {
+        let value = if fields.contains(name) {
+            let typ = field.typ(db).expect("struct field type error");
+            args.push(
+                ast::FunctionArg::Regular(ast::RegularFunctionArg {
+                    mut_: None,
+                    name: name.clone().into_node(),
+                    typ: types::concrete_type_desc(context, &typ).into_node(),
+                    default: None,
+                })
+                .into_node(),
+            );
+            ast::Expr::Name(name.clone())
+        } else {
+            ast::Expr::Attribute {
+                value: ast::Expr::Name(base_name.clone()).into_boxed_node(),
+                attr: name.clone().into_node(),
+            }
+        };
+        field_values.push(
+            ast::CallArg {
+                label: Some(name.clone().into_node()),
+                value: value.into_node(),
+            }
+            .into_node(),
+        );
+    }
+    args.push(
+        ast::FunctionArg::Regular(ast::RegularFunctionArg {
+            mut_: None,
+            name: base_name.into_node(),
+            typ: struct_desc.clone().into_node(),
+            default: None,
+        })
+        .into_node(),
+    );
+
+    let return_stmt = ast::FuncStmt::Return {
+        value: Some(
+            ast::Expr::Call {
+                func: ast::Expr::Name(names::struct_name(struct_)).into_boxed_node(),
+                generic_args: None,
+                args: field_values.into_node(),
+                base: None,
+            }
+            .into_node(),
+        ),
+    }
+    .into_node();
+
+    // Functions of a struct can only be called via the struct if they're public.
+    ast::Function {
+        attributes: vec![],
+        pub_: Some(Span::zero(SourceFileId::dummy_file())),
+        unsafe_: None,
+        name: names::struct_update_fn_name(struct_, fields).into_node(),
+        generic_params: vec![],
+        args,
+        return_type: Some(struct_desc.into_node()),
+        body: vec![return_stmt],
+        doc: None,
+    }
+}
+
+/// Builds the function that a list expression is lowered to. For a list with
+/// a fill value, `fill_from` is the number of elements that are given, and the
+/// function takes the fill value as its last argument.
+fn list_expr_to_fn_def(array: &Array, fill_from: Option<usize>) -> ast::Function {
+    let given = fill_from.unwrap_or(array.size);
+    let inner_type_desc = || names::fixed_size_type_desc(&FixedSize::Base(array.inner)).into_node();
+
     // Built the AST nodes for the function arguments
-    let args = (0..array.size)
+    let mut args = (0..given)
         .map(|index| {
             ast::FunctionArg::Regular(ast::RegularFunctionArg {
+                mut_: None,
                 name: SmolStr::new(format!("val{}", index)).into_node(),
-                typ: names::fixed_size_type_desc(&FixedSize::Base(array.inner)).into_node(),
+                typ: inner_type_desc(),
+                default: None,
             })
             .into_node()
         })
         .collect::<Vec<_>>();
+    if fill_from.is_some() {
+        args.push(
+            ast::FunctionArg::Regular(ast::RegularFunctionArg {
+                mut_: None,
+                name: SmolStr::new("fill").into_node(),
+                typ: inner_type_desc(),
+                default: None,
+            })
+            .into_node(),
+        );
+    }
 
     // Build the AST node for the array declaration
     let var_decl_name = "generated_array";
     let var_decl = ast::FuncStmt::VarDecl {
         target: ast::VarDeclTarget::Name(var_decl_name.into()).into_node(),
-        typ: names::fixed_size_type_desc(&FixedSize::Array(array.clone())).into_node(),
+        typ: Some(names::fixed_size_type_desc(&FixedSize::Array(array.clone())).into_node()),
         value: None,
     }
     .into_node();
 
     // Build the AST nodes for the individual assignments of array slots
-    let assignments = (0..array.size)
+    let assignments = (0..given)
         .map(|index| {
             ast::FuncStmt::Assign {
                 target: ast::Expr::Subscript {
@@ -188,6 +384,49 @@ fn list_expr_to_fn_def(array: &Array) -> ast::Function {
         })
         .collect::<Vec<_>>();
 
+    // Build the AST nodes for the loop that sets the remaining slots to the
+    // fill value
+    let fill_loop = if fill_from.is_some() {
+        let index_name = "index";
+        let index = || ast::Expr::Name(index_name.into()).into_node();
+        vec![
+            ast::FuncStmt::VarDecl {
+                target: ast::VarDeclTarget::Name(index_name.into()).into_node(),
+                typ: Some(names::fixed_size_type_desc(&FixedSize::u256()).into_node()),
+                value: Some(ast::Expr::Num(given.to_string().into()).into_node()),
+            }
+            .into_node(),
+            ast::FuncStmt::While {
+                test: ast::Expr::CompOperation {
+                    left: Box::new(index()),
+                    op: ast::CompOperator::Lt.into_node(),
+                    right: ast::Expr::Num(array.size.to_string().into()).into_boxed_node(),
+                }
+                .into_node(),
+                body: vec![
+                    ast::FuncStmt::Assign {
+                        target: ast::Expr::Subscript {
+                            value: ast::Expr::Name(var_decl_name.into()).into_boxed_node(),
+                            index: Box::new(index()),
+                        }
+                        .into_node(),
+                        value: ast::Expr::Name("fill".into()).into_node(),
+                    }
+                    .into_node(),
+                    ast::FuncStmt::AugAssign {
+                        target: index(),
+                        op: ast::BinOperator::Add.into_node(),
+                        value: ast::Expr::Num("1".into()).into_node(),
+                    }
+                    .into_node(),
+                ],
+            }
+            .into_node(),
+        ]
+    } else {
+        vec![]
+    };
+
     // Build the AST node for the return statement
     let return_stmt = ast::FuncStmt::Return {
         value: Some(ast::Expr::Name(var_decl_name.into()).into_node()),
@@ -197,13 +436,75 @@ fn list_expr_to_fn_def(array: &Array) -> ast::Function {
     let return_type =
         Some(names::fixed_size_type_desc(&FixedSize::Array(array.clone())).into_node());
 
+    let name = match fill_from {
+        Some(given) => names::list_fill_expr_generator_fn_name(array, given),
+        None => names::list_expr_generator_fn_name(array),
+    };
+
     // Put it all together in one AST node that holds the entire function definition
     ast::Function {
+        attributes: vec![],
         pub_: None,
         unsafe_: None,
-        name: names::list_expr_generator_fn_name(array).into_node(),
+        name: name.into_node(),
+        generic_params: vec![],
         args,
         return_type,
-        body: [vec![var_decl], assignments, vec![return_stmt]].concat(),
+        body: [vec![var_decl], assignments, fill_loop, vec![return_stmt]].concat(),
+        doc: None,
     }
 }
+
+/// Lowers the tree of a `use` statement. An overloaded function is imported by
+/// the names of the lowered functions that it stands for.
+fn use_tree(
+    db: &dyn AnalyzerDb,
+    module: ModuleId,
+    tree: &Node<ast::UseTree>,
+) -> Node<ast::UseTree> {
+    let kind = match &tree.kind {
+        ast::UseTree::Nested { prefix, children } => ast::UseTree::Nested {
+            prefix: prefix.clone(),
+            children: children
+                .iter()
+                .map(|child| use_tree(db, module, child))
+                .collect(),
+        },
+        ast::UseTree::Simple { path, rename } => {
+            let name = match rename {
+                Some(rename) => rename.kind.clone(),
+                None => path.segments.last().expect("empty use path").kind.clone(),
+            };
+            let overloads = match module.used_items(db).get(&name) {
+                Some((_, Item::Function(function))) => function.overloads(db),
+                _ => None,
+            };
+            match overloads {
+                Some(overloads) => ast::UseTree::Nested {
+                    prefix: ast::Path {
+                        segments: path.segments[..path.segments.len() - 1].to_vec(),
+                    },
+                    children: overloads
+                        .iter()
+                        .map(|function| {
+                            let name = names::overload_name(
+                                &function.name(db),
+                                &function.signature(db).param_types(),
+                            );
+                            ast::UseTree::Simple {
+                                path: ast::Path {
+                                    segments: vec![name.into_node()],
+                                },
+                                rename: None,
+                            }
+                            .into_node()
+                        })
+                        .collect(),
+                },
+                None => tree.kind.clone(),
+            }
+        }
+        ast::UseTree::Glob { .. } => tree.kind.clone(),
+    };
+    Node::new(kind, tree.span)
+}
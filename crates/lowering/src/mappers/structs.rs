@@ -1,5 +1,6 @@
 use crate::context::ModuleContext;
 use crate::mappers::{functions, types};
+use crate::names;
 use fe_analyzer::namespace::items::{StructFieldId, StructId};
 use fe_parser::ast;
 use fe_parser::node::Node;
@@ -20,12 +21,16 @@ pub fn struct_def(context: &mut ModuleContext, struct_: StructId) -> Node<ast::S
         .collect();
 
     let node = &struct_.data(context.db).ast;
+    let name = Node::new(names::struct_name(&struct_.typ(db)), node.kind.name.span);
     Node::new(
         ast::Struct {
-            name: node.kind.name.clone(),
+            attributes: node.kind.attributes.clone(),
+            name,
+            generic_params: vec![],
             fields,
             functions,
             pub_qual: None,
+            doc: None,
         },
         node.span,
     )
@@ -41,6 +46,7 @@ fn struct_field(context: &mut ModuleContext, field: StructFieldId) -> Node<ast::
             name: node.kind.name.clone(),
             typ: types::type_desc(context, node.kind.typ.clone(), &typ.into()),
             value: node.kind.value.clone(),
+            doc: None,
         },
         node.span,
     )
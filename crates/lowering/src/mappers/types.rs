@@ -1,10 +1,17 @@
 use crate::context::ModuleContext;
 use crate::names;
-use fe_analyzer::namespace::types::{Type, TypeDowncast};
+use fe_analyzer::namespace::types::{FixedSize, Type, TypeDowncast};
 use fe_parser::ast::{GenericArg, TypeDesc};
 use fe_parser::node::Node;
 
 pub fn type_desc(context: &mut ModuleContext, desc: Node<TypeDesc>, typ: &Type) -> Node<TypeDesc> {
+    // Generic parameters and instances of generic structs are replaced by the
+    // description of the concrete type.
+    if is_generic_instance(typ) || is_generic_param(context, &desc.kind) {
+        let typ = FixedSize::try_from(typ.clone()).expect("expected fixed size type");
+        return Node::new(concrete_type_desc(context, &typ), desc.span);
+    }
+
     match desc.kind {
         TypeDesc::Unit | TypeDesc::Base { .. } | TypeDesc::Path(_) => desc,
 
@@ -23,6 +30,35 @@ pub fn type_desc(context: &mut ModuleContext, desc: Node<TypeDesc>, typ: &Type)
             )
         }
 
+        TypeDesc::Function {
+            params,
+            return_type,
+        } => {
+            let typ = match typ {
+                Type::Function(function) => function,
+                _ => panic!("expected function type"),
+            };
+            Node::new(
+                TypeDesc::Function {
+                    params: params
+                        .into_iter()
+                        .zip(typ.params.iter())
+                        .map(|(param_desc, param_type)| {
+                            type_desc(context, param_desc, &param_type.clone().into())
+                        })
+                        .collect(),
+                    return_type: return_type.map(|desc| {
+                        Box::new(type_desc(
+                            context,
+                            *desc,
+                            &typ.return_type.as_ref().clone().into(),
+                        ))
+                    }),
+                },
+                desc.span,
+            )
+        }
+
         TypeDesc::Generic { base, args } => Node::new(
             TypeDesc::Generic {
                 base,
@@ -30,14 +66,13 @@ pub fn type_desc(context: &mut ModuleContext, desc: Node<TypeDesc>, typ: &Type)
                     args.kind
                         .into_iter()
                         .enumerate()
-                        .map(|(idx, arg)| match arg {
-                            GenericArg::TypeDesc(node) => GenericArg::TypeDesc(type_desc(
-                                context,
-                                node,
-                                &typ.generic_arg_type(idx)
-                                    .expect("expected generic type arg"),
-                            )),
-                            GenericArg::Int(_) | GenericArg::ConstExpr(_) => arg,
+                        .map(|(idx, arg)| match (arg, typ.generic_arg_type(idx)) {
+                            (GenericArg::TypeDesc(node), Some(arg_type)) => {
+                                GenericArg::TypeDesc(type_desc(context, node, &arg_type))
+                            }
+                            // Integer arguments, including constants given by
+                            // name, eg. `Array<u8, SIZE>`.
+                            (arg, _) => arg,
                         })
                         .collect(),
                     args.span,
@@ -47,3 +82,42 @@ pub fn type_desc(context: &mut ModuleContext, desc: Node<TypeDesc>, typ: &Type)
         ),
     }
 }
+
+/// Maps a type to its description, and records the tuples and generic struct
+/// instances that it contains, so that they're lowered to struct definitions.
+pub fn concrete_type_desc(context: &mut ModuleContext, typ: &FixedSize) -> TypeDesc {
+    match typ {
+        FixedSize::Tuple(tuple) => {
+            for item in tuple.items.iter() {
+                concrete_type_desc(context, item);
+            }
+            context.tuples.insert(tuple.clone());
+        }
+        FixedSize::Struct(struct_) if !struct_.generic_args.is_empty() => {
+            context.struct_instances.insert(struct_.id);
+        }
+        FixedSize::Function(function) => {
+            for param in function.params.iter() {
+                concrete_type_desc(context, param);
+            }
+            concrete_type_desc(context, &function.return_type);
+        }
+        _ => {}
+    }
+    names::fixed_size_type_desc(typ)
+}
+
+fn is_generic_instance(typ: &Type) -> bool {
+    matches!(typ, Type::Struct(struct_) if !struct_.generic_args.is_empty())
+}
+
+/// Returns `true` if the type description is the name of a generic parameter,
+/// which isn't a type that's visible in the module.
+fn is_generic_param(context: &ModuleContext, desc: &TypeDesc) -> bool {
+    match desc {
+        TypeDesc::Base { base } => {
+            !matches!(context.module.resolve_name(context.db, base), Ok(Some(_)))
+        }
+        _ => false,
+    }
+}
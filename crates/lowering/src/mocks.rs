@@ -0,0 +1,254 @@
+//! Mock contracts for interfaces.
+//!
+//! The mock of an interface implements each of its functions by returning a
+//! value that can be set beforehand, and counts the calls to each function.
+//! For a function `fn balance(self, owner: address) -> u256`, the mock has:
+//!
+//! - `pub fn balance(mut self, owner: address) -> u256`, which returns the
+//!   value given to `set_balance_return`
+//! - `pub fn set_balance_return(mut self, value: u256)`
+//! - `pub fn balance_call_count(self) -> u256`
+//!
+//! The mocked functions take `mut self` to count the calls, even if the
+//! functions of the interface don't.
+
+use fe_analyzer::namespace::items::{ContractId, Item, ModuleId, TypeDef};
+use fe_analyzer::AnalyzerDb;
+use fe_common::Span;
+use fe_parser::ast::{self, SmolStr};
+use fe_parser::node::Node;
+
+/// Returns a mock contract for each interface defined in the module.
+pub fn module_interface_mocks(db: &dyn AnalyzerDb, module: ModuleId) -> Vec<Node<ast::Contract>> {
+    module
+        .all_items(db)
+        .iter()
+        .filter_map(|item| match item {
+            Item::Type(TypeDef::Contract(id)) if id.is_interface(db) => {
+                Some(interface_mock(db, *id))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns a mock contract for the interface. The mock is named after the
+/// interface, with a `Mock` suffix.
+pub fn interface_mock(db: &dyn AnalyzerDb, interface: ContractId) -> Node<ast::Contract> {
+    let mut fields = vec![];
+    let mut body = vec![];
+
+    for function in interface.functions(db).values() {
+        let node = &function.data(db).ast;
+        let span = node.span;
+        let name = &node.kind.name.kind;
+        let return_field = SmolStr::from(format!("{}_return", name));
+        let calls_field = SmolStr::from(format!("{}_calls", name));
+
+        // Functions that return the unit type have no return value to set.
+        let return_type = match &function.signature(db).return_type {
+            Ok(typ) if typ.is_unit() => None,
+            Ok(typ) => node
+                .kind
+                .return_type
+                .clone()
+                .map(|desc| (desc, typ.is_base())),
+            Err(_) => None,
+        };
+
+        let mut stmts = vec![aug_assign_one(self_attribute(&calls_field, span))];
+        if let Some((typ, is_base)) = &return_type {
+            fields.push(field(&return_field, typ.clone()));
+
+            // Values that aren't base types are copied out of storage.
+            let mut value = self_attribute(&return_field, span);
+            if !is_base {
+                value = method_call(value, "to_mem");
+            }
+            stmts.push(Node::new(
+                ast::FuncStmt::Return { value: Some(value) },
+                span,
+            ));
+        }
+        fields.push(field(&calls_field, u256(span)));
+
+        body.push(function_def(
+            name,
+            node.kind
+                .args
+                .iter()
+                .filter(|arg| !matches!(arg.kind, ast::FunctionArg::Zelf { .. }))
+                .cloned(),
+            true,
+            return_type.as_ref().map(|(typ, _)| typ.clone()),
+            stmts,
+            span,
+        ));
+
+        if let Some((typ, _)) = return_type {
+            body.push(function_def(
+                &format!("set_{}", return_field),
+                [regular_arg("value", typ)],
+                true,
+                None,
+                vec![Node::new(
+                    ast::FuncStmt::Assign {
+                        target: self_attribute(&return_field, span),
+                        value: name_expr("value", span),
+                    },
+                    span,
+                )],
+                span,
+            ));
+        }
+
+        body.push(function_def(
+            &format!("{}_call_count", name),
+            [],
+            false,
+            Some(u256(span)),
+            vec![Node::new(
+                ast::FuncStmt::Return {
+                    value: Some(self_attribute(&calls_field, span)),
+                },
+                span,
+            )],
+            span,
+        ));
+    }
+
+    let node = &interface.data(db).ast;
+    Node::new(
+        ast::Contract {
+            attributes: vec![],
+            name: Node::new(
+                format!("{}Mock", node.kind.name.kind).into(),
+                node.kind.name.span,
+            ),
+            parents: vec![],
+            fields,
+            body,
+            pub_qual: None,
+            doc: None,
+        },
+        node.span,
+    )
+}
+
+/// A public function that takes `self`, or `mut self` if `mut_self` is
+/// `true`, and the given arguments.
+fn function_def(
+    name: &str,
+    args: impl IntoIterator<Item = Node<ast::FunctionArg>>,
+    mut_self: bool,
+    return_type: Option<Node<ast::TypeDesc>>,
+    body: Vec<Node<ast::FuncStmt>>,
+    span: Span,
+) -> ast::ContractStmt {
+    ast::ContractStmt::Function(Node::new(
+        ast::Function {
+            attributes: vec![],
+            pub_: Some(span),
+            unsafe_: None,
+            name: Node::new(name.into(), span),
+            generic_params: vec![],
+            args: std::iter::once(Node::new(
+                ast::FunctionArg::Zelf {
+                    mut_: mut_self.then_some(span),
+                },
+                span,
+            ))
+            .chain(args)
+            .collect(),
+            return_type,
+            body,
+            doc: None,
+        },
+        span,
+    ))
+}
+
+fn regular_arg(name: &str, typ: Node<ast::TypeDesc>) -> Node<ast::FunctionArg> {
+    let span = typ.span;
+    Node::new(
+        ast::FunctionArg::Regular(ast::RegularFunctionArg {
+            mut_: None,
+            name: Node::new(name.into(), span),
+            typ,
+            default: None,
+        }),
+        span,
+    )
+}
+
+fn field(name: &str, typ: Node<ast::TypeDesc>) -> Node<ast::Field> {
+    let span = typ.span;
+    Node::new(
+        ast::Field {
+            is_pub: false,
+            is_const: false,
+            name: Node::new(name.into(), span),
+            typ,
+            value: None,
+            doc: None,
+        },
+        span,
+    )
+}
+
+fn u256(span: Span) -> Node<ast::TypeDesc> {
+    Node::new(
+        ast::TypeDesc::Base {
+            base: "u256".into(),
+        },
+        span,
+    )
+}
+
+fn name_expr(name: &str, span: Span) -> Node<ast::Expr> {
+    Node::new(ast::Expr::Name(name.into()), span)
+}
+
+/// `self.<name>`
+fn self_attribute(name: &str, span: Span) -> Node<ast::Expr> {
+    Node::new(
+        ast::Expr::Attribute {
+            value: Box::new(name_expr("self", span)),
+            attr: Node::new(name.into(), span),
+        },
+        span,
+    )
+}
+
+/// `<value>.<name>()`
+fn method_call(value: Node<ast::Expr>, name: &str) -> Node<ast::Expr> {
+    let span = value.span;
+    Node::new(
+        ast::Expr::Call {
+            func: Box::new(Node::new(
+                ast::Expr::Attribute {
+                    value: Box::new(value),
+                    attr: Node::new(name.into(), span),
+                },
+                span,
+            )),
+            generic_args: None,
+            base: None,
+            args: Node::new(vec![], span),
+        },
+        span,
+    )
+}
+
+/// `<target> += 1`
+fn aug_assign_one(target: Node<ast::Expr>) -> Node<ast::FuncStmt> {
+    let span = target.span;
+    Node::new(
+        ast::FuncStmt::AugAssign {
+            target,
+            op: Node::new(ast::BinOperator::Add, span),
+            value: Node::new(ast::Expr::Num("1".into()), span),
+        },
+        span,
+    )
+}
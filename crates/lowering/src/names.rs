@@ -1,6 +1,6 @@
 use crate::names;
 use crate::utils::ZeroSpanNode;
-use fe_analyzer::namespace::types::{Array, Base, FixedSize, SafeNames, Tuple};
+use fe_analyzer::namespace::types::{Array, Base, FixedSize, SafeNames, Struct, Tuple};
 use fe_parser::ast::{self, SmolStr};
 
 /// The name of a lowered list expression generator function.
@@ -8,11 +8,48 @@ pub fn list_expr_generator_fn_name(list_expr_type: &Array) -> SmolStr {
     format!("list_expr_{}", list_expr_type.lower_snake()).into()
 }
 
+/// The name of the function that builds an array from a list with a fill
+/// value, e.g. `list_expr_array_u256_10_fill_2` for `[a, b, ..c]`.
+pub fn list_fill_expr_generator_fn_name(list_expr_type: &Array, given: usize) -> SmolStr {
+    format!("list_expr_{}_fill_{}", list_expr_type.lower_snake(), given).into()
+}
+
 /// The name of a lowered tuple struct definition.
 pub fn tuple_struct_name(tuple: &Tuple) -> SmolStr {
     format!("${}", tuple.lower_snake()).into()
 }
 
+/// The name of a lowered struct definition. Instances of generic structs are
+/// named after their generic args, e.g. `Pair$u8_bool_`.
+pub fn struct_name(struct_: &Struct) -> SmolStr {
+    if struct_.generic_args.is_empty() {
+        struct_.name.clone()
+    } else {
+        generic_instance_name(&struct_.name, &struct_.generic_args)
+    }
+}
+
+/// The name of the function that a struct update expression giving the
+/// fields `fields` is lowered to, e.g. `$update_Point$x$y`.
+pub fn struct_update_fn_name(struct_: &Struct, fields: &[SmolStr]) -> SmolStr {
+    format!("$update_{}${}", struct_name(struct_), fields.join("$")).into()
+}
+
+/// The name of a lowered instance of a generic function, e.g. `max$u8_`.
+pub fn generic_instance_name(name: &str, generic_args: &[FixedSize]) -> SmolStr {
+    let arg_names = generic_args
+        .iter()
+        .map(SafeNames::lower_snake)
+        .collect::<Vec<String>>();
+    format!("{}${}_", name, arg_names.join("_")).into()
+}
+
+/// The name of a lowered overloaded function, which is named after its
+/// parameter types, e.g. `min$i256_i256_`.
+pub fn overload_name(name: &str, param_types: &[FixedSize]) -> SmolStr {
+    generic_instance_name(name, param_types)
+}
+
 /// Maps a FixedSize type to its type description.
 pub fn fixed_size_type_desc(typ: &FixedSize) -> ast::TypeDesc {
     match typ {
@@ -35,11 +72,35 @@ pub fn fixed_size_type_desc(typ: &FixedSize) -> ast::TypeDesc {
             base: SmolStr::new("String").into_node(),
             args: vec![ast::GenericArg::Int(string.max_size.into_node())].into_node(),
         },
+        FixedSize::Vec(vec) => ast::TypeDesc::Generic {
+            base: SmolStr::new("Vec").into_node(),
+            args: vec![ast::GenericArg::TypeDesc(
+                fixed_size_type_desc(&FixedSize::Base(vec.inner)).into_node(),
+            )]
+            .into_node(),
+        },
         FixedSize::Contract(contract) => ast::TypeDesc::Base {
             base: contract.name.clone(),
         },
         FixedSize::Struct(strukt) => ast::TypeDesc::Base {
-            base: strukt.name.clone(),
+            base: names::struct_name(strukt),
+        },
+        FixedSize::Enum(enum_) => ast::TypeDesc::Base {
+            base: enum_.name.clone(),
+        },
+        FixedSize::Function(function) => ast::TypeDesc::Function {
+            params: function
+                .params
+                .iter()
+                .map(|param| fixed_size_type_desc(param).into_node())
+                .collect(),
+            return_type: if function.return_type.is_unit() {
+                None
+            } else {
+                Some(Box::new(
+                    fixed_size_type_desc(&function.return_type).into_node(),
+                ))
+            },
         },
     }
 }
@@ -1,5 +1,6 @@
-use fe_analyzer::namespace::items::ModuleId;
-use fe_common::diagnostics::print_diagnostics;
+use fe_analyzer::namespace::items::{ModuleId, ModuleSource};
+use fe_common::db::SourceDb;
+use fe_common::diagnostics::{print_diagnostics, Diagnostic};
 use fe_lowering::TestDb;
 use insta::assert_snapshot;
 use wasm_bindgen_test::wasm_bindgen_test;
@@ -13,14 +14,18 @@ macro_rules! test_file {
             let module =
                 ModuleId::new_standalone(&mut db, $path.into(), test_files::fixture($path).into());
 
-            if !module.diagnostics(&db).is_empty() {
+            if module.diagnostics(&db).iter().any(Diagnostic::is_error) {
                 print_diagnostics(&db, &module.diagnostics(&db));
                 panic!("failed to analyze module")
             }
 
             let lowered_module = fe_lowering::lower_main_module(&mut db, module);
 
-            if !lowered_module.diagnostics(&db).is_empty() {
+            if lowered_module
+                .diagnostics(&db)
+                .iter()
+                .any(Diagnostic::is_error)
+            {
                 print_diagnostics(&db, &lowered_module.diagnostics(&db));
                 panic!("failed to analyze lowered module")
             }
@@ -55,5 +60,133 @@ test_file! { struct_fn, "lowering/struct_fn.fe" }
 test_file! { ternary, "lowering/ternary.fe" }
 test_file! { and_or, "lowering/and_or.fe" }
 test_file! { module_level_events, "lowering/module_level_events.fe" }
+test_file! { enums, "lowering/enums.fe" }
+test_file! { generics, "lowering/generics.fe" }
+test_file! { match_stmt, "lowering/match.fe" }
+test_file! { interface, "lowering/interface.fe" }
+test_file! { loop_stmt, "lowering/loop.fe" }
+test_file! { reordered_call_args, "lowering/reordered_call_args.fe" }
+test_file! { operator_overloading, "lowering/operator_overloading.fe" }
+test_file! { function_values, "lowering/function_values.fe" }
+test_file! { inline_yul, "lowering/inline_yul.fe" }
+test_file! { loop_invariants, "lowering/loop_invariants.fe" }
+test_file! { struct_update, "lowering/struct_update.fe" }
+test_file! { chained_comparison, "lowering/chained_comparison.fe" }
+test_file! { default_params, "lowering/default_params.fe" }
 // TODO: the analyzer rejects lowered nested tuples.
 // test_file!(array_tuple, "lowering/array_tuple.fe");
+
+#[test]
+#[wasm_bindgen_test]
+fn interface_mocks() {
+    let path = "lowering/interface_mocks.fe";
+    let src = test_files::fixture(path);
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, path, src);
+
+    let mocks = fe_lowering::mocks::module_interface_mocks(&db, module)
+        .iter()
+        .map(|mock| mock.kind.to_string())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    // The mocks are valid in the module that defines the interfaces.
+    let with_mocks = format!("{}\n{}\n", src, mocks);
+    let mocked_module = ModuleId::new_standalone(&mut db, "with_mocks.fe", &with_mocks);
+    if !mocked_module.diagnostics(&db).is_empty() {
+        print_diagnostics(&db, &mocked_module.diagnostics(&db));
+        panic!("failed to analyze mocks")
+    }
+
+    if cfg!(target_arch = "wasm32") {
+        fe_common::assert_snapshot_wasm!("snapshots/lowering__interface_mocks.snap", mocks);
+    } else {
+        assert_snapshot!(mocks);
+    }
+}
+
+/// Items that an edit doesn't touch compare equal to the items before the edit,
+/// and keep their ids and the ids of their nodes. Lowering the module after each
+/// edit must give the same result as lowering the edited source from scratch.
+fn assert_lowering_survives_edits(versions: &[String]) {
+    fn lower(db: &mut TestDb, module: ModuleId) -> String {
+        if module.diagnostics(db).iter().any(Diagnostic::is_error) {
+            print_diagnostics(db, &module.diagnostics(db));
+            panic!("failed to analyze module")
+        }
+        let lowered_module = fe_lowering::lower_main_module(db, module);
+        if lowered_module
+            .diagnostics(db)
+            .iter()
+            .any(Diagnostic::is_error)
+        {
+            print_diagnostics(db, &lowered_module.diagnostics(db));
+            panic!("failed to analyze lowered module")
+        }
+        format!("{}", lowered_module.ast(db))
+    }
+
+    let mut db = TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, "unrelated_edit.fe", &versions[0]);
+    let file = match module.data(&db).source {
+        ModuleSource::File(file) => file,
+        _ => unreachable!(),
+    };
+    lower(&mut db, module);
+    for version in &versions[1..] {
+        db.set_file_content(file, version.as_str().into());
+        let lowered = lower(&mut db, module);
+
+        let mut fresh_db = TestDb::default();
+        let fresh_module = ModuleId::new_standalone(&mut fresh_db, "unrelated_edit.fe", version);
+        let expected = lower(&mut fresh_db, fresh_module);
+        assert_eq!(
+            lowered, expected,
+            "lowered module after edit to:\n{}",
+            version
+        );
+    }
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn lowering_survives_unrelated_edit() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    let edited = src.replace("return 1", "return 100");
+    // Undoing the edit brings back the interned items of the first parse.
+    assert_lowering_survives_edits(&[src.to_string(), edited.clone(), src.to_string(), edited]);
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn lowering_survives_edit_in_middle() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    // The first edit keeps the spans of the items after `Foo`, the second
+    // moves them.
+    assert_lowering_survives_edits(&[
+        src.to_string(),
+        src.replace("y: 1)", "y: 2)"),
+        src.replace("y: 1)", "y: 20)"),
+        src.to_string(),
+    ]);
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn lowering_survives_reparse() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    assert_lowering_survives_edits(&[src.to_string(), src.to_string()]);
+}
+
+#[test]
+#[wasm_bindgen_test]
+fn lowering_survives_comment_and_whitespace_edits() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    assert_lowering_survives_edits(&[
+        src.to_string(),
+        format!("# A new comment.\n\n{}", src),
+        src.replace("# Most of the tests", "# Most of our tests"),
+        src.replace("\n\nstruct Point", "\n\n\nstruct Point"),
+        src.to_string(),
+    ]);
+}
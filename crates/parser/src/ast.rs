@@ -19,16 +19,24 @@ pub enum ModuleStmt {
     Use(Node<Use>),
     TypeAlias(Node<TypeAlias>),
     Contract(Node<Contract>),
+    Interface(Node<Interface>),
+    Impl(Node<Impl>),
     Constant(Box<Node<ConstantDecl>>),
     Struct(Node<Struct>),
+    Enum(Node<Enum>),
     Function(Node<Function>),
     Event(Node<Event>),
     ParseError(Span),
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
-pub struct Pragma {
-    pub version_requirement: Node<SmolStr>,
+pub enum Pragma {
+    /// `pragma ^0.1.0`
+    Version { version_requirement: Node<SmolStr> },
+    /// `pragma edition 2024`
+    Edition { edition: Node<SmolStr> },
+    /// `pragma feature(generics, function_types)`
+    Feature { features: Vec<Node<SmolStr>> },
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -61,6 +69,10 @@ pub struct ConstantDecl {
     pub name: Node<SmolStr>,
     pub typ: Node<TypeDesc>,
     pub value: Node<Expr>,
+    pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -68,22 +80,77 @@ pub struct TypeAlias {
     pub name: Node<SmolStr>,
     pub typ: Node<TypeDesc>,
     pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Contract {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub attributes: Vec<Node<SmolStr>>,
     pub name: Node<SmolStr>,
+    /// The contracts that the contract inherits fields, events and functions
+    /// from, e.g. `Ownable` in `contract Token(Ownable):`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parents: Vec<Node<SmolStr>>,
     pub fields: Vec<Node<Field>>,
     pub body: Vec<ContractStmt>,
     pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
+}
+
+/// The external interface of a contract. The functions have no body.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Interface {
+    pub name: Node<SmolStr>,
+    pub functions: Vec<Node<Function>>,
+    pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
+}
+
+/// A declaration that a contract implements an interface, e.g.
+/// `impl Token for MyToken`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Impl {
+    pub interface: Node<SmolStr>,
+    pub contract: Node<SmolStr>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Struct {
+    pub attributes: Vec<Node<SmolStr>>,
     pub name: Node<SmolStr>,
+    pub generic_params: Vec<Node<SmolStr>>,
     pub fields: Vec<Node<Field>>,
     pub functions: Vec<Node<Function>>,
     pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Enum {
+    pub attributes: Vec<Node<SmolStr>>,
+    pub name: Node<SmolStr>,
+    pub variants: Vec<Node<Variant>>,
+    pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
+}
+
+/// An enum variant, with zero or more (unnamed) fields.
+/// Eg. `Empty`, or `Rectangle(u256, u256)`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Variant {
+    pub name: Node<SmolStr>,
+    pub fields: Vec<Node<TypeDesc>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -103,6 +170,11 @@ pub enum TypeDesc {
         base: Node<SmolStr>,
         args: Node<Vec<GenericArg>>,
     },
+    /// The type of a function reference, e.g. `fn(u256, bool) -> u8`.
+    Function {
+        params: Vec<Node<TypeDesc>>,
+        return_type: Option<Box<Node<TypeDesc>>>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -130,6 +202,9 @@ pub struct Field {
     pub name: Node<SmolStr>,
     pub typ: Node<TypeDesc>,
     pub value: Option<Node<Expr>>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -141,20 +216,31 @@ pub enum ContractStmt {
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Event {
+    /// The names of the attributes, e.g. `anonymous` for `#[anonymous]`.
+    pub attributes: Vec<Node<SmolStr>>,
     pub name: Node<SmolStr>,
     pub fields: Vec<Node<EventField>>,
     pub pub_qual: Option<Span>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Function {
+    /// The names of the attributes, e.g. `must_use` for `#[must_use]`.
+    pub attributes: Vec<Node<SmolStr>>,
     // qualifier order: `pub unsafe fn`
     pub pub_: Option<Span>,
     pub unsafe_: Option<Span>,
     pub name: Node<SmolStr>,
+    pub generic_params: Vec<Node<SmolStr>>,
     pub args: Vec<Node<FunctionArg>>,
     pub return_type: Option<Node<TypeDesc>>,
     pub body: Vec<Node<FuncStmt>>,
+    /// The `///` doc comment of the item, without the leading slashes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub doc: Option<Node<SmolStr>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -166,15 +252,28 @@ pub struct EventField {
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 pub struct RegularFunctionArg {
+    /// `mut x: T`, which allows the function to modify the value that's
+    /// passed in, e.g. the elements of an array.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mut_: Option<Span>,
     pub name: Node<SmolStr>,
     pub typ: Node<TypeDesc>,
+    /// The value that's passed if a call leaves the argument out, e.g. `0`
+    /// in `fee: u256 = 0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Node<Expr>>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
 #[allow(clippy::large_enum_variant)]
 pub enum FunctionArg {
     Regular(RegularFunctionArg),
-    Zelf,
+    /// `self` or `mut self`. Only functions that take `mut self` can modify
+    /// the fields of `self`.
+    Zelf {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mut_: Option<Span>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -183,9 +282,11 @@ pub enum FuncStmt {
     Return {
         value: Option<Node<Expr>>,
     },
+    /// `let x: u256 = 1`. The type can be omitted if the target is a tuple,
+    /// eg. `let (x, y) = pair`.
     VarDecl {
         target: Node<VarDeclTarget>,
-        typ: Node<TypeDesc>,
+        typ: Option<Node<TypeDesc>>,
         value: Option<Node<Expr>>,
     },
     ConstantDecl {
@@ -211,6 +312,12 @@ pub enum FuncStmt {
         test: Node<Expr>,
         body: Vec<Node<FuncStmt>>,
     },
+    /// `loop:`, or `let x: u256 = loop:` if the loop produces a value. The
+    /// value is given by the `break` statements that exit the loop.
+    Loop {
+        result: Option<(Node<SmolStr>, Node<TypeDesc>)>,
+        body: Vec<Node<FuncStmt>>,
+    },
     If {
         test: Node<Expr>,
         body: Vec<Node<FuncStmt>>,
@@ -228,12 +335,88 @@ pub enum FuncStmt {
         value: Node<Expr>,
     },
     Pass,
-    Break,
+    Break {
+        value: Option<Node<Expr>>,
+    },
     Continue,
     Revert {
         error: Option<Node<Expr>>,
     },
     Unsafe(Vec<Node<FuncStmt>>),
+    /// A block whose integer arithmetic wraps around on overflow, instead of
+    /// reverting.
+    Unchecked(Vec<Node<FuncStmt>>),
+    /// A statement with the `#[checked]` attribute, whose integer arithmetic
+    /// reverts on overflow even if the build profile disables overflow
+    /// checks. Unlike a block, it doesn't have a scope of its own. The parser
+    /// produces a single statement, which lowering may turn into several.
+    Checked(Vec<Node<FuncStmt>>),
+    /// `yul(x: a) -> (y: u256) { y := add(x, 1) }`, a block of inline Yul
+    /// code. The outputs are declared as variables after the block.
+    Yul {
+        inputs: Vec<Node<YulInput>>,
+        outputs: Vec<Node<YulOutput>>,
+        code: Node<SmolStr>,
+    },
+    Match {
+        value: Node<Expr>,
+        arms: Vec<Node<MatchArm>>,
+    },
+    /// `try token.transfer(to, value) as ok:` followed by `catch` clauses.
+    /// The body runs if the external call succeeds. Otherwise the revert data
+    /// of the call is matched against the `catch` clauses, in order.
+    Try {
+        call: Node<Expr>,
+        binding: Option<Node<SmolStr>>,
+        body: Vec<Node<FuncStmt>>,
+        catches: Vec<Node<CatchClause>>,
+    },
+}
+
+/// A `catch` clause of a `try` statement, e.g. `catch MyError as err:`, or
+/// `catch:` to catch any revert.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CatchClause {
+    /// The type of the caught error and the name it's bound to.
+    pub error: Option<(Node<TypeDesc>, Node<SmolStr>)>,
+    pub body: Vec<Node<FuncStmt>>,
+}
+
+/// An input of a `yul` block, e.g. `x: a`. The value of `a` is bound to the
+/// Yul variable `x`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct YulInput {
+    pub name: Node<SmolStr>,
+    pub value: Node<Expr>,
+}
+
+/// An output of a `yul` block, e.g. `y: u256`.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct YulOutput {
+    pub name: Node<SmolStr>,
+    pub typ: Node<TypeDesc>,
+}
+
+/// A `match` statement arm, eg. `Shape::Circle(radius): return radius`
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub struct MatchArm {
+    pub pattern: Node<Pattern>,
+    pub body: Vec<Node<FuncStmt>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+pub enum Pattern {
+    /// `_`, matches any value.
+    Wildcard,
+    /// A name, which matches any value and binds it to the name.
+    Binding(SmolStr),
+    Bool(bool),
+    Num(SmolStr),
+    /// An enum variant, eg. `Shape::Empty`, or `Shape::Rect(width, _)`
+    Variant {
+        path: Path,
+        fields: Option<Vec<Node<Pattern>>>,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
@@ -268,6 +451,13 @@ pub enum Expr {
         op: Node<CompOperator>,
         right: Box<Node<Expr>>,
     },
+    /// `a < b <= c`, which is true if `a < b` and `b <= c` are. Each operand
+    /// is evaluated at most once, and the comparisons stop at the first one
+    /// that's false.
+    ChainedComparison {
+        left: Box<Node<Expr>>,
+        comparisons: Vec<(Node<CompOperator>, Node<Expr>)>,
+    },
     Attribute {
         value: Box<Node<Expr>>,
         attr: Node<SmolStr>,
@@ -280,9 +470,17 @@ pub enum Expr {
         func: Box<Node<Expr>>,
         generic_args: Option<Node<Vec<GenericArg>>>,
         args: Node<Vec<Node<CallArg>>>,
+        /// The struct that the fields which aren't given are taken from, e.g.
+        /// `p` in `Point(x: 1, ..p)`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        base: Option<Box<Node<Expr>>>,
     },
     List {
         elts: Vec<Node<Expr>>,
+        /// The value of the elements which aren't given, e.g. `0` in
+        /// `[1, 2, ..0]`.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        fill: Option<Box<Node<Expr>>>,
     },
     Tuple {
         elts: Vec<Node<Expr>>,
@@ -345,12 +543,30 @@ impl Node<Contract> {
     }
 }
 
+impl Node<Interface> {
+    pub fn name(&self) -> &str {
+        &self.kind.name.kind
+    }
+}
+
 impl Node<Struct> {
     pub fn name(&self) -> &str {
         &self.kind.name.kind
     }
 }
 
+impl Node<Enum> {
+    pub fn name(&self) -> &str {
+        &self.kind.name.kind
+    }
+}
+
+impl Node<Variant> {
+    pub fn name(&self) -> &str {
+        &self.kind.name.kind
+    }
+}
+
 impl Node<Event> {
     pub fn name(&self) -> &str {
         &self.kind.name.kind
@@ -364,6 +580,24 @@ impl Node<Field> {
 }
 
 impl Function {
+    /// The function header without the trailing colon, e.g. `pub fn foo(x: u8) -> u8`.
+    pub fn signature_string(&self) -> String {
+        let mut sig = String::new();
+        if self.is_pub() {
+            sig.push_str("pub ");
+        }
+        sig.push_str(&format!(
+            "fn {}{}({})",
+            self.name.kind,
+            generic_params_string(&self.generic_params),
+            node_comma_joined(&self.args)
+        ));
+        if let Some(return_type) = self.return_type.as_ref() {
+            sig.push_str(&format!(" -> {}", return_type.kind));
+        }
+        sig
+    }
+
     pub fn is_pub(&self) -> bool {
         self.pub_.is_some()
     }
@@ -382,14 +616,62 @@ impl Node<FunctionArg> {
     pub fn name(&self) -> &str {
         match &self.kind {
             FunctionArg::Regular(arg) => &arg.name.kind,
-            FunctionArg::Zelf => "self",
+            FunctionArg::Zelf { .. } => "self",
+        }
+    }
+
+    /// The span of the `mut` keyword, if the parameter is mutable.
+    pub fn mut_span(&self) -> Option<Span> {
+        match &self.kind {
+            FunctionArg::Regular(arg) => arg.mut_,
+            FunctionArg::Zelf { mut_ } => *mut_,
         }
     }
 
     pub fn name_span(&self) -> Span {
         match &self.kind {
             FunctionArg::Regular(arg) => arg.name.span,
-            FunctionArg::Zelf => self.span,
+            // The `self` keyword is at the end of `mut self`.
+            FunctionArg::Zelf { .. } => Span::new(
+                self.span.file_id,
+                self.span.end - "self".len(),
+                self.span.end,
+            ),
+        }
+    }
+}
+
+/// Calls `f` with each statement of `stmts`, in order, and with each statement
+/// nested in their blocks, right after the statement that contains it.
+pub fn for_each_stmt<F>(stmts: &[Node<FuncStmt>], f: &mut F)
+where
+    F: FnMut(&FuncStmt),
+{
+    for node in stmts {
+        f(&node.kind);
+        match &node.kind {
+            FuncStmt::For { body, .. }
+            | FuncStmt::While { body, .. }
+            | FuncStmt::Loop { body, .. } => for_each_stmt(body, f),
+            FuncStmt::If { body, or_else, .. } => {
+                for_each_stmt(body, f);
+                for_each_stmt(or_else, f);
+            }
+            FuncStmt::Unsafe(body) | FuncStmt::Unchecked(body) | FuncStmt::Checked(body) => {
+                for_each_stmt(body, f)
+            }
+            FuncStmt::Match { arms, .. } => {
+                for arm in arms {
+                    for_each_stmt(&arm.kind.body, f);
+                }
+            }
+            FuncStmt::Try { body, catches, .. } => {
+                for_each_stmt(body, f);
+                for clause in catches {
+                    for_each_stmt(&clause.kind.body, f);
+                }
+            }
+            _ => {}
         }
     }
 }
@@ -407,8 +689,11 @@ impl Spanned for ModuleStmt {
             ModuleStmt::Use(inner) => inner.span,
             ModuleStmt::TypeAlias(inner) => inner.span,
             ModuleStmt::Contract(inner) => inner.span,
+            ModuleStmt::Interface(inner) => inner.span,
+            ModuleStmt::Impl(inner) => inner.span,
             ModuleStmt::Constant(inner) => inner.span,
             ModuleStmt::Struct(inner) => inner.span,
+            ModuleStmt::Enum(inner) => inner.span,
             ModuleStmt::Function(inner) => inner.span,
             ModuleStmt::Event(inner) => inner.span,
             ModuleStmt::ParseError(span) => *span,
@@ -438,8 +723,11 @@ impl fmt::Display for ModuleStmt {
             ModuleStmt::Use(node) => write!(f, "{}", node.kind),
             ModuleStmt::TypeAlias(node) => write!(f, "{}", node.kind),
             ModuleStmt::Contract(node) => write!(f, "{}", node.kind),
+            ModuleStmt::Interface(node) => write!(f, "{}", node.kind),
+            ModuleStmt::Impl(node) => write!(f, "{}", node.kind),
             ModuleStmt::Constant(node) => write!(f, "{}", node.kind),
             ModuleStmt::Struct(node) => write!(f, "{}", node.kind),
+            ModuleStmt::Enum(node) => write!(f, "{}", node.kind),
             ModuleStmt::Function(node) => write!(f, "{}", node.kind),
             ModuleStmt::Event(node) => write!(f, "{}", node.kind),
             ModuleStmt::ParseError(span) => {
@@ -451,7 +739,15 @@ impl fmt::Display for ModuleStmt {
 
 impl fmt::Display for Pragma {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "pragma {}", self.version_requirement.kind)
+        match self {
+            Pragma::Version {
+                version_requirement,
+            } => write!(f, "pragma {}", version_requirement.kind),
+            Pragma::Edition { edition } => write!(f, "pragma edition {}", edition.kind),
+            Pragma::Feature { features } => {
+                write!(f, "pragma feature({})", node_comma_joined(features))
+            }
+        }
     }
 }
 
@@ -511,7 +807,17 @@ impl fmt::Display for TypeAlias {
 
 impl fmt::Display for Contract {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "contract {}:", self.name.kind)?;
+        write!(f, "{}", attributes_string(&self.attributes))?;
+        if self.parents.is_empty() {
+            writeln!(f, "contract {}:", self.name.kind)?;
+        } else {
+            writeln!(
+                f,
+                "contract {}({}):",
+                self.name.kind,
+                node_comma_joined(&self.parents)
+            )?;
+        }
         if !self.fields.is_empty() {
             write!(indented(f), "{}\n\n", node_line_joined(&self.fields))?;
         }
@@ -523,9 +829,43 @@ impl fmt::Display for Contract {
     }
 }
 
+impl fmt::Display for Interface {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "interface {}:", self.name.kind)?;
+        if self.functions.is_empty() {
+            write!(indented(f), "pass")
+        } else {
+            let signatures = self
+                .functions
+                .iter()
+                .map(|function| {
+                    format!(
+                        "{}{}",
+                        attributes_string(&function.kind.attributes),
+                        function.kind.signature_string()
+                    )
+                })
+                .collect::<Vec<_>>();
+            write!(indented(f), "{}", signatures.join("\n"))
+        }
+    }
+}
+
+impl fmt::Display for Impl {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "impl {} for {}", self.interface.kind, self.contract.kind)
+    }
+}
+
 impl fmt::Display for Struct {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        writeln!(f, "struct {}:", self.name.kind)?;
+        write!(f, "{}", attributes_string(&self.attributes))?;
+        writeln!(
+            f,
+            "struct {}{}:",
+            self.name.kind,
+            generic_params_string(&self.generic_params)
+        )?;
         if self.fields.is_empty() && self.functions.is_empty() {
             write!(indented(f), "pass")
         } else {
@@ -538,6 +878,28 @@ impl fmt::Display for Struct {
     }
 }
 
+impl fmt::Display for Enum {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", attributes_string(&self.attributes))?;
+        writeln!(f, "enum {}:", self.name.kind)?;
+        if self.variants.is_empty() {
+            write!(indented(f), "pass")
+        } else {
+            write!(indented(f), "{}", node_line_joined(&self.variants))
+        }
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.fields.is_empty() {
+            write!(f, "{}", self.name.kind)
+        } else {
+            write!(f, "{}({})", self.name.kind, node_comma_joined(&self.fields))
+        }
+    }
+}
+
 impl fmt::Display for TypeDesc {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
@@ -548,6 +910,16 @@ impl fmt::Display for TypeDesc {
             TypeDesc::Generic { base, args } => {
                 write!(f, "{}<{}>", base.kind, comma_joined(&args.kind))
             }
+            TypeDesc::Function {
+                params,
+                return_type,
+            } => {
+                write!(f, "fn({})", node_comma_joined(params))?;
+                if let Some(return_type) = return_type {
+                    write!(f, " -> {}", return_type.kind)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -585,6 +957,7 @@ impl fmt::Display for ContractStmt {
 
 impl fmt::Display for Event {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", attributes_string(&self.attributes))?;
         writeln!(f, "event {}:", self.name.kind)?;
         if self.fields.is_empty() {
             write!(indented(f), "pass")
@@ -602,20 +975,8 @@ impl fmt::Display for Node<Function> {
 
 impl fmt::Display for Function {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        if self.is_pub() {
-            write!(f, "pub ")?;
-        }
-        write!(
-            f,
-            "fn {}({})",
-            self.name.kind,
-            node_comma_joined(&self.args)
-        )?;
-        if let Some(return_type) = self.return_type.as_ref() {
-            writeln!(f, " -> {}:", return_type.kind)?;
-        } else {
-            writeln!(f, ":")?;
-        }
+        write!(f, "{}", attributes_string(&self.attributes))?;
+        writeln!(f, "{}:", self.signature_string())?;
         write!(indented(f), "{}", node_line_joined(&self.body))
     }
 }
@@ -631,7 +992,14 @@ impl fmt::Display for EventField {
 
 impl fmt::Display for RegularFunctionArg {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "{}: {}", self.name.kind, self.typ.kind)
+        if self.mut_.is_some() {
+            write!(f, "mut ")?;
+        }
+        write!(f, "{}: {}", self.name.kind, self.typ.kind)?;
+        if let Some(default) = &self.default {
+            write!(f, " = {}", default.kind)?;
+        }
+        Ok(())
     }
 }
 
@@ -639,7 +1007,8 @@ impl fmt::Display for FunctionArg {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         match self {
             FunctionArg::Regular(arg) => write!(f, "{}", arg),
-            FunctionArg::Zelf => write!(f, "self"),
+            FunctionArg::Zelf { mut_: None } => write!(f, "self"),
+            FunctionArg::Zelf { mut_: Some(_) } => write!(f, "mut self"),
         }
     }
 }
@@ -655,11 +1024,14 @@ impl fmt::Display for FuncStmt {
                 }
             }
             FuncStmt::VarDecl { target, typ, value } => {
+                write!(f, "let {}", target.kind)?;
+                if let Some(typ) = typ {
+                    write!(f, ": {}", typ.kind)?;
+                }
                 if let Some(value) = value {
-                    write!(f, "let {}: {} = {}", target.kind, typ.kind, value.kind)
-                } else {
-                    write!(f, "let {}: {}", target.kind, typ.kind)
+                    write!(f, " = {}", value.kind)?;
                 }
+                Ok(())
             }
             FuncStmt::ConstantDecl { name, typ, value } => {
                 write!(f, "const {}: {} = {}", name.kind, typ.kind, value.kind)
@@ -676,6 +1048,14 @@ impl fmt::Display for FuncStmt {
                 writeln!(f, "while {}:", test.kind)?;
                 writeln!(indented(f), "{}", node_line_joined(body))
             }
+            FuncStmt::Loop { result, body } => {
+                if let Some((name, typ)) = result {
+                    writeln!(f, "let {}: {} = loop:", name.kind, typ.kind)?;
+                } else {
+                    writeln!(f, "loop:")?;
+                }
+                writeln!(indented(f), "{}", node_line_joined(body))
+            }
             FuncStmt::If {
                 test,
                 body,
@@ -702,7 +1082,13 @@ impl fmt::Display for FuncStmt {
             }
             FuncStmt::Expr { value } => write!(f, "{}", value.kind),
             FuncStmt::Pass => write!(f, "pass"),
-            FuncStmt::Break => write!(f, "break"),
+            FuncStmt::Break { value } => {
+                if let Some(value) = value {
+                    write!(f, "break {}", value.kind)
+                } else {
+                    write!(f, "break")
+                }
+            }
             FuncStmt::Continue => write!(f, "continue"),
             FuncStmt::Revert { error } => {
                 if let Some(error) = error {
@@ -715,6 +1101,94 @@ impl fmt::Display for FuncStmt {
                 writeln!(f, "unsafe:")?;
                 writeln!(indented(f), "{}", node_line_joined(body))
             }
+            FuncStmt::Unchecked(body) => {
+                writeln!(f, "unchecked:")?;
+                writeln!(indented(f), "{}", node_line_joined(body))
+            }
+            FuncStmt::Checked(body) => {
+                writeln!(f, "#[checked]")?;
+                write!(f, "{}", node_line_joined(body))
+            }
+            FuncStmt::Yul {
+                inputs,
+                outputs,
+                code,
+            } => {
+                write!(f, "yul")?;
+                if !inputs.is_empty() {
+                    write!(f, "({})", node_comma_joined(inputs))?;
+                }
+                if !outputs.is_empty() {
+                    write!(f, " -> ({})", node_comma_joined(outputs))?;
+                }
+                write!(f, " {{ {} }}", code.kind)
+            }
+            FuncStmt::Match { value, arms } => {
+                writeln!(f, "match {}:", value.kind)?;
+                writeln!(indented(f), "{}", node_line_joined(arms))
+            }
+            FuncStmt::Try {
+                call,
+                binding,
+                body,
+                catches,
+            } => {
+                if let Some(binding) = binding {
+                    writeln!(f, "try {} as {}:", call.kind, binding.kind)?;
+                } else {
+                    writeln!(f, "try {}:", call.kind)?;
+                }
+                writeln!(indented(f), "{}", node_line_joined(body))?;
+                write!(f, "{}", node_line_joined(catches))
+            }
+        }
+    }
+}
+
+impl fmt::Display for YulInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name.kind, self.value.kind)
+    }
+}
+
+impl fmt::Display for YulOutput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.name.kind, self.typ.kind)
+    }
+}
+
+impl fmt::Display for MatchArm {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.pattern.kind)?;
+        write!(indented(f), "{}", node_line_joined(&self.body))
+    }
+}
+
+impl fmt::Display for CatchClause {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if let Some((typ, name)) = &self.error {
+            writeln!(f, "catch {} as {}:", typ.kind, name.kind)?;
+        } else {
+            writeln!(f, "catch:")?;
+        }
+        write!(indented(f), "{}", node_line_joined(&self.body))
+    }
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Pattern::Wildcard => write!(f, "_"),
+            Pattern::Binding(name) => write!(f, "{}", name),
+            Pattern::Bool(val) => write!(f, "{}", val),
+            Pattern::Num(num) => write!(f, "{}", num),
+            Pattern::Variant { path, fields } => {
+                if let Some(fields) = fields {
+                    write!(f, "{}({})", path, node_comma_joined(fields))
+                } else {
+                    write!(f, "{}", path)
+                }
+            }
         }
     }
 }
@@ -759,24 +1233,51 @@ impl fmt::Display for Expr {
                 }
             }
             Expr::CompOperation { left, op, right } => {
-                let left = maybe_fmt_left_with_parens(&op.kind, &left.kind);
-                let right = maybe_fmt_right_with_parens(&op.kind, &right.kind);
+                let left = maybe_fmt_comparison_operand_with_parens(&left.kind, true);
+                let right = maybe_fmt_comparison_operand_with_parens(&right.kind, false);
                 write!(f, "{} {} {}", left, op.kind, right)
             }
+            Expr::ChainedComparison { left, comparisons } => {
+                write!(
+                    f,
+                    "{}",
+                    maybe_fmt_comparison_operand_with_parens(&left.kind, true)
+                )?;
+                for (op, operand) in comparisons {
+                    let operand = maybe_fmt_comparison_operand_with_parens(&operand.kind, false);
+                    write!(f, " {} {}", op.kind, operand)?;
+                }
+                Ok(())
+            }
             Expr::Attribute { value, attr } => write!(f, "{}.{}", value.kind, attr.kind),
             Expr::Subscript { value, index } => write!(f, "{}[{}]", value.kind, index.kind),
             Expr::Call {
                 func,
                 generic_args,
                 args,
+                base,
             } => {
                 write!(f, "{}", func.kind)?;
                 if let Some(generic_args) = generic_args {
                     write!(f, "<{}>", comma_joined(&generic_args.kind))?;
                 }
-                write!(f, "({})", node_comma_joined(&args.kind))
+                match base {
+                    Some(base) if args.kind.is_empty() => write!(f, "(..{})", base.kind),
+                    Some(base) => write!(f, "({}, ..{})", node_comma_joined(&args.kind), base.kind),
+                    None => write!(f, "({})", node_comma_joined(&args.kind)),
+                }
+            }
+            Expr::List { elts, fill: None } => write!(f, "[{}]", node_comma_joined(elts)),
+            Expr::List {
+                elts,
+                fill: Some(fill),
+            } => {
+                if elts.is_empty() {
+                    write!(f, "[..{}]", fill.kind)
+                } else {
+                    write!(f, "[{}, ..{}]", node_comma_joined(elts), fill.kind)
+                }
             }
-            Expr::List { elts } => write!(f, "[{}]", node_comma_joined(elts)),
             Expr::Tuple { elts } => write!(f, "({})", node_comma_joined(elts)),
             Expr::Bool(bool) => write!(f, "{}", bool),
             Expr::Name(name) => write!(f, "{}", name),
@@ -861,6 +1362,22 @@ fn node_comma_joined(nodes: &[Node<impl fmt::Display>]) -> String {
     comma_joined(&nodes.iter().map(|node| &node.kind).collect::<Vec<_>>())
 }
 
+fn generic_params_string(params: &[Node<SmolStr>]) -> String {
+    if params.is_empty() {
+        String::new()
+    } else {
+        format!("<{}>", node_comma_joined(params))
+    }
+}
+
+/// The attributes, each on its own line.
+fn attributes_string(attributes: &[Node<SmolStr>]) -> String {
+    attributes
+        .iter()
+        .map(|attribute| format!("#[{}]\n", attribute.kind))
+        .collect()
+}
+
 fn comma_joined(items: &[impl fmt::Display]) -> String {
     items
         .iter()
@@ -957,6 +1474,16 @@ fn maybe_fmt_right_with_parens(op: &impl InfixBindingPower, expr: &Expr) -> Stri
     }
 }
 
+/// Operands of a comparison that are comparisons themselves are always
+/// parenthesized, since `a < b < c` is a chained comparison.
+fn maybe_fmt_comparison_operand_with_parens(expr: &Expr, is_left: bool) -> String {
+    match expr {
+        Expr::CompOperation { .. } | Expr::ChainedComparison { .. } => format!("({})", expr),
+        _ if is_left => maybe_fmt_left_with_parens(&CompOperator::Lt, expr),
+        _ => maybe_fmt_right_with_parens(&CompOperator::Lt, expr),
+    }
+}
+
 fn maybe_fmt_operand_with_parens(op: &impl PrefixBindingPower, expr: &Expr) -> String {
     if op.prefix_binding_power() > expr_left_binding_power(expr) {
         format!("({})", expr)
@@ -974,6 +1501,9 @@ fn expr_left_binding_power(expr: &Expr) -> u8 {
         Expr::BinOperation { op, .. } => op.kind.infix_binding_power().0,
         Expr::UnaryOperation { op, .. } => op.kind.prefix_binding_power(),
         Expr::CompOperation { op, .. } => op.kind.infix_binding_power().0,
+        Expr::ChainedComparison { comparisons, .. } => {
+            comparisons[0].0.kind.infix_binding_power().0
+        }
         Expr::Attribute { .. } => max_power,
         Expr::Subscript { .. } => max_power,
         Expr::Call { .. } => max_power,
@@ -997,6 +1527,9 @@ fn expr_right_binding_power(expr: &Expr) -> u8 {
         Expr::BinOperation { op, .. } => op.kind.infix_binding_power().1,
         Expr::UnaryOperation { op, .. } => op.kind.prefix_binding_power(),
         Expr::CompOperation { op, .. } => op.kind.infix_binding_power().1,
+        Expr::ChainedComparison { comparisons, .. } => {
+            comparisons[0].0.kind.infix_binding_power().1
+        }
         Expr::Attribute { .. } => max_power,
         Expr::Subscript { .. } => max_power,
         Expr::Call { .. } => max_power,
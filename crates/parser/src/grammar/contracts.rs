@@ -1,10 +1,14 @@
-use super::functions::parse_fn_def;
-use super::types::{parse_event_def, parse_field, parse_opt_qualifier};
+use super::functions::{parse_fn_def, parse_fn_sig};
+use super::types::{
+    parse_attributes, parse_doc_comment, parse_event_def, parse_field, parse_opt_qualifier,
+    reject_attributes, reject_doc_comment,
+};
 
-use crate::ast::{Contract, ContractStmt};
+use crate::ast::{Contract, ContractStmt, Impl, Interface};
 use crate::grammar::functions::parse_single_word_stmt;
 use crate::node::{Node, Span};
-use crate::{ParseFailed, ParseResult, Parser, TokenKind};
+use crate::{Label, ParseFailed, ParseResult, Parser, Token, TokenKind};
+use smol_str::SmolStr;
 
 // Rule: all "statement" level parse functions consume their trailing
 // newline(s), either directly or via a function they call.
@@ -12,11 +16,13 @@ use crate::{ParseFailed, ParseResult, Parser, TokenKind};
 // trailing newlines to check whether it's followed by an `else` block, and is
 // done for all statements for consistency.
 
-/// Parse a contract definition.
+/// Parse a contract definition. The attributes and the optional `pub`
+/// qualifier must be parsed by the caller, and passed in.
 /// # Panics
 /// Panics if the next token isn't `contract`.
 pub fn parse_contract_def(
     par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
     contract_pub_qual: Option<Span>,
 ) -> ParseResult<Node<Contract>> {
     let contract_tok = par.assert(TokenKind::Contract);
@@ -40,13 +46,21 @@ pub fn parse_contract_def(
         |_| vec!["Note: `contract` must be followed by a name, which must start with a letter and contain only letters, numbers, or underscores".into()],
     )?;
 
-    let header_span = contract_tok.span + contract_name.span;
+    let parents = if par.peek() == Some(TokenKind::ParenOpen) {
+        parse_contract_parents(par)?
+    } else {
+        vec![]
+    };
+
+    let header_span = contract_tok.span + contract_name.span + parents.last();
     par.enter_block(header_span, "contract definition")?;
 
     let mut fields = vec![];
     let mut defs = vec![];
 
     loop {
+        let doc = parse_doc_comment(par)?;
+        let attributes = parse_attributes(par)?;
         let mut pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
         let const_qual = parse_opt_qualifier(par, TokenKind::Const);
         if pub_qual.is_none() && const_qual.is_some() && par.peek() == Some(TokenKind::Pub) {
@@ -56,10 +70,23 @@ pub fn parse_contract_def(
                 "`const pub` should be written `pub const`",
             );
         }
+        if !matches!(
+            par.peek(),
+            Some(TokenKind::Fn | TokenKind::Unsafe | TokenKind::Event)
+        ) {
+            reject_attributes(par, &attributes);
+        }
+        if !matches!(
+            par.peek(),
+            Some(TokenKind::Name | TokenKind::Fn | TokenKind::Unsafe | TokenKind::Event)
+        ) {
+            reject_doc_comment(par, &doc);
+        }
 
         match par.peek() {
             Some(TokenKind::Name) => {
-                let field = parse_field(par, pub_qual, const_qual)?;
+                let mut field = parse_field(par, pub_qual, const_qual)?;
+                field.kind.doc = doc;
                 if !defs.is_empty() {
                     par.error(field.span, "contract field definitions must come before any function or event definitions");
                 }
@@ -72,7 +99,9 @@ pub fn parse_contract_def(
                         "`const` qualifier can't be used with function definitions",
                     );
                 }
-                defs.push(ContractStmt::Function(parse_fn_def(par, pub_qual)?));
+                let mut function = parse_fn_def(par, attributes, pub_qual)?;
+                function.kind.doc = doc;
+                defs.push(ContractStmt::Function(function));
             }
             Some(TokenKind::Event) => {
                 if let Some(span) = pub_qual {
@@ -87,7 +116,9 @@ pub fn parse_contract_def(
                         "`const` qualifier can't be used with event definitions",
                     );
                 }
-                defs.push(ContractStmt::Event(parse_event_def(par, None)?));
+                let mut event = parse_event_def(par, attributes, None)?;
+                event.kind.doc = doc;
+                defs.push(ContractStmt::Event(event));
             }
             Some(TokenKind::Pass) => {
                 parse_single_word_stmt(par)?;
@@ -112,10 +143,160 @@ pub fn parse_contract_def(
     let span = header_span + contract_pub_qual + fields.last() + defs.last();
     Ok(Node::new(
         Contract {
+            attributes,
             name: Node::new(contract_name.text.into(), contract_name.span),
+            parents,
             fields,
             body: defs,
             pub_qual: contract_pub_qual,
+            doc: None,
+        },
+        span,
+    ))
+}
+
+/// Parse the parenthesized list of contracts that a contract inherits from,
+/// e.g. `(Ownable, Pausable)`.
+/// # Panics
+/// Panics if the next token isn't `(`.
+fn parse_contract_parents(par: &mut Parser) -> ParseResult<Vec<Node<SmolStr>>> {
+    par.assert(TokenKind::ParenOpen);
+    let mut parents = vec![];
+    loop {
+        if par.peek_or_err()? == TokenKind::ParenClose {
+            par.next()?;
+            break;
+        }
+        let name = par.expect_with_notes(
+            TokenKind::Name,
+            "failed to parse contract definition",
+            |_| vec!["Note: a contract can only inherit from contracts, which are listed by name, e.g. `contract Token(Ownable, Pausable):`".into()],
+        )?;
+        parents.push(Node::new(name.text.into(), name.span));
+        match par.peek_or_err()? {
+            TokenKind::Comma => {
+                par.next()?;
+            }
+            TokenKind::ParenClose => {}
+            _ => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse contract definition",
+                    vec!["Note: expected `,` or `)`".into()],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+    Ok(parents)
+}
+
+/// Parse an interface definition.
+/// # Panics
+/// Panics if the next token isn't `interface`.
+pub fn parse_interface_def(
+    par: &mut Parser,
+    interface_pub_qual: Option<Span>,
+) -> ParseResult<Node<Interface>> {
+    let interface_tok = par.assert(TokenKind::Interface);
+
+    // interface Token:
+    //   pub fn balance_of(owner: address) -> u256
+    //   pub fn transfer(to: address, value: u256) -> bool
+
+    let interface_name = par.expect_with_notes(
+        TokenKind::Name,
+        "failed to parse interface definition",
+        |_| vec!["Note: `interface` must be followed by a name, which must start with a letter and contain only letters, numbers, or underscores".into()],
+    )?;
+
+    let header_span = interface_tok.span + interface_name.span;
+    par.enter_block(header_span, "interface definition")?;
+
+    let mut functions = vec![];
+    loop {
+        let doc = parse_doc_comment(par)?;
+        let attributes = parse_attributes(par)?;
+        let pub_qual = parse_opt_qualifier(par, TokenKind::Pub);
+        if !matches!(par.peek(), Some(TokenKind::Fn | TokenKind::Unsafe)) {
+            reject_attributes(par, &attributes);
+            reject_doc_comment(par, &doc);
+        }
+        match par.peek() {
+            Some(TokenKind::Fn | TokenKind::Unsafe) => {
+                let mut function = parse_fn_sig(par, attributes, pub_qual)?;
+                function.kind.doc = doc;
+                if let Some(span) = function.kind.unsafe_ {
+                    par.error(
+                        span,
+                        "`unsafe` qualifier can't be used with interface functions",
+                    );
+                }
+                if par.peek() == Some(TokenKind::Colon) {
+                    par.fancy_error(
+                        "interface functions can't have a body",
+                        vec![Label::primary(
+                            function.span,
+                            "this function signature must be followed by a newline",
+                        )],
+                        vec![format!("Example: `{}`", function.kind.signature_string())],
+                    );
+                    return Err(ParseFailed);
+                }
+                par.expect_newline("interface function")?;
+                functions.push(function);
+            }
+            Some(TokenKind::Pass) => {
+                parse_single_word_stmt(par)?;
+            }
+            Some(TokenKind::Dedent) => {
+                par.next()?;
+                break;
+            }
+            None => break,
+            Some(_) => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse interface definition body",
+                    vec!["Note: an interface can only contain function signatures, e.g. `pub fn balance_of(owner: address) -> u256`".into()],
+                );
+                return Err(ParseFailed);
+            }
+        };
+    }
+
+    let span = header_span + interface_pub_qual + functions.last();
+    Ok(Node::new(
+        Interface {
+            name: Node::new(interface_name.text.into(), interface_name.span),
+            functions,
+            pub_qual: interface_pub_qual,
+            doc: None,
+        },
+        span,
+    ))
+}
+
+/// Parse a declaration that a contract implements an interface, e.g.
+/// `impl Token for MyToken`.
+/// # Panics
+/// Panics if the next token isn't `impl`.
+pub fn parse_impl(par: &mut Parser) -> ParseResult<Node<Impl>> {
+    let impl_tok = par.assert(TokenKind::Impl);
+    fn notes(_: &Token) -> Vec<String> {
+        vec!["Example: `impl Token for MyToken`".into()]
+    }
+    let interface = par.expect_with_notes(TokenKind::Name, "failed to parse `impl`", notes)?;
+    par.expect_with_notes(TokenKind::For, "failed to parse `impl`", notes)?;
+    let contract = par.expect_with_notes(TokenKind::Name, "failed to parse `impl`", notes)?;
+
+    let span = impl_tok.span + contract.span;
+    Ok(Node::new(
+        Impl {
+            interface: interface.into(),
+            contract: contract.into(),
         },
         span,
     ))
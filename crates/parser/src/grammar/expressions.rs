@@ -4,6 +4,7 @@ use crate::{Label, ParseFailed, ParseResult, Parser, Token, TokenKind};
 
 use super::types::parse_generic_args;
 
+use fe_common::numeric;
 use if_chain::if_chain;
 
 // Expressions are parsed in Pratt's top-down operator precedence style.
@@ -19,22 +20,27 @@ pub fn parse_expr(par: &mut Parser) -> ParseResult<Node<Expr>> {
 /// tightly than given binding power.
 pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<Expr>> {
     let mut expr_head = parse_expr_head(par)?;
+    // `true` if `expr_head` is a comparison that was parsed in this loop, and
+    // can be extended to a chained comparison like `a < b < c`.
+    let mut is_comparison = false;
 
     while let Some(op) = par.peek() {
         if let Some(lbp) = postfix_binding_power(op) {
             if lbp < min_bp {
                 break;
             }
+            is_comparison = false;
 
             expr_head = match op {
                 TokenKind::ParenOpen => {
-                    let args = parse_call_args(par)?;
+                    let (args, base) = parse_call_args_with_base(par)?;
                     let span = expr_head.span + args.span;
                     Node::new(
                         Expr::Call {
                             func: Box::new(expr_head),
                             generic_args: None,
                             args,
+                            base,
                         },
                         span,
                     )
@@ -83,7 +89,7 @@ pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<
             if_chain! {
                 if let Ok(generic_args) = parse_generic_args(&mut bt_par);
                 if matches!(bt_par.peek(), Some(TokenKind::ParenOpen));
-                if let Ok(args) = parse_call_args(&mut bt_par);
+                if let Ok((args, base)) = parse_call_args_with_base(&mut bt_par);
                 then {
                     let span = expr_head.span + args.span;
                     expr_head = Node::new(
@@ -91,10 +97,12 @@ pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<
                             func: Box::new(expr_head),
                             generic_args: Some(generic_args),
                             args,
+                            base,
                         },
                         span,
                     );
                     bt_par.accept();
+                    is_comparison = false;
                     continue;
                 }
             }
@@ -107,7 +115,21 @@ pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<
 
             let op_tok = par.next()?;
             let rhs = parse_expr_with_min_bp(par, rbp)?;
-            expr_head = infix_op(par, expr_head, &op_tok, rhs)?;
+            let is_comp_op = matches!(
+                op_tok.kind,
+                TokenKind::Lt
+                    | TokenKind::LtEq
+                    | TokenKind::Gt
+                    | TokenKind::GtEq
+                    | TokenKind::NotEq
+                    | TokenKind::EqEq
+            );
+            expr_head = if is_comparison && is_comp_op {
+                chain_comparison(expr_head, &op_tok, rhs)
+            } else {
+                infix_op(par, expr_head, &op_tok, rhs)?
+            };
+            is_comparison = is_comp_op;
             continue;
         }
         break;
@@ -118,9 +140,33 @@ pub fn parse_expr_with_min_bp(par: &mut Parser, min_bp: u8) -> ParseResult<Node<
 
 /// Parse call arguments
 pub fn parse_call_args(par: &mut Parser) -> ParseResult<Node<Vec<Node<CallArg>>>> {
+    let (args, base) = parse_call_args_with_base(par)?;
+    if let Some(base) = base {
+        par.fancy_error(
+            "unexpected struct update syntax",
+            vec![Label::primary(
+                base.span,
+                "`..` can only be used in a call to a struct constructor",
+            )],
+            vec![],
+        );
+        return Err(ParseFailed);
+    }
+    Ok(args)
+}
+
+/// The arguments of a call, and the base of a struct update if there is one.
+type CallArgsWithBase = (Node<Vec<Node<CallArg>>>, Option<Box<Node<Expr>>>);
+
+/// Parse the argument list of a call, which may end with the base of a struct
+/// update, e.g. `(x: 1, ..p)`.
+/// # Panics
+/// Panics if the next token isn't `(`.
+pub fn parse_call_args_with_base(par: &mut Parser) -> ParseResult<CallArgsWithBase> {
     use TokenKind::*;
     let lparen = par.assert(ParenOpen);
     let mut args = vec![];
+    let mut base = None;
     let mut span = lparen.span;
     loop {
         if par.peek_or_err()? == ParenClose {
@@ -128,6 +174,19 @@ pub fn parse_call_args(par: &mut Parser) -> ParseResult<Node<Vec<Node<CallArg>>>
             break;
         }
 
+        if par.peek_or_err()? == DotDot {
+            par.next()?;
+            base = Some(Box::new(parse_expr(par)?));
+            span += par
+                .expect_with_notes(
+                    ParenClose,
+                    "failed to parse function call argument list",
+                    |_| vec!["Note: the `..` base of a struct update must be the last argument, without a trailing comma.".into()],
+                )?
+                .span;
+            break;
+        }
+
         let arg = parse_expr(par)?;
         match par.peek_or_err()? {
             TokenKind::Eq => {
@@ -212,7 +271,7 @@ pub fn parse_call_args(par: &mut Parser) -> ParseResult<Node<Vec<Node<CallArg>>>
         }
     }
 
-    Ok(Node::new(args, span))
+    Ok((Node::new(args, span), base))
 }
 
 /// Try to build an expression starting with the given token.
@@ -222,7 +281,7 @@ fn parse_expr_head(par: &mut Parser) -> ParseResult<Node<Expr>> {
     match par.peek_or_err()? {
         Name | SelfValue | Int | Hex | Octal | Binary | Text | True | False => {
             let tok = par.next()?;
-            Ok(atom(par, &tok))
+            atom(par, &tok)
         }
         Plus | Minus | Not | Tilde => {
             let op = par.next()?;
@@ -297,13 +356,52 @@ fn postfix_binding_power(op: TokenKind) -> Option<u8> {
     }
 }
 
-/// Parse a square-bracket list expression, eg. `[1, 2, x]`
+/// Parse a square-bracket list expression, eg. `[1, 2, x]`, which may end
+/// with the value of the remaining elements, eg. `[1, 2, ..0]`.
 fn parse_list(par: &mut Parser) -> ParseResult<Node<Expr>> {
-    let lbracket = par.assert(TokenKind::BracketOpen);
-    let elts = parse_expr_list(par, TokenKind::BracketClose, None)?;
-    let rbracket = par.assert(TokenKind::BracketClose);
+    use TokenKind::*;
+    let lbracket = par.assert(BracketOpen);
+    let mut elts = vec![];
+    let mut fill = None;
+    loop {
+        match par.peek_or_err()? {
+            BracketClose => break,
+            DotDot => {
+                par.next()?;
+                fill = Some(Box::new(parse_expr(par)?));
+                if par.peek_or_err()? != BracketClose {
+                    let tok = par.next()?;
+                    par.fancy_error(
+                        "failed to parse list expression",
+                        vec![Label::primary(tok.span, "unexpected token")],
+                        vec!["Note: the `..` fill value of a list must be its last element, without a trailing comma.".into()],
+                    );
+                    return Err(ParseFailed);
+                }
+                break;
+            }
+            _ => {}
+        }
+        elts.push(parse_expr(par)?);
+        match par.peek_or_err()? {
+            Comma => {
+                par.next()?;
+            }
+            BracketClose => break,
+            _ => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "Unexpected token while parsing list of expressions",
+                    vec![],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+    let rbracket = par.assert(BracketClose);
     let span = lbracket.span + rbracket.span;
-    Ok(Node::new(Expr::List { elts }, span))
+    Ok(Node::new(Expr::List { elts, fill }, span))
 }
 
 /// Parse a paren-wrapped expression, which might turn out to be a tuple
@@ -385,12 +483,22 @@ fn parse_expr_list(
 /* node building utils */
 
 /// Create an "atom" expr from the given `Token` (`Name`, `Num`, `Bool`, etc)
-fn atom(par: &mut Parser, tok: &Token) -> Node<Expr> {
+fn atom(par: &mut Parser, tok: &Token) -> ParseResult<Node<Expr>> {
     use TokenKind::*;
 
     let expr = match tok.kind {
         Name | SelfValue => Expr::Name(tok.text.into()),
-        Int | Hex | Octal | Binary => Expr::Num(tok.text.into()),
+        Int | Hex | Octal | Binary => {
+            if let Err(err) = numeric::Literal::new(tok.text).validate() {
+                par.fancy_error(
+                    "invalid number literal",
+                    vec![Label::primary(tok.span, err.to_string())],
+                    vec![],
+                );
+                return Err(ParseFailed);
+            }
+            Expr::Num(tok.text.into())
+        }
         True | False => Expr::Bool(tok.kind == True),
         Text => {
             if let Some(string) = unescape_string(tok.text) {
@@ -402,7 +510,7 @@ fn atom(par: &mut Parser, tok: &Token) -> Node<Expr> {
         }
         _ => panic!("Unexpected atom token: {:?}", tok),
     };
-    Node::new(expr, tok.span)
+    Ok(Node::new(expr, tok.span))
 }
 
 fn unescape_string(quoted_string: &str) -> Option<String> {
@@ -577,6 +685,31 @@ fn unary_op(par: &mut Parser, op: &Token, operand: Node<Expr>) -> ParseResult<No
 
 /// Create an `Expr::CompOperation` node for the given operator and operands.
 fn comp_op(left: Node<Expr>, op: &Token, right: Node<Expr>) -> Node<Expr> {
+    let span = left.span + right.span;
+    Node::new(
+        Expr::CompOperation {
+            left: Box::new(left),
+            op: comp_operator(op),
+            right: Box::new(right),
+        },
+        span,
+    )
+}
+
+/// Extends the comparison `left`, e.g. `a < b`, with another comparison
+/// to a chained comparison like `a < b < c`.
+fn chain_comparison(left: Node<Expr>, op: &Token, right: Node<Expr>) -> Node<Expr> {
+    let span = left.span + right.span;
+    let (left, mut comparisons) = match left.kind {
+        Expr::CompOperation { left, op, right } => (left, vec![(op, *right)]),
+        Expr::ChainedComparison { left, comparisons } => (left, comparisons),
+        _ => unreachable!(),
+    };
+    comparisons.push((comp_operator(op), right));
+    Node::new(Expr::ChainedComparison { left, comparisons }, span)
+}
+
+fn comp_operator(op: &Token) -> Node<ast::CompOperator> {
     use ast::CompOperator;
     use TokenKind::*;
     let astop = match op.kind {
@@ -589,13 +722,5 @@ fn comp_op(left: Node<Expr>, op: &Token, right: Node<Expr>) -> Node<Expr> {
         EqEq => CompOperator::Eq,
         _ => panic!(),
     };
-    let span = left.span + right.span;
-    Node::new(
-        Expr::CompOperation {
-            left: Box::new(left),
-            op: Node::new(astop, op.span),
-            right: Box::new(right),
-        },
-        span,
-    )
+    Node::new(astop, op.span)
 }
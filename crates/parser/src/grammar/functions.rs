@@ -1,16 +1,44 @@
 use super::expressions::{parse_call_args, parse_expr};
-use super::types::parse_type_desc;
+use super::types::{
+    parse_attributes, parse_doc_comment, parse_generic_params, parse_type_desc, reject_doc_comment,
+};
 
 use crate::ast::{
-    BinOperator, Expr, FuncStmt, Function, FunctionArg, RegularFunctionArg, VarDeclTarget,
+    BinOperator, CatchClause, Expr, FuncStmt, Function, FunctionArg, MatchArm, Path, Pattern,
+    RegularFunctionArg, TypeDesc, UnaryOperator, VarDeclTarget, YulInput, YulOutput,
 };
 use crate::lexer::TokenKind;
 use crate::node::{Node, Span};
 use crate::{Label, ParseFailed, ParseResult, Parser};
+use smol_str::SmolStr;
+
+/// Parse a function definition. The attributes and the optional `pub`
+/// qualifier must be parsed by the caller, and passed in. Next token must be
+/// `unsafe` or `fn`.
+pub fn parse_fn_def(
+    par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
+    pub_qual: Option<Span>,
+) -> ParseResult<Node<Function>> {
+    let mut function = parse_fn_sig(par, attributes, pub_qual)?;
 
-/// Parse a function definition. The optional `pub` qualifier must be parsed by
-/// the caller, and passed in. Next token must be `unsafe` or `fn`.
-pub fn parse_fn_def(par: &mut Parser, mut pub_qual: Option<Span>) -> ParseResult<Node<Function>> {
+    // TODO: allow multi-line return type? `fn f()\n ->\n u8`
+    // TODO: allow single-line fn defs?
+    par.enter_block(function.span, "function definition")?;
+    function.kind.body = parse_block_stmts(par)?;
+    function.span += function.kind.body.last();
+    Ok(function)
+}
+
+/// Parse a function signature, up to and including the return type. The body
+/// of the returned function is empty. The attributes and the optional `pub`
+/// qualifier must be parsed by the caller, and passed in. Next token must be
+/// `unsafe` or `fn`.
+pub fn parse_fn_sig(
+    par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
+    mut pub_qual: Option<Span>,
+) -> ParseResult<Node<Function>> {
     let unsafe_qual = par.optional(TokenKind::Unsafe).map(|tok| tok.span);
     if let Some(pub_) = par.optional(TokenKind::Pub) {
         let unsafe_span =
@@ -30,6 +58,14 @@ pub fn parse_fn_def(par: &mut Parser, mut pub_qual: Option<Span>) -> ParseResult
     let name = par.expect(TokenKind::Name, "failed to parse function definition")?;
     let mut span = fn_tok.span + unsafe_qual + pub_qual + name.span;
 
+    let generic_params = if par.peek() == Some(TokenKind::Lt) {
+        let node = parse_generic_params(par)?;
+        span += node.span;
+        node.kind
+    } else {
+        vec![]
+    };
+
     let args = match par.peek_or_err()? {
         TokenKind::ParenOpen => {
             let node = parse_fn_param_list(par)?;
@@ -76,19 +112,17 @@ pub fn parse_fn_def(par: &mut Parser, mut pub_qual: Option<Span>) -> ParseResult
     };
     span += return_type.as_ref();
 
-    // TODO: allow multi-line return type? `fn f()\n ->\n u8`
-    // TODO: allow single-line fn defs?
-    par.enter_block(span, "function definition")?;
-    let body = parse_block_stmts(par)?;
-    span += body.last();
     Ok(Node::new(
         Function {
+            attributes,
             pub_: pub_qual,
             unsafe_: unsafe_qual,
             name: name.into(),
+            generic_params,
             args,
             return_type,
-            body,
+            body: vec![],
+            doc: None,
         },
         span,
     ))
@@ -103,12 +137,17 @@ fn parse_fn_param_list(par: &mut Parser) -> ParseResult<Node<Vec<Node<FunctionAr
                 span += par.next()?.span;
                 break;
             }
-            TokenKind::Name | TokenKind::SelfValue => {
+            TokenKind::Name | TokenKind::SelfValue | TokenKind::Mut => {
+                let mut_ = if par.peek() == Some(TokenKind::Mut) {
+                    Some(par.next()?.span)
+                } else {
+                    None
+                };
                 let name = par.next()?;
 
                 if name.kind == TokenKind::SelfValue {
-                    params.push(Node::new(FunctionArg::Zelf, name.span));
-                } else {
+                    params.push(Node::new(FunctionArg::Zelf { mut_ }, name.span + mut_));
+                } else if name.kind == TokenKind::Name {
                     par.expect_with_notes(
                         TokenKind::Colon,
                         "failed to parse function parameter",
@@ -121,14 +160,29 @@ fn parse_fn_param_list(par: &mut Parser) -> ParseResult<Node<Vec<Node<FunctionAr
                         },
                     )?;
                     let typ = parse_type_desc(par)?;
-                    let param_span = name.span + typ.span;
+                    let default = if par.peek() == Some(TokenKind::Eq) {
+                        par.next()?;
+                        Some(parse_expr(par)?)
+                    } else {
+                        None
+                    };
+                    let param_span = name.span + mut_ + typ.span + default.as_ref();
                     params.push(Node::new(
                         FunctionArg::Regular(RegularFunctionArg {
+                            mut_,
                             name: Node::new(name.text.into(), name.span),
                             typ,
+                            default,
                         }),
                         param_span,
                     ));
+                } else {
+                    par.unexpected_token_error(
+                        name.span,
+                        "failed to parse function parameter",
+                        vec!["Note: `mut` must be followed by `self` or a parameter name".into()],
+                    );
+                    return Err(ParseFailed);
                 }
 
                 if par.peek() == Some(TokenKind::Comma) {
@@ -194,7 +248,7 @@ fn aug_assign_op(tk: TokenKind) -> Option<BinOperator> {
     Some(op)
 }
 
-/// Parse a `continue`, `break`, `pass`, or `revert` statement.
+/// Parse a `continue` or `pass` statement.
 ///
 /// # Panics
 /// Panics if the next token isn't one of the above.
@@ -203,7 +257,6 @@ pub fn parse_single_word_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
     par.expect_newline(tok.kind.describe())?;
     let stmt = match tok.kind {
         TokenKind::Continue => FuncStmt::Continue,
-        TokenKind::Break => FuncStmt::Break,
         TokenKind::Pass => FuncStmt::Pass,
         _ => panic!(),
     };
@@ -219,14 +272,28 @@ pub fn parse_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
         For => parse_for_stmt(par),
         If => parse_if_stmt(par),
         While => parse_while_stmt(par),
+        Loop => parse_loop_stmt(par),
+        Match => parse_match_stmt(par),
         Return => parse_return_stmt(par),
         Assert => parse_assert_stmt(par),
         Revert => parse_revert_stmt(par),
-        Continue | Break | Pass => parse_single_word_stmt(par),
+        Break => parse_break_stmt(par),
+        Continue | Pass => parse_single_word_stmt(par),
         Emit => parse_emit_statement(par),
         Let => parse_var_decl(par),
         Const => parse_const_decl(par),
         Unsafe => parse_unsafe_block(par),
+        DocComment => {
+            let doc = parse_doc_comment(par)?;
+            reject_doc_comment(par, &doc);
+            parse_stmt(par)
+        }
+        Attribute => parse_attributed_stmt(par),
+        Name if par.peeked_text() == "yul" && is_yul_block(par) => parse_yul_block(par),
+        Name if par.peeked_text() == "unchecked" && is_unchecked_block(par) => {
+            parse_unchecked_block(par)
+        }
+        Name if par.peeked_text() == "try" && is_try_stmt(par) => parse_try_stmt(par),
         _ => parse_expr_stmt(par),
     }
 }
@@ -241,20 +308,45 @@ fn parse_var_decl(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
             let typ = parse_type_desc(par)?;
             let value = if par.peek() == Some(TokenKind::Eq) {
                 par.next()?;
+                if par.peek() == Some(TokenKind::Loop) {
+                    return parse_loop_decl(par, let_tkn.span, target, typ);
+                }
                 Some(parse_expr(par)?)
             } else {
                 None
             };
             let span = let_tkn.span + target.span + typ.span + value.as_ref();
             par.expect_newline("variable declaration")?;
-            Node::new(FuncStmt::VarDecl { target, typ, value }, span)
+            Node::new(
+                FuncStmt::VarDecl {
+                    target,
+                    typ: Some(typ),
+                    value,
+                },
+                span,
+            )
+        }
+        // The type of the variable is inferred from its value.
+        Some(TokenKind::Eq) => {
+            par.next()?;
+            let value = parse_expr(par)?;
+            let span = let_tkn.span + value.span;
+            par.expect_newline("variable declaration")?;
+            Node::new(
+                FuncStmt::VarDecl {
+                    target,
+                    typ: None,
+                    value: Some(value),
+                },
+                span,
+            )
         }
         _ => {
             par.fancy_error(
                 "failed to parse variable declaration",
                 vec![Label::primary(
                     expr.span,
-                    "Must be followed by type annotation",
+                    "Must be followed by a type annotation or a value",
                 )],
                 vec!["Example: `let x: u8 = 1`".into()],
             );
@@ -439,6 +531,172 @@ pub fn parse_while_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
     Ok(Node::new(FuncStmt::While { test, body }, span))
 }
 
+/// Parse a `loop` statement.
+///
+/// # Panics
+/// Panics if the next token isn't `loop`.
+pub fn parse_loop_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let loop_tok = par.assert(TokenKind::Loop);
+    par.enter_block(loop_tok.span, "`loop` statement")?;
+    let body = parse_block_stmts(par)?;
+    let span = loop_tok.span + body.last();
+
+    Ok(Node::new(FuncStmt::Loop { result: None, body }, span))
+}
+
+/// Parse the `loop` of a `let x: u256 = loop:` statement, whose `let` keyword,
+/// variable name, and type have already been parsed.
+fn parse_loop_decl(
+    par: &mut Parser,
+    let_span: Span,
+    target: Node<VarDeclTarget>,
+    typ: Node<TypeDesc>,
+) -> ParseResult<Node<FuncStmt>> {
+    let loop_tok = par.assert(TokenKind::Loop);
+    let name = match target.kind {
+        VarDeclTarget::Name(name) => Node::new(name, target.span),
+        VarDeclTarget::Tuple(_) => {
+            par.fancy_error(
+                "failed to parse `loop` variable declaration",
+                vec![Label::primary(
+                    target.span,
+                    "the value of a `loop` can't be destructured",
+                )],
+                vec!["Example: `let x: u256 = loop:`".into()],
+            );
+            return Err(ParseFailed);
+        }
+    };
+    par.enter_block(let_span + loop_tok.span, "`loop` statement")?;
+    let body = parse_block_stmts(par)?;
+    let span = let_span + loop_tok.span + body.last();
+
+    Ok(Node::new(
+        FuncStmt::Loop {
+            result: Some((name, typ)),
+            body,
+        },
+        span,
+    ))
+}
+
+/// Parse a `break` statement.
+///
+/// # Panics
+/// Panics if the next token isn't `break`.
+pub fn parse_break_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let break_tok = par.assert(TokenKind::Break);
+    let value = match par.peek() {
+        None | Some(TokenKind::Newline) => None,
+        Some(_) => Some(parse_expr(par)?),
+    };
+    par.expect_newline("break statement")?;
+    let span = break_tok.span + value.as_ref();
+    Ok(Node::new(FuncStmt::Break { value }, span))
+}
+
+/// Parse a `match` statement.
+///
+/// # Panics
+/// Panics if the next token isn't `match`.
+pub fn parse_match_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let match_tok = par.assert(TokenKind::Match);
+
+    let value = parse_expr(par)?;
+    par.enter_block(match_tok.span + value.span, "`match` statement")?;
+
+    let mut arms = vec![];
+    loop {
+        match par.peek() {
+            None => break,
+            Some(TokenKind::Dedent) => {
+                par.next()?;
+                break;
+            }
+            Some(_) => arms.push(parse_match_arm(par)?),
+        }
+    }
+
+    let span = match_tok.span + value.span + arms.last();
+    Ok(Node::new(FuncStmt::Match { value, arms }, span))
+}
+
+/// Parse a single arm of a `match` statement, eg. `Shape::Circle(radius):`
+/// followed by an indented block.
+fn parse_match_arm(par: &mut Parser) -> ParseResult<Node<MatchArm>> {
+    let expr = parse_expr(par)?;
+    let pattern = expr_to_pattern(par, expr)?;
+    par.enter_block(pattern.span, "`match` arm")?;
+    let body = parse_block_stmts(par)?;
+    let span = pattern.span + body.last();
+    Ok(Node::new(MatchArm { pattern, body }, span))
+}
+
+fn expr_to_pattern(par: &mut Parser, expr: Node<Expr>) -> ParseResult<Node<Pattern>> {
+    let pattern = match expr.kind {
+        Expr::Name(name) if name == "_" => Pattern::Wildcard,
+        Expr::Name(name) => Pattern::Binding(name),
+        Expr::Bool(val) => Pattern::Bool(val),
+        Expr::Num(num) => Pattern::Num(num),
+        Expr::UnaryOperation { op, operand } if op.kind == UnaryOperator::USub => {
+            match operand.kind {
+                Expr::Num(num) => Pattern::Num(format!("-{}", num).into()),
+                _ => return Err(pattern_error(par, expr.span)),
+            }
+        }
+        Expr::Path(path) => Pattern::Variant { path, fields: None },
+        Expr::Call {
+            func,
+            generic_args,
+            args,
+            base,
+        } => {
+            let path = match func.kind {
+                Expr::Path(path) => path,
+                Expr::Name(name) => Path {
+                    segments: vec![Node::new(name, func.span)],
+                },
+                _ => return Err(pattern_error(par, func.span)),
+            };
+            if let Some(generic_args) = generic_args {
+                return Err(pattern_error(par, generic_args.span));
+            }
+            if let Some(base) = base {
+                return Err(pattern_error(par, base.span));
+            }
+            let fields = args
+                .kind
+                .into_iter()
+                .map(|arg| {
+                    if let Some(label) = arg.kind.label {
+                        Err(pattern_error(par, label.span))
+                    } else {
+                        expr_to_pattern(par, arg.kind.value)
+                    }
+                })
+                .collect::<ParseResult<Vec<_>>>()?;
+            Pattern::Variant {
+                path,
+                fields: Some(fields),
+            }
+        }
+        _ => return Err(pattern_error(par, expr.span)),
+    };
+    Ok(Node::new(pattern, expr.span))
+}
+
+fn pattern_error(par: &mut Parser, span: Span) -> ParseFailed {
+    par.fancy_error(
+        "failed to parse `match` pattern",
+        vec![Label::primary(span, "invalid pattern")],
+        vec![
+            "A pattern can be `_`, a name, a boolean or integer literal,\nor an enum variant, eg. `Shape::Rect(width, _)`."
+                .into(),
+        ],
+    );
+    ParseFailed
+}
+
 /// Parse a `for` statement.
 ///
 /// # Panics
@@ -553,3 +811,267 @@ pub fn parse_unsafe_block(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
 
     Ok(Node::new(FuncStmt::Unsafe(body), span))
 }
+
+/// Checks whether the next tokens start an `unchecked` block, rather than e.g.
+/// a call of a function named `unchecked`. The parser isn't advanced.
+fn is_unchecked_block(par: &mut Parser) -> bool {
+    let mut bt_par = par.as_bt_parser();
+    let _ = bt_par.next();
+    bt_par.peek() == Some(TokenKind::Colon)
+}
+
+/// Parse an `unchecked` block, in which integer arithmetic wraps around on
+/// overflow.
+///
+/// # Panics
+/// Panics if the next token isn't the name `unchecked`.
+pub fn parse_unchecked_block(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let kw_tok = par.assert(TokenKind::Name);
+    assert_eq!(kw_tok.text, "unchecked", "internal parser error");
+    par.enter_block(kw_tok.span, "`unchecked` block")?;
+    let body = parse_block_stmts(par)?;
+    let span = kw_tok.span + body.last();
+
+    Ok(Node::new(FuncStmt::Unchecked(body), span))
+}
+
+/// Parse a statement with attributes. `#[checked]`, which keeps the overflow
+/// checks of the statement's arithmetic when the build profile disables them,
+/// is the only attribute that statements can have.
+///
+/// # Panics
+/// Panics if the next token isn't an attribute.
+pub fn parse_attributed_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let attributes = parse_attributes(par)?;
+    assert!(!attributes.is_empty(), "internal parser error");
+    let stmt = parse_stmt(par)?;
+
+    let mut checked = None;
+    for attr in &attributes {
+        if attr.kind == "checked" {
+            checked = Some(attr.span);
+        } else {
+            par.fancy_error(
+                format!("unknown statement attribute `{}`", attr.kind),
+                vec![Label::primary(attr.span, "this attribute isn't supported")],
+                vec!["Note: `#[checked]` is the only attribute that statements can have".into()],
+            );
+        }
+    }
+    Ok(match checked {
+        Some(span) => {
+            let span = span + stmt.span;
+            Node::new(FuncStmt::Checked(vec![stmt]), span)
+        }
+        None => stmt,
+    })
+}
+
+/// Checks whether the next tokens start a `try` statement, rather than e.g. a
+/// call of a function named `try`. The parser isn't advanced.
+fn is_try_stmt(par: &mut Parser) -> bool {
+    let mut bt_par = par.as_bt_parser();
+    let _ = bt_par.next();
+    matches!(
+        bt_par.peek(),
+        Some(TokenKind::Name | TokenKind::SelfValue)
+    )
+}
+
+/// Parse a `try` statement and its `catch` clauses, e.g.
+/// ```fe
+/// try token.balance_of(owner) as balance:
+///     return balance
+/// catch std::Error as err:
+///     return err.code
+/// catch:
+///     return 0
+/// ```
+///
+/// # Panics
+/// Panics if the next token isn't the name `try`.
+pub fn parse_try_stmt(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let try_tok = par.assert(TokenKind::Name);
+    assert_eq!(try_tok.text, "try", "internal parser error");
+
+    let call = parse_expr(par)?;
+    let binding = if par.peek() == Some(TokenKind::As) {
+        par.next()?;
+        let name = par.expect(TokenKind::Name, "failed to parse `try` statement")?;
+        Some(Node::<SmolStr>::from(name))
+    } else {
+        None
+    };
+    let header_span = try_tok.span + call.span + binding.as_ref();
+    par.enter_block(header_span, "`try` statement")?;
+    let body = parse_block_stmts(par)?;
+
+    let mut catches = vec![];
+    while par.peek() == Some(TokenKind::Name) && par.peeked_text() == "catch" {
+        catches.push(parse_catch_clause(par)?);
+    }
+    if catches.is_empty() {
+        par.fancy_error(
+            "`try` statement without a `catch` clause",
+            vec![Label::primary(header_span, "this `try` catches nothing")],
+            vec!["Hint: add a `catch:` clause, which catches any revert".into()],
+        );
+        return Err(ParseFailed);
+    }
+
+    let span = header_span + body.last() + catches.last();
+    Ok(Node::new(
+        FuncStmt::Try {
+            call,
+            binding,
+            body,
+            catches,
+        },
+        span,
+    ))
+}
+
+/// Parse a `catch` clause of a `try` statement, e.g. `catch MyError as err:`
+/// or `catch:`, followed by an indented block.
+fn parse_catch_clause(par: &mut Parser) -> ParseResult<Node<CatchClause>> {
+    let catch_tok = par.assert(TokenKind::Name);
+    let error = if par.peek() == Some(TokenKind::Colon) {
+        None
+    } else {
+        let typ = parse_type_desc(par)?;
+        par.expect_with_notes(TokenKind::As, "failed to parse `catch` clause", |_| {
+            vec!["Example: `catch MyError as err:`".into()]
+        })?;
+        let name = par.expect(TokenKind::Name, "failed to parse `catch` clause")?;
+        Some((typ, Node::<SmolStr>::from(name)))
+    };
+    let header_span = match &error {
+        Some((_, name)) => catch_tok.span + name.span,
+        None => catch_tok.span,
+    };
+    par.enter_block(header_span, "`catch` clause")?;
+    let body = parse_block_stmts(par)?;
+    let span = header_span + body.last();
+
+    Ok(Node::new(CatchClause { error, body }, span))
+}
+
+/// Checks whether the next tokens start a `yul` block, rather than e.g. a call
+/// of a function named `yul`. The parser isn't advanced.
+fn is_yul_block(par: &mut Parser) -> bool {
+    let mut bt_par = par.as_bt_parser();
+    let _ = bt_par.next();
+    if bt_par.peek() == Some(TokenKind::ParenOpen) && skip_parens(&mut bt_par).is_err() {
+        return false;
+    }
+    if bt_par.peek() == Some(TokenKind::Arrow) {
+        let _ = bt_par.next();
+        if bt_par.peek() == Some(TokenKind::ParenOpen) && skip_parens(&mut bt_par).is_err() {
+            return false;
+        }
+    }
+    bt_par.peek() == Some(TokenKind::BraceOpen)
+}
+
+fn skip_parens(par: &mut Parser) -> ParseResult<()> {
+    let mut depth = 0;
+    loop {
+        match par.next()?.kind {
+            TokenKind::ParenOpen => depth += 1,
+            TokenKind::ParenClose if depth == 1 => return Ok(()),
+            TokenKind::ParenClose => depth -= 1,
+            _ => {}
+        }
+    }
+}
+
+/// Parse a `yul` block, e.g. `yul(x: a) -> (y: u256) { y := add(x, 1) }`.
+///
+/// The Yul code is kept as text. Its tokens are joined with single spaces
+/// where the source has whitespace (or a comment) between them.
+///
+/// # Panics
+/// Panics if the next token isn't the name `yul`.
+pub fn parse_yul_block(par: &mut Parser) -> ParseResult<Node<FuncStmt>> {
+    let yul_tok = par.assert(TokenKind::Name);
+    assert_eq!(yul_tok.text, "yul", "internal parser error");
+
+    let mut inputs = vec![];
+    if par.peek() == Some(TokenKind::ParenOpen) {
+        par.next()?;
+        while par.peek_or_err()? != TokenKind::ParenClose {
+            let name = par.expect(TokenKind::Name, "failed to parse `yul` block input")?;
+            par.expect(TokenKind::Colon, "failed to parse `yul` block input")?;
+            let value = parse_expr(par)?;
+            let span = name.span + value.span;
+            inputs.push(Node::new(
+                YulInput {
+                    name: Node::new(name.text.into(), name.span),
+                    value,
+                },
+                span,
+            ));
+            if par.peek_or_err()? != TokenKind::ParenClose {
+                par.expect(TokenKind::Comma, "failed to parse `yul` block inputs")?;
+            }
+        }
+        par.next()?;
+    }
+
+    let mut outputs = vec![];
+    if par.peek() == Some(TokenKind::Arrow) {
+        par.next()?;
+        par.expect(TokenKind::ParenOpen, "failed to parse `yul` block outputs")?;
+        while par.peek_or_err()? != TokenKind::ParenClose {
+            let name = par.expect(TokenKind::Name, "failed to parse `yul` block output")?;
+            par.expect(TokenKind::Colon, "failed to parse `yul` block output")?;
+            let typ = parse_type_desc(par)?;
+            let span = name.span + typ.span;
+            outputs.push(Node::new(
+                YulOutput {
+                    name: Node::new(name.text.into(), name.span),
+                    typ,
+                },
+                span,
+            ));
+            if par.peek_or_err()? != TokenKind::ParenClose {
+                par.expect(TokenKind::Comma, "failed to parse `yul` block outputs")?;
+            }
+        }
+        par.next()?;
+    }
+
+    let open = par.expect(TokenKind::BraceOpen, "failed to parse `yul` block")?;
+    let mut code = String::new();
+    let mut prev_end = open.span.end;
+    let mut depth = 0;
+    let close = loop {
+        let tok = par.next()?;
+        match tok.kind {
+            TokenKind::BraceOpen => depth += 1,
+            TokenKind::BraceClose if depth == 0 => break tok,
+            TokenKind::BraceClose => depth -= 1,
+            _ => {}
+        }
+        if !code.is_empty() && tok.span.start > prev_end {
+            code.push(' ');
+        }
+        code.push_str(tok.text);
+        prev_end = tok.span.end;
+    };
+    if code.is_empty() {
+        par.error(open.span + close.span, "empty `yul` block");
+    }
+    let code = Node::new(code.into(), open.span + close.span);
+
+    let span = yul_tok.span + close.span;
+    par.expect_newline("`yul` block")?;
+    Ok(Node::new(
+        FuncStmt::Yul {
+            inputs,
+            outputs,
+            code,
+        },
+        span,
+    ))
+}
@@ -1,14 +1,16 @@
-use super::contracts::parse_contract_def;
+use super::contracts::{parse_contract_def, parse_impl, parse_interface_def};
 use super::expressions::parse_expr;
 use super::functions::parse_fn_def;
 use super::types::{
-    parse_event_def, parse_path_tail, parse_struct_def, parse_type_alias, parse_type_desc,
+    parse_attributes, parse_doc_comment, parse_enum_def, parse_event_def, parse_path_tail,
+    parse_struct_def, parse_type_alias, parse_type_desc, reject_attributes, reject_doc_comment,
 };
 use crate::ast::{ConstantDecl, Module, ModuleStmt, Pragma, Use, UseTree};
 use crate::node::{Node, Span};
 use crate::{Label, ParseFailed, ParseResult, Parser, TokenKind};
 
 use semver::VersionReq;
+use smol_str::SmolStr;
 
 /// Parse a [`Module`].
 pub fn parse_module(par: &mut Parser) -> Node<Module> {
@@ -39,47 +41,101 @@ pub fn parse_module(par: &mut Parser) -> Node<Module> {
 
 /// Parse a [`ModuleStmt`].
 pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
+    let doc = parse_doc_comment(par)?;
+    let mut stmt = parse_undocumented_module_stmt(par)?;
+    match &mut stmt {
+        ModuleStmt::Contract(node) => node.kind.doc = doc,
+        ModuleStmt::Interface(node) => node.kind.doc = doc,
+        ModuleStmt::Struct(node) => node.kind.doc = doc,
+        ModuleStmt::Enum(node) => node.kind.doc = doc,
+        ModuleStmt::TypeAlias(node) => node.kind.doc = doc,
+        ModuleStmt::Constant(node) => node.kind.doc = doc,
+        ModuleStmt::Event(node) => node.kind.doc = doc,
+        ModuleStmt::Function(node) => node.kind.doc = doc,
+        ModuleStmt::Pragma(_) | ModuleStmt::Use(_) | ModuleStmt::Impl(_) => {
+            reject_doc_comment(par, &doc)
+        }
+        ModuleStmt::ParseError(_) => {}
+    }
+    Ok(stmt)
+}
+
+/// Parse a [`ModuleStmt`] that follows its doc comment, if any.
+fn parse_undocumented_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
+    let attributes = parse_attributes(par)?;
+    let pub_span = par.optional(TokenKind::Pub).map(|tok| tok.span);
+    if !matches!(
+        par.peek(),
+        Some(
+            TokenKind::Fn
+                | TokenKind::Unsafe
+                | TokenKind::Struct
+                | TokenKind::Enum
+                | TokenKind::Event
+                | TokenKind::Contract
+        )
+    ) {
+        reject_attributes(par, &attributes);
+    }
+    if let Some(pub_span) = pub_span {
+        return parse_pub_module_stmt(par, attributes, pub_span);
+    }
+
     let stmt = match par.peek_or_err()? {
         TokenKind::Pragma => ModuleStmt::Pragma(parse_pragma(par)?),
         TokenKind::Use => ModuleStmt::Use(parse_use(par)?),
-        TokenKind::Contract => ModuleStmt::Contract(parse_contract_def(par, None)?),
-        TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, None)?),
+        TokenKind::Contract => ModuleStmt::Contract(parse_contract_def(par, attributes, None)?),
+        TokenKind::Interface => ModuleStmt::Interface(parse_interface_def(par, None)?),
+        TokenKind::Impl => ModuleStmt::Impl(parse_impl(par)?),
+        TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, attributes, None)?),
+        TokenKind::Enum => ModuleStmt::Enum(parse_enum_def(par, attributes, None)?),
         TokenKind::Type => ModuleStmt::TypeAlias(parse_type_alias(par, None)?),
-        TokenKind::Const => ModuleStmt::Constant(Box::new(parse_constant(par)?)),
+        TokenKind::Const => ModuleStmt::Constant(Box::new(parse_constant(par, None)?)),
+
+        TokenKind::Event => ModuleStmt::Event(parse_event_def(par, attributes, None)?),
 
         // Let these be parse errors for now:
-        TokenKind::Event => ModuleStmt::Event(parse_event_def(par, None)?),
         // TokenKind::Name if par.peeked_text() == "from" => parse_from_import(par),
-        TokenKind::Pub => {
-            let pub_span = par.next()?.span;
-            match par.peek_or_err()? {
-                TokenKind::Event => ModuleStmt::Event(parse_event_def(par, Some(pub_span))?),
-                TokenKind::Fn | TokenKind::Unsafe => {
-                    ModuleStmt::Function(parse_fn_def(par, Some(pub_span))?)
-                }
-                TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, Some(pub_span))?),
-                TokenKind::Type => ModuleStmt::TypeAlias(parse_type_alias(par, Some(pub_span))?),
-                TokenKind::Contract => {
-                    ModuleStmt::Contract(parse_contract_def(par, Some(pub_span))?)
-                }
-                _ => {
-                    let tok = par.next()?;
-                    par.unexpected_token_error(
-                        tok.span,
-                        "failed to parse module",
-                        vec!["Note: expected `fn`".into()],
-                    );
-                    return Err(ParseFailed);
-                }
-            }
+        TokenKind::Fn | TokenKind::Unsafe => {
+            ModuleStmt::Function(parse_fn_def(par, attributes, None)?)
         }
-        TokenKind::Fn | TokenKind::Unsafe => ModuleStmt::Function(parse_fn_def(par, None)?),
         _ => {
             let tok = par.next()?;
             par.unexpected_token_error(
                 tok.span,
                 "failed to parse module",
-                vec!["Note: expected import, contract, struct, type, const or event".into()],
+                vec!["Note: expected import, contract, interface, impl, struct, enum, type, const or event".into()],
+            );
+            return Err(ParseFailed);
+        }
+    };
+    Ok(stmt)
+}
+
+/// Parse a [`ModuleStmt`] that follows a `pub` qualifier.
+fn parse_pub_module_stmt(
+    par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
+    pub_span: Span,
+) -> ParseResult<ModuleStmt> {
+    let pub_span = Some(pub_span);
+    let stmt = match par.peek_or_err()? {
+        TokenKind::Event => ModuleStmt::Event(parse_event_def(par, attributes, pub_span)?),
+        TokenKind::Fn | TokenKind::Unsafe => {
+            ModuleStmt::Function(parse_fn_def(par, attributes, pub_span)?)
+        }
+        TokenKind::Struct => ModuleStmt::Struct(parse_struct_def(par, attributes, pub_span)?),
+        TokenKind::Enum => ModuleStmt::Enum(parse_enum_def(par, attributes, pub_span)?),
+        TokenKind::Type => ModuleStmt::TypeAlias(parse_type_alias(par, pub_span)?),
+        TokenKind::Const => ModuleStmt::Constant(Box::new(parse_constant(par, pub_span)?)),
+        TokenKind::Contract => ModuleStmt::Contract(parse_contract_def(par, attributes, pub_span)?),
+        TokenKind::Interface => ModuleStmt::Interface(parse_interface_def(par, pub_span)?),
+        _ => {
+            let tok = par.next()?;
+            par.unexpected_token_error(
+                tok.span,
+                "failed to parse module",
+                vec!["Note: expected `fn`".into()],
             );
             return Err(ParseFailed);
         }
@@ -90,7 +146,7 @@ pub fn parse_module_stmt(par: &mut Parser) -> ParseResult<ModuleStmt> {
 /// Parse a constant, e.g. `const MAGIC_NUMBER: u256 = 4711`.
 /// # Panics
 /// Panics if the next token isn't `const`.
-pub fn parse_constant(par: &mut Parser) -> ParseResult<Node<ConstantDecl>> {
+pub fn parse_constant(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Node<ConstantDecl>> {
     let const_tok = par.assert(TokenKind::Const);
     let name = par.expect(TokenKind::Name, "failed to parse constant declaration")?;
     par.expect_with_notes(
@@ -121,12 +177,14 @@ pub fn parse_constant(par: &mut Parser) -> ParseResult<Node<ConstantDecl>> {
 
     let exp = parse_expr(par)?;
 
-    let span = const_tok.span + exp.span;
+    let span = const_tok.span + pub_qual + exp.span;
     Ok(Node::new(
         ConstantDecl {
             name: name.into(),
             typ,
             value: exp,
+            pub_qual,
+            doc: None,
         },
         span,
     ))
@@ -222,11 +280,20 @@ pub fn parse_use_tree(par: &mut Parser) -> ParseResult<Node<UseTree>> {
     }
 }
 
-/// Parse a `pragma <version-requirement>` statement.
+/// Parse a `pragma <version-requirement>`, `pragma edition <edition>` or
+/// `pragma feature(<feature>, ...)` statement.
 pub fn parse_pragma(par: &mut Parser) -> ParseResult<Node<Pragma>> {
     let tok = par.assert(TokenKind::Pragma);
     assert_eq!(tok.text, "pragma");
 
+    if par.peek() == Some(TokenKind::Name) {
+        match par.peeked_text() {
+            "edition" => return parse_pragma_edition(par, tok.span),
+            "feature" => return parse_pragma_feature(par, tok.span),
+            _ => {}
+        }
+    }
+
     let mut version_string = String::new();
     let mut tokens = vec![];
     loop {
@@ -254,7 +321,7 @@ pub fn parse_pragma(par: &mut Parser) -> ParseResult<Node<Pragma>> {
 
     match VersionReq::parse(&version_string) {
         Ok(_) => Ok(Node::new(
-            Pragma {
+            Pragma::Version {
                 version_requirement: Node::new(version_string.into(), version_requirement_span),
             },
             tok.span + version_requirement_span,
@@ -272,3 +339,55 @@ pub fn parse_pragma(par: &mut Parser) -> ParseResult<Node<Pragma>> {
         }
     }
 }
+
+/// Parse the rest of a `pragma edition <edition>` statement, after `pragma`.
+fn parse_pragma_edition(par: &mut Parser, pragma_span: Span) -> ParseResult<Node<Pragma>> {
+    par.assert(TokenKind::Name);
+    let edition = par.expect_with_notes(
+        TokenKind::Int,
+        "failed to parse pragma statement: missing edition",
+        |_| vec!["Example: `pragma edition 2024`".into()],
+    )?;
+    Ok(Node::new(
+        Pragma::Edition {
+            edition: Node::new(edition.text.into(), edition.span),
+        },
+        pragma_span + edition.span,
+    ))
+}
+
+/// Parse the rest of a `pragma feature(<feature>, ...)` statement, after
+/// `pragma`.
+fn parse_pragma_feature(par: &mut Parser, pragma_span: Span) -> ParseResult<Node<Pragma>> {
+    par.assert(TokenKind::Name);
+    par.expect_with_notes(
+        TokenKind::ParenOpen,
+        "failed to parse pragma statement: missing feature list",
+        |_| vec!["Example: `pragma feature(generics)`".into()],
+    )?;
+
+    let mut features = vec![];
+    let span = loop {
+        if par.peek_or_err()? == TokenKind::ParenClose {
+            break par.next()?.span;
+        }
+        let name = par.expect(TokenKind::Name, "failed to parse pragma feature list")?;
+        features.push(Node::new(name.text.into(), name.span));
+        if par.peek_or_err()? == TokenKind::Comma {
+            par.next()?;
+        } else {
+            break par
+                .expect(TokenKind::ParenClose, "failed to parse pragma feature list")?
+                .span;
+        }
+    };
+
+    if features.is_empty() {
+        par.error(
+            pragma_span + span,
+            "failed to parse pragma statement: empty feature list",
+        );
+        return Err(ParseFailed);
+    }
+    Ok(Node::new(Pragma::Feature { features }, pragma_span + span))
+}
@@ -1,6 +1,7 @@
-use crate::ast::{self, EventField, Field, GenericArg, Path, TypeAlias, TypeDesc};
+use crate::ast::{self, EventField, Field, GenericArg, Path, TypeAlias, TypeDesc, Variant};
 use crate::grammar::expressions::parse_expr;
 use crate::grammar::functions::{parse_fn_def, parse_single_word_stmt};
+use crate::lexer::attribute_name;
 use crate::node::{Node, Span};
 use crate::Token;
 use crate::{ParseFailed, ParseResult, Parser, TokenKind};
@@ -9,26 +10,48 @@ use if_chain::if_chain;
 use smol_str::SmolStr;
 use vec1::Vec1;
 
-/// Parse a [`ModuleStmt::Struct`].
+/// Parse a [`ModuleStmt::Struct`]. The attributes and the optional `pub`
+/// qualifier must be parsed by the caller, and passed in.
 /// # Panics
 /// Panics if the next token isn't `struct`.
 pub fn parse_struct_def(
     par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
     struct_pub_qual: Option<Span>,
 ) -> ParseResult<Node<ast::Struct>> {
     let struct_tok = par.assert(TokenKind::Struct);
     let name = par.expect_with_notes(TokenKind::Name, "failed to parse struct definition", |_| {
         vec!["Note: a struct name must start with a letter or underscore, and contain letters, numbers, or underscores".into()]
     })?;
+    let generic_params = if par.peek() == Some(TokenKind::Lt) {
+        parse_generic_params(par)?
+    } else {
+        Node::new(vec![], name.span)
+    };
 
     let mut fields = vec![];
     let mut functions = vec![];
-    par.enter_block(struct_tok.span + name.span, "struct definition")?;
+    par.enter_block(
+        struct_tok.span + name.span + generic_params.span,
+        "struct definition",
+    )?;
     loop {
+        let doc = parse_doc_comment(par)?;
+        let fn_attributes = parse_attributes(par)?;
         let pub_qual = par.optional(TokenKind::Pub).map(|tok| tok.span);
+        if !matches!(par.peek(), Some(TokenKind::Fn | TokenKind::Unsafe)) {
+            reject_attributes(par, &fn_attributes);
+        }
+        if !matches!(
+            par.peek(),
+            Some(TokenKind::Name | TokenKind::Fn | TokenKind::Unsafe)
+        ) {
+            reject_doc_comment(par, &doc);
+        }
         match par.peek() {
             Some(TokenKind::Name) => {
-                let field = parse_field(par, pub_qual, None)?;
+                let mut field = parse_field(par, pub_qual, None)?;
+                field.kind.doc = doc;
                 if !functions.is_empty() {
                     par.error(
                         field.span,
@@ -38,7 +61,9 @@ pub fn parse_struct_def(
                 fields.push(field);
             }
             Some(TokenKind::Fn | TokenKind::Unsafe) => {
-                functions.push(parse_fn_def(par, pub_qual)?);
+                let mut function = parse_fn_def(par, fn_attributes, pub_qual)?;
+                function.kind.doc = doc;
+                functions.push(function);
             }
             Some(TokenKind::Dedent) => {
                 par.next()?;
@@ -58,10 +83,119 @@ pub fn parse_struct_def(
     let span = struct_tok.span + struct_pub_qual + name.span + fields.last();
     Ok(Node::new(
         ast::Struct {
+            attributes,
             name: name.into(),
+            generic_params: generic_params.kind,
             fields,
             functions,
             pub_qual: struct_pub_qual,
+            doc: None,
+        },
+        span,
+    ))
+}
+
+/// Parse a [`ModuleStmt::Enum`]. The attributes and the optional `pub`
+/// qualifier must be parsed by the caller, and passed in.
+/// # Panics
+/// Panics if the next token isn't `enum`.
+pub fn parse_enum_def(
+    par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
+    pub_qual: Option<Span>,
+) -> ParseResult<Node<ast::Enum>> {
+    use TokenKind::*;
+
+    let enum_tok = par.assert(Enum);
+    let name = par.expect_with_notes(Name, "failed to parse enum definition", |_| {
+        vec!["Note: an enum name must start with a letter or underscore, and contain letters, numbers, or underscores".into()]
+    })?;
+
+    let mut variants = vec![];
+    par.enter_block(enum_tok.span + name.span, "enum definition")?;
+    loop {
+        match par.peek() {
+            Some(Name) => {
+                variants.push(parse_variant(par)?);
+            }
+            Some(Pass) => {
+                parse_single_word_stmt(par)?;
+            }
+            Some(Dedent) => {
+                par.next()?;
+                break;
+            }
+            None => break,
+            Some(_) => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse enum definition",
+                    vec!["Note: expected a variant, eg. `Circle(u256)`".into()],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+    let span = enum_tok.span + pub_qual + name.span + variants.last();
+    Ok(Node::new(
+        ast::Enum {
+            attributes,
+            name: name.into(),
+            variants,
+            pub_qual,
+            doc: None,
+        },
+        span,
+    ))
+}
+
+/// Parse an enum variant, e.g. `Empty` or `Rectangle(u256, u256)`.
+pub fn parse_variant(par: &mut Parser) -> ParseResult<Node<Variant>> {
+    use TokenKind::*;
+
+    let name = par.expect(Name, "failed to parse enum variant")?;
+    let mut span = name.span;
+    let mut fields = vec![];
+    if par.peek() == Some(ParenOpen) {
+        span += par.next()?.span;
+        loop {
+            match par.peek_or_err()? {
+                ParenClose => {
+                    span += par.next()?.span;
+                    break;
+                }
+                Name | ParenOpen => {
+                    let field = parse_type_desc(par)?;
+                    span += field.span;
+                    fields.push(field);
+                    if par.peek_or_err()? == Comma {
+                        par.next()?;
+                    } else {
+                        span += par.expect(ParenClose, "failed to parse enum variant")?.span;
+                        break;
+                    }
+                }
+                _ => {
+                    let tok = par.next()?;
+                    par.unexpected_token_error(
+                        tok.span,
+                        "failed to parse enum variant",
+                        vec![format!(
+                            "Note: variant fields must be types, eg. `{}(u256, bool)`",
+                            name.text
+                        )],
+                    );
+                    return Err(ParseFailed);
+                }
+            }
+        }
+    }
+    par.expect_newline("enum variant")?;
+    Ok(Node::new(
+        Variant {
+            name: name.into(),
+            fields,
         },
         span,
     ))
@@ -87,6 +221,7 @@ pub fn parse_type_alias(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult
             name: name.into(),
             typ,
             pub_qual,
+            doc: None,
         },
         span,
     ))
@@ -95,7 +230,11 @@ pub fn parse_type_alias(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult
 /// Parse an event definition.
 /// # Panics
 /// Panics if the next token isn't `event`.
-pub fn parse_event_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<Node<ast::Event>> {
+pub fn parse_event_def(
+    par: &mut Parser,
+    attributes: Vec<Node<SmolStr>>,
+    pub_qual: Option<Span>,
+) -> ParseResult<Node<ast::Event>> {
     use TokenKind::*;
 
     let event_tok = par.assert(Event);
@@ -126,9 +265,11 @@ pub fn parse_event_def(par: &mut Parser, pub_qual: Option<Span>) -> ParseResult<
     let span = event_tok.span + pub_qual + name.span + fields.last();
     Ok(Node::new(
         ast::Event {
+            attributes,
             name: name.into(),
             fields,
             pub_qual,
+            doc: None,
         },
         span,
     ))
@@ -212,11 +353,71 @@ pub fn parse_field(
             name: name.into(),
             typ,
             value,
+            doc: None,
         },
         span,
     ))
 }
 
+/// Parse the attributes of an item, e.g. `#[must_use]`, each of which must
+/// be on its own line.
+pub fn parse_attributes(par: &mut Parser) -> ParseResult<Vec<Node<SmolStr>>> {
+    let mut attributes = vec![];
+    while par.peek() == Some(TokenKind::Attribute) {
+        let tok = par.next()?;
+        let name = attribute_name(tok.text).expect("attribute token without a name");
+        attributes.push(Node::new(name.into(), tok.span));
+        par.expect_newline("attribute")?;
+    }
+    Ok(attributes)
+}
+
+/// Parse the `///` doc comment of an item, if it has one. The lines of the
+/// comment are joined with newlines, without the leading `///` (and the space
+/// that usually follows it).
+pub fn parse_doc_comment(par: &mut Parser) -> ParseResult<Option<Node<SmolStr>>> {
+    let mut lines = vec![];
+    let mut span: Option<Span> = None;
+    while par.peek() == Some(TokenKind::DocComment) {
+        let tok = par.next()?;
+        let line = tok.text.trim_start_matches('/');
+        lines.push(line.strip_prefix(' ').unwrap_or(line));
+        span = Some(span.map_or(tok.span, |span| span + tok.span));
+        par.expect_newline("doc comment")?;
+    }
+    Ok(span.map(|span| Node::new(lines.join("\n").into(), span)))
+}
+
+/// Emits an error for a doc comment that isn't followed by an item that can
+/// have one.
+pub fn reject_doc_comment(par: &mut Parser, doc: &Option<Node<SmolStr>>) {
+    if let Some(doc) = doc {
+        par.fancy_error(
+            "unexpected doc comment",
+            vec![Label::primary(
+                doc.span,
+                "doc comments must be followed by an item definition, e.g. a function or struct",
+            )],
+            vec!["Hint: use `#` for a regular comment".into()],
+        );
+    }
+}
+
+/// Emits an error if there are attributes on an item that can't have
+/// attributes.
+pub fn reject_attributes(par: &mut Parser, attributes: &[Node<SmolStr>]) {
+    if let (Some(first), Some(last)) = (attributes.first(), attributes.last()) {
+        par.fancy_error(
+            "unexpected attribute",
+            vec![Label::primary(
+                first.span + last.span,
+                "attributes must be followed by a function, struct or enum definition",
+            )],
+            vec![],
+        );
+    }
+}
+
 /// Parse an optional qualifier (`pub`, `const`, or `idx`).
 pub fn parse_opt_qualifier(par: &mut Parser, tk: TokenKind) -> Option<Span> {
     if par.peek() == Some(tk) {
@@ -231,6 +432,46 @@ pub fn parse_opt_qualifier(par: &mut Parser, tk: TokenKind) -> Option<Span> {
 /// of `Map<address, u256>`).
 /// # Panics
 /// Panics if the first token isn't `<`.
+/// Parse the generic parameter list of a struct or function definition, e.g.
+/// `<T, U>`.
+/// # Panics
+/// Panics if the next token isn't `<`.
+pub fn parse_generic_params(par: &mut Parser) -> ParseResult<Node<Vec<Node<SmolStr>>>> {
+    use TokenKind::*;
+    let mut span = par.assert(Lt).span;
+
+    let mut params = vec![];
+    loop {
+        match par.peek_or_err()? {
+            Gt => {
+                span += par.next()?.span;
+                break;
+            }
+            Name => {
+                params.push(par.next()?.into());
+                if par.peek() == Some(Comma) {
+                    par.next()?;
+                } else {
+                    span += par
+                        .expect(Gt, "failed to parse generic parameter list")?
+                        .span;
+                    break;
+                }
+            }
+            _ => {
+                let tok = par.next()?;
+                par.unexpected_token_error(
+                    tok.span,
+                    "failed to parse generic parameter list",
+                    vec!["Note: generic parameters must be names, e.g. `<T, U>`".into()],
+                );
+                return Err(ParseFailed);
+            }
+        }
+    }
+    Ok(Node::new(params, span))
+}
+
 pub fn parse_generic_args(par: &mut Parser) -> ParseResult<Node<Vec<GenericArg>>> {
     use TokenKind::*;
     let mut span = par.assert(Lt).span;
@@ -265,7 +506,7 @@ pub fn parse_generic_args(par: &mut Parser) -> ParseResult<Node<Vec<GenericArg>>
                 break;
             }
             // Parse non-constant generic argument.
-            Name | ParenOpen => {
+            Name | ParenOpen | Fn => {
                 let typ = parse_type_desc(par)?;
                 args.push(GenericArg::TypeDesc(Node::new(typ.kind, typ.span)));
                 if par.peek() == Some(Comma) {
@@ -396,7 +637,7 @@ pub fn parse_type_desc(par: &mut Parser) -> ParseResult<Node<TypeDesc>> {
                         break;
                     }
 
-                    Name | ParenOpen => {
+                    Name | ParenOpen | Fn => {
                         let item = parse_type_desc(par)?;
                         span += item.span;
                         items.push(item);
@@ -435,6 +676,7 @@ pub fn parse_type_desc(par: &mut Parser) -> ParseResult<Node<TypeDesc>> {
                 )
             }
         }
+        Fn => parse_fn_type_desc(par)?,
         _ => {
             let tok = par.next()?;
             par.unexpected_token_error(tok.span, "failed to parse type description", vec![]);
@@ -497,3 +739,52 @@ pub fn parse_type_desc(par: &mut Parser) -> ParseResult<Node<TypeDesc>> {
 
     Ok(typ)
 }
+
+/// Parse the type of a function reference, e.g. `fn(u256, bool) -> u8`.
+/// # Panics
+/// Panics if the next token isn't `fn`.
+pub fn parse_fn_type_desc(par: &mut Parser) -> ParseResult<Node<TypeDesc>> {
+    use TokenKind::*;
+
+    let mut span = par.assert(Fn).span;
+    span += par
+        .expect(ParenOpen, "failed to parse function type description")?
+        .span;
+
+    let mut params = vec![];
+    loop {
+        if par.peek_or_err()? == ParenClose {
+            span += par.next()?.span;
+            break;
+        }
+        params.push(parse_type_desc(par)?);
+        if par.peek_or_err()? == Comma {
+            par.next()?;
+        } else {
+            span += par
+                .expect(
+                    ParenClose,
+                    "Unexpected token while parsing function type description",
+                )?
+                .span;
+            break;
+        }
+    }
+
+    let return_type = if par.peek() == Some(Arrow) {
+        par.next()?;
+        let typ = parse_type_desc(par)?;
+        span += typ.span;
+        Some(Box::new(typ))
+    } else {
+        None
+    };
+
+    Ok(Node::new(
+        TypeDesc::Function {
+            params,
+            return_type,
+        },
+        span,
+    ))
+}
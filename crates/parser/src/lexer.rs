@@ -2,7 +2,7 @@ mod token;
 use crate::node::Span;
 use fe_common::files::SourceFileId;
 use logos::Logos;
-pub use token::{Token, TokenKind};
+pub use token::{attribute_name, Token, TokenKind};
 
 #[derive(Clone)]
 pub struct Lexer<'a> {
@@ -102,4 +102,14 @@ mod tests {
             &[Newline, Contract, Newline, Name, Newline],
         );
     }
+
+    #[test]
+    fn doc_comments() {
+        check(
+            "/// A contract.\ncontract Foo: # not a doc comment\n  x: u256 / 2",
+            &[
+                DocComment, Newline, Contract, Name, Colon, Newline, Name, Colon, Name, Slash, Int,
+            ],
+        );
+    }
 }
@@ -11,6 +11,41 @@ pub struct Token<'a> {
     pub span: Span,
 }
 
+/// Returns the name of the attribute if `text` is an attribute, e.g. `must_use`
+/// for `#[must_use]`. The name of an attribute with an argument includes the
+/// argument, which is an identifier or a hex number, e.g. `repr(declared)` for
+/// `#[repr(declared)]` and `selector(0xa9059cbb)` for
+/// `#[selector(0xa9059cbb)]`.
+pub fn attribute_name(text: &str) -> Option<&str> {
+    let name = text.strip_prefix("#[")?.strip_suffix(']')?.trim();
+    let is_valid = match name.split_once('(') {
+        Some((attr, arg)) => {
+            let arg = arg.strip_suffix(')');
+            is_identifier(attr)
+                && matches!(arg, Some(arg) if is_identifier(arg) || is_hex_number(arg))
+        }
+        None => is_identifier(name),
+    };
+    if is_valid {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+fn is_identifier(text: &str) -> bool {
+    let mut chars = text.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+fn is_hex_number(text: &str) -> bool {
+    match text.strip_prefix("0x") {
+        Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
 impl<'a> From<Token<'a>> for Node<SmolStr> {
     fn from(tok: Token<'a>) -> Node<SmolStr> {
         Node::new(tok.text.into(), tok.span)
@@ -27,13 +62,22 @@ impl<'a> Add<&Token<'a>> for Span {
 
 #[derive(Debug, Copy, Clone, PartialEq, Logos)]
 pub enum TokenKind {
-    // Ignoring comments and spaces/tabs for now.
+    // Ignoring comments and spaces/tabs for now. A comment can't start with
+    // `#[`, which starts an attribute.
     // If we implement an auto-formatting tool, we'll probably want to change this.
-    #[regex(r"#[^\n]*", logos::skip)]
+    #[regex(r"#([^\[\n][^\n]*)?", logos::skip)]
     #[regex("[ \t]+", logos::skip)]
     #[error]
     Error,
 
+    /// An attribute, e.g. `#[must_use]`.
+    #[regex(r"#\[[^\]\n]*\]?", |lex| attribute_name(lex.slice()).is_some())]
+    Attribute,
+
+    /// A line of a doc comment, e.g. `/// The owner of the contract.`
+    #[regex(r"///[^\n]*")]
+    DocComment,
+
     #[regex(r"\n[ \t]*")]
     Newline,
 
@@ -43,13 +87,14 @@ pub enum TokenKind {
 
     #[regex("[a-zA-Z_][a-zA-Z0-9_]*")]
     Name,
-    #[regex("[0-9]+")]
+    /// A decimal number, e.g. `1_000`, `1e18` or `2.5e18`.
+    #[regex(r"[0-9][0-9_]*(\.[0-9][0-9_]*)?([eE][0-9][0-9_]*)?")]
     Int,
-    #[regex("0[xX][0-9a-fA-F]+")]
+    #[regex("0[xX]_*[0-9a-fA-F][0-9a-fA-F_]*")]
     Hex,
-    #[regex("0[oO][0-7]+")]
+    #[regex("0[oO]_*[0-7][0-7_]*")]
     Octal,
-    #[regex("0[bB][0-1]+")]
+    #[regex("0[bB]_*[0-1][0-1_]*")]
     Binary,
     // Float,
     #[regex(r#""([^"\\]|\\.)*""#)]
@@ -79,12 +124,18 @@ pub enum TokenKind {
     Else,
     #[token("emit")]
     Emit,
+    #[token("enum")]
+    Enum,
     #[token("event")]
     Event,
     #[token("idx")]
     Idx,
     #[token("if")]
     If,
+    #[token("impl")]
+    Impl,
+    #[token("interface")]
+    Interface,
     #[token("pragma")]
     Pragma,
     #[token("pass")]
@@ -107,6 +158,12 @@ pub enum TokenKind {
     Unsafe,
     #[token("while")]
     While,
+    #[token("match")]
+    Match,
+    #[token("mut")]
+    Mut,
+    #[token("loop")]
+    Loop,
 
     #[token("and")]
     And,
@@ -167,6 +224,8 @@ pub enum TokenKind {
     Eq,
     #[token(".")]
     Dot,
+    #[token("..")]
+    DotDot,
     #[token("%")]
     Percent,
     #[token("==")]
@@ -238,9 +297,12 @@ impl TokenKind {
             Elif => "keyword `elif`",
             Else => "keyword `else`",
             Emit => "keyword `emit`",
+            Enum => "keyword `enum`",
             Event => "keyword `event`",
             Idx => "keyword `idx`",
             If => "keyword `if`",
+            Impl => "keyword `impl`",
+            Interface => "keyword `interface`",
             Pragma => "keyword `pragma`",
             Pass => "keyword `pass`",
             For => "keyword `for`",
@@ -252,6 +314,9 @@ impl TokenKind {
             Type => "keyword `type`",
             Unsafe => "keyword `unsafe`",
             While => "keyword `while`",
+            Match => "keyword `match`",
+            Mut => "keyword `mut`",
+            Loop => "keyword `loop`",
             And => "keyword `and`",
             As => "keyword `as`",
             In => "keyword `in`",
@@ -280,6 +345,7 @@ impl TokenKind {
             GtGt => "symbol `>>`",
             Eq => "symbol `=`",
             Dot => "symbol `.`",
+            DotDot => "symbol `..`",
             Percent => "symbol `%`",
             EqEq => "symbol `==`",
             NotEq => "symbol `!=`",
@@ -300,6 +366,8 @@ impl TokenKind {
             LtLtEq => "symbol `<<=`",
             GtGtEq => "symbol `>>=`",
             Arrow => "symbol `->`",
+            Attribute => "an attribute",
+            DocComment => "a doc comment",
 
             Error => unreachable!(),
         }
@@ -6,9 +6,10 @@ mod parser;
 pub use parser::{Label, ParseFailed, ParseResult, Parser};
 pub mod node;
 
-use ast::Module;
+use ast::{Expr, Module};
 use fe_common::diagnostics::Diagnostic;
 use fe_common::files::SourceFileId;
+use node::Node;
 
 /// Parse a [`Module`] from the file content string.
 ///
@@ -28,3 +29,27 @@ pub fn parse_file(file_id: SourceFileId, src: &str) -> (Module, Vec<Diagnostic>)
     let node = crate::grammar::module::parse_module(&mut parser);
     (node.kind, parser.diagnostics)
 }
+
+/// Parse a single [`Expr`] that spans the whole string, e.g. the value of a
+/// constant that's defined on the command line.
+///
+/// Returns `None` if the string isn't a valid expression, in which case the
+/// returned diagnostics describe the problem.
+pub fn parse_expr(file_id: SourceFileId, src: &str) -> (Option<Node<Expr>>, Vec<Diagnostic>) {
+    let mut parser = Parser::new(file_id, src);
+    let expr = match crate::grammar::expressions::parse_expr(&mut parser) {
+        Ok(expr) if parser.done() => Some(expr),
+        Ok(_) => {
+            if let Ok(tok) = parser.next() {
+                parser.unexpected_token_error(
+                    tok.span,
+                    "expected the end of the expression",
+                    vec![],
+                );
+            }
+            None
+        }
+        Err(_) => None,
+    };
+    (expr, parser.diagnostics)
+}
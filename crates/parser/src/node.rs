@@ -1,5 +1,6 @@
 pub use fe_common::{Span, Spanned};
 use serde::{Deserialize, Serialize};
+use std::hash::{Hash, Hasher};
 use std::sync::atomic::{AtomicU32, Ordering};
 
 #[derive(Debug, PartialEq, Copy, Clone, Hash, Eq, Default, PartialOrd, Ord)]
@@ -12,7 +13,15 @@ impl NodeId {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone)]
+/// A node of the AST.
+///
+/// Nodes are compared and hashed by their kind and span, but not by their ids,
+/// which are unique to each parse. Parsing an unchanged item again gives an
+/// equal node, so the analyzer keeps the ids of the items that an edit doesn't
+/// touch, and doesn't recompute the queries about them, like their dependency
+/// graphs. An item that moves, e.g. because a line is added above it, doesn't
+/// compare equal, so its diagnostics are never reported at stale spans.
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Node<T> {
     pub kind: T,
     #[serde(skip_serializing, skip_deserializing)]
@@ -44,6 +53,21 @@ impl<T> Node<T> {
     }
 }
 
+impl<T: PartialEq> PartialEq for Node<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind == other.kind && self.span == other.span
+    }
+}
+
+impl<T: Eq> Eq for Node<T> {}
+
+impl<T: Hash> Hash for Node<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind.hash(state);
+        self.span.hash(state);
+    }
+}
+
 impl<T> Spanned for Node<T> {
     fn span(&self) -> Span {
         self.span
@@ -59,8 +59,14 @@ contract C:
 "#
 }
 
+test_parse_err! { doc_comment_on_use, module::parse_module, "/// Imports.\nuse foo::bar\n" }
+test_parse_err! { doc_comment_in_fn_body, module::parse_module, "fn f():\n  /// Not an item.\n  pass\n" }
+test_parse_err! { pragma_missing_edition, module::parse_module, "pragma edition\n" }
+test_parse_err! { pragma_empty_feature_list, module::parse_module, "pragma feature()\n" }
+
 test_parse_err! { contract_bad_name, module::parse_module, "contract 1X:\n x: u8" }
 test_parse_err! { contract_empty_body, module::parse_module, "contract X:\n \n \ncontract Y:\n x: u8" }
+test_parse_err! { contract_bad_parent, module::parse_module, "contract X(1Y):\n x: u8" }
 test_parse_err! { contract_field_after_def, module::parse_module, r#"
 contract C:
   fn f():
@@ -74,29 +80,47 @@ test_parse_err! { module_pub_event, module::parse_module, "pub event E:\n  x: u8
 test_parse_err! { contract_pub_event, module::parse_module, "contract C:\n pub event E:\n  x: u8" }
 test_parse_err! { contract_const_pub, module::parse_module, "contract C:\n const pub x: u8" }
 test_parse_err! { contract_const_fn, module::parse_module, "contract C:\n const fn f():\n  pass" }
+test_parse_err! { interface_fn_body, module::parse_module, "interface I:\n pub fn f() -> u8:\n  return 1" }
+test_parse_err! { interface_field, module::parse_module, "interface I:\n x: u8" }
+test_parse_err! { impl_missing_for, module::parse_module, "impl Token MyToken" }
+test_parse_err! { enum_variant_named_field, module::parse_module, "enum E:\n  Circle(radius: u256)" }
 test_parse_err! { emit_no_args, functions::parse_stmt, "emit x" }
 test_parse_err! { emit_expr, functions::parse_stmt, "emit x + 1" }
 test_parse_err! { emit_bad_call, functions::parse_stmt, "emit MyEvent(1)()" }
+test_parse_err! { try_without_catch, functions::parse_stmt, "try foo.bar() as x:\n  pass\n" }
+test_parse_err! { catch_missing_binding, functions::parse_stmt, "try foo.bar():\n  pass\ncatch MyError:\n  pass\n" }
 test_parse_err! { expr_bad_prefix, expressions::parse_expr, "*x + 1" }
 test_parse_err! { expr_path_left, expressions::parse_expr, "(1 + 2)::foo::bar" }
 test_parse_err! { expr_path_right, expressions::parse_expr, "foo::10::bar" }
 test_parse_err! { expr_dotted_number, expressions::parse_expr, "3.14" }
+test_parse_err! { expr_fractional_number, expressions::parse_expr, "1.25e1" }
+test_parse_err! { expr_number_exponent_too_large, expressions::parse_expr, "1e100" }
 test_parse_err! { expr_call_eq_label, expressions::parse_expr, "foo(bar=1, baz = 2)" }
+test_parse_err! { expr_call_base_not_last, expressions::parse_expr, "Point(..p, x: 1)" }
+test_parse_err! { expr_list_fill_not_last, expressions::parse_expr, "[..0, 1]" }
+test_parse_err! { emit_struct_update, functions::parse_stmt, "emit MyEvent(x: 1, ..e)" }
 test_parse_err! { expr_assignment, expressions::parse_expr, "1 + (x = y)" }
+test_parse_err! { match_bad_pattern, functions::parse_stmt, "match x:\n a + 1:\n  pass" }
+test_parse_err! { match_labeled_field, functions::parse_stmt, "match x:\n Foo::Bar(a: x):\n  pass" }
+test_parse_err! { yul_empty, functions::parse_stmt, "yul(x: 1) {}" }
+test_parse_err! { yul_unlabeled_input, functions::parse_stmt, "yul(x) -> (y: u256) { y := x }" }
 test_parse_err! { for_no_in, functions::parse_stmt, "for x:\n pass" }
+test_parse_err! { loop_decl_tuple, functions::parse_stmt, "let (a, b): (u256, u256) = loop:\n break (1, 2)" }
 test_parse_err! { fn_no_args, module::parse_module, "fn f:\n  return 5" }
 test_parse_err! { fn_unsafe_pub, module::parse_module, "unsafe pub fn f():\n  return 5" }
 test_parse_err! { fn_def_kw, module::parse_module, "contract C:\n pub def f(x: u8):\n  return x" }
+test_parse_err! { fn_def_mut_without_name, module::parse_module, "fn f(mut: u8):\n  return" }
 test_parse_err! { if_no_body, functions::parse_stmt, "if x:\nelse:\n x" }
 test_parse_err! { use_bad_name, module::parse_use, "use x as 123" }
 test_parse_err! { module_bad_stmt, module::parse_module, "if x:\n y" }
 test_parse_err! { module_nonsense, module::parse_module, "))" }
+test_parse_err! { struct_bad_generic_param, module::parse_module, "struct S<u8, 3>:\n x: u8" }
 test_parse_err! { struct_bad_field_name, module::parse_module, "struct f:\n pub event" }
 test_parse_err! { stmt_vardecl_attr, functions::parse_stmt, "f.s : u" }
 test_parse_err! { stmt_vardecl_tuple, functions::parse_stmt, "(a, x+1) : u256" }
 test_parse_err! { stmt_vardecl_tuple_empty, functions::parse_stmt, "(a, ()) : u256" }
 test_parse_err! { stmt_vardecl_subscript, functions::parse_stmt, "a[1] : u256" }
-test_parse_err! { stmt_vardecl_missing_type_annotation, functions::parse_stmt, "let x = 1" }
+test_parse_err! { stmt_vardecl_tuple_missing_value, functions::parse_stmt, "let (a, b)" }
 test_parse_err! { stmt_vardecl_missing_type_annotation_2, functions::parse_stmt, "let x" }
 test_parse_err! { stmt_vardecl_missing_type_annotation_3, functions::parse_stmt, "let x:" }
 test_parse_err! { stmt_vardecl_invalid_type_annotation, functions::parse_stmt, "let x: y + z" }
@@ -109,6 +133,10 @@ test_parse_err! { self_struct, module::parse_module, "struct self:\n pass" }
 test_parse_err! { self_fn, module::parse_module, "pub fn self():\n pass" }
 test_parse_err! { self_use1, module::parse_module, "use self as bar" }
 test_parse_err! { self_use2, module::parse_module, "use bar as self" }
+test_parse_err! { attribute_on_const, module::parse_module, "#[must_use]\nconst x: u8 = 10" }
+test_parse_err! { attribute_on_contract_field, module::parse_module, "contract C:\n #[must_use]\n x: u8" }
+test_parse_err! { attribute_same_line, module::parse_module, "#[must_use] fn f():\n pass" }
+test_parse_err! { attribute_on_stmt, module::parse_module, "fn f():\n #[must_use]\n x = 1\n" }
 
 // assert_snapshot! doesn't like the invalid escape code
 #[test]
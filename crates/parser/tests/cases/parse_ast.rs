@@ -68,16 +68,21 @@ macro_rules! test_parse {
 
 test_parse! { expr_call1, expressions::parse_expr, "foo()" }
 test_parse! { expr_call2, expressions::parse_expr, "foo(1,2,x:3)" }
+test_parse! { expr_call_struct_update, expressions::parse_expr, "Point(x: 1, y, ..other.p)" }
 test_parse! { expr_attr1, expressions::parse_expr, "foo.bar[0][y]" }
 test_parse! { expr_attr2, expressions::parse_expr, "a[x].b[y](1)" }
 test_parse! { expr_num1, expressions::parse_expr, "12345" }
 test_parse! { expr_num2, expressions::parse_expr, "00001" }
 test_parse! { expr_hex1, expressions::parse_expr, "0xbeefbeef" }
 test_parse! { expr_hex2, expressions::parse_expr, "0xFEED1234" }
+test_parse! { expr_num_separators, expressions::parse_expr, "1_000_000 + 0xff_ff" }
+test_parse! { expr_num_scientific, expressions::parse_expr, "2.5e18 - 1e3" }
 test_parse! { expr_path_call, expressions::parse_expr, "foo::bar::abc1()" }
 test_parse! { expr_string, expressions::parse_expr, r#""hi \tmom\n""# }
 test_parse! { expr_list, expressions::parse_expr, "[]" }
 test_parse! { expr_list2, expressions::parse_expr, "[x, y, z,]" }
+test_parse! { expr_list_fill, expressions::parse_expr, "[x, y, ..z]" }
+test_parse! { expr_list_only_fill, expressions::parse_expr, "[..0]" }
 test_parse! { expr_ternary, expressions::parse_expr, "x + 1 if y + 2 else z + 3" }
 test_parse! { expr_group, expressions::parse_expr, "(1 + 2) * 3" }
 test_parse! { expr_tuple1, expressions::parse_expr, "(1,)" }
@@ -95,6 +100,8 @@ test_parse! { ops_bit2, expressions::parse_expr, "a ^ b & c" }
 test_parse! { ops_bit3, expressions::parse_expr, "a | b ^ c" }
 test_parse! { ops_shift, expressions::parse_expr, "a << b >> c" }
 test_parse! { ops_bool, expressions::parse_expr, "a or b and c" }
+test_parse! { ops_comp_chain, expressions::parse_expr, "a < b <= c + 1 == d" }
+test_parse! { ops_comp_parens, expressions::parse_expr, "(a == b) == c and x < y" }
 
 test_parse! { stmt_assert_no_msg, functions::parse_stmt, "assert x == y" }
 test_parse! { stmt_assert_msg, functions::parse_stmt, "assert x == y, z" }
@@ -122,11 +129,47 @@ test_parse! { stmt_if, functions::parse_stmt, "if a:\n b" }
 test_parse! { stmt_if2, functions::parse_stmt, "if a:\n b \nelif c:\n d \nelif e: \n f \nelse:\n g" }
 test_parse! { stmt_while, functions::parse_stmt, "while a > 5:\n a -= 1" }
 test_parse! { stmt_for, functions::parse_stmt, "for a in b[0]:\n pass" }
+test_parse! { stmt_loop, functions::parse_stmt, "loop:\n if a > 5:\n  break\n a += 1" }
+test_parse! { stmt_loop_decl, functions::parse_stmt, "let x: u256 = loop:\n if a > 5:\n  break a * 2\n a += 1" }
+test_parse! { stmt_unchecked, functions::parse_stmt, "unchecked:\n a += 1\n b = a * c" }
+test_parse! { stmt_unchecked_call, functions::parse_stmt, "unchecked(a)" }
+test_parse! { stmt_checked, functions::parse_stmt, "#[checked]\nlet x: u256 = a * b" }
+test_parse! { stmt_match, functions::parse_stmt, r#"match shape:
+  Shape::Circle(r):
+    return r
+  Shape::Rect(w, _):
+    return w
+  Shape::Empty:
+    return 0
+  other:
+    pass
+"# }
+test_parse! { stmt_try, functions::parse_stmt, r#"try token.balance_of(owner) as balance:
+  return balance
+catch std::Error as err:
+  return err.code
+catch String<100> as reason:
+  pass
+catch:
+  return 0
+"# }
+test_parse! { stmt_try_call, functions::parse_stmt, "try(a)" }
+test_parse! { stmt_match_literals, functions::parse_stmt, "match x:\n 1:\n  a\n -1:\n  b\n true:\n  c\n _:\n  d" }
+test_parse! { stmt_yul, functions::parse_stmt, r#"yul(src: ptr, len: 32) -> (total: u256) {
+  for { let i := 0 } lt(i, len) { i := add(i, 32) } {
+    total := add(total, mload(add(src, i))) # sum the words
+  }
+}"# }
+test_parse! { stmt_yul_call, functions::parse_stmt, "yul(a: 1)" }
 test_parse! { stmt_var_decl_name, functions::parse_stmt, "let foo: u256 = 1" }
 test_parse! { stmt_var_decl_tuple, functions::parse_stmt, "let (foo, bar): (u256, u256) = (10, 10)" }
 test_parse! { stmt_var_decl_tuples, functions::parse_stmt, "let (a, (b, (c, d))): x" }
+test_parse! { stmt_var_decl_tuple_untyped, functions::parse_stmt, "let (a, (b, c)) = foo()" }
+test_parse! { stmt_var_decl_untyped, functions::parse_stmt, "let foo = bar(1)" }
 test_parse! { type_def, try_parse_module, "type X = Map<address, u256>" }
 test_parse! { pub_type_def, try_parse_module, "pub type X = Map<address, u256>" }
+test_parse! { const_def, try_parse_module, "const X: u256 = 10" }
+test_parse! { pub_const_def, try_parse_module, "pub const X: u256 = 10" }
 test_parse! { type_name, types::parse_type_desc, "MyType" }
 test_parse! { type_array, types::parse_type_desc, "Array<address, 25>" }
 test_parse! { type_3d, types::parse_type_desc, "Array<Array<Array<u256, 4>, 4>, 4>" }
@@ -139,17 +182,27 @@ test_parse! { type_map3, types::parse_type_desc, "Map<address, Map<u8, Map<u8, u
 test_parse! { type_map4, types::parse_type_desc, "map < address , map < u8, u256 > >" }
 test_parse! { type_tuple, types::parse_type_desc, "(u8, u16, address, Map<u8, u8>)" }
 test_parse! { type_unit, types::parse_type_desc, "()" }
+test_parse! { type_fn, types::parse_type_desc, "fn(u8, (bool, address)) -> fn() -> u256" }
+test_parse! { type_fn_unit, types::parse_type_desc, "fn()" }
 
 test_parse! { fn_def, try_parse_module, "fn foo21(x: bool, y: address,) -> bool:\n x"}
 test_parse! { fn_def_pub, try_parse_module, "pub fn foo21(x: bool, y: address,) -> bool:\n x"}
 test_parse! { fn_def_unsafe, try_parse_module, "unsafe fn foo21(x: bool, y: address,) -> bool:\n x"}
 test_parse! { fn_def_pub_unsafe, try_parse_module, "pub unsafe fn foo21(x: bool, y: address,) -> bool:\n x"}
+test_parse! { fn_def_generic, try_parse_module, "fn pick<T, U>(x: T, y: U, first: bool) -> T:\n return x"}
+test_parse! { fn_def_attribute, try_parse_module, "#[must_use]\npub fn foo() -> bool:\n return true"}
+test_parse! { fn_def_attribute_with_hex_arg, try_parse_module, "#[selector(0xa9059cbb)]\npub fn foo():\n pass"}
+test_parse! { fn_def_mut, try_parse_module, "fn foo(mut self, mut x: Array<u256, 3>, y: u8):\n x[0] = y"}
+test_parse! { fn_def_default_params, try_parse_module, "fn fee(value: u256, percent: u256 = 1, minimum: u256 = MIN * 2) -> u256:\n return value"}
 test_parse! { event_def, try_parse_module, "event Foo:\n  x: address\n  idx y: u8" }
 test_parse! { empty_event_def, try_parse_module, "event Foo:\n  pass" }
 test_parse! { pub_event_def, try_parse_module, "event Foo:\n  x: address\n  idx y: u8" }
+test_parse! { anonymous_event_def, try_parse_module, "#[anonymous]\nevent Foo:\n  idx x: address" }
 test_parse! { pragma1, module::parse_pragma, "pragma 0.1.0" }
 test_parse! { pragma2, module::parse_pragma, "pragma 0.1.0-alpha" }
 test_parse! { pragma3, module::parse_pragma, "pragma >= 1.2, < 1.5" }
+test_parse! { pragma_edition, module::parse_pragma, "pragma edition 2024" }
+test_parse! { pragma_feature, module::parse_pragma, "pragma feature(generics, function_types)" }
 
 test_parse! { use_simple1, module::parse_use, "use foo::bar" }
 test_parse! { use_simple2, module::parse_use, "use foo::bar as baz" }
@@ -172,9 +225,57 @@ test_parse! { struct_def, try_parse_module, r#"struct S:
   unsafe fn bar():
     pass
 "# }
+test_parse! { doc_comments, try_parse_module, r#"/// A token.
+///
+///Tracks balances.
+contract Token:
+  /// The balance of each owner.
+  balances: Map<address, u256>
+
+  /// Emitted on transfers.
+  event Transfer:
+    value: u256
+
+  /// Returns the balance of `owner`.
+  #[must_use]
+  pub fn balance_of(self, owner: address) -> u256:
+    return self.balances[owner]
+
+/// A point.
+struct Point:
+  /// The x coordinate.
+  pub x: u256
+"# }
 test_parse! { empty_struct_def, try_parse_module, r#"struct S:
   pass
 "# }
+test_parse! { struct_def_generic, try_parse_module, r#"struct Pair<A, B>:
+  pub first: A
+  pub second: B
+
+  pub fn swap(self) -> Pair<B, A>:
+    return Pair<B, A>(first: self.second, second: self.first)
+"# }
+test_parse! { enum_def, try_parse_module, r#"pub enum Shape:
+  Empty
+  Circle(u256)
+  Rectangle(u256, u256)
+  Pair((u8, bool))
+"# }
+test_parse! { struct_def_attribute, try_parse_module, r#"#[must_use]
+struct Receipt:
+  pub ok: bool
+
+  #[must_use]
+  pub fn is_ok(self) -> bool:
+    return self.ok
+"# }
+test_parse! { struct_def_attribute_with_arg, try_parse_module, r#"#[packed]
+#[repr(declared)]
+struct Account:
+  pub owner: address
+  pub frozen: bool
+"# }
 
 test_parse! { contract_def, try_parse_module, r#"contract Foo:
   x: address
@@ -190,11 +291,27 @@ test_parse! { empty_contract_def, try_parse_module, r#"contract Foo:
     pass
 "# }
 
+test_parse! { contract_def_parents, try_parse_module, r#"contract Foo(Ownable, Pausable):
+    x: u8
+"# }
+
+test_parse! { contract_def_attributes, try_parse_module, r#"#[abi(packed_events)]
+pub contract Foo:
+    x: u8
+"# }
+
 test_parse! { pub_contract_def, try_parse_module, r#"pub contract Foo:
     pub fn foo() -> u8:
       return 10
 "# }
 
+test_parse! { interface_def, try_parse_module, r#"pub interface Token:
+    pub fn balance_of(owner: address) -> u256
+    fn transfer(self, to: address, value: u256)
+"# }
+
+test_parse! { impl_def, try_parse_module, "impl Token for MyToken" }
+
 test_parse! { module_stmts, try_parse_module, r#"
 pragma 0.5.0
 
@@ -0,0 +1,25 @@
+contract SolidityCounter {
+  uint256 count;
+
+  function increment(uint256 by) public returns (uint256) {
+    count += by;
+    return count;
+  }
+
+  function value() public view returns (uint256) {
+    return count;
+  }
+}
+
+contract DriftedCounter {
+  uint128 count;
+
+  function increment(uint128 by) public returns (uint128) {
+    count += by;
+    return count;
+  }
+
+  function value() public view returns (uint256, bool) {
+    return (count, true);
+  }
+}
@@ -0,0 +1,644 @@
+//! A reference evaluator for Fe functions.
+//!
+//! The evaluator interprets the analyzed AST of a function directly, with
+//! integers of unbounded size, and checks the results against the bounds of
+//! their types the way the language reference describes. Comparing its results
+//! with the results of the compiled code catches miscompilations.
+//!
+//! Only functions that don't access storage or the context, and that only use
+//! booleans, integers, addresses and arrays, are supported.
+
+use fe_analyzer::builtins::IntegerTypeMethod;
+use fe_analyzer::context::{CallType, Constant, FunctionBody};
+use fe_analyzer::namespace::items::FunctionId;
+use fe_analyzer::namespace::types::{Base, FixedSize, Integer, Type};
+use fe_analyzer::AnalyzerDb;
+use fe_common::numeric;
+use fe_parser::ast;
+use fe_parser::node::Node;
+use num_bigint::{BigInt, Sign};
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The number of statements and expressions that are evaluated before the
+/// evaluation is given up.
+const STEP_LIMIT: usize = 1_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Unit,
+    Bool(bool),
+    /// An integer or an address.
+    Int(BigInt),
+    Array(Vec<Value>),
+}
+
+/// The way a function reverts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Revert {
+    /// `Panic(uint256)` with the given code.
+    Panic(u64),
+    /// `Error(string)` with the given reason.
+    Error(String),
+    /// Any other revert, e.g. `revert` without an error.
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    Revert(Revert),
+    /// The function uses a feature that the evaluator doesn't support.
+    Unsupported(String),
+}
+
+const PANIC_ASSERT: u64 = 0x01;
+const PANIC_OVERFLOW: u64 = 0x11;
+const PANIC_DIV_BY_ZERO: u64 = 0x12;
+const PANIC_OUT_OF_BOUNDS: u64 = 0x32;
+
+impl Value {
+    /// The value of type `typ` that is stored in an ABI encoded word. Bits that
+    /// don't fit the type are ignored. Returns `None` for types that aren't
+    /// supported by the evaluator.
+    pub fn from_word(typ: &FixedSize, word: &[u8; 32]) -> Option<Value> {
+        match typ {
+            FixedSize::Base(Base::Bool) => Some(Value::Bool(word[31] & 1 == 1)),
+            FixedSize::Base(Base::Numeric(integer)) => Some(Value::Int(truncate(
+                integer,
+                BigInt::from_bytes_be(Sign::Plus, word),
+            ))),
+            FixedSize::Base(Base::Address) => {
+                Some(Value::Int(BigInt::from_bytes_be(Sign::Plus, &word[12..])))
+            }
+            _ => None,
+        }
+    }
+
+    /// The value as an ABI encoded word. Negative integers are encoded in two's
+    /// complement.
+    pub fn to_word(&self) -> Option<[u8; 32]> {
+        match self {
+            Value::Bool(value) => {
+                let mut word = [0; 32];
+                word[31] = *value as u8;
+                Some(word)
+            }
+            Value::Int(value) => {
+                let bytes = value.to_signed_bytes_be();
+                let fill = if value.is_negative() { 0xff } else { 0 };
+                let mut word = [fill; 32];
+                word[32 - bytes.len()..].copy_from_slice(&bytes);
+                Some(word)
+            }
+            Value::Unit | Value::Array(_) => None,
+        }
+    }
+
+    fn as_int(&self) -> &BigInt {
+        match self {
+            Value::Int(value) => value,
+            _ => panic!("expected an integer, found {:?}", self),
+        }
+    }
+
+    fn as_bool(&self) -> bool {
+        match self {
+            Value::Bool(value) => *value,
+            _ => panic!("expected a boolean, found {:?}", self),
+        }
+    }
+}
+
+/// Evaluates a call of `function` with the given arguments.
+pub fn eval_function(
+    db: &dyn AnalyzerDb,
+    function: FunctionId,
+    args: Vec<Value>,
+) -> Result<Value, EvalError> {
+    Evaluator {
+        db,
+        steps: 0,
+        call_depth: 0,
+    }
+    .call(function, args)
+}
+
+/// Wraps `value` into the range of `integer`, like a conversion to a smaller
+/// type does.
+pub fn truncate(integer: &Integer, value: BigInt) -> BigInt {
+    let modulus = BigInt::one() << integer.bits();
+    let mut value = value % &modulus;
+    if value.is_negative() {
+        value += &modulus;
+    }
+    if integer.is_signed() && value > integer.max_value() {
+        value -= modulus;
+    }
+    value
+}
+
+enum Flow {
+    Next,
+    Break(Option<Value>),
+    Continue,
+    Return(Value),
+}
+
+struct Evaluator<'a> {
+    db: &'a dyn AnalyzerDb,
+    steps: usize,
+    call_depth: usize,
+}
+
+struct Frame {
+    function: FunctionId,
+    body: Rc<FunctionBody>,
+    vars: HashMap<ast::SmolStr, Value>,
+}
+
+fn unsupported<T>(what: impl Into<String>) -> Result<T, EvalError> {
+    Err(EvalError::Unsupported(what.into()))
+}
+
+fn panic<T>(code: u64) -> Result<T, EvalError> {
+    Err(EvalError::Revert(Revert::Panic(code)))
+}
+
+/// The result of an integer operation, or an overflow panic if it's out of
+/// the range of `integer`.
+fn checked(integer: &Integer, value: BigInt) -> Result<Value, EvalError> {
+    if integer.fits(value.clone()) {
+        Ok(Value::Int(value))
+    } else {
+        panic(PANIC_OVERFLOW)
+    }
+}
+
+impl Evaluator<'_> {
+    fn call(&mut self, function: FunctionId, args: Vec<Value>) -> Result<Value, EvalError> {
+        if function.takes_self(self.db) {
+            return unsupported("functions that take `self`");
+        }
+        if self.call_depth > 64 {
+            return unsupported("deep recursion");
+        }
+
+        let signature = function.signature(self.db);
+        let mut frame = Frame {
+            function,
+            body: function.body(self.db),
+            vars: signature
+                .params
+                .iter()
+                .map(|param| param.name.clone())
+                .zip(args)
+                .collect(),
+        };
+
+        self.call_depth += 1;
+        let flow = self.stmts(&mut frame, &function.data(self.db).ast.kind.body);
+        self.call_depth -= 1;
+
+        match flow? {
+            Flow::Return(value) => Ok(value),
+            _ => Ok(Value::Unit),
+        }
+    }
+
+    fn step(&mut self) -> Result<(), EvalError> {
+        self.steps += 1;
+        if self.steps > STEP_LIMIT {
+            return unsupported("long running functions");
+        }
+        Ok(())
+    }
+
+    fn stmts(
+        &mut self,
+        frame: &mut Frame,
+        stmts: &[Node<ast::FuncStmt>],
+    ) -> Result<Flow, EvalError> {
+        for stmt in stmts {
+            match self.stmt(frame, stmt)? {
+                Flow::Next => {}
+                flow => return Ok(flow),
+            }
+        }
+        Ok(Flow::Next)
+    }
+
+    fn stmt(&mut self, frame: &mut Frame, stmt: &Node<ast::FuncStmt>) -> Result<Flow, EvalError> {
+        self.step()?;
+        match &stmt.kind {
+            ast::FuncStmt::Return { value } => {
+                let value = match value {
+                    Some(value) => self.expr(frame, value)?,
+                    None => Value::Unit,
+                };
+                Ok(Flow::Return(value))
+            }
+            ast::FuncStmt::VarDecl { target, typ, value } => {
+                let name = match &target.kind {
+                    ast::VarDeclTarget::Name(name) => name.clone(),
+                    ast::VarDeclTarget::Tuple(_) => return unsupported("tuples"),
+                };
+                let value = match value {
+                    Some(value) => self.expr(frame, value)?,
+                    None => {
+                        let decl_id = typ.as_ref().map_or(target.id, |typ| typ.id);
+                        default_value(&frame.body.var_decl_types[&decl_id])?
+                    }
+                };
+                frame.vars.insert(name, value);
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::ConstantDecl { name, value, .. } => {
+                let value = self.expr(frame, value)?;
+                frame.vars.insert(name.kind.clone(), value);
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::Assign { target, value } => {
+                let value = self.expr(frame, value)?;
+                self.assign(frame, target, value)?;
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::AugAssign { target, op, value } => {
+                let current = self.expr(frame, target)?;
+                let value = self.expr(frame, value)?;
+                let integer = self.integer(frame, target)?;
+                let result = bin_operation(&integer, op.kind, current.as_int(), value.as_int())?;
+                self.assign(frame, target, result)?;
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::For { target, iter, body } => {
+                let items = match self.expr(frame, iter)? {
+                    Value::Array(items) => items,
+                    _ => return unsupported("iterating over anything but arrays"),
+                };
+                for item in items {
+                    frame.vars.insert(target.kind.clone(), item);
+                    match self.stmts(frame, body)? {
+                        Flow::Next | Flow::Continue => {}
+                        Flow::Break(_) => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::While { test, body } => {
+                while self.expr(frame, test)?.as_bool() {
+                    self.step()?;
+                    match self.stmts(frame, body)? {
+                        Flow::Next | Flow::Continue => {}
+                        Flow::Break(_) => break,
+                        flow @ Flow::Return(_) => return Ok(flow),
+                    }
+                }
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::Loop { result, body } => loop {
+                self.step()?;
+                match self.stmts(frame, body)? {
+                    Flow::Next | Flow::Continue => {}
+                    Flow::Break(value) => {
+                        if let (Some((name, _)), Some(value)) = (result, value) {
+                            frame.vars.insert(name.kind.clone(), value);
+                        }
+                        return Ok(Flow::Next);
+                    }
+                    flow @ Flow::Return(_) => return Ok(flow),
+                }
+            },
+            ast::FuncStmt::If {
+                test,
+                body,
+                or_else,
+            } => {
+                if self.expr(frame, test)?.as_bool() {
+                    self.stmts(frame, body)
+                } else {
+                    self.stmts(frame, or_else)
+                }
+            }
+            ast::FuncStmt::Assert { test, msg } => {
+                if self.expr(frame, test)?.as_bool() {
+                    return Ok(Flow::Next);
+                }
+                match msg.as_ref().map(|msg| &msg.kind) {
+                    None => panic(PANIC_ASSERT),
+                    Some(ast::Expr::Str(reason)) => {
+                        Err(EvalError::Revert(Revert::Error(reason.to_string())))
+                    }
+                    Some(_) => unsupported("assert messages that aren't string literals"),
+                }
+            }
+            ast::FuncStmt::Expr { value } => {
+                self.expr(frame, value)?;
+                Ok(Flow::Next)
+            }
+            ast::FuncStmt::Unsafe(body) | ast::FuncStmt::Checked(body) => self.stmts(frame, body),
+            ast::FuncStmt::Pass => Ok(Flow::Next),
+            ast::FuncStmt::Break { value } => {
+                let value = match value {
+                    Some(value) => Some(self.expr(frame, value)?),
+                    None => None,
+                };
+                Ok(Flow::Break(value))
+            }
+            ast::FuncStmt::Continue => Ok(Flow::Continue),
+            ast::FuncStmt::Revert { error: None } => Err(EvalError::Revert(Revert::Other)),
+            ast::FuncStmt::Revert { error: Some(_) } => unsupported("reverting with an error"),
+            ast::FuncStmt::Emit { .. } => unsupported("events"),
+            ast::FuncStmt::Yul { .. } => unsupported("yul blocks"),
+            ast::FuncStmt::Unchecked(_) => unsupported("unchecked blocks"),
+            ast::FuncStmt::Match { .. } => unsupported("match statements"),
+            ast::FuncStmt::Try { .. } => unsupported("try statements"),
+        }
+    }
+
+    fn assign(
+        &mut self,
+        frame: &mut Frame,
+        target: &Node<ast::Expr>,
+        value: Value,
+    ) -> Result<(), EvalError> {
+        match &target.kind {
+            ast::Expr::Name(name) => {
+                frame.vars.insert(name.clone(), value);
+                Ok(())
+            }
+            ast::Expr::Subscript {
+                value: array,
+                index,
+            } => {
+                let index = self.expr(frame, index)?;
+                let name = match &array.kind {
+                    ast::Expr::Name(name) => name,
+                    _ => return unsupported("assignments to nested subscripts"),
+                };
+                match frame.vars.get_mut(name) {
+                    Some(Value::Array(items)) => {
+                        match index
+                            .as_int()
+                            .to_usize()
+                            .and_then(|index| items.get_mut(index))
+                        {
+                            Some(item) => {
+                                *item = value;
+                                Ok(())
+                            }
+                            None => panic(PANIC_OUT_OF_BOUNDS),
+                        }
+                    }
+                    _ => unsupported("assignments to subscripts of anything but arrays"),
+                }
+            }
+            _ => unsupported("assignments to anything but variables and array items"),
+        }
+    }
+
+    fn typ(&self, frame: &Frame, expr: &Node<ast::Expr>) -> Type {
+        frame
+            .body
+            .expressions
+            .get(&expr.id)
+            .expect("expression isn't analyzed")
+            .typ
+            .clone()
+    }
+
+    fn integer(&self, frame: &Frame, expr: &Node<ast::Expr>) -> Result<Integer, EvalError> {
+        match self.typ(frame, expr) {
+            Type::Base(Base::Numeric(integer)) => Ok(integer),
+            typ => unsupported(format!("operations on values of type {}", typ)),
+        }
+    }
+
+    fn expr(&mut self, frame: &mut Frame, expr: &Node<ast::Expr>) -> Result<Value, EvalError> {
+        self.step()?;
+        match &expr.kind {
+            ast::Expr::Bool(value) => Ok(Value::Bool(*value)),
+            ast::Expr::Num(num) => Ok(Value::Int(
+                numeric::Literal::new(num)
+                    .parse::<BigInt>()
+                    .expect("invalid number literal"),
+            )),
+            ast::Expr::Unit => Ok(Value::Unit),
+            ast::Expr::Name(name) => {
+                if let Some(value) = frame.vars.get(name) {
+                    return Ok(value.clone());
+                }
+                let module = frame.function.module(self.db);
+                match module.resolve_constant(self.db, name) {
+                    Ok(Some(constant)) => match constant.constant_value(self.db) {
+                        Ok(Constant::Int(value)) => Ok(Value::Int(value)),
+                        Ok(Constant::Bool(value)) => Ok(Value::Bool(value)),
+                        _ => unsupported("string constants"),
+                    },
+                    _ => unsupported(format!("the name `{}`", name)),
+                }
+            }
+            ast::Expr::Ternary {
+                if_expr,
+                test,
+                else_expr,
+            } => {
+                if self.expr(frame, test)?.as_bool() {
+                    self.expr(frame, if_expr)
+                } else {
+                    self.expr(frame, else_expr)
+                }
+            }
+            ast::Expr::BoolOperation { left, op, right } => {
+                let left = self.expr(frame, left)?.as_bool();
+                // The right operand is only evaluated if it's needed.
+                match (op.kind, left) {
+                    (ast::BoolOperator::And, false) => Ok(Value::Bool(false)),
+                    (ast::BoolOperator::Or, true) => Ok(Value::Bool(true)),
+                    _ => self.expr(frame, right),
+                }
+            }
+            ast::Expr::BinOperation { left, op, right } => {
+                let integer = self.integer(frame, expr)?;
+                let left = self.expr(frame, left)?;
+                let right = self.expr(frame, right)?;
+                bin_operation(&integer, op.kind, left.as_int(), right.as_int())
+            }
+            ast::Expr::UnaryOperation { op, operand } => {
+                let value = self.expr(frame, operand)?;
+                match op.kind {
+                    ast::UnaryOperator::Not => Ok(Value::Bool(!value.as_bool())),
+                    ast::UnaryOperator::USub => {
+                        checked(&self.integer(frame, expr)?, -value.as_int())
+                    }
+                    ast::UnaryOperator::Invert => Ok(Value::Int(truncate(
+                        &self.integer(frame, expr)?,
+                        !value.as_int(),
+                    ))),
+                }
+            }
+            ast::Expr::CompOperation { left, op, right } => {
+                let left = self.expr(frame, left)?;
+                let right = self.expr(frame, right)?;
+                Ok(Value::Bool(comp_operation(op.kind, &left, &right)?))
+            }
+            ast::Expr::ChainedComparison { left, comparisons } => {
+                let mut left = self.expr(frame, left)?;
+                // The comparisons stop at the first one that's false.
+                for (op, right) in comparisons {
+                    let right = self.expr(frame, right)?;
+                    if !comp_operation(op.kind, &left, &right)? {
+                        return Ok(Value::Bool(false));
+                    }
+                    left = right;
+                }
+                Ok(Value::Bool(true))
+            }
+            ast::Expr::Subscript { value, index } => {
+                let value = self.expr(frame, value)?;
+                let index = self.expr(frame, index)?;
+                match value {
+                    Value::Array(items) => {
+                        match index.as_int().to_usize().and_then(|index| items.get(index)) {
+                            Some(item) => Ok(item.clone()),
+                            None => panic(PANIC_OUT_OF_BOUNDS),
+                        }
+                    }
+                    _ => unsupported("subscripts of anything but arrays"),
+                }
+            }
+            ast::Expr::List { fill: Some(_), .. } => unsupported("list fill values"),
+            ast::Expr::List { elts, fill: None } => Ok(Value::Array(
+                elts.iter()
+                    .map(|elt| self.expr(frame, elt))
+                    .collect::<Result<_, _>>()?,
+            )),
+            ast::Expr::Call { func, args, .. } => {
+                let args = args
+                    .kind
+                    .iter()
+                    .map(|arg| self.expr(frame, &arg.kind.value))
+                    .collect::<Result<Vec<_>, _>>()?;
+                let call_type = frame.body.calls.get(&func.id).cloned();
+                match call_type {
+                    Some(CallType::Pure(function)) => self.call(function, args),
+                    Some(CallType::TypeConstructor(Type::Base(Base::Numeric(integer)))) => {
+                        Ok(Value::Int(truncate(&integer, args[0].as_int().clone())))
+                    }
+                    Some(CallType::TypeConstructor(Type::Base(_))) => Ok(args[0].clone()),
+                    Some(CallType::BuiltinIntegerConversion { function, to, .. }) => {
+                        let value = args[0].as_int().clone();
+                        match function {
+                            IntegerTypeMethod::UncheckedFrom => {
+                                Ok(Value::Int(truncate(&to, value)))
+                            }
+                            IntegerTypeMethod::TryFrom if to.fits(value.clone()) => {
+                                Ok(Value::Int(value))
+                            }
+                            IntegerTypeMethod::TryFrom => Err(EvalError::Revert(Revert::Other)),
+                        }
+                    }
+                    _ => unsupported("calls of anything but pure functions and conversions"),
+                }
+            }
+            ast::Expr::Attribute { .. } => unsupported("attributes"),
+            ast::Expr::Tuple { .. } => unsupported("tuples"),
+            ast::Expr::Path(_) => unsupported("paths"),
+            ast::Expr::Str(_) => unsupported("strings"),
+        }
+    }
+}
+
+/// The zero value of a type, which is the value of variables that are
+/// declared without a value.
+fn default_value(typ: &FixedSize) -> Result<Value, EvalError> {
+    match typ {
+        FixedSize::Base(Base::Bool) => Ok(Value::Bool(false)),
+        FixedSize::Base(Base::Numeric(_) | Base::Address) => Ok(Value::Int(BigInt::zero())),
+        FixedSize::Base(Base::Unit) => Ok(Value::Unit),
+        FixedSize::Array(array) => Ok(Value::Array(vec![
+            default_value(&FixedSize::Base(
+                array.inner
+            ))?;
+            array.size
+        ])),
+        _ => unsupported(format!("values of type {}", typ)),
+    }
+}
+
+fn comp_operation(op: ast::CompOperator, left: &Value, right: &Value) -> Result<bool, EvalError> {
+    Ok(match (op, left, right) {
+        (ast::CompOperator::Eq, _, _) => left == right,
+        (ast::CompOperator::NotEq, _, _) => left != right,
+        (ast::CompOperator::Lt, Value::Int(left), Value::Int(right)) => left < right,
+        (ast::CompOperator::LtE, Value::Int(left), Value::Int(right)) => left <= right,
+        (ast::CompOperator::Gt, Value::Int(left), Value::Int(right)) => left > right,
+        (ast::CompOperator::GtE, Value::Int(left), Value::Int(right)) => left >= right,
+        _ => return unsupported("ordering of values that aren't integers"),
+    })
+}
+
+fn bin_operation(
+    integer: &Integer,
+    op: ast::BinOperator,
+    left: &BigInt,
+    right: &BigInt,
+) -> Result<Value, EvalError> {
+    match op {
+        ast::BinOperator::Add => checked(integer, left + right),
+        ast::BinOperator::Sub => checked(integer, left - right),
+        ast::BinOperator::Mult => checked(integer, left * right),
+        // Division rounds towards zero, and the remainder has the sign of
+        // the dividend, which is how `BigInt` works too.
+        ast::BinOperator::Div | ast::BinOperator::Mod if right.is_zero() => {
+            panic(PANIC_DIV_BY_ZERO)
+        }
+        ast::BinOperator::Div => checked(integer, left / right),
+        ast::BinOperator::Mod => checked(integer, left % right),
+        ast::BinOperator::Pow => pow(integer, left, right),
+        ast::BinOperator::LShift => {
+            let shift = right.to_usize().unwrap_or(usize::MAX).min(256);
+            Ok(Value::Int(truncate(integer, left << shift)))
+        }
+        // Shifting a negative number to the right rounds towards negative
+        // infinity, like an arithmetic shift.
+        ast::BinOperator::RShift => {
+            let shift = right.to_usize().unwrap_or(usize::MAX).min(256);
+            Ok(Value::Int(left >> shift))
+        }
+        ast::BinOperator::BitOr => Ok(Value::Int(left | right)),
+        ast::BinOperator::BitXor => Ok(Value::Int(left ^ right)),
+        ast::BinOperator::BitAnd => Ok(Value::Int(left & right)),
+    }
+}
+
+fn pow(integer: &Integer, base: &BigInt, exponent: &BigInt) -> Result<Value, EvalError> {
+    if exponent.is_zero() {
+        return Ok(Value::Int(BigInt::one()));
+    }
+    // The result of any other base overflows after at most 256
+    // multiplications, so the loop below ends.
+    if base.is_zero() || base.is_one() {
+        return Ok(Value::Int(base.clone()));
+    }
+    if *base == -BigInt::one() {
+        let is_even = (exponent % 2u8).is_zero();
+        return Ok(Value::Int(if is_even {
+            BigInt::one()
+        } else {
+            base.clone()
+        }));
+    }
+
+    let mut result = BigInt::one();
+    let mut remaining = exponent.clone();
+    while remaining.is_positive() {
+        result *= base;
+        if !integer.fits(result.clone()) {
+            return panic(PANIC_OVERFLOW);
+        }
+        remaining -= 1u8;
+    }
+    Ok(Value::Int(result))
+}
@@ -3,11 +3,15 @@ use fe_common::diagnostics::print_diagnostics;
 use fe_common::utils::keccak;
 use fe_driver as driver;
 use fe_yulgen::runtime::functions;
+use indexmap::IndexMap;
 use primitive_types::{H160, U256};
 use std::collections::BTreeMap;
+use std::ops::{Index, IndexMut};
 use std::str::FromStr;
 use yultsur::*;
 
+pub mod evaluator;
+
 pub trait ToBeBytes {
     fn to_be_bytes(&self) -> [u8; 32];
 }
@@ -185,9 +189,181 @@ impl ContractHarness {
         }
     }
 
+    /// Checks that logs with the given data were emitted, regardless of their
+    /// topics. Unlike [`ContractHarness::events_emitted`], this works for logs
+    /// whose data isn't ABI encoded, like the packed data of events.
+    // Executor must be passed by value to get emitted events.
+    pub fn logs_emitted(&self, executor: Executor, logs: &[&[u8]]) {
+        let raw_logs = executor.into_state().deconstruct().1;
+
+        for expected_data in logs {
+            if !raw_logs.iter().any(|log| log.data == *expected_data) {
+                panic!(
+                    "no logs with data {}\nfound: {:?}",
+                    hex::encode(expected_data),
+                    raw_logs
+                        .iter()
+                        .map(|log| hex::encode(&log.data))
+                        .collect::<Vec<_>>()
+                )
+            }
+        }
+    }
+
     pub fn set_caller(&mut self, caller: H160) {
         self.caller = caller;
     }
+
+    /// Checks that the deployed contract, e.g. one compiled from Solidity,
+    /// implements the interface `interface` of a compiled Fe module: every
+    /// function of the interface must be in the contract's ABI, with the same
+    /// selector (i.e. the same name and parameter types) and return type.
+    pub fn verify_interface(
+        &self,
+        compiled_module: &driver::CompiledModule,
+        interface: &str,
+    ) -> Result<(), Vec<InterfaceMismatch>> {
+        verify_interface(&self.abi, &compiled_module.interface_manifest, interface)
+    }
+}
+
+/// A function of a Fe interface that a contract doesn't implement, found by
+/// [`ContractHarness::verify_interface`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InterfaceMismatch {
+    /// The canonical signature of the interface function, e.g.
+    /// `transfer(address,uint256)`.
+    pub signature: String,
+    pub reason: String,
+}
+
+/// Checks that `abi` implements the interface `interface` of an interface
+/// manifest (see [`driver::CompiledModule::interface_manifest`]).
+pub fn verify_interface(
+    abi: &ethabi::Contract,
+    interface_manifest: &str,
+    interface: &str,
+) -> Result<(), Vec<InterfaceMismatch>> {
+    let manifest: serde_json::Value =
+        serde_json::from_str(interface_manifest).expect("unable to parse the interface manifest");
+    let functions = manifest["interfaces"]
+        .as_array()
+        .expect("interfaces not an array")
+        .iter()
+        .find(|item| item["name"] == interface)
+        .unwrap_or_else(|| panic!("could not find interface `{}` in the manifest", interface))
+        ["functions"]
+        .as_array()
+        .expect("functions not an array");
+
+    let mismatches = functions
+        .iter()
+        .filter_map(|function| {
+            let name = function["name"].as_str().expect("name not a string");
+            let signature = function["signature"]
+                .as_str()
+                .expect("signature not a string");
+            let selector = function["selector"]
+                .as_str()
+                .expect("selector not a string");
+            let returns = function["returns"].as_str().expect("returns not a string");
+            let mismatch = |reason: String| {
+                Some(InterfaceMismatch {
+                    signature: signature.to_string(),
+                    reason,
+                })
+            };
+
+            let overloads = match abi.functions.get(name) {
+                Some(overloads) => overloads,
+                None => return mismatch(format!("the contract has no function named `{}`", name)),
+            };
+            let implementation = overloads.iter().find(|implementation| {
+                format!("0x{}", hex::encode(implementation.short_signature())) == selector
+            });
+            let implementation = match implementation {
+                Some(implementation) => implementation,
+                None => {
+                    let found = overloads
+                        .iter()
+                        .map(|overload| {
+                            let inputs = overload
+                                .inputs
+                                .iter()
+                                .map(|input| input.kind.to_string())
+                                .collect::<Vec<_>>();
+                            format!("{}({})", overload.name, inputs.join(","))
+                        })
+                        .collect::<Vec<_>>();
+                    return mismatch(format!(
+                        "the contract has no function with the selector {}, found: {}",
+                        selector,
+                        found.join(", ")
+                    ));
+                }
+            };
+
+            // Solidity functions can return several values, which are
+            // encoded like a tuple.
+            let outputs = implementation
+                .outputs
+                .iter()
+                .map(|output| output.kind.to_string())
+                .collect::<Vec<_>>();
+            let actual_returns = match outputs.as_slice() {
+                [output] => output.clone(),
+                [] => String::new(),
+                _ => format!("({})", outputs.join(",")),
+            };
+            if actual_returns != returns {
+                return mismatch(format!(
+                    "the function returns `{}` instead of `{}`",
+                    actual_returns, returns
+                ));
+            }
+            None
+        })
+        .collect::<Vec<_>>();
+
+    if mismatches.is_empty() {
+        Ok(())
+    } else {
+        Err(mismatches)
+    }
+}
+
+/// A copy of the state of an executor, taken with
+/// [`ExecutorSnapshots::snapshot`].
+#[allow(dead_code)]
+pub struct Snapshot<'a>(StackState<'a>);
+
+/// Saves and restores the state of an executor, so that contracts deployed
+/// once can be reused by several test cases that modify their storage.
+///
+/// ```ignore
+/// let harness = deploy_contract(&mut executor, "aug_assign.fe", "Foo", &[]);
+/// let snapshot = executor.snapshot();
+/// harness.test_function(&mut executor, "add_from_sto", &[..], Some(&..));
+/// executor.revert_to(&snapshot);
+/// ```
+pub trait ExecutorSnapshots<'a> {
+    /// Returns a copy of the current state, including balances, storage,
+    /// deployed code and emitted logs.
+    fn snapshot(&self) -> Snapshot<'a>;
+
+    /// Restores the state saved in `snapshot`. A snapshot can be restored
+    /// any number of times.
+    fn revert_to(&mut self, snapshot: &Snapshot<'a>);
+}
+
+impl<'a> ExecutorSnapshots<'a> for Executor<'a> {
+    fn snapshot(&self) -> Snapshot<'a> {
+        Snapshot(self.state().clone())
+    }
+
+    fn revert_to(&mut self, snapshot: &Snapshot<'a>) {
+        *self.state_mut() = snapshot.0.clone();
+    }
 }
 
 #[allow(dead_code)]
@@ -257,10 +433,18 @@ pub fn encoded_over_or_underflow() -> Vec<u8> {
     encode_revert("Panic(uint256)", &[uint_token(0x11)])
 }
 
+pub fn encoded_panic_empty_array_pop() -> Vec<u8> {
+    encode_revert("Panic(uint256)", &[uint_token(0x31)])
+}
+
 pub fn encoded_panic_out_of_bounds() -> Vec<u8> {
     encode_revert("Panic(uint256)", &[uint_token(0x32)])
 }
 
+pub fn encoded_panic_uninitialized_function() -> Vec<u8> {
+    encode_revert("Panic(uint256)", &[uint_token(0x51)])
+}
+
 pub fn encoded_div_or_mod_by_zero() -> Vec<u8> {
     encode_revert("Panic(uint256)", &[uint_token(0x12)])
 }
@@ -277,21 +461,23 @@ pub fn deploy_contract(
     contract_name: &str,
     init_params: &[ethabi::Token],
 ) -> ContractHarness {
-    let mut db = driver::Db::default();
-    let compiled_module = match driver::compile_single_file(
-        &mut db,
-        fixture,
-        test_files::fixture(fixture),
-        true,
-        true,
-    ) {
-        Ok(module) => module,
-        Err(error) => {
-            fe_common::diagnostics::print_diagnostics(&db, &error.0);
-            panic!("failed to compile module: {}", fixture)
-        }
-    };
+    deploy_compiled_contract(
+        executor,
+        &compile_fixture(fixture),
+        contract_name,
+        init_params,
+    )
+}
 
+/// Deploys a contract of a module compiled with [`compile_fixture`].
+#[allow(dead_code)]
+#[cfg(feature = "solc-backend")]
+pub fn deploy_compiled_contract(
+    executor: &mut Executor,
+    compiled_module: &driver::CompiledModule,
+    contract_name: &str,
+    init_params: &[ethabi::Token],
+) -> ContractHarness {
     let compiled_contract = compiled_module
         .contracts
         .get(contract_name)
@@ -305,6 +491,159 @@ pub fn deploy_contract(
     )
 }
 
+/// Compiles a fixture with optimizations, and panics if it has errors.
+#[cfg(feature = "solc-backend")]
+pub fn compile_fixture(fixture: &str) -> driver::CompiledModule {
+    compile_fixture_with_abi_decoding(fixture, driver::AbiDecoding::default())
+}
+
+/// Compiles a fixture like [`compile_fixture`], with the given validation of
+/// the arguments of public functions.
+#[cfg(feature = "solc-backend")]
+pub fn compile_fixture_with_abi_decoding(
+    fixture: &str,
+    decoding: driver::AbiDecoding,
+) -> driver::CompiledModule {
+    use driver::YulgenDb;
+
+    compile_fixture_with_options(fixture, |db| db.set_abi_decoding(decoding))
+}
+
+/// Compiles a fixture like [`compile_fixture`], with the code generation
+/// options that `configure` sets on the database.
+#[cfg(feature = "solc-backend")]
+pub fn compile_fixture_with_options(
+    fixture: &str,
+    configure: impl FnOnce(&mut driver::Db),
+) -> driver::CompiledModule {
+    let mut db = driver::Db::default();
+    configure(&mut db);
+    match driver::compile_single_file(&mut db, fixture, test_files::fixture(fixture), true, true) {
+        Ok(module) => module,
+        Err(error) => {
+            fe_common::diagnostics::print_diagnostics(&db, &error.0);
+            panic!("failed to compile module: {}", fixture)
+        }
+    }
+}
+
+/// An argument of a contract's `__init__` function, for deploying with a
+/// [`Deployer`].
+pub enum InitParam {
+    Token(ethabi::Token),
+    /// The address of a contract that was deployed earlier by the same
+    /// `Deployer`, referred to by its name in the deployer.
+    Contract(&'static str),
+}
+
+impl From<ethabi::Token> for InitParam {
+    fn from(token: ethabi::Token) -> Self {
+        InitParam::Token(token)
+    }
+}
+
+/// Deploys several fixture contracts, in the order they're added. The
+/// addresses of deployed contracts can be passed to the `__init__` functions
+/// of the contracts that come after them. Each fixture is compiled once.
+///
+/// ```ignore
+/// let contracts = Deployer::new()
+///     .contract("foo", "external_calls.fe", "Foo", vec![])
+///     .contract("proxy", "external_calls.fe", "FooProxy", vec![InitParam::Contract("foo")])
+///     .deploy(&mut executor);
+/// contracts["proxy"].test_function(&mut executor, "baz", &[], None);
+/// ```
+#[derive(Default)]
+pub struct Deployer {
+    contracts: Vec<(&'static str, &'static str, &'static str, Vec<InitParam>)>,
+}
+
+impl Deployer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the contract `contract_name` of `fixture`, which is referred to
+    /// as `name` after it's deployed.
+    pub fn contract(
+        mut self,
+        name: &'static str,
+        fixture: &'static str,
+        contract_name: &'static str,
+        init_params: Vec<InitParam>,
+    ) -> Self {
+        self.contracts
+            .push((name, fixture, contract_name, init_params));
+        self
+    }
+
+    #[cfg(feature = "solc-backend")]
+    pub fn deploy(self, executor: &mut Executor) -> DeployedContracts {
+        let mut modules = IndexMap::new();
+        let mut deployed = DeployedContracts::default();
+
+        for (name, fixture, contract_name, init_params) in self.contracts {
+            let module = modules
+                .entry(fixture)
+                .or_insert_with(|| compile_fixture(fixture));
+            let compiled_contract = module
+                .contracts
+                .get(contract_name)
+                .unwrap_or_else(|| panic!("could not find contract {} in fixture", contract_name));
+
+            let init_params = init_params
+                .into_iter()
+                .map(|param| match param {
+                    InitParam::Token(token) => token,
+                    InitParam::Contract(name) => deployed.address_token(name),
+                })
+                .collect::<Vec<_>>();
+
+            let harness = _deploy_contract(
+                executor,
+                &compiled_contract.bytecode,
+                &compiled_contract.json_abi,
+                &init_params,
+            );
+            deployed.0.insert(name, harness);
+        }
+
+        deployed
+    }
+}
+
+/// The contracts deployed by a [`Deployer`], by name.
+#[derive(Default)]
+pub struct DeployedContracts(IndexMap<&'static str, ContractHarness>);
+
+impl DeployedContracts {
+    pub fn address(&self, name: &str) -> H160 {
+        self[name].address
+    }
+
+    pub fn address_token(&self, name: &str) -> ethabi::Token {
+        ethabi::Token::Address(self.address(name))
+    }
+}
+
+impl Index<&str> for DeployedContracts {
+    type Output = ContractHarness;
+
+    fn index(&self, name: &str) -> &ContractHarness {
+        self.0
+            .get(name)
+            .unwrap_or_else(|| panic!("no contract named {} was deployed", name))
+    }
+}
+
+impl IndexMut<&str> for DeployedContracts {
+    fn index_mut(&mut self, name: &str) -> &mut ContractHarness {
+        self.0
+            .get_mut(name)
+            .unwrap_or_else(|| panic!("no contract named {} was deployed", name))
+    }
+}
+
 #[allow(dead_code)]
 #[cfg(feature = "solc-backend")]
 pub fn deploy_contract_from_ingot(
@@ -336,6 +675,103 @@ pub fn deploy_contract_from_ingot(
     )
 }
 
+/// Calls each public function of a contract that doesn't take `self` with
+/// random arguments, and checks that the compiled code returns or reverts the
+/// way the [`evaluator`] says it should. `random_word` returns an ABI encoded
+/// word that's used as an argument; bits that don't fit the type of the
+/// parameter are ignored.
+#[allow(dead_code)]
+#[cfg(feature = "solc-backend")]
+pub fn audit_contract(
+    executor: &mut Executor,
+    fixture: &str,
+    contract_name: &str,
+    runs: usize,
+    random_word: &mut dyn FnMut() -> [u8; 32],
+) {
+    use evaluator::{EvalError, Revert, Value};
+    use fe_analyzer::namespace::items::ModuleId;
+    use fe_analyzer::namespace::types::{Base, FixedSize};
+
+    let harness = deploy_contract(executor, fixture, contract_name, &[]);
+
+    let mut db = fe_analyzer::TestDb::default();
+    let module = ModuleId::new_standalone(&mut db, fixture, test_files::fixture(fixture));
+    let contract = module
+        .all_contracts(&db)
+        .iter()
+        .find(|contract| contract.name(&db) == contract_name)
+        .copied()
+        .expect("could not find contract in fixture");
+
+    for (name, function) in contract.functions(&db).iter() {
+        if !function.is_public(&db) || function.takes_self(&db) {
+            continue;
+        }
+        let param_types = function
+            .signature(&db)
+            .params
+            .iter()
+            .map(|param| param.typ.clone().expect("invalid parameter type"))
+            .collect::<Vec<_>>();
+
+        for _ in 0..runs {
+            let args = param_types
+                .iter()
+                .map(|typ| {
+                    Value::from_word(typ, &random_word())
+                        .unwrap_or_else(|| panic!("can't audit `{}`: unsupported parameter", name))
+                })
+                .collect::<Vec<_>>();
+            let tokens = param_types
+                .iter()
+                .zip(&args)
+                .map(|(typ, arg)| {
+                    let word = arg.to_word().expect("argument isn't a word");
+                    match typ {
+                        FixedSize::Base(Base::Bool) => ethabi::Token::Bool(word[31] == 1),
+                        FixedSize::Base(Base::Address) => {
+                            ethabi::Token::Address(H160::from_slice(&word[12..]))
+                        }
+                        FixedSize::Base(Base::Numeric(integer)) if integer.is_signed() => {
+                            ethabi::Token::Int(U256::from_big_endian(&word))
+                        }
+                        _ => ethabi::Token::Uint(U256::from_big_endian(&word)),
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            let expected = evaluator::eval_function(&db, *function, args.clone());
+            let capture = harness.capture_call(executor, name, &tokens);
+            let matches = match (&expected, &capture) {
+                (Err(EvalError::Unsupported(feature)), _) => {
+                    panic!("can't audit `{}`: {} aren't supported", name, feature)
+                }
+                (_, evm::Capture::Trap(_)) => false,
+                (Ok(value), evm::Capture::Exit((ExitReason::Succeed(_), output))) => {
+                    *output == value.to_word().map_or(vec![], |word| word.to_vec())
+                }
+                (
+                    Err(EvalError::Revert(revert)),
+                    evm::Capture::Exit((ExitReason::Revert(_), output)),
+                ) => match revert {
+                    Revert::Panic(code) => {
+                        *output == encode_revert("Panic(uint256)", &[uint_token(*code)])
+                    }
+                    Revert::Error(reason) => *output == encode_error_reason(reason),
+                    Revert::Other => true,
+                },
+                _ => false,
+            };
+            assert!(
+                matches,
+                "`{}` called with {:?} was expected to result in {:?}, but the compiled code exited with {:?}",
+                name, args, expected, capture
+            );
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[cfg(feature = "solc-backend")]
 pub fn deploy_solidity_contract(
@@ -400,13 +836,22 @@ fn get_function_selector(signature: &str) -> String {
     hex::encode(&keccak::full_as_bytes(signature.as_bytes())[..4])
 }
 
+/// Load a JSON ABI, skipping `error` entries, which ethabi 14 doesn't understand.
+fn load_abi(json: &str) -> ethabi::Contract {
+    let mut entries: Vec<serde_json::Value> =
+        serde_json::from_str(json).expect("unable to parse the ABI");
+    entries.retain(|entry| entry["type"] != "error");
+    ethabi::Contract::load(serde_json::to_string(&entries).unwrap().as_bytes())
+        .expect("unable to load the ABI")
+}
+
 fn _deploy_contract(
     executor: &mut Executor,
     bytecode: &str,
     abi: &str,
     init_params: &[ethabi::Token],
 ) -> ContractHarness {
-    let abi = ethabi::Contract::load(abi.as_bytes()).expect("unable to load the ABI");
+    let abi = load_abi(abi);
 
     let mut bytecode = hex::decode(bytecode).expect("failed to decode bytecode");
 
@@ -532,8 +977,7 @@ pub fn load_contract(address: H160, fixture: &str, contract_name: &str) -> Contr
         .contracts
         .get(contract_name)
         .expect("could not find contract in fixture");
-    let abi = ethabi::Contract::load(compiled_contract.json_abi.as_bytes())
-        .expect("unable to load the ABI");
+    let abi = load_abi(&compiled_contract.json_abi);
 
     ContractHarness::new(address, abi)
 }
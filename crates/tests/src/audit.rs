@@ -0,0 +1,41 @@
+//! Tests that compare the results of compiled contracts with the results of
+//! the reference evaluator, for random arguments.
+#![cfg(feature = "solc-backend")]
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rstest::rstest;
+
+use fe_compiler_test_utils::*;
+use fe_compiler_test_utils::{self as test_utils};
+
+/// A random ABI encoded word. Values at the bounds of the integer types are
+/// more likely to reveal bugs than uniformly random values, so they're picked
+/// more often.
+fn random_word(rng: &mut StdRng) -> [u8; 32] {
+    let mut word = [0; 32];
+    match rng.gen_range(0, 5) {
+        // zero, the maximum of the unsigned types and -1
+        0 => {}
+        1 => word = [0xff; 32],
+        // the bounds of `i8`
+        2 => word[31] = if rng.gen() { 0x7f } else { 0x80 },
+        3 => word[31] = rng.gen(),
+        _ => word = rng.gen(),
+    }
+    word
+}
+
+#[rstest(
+    fixture,
+    case("differential/math_u8.fe"),
+    case("differential/math_i8.fe"),
+    case("audit/control_flow.fe")
+)]
+fn audit(fixture: &str) {
+    with_executor(&|mut executor| {
+        let mut rng = StdRng::seed_from_u64(0);
+        test_utils::audit_contract(&mut executor, fixture, "Foo", 50, &mut || {
+            random_word(&mut rng)
+        });
+    })
+}
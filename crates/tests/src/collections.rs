@@ -0,0 +1,103 @@
+//! Fuzz tests that run random sequences of operations on the storage
+//! collections of std, and check that they behave like the same collections in
+//! Rust.
+#![cfg(feature = "solc-backend")]
+use proptest::prelude::*;
+use std::collections::VecDeque;
+
+use fe_compiler_test_utils::*;
+
+#[derive(Debug, Clone)]
+enum DequeOp {
+    PushBack(u64),
+    PushFront(u64),
+    PopBack,
+    PopFront,
+}
+
+fn deque_op() -> impl Strategy<Value = DequeOp> {
+    prop_oneof![
+        any::<u64>().prop_map(DequeOp::PushBack),
+        any::<u64>().prop_map(DequeOp::PushFront),
+        Just(DequeOp::PopBack),
+        Just(DequeOp::PopFront),
+    ]
+}
+
+#[derive(Debug, Clone)]
+enum StackOp {
+    Push(u64),
+    Pop,
+}
+
+fn stack_op() -> impl Strategy<Value = StackOp> {
+    prop_oneof![any::<u64>().prop_map(StackOp::Push), Just(StackOp::Pop)]
+}
+
+/// Checks a call that removes a value, which reverts if the collection is
+/// empty.
+fn test_remove(
+    harness: &ContractHarness,
+    executor: &mut Executor,
+    name: &str,
+    expected: Option<u64>,
+) {
+    match expected {
+        Some(value) => harness.test_function(executor, name, &[], Some(&uint_token(value))),
+        None => validate_revert(
+            harness.capture_call(executor, name, &[]),
+            &encode_revert("Error(uint256)", &[uint_token(0x121)]),
+        ),
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn deque(ops in prop::collection::vec(deque_op(), 0..32)) {
+        with_executor(&|mut executor| {
+            let harness = deploy_contract(&mut executor, "features/deque.fe", "Foo", &[]);
+            let mut model = VecDeque::new();
+
+            for op in &ops {
+                match op {
+                    DequeOp::PushBack(value) => {
+                        harness.test_function(&mut executor, "push_back", &[uint_token(*value)], None);
+                        model.push_back(*value);
+                    }
+                    DequeOp::PushFront(value) => {
+                        harness.test_function(&mut executor, "push_front", &[uint_token(*value)], None);
+                        model.push_front(*value);
+                    }
+                    DequeOp::PopBack => test_remove(&harness, &mut executor, "pop_back", model.pop_back()),
+                    DequeOp::PopFront => test_remove(&harness, &mut executor, "pop_front", model.pop_front()),
+                }
+                harness.test_function(&mut executor, "length", &[], Some(&uint_token(model.len() as u64)));
+            }
+
+            for (index, value) in model.iter().enumerate() {
+                harness.test_function(&mut executor, "get", &[uint_token(index as u64)], Some(&uint_token(*value)));
+            }
+        });
+    }
+
+    #[test]
+    fn stack(ops in prop::collection::vec(stack_op(), 0..32)) {
+        with_executor(&|mut executor| {
+            let harness = deploy_contract(&mut executor, "features/stack.fe", "Foo", &[]);
+            let mut model = vec![];
+
+            for op in &ops {
+                match op {
+                    StackOp::Push(value) => {
+                        harness.test_function(&mut executor, "push", &[uint_token(*value)], None);
+                        model.push(*value);
+                    }
+                    StackOp::Pop => test_remove(&harness, &mut executor, "pop", model.pop()),
+                }
+                harness.test_function(&mut executor, "length", &[], Some(&uint_token(model.len() as u64)));
+            }
+        });
+    }
+}
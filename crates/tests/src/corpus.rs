@@ -0,0 +1,132 @@
+//! A regression corpus of fixture contracts. For each contract, the hash of
+//! its bytecode and the gas used to deploy it and to call some of its
+//! functions are compared to the numbers recorded in the snapshot of the
+//! test, so that any change to the generated code shows up in review.
+//!
+//! If a change to the code generator is expected to change the numbers,
+//! run `cargo insta test --features solc-backend -p fe-compiler-tests -- corpus`
+//! followed by `cargo insta review`, and explain the change in the pull
+//! request.
+#![cfg(feature = "solc-backend")]
+
+use fe_common::utils::keccak;
+use fe_compiler_test_utils::*;
+use insta::assert_snapshot;
+use std::cell::RefCell;
+
+/// Deploys the contract and calls the functions in order, and returns a
+/// report of the bytecode hash and the gas used by each step.
+fn corpus_report(
+    fixture: &str,
+    contract_name: &str,
+    init_params: &[ethabi::Token],
+    calls: &[(&str, &[ethabi::Token])],
+) -> String {
+    let module = compile_fixture(fixture);
+    let bytecode = &module
+        .contracts
+        .get(contract_name)
+        .expect("could not find contract in fixture")
+        .bytecode;
+    let bytecode = hex::decode(bytecode).expect("failed to decode bytecode");
+
+    let report = RefCell::new(vec![
+        format!("bytecode size: {}", bytecode.len()),
+        format!("bytecode hash: {}", keccak::full(&bytecode)),
+    ]);
+
+    with_executor(&|mut executor| {
+        let used_gas = executor.used_gas();
+        let harness = deploy_compiled_contract(&mut executor, &module, contract_name, init_params);
+        let mut report = report.borrow_mut();
+        report.push(format!("deploy: {} gas", executor.used_gas() - used_gas));
+
+        for (name, args) in calls {
+            let used_gas = executor.used_gas();
+            let exit_reason = match harness.capture_call(&mut executor, name, args) {
+                evm::Capture::Exit((reason, _)) => reason,
+                evm::Capture::Trap(_) => unreachable!(),
+            };
+            report.push(format!(
+                "{}: {} gas, {:?}",
+                name,
+                executor.used_gas() - used_gas,
+                exit_reason
+            ));
+        }
+    });
+
+    report.into_inner().join("\n")
+}
+
+macro_rules! test_corpus {
+    ($name:ident, $fixture:expr, $contract:expr, [$($init:expr),*], [$(($fn:expr, [$($arg:expr),*])),* $(,)?]) => {
+        #[test]
+        fn $name() {
+            assert_snapshot!(corpus_report(
+                $fixture,
+                $contract,
+                &[$($init),*],
+                &[$(($fn, &[$($arg),*][..])),*],
+            ));
+        }
+    };
+}
+
+test_corpus! {
+    erc20_token,
+    "demos/erc20_token.fe",
+    "ERC20",
+    [string_token("Fe Coin"), string_token("fe")],
+    [
+        ("transfer", [address_token("2000000000000000000000000000000000000002"), uint_token(100)]),
+        ("approve", [address_token("2000000000000000000000000000000000000002"), uint_token(50)]),
+        ("balanceOf", [address_token(DEFAULT_CALLER)]),
+        ("name", []),
+    ]
+}
+
+test_corpus! {
+    guest_book,
+    "demos/guest_book.fe",
+    "GuestBook",
+    [],
+    [
+        ("sign", [string_token("hello world")]),
+        ("get_msg", [address_token(DEFAULT_CALLER)]),
+    ]
+}
+
+test_corpus! {
+    math,
+    "features/math.fe",
+    "Math",
+    [],
+    [
+        ("sqrt", [uint_token(1000000)]),
+        ("min", [uint_token(1), uint_token(2)]),
+    ]
+}
+
+test_corpus! {
+    aug_assign,
+    "features/aug_assign.fe",
+    "Foo",
+    [],
+    [
+        ("pow", [uint_token(3), uint_token(5)]),
+        ("add_from_sto", [uint_token(2), uint_token(5)]),
+    ]
+}
+
+test_corpus! {
+    packed_struct,
+    "features/packed_struct.fe",
+    "Foo",
+    [],
+    [
+        ("set_account", [address_token("1234000000000000000000000000000000005678"), uint_token(1000)]),
+        ("bump", []),
+        ("get_account", []),
+    ]
+}
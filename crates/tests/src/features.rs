@@ -4,6 +4,7 @@
 use evm_runtime::Handler;
 use primitive_types::{H160, U256};
 use rstest::rstest;
+use std::cmp::Ordering;
 use std::collections::BTreeMap;
 
 use fe_common::utils::keccak;
@@ -108,6 +109,459 @@ fn test_send_value() {
     })
 }
 
+#[test]
+fn test_permit() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "permit.fe", "Foo", &[]);
+
+        // A signature of `digest` by the key 0x4c0883a6...3f362318
+        let signer = "2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+        let digest = "19caaf813611a572cb901553f9be0da6c03230787ae81b6e33f3837253daa02a";
+        let r = "bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020d";
+        let s = "254f107badc9c3d2e3dc86c3d13f37c403c6d7d363f3cb5d6ee40ed766cb3fa9";
+        let word =
+            |hex: &str| ethabi::Token::Uint(U256::from_big_endian(&hex::decode(hex).unwrap()));
+
+        harness.test_function(
+            &mut executor,
+            "recover",
+            &[word(digest), uint_token(27), word(r), word(s)],
+            Some(&address_token(signer)),
+        );
+
+        let packed = [hex::decode(r).unwrap(), hex::decode(s).unwrap(), vec![27]].concat();
+        harness.test_function(
+            &mut executor,
+            "recover_packed",
+            &[word(digest), ethabi::Token::Bytes(packed)],
+            Some(&address_token(signer)),
+        );
+
+        // A signature with the wrong `v` value is rejected
+        validate_revert(
+            harness.capture_call(
+                &mut executor,
+                "recover",
+                &[word(digest), uint_token(29), word(r), word(s)],
+            ),
+            &encode_revert("Error(uint256)", &[uint_token(0x110)]),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "nonces",
+            &[address_token(signer)],
+            Some(&uint_token(0)),
+        );
+
+        // The signature doesn't match the permit message
+        validate_revert(
+            harness.capture_call(
+                &mut executor,
+                "permit",
+                &[
+                    address_token(signer),
+                    address_token(SOME_ADDRESS),
+                    uint_token(100),
+                    word("ff".repeat(32).as_str()),
+                    uint_token(27),
+                    word(r),
+                    word(s),
+                ],
+            ),
+            &encode_revert("Error(uint256)", &[uint_token(0x110)]),
+        );
+    })
+}
+
+#[test]
+fn test_ecdsa() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "ecdsa.fe", "Foo", &[]);
+
+        // A signature of `digest` by the key 0x4c0883a6...3f362318
+        let signer = "2c7536e3605d9c16a7a3d7b1898e529396a65c23";
+        let digest = "19caaf813611a572cb901553f9be0da6c03230787ae81b6e33f3837253daa02a";
+        let r = "bb50e2d89a4ed70663d080659fe0ad4b9bc3e06c17a227433966cb59ceee020d";
+        let s = "254f107badc9c3d2e3dc86c3d13f37c403c6d7d363f3cb5d6ee40ed766cb3fa9";
+        // The order of secp256k1 minus `s`, which makes the same signature
+        // malleable when `v` is flipped
+        let high_s = "dab0ef8452363c2d1c23793c2ec0c83ab6e805134b54d4de50ee4fb5696b0198";
+        let zero = "00".repeat(32);
+        let word =
+            |hex: &str| ethabi::Token::Uint(U256::from_big_endian(&hex::decode(hex).unwrap()));
+
+        harness.test_function(
+            &mut executor,
+            "recover",
+            &[word(digest), uint_token(27), word(r), word(s)],
+            Some(&address_token(signer)),
+        );
+
+        for (v, r, s, rejection) in [
+            (27, r, s, 0),
+            (29, r, s, 1),
+            (0, r, s, 1),
+            (28, r, high_s, 2),
+            (27, zero.as_str(), s, 3),
+        ] {
+            harness.test_function(
+                &mut executor,
+                "rejection",
+                &[word(digest), uint_token(v), word(r), word(s)],
+                Some(&uint_token(rejection)),
+            );
+        }
+
+        harness.test_function(
+            &mut executor,
+            "recover",
+            &[word(digest), uint_token(28), word(r), word(high_s)],
+            Some(&address_token("0000000000000000000000000000000000000000")),
+        );
+    })
+}
+
+#[test]
+fn test_enumerable_map() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "enumerable_map.fe", "Foo", &[]);
+        let alice = address_token("1000000000000000000000000000000000000001");
+        let bob = address_token("2000000000000000000000000000000000000002");
+        let carol = address_token("3000000000000000000000000000000000000003");
+
+        for (holder, value) in [(&alice, 10), (&bob, 20), (&carol, 30)] {
+            harness.test_function(
+                &mut executor,
+                "set_balance",
+                &[holder.clone(), uint_token(value)],
+                None,
+            );
+        }
+        // Updating a value doesn't add the key again
+        harness.test_function(
+            &mut executor,
+            "set_balance",
+            &[alice.clone(), uint_token(15)],
+            None,
+        );
+        harness.test_function(&mut executor, "holder_count", &[], Some(&uint_token(3)));
+        harness.test_function(&mut executor, "holder_at", &[uint_token(1)], Some(&bob));
+        harness.test_function(&mut executor, "total_supply", &[], Some(&uint_token(65)));
+
+        // Removing `bob` moves the last key into its place
+        harness.test_function(
+            &mut executor,
+            "set_balance",
+            &[bob.clone(), uint_token(0)],
+            None,
+        );
+        harness.test_function(&mut executor, "holder_count", &[], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "holder_at", &[uint_token(0)], Some(&alice));
+        harness.test_function(&mut executor, "holder_at", &[uint_token(1)], Some(&carol));
+        harness.test_function(
+            &mut executor,
+            "is_holder",
+            &[bob.clone()],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(&mut executor, "balance_of", &[bob], Some(&uint_token(0)));
+        harness.test_function(&mut executor, "total_supply", &[], Some(&uint_token(45)));
+
+        validate_revert(
+            harness.capture_call(&mut executor, "holder_at", &[uint_token(2)]),
+            &encode_revert("Error(uint256)", &[uint_token(0x120)]),
+        );
+    })
+}
+
+#[test]
+fn test_deque() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "deque.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "is_empty", &[], Some(&bool_token(true)));
+        harness.test_function(&mut executor, "push_back", &[uint_token(2)], None);
+        harness.test_function(&mut executor, "push_back", &[uint_token(3)], None);
+        // The front wraps around to the end of the values' slots
+        harness.test_function(&mut executor, "push_front", &[uint_token(1)], None);
+        harness.test_function(&mut executor, "length", &[], Some(&uint_token(3)));
+        harness.test_function(&mut executor, "front", &[], Some(&uint_token(1)));
+        harness.test_function(&mut executor, "back", &[], Some(&uint_token(3)));
+        harness.test_function(&mut executor, "get", &[uint_token(1)], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "other_length", &[], Some(&uint_token(0)));
+
+        harness.test_function(&mut executor, "pop_front", &[], Some(&uint_token(1)));
+        harness.test_function(&mut executor, "pop_back", &[], Some(&uint_token(3)));
+        harness.test_function(&mut executor, "pop_front", &[], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "is_empty", &[], Some(&bool_token(true)));
+
+        for name in ["pop_front", "pop_back", "front", "back"] {
+            validate_revert(
+                harness.capture_call(&mut executor, name, &[]),
+                &encode_revert("Error(uint256)", &[uint_token(0x121)]),
+            );
+        }
+        validate_revert(
+            harness.capture_call(&mut executor, "get", &[uint_token(0)]),
+            &encode_revert("Error(uint256)", &[uint_token(0x120)]),
+        );
+    })
+}
+
+#[test]
+fn test_stack() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "stack.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "push", &[uint_token(1)], None);
+        harness.test_function(&mut executor, "push", &[uint_token(2)], None);
+        harness.test_function(&mut executor, "length", &[], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "top", &[], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "pop", &[], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "pop", &[], Some(&uint_token(1)));
+        harness.test_function(&mut executor, "is_empty", &[], Some(&bool_token(true)));
+
+        for name in ["pop", "top"] {
+            validate_revert(
+                harness.capture_call(&mut executor, name, &[]),
+                &encode_revert("Error(uint256)", &[uint_token(0x121)]),
+            );
+        }
+    })
+}
+
+#[test]
+fn test_merkle_proof() {
+    let hash = |hex: &str| ethabi::Token::Uint(U256::from_str_radix(hex, 16).unwrap());
+    let leaf = |letter: &str| {
+        ethabi::Token::Uint(U256::from_big_endian(&keccak::full_as_bytes(
+            letter.as_bytes(),
+        )))
+    };
+    let proof = |hashes: &[&str]| {
+        let mut items: Vec<_> = hashes.iter().map(|hex| hash(hex)).collect();
+        items.resize(5, uint_token(0));
+        ethabi::Token::FixedArray(items)
+    };
+
+    // The tree of OpenZeppelin's `MerkleProof` tests, whose leaves are the
+    // hashes of the letters "a" to "z". The last node of a level with an odd
+    // number of nodes is moved up to the next level.
+    let root = hash("47df3f5ed10f50791c589f1f45926c45023be386add6f1af8aedc037ff53d15a");
+    let proof_a = proof(&[
+        "b5553de315e0edf504d9150af82dafa5c4667fa618ed0a6f19c69b41166c5510",
+        "d253a52d4cb00de2895e85f2529e2976e6aaaa5c18106b68ab66813e14415669",
+        "65d48a9a71389c608324abbe0156c2f0dd4f751d76d5f789779f4b1a25ef4a02",
+        "83d4f0b3ec29205d420087ea6fd7d4921dd43df3493df649bce90811a6aa8ef8",
+        "003acd812bb3a1d9984c913a2ca579edbfc09b70f3f5f275102d6861d7ef4395",
+    ]);
+    let proof_z = proof(&[
+        "83847cf31c36389df832d0d4d3df7cf28f211e3f83173e5c157bab31573d61f3",
+        "e0745da87485cb60157fc5f044dea56e697f5d7febb17ef90182ab36d283e715",
+        "e7ae50e782cbf16cddca4fb340415ed5203a28781d47c20b81e717c4332b97d9",
+    ]);
+
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "merkle_proof.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "verify",
+            &[proof_a.clone(), uint_token(5), root.clone(), leaf("a")],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "verify",
+            &[proof_z.clone(), uint_token(3), root.clone(), leaf("z")],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "process_proof",
+            &[proof_a.clone(), uint_token(5), leaf("a")],
+            Some(&root),
+        );
+
+        // The proof of a different leaf
+        harness.test_function(
+            &mut executor,
+            "verify",
+            &[proof_z.clone(), uint_token(3), root.clone(), leaf("a")],
+            Some(&bool_token(false)),
+        );
+        // A truncated proof
+        harness.test_function(
+            &mut executor,
+            "verify",
+            &[proof_a.clone(), uint_token(4), root.clone(), leaf("a")],
+            Some(&bool_token(false)),
+        );
+
+        // The nodes are sorted before they're hashed
+        let hash_1_2 = hash("e90b7bceb6e7df5418fb78d8ee546e97c83a08bbccc01a0644d599ccd2a7c2e0");
+        harness.test_function(
+            &mut executor,
+            "hash_pair",
+            &[uint_token(1), uint_token(2)],
+            Some(&hash_1_2),
+        );
+        harness.test_function(
+            &mut executor,
+            "hash_pair",
+            &[uint_token(2), uint_token(1)],
+            Some(&hash_1_2),
+        );
+    })
+}
+
+#[test]
+fn test_encoding() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "encoding.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "hex_encode",
+            &[string_token("hello")],
+            Some(&string_token("68656c6c6f")),
+        );
+        harness.test_function(
+            &mut executor,
+            "hex_encode_u256",
+            &[uint_token(0xabcdef)],
+            Some(&string_token(&format!("0x{:0>64}", "abcdef"))),
+        );
+        harness.test_function(
+            &mut executor,
+            "hex_encode_address",
+            &[address_token(SOME_ADDRESS)],
+            Some(&string_token(&format!("0x{}", SOME_ADDRESS))),
+        );
+
+        for (data, encoded) in [
+            ("", ""),
+            ("a", "YQ=="),
+            ("ab", "YWI="),
+            ("abc", "YWJj"),
+            ("hello", "aGVsbG8="),
+        ] {
+            harness.test_function(
+                &mut executor,
+                "base64_encode",
+                &[string_token(data)],
+                Some(&string_token(encoded)),
+            );
+        }
+
+        harness.test_function(
+            &mut executor,
+            "token_uri",
+            &[string_token("Fe")],
+            Some(&string_token(
+                "data:application/json;base64,eyJuYW1lIjoiRmUifQ==",
+            )),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "base64_encode_short",
+            &[string_token("")],
+            Some(&string_token("")),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "base64_encode_short",
+            &[string_token("hello")],
+            &encoded_panic_out_of_bounds(),
+        );
+    })
+}
+
+#[test]
+fn test_time() {
+    let vicinity = evm::backend::MemoryVicinity {
+        gas_price: U256::zero(),
+        origin: H160::zero(),
+        chain_id: U256::zero(),
+        block_hashes: Vec::new(),
+        block_number: U256::zero(),
+        block_coinbase: H160::zero(),
+        block_timestamp: U256::from(1000),
+        block_difficulty: U256::zero(),
+        block_gas_limit: primitive_types::U256::MAX,
+    };
+    let backend = evm::backend::MemoryBackend::new(&vicinity, BTreeMap::new());
+
+    with_executor_backend(backend, &|mut executor| {
+        let harness = deploy_contract(&mut executor, "time.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "deadline",
+            &[uint_token(1000), uint_token(3)],
+            Some(&uint_token(1000 + 3 * 86400)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "deadline",
+            &[ethabi::Token::Uint(U256::MAX - 10), uint_token(1)],
+            &encoded_over_or_underflow(),
+        );
+
+        for (deadline, is_expired) in [(999, true), (1000, false), (1001, false)] {
+            harness.test_function(
+                &mut executor,
+                "is_expired",
+                &[uint_token(deadline)],
+                Some(&bool_token(is_expired)),
+            );
+        }
+
+        harness.test_function(
+            &mut executor,
+            "elapsed",
+            &[uint_token(100), uint_token(250)],
+            Some(&uint_token(150)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "elapsed",
+            &[uint_token(250), uint_token(100)],
+            &encoded_over_or_underflow(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "total",
+            &[],
+            Some(&uint_token(604800 + 2 * 86400 + 3 * 3600 + 4 * 60 + 5 - 60)),
+        );
+        harness.test_function(
+            &mut executor,
+            "repeated",
+            &[uint_token(60), uint_token(5)],
+            Some(&uint_token(300)),
+        );
+
+        for (a, b) in [(2, 1), (1, 1), (1, 2)] {
+            harness.test_function(
+                &mut executor,
+                "is_longer",
+                &[uint_token(a), uint_token(b)],
+                Some(&bool_token(a > b)),
+            );
+            harness.test_function(
+                &mut executor,
+                "is_same_time",
+                &[uint_token(a), uint_token(b)],
+                Some(&bool_token(a == b)),
+            );
+        }
+    })
+}
+
 #[test]
 fn test_revert() {
     with_executor(&|mut executor| {
@@ -225,6 +679,11 @@ fn test_assert() {
             ),
             &encode_error_reason(reason),
         );
+
+        validate_revert(
+            harness.capture_call(&mut executor, "revert_with_value", &[uint_token(4)]),
+            &encode_error_reason("Must be greater than five, got 4"),
+        );
     })
 }
 
@@ -270,6 +729,8 @@ fn test_arrays() {
     case("while_loop_test_from_sto.fe", &[], uint_token(42)),
     case("while_loop_with_break.fe", &[], uint_token(1)),
     case("while_loop_with_break_2.fe", &[], uint_token(1)),
+    case("loop.fe", &[uint_token(5)], uint_token(25)),
+    case("loop.fe", &[uint_token(1)], uint_token(18)),
     case("if_statement.fe", &[uint_token(6)], uint_token(1)),
     case("if_statement.fe", &[uint_token(4)], uint_token(0)),
     case("if_statement_test_from_sto.fe", &[], uint_token(42)),
@@ -277,6 +738,9 @@ fn test_arrays() {
     case("if_statement_with_block_declaration.fe", &[], uint_token(1)),
     case("ternary_expression.fe", &[uint_token(6)], uint_token(1)),
     case("ternary_expression.fe", &[uint_token(4)], uint_token(0)),
+    case("ternary_expression_types.fe", &[uint_token(200)], uint_token(100)),
+    case("ternary_expression_types.fe", &[uint_token(50)], uint_token(50)),
+    case("ternary_expression_types.fe", &[uint_token(5)], uint_token(0)),
     case("call_statement_without_args.fe", &[], uint_token(100)),
     case("call_statement_with_args.fe", &[], uint_token(100)),
     case("call_statement_with_args_2.fe", &[], uint_token(100)),
@@ -314,6 +778,8 @@ fn test_arrays() {
     case("return_msg_sig.fe", &[], uint_token(4273672062)),
     case("return_sum_list_expression_1.fe", &[], uint_token(210)),
     case("return_sum_list_expression_2.fe", &[], uint_token(210)),
+    case("return_sum_list_expression_fill.fe", &[uint_token(10)], uint_token(376)),
+    case("return_sum_list_expression_fill.fe", &[uint_token(0)], uint_token(6)),
     case("pure_fn.fe", &[uint_token(42), uint_token(26)], uint_token(68)),
     case("pure_fn_internal_call.fe", &[uint_token(42), uint_token(26)], uint_token(68)),
     case("pure_fn_standalone.fe", &[uint_token(5)], uint_token(210)),
@@ -428,6 +894,7 @@ fn test_arrays() {
     case("radix_hex.fe", &[], uint_token(0xfe)),
     case("radix_octal.fe", &[], uint_token(0o70)),
     case("radix_binary.fe", &[], uint_token(0b10)),
+    case("number_literals.fe", &[], uint_token(1_000_000_000_000_069_100)),
     case::map_tuple("map_tuple.fe", &[uint_token(1234)], uint_token(1234)),
     case::int_literal_coercion("int_literal_coercion.fe", &[], uint_token(300)),
     case::associated_fns("associated_fns.fe", &[uint_token(12)], uint_token(144)),
@@ -580,8 +1047,6 @@ fn return_builtin_attributes() {
             deploy_contract(&mut executor, "return_builtin_attributes.fe", "Foo", &[]);
         let sender = address_token("1234000000000000000000000000000000005678");
         harness.caller = sender.clone().into_address().unwrap();
-        let value = 55555;
-        harness.value = U256::from(value);
         harness.test_function(&mut executor, "coinbase", &[], Some(&block_coinbase));
         harness.test_function(
             &mut executor,
@@ -603,7 +1068,14 @@ fn return_builtin_attributes() {
         );
         harness.test_function(&mut executor, "chainid", &[], Some(&uint_token(chain_id)));
         harness.test_function(&mut executor, "sender", &[], Some(&sender));
+
+        // only `#[payable]` functions accept ether
+        let value = 55555;
+        harness.value = U256::from(value);
         harness.test_function(&mut executor, "value", &[], Some(&uint_token(value)));
+        harness.test_function_reverts(&mut executor, "sender", &[], &[]);
+        harness.value = U256::zero();
+
         harness.test_function(&mut executor, "origin", &[], Some(&origin));
         harness.test_function(
             &mut executor,
@@ -687,7 +1159,7 @@ fn nested_map() {
         harness.test_function(
             &mut executor,
             "read_baz",
-            &[address1, uint_token(26)],
+            &[address1.clone(), uint_token(26)],
             Some(&bool_token(true)),
         );
         harness.test_function(
@@ -699,7 +1171,52 @@ fn nested_map() {
         harness.test_function(
             &mut executor,
             "read_baz",
-            &[address2, uint_token(100)],
+            &[address2.clone(), uint_token(100)],
+            Some(&bool_token(false)),
+        );
+
+        // increment bar in place
+        harness.test_function(
+            &mut executor,
+            "increment_bar",
+            &[address1.clone(), address2.clone()],
+            Some(&uint_token(13)),
+        );
+        harness.test_function(
+            &mut executor,
+            "increment_bar",
+            &[address2.clone(), address2.clone()],
+            Some(&uint_token(1)),
+        );
+
+        // write and read flags (address -> u256 -> u8 -> bool)
+        harness.test_function(
+            &mut executor,
+            "write_flag",
+            &[
+                address1.clone(),
+                uint_token(26),
+                uint_token(3),
+                bool_token(true),
+            ],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "read_flag",
+            &[address1.clone(), uint_token(26), uint_token(3)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "read_flag",
+            &[address1, uint_token(26), uint_token(4)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "read_flag",
+            &[address2, uint_token(26), uint_token(3)],
             Some(&bool_token(false)),
         );
     })
@@ -729,6 +1246,18 @@ fn events() {
             &[addr1.clone(), addr2],
             None,
         );
+        harness.test_function(
+            &mut executor,
+            "emit_indexed",
+            &[string_token("Fe"), uint_token(26), uint_token(42)],
+            None,
+        );
+
+        // Indexed values of reference types are logged as hashes.
+        let hash = |data: &[u8]| ethabi::Token::FixedBytes(keccak::full_as_bytes(data).to_vec());
+        // A struct and a static array with the same elements have the same
+        // encoding.
+        let point_hash = hash(&ethabi::encode(&[uint_token(26), uint_token(42)]));
 
         harness.events_emitted(
             executor,
@@ -737,86 +1266,1168 @@ fn events() {
                 ("Bases", &[uint_token(26), addr1.clone()]),
                 ("Mix", &[uint_token(26), addr1, uint_token(42), bytes]),
                 ("Addresses", &[addr_array]),
+                (
+                    "Indexed",
+                    &[hash(b"Fe"), point_hash.clone(), point_hash, uint_token(68)],
+                ),
             ],
         );
     })
 }
 
 #[test]
-fn constructor() {
+fn module_events() {
     with_executor(&|mut executor| {
-        let harness = deploy_contract(
+        let foo = deploy_contract(&mut executor, "module_events.fe", "Foo", &[]);
+        let bar = deploy_contract(&mut executor, "module_events.fe", "Bar", &[]);
+
+        let sender = address_token(DEFAULT_CALLER);
+        let receiver = address_token("2000000000000000000000000000000000000002");
+
+        foo.test_function(
+            &mut executor,
+            "transfer",
+            &[receiver.clone(), uint_token(26)],
+            None,
+        );
+        bar.test_function(&mut executor, "transfer", &[receiver.clone()], None);
+        foo.test_function(&mut executor, "emit_ping", &[uint_token(10)], None);
+
+        // The logs of an anonymous event have one topic for each indexed
+        // field, and no signature topic.
+        foo.events_emitted(
+            executor,
+            &[
+                (
+                    "Transfer",
+                    &[sender.clone(), receiver.clone(), uint_token(26)],
+                ),
+                ("Transfer", &[sender, receiver, uint_token(1)]),
+                (
+                    "Ping",
+                    &[
+                        uint_token(10),
+                        uint_token(11),
+                        uint_token(12),
+                        uint_token(13),
+                    ],
+                ),
+            ],
+        );
+    })
+}
+
+#[test]
+fn default_params() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "default_params.fe", "Foo", &[]);
+
+        // 1% of 5000, or at least `BASE_FEE * 2`
+        harness.test_function(
+            &mut executor,
+            "default_fee",
+            &[uint_token(5000)],
+            Some(&uint_token(50)),
+        );
+        harness.test_function(
+            &mut executor,
+            "default_fee",
+            &[uint_token(100)],
+            Some(&uint_token(20)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fee_with_percent",
+            &[uint_token(5000), uint_token(3)],
+            Some(&uint_token(150)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fee_with_minimum",
+            &[uint_token(100), uint_token(0)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "offset",
+            &[int_token(5)],
+            Some(&int_token(4)),
+        );
+        harness.test_function(
+            &mut executor,
+            "withdraw",
+            &[uint_token(100), uint_token(30)],
+            Some(&uint_token(1)),
+        );
+
+        harness.test_function(&mut executor, "set_fee", &[uint_token(7)], None);
+        harness.test_function(&mut executor, "fee_percent", &[], Some(&uint_token(7)));
+    })
+}
+
+#[test]
+fn contract_inheritance() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "contract_inheritance.fe", "Counter", &[]);
+        let owner = address_token(DEFAULT_CALLER);
+        let new_owner = address_token("2000000000000000000000000000000000000002");
+
+        // `owner` and `paused` are inherited from `Ownable` and `Pausable`.
+        harness.test_function(&mut executor, "owner", &[], Some(&owner));
+        harness.test_function(&mut executor, "paused", &[], Some(&bool_token(false)));
+
+        harness.test_function(&mut executor, "increment", &[], None);
+        harness.test_function(&mut executor, "count", &[], Some(&uint_token(1)));
+
+        harness.test_function(&mut executor, "set_paused", &[bool_token(true)], None);
+        harness.test_function_reverts(
+            &mut executor,
+            "increment",
+            &[],
+            &encode_error_reason("paused"),
+        );
+        harness.test_function(&mut executor, "set_paused", &[bool_token(false)], None);
+
+        harness.test_function(
+            &mut executor,
+            "transfer_ownership",
+            &[new_owner.clone()],
+            None,
+        );
+        harness.test_function(&mut executor, "owner", &[], Some(&new_owner));
+        harness.test_function_reverts(
+            &mut executor,
+            "set_paused",
+            &[bool_token(true)],
+            &encode_error_reason("not the owner"),
+        );
+
+        harness.set_caller(address("2000000000000000000000000000000000000002"));
+        harness.test_function(&mut executor, "set_paused", &[bool_token(true)], None);
+
+        harness.events_emitted(executor, &[("OwnershipTransferred", &[owner, new_owner])]);
+    })
+}
+
+#[test]
+fn fallback_receive() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "fallback_receive.fe", "Vault", &[]);
+        let sender = address_token(DEFAULT_CALLER);
+
+        // Calls with empty calldata go to `__receive__`, which is payable.
+        harness.value = U256::from(100);
+        harness.test_call_returns(&mut executor, vec![], &[]);
+        harness.value = U256::zero();
+        harness.test_function(&mut executor, "total_deposits", &[], Some(&uint_token(100)));
+
+        // Calls that don't select a function go to `__fallback__`.
+        let unknown_selector = vec![0xde, 0xad, 0xbe, 0xef];
+        harness.test_call_returns(&mut executor, unknown_selector.clone(), &[]);
+        harness.test_function(&mut executor, "fallback_count", &[], Some(&uint_token(1)));
+
+        // `__fallback__` isn't `#[payable]`.
+        harness.value = U256::from(1);
+        harness.test_call_reverts(&mut executor, unknown_selector, &[]);
+        harness.value = U256::zero();
+
+        harness.events_emitted(executor, &[("Received", &[sender, uint_token(100)])]);
+    })
+}
+
+#[test]
+fn function_overloading() {
+    with_executor(&|mut executor| {
+        let mut harness = deploy_contract(&mut executor, "function_overloading.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "min_unsigned",
+            &[uint_token(7), uint_token(3)],
+            Some(&uint_token(3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "min_signed",
+            &[int_token(-7), int_token(3)],
+            Some(&int_token(-7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "max_with_literal",
+            &[int_token(-20)],
+            Some(&int_token(-10)),
+        );
+        harness.test_function(&mut executor, "totals", &[], Some(&uint_token(15)));
+    })
+}
+
+#[test]
+fn constructor() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(
             &mut executor,
             "constructor.fe",
             "Foo",
             &[uint_token(26), uint_token(42)],
         );
 
-        harness.test_function(&mut executor, "read_bar", &[], Some(&uint_token(68)));
-    })
+        harness.test_function(&mut executor, "read_bar", &[], Some(&uint_token(68)));
+    })
+}
+
+#[test]
+fn strings() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(
+            &mut executor,
+            "strings.fe",
+            "Foo",
+            &[
+                string_token("string 1"),
+                address_token("1000000000000000000000000000000000000001"),
+                string_token("string 2"),
+                uint_token(42),
+                string_token("string 3"),
+            ],
+        );
+
+        harness.test_function(
+            &mut executor,
+            "bar",
+            &[string_token("string 4"), string_token("string 5")],
+            Some(&string_token("string 5")),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "return_static_string",
+            &[],
+            Some(&string_token("The quick brown fox jumps over the lazy dog")),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "return_casted_static_string",
+            &[],
+            Some(&string_token("foo")),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "return_special_chars",
+            &[],
+            Some(&string_token(
+                "\n\"'\r\t
+        foo\\",
+            )),
+        );
+
+        harness.events_emitted(
+            executor,
+            &[(
+                "MyEvent",
+                &[
+                    string_token("string 2"),
+                    uint_token(42),
+                    string_token("string 1"),
+                    string_token("string 3"),
+                    address_token("1000000000000000000000000000000000000001"),
+                    string_token("static string"),
+                    string_token("foo"),
+                ],
+            )],
+        );
+    });
+}
+
+#[test]
+fn string_operations() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "string_operations.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "join",
+            &[string_token("foo"), string_token("bar baz")],
+            Some(&string_token("foobar baz")),
+        );
+        harness.test_function(
+            &mut executor,
+            "join",
+            &[string_token(""), string_token("bar")],
+            Some(&string_token("bar")),
+        );
+
+        harness.test_function(&mut executor, "set_name", &[string_token("Fe")], None);
+        harness.test_function(
+            &mut executor,
+            "greet",
+            &[],
+            Some(&string_token("Hello, Fe")),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "substring",
+            &[string_token("hello world"), uint_token(6), uint_token(11)],
+            Some(&string_token("world")),
+        );
+        harness.test_function(
+            &mut executor,
+            "substring",
+            &[string_token("hello"), uint_token(2), uint_token(2)],
+            Some(&string_token("")),
+        );
+        validate_revert(
+            harness.capture_call(
+                &mut executor,
+                "substring",
+                &[string_token("hello"), uint_token(2), uint_token(6)],
+            ),
+            &encoded_panic_out_of_bounds(),
+        );
+        validate_revert(
+            harness.capture_call(
+                &mut executor,
+                "substring",
+                &[string_token("hello"), uint_token(3), uint_token(2)],
+            ),
+            &encoded_panic_out_of_bounds(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "prefix",
+            &[string_token("foobar")],
+            Some(&string_token("foo")),
+        );
+
+        for (a, b, ordering) in [
+            ("foo", "foo", Ordering::Equal),
+            ("foo", "bar", Ordering::Greater),
+            ("foo", "foobar", Ordering::Less),
+            ("", "a", Ordering::Less),
+            ("b", "abc", Ordering::Greater),
+        ] {
+            let args = [string_token(a), string_token(b)];
+            for (function, expected) in [
+                ("eq", ordering == Ordering::Equal),
+                ("ne", ordering != Ordering::Equal),
+                ("lt", ordering == Ordering::Less),
+                ("lte", ordering != Ordering::Greater),
+                ("gt", ordering == Ordering::Greater),
+                ("gte", ordering != Ordering::Less),
+            ] {
+                harness.test_function(&mut executor, function, &args, Some(&bool_token(expected)));
+            }
+        }
+
+        harness.test_function(
+            &mut executor,
+            "is_foo",
+            &[string_token("foo")],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_foo",
+            &[string_token("fo")],
+            Some(&bool_token(false)),
+        );
+
+        for (value, digits) in [
+            (uint_token(0), "0"),
+            (uint_token(42), "42"),
+            (uint_token(u64::MAX), "18446744073709551615"),
+            (
+                ethabi::Token::Uint(U256::MAX),
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+            ),
+        ] {
+            harness.test_function(
+                &mut executor,
+                "u256_to_string",
+                &[value],
+                Some(&string_token(digits)),
+            );
+        }
+        for (value, digits) in [(0, "0"), (127, "127"), (-5, "-5"), (-128, "-128")] {
+            harness.test_function(
+                &mut executor,
+                "i8_to_string",
+                &[int_token(value)],
+                Some(&string_token(digits)),
+            );
+        }
+        harness.test_function(
+            &mut executor,
+            "i256_to_string",
+            &[int_token(-1000000)],
+            Some(&string_token("-1000000")),
+        );
+    });
+}
+
+#[test]
+fn vec() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "vec.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "sum", &[uint_token(0)], Some(&uint_token(0)));
+        harness.test_function(
+            &mut executor,
+            "sum",
+            &[uint_token(10)],
+            Some(&uint_token(45)),
+        );
+
+        harness.test_function(&mut executor, "get", &[uint_token(0)], Some(&int_token(10)));
+        harness.test_function(
+            &mut executor,
+            "get",
+            &[uint_token(1)],
+            Some(&int_token(-25)),
+        );
+        validate_revert(
+            harness.capture_call(&mut executor, "get", &[uint_token(3)]),
+            &encoded_panic_out_of_bounds(),
+        );
+
+        harness.test_function(&mut executor, "len", &[uint_token(6)], Some(&uint_token(6)));
+
+        harness.test_function(
+            &mut executor,
+            "clone_and_push",
+            &[uint_token(3)],
+            Some(&tuple_token(&[uint_token(3), uint_token(104)])),
+        );
+        harness.test_function(
+            &mut executor,
+            "clone_and_push",
+            &[uint_token(5)],
+            Some(&tuple_token(&[uint_token(5), uint_token(111)])),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "split",
+            &[uint_token(10)],
+            Some(&tuple_token(&[uint_token(20), uint_token(125)])),
+        );
+
+        validate_revert(
+            harness.capture_call(&mut executor, "pop_empty", &[]),
+            &encoded_panic_empty_array_pop(),
+        );
+    });
+}
+
+#[test]
+fn low_level_calls() {
+    with_executor(&|mut executor| {
+        let counter = deploy_contract(&mut executor, "low_level_calls.fe", "Counter", &[]);
+        let caller = deploy_contract(&mut executor, "low_level_calls.fe", "Caller", &[]);
+        let counter_address = ethabi::Token::Address(counter.address);
+
+        caller.test_function(
+            &mut executor,
+            "call_increment",
+            &[counter_address.clone()],
+            Some(&uint_token(1)),
+        );
+        caller.test_function(
+            &mut executor,
+            "read_count",
+            &[counter_address.clone()],
+            Some(&uint_token(1)),
+        );
+        caller.test_function(
+            &mut executor,
+            "static_increment",
+            &[counter_address.clone()],
+            Some(&bool_token(false)),
+        );
+
+        // `delegate_call` runs the code of the counter on the storage of the
+        // caller.
+        caller.test_function(
+            &mut executor,
+            "delegate_increment",
+            &[counter_address.clone()],
+            Some(&uint_token(1)),
+        );
+        caller.test_function(&mut executor, "count", &[], Some(&uint_token(1)));
+        counter.test_function(&mut executor, "count", &[], Some(&uint_token(1)));
+
+        // The selector of `Failure(uint256)`, followed by the code.
+        caller.test_function(
+            &mut executor,
+            "call_fail",
+            &[counter_address],
+            Some(&uint_token(36)),
+        );
+    })
+}
+
+#[test]
+fn external_call_checks() {
+    with_executor(&|mut executor| {
+        let callee = deploy_contract(&mut executor, "external_call_checks.fe", "Callee", &[]);
+        let liar = deploy_contract(&mut executor, "external_call_checks.fe", "Liar", &[]);
+        let caller = deploy_contract(&mut executor, "external_call_checks.fe", "Caller", &[]);
+        let callee_address = ethabi::Token::Address(callee.address);
+        let liar_address = ethabi::Token::Address(liar.address);
+        let no_code = address_token("2000000000000000000000000000000000000002");
+
+        caller.test_function(
+            &mut executor,
+            "call_small",
+            &[callee_address.clone()],
+            Some(&uint_token(255)),
+        );
+        caller.test_function(&mut executor, "call_ping", &[callee_address.clone()], None);
+
+        // The revert data of the callee is propagated verbatim.
+        validate_revert(
+            caller.capture_call(&mut executor, "call_fail", &[callee_address]),
+            &encode_revert("Failure(uint256)", &[uint_token(7)]),
+        );
+
+        // The return data of `Liar.small` is too large for a `u8`.
+        validate_revert(
+            caller.capture_call(&mut executor, "call_small", &[liar_address]),
+            &encode_revert("Error(uint256)", &[uint_token(0x103)]),
+        );
+
+        // A call to an address without code reverts, instead of succeeding
+        // without doing anything.
+        validate_revert(
+            caller.capture_call(&mut executor, "call_ping", &[no_code.clone()]),
+            &encode_revert("Error(uint256)", &[uint_token(0x102)]),
+        );
+        validate_revert(
+            caller.capture_call(&mut executor, "call_small", &[no_code]),
+            &encode_revert("Error(uint256)", &[uint_token(0x103)]),
+        );
+    })
+}
+
+#[test]
+fn try_catch() {
+    with_executor(&|mut executor| {
+        let callee = deploy_contract(&mut executor, "try_catch.fe", "Callee", &[]);
+        let caller = deploy_contract(&mut executor, "try_catch.fe", "Caller", &[]);
+        let callee_address = ethabi::Token::Address(callee.address);
+
+        caller.test_function(
+            &mut executor,
+            "double",
+            &[callee_address.clone(), uint_token(21)],
+            Some(&uint_token(42)),
+        );
+        caller.test_function(
+            &mut executor,
+            "fail",
+            &[callee_address.clone(), uint_token(7)],
+            Some(&uint_token(7)),
+        );
+        caller.test_function(
+            &mut executor,
+            "fail_assert",
+            &[callee_address.clone()],
+            Some(&string_token("no luck")),
+        );
+        caller.test_function(
+            &mut executor,
+            "overflow",
+            &[callee_address.clone(), uint_token(0)],
+            Some(&uint_token(255)),
+        );
+        caller.test_function(
+            &mut executor,
+            "overflow",
+            &[callee_address.clone(), uint_token(1)],
+            Some(&uint_token(0x11)),
+        );
+        caller.test_function(
+            &mut executor,
+            "fail_plain",
+            &[callee_address.clone()],
+            Some(&uint_token(3)),
+        );
+        caller.test_function(
+            &mut executor,
+            "nested",
+            &[callee_address.clone(), uint_token(5)],
+            Some(&uint_token(11)),
+        );
+
+        // Errors that aren't caught are propagated verbatim.
+        validate_revert(
+            caller.capture_call(&mut executor, "fail_unexpected", &[callee_address]),
+            &encode_revert("Unexpected(bool)", &[bool_token(true)]),
+        );
+
+        // A call to an address without code isn't a revert of the callee, so
+        // it can't be caught.
+        validate_revert(
+            caller.capture_call(
+                &mut executor,
+                "fail_plain",
+                &[address_token("2000000000000000000000000000000000000002")],
+            ),
+            &encode_revert("Error(uint256)", &[uint_token(0x102)]),
+        );
+
+        // The calls of the caller are reported with the selectors of the
+        // callee's functions.
+        let module = test_utils::compile_fixture("features/try_catch.fe");
+        let report: serde_json::Value =
+            serde_json::from_str(&module.external_calls).expect("invalid report");
+        let calls = report["try_catch::Caller"]
+            .as_array()
+            .expect("calls of `Caller` aren't reported");
+        assert_eq!(calls.len(), 8);
+        assert!(calls
+            .iter()
+            .all(|call| call["is_try"] == true && call["transfers_value"] == false));
+        assert_eq!(calls[0]["function"], "try_catch::Caller::double");
+        assert_eq!(calls[0]["code"], "callee.double");
+        assert_eq!(calls[0]["callee"]["contract"], "try_catch::Callee");
+        let selector = callee.abi.function("double").unwrap().short_signature();
+        assert_eq!(
+            calls[0]["callee"]["selector"],
+            format!("0x{}", hex::encode(selector))
+        );
+        assert_eq!(report["try_catch::Callee"], serde_json::json!([]));
+    })
+}
+
+#[test]
+fn erc165() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "erc165.fe", "Token", &[]);
+        let interface_id = |id: &str| ethabi::Token::FixedBytes(hex::decode(id).unwrap());
+
+        for (id, is_supported) in [
+            // ERC-165 itself
+            ("01ffc9a7", true),
+            // `name()` ^ `burn(uint256)`
+            ("446bb26b", true),
+            // `Named`
+            ("06fdde03", true),
+            ("ffffffff", false),
+            ("42966c68", false),
+        ] {
+            harness.test_function(
+                &mut executor,
+                "supportsInterface",
+                &[interface_id(id)],
+                Some(&bool_token(is_supported)),
+            );
+        }
+    })
+}
+
+#[test]
+fn abi_builtins() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "abi_builtins.fe", "Foo", &[]);
+        let address = address_token("2000000000000000000000000000000000000002");
+
+        harness.test_function(
+            &mut executor,
+            "encoding_size",
+            &[uint_token(1), string_token("hello")],
+            Some(&uint_token(128)),
+        );
+        harness.test_function(
+            &mut executor,
+            "encoded_byte",
+            &[uint_token(0x0102), uint_token(30)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "roundtrip_u256",
+            &[uint_token(42)],
+            Some(&uint_token(42)),
+        );
+        harness.test_function(
+            &mut executor,
+            "roundtrip_point",
+            &[uint_token(7), int_token(-3)],
+            Some(&int_token(-3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "roundtrip_tuple",
+            &[address, bool_token(true)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "roundtrip_string",
+            &[string_token("hi")],
+            Some(&string_token("hi")),
+        );
+        harness.test_function(
+            &mut executor,
+            "decode_u8",
+            &[uint_token(255)],
+            Some(&uint_token(255)),
+        );
+
+        // Data that isn't a valid encoding of the type is rejected.
+        validate_revert(
+            harness.capture_call(&mut executor, "decode_u8", &[uint_token(256)]),
+            &encode_revert("Error(uint256)", &[uint_token(0x103)]),
+        );
+        validate_revert(
+            harness.capture_call(&mut executor, "decode_short", &[uint_token(1)]),
+            &encode_revert("Error(uint256)", &[uint_token(0x103)]),
+        );
+    })
+}
+
+#[test]
+fn packed_events() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "packed_events.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "emit_packed",
+            &[address_token("2000000000000000000000000000000000000002")],
+            None,
+        );
+        harness.test_function(&mut executor, "log", &[string_token("hi")], None);
+
+        // Each value takes up only as many bytes as its type, and strings are
+        // logged without their length or any padding.
+        let mut addr = [0; 20];
+        addr[0] = 0x20;
+        addr[19] = 0x02;
+        let packed = [&[0x12, 0x34, 0xfe, 0x01][..], &addr, b"hello", &[1, 2, 3]].concat();
+
+        harness.logs_emitted(executor, &[&packed, b"hi"]);
+    })
+}
+
+#[test]
+fn hashing() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "hashing.fe", "Foo", &[]);
+        let hash = |bytes: &[u8]| {
+            ethabi::Token::Uint(U256::from_big_endian(&keccak::full_as_bytes(bytes)))
+        };
+        let address = address_token("2000000000000000000000000000000000000002");
+        let small_array = |values: &[u64]| {
+            ethabi::Token::FixedArray(values.iter().map(|value| uint_token(*value)).collect())
+        };
+
+        harness.test_function(
+            &mut executor,
+            "hash_point",
+            &[uint_token(7), uint_token(9)],
+            Some(&hash(&ethabi::encode(&[uint_token(7), uint_token(9)]))),
+        );
+        harness.test_function(
+            &mut executor,
+            "hash_tuple",
+            &[address.clone(), bool_token(true)],
+            Some(&hash(&ethabi::encode(&[address, bool_token(true)]))),
+        );
+        harness.test_function(
+            &mut executor,
+            "hash_array",
+            &[small_array(&[1, 2, 3])],
+            Some(&hash(&ethabi::encode(&[small_array(&[1, 2, 3])]))),
+        );
+        harness.test_function(
+            &mut executor,
+            "hash_bytes",
+            &[small_array(&[0x61, 0x62, 0x63])],
+            Some(&hash(b"abc")),
+        );
+    })
+}
+
+#[test]
+fn dead_stores() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "dead_stores.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "set_twice",
+            &[uint_token(5)],
+            Some(&uint_token(105)),
+        );
+        harness.test_function(
+            &mut executor,
+            "set_twice",
+            &[uint_token(7)],
+            Some(&uint_token(207)),
+        );
+    });
+}
+
+#[test]
+fn in_bounds_indices() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "in_bounds_indices.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "get",
+            &[uint_array_token(&[1, 2, 3, 4]), uint_token(6)],
+            Some(&uint_token(31)),
+        );
+        harness.test_function(
+            &mut executor,
+            "get_from_storage",
+            &[uint_token(511)],
+            Some(&uint_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "get_from_storage",
+            &[uint_token(0)],
+            Some(&uint_token(0)),
+        );
+    });
+}
+
+#[test]
+fn loop_invariants() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "loop_invariants.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "count_to_limit", &[], Some(&uint_token(12)));
+        harness.test_function(
+            &mut executor,
+            "add_steps",
+            &[uint_token(2)],
+            Some(&uint_token(6)),
+        );
+        harness.test_function(
+            &mut executor,
+            "add_steps",
+            &[uint_token(3)],
+            Some(&uint_token(9)),
+        );
+    });
+}
+
+#[test]
+fn inline_yul() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "inline_yul.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "add",
+            &[uint_token(2), uint_token(3)],
+            Some(&uint_token(5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sum",
+            &[uint_array_token(&[1, 2, 3, 4])],
+            Some(&uint_token(10)),
+        );
+        harness.test_function(
+            &mut executor,
+            "copy",
+            &[uint_array_token(&[1, 2, 3, 4])],
+            Some(&uint_array_token(&[1, 2, 3, 4])),
+        );
+    });
 }
 
 #[test]
-fn strings() {
+fn function_values() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "function_values.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "calculate",
+            &[uint_token(0), uint_token(6), uint_token(3)],
+            Some(&uint_token(9)),
+        );
+        harness.test_function(
+            &mut executor,
+            "calculate",
+            &[uint_token(1), uint_token(6), uint_token(3)],
+            Some(&uint_token(18)),
+        );
+        harness.test_function(
+            &mut executor,
+            "calculate",
+            &[uint_token(2), uint_token(6), uint_token(3)],
+            Some(&uint_token(3)),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "fold",
+            &[uint_token(4)],
+            Some(&uint_token(10)),
+        );
+
+        harness.test_function(&mut executor, "check", &[uint_token(9)], None);
+        validate_revert(
+            harness.capture_call(&mut executor, "check", &[uint_token(10)]),
+            &encoded_panic_assert(),
+        );
+
+        validate_revert(
+            harness.capture_call(&mut executor, "uninitialized", &[]),
+            &encoded_panic_uninitialized_function(),
+        );
+    });
+}
+
+#[test]
+fn init_only() {
     with_executor(&|mut executor| {
+        let owner = address_token("1234000000000000000000000000000000005678");
         let harness = deploy_contract(
             &mut executor,
-            "strings.fe",
+            "init_only.fe",
             "Foo",
-            &[
-                string_token("string 1"),
-                address_token("1000000000000000000000000000000000000001"),
-                string_token("string 2"),
-                uint_token(42),
-                string_token("string 3"),
-            ],
+            &[owner.clone(), uint_token(42)],
         );
 
+        harness.test_function(&mut executor, "owner", &[], Some(&owner));
+        harness.test_function(&mut executor, "limit", &[], Some(&uint_token(42)));
+    });
+}
+
+#[test]
+fn packed_struct() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "packed_struct.fe", "Foo", &[]);
+        let owner = address_token("1234000000000000000000000000000000005678");
+
         harness.test_function(
             &mut executor,
-            "bar",
-            &[string_token("string 4"), string_token("string 5")],
-            Some(&string_token("string 5")),
+            "set_account",
+            &[owner.clone(), uint_token(1000)],
+            None,
         );
-
         harness.test_function(
             &mut executor,
-            "return_static_string",
+            "get_account",
             &[],
-            Some(&string_token("The quick brown fox jumps over the lazy dog")),
+            Some(&tuple_token(&[
+                owner.clone(),
+                uint_token(1),
+                uint_token(2),
+                bool_token(false),
+                uint_token(3),
+                uint_token(1000),
+            ])),
         );
+        harness.test_function(&mut executor, "bump", &[], Some(&uint_token(2)));
+        harness.test_function(&mut executor, "owner", &[], Some(&owner));
+        harness.test_function(&mut executor, "frozen", &[], Some(&bool_token(true)));
 
         harness.test_function(
             &mut executor,
-            "return_casted_static_string",
-            &[],
-            Some(&string_token("foo")),
+            "store_account",
+            &[owner.clone(), uint_token(7), uint_token(26)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "account_kind",
+            &[owner.clone()],
+            Some(&uint_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "account_balance",
+            &[owner],
+            Some(&uint_token(26)),
         );
+    });
+}
+
+#[test]
+fn packed_struct_reordered() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "packed_struct_reordered.fe", "Foo", &[]);
+        let maker = address_token("1234000000000000000000000000000000005678");
+        let order = |filled| {
+            tuple_token(&[
+                maker.clone(),
+                uint_token(1000),
+                uint_token(26),
+                bool_token(filled),
+            ])
+        };
 
         harness.test_function(
             &mut executor,
-            "return_special_chars",
-            &[],
-            Some(&string_token(
-                "\n\"'\r\t
-        foo\\",
-            )),
+            "set_orders",
+            &[maker.clone(), uint_token(1000), uint_token(26)],
+            None,
         );
+        harness.test_function(&mut executor, "get_order", &[], Some(&order(false)));
+        harness.test_function(&mut executor, "fill", &[], None);
+        harness.test_function(&mut executor, "get_order", &[], Some(&order(true)));
+        harness.test_function(&mut executor, "get_declared_order", &[], Some(&order(true)));
+        harness.test_function(&mut executor, "expiry", &[], Some(&uint_token(26)));
+    });
 
-        harness.events_emitted(
-            executor,
-            &[(
-                "MyEvent",
-                &[
-                    string_token("string 2"),
-                    uint_token(42),
-                    string_token("string 1"),
-                    string_token("string 3"),
-                    address_token("1000000000000000000000000000000000000001"),
-                    string_token("static string"),
-                    string_token("foo"),
-                ],
-            )],
+    let module = compile_fixture("features/packed_struct_reordered.fe");
+    assert_eq!(
+        module.contracts["Foo"].storage_layout,
+        r#"[
+  {
+    "layout": [
+      {
+        "name": "amount",
+        "offset": 0,
+        "size": 32,
+        "type": "u256"
+      },
+      {
+        "name": "maker",
+        "offset": 32,
+        "size": 20,
+        "type": "address"
+      },
+      {
+        "name": "expiry",
+        "offset": 52,
+        "size": 8,
+        "type": "u64"
+      },
+      {
+        "name": "filled",
+        "offset": 60,
+        "size": 1,
+        "type": "bool"
+      }
+    ],
+    "name": "order",
+    "nonce": 0,
+    "type": "Order",
+    "type_id": "0xc811b5685efe1368c36a2a1fa7ce3081"
+  },
+  {
+    "layout": [
+      {
+        "name": "maker",
+        "offset": 0,
+        "size": 20,
+        "type": "address"
+      },
+      {
+        "name": "amount",
+        "offset": 32,
+        "size": 32,
+        "type": "u256"
+      },
+      {
+        "name": "expiry",
+        "offset": 64,
+        "size": 8,
+        "type": "u64"
+      },
+      {
+        "name": "filled",
+        "offset": 72,
+        "size": 1,
+        "type": "bool"
+      }
+    ],
+    "name": "declared_order",
+    "nonce": 1,
+    "type": "DeclaredOrder",
+    "type_id": "0x96678eba98e67524a348232006cbd125"
+  }
+]"#
+    );
+}
+
+#[test]
+fn integer_conversions() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "integer_conversions.fe", "Foo", &[]);
+
+        for (name, input, expected) in [
+            ("u8_from_u256", uint_token(255), Some(uint_token(255))),
+            ("u8_from_u256", uint_token(256), None),
+            ("u16_from_i8", int_token(100), Some(uint_token(100))),
+            ("u16_from_i8", int_token(-1), None),
+            ("i8_from_u256", uint_token(127), Some(int_token(127))),
+            ("i8_from_u256", uint_token(128), None),
+            ("i8_from_i64", int_token(-128), Some(int_token(-128))),
+            ("i8_from_i64", int_token(-129), None),
+            ("u256_from_i256", int_token(5), Some(uint_token(5))),
+            ("u256_from_i256", int_token(-1), None),
+            ("truncate_to_u8", uint_token(258), Some(uint_token(2))),
+            (
+                "i8_to_u256",
+                int_token(-1),
+                Some(ethabi::Token::Uint(U256::MAX)),
+            ),
+        ] {
+            match expected {
+                Some(expected) => {
+                    harness.test_function(&mut executor, name, &[input], Some(&expected))
+                }
+                None => harness.test_function_reverts(
+                    &mut executor,
+                    name,
+                    &[input],
+                    &encoded_over_or_underflow(),
+                ),
+            }
+        }
+    });
+}
+
+#[test]
+fn operator_overloading() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "operator_overloading.fe", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "mul_add",
+            &[uint_token(2), uint_token(3), uint_token(4)],
+            Some(&uint_token(10)),
+        );
+        harness.test_function(
+            &mut executor,
+            "ratio",
+            &[uint_token(1), uint_token(4)],
+            Some(&uint_token(250)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_less",
+            &[uint_token(1), uint_token(2)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "is_equal",
+            &[uint_token(3), uint_token(4)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sum",
+            &[uint_token(3)],
+            Some(&uint_token(5500)),
         );
     });
 }
@@ -855,6 +2466,63 @@ fn test_numeric_sizes() {
     })
 }
 
+#[test]
+fn test_non_power_of_two_sizes() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "non_power_of_two_sizes.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "set_fee", &[uint_token(3000)], None);
+        harness.test_function(&mut executor, "get_fee", &[], Some(&uint_token(3000)));
+
+        harness.test_function(
+            &mut executor,
+            "add_u24",
+            &[uint_token(16777214), uint_token(1)],
+            Some(&uint_token(16777215)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "add_u24",
+            &[uint_token(16777215), uint_token(1)],
+            &encoded_over_or_underflow(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "sub_i40",
+            &[int_token(-549755813887), int_token(1)],
+            Some(&int_token(-549755813888)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "sub_i40",
+            &[int_token(-549755813888), int_token(1)],
+            &encoded_over_or_underflow(),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "get_u24_max",
+            &[],
+            Some(&uint_token(16777215)),
+        );
+        harness.test_function(
+            &mut executor,
+            "get_i40_min",
+            &[],
+            Some(&int_token(-549755813888)),
+        );
+
+        // casting to a smaller size masks the value
+        harness.test_function(
+            &mut executor,
+            "cast_u256_to_u24",
+            &[uint_token(0x1000001)],
+            Some(&uint_token(1)),
+        );
+    })
+}
+
 #[test]
 fn sized_vals_in_sto() {
     with_executor(&|mut executor| {
@@ -1193,6 +2861,33 @@ fn checked_arithmetic() {
     });
 }
 
+#[test]
+fn struct_update() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "struct_update.fe", "Foo", &[]);
+        let point = |x, y, z| tuple_token(&[uint_token(x), uint_token(y), uint_token(z)]);
+
+        harness.test_function(
+            &mut executor,
+            "move_x",
+            &[uint_token(7)],
+            Some(&point(7, 2, 3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "evaluation_order",
+            &[],
+            Some(&point(1, 20, 200)),
+        );
+        harness.test_function(
+            &mut executor,
+            "count_to",
+            &[uint_token(3)],
+            Some(&uint_token(6)),
+        );
+    })
+}
+
 #[test]
 fn structs() {
     with_executor(&|mut executor| {
@@ -1301,6 +2996,28 @@ fn keccak() {
     });
 }
 
+#[test]
+fn module_const() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "module_const.fe", "Foo", &[]);
+        let fe_hash = keccak::full_as_bytes("Fe".as_bytes());
+
+        harness.test_function(
+            &mut executor,
+            "fe_hash",
+            &[],
+            Some(&ethabi::Token::Uint(fe_hash.into())),
+        );
+        harness.test_function(
+            &mut executor,
+            "masked_hash",
+            &[],
+            Some(&uint_token(fe_hash[31].into())),
+        );
+        harness.test_function(&mut executor, "buffer_sum", &[], Some(&uint_token(20)));
+    });
+}
+
 #[test]
 fn short_circuit() {
     with_executor(&|mut executor| {
@@ -1324,6 +3041,60 @@ fn short_circuit() {
             Some(&bool_token(true)),
         );
         harness.test_function_reverts(&mut executor, "short_circuit_or", &[bool_token(false)], &[]);
+
+        harness.test_function(
+            &mut executor,
+            "short_circuit_external",
+            &[bool_token(false)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "short_circuit_external",
+            &[bool_token(true)],
+            &[],
+        );
+
+        harness.test_function(
+            &mut executor,
+            "short_circuit_nested",
+            &[bool_token(false)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "short_circuit_nested",
+            &[bool_token(true)],
+            Some(&bool_token(true)),
+        );
+
+        harness.test_function(
+            &mut executor,
+            "short_circuit_chain",
+            &[uint_token(0)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "short_circuit_chain",
+            &[uint_token(20)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function_reverts(&mut executor, "short_circuit_chain", &[uint_token(5)], &[]);
+
+        // The middle operand of a chained comparison is only evaluated once.
+        harness.test_function(
+            &mut executor,
+            "chained_comparison",
+            &[uint_token(0), uint_token(5)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "chained_comparison",
+            &[uint_token(5), uint_token(10)],
+            Some(&uint_token(0)),
+        );
     });
 }
 
@@ -1358,10 +3129,12 @@ fn two_contracts() {
 #[test]
 fn external_contract() {
     with_executor(&|mut executor| {
-        let foo_harness = deploy_contract(&mut executor, "external_contract.fe", "Foo", &[]);
-        let proxy_harness = deploy_contract(&mut executor, "external_contract.fe", "FooProxy", &[]);
-
-        let foo_address = ethabi::Token::Address(foo_harness.address);
+        let contracts = Deployer::new()
+            .contract("foo", "features/external_contract.fe", "Foo", vec![])
+            .contract("proxy", "features/external_contract.fe", "FooProxy", vec![])
+            .deploy(&mut executor);
+        let proxy_harness = &contracts["proxy"];
+        let foo_address = contracts.address_token("foo");
         let my_num = uint_token(26);
         let my_addrs = address_array_token(&["0", "1", "42", "3", "4"]);
         let my_string = string_token("hello world");
@@ -1378,18 +3151,67 @@ fn external_contract() {
             None,
         );
 
-        let a = 26;
-        let b = 42;
-        let c = 26 * 42;
+        let a = 26;
+        let b = 42;
+        let c = 26 * 42;
+
+        proxy_harness.test_function(
+            &mut executor,
+            "call_build_array",
+            &[foo_address, uint_token(a), uint_token(b)],
+            Some(&uint_array_token(&[a, c, b])),
+        );
+
+        contracts["foo"].events_emitted(executor, &[("MyEvent", &[my_num, my_addrs, my_string])]);
+    })
+}
+
+#[test]
+fn interface() {
+    with_executor(&|mut executor| {
+        let contracts = Deployer::new()
+            .contract("counter", "features/interface.fe", "SimpleCounter", vec![])
+            .contract("proxy", "features/interface.fe", "CounterProxy", vec![])
+            .deploy(&mut executor);
+        let (counter_harness, proxy_harness) = (&contracts["counter"], &contracts["proxy"]);
+        let counter_address = contracts.address_token("counter");
 
         proxy_harness.test_function(
             &mut executor,
-            "call_build_array",
-            &[foo_address, uint_token(a), uint_token(b)],
-            Some(&uint_array_token(&[a, c, b])),
+            "increment_twice",
+            &[counter_address.clone(), uint_token(3)],
+            Some(&uint_token(9)),
+        );
+        proxy_harness.test_function(
+            &mut executor,
+            "value",
+            &[counter_address],
+            Some(&uint_token(6)),
         );
+        counter_harness.test_function(&mut executor, "value", &[], Some(&uint_token(6)));
+    })
+}
 
-        foo_harness.events_emitted(executor, &[("MyEvent", &[my_num, my_addrs, my_string])]);
+#[test]
+fn selector() {
+    with_executor(&|mut executor| {
+        let contracts = Deployer::new()
+            .contract("token", "features/selector.fe", "PinnedToken", vec![])
+            .contract("sender", "features/selector.fe", "Sender", vec![])
+            .deploy(&mut executor);
+        let token_address = contracts.address_token("token");
+        let to = address_token("2000000000000000000000000000000000000002");
+
+        // `Token.transfer` and `Token.balanceOf` reach the functions that pin
+        // their selectors.
+        for balance in [5, 10] {
+            contracts["sender"].test_function(
+                &mut executor,
+                "send",
+                &[token_address.clone(), to.clone(), uint_token(5)],
+                Some(&uint_token(balance)),
+            );
+        }
     })
 }
 
@@ -1408,6 +3230,16 @@ fn create2_contract() {
         let foo_harness = load_contract(foo_address, "create2_contract.fe", "Foo");
 
         foo_harness.test_function(&mut executor, "get_my_num", &[], Some(&uint_token(42)));
+
+        let predicted = factory_harness
+            .call_function(&mut executor, "predict_foo", &[uint_token(7)])
+            .expect("factory did not return an address");
+        factory_harness.test_function(
+            &mut executor,
+            "create2_foo_at",
+            &[uint_token(7)],
+            Some(&predicted),
+        );
     })
 }
 
@@ -1477,34 +3309,35 @@ fn self_address() {
     });
 }
 
-#[rstest(
-    target,
-    op,
-    value,
-    expected,
-    case(2, "add", 5, 7),
-    case(42, "sub", 26, 16),
-    case(10, "mul", 42, 420),
-    case(43, "div", 5, 8),
-    case(43, "mod", 5, 3),
-    case(3, "pow", 5, 243),
-    case(1, "lshift", 7, 128),
-    case(128, "rshift", 7, 1),
-    case(26, "bit_or", 42, 58),
-    case(26, "bit_xor", 42, 48),
-    case(26, "bit_and", 42, 10),
-    case(2, "add_from_sto", 5, 7),
-    case(2, "add_from_mem", 5, 7)
-)]
-fn aug_assign(target: u64, op: &str, value: u64, expected: u64) {
+#[test]
+fn aug_assign() {
     with_executor(&|mut executor| {
         let harness = deploy_contract(&mut executor, "aug_assign.fe", "Foo", &[]);
-        harness.test_function(
-            &mut executor,
-            op,
-            &[uint_token(target), uint_token(value)],
-            Some(&uint_token(expected)),
-        );
+        let snapshot = executor.snapshot();
+
+        for (target, op, value, expected) in [
+            (2, "add", 5, 7),
+            (42, "sub", 26, 16),
+            (10, "mul", 42, 420),
+            (43, "div", 5, 8),
+            (43, "mod", 5, 3),
+            (3, "pow", 5, 243),
+            (1, "lshift", 7, 128),
+            (128, "rshift", 7, 1),
+            (26, "bit_or", 42, 58),
+            (26, "bit_xor", 42, 48),
+            (26, "bit_and", 42, 10),
+            (2, "add_from_sto", 5, 7),
+            (2, "add_from_mem", 5, 7),
+        ] {
+            harness.test_function(
+                &mut executor,
+                op,
+                &[uint_token(target), uint_token(value)],
+                Some(&uint_token(expected)),
+            );
+            executor.revert_to(&snapshot);
+        }
     });
 }
 
@@ -1521,6 +3354,172 @@ fn base_tuple() {
     });
 }
 
+#[test]
+fn enums() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "enums.fe", "Foo", &[]);
+        harness.test_function(
+            &mut executor,
+            "empty",
+            &[],
+            Some(&tuple_token(&[
+                uint_token(0),
+                uint_token(0),
+                uint_token(0),
+                uint_token(0),
+            ])),
+        );
+        harness.test_function(
+            &mut executor,
+            "circle",
+            &[uint_token(26)],
+            Some(&tuple_token(&[
+                uint_token(1),
+                uint_token(26),
+                uint_token(0),
+                uint_token(0),
+            ])),
+        );
+        harness.test_function(
+            &mut executor,
+            "rect",
+            &[uint_token(3), uint_token(4)],
+            Some(&tuple_token(&[
+                uint_token(2),
+                uint_token(0),
+                uint_token(3),
+                uint_token(4),
+            ])),
+        );
+        harness.test_function(
+            &mut executor,
+            "flag",
+            &[address_token("2a")],
+            Some(&tuple_token(&[
+                uint_token(1),
+                bool_token(true),
+                address_token("2a"),
+            ])),
+        );
+        harness.test_function(
+            &mut executor,
+            "store_circle",
+            &[uint_token(7)],
+            Some(&tuple_token(&[
+                uint_token(1),
+                uint_token(7),
+                uint_token(0),
+                uint_token(0),
+            ])),
+        );
+    });
+}
+
+#[test]
+fn match_stmt() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "match.fe", "Foo", &[]);
+        harness.test_function(&mut executor, "empty_area", &[], Some(&uint_token(0)));
+        harness.test_function(
+            &mut executor,
+            "circle_area",
+            &[uint_token(2)],
+            Some(&uint_token(12)),
+        );
+        harness.test_function(
+            &mut executor,
+            "rect_area",
+            &[uint_token(3), uint_token(4)],
+            Some(&uint_token(12)),
+        );
+        harness.test_function(
+            &mut executor,
+            "stored_width",
+            &[uint_token(5)],
+            Some(&uint_token(5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "lookup",
+            &[uint_token(0)],
+            Some(&uint_token(100)),
+        );
+        harness.test_function(
+            &mut executor,
+            "lookup",
+            &[uint_token(1)],
+            Some(&uint_token(200)),
+        );
+        harness.test_function(
+            &mut executor,
+            "lookup",
+            &[uint_token(7)],
+            Some(&uint_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "signed",
+            &[int_token(-1)],
+            Some(&int_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "signed",
+            &[int_token(-5)],
+            Some(&int_token(-5)),
+        );
+        harness.test_function(
+            &mut executor,
+            "negate",
+            &[bool_token(true)],
+            Some(&bool_token(false)),
+        );
+        harness.test_function(
+            &mut executor,
+            "negate",
+            &[bool_token(false)],
+            Some(&bool_token(true)),
+        );
+    });
+}
+
+#[test]
+fn generics() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "generics.fe", "Foo", &[]);
+        harness.test_function(
+            &mut executor,
+            "swapped_first",
+            &[uint_token(1), bool_token(true)],
+            Some(&bool_token(true)),
+        );
+        harness.test_function(
+            &mut executor,
+            "largest",
+            &[uint_token(3), uint_token(7)],
+            Some(&uint_token(10)),
+        );
+        harness.test_function(
+            &mut executor,
+            "largest",
+            &[uint_token(20), uint_token(7)],
+            Some(&uint_token(20)),
+        );
+        harness.test_function(
+            &mut executor,
+            "wrapped",
+            &[uint_token(5)],
+            Some(&uint_token(7)),
+        );
+        harness.test_function(
+            &mut executor,
+            "wrapped_pair",
+            &[uint_token(9)],
+            Some(&uint_token(9)),
+        );
+    });
+}
+
 #[test]
 fn tuple_destructuring() {
     with_executor(&|mut executor| {
@@ -1532,6 +3531,70 @@ fn tuple_destructuring() {
             &[uint_token(1), bool_token(false)],
             Some(&uint_token(1)),
         );
+        harness.test_function(
+            &mut executor,
+            "untyped",
+            &[uint_token(3), bool_token(true)],
+            Some(&uint_token(9)),
+        );
+        harness.test_function(
+            &mut executor,
+            "untyped",
+            &[uint_token(3), bool_token(false)],
+            Some(&uint_token(3)),
+        );
+        harness.test_function(
+            &mut executor,
+            "swap",
+            &[uint_token(1), uint_token(2)],
+            Some(&uint_token(21)),
+        );
+    });
+}
+
+#[test]
+fn let_type_inference() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "let_type_inference.fe", "Foo", &[]);
+        let owner = address_token("1000000000000000000000000000000000000001");
+        harness.test_function(
+            &mut executor,
+            "open",
+            &[owner.clone(), uint_token(26)],
+            None,
+        );
+        harness.test_function(
+            &mut executor,
+            "position_amount",
+            &[owner],
+            Some(&uint_token(26)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "position_amount",
+            &[address_token("2000000000000000000000000000000000000002")],
+            &[],
+        );
+        harness.test_function(
+            &mut executor,
+            "sum",
+            &[uint_token(200), uint_token(100)],
+            Some(&uint_token(300)),
+        );
+        harness.test_function(
+            &mut executor,
+            "split",
+            &[uint_token(7)],
+            Some(&uint_token(34)),
+        );
+        harness.test_function(&mut executor, "literal", &[], Some(&uint_token(3)));
+        harness.test_function(
+            &mut executor,
+            "pick",
+            &[bool_token(false), uint_token(1), uint_token(2)],
+            Some(&uint_token(2)),
+        );
+        harness.test_function(&mut executor, "greeting", &[], Some(&string_token("hello")));
     });
 }
 
@@ -1753,6 +3816,246 @@ fn abi_decode_checks() {
     });
 }
 
+#[test]
+fn abi_decode_lenient() {
+    with_executor(&|mut executor| {
+        let module = test_utils::compile_fixture_with_abi_decoding(
+            "features/abi_decode_lenient.fe",
+            fe_driver::AbiDecoding::Lenient,
+        );
+        let harness = deploy_compiled_contract(&mut executor, &module, "Foo", &[]);
+
+        // dirty high bits of a `u8` are cleared, and trailing bytes are ignored
+        let mut data = harness.build_calldata("echo_u8", &[uint_token(42)]);
+        data[4 + 30] = 1;
+        data.extend_from_slice(&[0; 8]);
+        harness.test_call_returns(&mut executor, data, &ethabi::encode(&[uint_token(42)]));
+
+        // an `i16` is sign extended
+        let mut data = harness.build_calldata("echo_i16", &[int_token(-2)]);
+        data[4] = 0;
+        harness.test_call_returns(&mut executor, data, &ethabi::encode(&[int_token(-2)]));
+
+        // any non-zero word is `true`
+        let mut data = harness.build_calldata("echo_bool", &[bool_token(false)]);
+        data[4] = 1;
+        harness.test_call_returns(&mut executor, data, &ethabi::encode(&[bool_token(true)]));
+
+        let mut data = harness.build_calldata("echo_address", &[address_token("a")]);
+        data[4 + 11] = 0xff;
+        harness.test_call_returns(&mut executor, data, &ethabi::encode(&[address_token("a")]));
+
+        // the padding after string data isn't checked
+        let mut data = harness.build_calldata("echo_string", &[string_token("hello")]);
+        data[4 + 32 + 32 + 31] = 1;
+        data.push(42);
+        harness.test_call_returns(
+            &mut executor,
+            data,
+            &ethabi::encode(&[string_token("hello")]),
+        );
+    });
+}
+
+#[test]
+fn unchecked_block() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "unchecked_block.fe", "Foo", &[]);
+        harness.test_function(
+            &mut executor,
+            "add_u8",
+            &[uint_token(255), uint_token(2)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sub_i8",
+            &[int_token(-128), int_token(1)],
+            Some(&int_token(127)),
+        );
+        harness.test_function(
+            &mut executor,
+            "neg_i8",
+            &[int_token(-128)],
+            Some(&int_token(-128)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sum",
+            &[uint_array_token(&[200, 100, 10, 1])],
+            Some(&uint_token(55)),
+        );
+        harness.test_function(
+            &mut executor,
+            "checked_after_block",
+            &[uint_token(255), uint_token(11)],
+            Some(&uint_token(20)),
+        );
+
+        // arithmetic after the block is checked again
+        harness.test_function_reverts(
+            &mut executor,
+            "checked_after_block",
+            &[uint_token(100), uint_token(100)],
+            &encoded_over_or_underflow(),
+        );
+
+        // division by zero is checked either way
+        harness.test_function_reverts(
+            &mut executor,
+            "div_u8",
+            &[uint_token(1), uint_token(0)],
+            &encoded_div_or_mod_by_zero(),
+        );
+    })
+}
+
+#[test]
+fn overflow_unchecked() {
+    with_executor(&|mut executor| {
+        // overflow checks and revert strings are on by default
+        let harness = deploy_contract(&mut executor, "overflow_unchecked.fe", "Foo", &[]);
+        harness.test_function_reverts(
+            &mut executor,
+            "add_u8",
+            &[uint_token(255), uint_token(1)],
+            &encoded_over_or_underflow(),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "check",
+            &[uint_token(10)],
+            &encode_error_reason("x is too large"),
+        );
+        let module = test_utils::compile_fixture("features/overflow_unchecked.fe");
+        assert_eq!(module.unchecked_arithmetic, "[]");
+
+        let module =
+            test_utils::compile_fixture_with_options("features/overflow_unchecked.fe", |db| {
+                use fe_driver::YulgenDb;
+                db.set_overflow_checks(false);
+                db.set_strip_revert_strings(true);
+            });
+        let harness = deploy_compiled_contract(&mut executor, &module, "Foo", &[]);
+        harness.test_function(
+            &mut executor,
+            "add_u8",
+            &[uint_token(255), uint_token(2)],
+            Some(&uint_token(1)),
+        );
+        harness.test_function(
+            &mut executor,
+            "sub_i8",
+            &[int_token(-128), int_token(1)],
+            Some(&int_token(127)),
+        );
+        harness.test_function(
+            &mut executor,
+            "mul_u16",
+            &[uint_token(300), uint_token(300)],
+            Some(&uint_token(90000 - 65536)),
+        );
+        harness.test_function(
+            &mut executor,
+            "pow_u8",
+            &[uint_token(2), uint_token(9)],
+            Some(&uint_token(0)),
+        );
+        harness.test_function(
+            &mut executor,
+            "neg_i8",
+            &[int_token(-128)],
+            Some(&int_token(-128)),
+        );
+
+        // division by zero is checked either way
+        harness.test_function_reverts(
+            &mut executor,
+            "div_u8",
+            &[uint_token(1), uint_token(0)],
+            &encoded_div_or_mod_by_zero(),
+        );
+
+        // the message of a failing assert is left out
+        harness.test_function_reverts(&mut executor, "check", &[uint_token(10)], &[]);
+        harness.test_function(&mut executor, "check", &[uint_token(9)], None);
+
+        // `#[checked]` statements are checked either way
+        for function in ["checked_add_u8", "checked_in_unchecked"] {
+            harness.test_function_reverts(
+                &mut executor,
+                function,
+                &[uint_token(255), uint_token(1)],
+                &encoded_over_or_underflow(),
+            );
+        }
+
+        // the arithmetic that isn't checked is reported
+        let report: serde_json::Value =
+            serde_json::from_str(&module.unchecked_arithmetic).expect("invalid report");
+        let operations = report
+            .as_array()
+            .expect("report isn't an array")
+            .iter()
+            .map(|operation| {
+                (
+                    operation["function"].as_str().unwrap(),
+                    operation["code"].as_str().unwrap(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            operations,
+            vec![
+                ("overflow_unchecked::Foo::add_u8", "x + y"),
+                ("overflow_unchecked::Foo::sub_i8", "x - y"),
+                ("overflow_unchecked::Foo::mul_u16", "x * y"),
+                ("overflow_unchecked::Foo::pow_u8", "x ** y"),
+                ("overflow_unchecked::Foo::neg_i8", "-x"),
+            ]
+        );
+    })
+}
+
+#[test]
+fn constant_defines() {
+    with_executor(&|mut executor| {
+        let harness = deploy_contract(&mut executor, "constant_defines.fe", "Foo", &[]);
+        harness.test_function(&mut executor, "token", &[], Some(&address_token("0")));
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[uint_token(10_000)],
+            Some(&uint_token(0)),
+        );
+
+        let module =
+            test_utils::compile_fixture_with_options("features/constant_defines.fe", |db| {
+                fe_driver::define_constants(
+                    db,
+                    &[
+                        ("TOKEN", format!("0x{}", SOME_ADDRESS)),
+                        ("FEES_ENABLED", "true".to_string()),
+                        ("FEE_BPS", "2.5e1".to_string()),
+                    ],
+                )
+            });
+        let harness = deploy_compiled_contract(&mut executor, &module, "Foo", &[]);
+        harness.test_function(
+            &mut executor,
+            "token",
+            &[],
+            Some(&address_token(SOME_ADDRESS)),
+        );
+        harness.test_function(
+            &mut executor,
+            "fee",
+            &[uint_token(10_000)],
+            Some(&uint_token(25)),
+        );
+    })
+}
+
 #[test]
 fn intrinsics() {
     with_executor(&|mut executor| {
@@ -1805,6 +4108,24 @@ fn call_fn() {
     });
 }
 
+#[test]
+fn reordered_call_args() {
+    with_executor(&|mut executor| {
+        let bar = deploy_contract(&mut executor, "reordered_call_args.fe", "Bar", &[]);
+        let harness = deploy_contract(&mut executor, "reordered_call_args.fe", "Foo", &[]);
+
+        harness.test_function(&mut executor, "pure_call", &[], Some(&uint_token(9)));
+        harness.test_function(&mut executor, "method_call", &[], Some(&uint_token(123)));
+        harness.test_function(&mut executor, "generic_call", &[], Some(&uint_token(71)));
+        harness.test_function(
+            &mut executor,
+            "external_call",
+            &[ethabi::Token::Address(bar.address)],
+            Some(&uint_token(654)),
+        );
+    });
+}
+
 #[rstest(
     method,
     params,
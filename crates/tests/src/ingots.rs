@@ -29,6 +29,35 @@ fn test_ingot_pub_contract() {
         let _harness = deploy_ingot(&mut executor, "visibility_ingot", "Foo", &[]);
     })
 }
+#[test]
+fn test_ingot_with_glob_imports() {
+    with_executor(&|mut executor| {
+        let harness = deploy_ingot(&mut executor, "glob_imports_ingot", "Foo", &[]);
+
+        harness.test_function(
+            &mut executor,
+            "deposit",
+            &[uint_token(100)],
+            Some(&uint_token(100)),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "deposit",
+            &[uint_token(1001)],
+            &encode_revert("TooMuch(uint256)", &[uint_token(1001)]),
+        );
+        harness.test_function_reverts(
+            &mut executor,
+            "withdraw",
+            &[uint_token(101)],
+            &encode_revert(
+                "NotEnough(uint256,uint256)",
+                &[uint_token(101), uint_token(100)],
+            ),
+        );
+    })
+}
+
 #[test]
 fn test_basic_ingot() {
     with_executor(&|mut executor| {
@@ -1,4 +1,10 @@
 #[cfg(test)]
+mod audit;
+#[cfg(test)]
+mod collections;
+#[cfg(test)]
+mod corpus;
+#[cfg(test)]
 mod crashes;
 #[cfg(test)]
 mod demo_erc20;
@@ -99,3 +99,41 @@ fn signext_int_array(method: &str, params: &[ethabi::Token], expected: Option<et
         harness.test_function(&mut executor, method, params, expected.as_ref());
     })
 }
+
+#[test]
+fn verify_solidity_interface_implementation() {
+    with_executor(&|mut executor| {
+        let module = compile_fixture("features/interface.fe");
+
+        let counter = deploy_solidity_contract(
+            &mut executor,
+            "solidity/counter.sol",
+            "SolidityCounter",
+            &[],
+            false,
+        );
+        assert_eq!(counter.verify_interface(&module, "Counter"), Ok(()));
+
+        let drifted = deploy_solidity_contract(
+            &mut executor,
+            "solidity/counter.sol",
+            "DriftedCounter",
+            &[],
+            false,
+        );
+        let mismatches = drifted
+            .verify_interface(&module, "Counter")
+            .expect_err("interface mismatches not found");
+        assert_eq!(
+            mismatches
+                .iter()
+                .map(|mismatch| mismatch.signature.as_str())
+                .collect::<Vec<_>>(),
+            vec!["increment(uint256)", "value()"]
+        );
+        assert_eq!(
+            mismatches[1].reason,
+            "the function returns `(uint256,bool)` instead of `uint256`"
+        );
+    })
+}
@@ -252,14 +252,16 @@ fn tuple_stress() {
 #[test]
 fn external_calls_stress() {
     with_executor(&|mut executor| {
-        let foo_harness = deploy_contract(&mut executor, "external_calls.fe", "Foo", &[]);
-        let foo_address = ethabi::Token::Address(foo_harness.address);
-        let proxy_harness = deploy_contract(
-            &mut executor,
-            "external_calls.fe",
-            "FooProxy",
-            &[foo_address],
-        );
+        let contracts = Deployer::new()
+            .contract("foo", "stress/external_calls.fe", "Foo", vec![])
+            .contract(
+                "proxy",
+                "stress/external_calls.fe",
+                "FooProxy",
+                vec![InitParam::Contract("foo")],
+            )
+            .deploy(&mut executor);
+        let proxy_harness = &contracts["proxy"];
 
         let my_tuple = tuple_token(&[uint_token(42), address_token("26")]);
         let my_string = string_token("hello world");
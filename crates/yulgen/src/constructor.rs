@@ -1,7 +1,7 @@
 use crate::names::abi as abi_names;
 use crate::operations::abi as abi_operations;
 use crate::runtime::functions;
-use crate::types::{AbiDecodeLocation, AbiType};
+use crate::types::{AbiDecodeLocation, AbiDecoding, AbiType};
 use yultsur::*;
 
 /// Builds a constructor for a contract with no init function.
@@ -11,7 +11,11 @@ use yultsur::*;
 pub fn build() -> yul::Code {
     // we get the deployment statements and wrap them in a code block
     let deployment = deployment();
-    code! { [deployment...] }
+    // a contract without an init function can't receive ether when it's created
+    code! {
+        (if (callvalue()) { (revert(0, 0)) })
+        [deployment...]
+    }
 }
 
 /// Builds a constructor for a contract with an init function.
@@ -20,11 +24,16 @@ pub fn build_with_init(
     init_function_name: &str,
     init_params: &[AbiType],
     init_callgraph: Vec<yul::Statement>,
+    is_payable: bool,
 ) -> yul::Code {
     // Generate names for our constructor parameters.
     let (param_idents, param_exprs) = abi_names::vals("init", init_params.len());
 
-    let decode_fns = functions::abi::decode_functions(init_params, AbiDecodeLocation::Memory);
+    let decode_fns = functions::abi::decode_functions(
+        init_params,
+        AbiDecodeLocation::Memory,
+        AbiDecoding::Strict,
+    );
 
     // Decode the parameters, if any are given.
     let maybe_decode_params = if init_params.is_empty() {
@@ -45,6 +54,12 @@ pub fn build_with_init(
 
     let contract_name = literal_expression! { (format!("\"{}\"", contract_name)) };
 
+    let maybe_reject_value = if is_payable {
+        statements! {}
+    } else {
+        statements! { (if (callvalue()) { (revert(0, 0)) }) }
+    };
+
     let deployment = deployment();
 
     // Build a constructor that runs a user defined init function. Parameters for
@@ -59,6 +74,9 @@ pub fn build_with_init(
         [init_callgraph...]
         [decode_fns...]
 
+        // revert if ether is sent to an init function that isn't payable
+        [maybe_reject_value...]
+
         // copy the encoded parameters to memory
         (let params_start_code := datasize([contract_name]))
         (let params_end_code := codesize())
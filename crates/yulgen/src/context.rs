@@ -1,5 +1,6 @@
 use crate::{AnalyzerDb, YulgenDb};
 use fe_analyzer::context::{CallType, ExpressionAttributes, FunctionBody};
+use fe_analyzer::namespace::items::{Class, FunctionId};
 use fe_analyzer::namespace::types::{Event, FixedSize};
 use fe_parser::ast;
 use fe_parser::node::Node;
@@ -8,18 +9,47 @@ use std::rc::Rc;
 pub struct FnContext<'a> {
     pub adb: &'a dyn AnalyzerDb,
     pub db: &'a dyn YulgenDb,
+    function: FunctionId,
     fn_body: Rc<FunctionBody>,
+    /// `true` while the statements of an `unchecked` block are mapped.
+    pub unchecked: bool,
+    /// `true` while the statements of a `#[checked]` statement are mapped,
+    /// outside of any `unchecked` block that it contains.
+    pub checked: bool,
+    /// The number of `try` statements that have been mapped so far, used to
+    /// give their Yul variables unique names.
+    pub try_count: usize,
 }
 
 impl<'a> FnContext<'a> {
-    pub fn new(db: &'a dyn YulgenDb, fn_body: Rc<FunctionBody>) -> Self {
+    pub fn new(db: &'a dyn YulgenDb, function: FunctionId) -> Self {
         Self {
             adb: db.upcast(),
             db,
-            fn_body,
+            function,
+            fn_body: function.body(db.upcast()),
+            unchecked: false,
+            checked: false,
+            try_count: 0,
         }
     }
 
+    /// Whether integer arithmetic at the current position reverts when it
+    /// overflows. `#[checked]` statements are checked even if the build
+    /// profile disables overflow checks.
+    pub fn overflow_checks(&self) -> bool {
+        self.checked || (!self.unchecked && self.db.overflow_checks())
+    }
+
+    /// Whether the data of the events that the function emits is packed,
+    /// because its contract has the `#[abi(packed_events)]` attribute.
+    pub fn packs_event_data(&self) -> bool {
+        matches!(
+            self.function.class(self.adb),
+            Some(Class::Contract(contract)) if contract.packs_event_data(self.adb)
+        )
+    }
+
     /// Get information that has been attributed to an expression node.
     pub fn expression_attributes(&self, expr: &Node<ast::Expr>) -> &ExpressionAttributes {
         self.fn_body
@@ -36,6 +66,14 @@ impl<'a> FnContext<'a> {
             .expect("missing declaration type")
     }
 
+    /// Get the type of the error caught by a `catch` clause.
+    pub fn catch_type(&self, typ: &Node<ast::TypeDesc>) -> &FixedSize {
+        self.fn_body
+            .catch_types
+            .get(&typ.id)
+            .expect("missing catch type")
+    }
+
     /// Get information that has been attributed to a call expression node.
     pub fn call_type(&self, expr: &Node<ast::Expr>) -> CallType {
         self.fn_body
@@ -45,6 +83,24 @@ impl<'a> FnContext<'a> {
             .expect("missing call type")
     }
 
+    /// Get the function that a name expression refers to, if it's used as a
+    /// function value.
+    pub fn function_ref(&self, expr: &Node<ast::Expr>) -> Option<FunctionId> {
+        self.fn_body.function_refs.get(&expr.id).copied()
+    }
+
+    /// Whether an assignment statement stores a value that is overwritten
+    /// before it's read.
+    pub fn is_dead_store(&self, stmt: &Node<ast::FuncStmt>) -> bool {
+        self.fn_body.dead_stores.contains(&stmt.id)
+    }
+
+    /// Whether the index of an array subscript is known to be within the
+    /// bounds of the array.
+    pub fn is_in_bounds_index(&self, subscript: &Node<ast::Expr>) -> bool {
+        self.fn_body.in_bounds_indices.contains(&subscript.id)
+    }
+
     /// Get information that has been attributed to an emit statement node.
     pub fn emitted_event(&self, emit_stmt: &Node<ast::FuncStmt>) -> Rc<Event> {
         self.fn_body
@@ -1,4 +1,5 @@
-use crate::types::AbiType;
+use crate::types::{AbiDecoding, AbiType};
+use fe_analyzer::builtins::EvmVersion;
 use fe_analyzer::namespace::items::{ContractId, EventId, FunctionId, ModuleId, StructId};
 use fe_analyzer::AnalyzerDb;
 use fe_common::db::{SourceDb, SourceDbStorage, Upcast, UpcastMut};
@@ -21,6 +22,17 @@ pub trait YulgenDb:
     + UpcastMut<dyn SourceDb>
     + UpcastMut<dyn AnalyzerDb>
 {
+    /// How strictly the arguments of external calls are validated.
+    #[salsa::input]
+    fn abi_decoding(&self) -> AbiDecoding;
+    /// Whether integer arithmetic reverts when it overflows, instead of
+    /// wrapping around.
+    #[salsa::input]
+    fn overflow_checks(&self) -> bool;
+    /// Whether failing `assert` statements revert without their message.
+    #[salsa::input]
+    fn strip_revert_strings(&self) -> bool;
+
     #[salsa::invoke(queries::compile_module)]
     fn compile_module(&self, module_id: ModuleId) -> IndexMap<String, String>;
 
@@ -31,10 +43,16 @@ pub trait YulgenDb:
 
     #[salsa::invoke(queries::functions::function_yul_name)]
     fn function_yul_name(&self, function: FunctionId) -> SmolStr;
+    #[salsa::invoke(queries::functions::function_value_id)]
+    fn function_value_id(&self, function: FunctionId) -> SmolStr;
     #[salsa::invoke(queries::functions::function_external_call_name)]
     fn function_external_call_name(&self, function: FunctionId) -> SmolStr;
     #[salsa::invoke(queries::functions::function_external_call_fn)]
     fn function_external_call_fn(&self, function: FunctionId) -> Vec<yul::Statement>;
+    #[salsa::invoke(queries::functions::function_external_try_call_name)]
+    fn function_external_try_call_name(&self, function: FunctionId) -> SmolStr;
+    #[salsa::invoke(queries::functions::function_external_try_call_fn)]
+    fn function_external_try_call_fn(&self, function: FunctionId) -> Vec<yul::Statement>;
     #[salsa::invoke(queries::functions::function_def)]
     fn function_def(&self, function: FunctionId) -> yul::Statement;
     #[salsa::invoke(queries::functions::function_sig_abi_types)]
@@ -61,6 +79,16 @@ pub trait YulgenDb:
     fn struct_init_name(&self, id: StructId) -> SmolStr;
     #[salsa::invoke(queries::structs::struct_init_fn)]
     fn struct_init_fn(&self, id: StructId) -> yul::Statement;
+    #[salsa::invoke(queries::structs::struct_packed_offsets)]
+    fn struct_packed_offsets(&self, id: StructId) -> Rc<[usize]>;
+    #[salsa::invoke(queries::structs::struct_sload_packed_name)]
+    fn struct_sload_packed_name(&self, id: StructId) -> SmolStr;
+    #[salsa::invoke(queries::structs::struct_sload_packed_fn)]
+    fn struct_sload_packed_fn(&self, id: StructId) -> yul::Statement;
+    #[salsa::invoke(queries::structs::struct_sstore_packed_name)]
+    fn struct_sstore_packed_name(&self, id: StructId) -> SmolStr;
+    #[salsa::invoke(queries::structs::struct_sstore_packed_fn)]
+    fn struct_sstore_packed_fn(&self, id: StructId) -> yul::Statement;
     #[salsa::invoke(queries::structs::struct_api_fns)]
     fn struct_api_fns(&self, id: StructId) -> Vec<yul::Statement>;
 }
@@ -71,12 +99,25 @@ pub trait YulgenDb:
     fe_lowering::db::LoweringDbStorage,
     YulgenDbStorage
 )]
-#[derive(Default)]
 pub struct Db {
     storage: salsa::Storage<Db>,
 }
 impl salsa::Database for Db {}
 
+impl Default for Db {
+    fn default() -> Self {
+        let mut db = Db {
+            storage: Default::default(),
+        };
+        db.set_abi_decoding(AbiDecoding::default());
+        db.set_overflow_checks(true);
+        db.set_strip_revert_strings(false);
+        db.set_constant_defines(Rc::new(IndexMap::new()));
+        db.set_evm_version(EvmVersion::default());
+        db
+    }
+}
+
 impl Upcast<dyn SourceDb> for Db {
     fn upcast(&self) -> &(dyn SourceDb + 'static) {
         &*self
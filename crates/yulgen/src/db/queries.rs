@@ -11,7 +11,7 @@ pub mod structs;
 
 pub fn compile_module(db: &dyn YulgenDb, module: ModuleId) -> IndexMap<String, String> {
     mappers::module::module(db, module)
-        .drain()
+        .into_iter()
         .map(|(name, object)| (name, to_safe_json(object)))
         .collect()
 }
@@ -3,11 +3,13 @@ use crate::context::FnContext;
 use crate::db::YulgenDb;
 use crate::mappers::functions::multiple_func_stmt;
 use crate::runtime::{abi_dispatcher, functions};
-use crate::types::{AbiDecodeLocation, AsAbiType};
-use fe_analyzer::builtins::ValueMethod;
+use crate::types::{AbiDecodeLocation, AbiDecoding, AsAbiType};
+use fe_analyzer::builtins::{GlobalFunction, ValueMethod};
 use fe_analyzer::context::CallType;
-use fe_analyzer::namespace::items::{walk_local_dependencies, ContractId, DepGraph, Item, TypeDef};
-use fe_analyzer::namespace::types::FixedSize;
+use fe_analyzer::namespace::items::{
+    walk_local_dependencies, ContractId, DepGraph, FunctionId, Item, TypeDef,
+};
+use fe_analyzer::namespace::types::{Base, FixedSize, FunctionType, Integer, Type};
 use fe_common::utils::keccak;
 use indexmap::IndexSet;
 use smol_str::SmolStr;
@@ -34,7 +36,7 @@ pub fn contract_object(db: &dyn YulgenDb, contract: ContractId) -> yul::Object {
         // - call the `$$__call__` function
         let call_fn_ident = identifier! { ("$$__call__") };
         if let Some(call_fn) = contract.call_function(adb) {
-            let mut fn_context = FnContext::new(db, call_fn.body(adb));
+            let mut fn_context = FnContext::new(db, call_fn);
             let function_statements =
                 multiple_func_stmt(&mut fn_context, &call_fn.data(adb).ast.kind.body);
             let call_fn_yul = function_definition! {
@@ -77,6 +79,7 @@ pub fn contract_object(db: &dyn YulgenDb, contract: ContractId) -> yul::Object {
             &db.function_yul_name(init_fn),
             &params,
             functions,
+            init_fn.is_payable(adb),
         );
 
         // Return constructor object
@@ -99,31 +102,56 @@ pub fn contract_object(db: &dyn YulgenDb, contract: ContractId) -> yul::Object {
 /// Dispatch function and required encode/decode functions.
 pub fn contract_abi_dispatcher(db: &dyn YulgenDb, contract: ContractId) -> Vec<yul::Statement> {
     let adb = db.upcast();
+    let decoding = db.abi_decoding();
     let public_functions = contract
         .public_functions(adb)
         .values()
         .map(|id| {
-            let bare_name = id.name(adb);
+            let selector = id.selector(adb);
             let qualified_name = db.function_yul_name(*id);
             let (param_types, return_type) = db.function_sig_abi_types(*id);
-            (bare_name, qualified_name, param_types, return_type)
+            let is_payable = id.is_payable(adb);
+            (
+                selector,
+                qualified_name,
+                param_types,
+                return_type,
+                is_payable,
+            )
         })
         .collect::<Vec<_>>();
 
     let mut fns =
         public_functions
             .iter()
-            .fold(vec![], |mut fns, (_, _, param_types, return_type)| {
+            .fold(vec![], |mut fns, (_, _, param_types, return_type, _)| {
                 fns.extend(functions::abi::decode_functions(
                     param_types,
                     AbiDecodeLocation::Calldata,
+                    decoding,
                 ));
                 if let Some(return_type) = return_type {
                     fns.push(functions::abi::encode(&[return_type.clone()]));
                 }
                 fns
             });
-    fns.push(abi_dispatcher::dispatcher(&public_functions));
+    let fallback = contract
+        .fallback_function(adb)
+        .map(|id| (db.function_yul_name(id), id.is_payable(adb)));
+    let receive = contract
+        .receive_function(adb)
+        .map(|id| db.function_yul_name(id));
+    let interface_ids = if contract.supports_erc165(adb) {
+        fe_abi::erc165_interface_ids(adb, contract)
+    } else {
+        vec![]
+    };
+    fns.push(abi_dispatcher::dispatcher(
+        &public_functions,
+        fallback,
+        receive,
+        &interface_ids,
+    ));
     fns.sort();
     fns.dedup();
     fns
@@ -138,6 +166,9 @@ fn build_dependency_graph(
 
     let mut string_literals = IndexSet::<SmolStr>::new();
     let mut created_contracts = IndexSet::<ContractId>::new();
+    let mut integers = IndexSet::<Integer>::new();
+    let mut function_refs = IndexSet::<FunctionId>::new();
+    let mut function_value_types = IndexSet::<FunctionType>::new();
 
     // We need all of the "std" yul functions,
     // because we can't yet track which ones are needed.
@@ -164,12 +195,54 @@ fn build_dependency_graph(
                                 .expect("abi_encode non-fixedsize type");
                             yulfns.push(functions::abi::encode(&[typ.as_abi_type(adb)]));
                         }
+                        CallType::BuiltinAbiFunction {
+                            function: GlobalFunction::AbiEncode,
+                            typ,
+                        } => {
+                            let typ = typ.as_abi_type(adb);
+                            yulfns.push(functions::abi::encode(&[typ.clone()]));
+                            yulfns.push(functions::abi::encode_vec(&typ));
+                        }
+                        CallType::BuiltinAbiFunction {
+                            function: GlobalFunction::Keccak256,
+                            typ,
+                        } => {
+                            let typ = typ.as_abi_type(adb);
+                            yulfns.push(functions::abi::encode(&[typ.clone()]));
+                            yulfns.push(functions::abi::hash(&typ));
+                        }
+                        CallType::BuiltinAbiFunction { typ, .. } => {
+                            let typ = typ.as_abi_type(adb);
+                            yulfns.extend(functions::abi::decode_functions(
+                                &[typ.clone()],
+                                AbiDecodeLocation::Memory,
+                                AbiDecoding::Strict,
+                            ));
+                            yulfns.push(functions::abi::decode_vec(&typ));
+                        }
                         CallType::BuiltinAssociatedFunction { contract, .. } => {
                             created_contracts.insert(*contract);
                         }
+                        CallType::FunctionValue(typ) => {
+                            function_value_types.insert(typ.clone());
+                        }
                         _ => {}
                     }
                 }
+                for func in body.try_calls.iter() {
+                    if let Some(CallType::External { function: fun, .. }) = body.calls.get(func) {
+                        yulfns.extend(db.function_external_try_call_fn(*fun));
+                    }
+                }
+                // The errors caught by `catch` clauses are decoded from the
+                // revert data.
+                for error in body.catch_types.values() {
+                    yulfns.extend(functions::abi::decode_functions(
+                        &[error.as_abi_type(adb)],
+                        AbiDecodeLocation::Memory,
+                        AbiDecoding::Strict,
+                    ));
+                }
 
                 for struct_ in db.function_revert_errors(function).iter() {
                     yulfns.push(functions::abi::encode(&[db.struct_abi_type(*struct_)]));
@@ -182,7 +255,12 @@ fn build_dependency_graph(
                     yulfns.push(functions::revert::error_revert(string_type));
                     yulfns.push(functions::abi::encode(&[string_type.clone()]));
                 }
+                function_refs.extend(body.function_refs.values().copied());
                 string_literals.extend(body.string_literals.iter().cloned());
+                integers.extend(body.expressions.values().filter_map(|attr| match attr.typ {
+                    Type::Base(Base::Numeric(integer)) => Some(integer),
+                    _ => None,
+                }));
             }
             Item::Type(TypeDef::Struct(struct_)) => {
                 // We don't know which struct fields are actually accessed, so we need
@@ -196,6 +274,29 @@ fn build_dependency_graph(
         }
     });
 
+    // Math functions for integer sizes that aren't part of the std functions.
+    for integer in integers {
+        yulfns.extend(functions::math::integer_fns(integer));
+    }
+
+    // Functions that call function values, dispatching to the referenced
+    // functions of the same type.
+    for typ in function_value_types {
+        let functions = function_refs
+            .iter()
+            .filter(|function| {
+                matches!(function.signature(adb).function_type(), Ok(fn_type) if fn_type == typ)
+            })
+            .map(|function| {
+                (
+                    db.function_value_id(*function),
+                    db.function_yul_name(*function),
+                )
+            })
+            .collect::<Vec<_>>();
+        yulfns.push(functions::function_values::call_fn(&typ, &functions));
+    }
+
     yulfns.sort();
     yulfns.dedup();
 
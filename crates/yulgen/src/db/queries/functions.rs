@@ -1,14 +1,16 @@
+use crate::constants::ERROR_INVALID_ABI_DATA;
 use crate::context::FnContext;
 use crate::db::YulgenDb;
 use crate::mappers::functions::multiple_func_stmt;
 use crate::names;
-use crate::operations::abi as abi_operations;
+use crate::operations::abi::{self as abi_operations, EncodingSize};
+use crate::operations::revert as revert_operations;
 use crate::runtime::functions;
-use crate::types::{to_abi_selector_names, to_abi_types, AbiDecodeLocation, AbiType, AsAbiType};
-use fe_abi::utils as abi_utils;
+use crate::types::{to_abi_types, AbiDecodeLocation, AbiDecoding, AbiType, AsAbiType};
 use fe_analyzer::namespace::items::{Class, FunctionId, Item, StructId, TypeDef};
 use fe_analyzer::namespace::types::{Struct, Type};
-use fe_parser::{ast, node::Node};
+use fe_common::utils::keccak;
+use fe_parser::ast;
 use indexmap::IndexSet;
 use smol_str::SmolStr;
 use std::rc::Rc;
@@ -19,6 +21,12 @@ pub fn function_yul_name(db: &dyn YulgenDb, function: FunctionId) -> SmolStr {
     format!("$${}", Item::Function(function).path(db.upcast()).join("$")).into()
 }
 
+/// The id of a function when it's used as a value: the first four bytes of
+/// the hash of its Yul name.
+pub fn function_value_id(db: &dyn YulgenDb, function: FunctionId) -> SmolStr {
+    keccak::partial(db.function_yul_name(function).as_bytes(), 4).into()
+}
+
 pub fn function_def(db: &dyn YulgenDb, function: FunctionId) -> yul::Statement {
     let analyzer_db = db.upcast();
     let sig = function.signature(analyzer_db);
@@ -33,7 +41,7 @@ pub fn function_def(db: &dyn YulgenDb, function: FunctionId) -> yul::Statement {
     };
     param_names.extend(sig.params.iter().map(|param| names::var_name(&param.name)));
 
-    let mut fn_context = FnContext::new(db, function.body(analyzer_db));
+    let mut fn_context = FnContext::new(db, function);
     let function_statements =
         multiple_func_stmt(&mut fn_context, &function.data(analyzer_db).ast.kind.body);
 
@@ -63,7 +71,7 @@ pub fn revert_types(db: &dyn YulgenDb, function: FunctionId) -> Rc<IndexSet<Stru
     let body = function.body(db.upcast());
 
     let mut structs = IndexSet::new();
-    for_each_stmt(&function.data(db.upcast()).ast.kind.body, &mut |stmt| {
+    ast::for_each_stmt(&function.data(db.upcast()).ast.kind.body, &mut |stmt| {
         if let ast::FuncStmt::Revert { error: Some(node) } = stmt {
             let attr = body
                 .expressions
@@ -82,7 +90,7 @@ pub fn assert_string_types(db: &dyn YulgenDb, function: FunctionId) -> Rc<IndexS
     let body = function.body(db.upcast());
 
     let mut strings = IndexSet::new();
-    for_each_stmt(&function.data(db.upcast()).ast.kind.body, &mut |stmt| {
+    ast::for_each_stmt(&function.data(db.upcast()).ast.kind.body, &mut |stmt| {
         if let ast::FuncStmt::Assert {
             msg: Some(node), ..
         } = stmt
@@ -108,94 +116,157 @@ pub fn function_external_call_name(db: &dyn YulgenDb, function: FunctionId) -> S
 /// Create a yul function to make a call to an external contract function.
 /// Includes required encode/decode functions.
 pub fn function_external_call_fn(db: &dyn YulgenDb, function: FunctionId) -> Vec<yul::Statement> {
-    let adb = db.upcast();
-    if !matches!(function.class(adb), Some(Class::Contract(_))) {
-        panic!("external call to non-contract fn")
-    };
-
-    let function_name = function.name(adb);
-    // get the name of the call function and its parameters
     let call_fn_name = identifier! { (db.function_external_call_name(function)) };
-    let (param_types, return_type) = db.function_sig_abi_types(function);
-
-    // create a pair of identifiers and expressions for the parameters
-    let (param_idents, param_exprs) = names::abi::vals("param", param_types.len());
-    // the function selector must be added to the first 4 bytes of the calldata
-    let selector = {
-        let selector =
-            abi_utils::func_selector(&function_name, &to_abi_selector_names(&param_types));
-        literal_expression! { (selector) }
-    };
-
-    // the size of the encoded data
-    let encoding_size = abi_operations::encoding_size(&param_types, &param_exprs);
-    // the operations used to encode the parameters
-    let encoding_operation = abi_operations::encode(&param_types, param_exprs);
-
-    let mut fns = vec![functions::abi::encode(&param_types)];
+    let (param_idents, encode_input, mut fns) = external_call_input(db, function);
+    let (_, return_type) = db.function_sig_abi_types(function);
 
     if let Some(return_type) = return_type {
+        let decode_output = external_call_output(&return_type);
         fns.extend(functions::abi::decode_functions(
-            &[return_type.clone()],
-            AbiDecodeLocation::Memory,
-        ));
-        let decoding_operation = abi_operations::decode_data(
             &[return_type],
-            expression! { outstart },
-            expression! { add(outstart, outsize) },
             AbiDecodeLocation::Memory,
-        );
-        // return data must be captured and decoded
+            AbiDecoding::Strict,
+        ));
         fns.push(function_definition! {
             function [call_fn_name](addr, [param_idents...]) -> return_val {
-                (let instart := alloc_mstoren([selector], 4))
-                (let insize := add(4, [encoding_size]))
-                (pop([encoding_operation]))
+                [encode_input...]
                 (let success := call((gas()), addr, 0, instart, insize, 0, 0))
-                (let outsize := returndatasize())
-                (let outstart := alloc(outsize))
-                (returndatacopy(outstart, 0, outsize))
-                (if (iszero(success)) { (revert(outstart, outsize)) })
-                (return_val := [decoding_operation])
+                (if (iszero(success)) { (revert_with_return_data()) })
+                [decode_output...]
             }
         })
     } else {
-        // unit type; there is no return data to handle
+        // unit type; there is no return data to handle, so the address must
+        // have code for the call to do anything
         // TODO: return_val isn't assigned
         fns.push(function_definition! {
             function [call_fn_name](addr, [param_idents...]) -> return_val {
-                (let instart := alloc_mstoren([selector], 4))
-                (let insize := add(4, [encoding_size]))
-                (pop([encoding_operation]))
+                (check_call_target(addr))
+                [encode_input...]
                 (let success := call((gas()), addr, 0, instart, insize, 0, 0))
-                (if (iszero(success)) {
-                    (let outsize := returndatasize())
-                    (let outstart := alloc(outsize))
-                    (returndatacopy(outstart, 0, outsize))
-                    (revert(outstart, outsize))
-                })
+                (if (iszero(success)) { (revert_with_return_data()) })
             }
         })
     }
     fns
 }
 
-fn for_each_stmt<F>(stmts: &[Node<ast::FuncStmt>], f: &mut F)
-where
-    F: FnMut(&ast::FuncStmt),
-{
-    for node in stmts {
-        f(&node.kind);
-        match &node.kind {
-            ast::FuncStmt::For { body, .. } | ast::FuncStmt::While { body, .. } => {
-                for_each_stmt(body, f)
-            }
-            ast::FuncStmt::If { body, or_else, .. } => {
-                for_each_stmt(body, f);
-                for_each_stmt(or_else, f);
-            }
-            ast::FuncStmt::Unsafe(body) => for_each_stmt(body, f),
-            _ => {}
+pub fn function_external_try_call_name(db: &dyn YulgenDb, function: FunctionId) -> SmolStr {
+    format!("try_call_{}", db.function_yul_name(function)).into()
+}
+
+/// Create a yul function to make the external call of a `try` statement.
+/// Unlike the function of [`function_external_call_fn`], it doesn't revert
+/// if the callee reverts. It returns whether the call succeeded, and the
+/// return value if it did. The revert data is left in the return data
+/// buffer.
+pub fn function_external_try_call_fn(
+    db: &dyn YulgenDb,
+    function: FunctionId,
+) -> Vec<yul::Statement> {
+    let (param_idents, encode_input, mut fns) = external_call_input(db, function);
+    let (_, return_type) = db.function_sig_abi_types(function);
+
+    let block = if let Some(return_type) = return_type {
+        let decode_output = external_call_output(&return_type);
+        fns.extend(functions::abi::decode_functions(
+            &[return_type],
+            AbiDecodeLocation::Memory,
+            AbiDecoding::Strict,
+        ));
+        block! {
+            [encode_input...]
+            (success := call((gas()), addr, 0, instart, insize, 0, 0))
+            (if success { [decode_output...] })
+        }
+    } else {
+        block! {
+            (check_call_target(addr))
+            [encode_input...]
+            (success := call((gas()), addr, 0, instart, insize, 0, 0))
         }
+    };
+    // yultsur's macros don't support functions with more than one return
+    // value.
+    fns.push(yul::Statement::FunctionDefinition(yul::FunctionDefinition {
+        name: identifier! { (db.function_external_try_call_name(function)) },
+        parameters: std::iter::once(identifier! { addr })
+            .chain(param_idents)
+            .collect(),
+        returns: identifiers! { ("success") ("return_val") },
+        block,
+    }));
+    fns
+}
+
+/// The parameters of a function that calls `function` of another contract,
+/// and the statements that encode the calldata of the call at `instart`,
+/// with its size in `insize`. Includes the required encode functions.
+fn external_call_input(
+    db: &dyn YulgenDb,
+    function: FunctionId,
+) -> (Vec<yul::Identifier>, Vec<yul::Statement>, Vec<yul::Statement>) {
+    let adb = db.upcast();
+    if !matches!(function.class(adb), Some(Class::Contract(_))) {
+        panic!("external call to non-contract fn")
+    };
+
+    let (param_types, _) = db.function_sig_abi_types(function);
+
+    // create a pair of identifiers and expressions for the parameters
+    let (param_idents, param_exprs) = names::abi::vals("param", param_types.len());
+    // the function selector must be added to the first 4 bytes of the calldata
+    let selector = literal_expression! { (function.selector(adb).to_string()) };
+
+    // the size of the encoded data
+    let encoding_size = abi_operations::encoding_size(&param_types, &param_exprs);
+    // the operations used to encode the parameters
+    let encoding_operation = abi_operations::encode(&param_types, param_exprs);
+
+    let statements = vec![
+        statement! { let instart := alloc_mstoren([selector], 4) },
+        statement! { let insize := add(4, [encoding_size]) },
+        statement! { pop([encoding_operation]) },
+    ];
+    (
+        param_idents,
+        statements,
+        vec![functions::abi::encode(&param_types)],
+    )
+}
+
+/// The statements that copy the data returned by a successful call to
+/// memory, and decode it into `return_val`.
+fn external_call_output(return_type: &AbiType) -> Vec<yul::Statement> {
+    // the size of the return data is checked before it's copied, and the
+    // decoding checks its layout
+    let size_check = return_data_size_check(return_type);
+    let decoding_operation = abi_operations::decode_data(
+        &[return_type.clone()],
+        expression! { outstart },
+        expression! { add(outstart, outsize) },
+        AbiDecodeLocation::Memory,
+    );
+    vec![
+        statement! { let outsize := returndatasize() },
+        size_check,
+        statement! { let outstart := alloc(outsize) },
+        statement! { returndatacopy(outstart, 0, outsize) },
+        statement! { return_val := [decoding_operation] },
+    ]
+}
+
+/// Reverts with `Error(0x103)` if the size of the data returned by a call,
+/// `outsize`, doesn't fit the encoding of the return type.
+fn return_data_size_check(return_type: &AbiType) -> yul::Statement {
+    let invalid_data = revert_operations::error_revert_numeric(ERROR_INVALID_ABI_DATA);
+    match abi_operations::encoding_known_size(&[return_type.clone()]) {
+        EncodingSize::Exact(size) => statement! {
+            if (iszero((eq(outsize, [size])))) { [invalid_data] }
+        },
+        EncodingSize::Bounded { min, max } => statement! {
+            if (or((lt(outsize, [min])), (gt(outsize, [max])))) { [invalid_data] }
+        },
     }
 }
+
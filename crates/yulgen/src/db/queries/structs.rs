@@ -1,7 +1,9 @@
 use crate::db::YulgenDb;
 use crate::types::{AbiType, AsAbiType, EvmSized};
+use crate::utils::ceil_32;
 use fe_analyzer::namespace::items::{Item, StructId, TypeDef};
 use smol_str::SmolStr;
+use std::cmp::Reverse;
 use std::rc::Rc;
 use yultsur::*;
 
@@ -129,9 +131,156 @@ pub fn struct_init_fn(db: &dyn YulgenDb, struct_: StructId) -> yul::Statement {
     }
 }
 
+/// The byte offsets of the fields of a `#[packed]` struct in storage, in
+/// declaration order.
+///
+/// The fields are laid out in declaration order, and a field that doesn't fit
+/// in the rest of the current word starts a new word. If placing the largest
+/// fields first, each in the first word with enough room left, takes fewer
+/// words, that layout is used instead, unless the struct has the
+/// `#[repr(declared)]` attribute.
+pub fn struct_packed_offsets(db: &dyn YulgenDb, struct_: StructId) -> Rc<[usize]> {
+    let sizes = struct_
+        .fields(db.upcast())
+        .values()
+        .map(|field| field.typ(db.upcast()).expect("struct field error").size())
+        .collect::<Vec<_>>();
+
+    let declared = declared_offsets(&sizes);
+    if struct_.is_repr_declared(db.upcast()) {
+        return declared.into();
+    }
+    let reordered = reordered_offsets(&sizes);
+    if word_count(&reordered, &sizes) < word_count(&declared, &sizes) {
+        reordered.into()
+    } else {
+        declared.into()
+    }
+}
+
+fn declared_offsets(sizes: &[usize]) -> Vec<usize> {
+    let mut next_offset = 0;
+    sizes
+        .iter()
+        .map(|size| {
+            if next_offset % 32 + size > 32 {
+                next_offset = ceil_32(next_offset);
+            }
+            let offset = next_offset;
+            next_offset += size;
+            offset
+        })
+        .collect()
+}
+
+fn reordered_offsets(sizes: &[usize]) -> Vec<usize> {
+    let mut order = (0..sizes.len()).collect::<Vec<_>>();
+    order.sort_by_key(|index| Reverse(sizes[*index]));
+
+    // The number of bytes used in each word.
+    let mut words: Vec<usize> = vec![];
+    let mut offsets = vec![0; sizes.len()];
+    for index in order {
+        let size = sizes[index];
+        let word = match words.iter().position(|used| used + size <= 32) {
+            Some(word) => word,
+            None => {
+                words.push(0);
+                words.len() - 1
+            }
+        };
+        offsets[index] = word * 32 + words[word];
+        words[word] += size;
+    }
+    offsets
+}
+
+fn word_count(offsets: &[usize], sizes: &[usize]) -> usize {
+    offsets
+        .iter()
+        .zip(sizes)
+        .map(|(offset, size)| ceil_32(offset + size) / 32)
+        .max()
+        .unwrap_or(0)
+}
+
+pub fn struct_sload_packed_name(db: &dyn YulgenDb, struct_: StructId) -> SmolStr {
+    format!("{}.sload_packed", db.struct_qualified_name(struct_)).into()
+}
+
+/// Copies a `#[packed]` struct from storage to a newly allocated struct in
+/// memory.
+pub fn struct_sload_packed_fn(db: &dyn YulgenDb, struct_: StructId) -> yul::Statement {
+    let function_name = identifier! { (db.struct_sload_packed_name(struct_)) };
+    let init_name = identifier! { (db.struct_init_name(struct_)) };
+    let offsets = db.struct_packed_offsets(struct_);
+
+    let fields = struct_
+        .fields(db.upcast())
+        .values()
+        .zip(offsets.iter())
+        .map(|(field, offset)| {
+            let size = field.typ(db.upcast()).expect("struct field error").size();
+            let offset = literal_expression! { (offset) };
+            let size = literal_expression! { (size) };
+            expression! { bytes_sloadn((add(sptr, [offset])), [size]) }
+        })
+        .collect::<Vec<_>>();
+
+    function_definition! {
+        function [function_name](sptr) -> return_val {
+            (return_val := [init_name]([fields...]))
+        }
+    }
+}
+
+pub fn struct_sstore_packed_name(db: &dyn YulgenDb, struct_: StructId) -> SmolStr {
+    format!("{}.sstore_packed", db.struct_qualified_name(struct_)).into()
+}
+
+/// Copies a `#[packed]` struct from memory to storage.
+pub fn struct_sstore_packed_fn(db: &dyn YulgenDb, struct_: StructId) -> yul::Statement {
+    let function_name = identifier! { (db.struct_sstore_packed_name(struct_)) };
+    let offsets = db.struct_packed_offsets(struct_);
+
+    let stores = struct_
+        .fields(db.upcast())
+        .iter()
+        .zip(offsets.iter())
+        .filter_map(|((name, field), offset)| {
+            let size = field.typ(db.upcast()).expect("struct field error").size();
+            if size == 0 {
+                return None;
+            }
+            let getter = identifier! { (db.struct_getter_name(struct_, name.clone())) };
+            let offset = literal_expression! { (offset) };
+            let size = literal_expression! { (size) };
+            Some(statement! {
+                bytes_sstoren((add(sptr, [offset])), [size], (mloadn(([getter](mptr)), [size])))
+            })
+        })
+        .collect::<Vec<_>>();
+
+    function_definition! {
+        function [function_name](sptr, mptr) {
+            [stores...]
+        }
+    }
+}
+
 pub fn struct_api_fns(db: &dyn YulgenDb, struct_: StructId) -> Vec<yul::Statement> {
+    let packed_fns = if struct_.is_packed(db.upcast()) {
+        vec![
+            db.struct_sload_packed_fn(struct_),
+            db.struct_sstore_packed_fn(struct_),
+        ]
+    } else {
+        vec![]
+    };
+
     [
         vec![db.struct_init_fn(struct_)],
+        packed_fns,
         struct_
             .fields(db.upcast())
             .keys()
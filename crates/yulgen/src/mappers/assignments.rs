@@ -16,6 +16,12 @@ pub fn assign(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statem
         value: value_node,
     } = &stmt.kind
     {
+        // The value of a dead store is still evaluated, for its side effects.
+        if context.is_dead_store(stmt) {
+            let value = expressions::expr(context, value_node);
+            return statement! { pop([value]) };
+        }
+
         let target = expressions::expr(context, target_node);
         let value = expressions::expr(context, value_node);
 
@@ -29,8 +35,12 @@ pub fn assign(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statem
             target_attributes.final_location(),
         ) {
             (Location::Memory, Location::Storage { .. }) => {
-                if let fe::Expr::Attribute { .. } = &target_node.kind {
-                    if let Type::Struct(struct_) = &target_attributes.typ {
+                if let Type::Struct(struct_) = &target_attributes.typ {
+                    // Packed structs have a different layout in storage, so they
+                    // can never be copied byte for byte.
+                    if struct_.id.is_packed(context.db.upcast())
+                        || matches!(&target_node.kind, fe::Expr::Attribute { .. })
+                    {
                         return struct_operations::copy_to_storage(
                             context.db, struct_, target, value,
                         );
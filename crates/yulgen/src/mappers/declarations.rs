@@ -10,7 +10,10 @@ use yultsur::*;
 /// Builds a Yul statement from a Fe variable declaration
 pub fn var_decl(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
     if let fe::FuncStmt::VarDecl { target, typ, value } = &stmt.kind {
-        let decl_type = context.declaration_type(typ);
+        let decl_type = context.declaration_type(
+            typ.as_ref()
+                .expect("untyped declarations should have been lowered"),
+        );
 
         let target = names::var_name(var_decl_name(&target.kind));
 
@@ -19,7 +22,8 @@ pub fn var_decl(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Stat
             statement! { let [target] := [value] }
         } else {
             match decl_type {
-                FixedSize::Base(_) => statement! { let [target] := 0 },
+                // An uninitialized function value doesn't refer to any function.
+                FixedSize::Base(_) | FixedSize::Function(_) => statement! { let [target] := 0 },
                 typ => {
                     let size = literal_expression! { (typ.size()) };
                     statement! { let [target] := alloc([size]) }
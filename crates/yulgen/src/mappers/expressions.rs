@@ -7,7 +7,7 @@ use crate::operations::{
 use crate::types::{AsAbiType, EvmSized};
 use fe_analyzer::builtins::{
     self, BlockField, ChainField, ContractSelfField, ContractTypeMethod, GlobalFunction,
-    GlobalObject, MsgField, TxField,
+    GlobalObject, IntegerTypeMethod, MsgField, TxField,
 };
 use fe_analyzer::context::{CallType, Location};
 use fe_analyzer::namespace::items::Class;
@@ -24,7 +24,7 @@ use yultsur::*;
 /// Builds a Yul expression from a Fe expression.
 pub fn expr(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     let expression = match &exp.kind {
-        fe::Expr::Name(_) => expr_name(exp),
+        fe::Expr::Name(_) => expr_name(context, exp),
         fe::Expr::Path(_) => panic!("path expressions should be lowered or rejected"),
         fe::Expr::Num(_) => expr_num(exp),
         fe::Expr::Bool(_) => expr_bool(exp),
@@ -32,6 +32,9 @@ pub fn expr(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
         fe::Expr::Attribute { .. } => expr_attribute(context, exp),
         fe::Expr::Ternary { .. } => panic!("ternary expressions should be lowered"),
         fe::Expr::BoolOperation { .. } => panic!("bool operation expressions should be lowered"),
+        fe::Expr::ChainedComparison { .. } => {
+            panic!("chained comparison expressions should be lowered")
+        }
         fe::Expr::BinOperation { .. } => expr_bin_operation(context, exp),
         fe::Expr::UnaryOperation { .. } => expr_unary_operation(context, exp),
         fe::Expr::CompOperation { .. } => expr_comp_operation(context, exp),
@@ -44,12 +47,13 @@ pub fn expr(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
 
     let attributes = context.expression_attributes(exp);
     match (attributes.location, attributes.move_location) {
-        (from, Some(to)) => move_expression(expression, attributes.typ.clone(), from, to),
+        (from, Some(to)) => move_expression(context, expression, attributes.typ.clone(), from, to),
         (_, None) => expression,
     }
 }
 
 fn move_expression(
+    context: &FnContext,
     val: yul::Expression,
     typ: Type,
     from: Location,
@@ -79,7 +83,12 @@ fn move_expression(
             }
         }
         (Location::Memory, Location::Memory) => data_operations::mcopym(fixed_size, val),
-        (Location::Storage { .. }, Location::Memory) => data_operations::scopym(fixed_size, val),
+        (Location::Storage { .. }, Location::Memory) => match typ {
+            Type::Struct(struct_) if struct_.id.is_packed(context.db.upcast()) => {
+                struct_operations::copy_packed_to_memory(context.db, struct_.id, val)
+            }
+            _ => data_operations::scopym(fixed_size, val),
+        },
         _ => panic!("invalid expression move: {:?} {:?}", from, to),
     }
 }
@@ -106,7 +115,19 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
                 let size = identifier_expression! { (size.size()) };
                 expression! { [func_name]([yul_args[0].clone()], [size]) }
             }
+            GlobalFunction::AbiEncode | GlobalFunction::AbiDecode => {
+                unreachable!("abi functions are called as `CallType::BuiltinAbiFunction`")
+            }
         },
+        CallType::BuiltinAbiFunction { function, typ } => {
+            let typ = typ.as_abi_type(context.adb);
+            let func_name = match function {
+                GlobalFunction::AbiEncode => names::abi::encode_vec(&typ),
+                GlobalFunction::AbiDecode => names::abi::decode_vec(&typ),
+                GlobalFunction::Keccak256 => names::abi::hash(&typ),
+            };
+            expression! { [func_name]([yul_args[0].clone()]) }
+        }
         CallType::Intrinsic(func) => {
             let yul_name = identifier! { (func.as_ref().strip_prefix("__").unwrap()) };
             expression! { [yul_name]([yul_args...]) }
@@ -117,6 +138,11 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
                 _ => unreachable!(),
             };
             match method {
+                builtins::ValueMethod::Clone if matches!(typ, Type::Vec(_)) => {
+                    let inner_size = vec_inner_size(&typ);
+                    let target = expr(context, target);
+                    expression! { vec_clone([target], [inner_size]) }
+                }
                 // Copying is done in `expr(..)` based on the move location set
                 // in the expression's attributes, so we just map the value for
                 // `to_mem` and `clone`.
@@ -130,15 +156,69 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
                     ),
                     _ => panic!("invalid attributes"),
                 },
+                builtins::ValueMethod::Concat => {
+                    let target = expr(context, target);
+                    expression! { string_concat([target], [yul_args[0].clone()]) }
+                }
+                builtins::ValueMethod::Slice => {
+                    let target = expr(context, target);
+                    expression! { string_slice([target], [yul_args[0].clone()], [yul_args[1].clone()]) }
+                }
+                builtins::ValueMethod::Push => {
+                    let inner_size = vec_inner_size(&typ);
+                    let target = expr(context, target);
+                    expression! { vec_push([target], [yul_args[0].clone()], [inner_size]) }
+                }
+                builtins::ValueMethod::Pop => {
+                    let inner_size = vec_inner_size(&typ);
+                    let target = expr(context, target);
+                    let item = expression! { vec_pop([target], [inner_size]) };
+                    match typ {
+                        Type::Vec(vec) => match vec.inner {
+                            Base::Numeric(integer) => {
+                                math_operations::adjust_numeric_size(&integer, item)
+                            }
+                            _ => item,
+                        },
+                        _ => panic!("invalid attributes"),
+                    }
+                }
+                builtins::ValueMethod::Len => {
+                    let target = expr(context, target);
+                    expression! { mload([target]) }
+                }
+                builtins::ValueMethod::ToString => {
+                    let is_signed = match typ {
+                        Type::Base(Base::Numeric(integer)) => integer.is_signed(),
+                        _ => panic!("invalid attributes"),
+                    };
+                    let target = expr(context, target);
+                    let is_signed = literal_expression! { (is_signed) };
+                    expression! { int_to_string([target], [is_signed]) }
+                }
             }
         }
         CallType::TypeConstructor(Type::Struct(val)) => {
             struct_operations::init(context.db, val.id, yul_args)
         }
+        CallType::TypeConstructor(Type::Vec(_)) => match yul_args.first() {
+            Some(string) => expression! { vec_from_string([string.clone()]) },
+            None => expression! { vec_new() },
+        },
+        CallType::TypeConstructor(Type::String(string))
+            if matches!(
+                context.expression_attributes(&args.kind[0].kind.value).typ,
+                Type::Vec(_)
+            ) =>
+        {
+            let max_size = literal_expression! { (string.max_size) };
+            expression! { string_from_vec([yul_args[0].clone()], [max_size]) }
+        }
         CallType::TypeConstructor(Type::Base(Base::Numeric(integer))) => {
             math_operations::adjust_numeric_size(&integer, yul_args[0].clone())
         }
         CallType::TypeConstructor(_) => yul_args[0].clone(),
+        CallType::EnumConstructor(_) => unreachable!("enums are lowered to structs"),
         CallType::Pure(func) => {
             let func_name = identifier! { (context.db.function_yul_name(func)) };
             expression! { [func_name]([yul_args...]) }
@@ -154,8 +234,23 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
                 ContractTypeMethod::Create => {
                     contract_operations::create(&contract_name, yul_args[0].clone())
                 }
+                ContractTypeMethod::AddressOf => contract_operations::create2_address(
+                    &contract_name,
+                    yul_args[0].clone(),
+                    yul_args[1].clone(),
+                ),
             }
         }
+        CallType::BuiltinIntegerConversion { function, from, to } => match function {
+            IntegerTypeMethod::TryFrom => {
+                let func_name = names::checked_conversion(&to);
+                let check_sign = literal_expression! { (from.is_signed() != to.is_signed()) };
+                expression! { [func_name]([yul_args[0].clone()], [check_sign]) }
+            }
+            IntegerTypeMethod::UncheckedFrom => {
+                math_operations::adjust_numeric_size(&to, yul_args[0].clone())
+            }
+        },
         CallType::AssociatedFunction { class, function } => {
             assert!(
                 matches!(class, Class::Struct(_)),
@@ -190,6 +285,11 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
                 }
             }
         }
+        CallType::FunctionValue(typ) => {
+            let func_name = names::function_value_call(&typ);
+            let func = expr(context, func);
+            expression! { [func_name]([func], [yul_args...]) }
+        }
         CallType::External { function, .. } => {
             let target = match &func.kind {
                 fe::Expr::Attribute { value, .. } => value,
@@ -202,6 +302,14 @@ fn expr_call(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     };
 }
 
+/// The item size of a vec, as a Yul literal.
+fn vec_inner_size(typ: &Type) -> yul::Expression {
+    match typ {
+        Type::Vec(vec) => literal_expression! { (vec.inner.size()) },
+        _ => panic!("invalid attributes"),
+    }
+}
+
 pub fn expr_comp_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
     if let fe::Expr::CompOperation { left, op, right } = &exp.kind {
         let yul_left = expr(context, left);
@@ -209,6 +317,23 @@ pub fn expr_comp_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul
 
         let typ = &context.expression_attributes(left).typ;
 
+        if let Type::String(_) = typ {
+            return match op.kind {
+                fe::CompOperator::Eq => expression! { string_eq([yul_left], [yul_right]) },
+                fe::CompOperator::NotEq => {
+                    expression! { iszero((string_eq([yul_left], [yul_right]))) }
+                }
+                fe::CompOperator::Lt => expression! { string_lt([yul_left], [yul_right]) },
+                fe::CompOperator::LtE => {
+                    expression! { iszero((string_lt([yul_right], [yul_left]))) }
+                }
+                fe::CompOperator::Gt => expression! { string_lt([yul_right], [yul_left]) },
+                fe::CompOperator::GtE => {
+                    expression! { iszero((string_lt([yul_left], [yul_right]))) }
+                }
+            };
+        }
+
         return match op.kind {
             fe::CompOperator::Eq => expression! { eq([yul_left], [yul_right]) },
             fe::CompOperator::NotEq => expression! { iszero((eq([yul_left], [yul_right]))) },
@@ -240,24 +365,40 @@ pub fn expr_bin_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul:
         let yul_right = expr(context, right);
 
         let typ = &context.expression_attributes(left).typ;
+        // Without overflow checks (or in an `unchecked` block), the result
+        // wraps around to the size of the type. Division and modulo are
+        // checked either way.
+        let overflow_checks = context.overflow_checks();
 
         return match op.kind {
             fe::BinOperator::Add => match typ {
-                Type::Base(Base::Numeric(integer)) => {
+                Type::Base(Base::Numeric(integer)) if overflow_checks => {
                     expression! { [names::checked_add(integer)]([yul_left], [yul_right]) }
                 }
+                Type::Base(Base::Numeric(integer)) => math_operations::adjust_numeric_size(
+                    integer,
+                    expression! { add([yul_left], [yul_right]) },
+                ),
                 _ => unimplemented!("Addition for non-numeric types not yet supported"),
             },
             fe::BinOperator::Sub => match typ {
-                Type::Base(Base::Numeric(integer)) => {
+                Type::Base(Base::Numeric(integer)) if overflow_checks => {
                     expression! { [names::checked_sub(integer)]([yul_left], [yul_right]) }
                 }
+                Type::Base(Base::Numeric(integer)) => math_operations::adjust_numeric_size(
+                    integer,
+                    expression! { sub([yul_left], [yul_right]) },
+                ),
                 _ => unimplemented!("Subtraction for non-numeric types not yet supported"),
             },
             fe::BinOperator::Mult => match typ {
-                Type::Base(Base::Numeric(integer)) => {
+                Type::Base(Base::Numeric(integer)) if overflow_checks => {
                     expression! { [names::checked_mul(integer)]([yul_left], [yul_right]) }
                 }
+                Type::Base(Base::Numeric(integer)) => math_operations::adjust_numeric_size(
+                    integer,
+                    expression! { mul([yul_left], [yul_right]) },
+                ),
                 _ => unreachable!(),
             },
             fe::BinOperator::Div => match typ {
@@ -287,9 +428,13 @@ pub fn expr_bin_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul:
                 _ => unreachable!(),
             },
             fe::BinOperator::Pow => match typ {
-                Type::Base(Base::Numeric(integer)) => {
+                Type::Base(Base::Numeric(integer)) if overflow_checks => {
                     expression! { [names::checked_exp(integer)]([yul_left], [yul_right]) }
                 }
+                Type::Base(Base::Numeric(integer)) => math_operations::adjust_numeric_size(
+                    integer,
+                    expression! { exp([yul_left], [yul_right]) },
+                ),
                 _ => unreachable!(),
             },
         };
@@ -313,8 +458,13 @@ pub fn expr_unary_operation(context: &mut FnContext, exp: &Node<fe::Expr>) -> yu
                             // in adding a runtime check.
                             let zero = literal_expression! {0};
                             expression! { sub([zero], [yul_operand]) }
-                        } else {
+                        } else if context.overflow_checks() {
                             expression! { [names::checked_neg(integer)]([yul_operand]) }
+                        } else {
+                            math_operations::adjust_numeric_size(
+                                integer,
+                                expression! { sub(0, [yul_operand]) },
+                            )
                         }
                     }
                     _ => unreachable!(),
@@ -342,7 +492,10 @@ pub fn expr_name_string(exp: &Node<fe::Expr>) -> SmolStr {
     unreachable!()
 }
 
-fn expr_name(exp: &Node<fe::Expr>) -> yul::Expression {
+fn expr_name(context: &FnContext, exp: &Node<fe::Expr>) -> yul::Expression {
+    if let Some(function) = context.function_ref(exp) {
+        return literal_expression! { (context.db.function_value_id(function)) };
+    }
     let name = expr_name_string(exp);
 
     identifier_expression! { [names::var_name(&name)] }
@@ -398,7 +551,11 @@ fn expr_subscript(context: &mut FnContext, exp: &Node<fe::Expr>) -> yul::Express
 
         return match &value_attributes.typ {
             Type::Map(_) => data_operations::keyed_map(value, index),
+            Type::Array(array) if context.is_in_bounds_index(exp) => {
+                data_operations::indexed_array_unchecked(array.clone(), value, index)
+            }
             Type::Array(array) => data_operations::indexed_array(array.clone(), value, index),
+            Type::Vec(vec) => data_operations::indexed_vec(*vec, value, index),
             _ => panic!("invalid attributes"),
         };
     }
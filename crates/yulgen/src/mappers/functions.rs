@@ -2,11 +2,13 @@ use crate::constants::PANIC_FAILED_ASSERTION;
 use crate::context::FnContext;
 use crate::mappers::{assignments, declarations, expressions};
 use crate::names;
+use crate::operations::abi as abi_operations;
+use crate::operations::abi::EncodingSize;
 use crate::operations::data as data_operations;
 use crate::operations::revert as revert_operations;
-use crate::types::{AbiType, AsAbiType, EvmSized};
+use crate::types::{AbiDecodeLocation, AbiType, AsAbiType, EvmSized};
 use fe_analyzer::context::{CallType, ExpressionAttributes};
-use fe_analyzer::namespace::types::{Base, Type};
+use fe_analyzer::namespace::types::{Base, FixedSize, Type};
 use fe_parser::ast as fe;
 use fe_parser::node::Node;
 use if_chain::if_chain;
@@ -18,7 +20,19 @@ pub fn multiple_func_stmt(
 ) -> Vec<yul::Statement> {
     statements
         .iter()
-        .map(|statement| func_stmt(context, statement))
+        .flat_map(|statement| match &statement.kind {
+            // The outputs of a `yul` block are declared next to it.
+            fe::FuncStmt::Yul { .. } => yul_block(context, statement),
+            // The variables that a `#[checked]` statement declares stay in
+            // scope, so its statements aren't put in a block.
+            fe::FuncStmt::Checked(body) => {
+                let checked = std::mem::replace(&mut context.checked, true);
+                let yul_body = multiple_func_stmt(context, body);
+                context.checked = checked;
+                yul_body
+            }
+            _ => vec![func_stmt(context, statement)],
+        })
         .collect()
 }
 
@@ -32,22 +46,80 @@ fn func_stmt(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Stateme
         fe::FuncStmt::AugAssign { .. } => panic!("AugAssign should be lowered"),
         fe::FuncStmt::For { .. } => for_loop(context, stmt),
         fe::FuncStmt::While { .. } => while_loop(context, stmt),
+        fe::FuncStmt::Loop { .. } => loop_statement(context, stmt),
         fe::FuncStmt::If { .. } => if_statement(context, stmt),
+        fe::FuncStmt::Match { .. } => panic!("Match should be lowered"),
+        fe::FuncStmt::Try { .. } => try_statement(context, stmt),
         fe::FuncStmt::Unsafe(body) => {
             let yul_body = multiple_func_stmt(context, body);
             block_statement! {
                 [yul_body...]
             }
         }
+        fe::FuncStmt::Unchecked(body) => {
+            let unchecked = std::mem::replace(&mut context.unchecked, true);
+            let checked = std::mem::replace(&mut context.checked, false);
+            let yul_body = multiple_func_stmt(context, body);
+            context.unchecked = unchecked;
+            context.checked = checked;
+            block_statement! {
+                [yul_body...]
+            }
+        }
         fe::FuncStmt::Assert { .. } => assert(context, stmt),
         fe::FuncStmt::Expr { .. } => expr(context, stmt),
         fe::FuncStmt::Pass => statement! { pop(0) },
-        fe::FuncStmt::Break => break_statement(context, stmt),
+        fe::FuncStmt::Break { .. } => break_statement(context, stmt),
         fe::FuncStmt::Continue => continue_statement(context, stmt),
         fe::FuncStmt::Revert { .. } => revert(context, stmt),
+        fe::FuncStmt::Yul { .. } => unreachable!("`yul` blocks are mapped by `multiple_func_stmt`"),
+        fe::FuncStmt::Checked(_) => {
+            unreachable!("`#[checked]` statements are mapped by `multiple_func_stmt`")
+        }
     }
 }
 
+/// Declares the outputs of a `yul` block, and runs its code in a block with
+/// the inputs and outputs bound to plain Yul variables.
+fn yul_block(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> Vec<yul::Statement> {
+    if let fe::FuncStmt::Yul {
+        inputs,
+        outputs,
+        code,
+    } = &stmt.kind
+    {
+        let mut statements = vec![];
+        let mut block = vec![];
+        for input in inputs {
+            let name = identifier! { (input.kind.name.kind) };
+            let value = expressions::expr(context, &input.kind.value);
+            block.push(statement! { let [name] := [value] });
+        }
+        for output in outputs {
+            let name = identifier! { (output.kind.name.kind) };
+            let var = names::var_name(&output.kind.name.kind);
+            statements.push(statement! { let [var] := 0 });
+            block.push(statement! { let [name] := 0 });
+        }
+
+        // The code isn't parsed, so it's inserted as is.
+        let code = yul::Statement::Expression(yul::Expression::Identifier(identifier! {
+            (code.kind)
+        }));
+        block.push(block_statement! { [code] });
+
+        for output in outputs {
+            let name = identifier_expression! { (output.kind.name.kind) };
+            let var = names::var_name(&output.kind.name.kind);
+            block.push(statement! { [var] := [name] });
+        }
+        statements.push(block_statement! { [block...] });
+        return statements;
+    }
+
+    unreachable!()
+}
+
 fn for_loop(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
     if let fe::FuncStmt::For { target, iter, body } = &stmt.kind {
         let iterator = expressions::expr(context, iter);
@@ -96,6 +168,146 @@ fn if_statement(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Stat
     unreachable!()
 }
 
+/// Makes the external call of a `try` statement, and runs its body if the call
+/// succeeds. Otherwise, the revert data is matched against the `catch`
+/// clauses in order, by the selector and the size of the data. If no clause
+/// matches, the revert data is propagated.
+fn try_statement(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
+    if let fe::FuncStmt::Try {
+        call,
+        binding,
+        body,
+        catches,
+    } = &stmt.kind
+    {
+        // Yul doesn't allow shadowing, so the variables of nested `try`
+        // statements are numbered.
+        context.try_count += 1;
+        let var = |name: &str| format!("try_{}_{}", name, context.try_count);
+        let (success, value, size, data, selector, caught) = (
+            var("success"),
+            var("value"),
+            var("size"),
+            var("data"),
+            var("selector"),
+            var("caught"),
+        );
+
+        let function = match &call.kind {
+            fe::Expr::Call { func, .. } => match context.call_type(func) {
+                CallType::External { function, .. } => function,
+                _ => panic!("`try` with a non-external call"),
+            },
+            _ => panic!("`try` with a non-call expression"),
+        };
+        let arguments = match expressions::expr(context, call) {
+            yul::Expression::FunctionCall(call) => call.arguments,
+            _ => unreachable!(),
+        };
+        let try_call = yul::Expression::FunctionCall(yul::FunctionCall {
+            identifier: identifier! { (context.db.function_external_try_call_name(function)) },
+            arguments,
+        });
+
+        let mut yul_body = vec![];
+        if let Some(binding) = binding {
+            let name = names::var_name(&binding.kind);
+            yul_body.push(statement! { let [name] := [identifier_expression! { (value) }] });
+        }
+        yul_body.extend(multiple_func_stmt(context, body));
+
+        let size_expr = identifier_expression! { (size) };
+        let data_expr = identifier_expression! { (data) };
+        let caught_expr = identifier_expression! { (caught) };
+        let mut yul_catches = vec![];
+        let mut catches_all = false;
+        for catch in catches {
+            let mut yul_catch = vec![statement! { [identifier! { (caught) }] := 1 }];
+            let test = match &catch.kind.error {
+                Some((typ, name)) => {
+                    let (error_name, abi_type) = match context.catch_type(typ) {
+                        FixedSize::Struct(struct_) => {
+                            (struct_.name.to_string(), struct_.as_abi_type(context.adb))
+                        }
+                        FixedSize::String(string) => {
+                            ("Error".to_string(), string.as_abi_type(context.adb))
+                        }
+                        _ => panic!("invalid catch type"),
+                    };
+                    let error_selector = revert_operations::error_selector(&error_name, &abi_type);
+                    let error_size = expression! { sub([size_expr.clone()], 4) };
+                    let size_matches = match abi_operations::encoding_known_size(
+                        &[abi_type.clone()],
+                    ) {
+                        EncodingSize::Exact(expected) => {
+                            expression! { eq([error_size], [expected]) }
+                        }
+                        EncodingSize::Bounded { min, max } => expression! {
+                            and((iszero((lt([error_size.clone()], [min])))), (iszero((gt([error_size], [max])))))
+                        },
+                    };
+                    let decoded = abi_operations::decode_data(
+                        &[abi_type],
+                        expression! { add([data_expr.clone()], 4) },
+                        expression! { add([data_expr.clone()], [size_expr.clone()]) },
+                        AbiDecodeLocation::Memory,
+                    );
+                    let name = names::var_name(&name.kind);
+                    yul_catch.push(statement! { let [name] := [decoded] });
+                    expression! {
+                        and(
+                            (iszero([caught_expr.clone()])),
+                            (and((eq([identifier_expression! { (selector) }], [error_selector])), [size_matches]))
+                        )
+                    }
+                }
+                None => {
+                    catches_all = true;
+                    expression! { iszero([caught_expr.clone()]) }
+                }
+            };
+            yul_catch.extend(multiple_func_stmt(context, &catch.kind.body));
+            yul_catches.push(statement! { if ([test]) { [yul_catch...] } });
+        }
+        if !catches_all {
+            yul_catches.push(statement! {
+                if (iszero([caught_expr])) {
+                    (revert([data_expr.clone()], [size_expr.clone()]))
+                }
+            });
+        }
+
+        let outcome = switch! {
+            switch ([identifier_expression! { (success) }])
+            (case 1 { [yul_body...] })
+            (default {
+                // The revert data is copied to memory, to be decoded by the
+                // matching clause.
+                (let [identifier! { (size) }] := returndatasize())
+                (let [identifier! { (data) }] := alloc([size_expr.clone()]))
+                (returndatacopy([data_expr.clone()], 0, [size_expr.clone()]))
+                (let [identifier! { (selector) }] := 0)
+                (if (gt([size_expr], 3)) {
+                    ([identifier! { (selector) }] := shr(224, (mload([data_expr]))))
+                })
+                (let [identifier! { (caught) }] := 0)
+                [yul_catches...]
+            })
+        };
+        return block_statement! {
+            (let [identifier! { (success) }] := 0)
+            (let [identifier! { (value) }] := 0)
+            [yul::Statement::Assignment(yul::Assignment {
+                identifiers: vec![identifier! { (success) }, identifier! { (value) }],
+                expression: try_call,
+            })]
+            [outcome]
+        };
+    }
+
+    unreachable!()
+}
+
 fn expr(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
     if let fe::FuncStmt::Expr { value } = &stmt.kind {
         let expr = expressions::expr(context, value);
@@ -163,7 +375,13 @@ fn emit(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
                 )
             })
             .collect();
-        return data_operations::emit_event(&event.name, &event_fields, event_values);
+        return data_operations::emit_event(
+            &event.name,
+            &event_fields,
+            event.anonymous,
+            context.packs_event_data(),
+            event_values,
+        );
     }
 
     unreachable!()
@@ -177,7 +395,15 @@ fn assert(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement
                 let msg = expressions::expr(context, val);
                 let msg_attributes = context.expression_attributes(val).clone();
 
-                if let Type::String(string) = msg_attributes.typ {
+                if context.db.strip_revert_strings() {
+                    // The message is still evaluated, in case it has side effects.
+                    statement! {
+                        if (iszero([test])) {
+                            (pop([msg]))
+                            (revert(0, 0))
+                        }
+                    }
+                } else if let Type::String(string) = msg_attributes.typ {
                     let abi_type = string.as_abi_type(context.adb);
                     statement! {
                         if (iszero([test])) {
@@ -202,7 +428,8 @@ fn assert(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement
 }
 
 fn break_statement(_context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
-    if let fe::FuncStmt::Break {} = &stmt.kind {
+    if let fe::FuncStmt::Break { value } = &stmt.kind {
+        assert!(value.is_none(), "`break` with a value should be lowered");
         return statement! { break };
     }
 
@@ -248,3 +475,20 @@ fn while_loop(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statem
 
     unreachable!()
 }
+
+fn loop_statement(context: &mut FnContext, stmt: &Node<fe::FuncStmt>) -> yul::Statement {
+    if let fe::FuncStmt::Loop { result, body } = &stmt.kind {
+        assert!(result.is_none(), "`loop` with a value should be lowered");
+        let test = literal_expression! { (1) };
+        let yul_body = multiple_func_stmt(context, body);
+
+        return block_statement! {
+            (for {} ([test]) {}
+            {
+                [yul_body...]
+            })
+        };
+    }
+
+    unreachable!()
+}
@@ -1,10 +1,11 @@
 use crate::{ModuleId, YulgenDb};
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use yultsur::yul;
 
-pub type YulContracts = HashMap<String, yul::Object>;
+/// Yul objects of a module's contracts, in declaration order.
+pub type YulContracts = IndexMap<String, yul::Object>;
 
-/// Builds a vector of Yul contracts from a Fe module.
+/// Builds a map of Yul contracts from a Fe module.
 pub fn module(db: &dyn YulgenDb, module: ModuleId) -> YulContracts {
     module
         .all_contracts(db.upcast())
@@ -8,6 +8,27 @@ pub fn encode(_types: &[AbiType]) -> yul::Identifier {
     identifier! { (name) }
 }
 
+/// The name of the function that abi encodes a value into a new `Vec<u8>`.
+pub fn encode_vec(typ: &AbiType) -> yul::Identifier {
+    let name = format!("abi_encode_vec_{}", self::typ(typ));
+
+    identifier! { (name) }
+}
+
+/// The name of the function that decodes the bytes of a `Vec<u8>`.
+pub fn decode_vec(typ: &AbiType) -> yul::Identifier {
+    let name = format!("abi_decode_vec_{}", self::typ(typ));
+
+    identifier! { (name) }
+}
+
+/// The name of the function that hashes the abi encoding of a value.
+pub fn hash(typ: &AbiType) -> yul::Identifier {
+    let name = format!("abi_hash_{}", self::typ(typ));
+
+    identifier! { (name) }
+}
+
 pub fn decode_data(_types: &[AbiType], location: AbiDecodeLocation) -> yul::Identifier {
     let name = format!(
         "abi_decode_data_{}_{}",
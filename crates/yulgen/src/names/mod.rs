@@ -1,6 +1,6 @@
 use crate::names::abi as abi_names;
 use crate::types::AbiType;
-use fe_analyzer::namespace::types::Integer;
+use fe_analyzer::namespace::types::{FunctionType, Integer, SafeNames};
 use yultsur::*;
 
 pub mod abi;
@@ -60,6 +60,17 @@ pub fn adjust_numeric_size(size: &Integer) -> yul::Identifier {
     identifier! {(format!("adjust_numeric_{}", size.as_ref().to_lowercase()))}
 }
 
+/// Generate a function name to convert a value to the given integer type,
+/// reverting if it doesn't fit
+pub fn checked_conversion(size: &Integer) -> yul::Identifier {
+    identifier! {(format!("checked_conversion_{}", size.as_ref().to_lowercase()))}
+}
+
+/// Generate a function name to call a value of the given function type
+pub fn function_value_call(typ: &FunctionType) -> yul::Identifier {
+    identifier! {(format!("call_{}", typ.lower_snake()))}
+}
+
 /// Generate a safe variable name for a user defined function
 pub fn var_name(name: &str) -> yul::Identifier {
     identifier! { (format!("${}", name)) }
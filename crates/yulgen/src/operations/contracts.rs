@@ -25,3 +25,21 @@ pub fn create(name: &str, value: yul::Expression) -> yul::Expression {
         )
     }
 }
+
+/// Computes the address at which the `create2` operation deploys a given
+/// contract, when executed by `deployer` with the given salt.
+pub fn create2_address(
+    name: &str,
+    deployer: yul::Expression,
+    salt: yul::Expression,
+) -> yul::Expression {
+    let name = literal_expression! { (format!("\"{}\"", name)) };
+    expression! {
+        contract_create2_address(
+            (dataoffset([name.clone()])),
+            (datasize([name])),
+            [deployer],
+            [salt]
+        )
+    }
+}
@@ -1,6 +1,6 @@
 use crate::operations::abi as abi_operations;
 use crate::types::{AbiType, EvmSized};
-use fe_analyzer::namespace::types::Array;
+use fe_analyzer::namespace::types::{Array, FeVec};
 use yultsur::*;
 
 /// Loads a value of the given type from storage.
@@ -70,35 +70,22 @@ pub fn mcopym<T: EvmSized>(typ: T, ptr: yul::Expression) -> yul::Expression {
 }
 
 /// Logs an event.
+///
+/// If `packed` is true, the values of the non-indexed fields are packed into
+/// the data of the log, like `abi.encodePacked` in Solidity, instead of being
+/// ABI encoded.
 pub fn emit_event(
     event_name: &str,
     fields: &[(AbiType, bool)], // is_idx
+    anonymous: bool,
+    packed: bool,
     vals: Vec<yul::Expression>,
 ) -> yul::Statement {
-    // (abi_type, is_idx)
-    let topics = {
-        // the first topic is the hash of the event signature
-        let topic_0 = fe_abi::utils::event_topic(
-            event_name,
-            &fields
-                .iter()
-                .map(|(abi_type, _)| abi_type.selector_name())
-                .collect::<Vec<_>>(),
-        );
-        let mut topics = vec![literal_expression! { (topic_0) }];
-
-        // Types will be relevant here when we implement indexed array values.
-        // For now we assume these are all base type values and therefore do not need to
-        // be hashed.
-        let mut idx_field_vals = fields
-            .iter()
-            .zip(vals.iter())
-            .filter_map(|((_field_type, is_idx), val)| is_idx.then(|| val.clone()))
-            .collect::<Vec<_>>();
+    if packed {
+        return emit_packed_event(event_name, fields, anonymous, vals);
+    }
 
-        topics.append(&mut idx_field_vals);
-        topics
-    };
+    let topics = event_topics(event_name, fields, anonymous, &vals);
 
     let (non_idx_field_types, non_idx_field_vals): (Vec<_>, Vec<_>) = fields
         .iter()
@@ -114,6 +101,126 @@ pub fn emit_event(
     return statement! { [log_func]([encoding], [encoding_size], [topics...]) };
 }
 
+/// Logs an event whose data is packed: each value takes up only as many bytes
+/// as its type, and the bytes of a string are logged without their length or
+/// any padding.
+fn emit_packed_event(
+    event_name: &str,
+    fields: &[(AbiType, bool)], // is_idx
+    anonymous: bool,
+    vals: Vec<yul::Expression>,
+) -> yul::Statement {
+    // The values are evaluated before the data is packed, so that the memory
+    // that they allocate doesn't end up in the middle of the data.
+    let names = (0..vals.len())
+        .map(|index| format!("packed_val_{}", index))
+        .collect::<Vec<_>>();
+    let bindings = names
+        .iter()
+        .zip(vals)
+        .map(|(name, val)| {
+            let name = identifier! { (name) };
+            statement! { let [name] := [val] }
+        })
+        .collect::<Vec<_>>();
+    let vals = names
+        .iter()
+        .map(|name| identifier_expression! { (name) })
+        .collect::<Vec<_>>();
+
+    let topics = event_topics(event_name, fields, anonymous, &vals);
+    let packing = fields
+        .iter()
+        .zip(vals)
+        .filter(|((_, is_idx), _)| !is_idx)
+        .map(|((typ, _), val)| pack_value(typ, val))
+        .collect::<Vec<_>>();
+
+    let log_func = identifier! { (format!("log{}", topics.len())) };
+
+    yul::Statement::Block(block! {
+        [bindings...]
+        (let packed_ptr := avail())
+        [packing...]
+        ([log_func](packed_ptr, (sub((avail()), packed_ptr)), [topics...]))
+    })
+}
+
+/// Copies a value to the end of the packed data of an event.
+fn pack_value(typ: &AbiType, val: yul::Expression) -> yul::Statement {
+    match typ {
+        AbiType::Uint { size } | AbiType::Int { size } => {
+            let size = literal_expression! { (size) };
+            statement! { pop((alloc_mstoren([val], [size]))) }
+        }
+        AbiType::Bool => statement! { pop((alloc_mstoren([val], 1))) },
+        AbiType::Address => statement! { pop((alloc_mstoren([val], 20))) },
+        AbiType::String { .. } => {
+            statement! { pop((mcopym((add([val.clone()], 32)), (mload([val]))))) }
+        }
+        AbiType::Bytes { size } => {
+            let size = literal_expression! { (size) };
+            statement! { pop((mcopym([val], [size]))) }
+        }
+        AbiType::StaticArray { .. } | AbiType::Tuple { .. } => {
+            unreachable!("the analyzer rejects packed events with arrays and tuples")
+        }
+    }
+}
+
+/// The topics of the log of an event: the hash of the event signature, unless
+/// the event is anonymous, followed by the values of the indexed fields.
+fn event_topics(
+    event_name: &str,
+    fields: &[(AbiType, bool)], // is_idx
+    anonymous: bool,
+    vals: &[yul::Expression],
+) -> Vec<yul::Expression> {
+    let mut topics = vec![];
+    if !anonymous {
+        let topic_0 = fe_abi::utils::event_topic(
+            event_name,
+            &fields
+                .iter()
+                .map(|(abi_type, _)| abi_type.selector_name())
+                .collect::<Vec<_>>(),
+        );
+        topics.push(literal_expression! { (topic_0) });
+    }
+
+    topics.extend(
+        fields
+            .iter()
+            .zip(vals.iter())
+            .filter_map(|((field_type, is_idx), val)| {
+                is_idx.then(|| event_topic(field_type, val.clone()))
+            }),
+    );
+    topics
+}
+
+/// The topic that an indexed event field is logged as. Values of reference
+/// types are hashed, as in the ABI spec: a string or byte array is hashed as
+/// its bytes, and an array or tuple as its ABI encoding, in which every
+/// element is padded to 32 bytes.
+fn event_topic(typ: &AbiType, val: yul::Expression) -> yul::Expression {
+    match typ {
+        AbiType::String { .. } => expression! { string_keccak256([val]) },
+        AbiType::Bytes { size } => {
+            let size = literal_expression! { (size) };
+            expression! { keccak256([val], [size]) }
+        }
+        AbiType::StaticArray { .. } | AbiType::Tuple { .. } => {
+            // The analyzer rejects tuples with strings or byte arrays in them,
+            // so the size of the encoding is known without reading `val`.
+            let size = abi_operations::encoding_size(&[typ.clone()], &[val.clone()]);
+            let encoding = abi_operations::encode(&[typ.clone()], vec![val]);
+            expression! { keccak256([encoding], [size]) }
+        }
+        AbiType::Uint { .. } | AbiType::Int { .. } | AbiType::Bool | AbiType::Address => val,
+    }
+}
+
 /// Sums a list of expressions using nested add operations.
 pub fn sum(vals: Vec<yul::Expression>) -> yul::Expression {
     if vals.is_empty() {
@@ -142,3 +249,21 @@ pub fn indexed_array(
     let array_length = literal_expression! { (typ.size) };
     expression! { get_array_item([array], [array_length], [index], [inner_size] ) }
 }
+
+/// Finds the location of an array element whose index is known to be within
+/// the bounds of the array, without checking the index.
+pub fn indexed_array_unchecked(
+    typ: Array,
+    array: yul::Expression,
+    index: yul::Expression,
+) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { add([array], (mul([index], [inner_size]))) }
+}
+
+/// Finds the location of a vec element base on the element size, element
+/// index, and vec location.
+pub fn indexed_vec(typ: FeVec, vec: yul::Expression, index: yul::Expression) -> yul::Expression {
+    let inner_size = literal_expression! { (typ.inner.size()) };
+    expression! { vec_item([vec], [index], [inner_size]) }
+}
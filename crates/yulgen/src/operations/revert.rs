@@ -30,3 +30,23 @@ pub fn revert(name: &str, typ: &AbiType, val: yul::Expression) -> yul::Statement
     let func_name = names::revert(name, typ);
     statement! { [func_name]([val]) }
 }
+
+/// The selector of the revert data of an error with the given name and type,
+/// as a Yul literal.
+///
+/// The parentheses on a tuple are removed in the selector preimage, e.g. we
+/// use `MyError(bool,address)` instead of `MyError((bool,address))`.
+pub fn error_selector(name: &str, typ: &AbiType) -> yul::Expression {
+    let selector_params = match typ.clone() {
+        AbiType::Tuple { components } => components,
+        typ => vec![typ],
+    };
+    let selector = fe_abi::utils::func_selector(
+        name,
+        &selector_params
+            .iter()
+            .map(AbiType::selector_name)
+            .collect::<Vec<_>>(),
+    );
+    literal_expression! { (selector) }
+}
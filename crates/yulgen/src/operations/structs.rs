@@ -20,7 +20,13 @@ pub fn get_attribute(
     location: Location,
 ) -> yul::Expression {
     let function_name = identifier! { (db.struct_getter_name(struct_, field_name.into())) };
-    if struct_.is_base_type(db.upcast(), field_name) {
+    if struct_.is_packed(db.upcast()) && matches!(location, Location::Storage { .. }) {
+        let index = struct_
+            .field_index(db.upcast(), field_name)
+            .expect("unknown field");
+        let offset = literal_expression! { (db.struct_packed_offsets(struct_)[index]) };
+        expression! { add([val], [offset]) }
+    } else if struct_.is_base_type(db.upcast(), field_name) {
         expression! { [function_name]([val]) }
     } else if matches!(location, Location::Storage { .. }) {
         let index = struct_
@@ -34,12 +40,28 @@ pub fn get_attribute(
     }
 }
 
+/// Copies a `#[packed]` struct from storage to a newly allocated struct in
+/// memory.
+pub fn copy_packed_to_memory(
+    db: &dyn YulgenDb,
+    struct_: StructId,
+    value: yul::Expression,
+) -> yul::Expression {
+    let function_name = identifier! { (db.struct_sload_packed_name(struct_)) };
+    expression! { [function_name]([value]) }
+}
+
 pub fn copy_to_storage(
     db: &dyn YulgenDb,
     struct_: &Struct,
     target: yul::Expression,
     value: yul::Expression,
 ) -> yul::Statement {
+    if struct_.id.is_packed(db.upcast()) {
+        let function_name = identifier! { (db.struct_sstore_packed_name(struct_.id)) };
+        return statement! { [function_name]([target], [value]) };
+    }
+
     let yul_body = [
         // We first copy the entire struct from memory to storage *including* the memory references.
         // The memory references are a pointless waste of storage space and are never read or written to.
@@ -74,7 +96,12 @@ pub fn copy_to_storage(
                     );
                     // We have to go over all struct fields to copy the actual data of the reference type fields
                     // to their respective location in storage because all we copied so far were useless memory references
-                    Some(data_operations::mcopys(typ, field_to, field_from))
+                    match typ {
+                        FixedSize::Struct(inner) if inner.id.is_packed(db.upcast()) => {
+                            Some(copy_to_storage(db, &inner, field_to, field_from))
+                        }
+                        _ => Some(data_operations::mcopys(typ, field_to, field_from)),
+                    }
                 }
             })
             .collect(),
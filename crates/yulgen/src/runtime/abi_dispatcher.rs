@@ -1,46 +1,115 @@
 use crate::names::abi as abi_names;
 use crate::operations::abi as abi_operations;
-use crate::types::{to_abi_selector_names, AbiDecodeLocation, AbiType};
-use fe_abi::utils as abi_utils;
+use crate::types::{AbiDecodeLocation, AbiType};
+use fe_abi::utils::ERC165_INTERFACE_ID;
+use fe_analyzer::namespace::selector::Selector;
 use smol_str::SmolStr;
 use yultsur::*;
 
 /// Builds a switch statement that dispatches calls to the contract and wraps it in
-/// a `$$__call__` function.
+/// a `$$__call__` function. The first item of each function is its selector,
+/// and the last item is `true` if the function is payable; calls to the other
+/// functions revert if they transfer ether.
+///
+/// Calls with empty calldata go to the `receive` function, if there is one.
+/// Calls that don't select any of the functions go to the `fallback`
+/// function, which is paired with whether it's payable, and do nothing if
+/// there is no fallback function.
+///
+/// If `interface_ids` isn't empty, the contract also answers the ERC-165
+/// `supportsInterface(bytes4)` function with whether the given interface id
+/// is one of them.
 pub fn dispatcher(
-    functions: &[(SmolStr, SmolStr, impl AsRef<[AbiType]>, Option<AbiType>)],
+    functions: &[(
+        Selector,
+        SmolStr,
+        impl AsRef<[AbiType]>,
+        Option<AbiType>,
+        bool,
+    )],
+    fallback: Option<(SmolStr, bool)>,
+    receive: Option<SmolStr>,
+    interface_ids: &[u32],
 ) -> yul::Statement {
-    let arms = functions
+    let mut arms = functions
         .iter()
-        .map(|(name, qname, params, ret)| dispatch_arm(name, qname, params.as_ref(), ret))
+        .map(|(selector, qname, params, ret, is_payable)| {
+            dispatch_arm(*selector, qname, params.as_ref(), ret, *is_payable)
+        })
         .collect::<Vec<_>>();
+    if !interface_ids.is_empty() {
+        arms.push(supports_interface_arm(interface_ids));
+    }
+
+    // `__receive__` is always payable.
+    let maybe_receive = if let Some(receive) = receive {
+        let receive = identifier! { (receive) };
+        statements! {
+            (if (iszero((calldatasize()))) {
+                (pop([receive]()))
+                (return(0, 0))
+            })
+        }
+    } else {
+        statements! {}
+    };
+
+    let default = if let Some((fallback, is_payable)) = fallback {
+        let fallback = identifier! { (fallback) };
+        let maybe_reject_value = if is_payable {
+            statements! {}
+        } else {
+            statements! { (if (callvalue()) { (revert(0, 0)) }) }
+        };
+        statements! {
+            [maybe_reject_value...]
+            (pop([fallback]()))
+            (return(0, 0))
+        }
+    } else {
+        statements! { (return(0, 0)) }
+    };
 
     let dispatcher = if arms.is_empty() {
-        statement! { return(0, 0) }
+        default
     } else {
-        switch! {
+        let default = match default.as_slice() {
+            [stmt] => stmt.clone(),
+            _ => yul::Statement::Block(yul::Block {
+                statements: default,
+            }),
+        };
+        vec![switch! {
             switch (cloadn(0, 4))
             [arms...]
-            (default { (return(0, 0)) })
-        }
+            (default { [default] })
+        }]
     };
 
     let call_fn_ident = identifier! { ("$$__call__") };
 
     function_definition! {
         function [call_fn_ident]() {
-            [dispatcher]
+            [maybe_receive...]
+            [dispatcher...]
         }
     }
 }
 
 fn dispatch_arm(
-    bare_name: &str,
+    selector: Selector,
     qualified_name: &str,
     params: &[AbiType],
     return_type: &Option<AbiType>,
+    is_payable: bool,
 ) -> yul::Case {
-    let selector = selector(bare_name, params);
+    let selector = selector_literal(selector);
+
+    let maybe_reject_value = if is_payable {
+        statements! {}
+    } else {
+        statements! { (if (callvalue()) { (revert(0, 0)) }) }
+    };
 
     let (param_idents, param_exprs) = abi_names::vals("call", params.len());
 
@@ -82,31 +151,68 @@ fn dispatch_arm(
 
     case! {
         case [selector] {
+            [maybe_reject_value...]
             [maybe_decode_params...]
             [call_and_maybe_encode_return...]
         }
     }
 }
 
-fn selector(name: &str, params: &[AbiType]) -> yul::Literal {
-    literal! { (abi_utils::func_selector(name, &to_abi_selector_names(params))) }
+/// Returns whether the `bytes4` interface id in the calldata is one of the
+/// given ids. The call reverts if it transfers ether, or if the calldata is
+/// too short to hold the id.
+fn supports_interface_arm(interface_ids: &[u32]) -> yul::Case {
+    let selector = selector_literal(Selector(ERC165_INTERFACE_ID));
+    let is_supported = interface_ids
+        .iter()
+        .map(|id| {
+            let id = literal_expression! { (format!("{:#010x}", id)) };
+            expression! { eq(interface_id, [id]) }
+        })
+        .reduce(|supported, is_id| expression! { or([supported], [is_id]) })
+        .expect("no interface ids");
+
+    case! {
+        case [selector] {
+            (if (callvalue()) { (revert(0, 0)) })
+            (if (lt((calldatasize()), 36)) { (revert(0, 0)) })
+            (let interface_id := shr(224, (calldataload(4))))
+            (mstore(0, [is_supported]))
+            (return(0, 32))
+        }
+    }
+}
+
+fn selector_literal(selector: Selector) -> yul::Literal {
+    literal! { (selector.to_string()) }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::runtime::abi_dispatcher::selector;
-    use crate::types::AbiType;
+    use crate::runtime::abi_dispatcher::selector_literal;
+    use fe_analyzer::namespace::selector::Selector;
 
     #[test]
     fn test_selector_literal_basic() {
-        assert_eq!(selector("foo", &[]).to_string(), String::from("0xc2985578"),)
+        assert_eq!(
+            selector_literal(Selector::of_signature("foo()")).to_string(),
+            String::from("0xc2985578"),
+        )
     }
 
     #[test]
     fn test_selector_literal() {
         assert_eq!(
-            selector("bar", &[AbiType::Uint { size: 32 }]).to_string(),
+            selector_literal(Selector::of_signature("bar(uint256)")).to_string(),
             String::from("0x0423a132"),
         )
     }
+
+    #[test]
+    fn test_selector_literal_pads() {
+        assert_eq!(
+            selector_literal(Selector(0x00ff)).to_string(),
+            String::from("0x000000ff"),
+        )
+    }
 }
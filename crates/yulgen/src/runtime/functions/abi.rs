@@ -3,7 +3,7 @@ use crate::names::abi as abi_names;
 use crate::operations::abi as abi_operations;
 use crate::operations::abi::EncodingSize;
 use crate::operations::revert as revert_operations;
-use crate::types::{AbiDecodeLocation, AbiType};
+use crate::types::{AbiDecodeLocation, AbiDecoding, AbiType};
 use crate::utils::ceil_32;
 use yultsur::*;
 
@@ -26,16 +26,26 @@ pub fn all() -> Vec<yul::Statement> {
 /// The decoding of each component is handled by a separate function, eg.
 /// `abi_decode_component_uint32_mem`; these component decoding functions
 /// are also included in the returned `Vec`.
-pub fn decode_functions(types: &[AbiType], location: AbiDecodeLocation) -> Vec<yul::Statement> {
+///
+/// The functions are named after the location only, so all of the functions
+/// that decode from the same location must be generated with the same
+/// `decoding`.
+pub fn decode_functions(
+    types: &[AbiType],
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> Vec<yul::Statement> {
     let mut component_fns: Vec<_> = types.iter().fold(vec![], |mut funcs, typ| {
-        funcs.push(decode_component(typ, location));
+        funcs.push(decode_component(typ, location, decoding));
         match typ {
             AbiType::Tuple { components } => {
                 for ctyp in components {
-                    funcs.push(decode_component(ctyp, location))
+                    funcs.push(decode_component(ctyp, location, decoding))
                 }
             }
-            AbiType::StaticArray { inner, .. } => funcs.push(decode_component(inner, location)),
+            AbiType::StaticArray { inner, .. } => {
+                funcs.push(decode_component(inner, location, decoding))
+            }
             _ => {}
         };
         funcs
@@ -43,12 +53,16 @@ pub fn decode_functions(types: &[AbiType], location: AbiDecodeLocation) -> Vec<y
 
     component_fns.sort();
     component_fns.dedup();
-    component_fns.push(decode_data(types, location));
+    component_fns.push(decode_data(types, location, decoding));
     component_fns
 }
 
 /// Creates a function that decodes ABI encoded data.
-fn decode_data(types: &[AbiType], location: AbiDecodeLocation) -> yul::Statement {
+fn decode_data(
+    types: &[AbiType],
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     #[derive(Clone)]
     struct IdentExpr {
         ident: yul::Identifier,
@@ -104,14 +118,23 @@ fn decode_data(types: &[AbiType], location: AbiDecodeLocation) -> yul::Statement
         })
         .collect();
 
-    let size_check = match abi_operations::encoding_known_size(types) {
-        EncodingSize::Exact(size) => statements! {
+    // Lenient decoding only checks that the data isn't too short.
+    let size_check = match (abi_operations::encoding_known_size(types), decoding) {
+        (EncodingSize::Exact(min) | EncodingSize::Bounded { min, .. }, AbiDecoding::Lenient) => {
+            statements! {
+                (let encoding_size := sub(data_end, head_start))
+                (if (lt(encoding_size, [min])) {
+                    [revert_with_invalid_abi_data()]
+                })
+            }
+        }
+        (EncodingSize::Exact(size), AbiDecoding::Strict) => statements! {
             (let encoding_size := sub(data_end, head_start))
             (if (iszero((eq(encoding_size, [size])))) {
                 [revert_with_invalid_abi_data()]
             })
         },
-        EncodingSize::Bounded { min, max } => statements! {
+        (EncodingSize::Bounded { min, max }, AbiDecoding::Strict) => statements! {
             (let encoding_size := sub(data_end, head_start))
             (if (or(
                 (lt(encoding_size, [min])),
@@ -181,12 +204,21 @@ fn decode_data(types: &[AbiType], location: AbiDecodeLocation) -> yul::Statement
         start_offset_exprs.push(expression! { encoding_size });
         end_offset_exprs.insert(0, encoding_head_size);
 
+        let count = start_offset_exprs.len();
         start_offset_exprs
             .into_iter()
             .zip(end_offset_exprs)
-            .map(|(start_offset, end_offset)| {
-                statement! {
-                    if (iszero((eq([start_offset], [end_offset])))) { [revert_with_invalid_abi_data()] }
+            .enumerate()
+            .map(|(index, (start_offset, end_offset))| {
+                if decoding == AbiDecoding::Lenient && index == count - 1 {
+                    // The data may be followed by any number of bytes.
+                    statement! {
+                        if (gt([end_offset], [start_offset])) { [revert_with_invalid_abi_data()] }
+                    }
+                } else {
+                    statement! {
+                        if (iszero((eq([start_offset], [end_offset])))) { [revert_with_invalid_abi_data()] }
+                    }
                 }
             })
             .collect()
@@ -209,56 +241,81 @@ fn decode_data(types: &[AbiType], location: AbiDecodeLocation) -> yul::Statement
 }
 
 /// Creates a function that decodes a single component in ABI encoded data.
-pub fn decode_component(typ: &AbiType, location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component(
+    typ: &AbiType,
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     match typ {
         AbiType::StaticArray { inner, size } => {
             decode_component_static_array(inner, *size, location)
         }
         AbiType::Tuple { components: elems } => decode_component_tuple(elems, location),
-        AbiType::Uint { size } => decode_component_uint(*size, location),
-        AbiType::Int { size } => decode_component_int(*size, location),
-        AbiType::Bool => decode_component_bool(location),
-        AbiType::Address => decode_component_address(location),
-        AbiType::String { max_size } => decode_component_string(*max_size, location),
-        AbiType::Bytes { size } => decode_component_bytes(*size, location),
+        AbiType::Uint { size } => decode_component_uint(*size, location, decoding),
+        AbiType::Int { size } => decode_component_int(*size, location, decoding),
+        AbiType::Bool => decode_component_bool(location, decoding),
+        AbiType::Address => decode_component_address(location, decoding),
+        AbiType::String { max_size } => decode_component_string(*max_size, location, decoding),
+        AbiType::Bytes { size } => decode_component_bytes(*size, location, decoding),
     }
 }
 
-pub fn decode_component_uint(size: usize, location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component_uint(
+    size: usize,
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     let func_name = abi_names::decode_component_uint(size, location);
     let decode_expr = load_word(expression! { ptr }, location);
-    let check_padding = check_left_padding(
-        literal_expression! { ((32 - size) * 8) },
-        expression! { return_val },
-    );
+    let check_padding = match decoding {
+        AbiDecoding::Strict => vec![check_left_padding(
+            literal_expression! { ((32 - size) * 8) },
+            expression! { return_val },
+        )],
+        AbiDecoding::Lenient => clean_return_val(size),
+    };
 
     function_definition! {
          function [func_name](head_start, offset) -> return_val {
             (let ptr := add(head_start, offset))
             (return_val := [decode_expr])
-            [check_padding]
+            [check_padding...]
          }
     }
 }
 
-pub fn decode_component_int(size: usize, location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component_int(
+    size: usize,
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     let func_name = abi_names::decode_component_int(size, location);
     let decode_expr = load_word(expression! { ptr }, location);
-    let check_size = check_int_size(size, expression! { return_val });
+    let check_size = match decoding {
+        AbiDecoding::Strict => vec![check_int_size(size, expression! { return_val })],
+        AbiDecoding::Lenient if size == 32 => vec![],
+        AbiDecoding::Lenient => {
+            let byte_index = literal_expression! { (size - 1) };
+            statements! { (return_val := signextend([byte_index], return_val)) }
+        }
+    };
 
     function_definition! {
          function [func_name](head_start, offset) -> return_val {
             (let ptr := add(head_start, offset))
             (return_val := [decode_expr])
-            [check_size]
+            [check_size...]
          }
     }
 }
 
-pub fn decode_component_bool(location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component_bool(location: AbiDecodeLocation, decoding: AbiDecoding) -> yul::Statement {
     let func_name = abi_names::decode_component_bool(location);
     let decode_expr = load_word(expression! { ptr }, location);
-    let check_padding = check_left_padding(expression! { 255 }, expression! { return_val });
+    let check_padding = match decoding {
+        AbiDecoding::Strict => check_left_padding(expression! { 255 }, expression! { return_val }),
+        AbiDecoding::Lenient => statement! { return_val := iszero((iszero(return_val))) },
+    };
 
     function_definition! {
          function [func_name](head_start, offset) -> return_val {
@@ -269,16 +326,25 @@ pub fn decode_component_bool(location: AbiDecodeLocation) -> yul::Statement {
     }
 }
 
-pub fn decode_component_address(location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component_address(
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     let func_name = abi_names::decode_component_address(location);
     let decode_expr = load_word(expression! { ptr }, location);
-    let check_padding = check_left_padding(expression! { 96 }, expression! { return_val });
+    let check_padding = match decoding {
+        AbiDecoding::Strict => vec![check_left_padding(
+            expression! { 96 },
+            expression! { return_val },
+        )],
+        AbiDecoding::Lenient => clean_return_val(20),
+    };
 
     function_definition! {
          function [func_name](head_start, offset) -> return_val {
             (let ptr := add(head_start, offset))
             (return_val := [decode_expr])
-            [check_padding]
+            [check_padding...]
          }
     }
 }
@@ -343,9 +409,14 @@ pub fn decode_component_tuple(elems: &[AbiType], location: AbiDecodeLocation) ->
     }
 }
 
-pub fn decode_component_bytes(size: usize, location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component_bytes(
+    size: usize,
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     let func_name = abi_names::decode_component_bytes(size, location);
     let size = literal_expression! { (size) };
+    let check_padding = check_end_padding(location, decoding);
 
     function_definition! {
          function [func_name](head_start, head_offset) -> return_val, data_start_offset, data_end_offset {
@@ -357,12 +428,7 @@ pub fn decode_component_bytes(size: usize, location: AbiDecodeLocation) -> yul::
             (let data_size := add(bytes_size, 32))
             (let padded_data_size := ceil32(data_size))
             (data_end_offset := add(data_start_offset, padded_data_size))
-            (let end_word := [load_word(expression! { sub((add(head_start, data_end_offset)), 32) }, location)])
-            (let padding_size_bits := mul((sub(padded_data_size, data_size)), 8))
-            [check_right_padding(
-                expression! { padding_size_bits },
-                expression! { end_word }
-            )]
+            [check_padding...]
             (return_val := [copy_data(
                 // We do not copy the dynamic size value like we do with strings, so we add 32 bytes
                 // to the start and subtract 32 bytes from the size being copied.
@@ -374,9 +440,14 @@ pub fn decode_component_bytes(size: usize, location: AbiDecodeLocation) -> yul::
     }
 }
 
-pub fn decode_component_string(max_size: usize, location: AbiDecodeLocation) -> yul::Statement {
+pub fn decode_component_string(
+    max_size: usize,
+    location: AbiDecodeLocation,
+    decoding: AbiDecoding,
+) -> yul::Statement {
     let func_name = abi_names::decode_component_string(max_size, location);
     let max_size = literal_expression! { (max_size) };
+    let check_padding = check_end_padding(location, decoding);
 
     function_definition! {
          function [func_name](head_start, head_offset) -> return_val, data_start_offset, data_end_offset {
@@ -388,12 +459,7 @@ pub fn decode_component_string(max_size: usize, location: AbiDecodeLocation) ->
             (let data_size := add(string_size, 32))
             (let padded_data_size := ceil32(data_size))
             (data_end_offset := add(data_start_offset, padded_data_size))
-            (let end_word := [load_word(expression! { sub((add(head_start, data_end_offset)), 32) }, location)])
-            (let padding_size_bits := mul((sub(padded_data_size, data_size)), 8))
-            [check_right_padding(
-                expression! { padding_size_bits },
-                expression! { end_word }
-            )]
+            [check_padding...]
             (return_val := [copy_data(
                 expression! { data_start },
                 expression! { data_size },
@@ -450,6 +516,34 @@ fn check_right_padding(size_bits: yul::Expression, val: yul::Expression) -> yul:
     }
 }
 
+/// Reverts if the last word of dynamically sized data isn't right padded with
+/// zeros. Nothing is checked when decoding leniently.
+///
+/// Expects `head_start`, `data_end_offset`, `padded_data_size` and `data_size`
+/// to be defined.
+fn check_end_padding(location: AbiDecodeLocation, decoding: AbiDecoding) -> Vec<yul::Statement> {
+    match decoding {
+        AbiDecoding::Strict => statements! {
+            (let end_word := [load_word(expression! { sub((add(head_start, data_end_offset)), 32) }, location)])
+            (let padding_size_bits := mul((sub(padded_data_size, data_size)), 8))
+            [check_right_padding(
+                expression! { padding_size_bits },
+                expression! { end_word }
+            )]
+        },
+        AbiDecoding::Lenient => vec![],
+    }
+}
+
+/// Clears the bits of `return_val` to the left of the given number of bytes.
+fn clean_return_val(size: usize) -> Vec<yul::Statement> {
+    if size == 32 {
+        return vec![];
+    }
+    let mask = literal_expression! { (format!("0x{}", "ff".repeat(size))) };
+    statements! { (return_val := and(return_val, [mask])) }
+}
+
 /// Reverts if the integer value does not fit within the given number of bytes.
 fn check_int_size(size: usize, val: yul::Expression) -> yul::Statement {
     // the bits to the left of this size should be either all 0s or all 1s
@@ -514,6 +608,62 @@ pub fn unpack() -> yul::Statement {
 //     }
 // }
 
+/// Creates a function that abi encodes a value into a new `Vec<u8>`, for
+/// `abi_encode`. The items of the vec are the encoded bytes.
+pub fn encode_vec(typ: &AbiType) -> yul::Statement {
+    let func_name = abi_names::encode_vec(typ);
+    let val_expr = vec![expression! { val }];
+    let encoding_size = abi_operations::encoding_size(&[typ.clone()], &val_expr);
+    let encode_val = abi_operations::encode(&[typ.clone()], val_expr);
+
+    function_definition! {
+        function [func_name](val) -> vec {
+            (let size := [encoding_size])
+            (let items := [encode_val])
+            (vec := alloc(96))
+            (mstore(vec, size))
+            (mstore((add(vec, 32)), size))
+            (mstore((add(vec, 64)), items))
+        }
+    }
+}
+
+/// Creates a function that decodes the bytes of a `Vec<u8>` into a value, for
+/// `abi_decode`. Reverts if the bytes aren't a valid encoding of the type.
+pub fn decode_vec(typ: &AbiType) -> yul::Statement {
+    let func_name = abi_names::decode_vec(typ);
+    let decode_val = abi_operations::decode_data(
+        &[typ.clone()],
+        expression! { items },
+        expression! { add(items, (mload(vec))) },
+        AbiDecodeLocation::Memory,
+    );
+
+    function_definition! {
+        function [func_name](vec) -> return_val {
+            (let items := mload((add(vec, 64))))
+            (return_val := [decode_val])
+        }
+    }
+}
+
+/// Creates a function that hashes the abi encoding of a value, for
+/// `keccak256` of an array, tuple or struct.
+pub fn hash(typ: &AbiType) -> yul::Statement {
+    let func_name = abi_names::hash(typ);
+    let val_expr = vec![expression! { val }];
+    let encoding_size = abi_operations::encoding_size(&[typ.clone()], &val_expr);
+    let encode_val = abi_operations::encode(&[typ.clone()], val_expr);
+
+    function_definition! {
+        function [func_name](val) -> return_val {
+            (let size := [encoding_size])
+            (let ptr := [encode_val])
+            (return_val := keccak256(ptr, size))
+        }
+    }
+}
+
 /// Generates an encoding function for any set of type parameters.
 pub fn encode(types: &[AbiType]) -> yul::Statement {
     let func_name = abi_names::encode(types);
@@ -1,8 +1,10 @@
+use crate::constants::ERROR_CALL_TO_NON_CONTRACT;
+use crate::operations::revert as revert_operations;
 use yultsur::*;
 
 /// Return all contacts runtime functions
 pub fn all() -> Vec<yul::Statement> {
-    vec![create2(), create()]
+    vec![create2(), create(), create2_address(), check_call_target()]
 }
 
 /// Function that executes the `create2` operation.
@@ -26,3 +28,33 @@ pub fn create() -> yul::Statement {
         }
     }
 }
+
+/// Function that computes the address at which the `create2` operation deploys
+/// the given init code.
+pub fn create2_address() -> yul::Statement {
+    function_definition! {
+        function contract_create2_address(data_ptr, data_size, deployer, salt) -> return_address {
+            (let mptr := alloc(data_size))
+            (datacopy(mptr, data_ptr, data_size))
+            (let code_hash := keccak256(mptr, data_size))
+            (let ptr := alloc(85))
+            (mstore8(ptr, 0xff))
+            (mstore((add(ptr, 1)), (shl(96, deployer))))
+            (mstore((add(ptr, 21)), salt))
+            (mstore((add(ptr, 53)), code_hash))
+            (return_address := shr(96, (shl(96, (keccak256(ptr, 85))))))
+        }
+    }
+}
+
+/// Function that reverts with `Error(0x102)` if there's no code at the given
+/// address. A call to such an address succeeds without returning any data.
+pub fn check_call_target() -> yul::Statement {
+    function_definition! {
+        function check_call_target(addr) {
+            (if (iszero((extcodesize(addr)))) {
+                [revert_operations::error_revert_numeric(ERROR_CALL_TO_NON_CONTRACT)]
+            })
+        }
+    }
+}
@@ -0,0 +1,45 @@
+use crate::constants::PANIC_UNINITIALIZED_FUNCTION;
+use crate::names;
+use crate::names::abi as abi_names;
+use crate::operations::revert as revert_operations;
+use fe_analyzer::namespace::types::FunctionType;
+use smol_str::SmolStr;
+use yultsur::*;
+
+/// Generate a YUL function that calls the function referred to by a value of
+/// the given function type. A function value is the id of the function, so the
+/// call is dispatched with a switch over the ids of the given
+/// `(id, function name)` pairs. Calling a value that doesn't refer to any of
+/// the functions, e.g. an uninitialized one, reverts with a panic.
+pub fn call_fn(typ: &FunctionType, functions: &[(SmolStr, SmolStr)]) -> yul::Statement {
+    let func_name = names::function_value_call(typ);
+    let (param_idents, param_exprs) = abi_names::vals("call", typ.params.len());
+
+    let cases = functions
+        .iter()
+        .map(|(id, name)| {
+            let id = literal! { (id) };
+            let name = identifier! { (name) };
+            let args = param_exprs.clone();
+            let call = expression! { [name]([args...]) };
+            case! {
+                case [id] {
+                    (return_val := [call])
+                }
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let switch = switch! {
+        switch func
+        [cases...]
+        (default { [revert_operations::panic_revert(PANIC_UNINITIALIZED_FUNCTION)] })
+    };
+    let params = [vec![identifier! { func }], param_idents].concat();
+
+    function_definition! {
+        function [func_name]([params...]) -> return_val {
+            [switch]
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::constants::{numeric_min_max, PANIC_DIV_OR_MOD_BY_ZERO, PANIC_OVER_OR_UNDERFLOW};
 use crate::names;
+use crate::operations::math as math_operations;
 use crate::operations::revert as revert_operations;
 use fe_analyzer::namespace::types::Integer;
 use yultsur::*;
@@ -126,6 +127,56 @@ pub fn adjust_numeric_size_fns() -> Vec<yul::Statement> {
     ]
 }
 
+/// Return a vector of runtime functions for integer conversions that revert
+/// if the value doesn't fit in the target type
+pub fn checked_conversion_fns() -> Vec<yul::Statement> {
+    vec![
+        checked_conversion(Integer::U256),
+        checked_conversion(Integer::U128),
+        checked_conversion(Integer::U64),
+        checked_conversion(Integer::U32),
+        checked_conversion(Integer::U16),
+        checked_conversion(Integer::U8),
+        checked_conversion(Integer::I256),
+        checked_conversion(Integer::I128),
+        checked_conversion(Integer::I64),
+        checked_conversion(Integer::I32),
+        checked_conversion(Integer::I16),
+        checked_conversion(Integer::I8),
+    ]
+}
+
+/// Return all math runtime functions that are specific to the given integer
+/// size. The functions for power-of-two sizes are already part of [`all`];
+/// functions for other sizes (e.g. `u24`) are only included on demand.
+pub fn integer_fns(integer: Integer) -> Vec<yul::Statement> {
+    let mut fns = if integer.is_signed() {
+        vec![
+            checked_neg_signed(integer),
+            checked_add_signed(integer),
+            checked_div_signed(integer),
+            _checked_exp_signed(integer),
+            checked_mul_signed(integer),
+            checked_sub_signed(integer),
+        ]
+    } else {
+        vec![
+            checked_add_unsigned(integer),
+            _checked_exp_unsigned(integer),
+            checked_mul_unsigned(integer),
+        ]
+    };
+    fns.push(checked_conversion(integer));
+    if integer.size() < 32 {
+        fns.push(if integer.is_signed() {
+            adjust_numeric_signed(integer, integer.size() - 1)
+        } else {
+            adjust_numeric_unsigned(integer)
+        });
+    }
+    fns
+}
+
 // Return all math runtime functions
 pub fn all() -> Vec<yul::Statement> {
     [
@@ -136,6 +187,7 @@ pub fn all() -> Vec<yul::Statement> {
         checked_mul_fns(),
         checked_sub_fns(),
         checked_neg_fns(),
+        checked_conversion_fns(),
         adjust_numeric_size_fns(),
     ]
     .concat()
@@ -358,6 +410,21 @@ fn adjust_numeric_signed(size: Integer, base: usize) -> yul::Statement {
     }
 }
 
+fn checked_conversion(size: Integer) -> yul::Statement {
+    let fn_name = names::checked_conversion(&size);
+    let adjusted = math_operations::adjust_numeric_size(&size, expression! { value });
+    function_definition! {
+        function [fn_name](value, check_sign) -> converted {
+            // The value doesn't fit if it changes when adjusted to the new size,
+            // or if it's negative and the conversion changes the sign.
+            (if (or((iszero((eq(value, [adjusted])))), (and(check_sign, (slt(value, 0)))))) {
+                [revert_with_over_or_under_flow()]
+            })
+            (converted := value)
+        }
+    }
+}
+
 fn checked_exp_helper() -> yul::Statement {
     // TODO: Refactor once https://github.com/ethereum/fe/issues/314 is resolved
     yul::Statement::FunctionDefinition(yul::FunctionDefinition {
@@ -506,11 +573,7 @@ fn checked_exp_unsigned() -> yul::Statement {
 }
 
 fn get_min_max(integer: Integer) -> (yul::Expression, yul::Expression) {
-    let map = numeric_min_max();
-
-    map.get(&integer)
-        .expect("Expected integer to be in map")
-        .to_owned()
+    numeric_min_max(integer)
 }
 
 fn get_max(integer: Integer) -> yul::Expression {
@@ -3,8 +3,11 @@ use yultsur::*;
 pub mod abi;
 pub mod contracts;
 pub mod data;
+pub mod function_values;
 pub mod math;
 pub mod revert;
+pub mod strings;
+pub mod vecs;
 
 /// Returns all functions that should be available during runtime.
 pub fn std() -> Vec<yul::Statement> {
@@ -14,6 +17,8 @@ pub fn std() -> Vec<yul::Statement> {
         data::all(),
         math::all(),
         revert::all(),
+        strings::all(),
+        vecs::all(),
     ]
     .concat()
 }
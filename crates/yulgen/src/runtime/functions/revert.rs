@@ -1,5 +1,6 @@
 use crate::names;
 use crate::operations::abi as abi_operations;
+use crate::operations::revert as revert_operations;
 use crate::types::AbiType;
 use yultsur::*;
 
@@ -26,22 +27,7 @@ pub fn panic_revert() -> yul::Statement {
 /// Note: The parentheses on a tuple are removed in the selector preimage.
 pub fn revert(name: &str, typ: &AbiType) -> yul::Statement {
     let func_name = names::revert(name, typ);
-    // the selector parens around a tuple are removed for the selector preimage
-    // e.g. we use `MyError(bool, address)` instead of `MyError((bool, address))`
-    let selector = {
-        let selector_params = match typ.clone() {
-            AbiType::Tuple { components } => components,
-            typ => vec![typ],
-        };
-        let selector = fe_abi::utils::func_selector(
-            name,
-            &selector_params
-                .iter()
-                .map(AbiType::selector_name)
-                .collect::<Vec<_>>(),
-        );
-        literal_expression! { (selector) }
-    };
+    let selector = revert_operations::error_selector(name, typ);
     let val_expr = vec![expression! { val }];
     let encoding_size = abi_operations::encoding_size(&[typ.clone()], &val_expr);
     let encode_val = abi_operations::encode(&[typ.clone()], val_expr);
@@ -55,7 +41,24 @@ pub fn revert(name: &str, typ: &AbiType) -> yul::Statement {
     }
 }
 
+/// Generate a YUL function to revert with the return data of the last call,
+/// so that the revert data of a failed call is propagated verbatim.
+pub fn revert_with_return_data() -> yul::Statement {
+    function_definition! {
+        function revert_with_return_data() {
+            (let size := returndatasize())
+            (let ptr := alloc(size))
+            (returndatacopy(ptr, 0, size))
+            (revert(ptr, size))
+        }
+    }
+}
+
 /// Return all revert functions used by default.
 pub fn all() -> Vec<yul::Statement> {
-    vec![panic_revert(), error_revert_numeric()]
+    vec![
+        panic_revert(),
+        error_revert_numeric(),
+        revert_with_return_data(),
+    ]
 }
@@ -0,0 +1,138 @@
+use crate::constants::PANIC_OUT_OF_BOUNDS;
+use crate::operations::revert as revert_operations;
+
+use yultsur::*;
+
+/// Return all string runtime functions
+pub fn all() -> Vec<yul::Statement> {
+    vec![
+        int_to_string(),
+        string_concat(),
+        string_eq(),
+        string_from_vec(),
+        string_keccak256(),
+        string_lt(),
+        string_slice(),
+    ]
+}
+
+/// Concatenates two strings in memory into a newly allocated string.
+pub fn string_concat() -> yul::Statement {
+    function_definition! {
+        function string_concat(a, b) -> mptr {
+            (let a_len := mload(a))
+            (let b_len := mload(b))
+            (mptr := alloc(32))
+            (mstore(mptr, (add(a_len, b_len))))
+            // `alloc` hands out consecutive segments, so the content of `b`
+            // is copied right after the content of `a`.
+            (pop((mcopym((add(a, 32)), a_len))))
+            (pop((mcopym((add(b, 32)), b_len))))
+        }
+    }
+}
+
+/// Copies the bytes `start..end` of a string in memory into a newly
+/// allocated string. Reverts with a panic if the range is out of bounds.
+pub fn string_slice() -> yul::Statement {
+    function_definition! {
+        function string_slice(ptr, start, end) -> mptr {
+            (if (or((gt(start, end)), (gt(end, (mload(ptr)))))) {
+                [revert_operations::panic_revert(PANIC_OUT_OF_BOUNDS)]
+            })
+            (let len := sub(end, start))
+            (mptr := alloc(32))
+            (mstore(mptr, len))
+            (pop((mcopym((add((add(ptr, 32)), start)), len))))
+        }
+    }
+}
+
+/// Copies the items of a `Vec<u8>` into a newly allocated string. Reverts
+/// with a panic if the vec has more than `max_size` items.
+pub fn string_from_vec() -> yul::Statement {
+    function_definition! {
+        function string_from_vec(vec, max_size) -> mptr {
+            (let len := mload(vec))
+            (if (gt(len, max_size)) {
+                [revert_operations::panic_revert(PANIC_OUT_OF_BOUNDS)]
+            })
+            (mptr := alloc(32))
+            (mstore(mptr, len))
+            (pop((mcopym((mload((add(vec, 64)))), len))))
+        }
+    }
+}
+
+/// Returns 1 if two strings in memory have the same content, and 0 otherwise.
+pub fn string_eq() -> yul::Statement {
+    function_definition! {
+        function string_eq(a, b) -> result {
+            (let len := mload(a))
+            (result := and(
+                (eq(len, (mload(b)))),
+                (eq((keccak256((add(a, 32)), len)), (keccak256((add(b, 32)), len))))
+            ))
+        }
+    }
+}
+
+/// Returns the keccak256 hash of the content of a string in memory.
+pub fn string_keccak256() -> yul::Statement {
+    function_definition! {
+        function string_keccak256(ptr) -> hash {
+            (hash := keccak256((add(ptr, 32)), (mload(ptr))))
+        }
+    }
+}
+
+/// Returns 1 if the string `a` comes before the string `b` in lexicographic
+/// byte order, and 0 otherwise.
+pub fn string_lt() -> yul::Statement {
+    function_definition! {
+        function string_lt(a, b) -> result {
+            (let a_len := mload(a))
+            (let b_len := mload(b))
+            (let min_len := a_len)
+            (if (lt(b_len, a_len)) { (min_len := b_len) })
+            // A proper prefix comes first.
+            (result := lt(a_len, b_len))
+            (for {(let i := 0)} (lt(i, min_len)) {(i := add(i, 1))}
+            {
+                (let a_byte := byte(0, (mload((add((add(a, 32)), i))))))
+                (let b_byte := byte(0, (mload((add((add(b, 32)), i))))))
+                (if (iszero((eq(a_byte, b_byte)))) {
+                    (result := lt(a_byte, b_byte))
+                    // The first differing byte decides; end the loop.
+                    (i := min_len)
+                })
+            })
+        }
+    }
+}
+
+/// Returns a newly allocated string with the decimal digits of an integer,
+/// preceded by a minus sign if `is_signed` is 1 and the integer is negative.
+pub fn int_to_string() -> yul::Statement {
+    function_definition! {
+        function int_to_string(value, is_signed) -> mptr {
+            (let negative := and(is_signed, (slt(value, 0))))
+            (if negative { (value := sub(0, value)) })
+            // The digits are counted first, so they can be written from the
+            // last one to the first.
+            (let len := add(negative, 1))
+            (for {(let rest := div(value, 10))} (gt(rest, 0)) {(rest := div(rest, 10))}
+            {
+                (len := add(len, 1))
+            })
+            (mptr := alloc((add(32, len))))
+            (mstore(mptr, len))
+            (for {(let i := len)} (gt(i, negative)) {(i := sub(i, 1))}
+            {
+                (mstore8((add((add(mptr, 31)), i)), (add(48, (mod(value, 10))))))
+                (value := div(value, 10))
+            })
+            (if negative { (mstore8((add(mptr, 32)), 45)) })
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use crate::constants::{PANIC_EMPTY_ARRAY_POP, PANIC_OUT_OF_BOUNDS};
+use crate::operations::revert as revert_operations;
+
+use yultsur::*;
+
+/// Return all vec runtime functions
+pub fn all() -> Vec<yul::Statement> {
+    vec![
+        vec_clone(),
+        vec_from_string(),
+        vec_item(),
+        vec_new(),
+        vec_pop(),
+        vec_push(),
+        vec_realloc(),
+    ]
+}
+
+/// Allocates an empty vec.
+///
+/// A vec is a segment of three words: the length, the capacity, and a pointer
+/// to the items. Items are stored back to back using `inner_size` bytes each.
+pub fn vec_new() -> yul::Statement {
+    function_definition! {
+        function vec_new() -> vec {
+            (vec := alloc(96))
+            (mstore(vec, 0))
+            (mstore((add(vec, 32)), 0))
+            (mstore((add(vec, 64)), 0))
+        }
+    }
+}
+
+/// Moves the items of a vec into a newly allocated segment that can hold
+/// `capacity` items.
+pub fn vec_realloc() -> yul::Statement {
+    function_definition! {
+        function vec_realloc(vec, capacity, inner_size) {
+            // Segments are rounded up to whole words, so the last item can be
+            // copied along with the rest of its word.
+            (let items := alloc((mul((div((add((mul(capacity, inner_size)), 31)), 32)), 32))))
+            (let old_items := mload((add(vec, 64))))
+            (let size := mul((mload(vec)), inner_size))
+            (for {(let i := 0)} (lt(i, size)) {(i := add(i, 32))}
+            {
+                (mstore((add(items, i)), (mload((add(old_items, i))))))
+            })
+            (mstore((add(vec, 32)), capacity))
+            (mstore((add(vec, 64)), items))
+        }
+    }
+}
+
+/// Adds an item to the end of a vec, and doubles its capacity if it's full.
+/// Returns 0, like a function with unit return type.
+pub fn vec_push() -> yul::Statement {
+    function_definition! {
+        function vec_push(vec, item, inner_size) -> return_val {
+            (let len := mload(vec))
+            (if (eq(len, (mload((add(vec, 32)))))) {
+                (let capacity := mul(len, 2))
+                (if (lt(capacity, 4)) { (capacity := 4) })
+                (vec_realloc(vec, capacity, inner_size))
+            })
+            (mstoren((add((mload((add(vec, 64)))), (mul(len, inner_size)))), inner_size, item))
+            (mstore(vec, (add(len, 1))))
+        }
+    }
+}
+
+/// Removes the last item of a vec and returns it. Reverts with a panic if the
+/// vec is empty.
+pub fn vec_pop() -> yul::Statement {
+    function_definition! {
+        function vec_pop(vec, inner_size) -> item {
+            (let len := mload(vec))
+            (if (iszero(len)) {
+                [revert_operations::panic_revert(PANIC_EMPTY_ARRAY_POP)]
+            })
+            (len := sub(len, 1))
+            (mstore(vec, len))
+            (item := mloadn((add((mload((add(vec, 64)))), (mul(len, inner_size)))), inner_size))
+        }
+    }
+}
+
+/// Returns a pointer to the vec item at the requested index. Reverts with a
+/// panic if the index is out of bounds.
+pub fn vec_item() -> yul::Statement {
+    function_definition! {
+        function vec_item(vec, index, inner_size) -> ptr {
+            (if (iszero((lt(index, (mload(vec)))))) {
+                [revert_operations::panic_revert(PANIC_OUT_OF_BOUNDS)]
+            })
+            (ptr := add((mload((add(vec, 64)))), (mul(index, inner_size))))
+        }
+    }
+}
+
+/// Copies a vec and its items into newly allocated memory.
+pub fn vec_clone() -> yul::Statement {
+    function_definition! {
+        function vec_clone(vec, inner_size) -> new_vec {
+            (new_vec := mcopym(vec, 96))
+            (vec_realloc(new_vec, (mload(vec)), inner_size))
+        }
+    }
+}
+
+/// Copies the bytes of a string in memory into a newly allocated `Vec<u8>`.
+pub fn vec_from_string() -> yul::Statement {
+    function_definition! {
+        function vec_from_string(ptr) -> vec {
+            (let len := mload(ptr))
+            (vec := alloc(96))
+            (mstore(vec, len))
+            // The content of the string is used as the items, which
+            // `vec_realloc` copies into a segment of their own.
+            (mstore((add(vec, 64)), (add(ptr, 32))))
+            (vec_realloc(vec, len, 1))
+        }
+    }
+}
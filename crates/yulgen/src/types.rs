@@ -1,5 +1,5 @@
 use fe_analyzer::namespace::types::{
-    Array, Base, Contract, FeString, FixedSize, Integer, Struct, Tuple,
+    Array, Base, Contract, FeString, FeVec, FixedSize, Integer, Struct, Tuple,
 };
 use fe_analyzer::AnalyzerDb;
 
@@ -15,22 +15,18 @@ impl EvmSized for FixedSize {
             FixedSize::Array(array) => array.size(),
             FixedSize::Tuple(tuple) => tuple.size(),
             FixedSize::String(string) => string.size(),
+            FixedSize::Vec(vec) => vec.size(),
+            FixedSize::Function(_) => 32,
             FixedSize::Contract(contract) => contract.size(),
             FixedSize::Struct(val) => val.size(),
+            FixedSize::Enum(_) => unreachable!("enums are lowered to structs"),
         }
     }
 }
 
 impl EvmSized for Integer {
     fn size(&self) -> usize {
-        match self {
-            Integer::U8 | Integer::I8 => 1,
-            Integer::U16 | Integer::I16 => 2,
-            Integer::U32 | Integer::I32 => 4,
-            Integer::U64 | Integer::I64 => 8,
-            Integer::U128 | Integer::I128 => 16,
-            Integer::U256 | Integer::I256 => 32,
-        }
+        Integer::size(self)
     }
 }
 
@@ -69,6 +65,13 @@ impl EvmSized for FeString {
     }
 }
 
+impl EvmSized for FeVec {
+    /// A vec value is a pointer to the vec in memory.
+    fn size(&self) -> usize {
+        32
+    }
+}
+
 impl EvmSized for Contract {
     fn size(&self) -> usize {
         32
@@ -94,6 +97,19 @@ pub enum AbiDecodeLocation {
     Memory,
 }
 
+/// How strictly the ABI encoded arguments of external calls are validated
+/// when they're decoded from calldata.
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd, Ord, Eq, Hash)]
+pub enum AbiDecoding {
+    /// Reverts if a value has dirty high bits or if the calldata is longer
+    /// than the encoding of the arguments.
+    #[default]
+    Strict,
+    /// Cleans the high bits of values and ignores calldata that follows the
+    /// encoding of the arguments.
+    Lenient,
+}
+
 pub fn to_abi_types(db: &dyn AnalyzerDb, types: &[impl AsAbiType]) -> Vec<AbiType> {
     types.iter().map(|typ| typ.as_abi_type(db)).collect()
 }
@@ -173,8 +189,11 @@ impl AsAbiType for FixedSize {
             FixedSize::Array(array) => array.as_abi_type(db),
             FixedSize::Tuple(tuple) => tuple.as_abi_type(db),
             FixedSize::String(string) => string.as_abi_type(db),
+            FixedSize::Vec(_) => panic!("vec type is not abi encodable"),
+            FixedSize::Function(_) => panic!("function type is not abi encodable"),
             FixedSize::Contract(_) => AbiType::Address,
             FixedSize::Struct(val) => val.as_abi_type(db),
+            FixedSize::Enum(_) => unreachable!("enums are lowered to structs"),
         }
     }
 }
@@ -1,9 +1,14 @@
+use fe_analyzer::namespace::items::{ModuleId, ModuleSource};
+use fe_analyzer::namespace::selector::Selector;
+use fe_common::db::SourceDb;
+use fe_common::diagnostics::Diagnostic;
 use fe_yulgen::constructor;
 use fe_yulgen::names::abi as abi_names;
 use fe_yulgen::operations::{abi as abi_operations, data as data_operations};
 use fe_yulgen::runtime::abi_dispatcher;
 use fe_yulgen::runtime::functions::{abi as abi_functions, revert as revert_functions};
-use fe_yulgen::types::{AbiDecodeLocation, AbiType};
+use fe_yulgen::types::{AbiDecodeLocation, AbiDecoding, AbiType};
+use indexmap::IndexMap;
 use insta::assert_display_snapshot;
 use smol_str::SmolStr;
 use wasm_bindgen_test::wasm_bindgen_test;
@@ -29,25 +34,27 @@ macro_rules! test_yulgen {
 // constructor
 test_yulgen! { constructor_no_init,  constructor::build() }
 
-fn functions() -> Vec<(SmolStr, SmolStr, Vec<AbiType>, Option<AbiType>)> {
+fn functions() -> Vec<(Selector, SmolStr, Vec<AbiType>, Option<AbiType>, bool)> {
     vec![
         (
-            "hello_world".into(),
+            Selector::of_signature("hello_world()"),
             "$$somemod$hello_world".into(),
             vec![],
             Some(AbiType::String { max_size: 42 }),
+            false,
         ),
         (
-            "add".into(),
+            Selector::of_signature("add(uint256,uint256)"),
             "$$somemod$add".into(),
             vec![AbiType::Uint { size: 32 }, AbiType::Uint { size: 32 }],
             Some(AbiType::Uint { size: 32 }),
+            true,
         ),
     ]
 }
 
 // ABI dispatcher
-test_yulgen! { abi_dispatcher,  abi_dispatcher::dispatcher(&functions()) }
+test_yulgen! { abi_dispatcher,  abi_dispatcher::dispatcher(&functions(), None, None, &[]) }
 
 // ABI encoding functions
 test_yulgen! {
@@ -58,7 +65,7 @@ test_yulgen! {
 // ABI decoding functions
 test_yulgen! {
     abi_decode_data_address_bool_mem_function,
-    yul::Block { statements: abi_functions::decode_functions(&[AbiType::Bool, AbiType::Address], AbiDecodeLocation::Memory) }
+    yul::Block { statements: abi_functions::decode_functions(&[AbiType::Bool, AbiType::Address], AbiDecodeLocation::Memory, AbiDecoding::Strict) }
 }
 test_yulgen! {
     abi_decode_data_u256_bytes_string_bool_address_bytes_calldata_function,
@@ -69,28 +76,44 @@ test_yulgen! {
         AbiType::Bool,
         AbiType::Address,
         AbiType::Bytes { size: 100 },
-    ], AbiDecodeLocation::Calldata) }
+    ], AbiDecodeLocation::Calldata, AbiDecoding::Strict) }
 }
 test_yulgen! {
     abi_decode_component_uint256_mem_function,
-    abi_functions::decode_component_uint(32, AbiDecodeLocation::Memory)
+    abi_functions::decode_component_uint(32, AbiDecodeLocation::Memory, AbiDecoding::Strict)
 }
 test_yulgen! {
     abi_decode_component_int16_calldata_function,
-    abi_functions::decode_component_int(2, AbiDecodeLocation::Calldata)
+    abi_functions::decode_component_int(2, AbiDecodeLocation::Calldata, AbiDecoding::Strict)
 }
 test_yulgen! {
     abi_decode_component_bool_calldata_function,
-    abi_functions::decode_component_bool(AbiDecodeLocation::Calldata)
+    abi_functions::decode_component_bool(AbiDecodeLocation::Calldata, AbiDecoding::Strict)
 }
 test_yulgen! {
     abi_decode_component_address_mem_function,
-    abi_functions::decode_component_bool(AbiDecodeLocation::Memory)
+    abi_functions::decode_component_bool(AbiDecodeLocation::Memory, AbiDecoding::Strict)
 }
 test_yulgen! {
     abi_decode_component_static_array_address_calldata_function,
     abi_functions::decode_component_static_array(&AbiType::Address, 42, AbiDecodeLocation::Calldata)
 }
+test_yulgen! {
+    abi_decode_component_uint8_calldata_lenient_function,
+    abi_functions::decode_component_uint(1, AbiDecodeLocation::Calldata, AbiDecoding::Lenient)
+}
+test_yulgen! {
+    abi_decode_component_int16_calldata_lenient_function,
+    abi_functions::decode_component_int(2, AbiDecodeLocation::Calldata, AbiDecoding::Lenient)
+}
+test_yulgen! {
+    abi_decode_component_bool_calldata_lenient_function,
+    abi_functions::decode_component_bool(AbiDecodeLocation::Calldata, AbiDecoding::Lenient)
+}
+test_yulgen! {
+    abi_decode_component_address_calldata_lenient_function,
+    abi_functions::decode_component_address(AbiDecodeLocation::Calldata, AbiDecoding::Lenient)
+}
 test_yulgen! {
     abi_decode_component_tuple_u256_address_mem_function,
     abi_functions::decode_component_tuple(
@@ -100,21 +123,33 @@ test_yulgen! {
 }
 test_yulgen! {
     abi_decode_component_bytes_26_mem_function,
-    abi_functions::decode_component_bytes(26, AbiDecodeLocation::Memory)
+    abi_functions::decode_component_bytes(26, AbiDecodeLocation::Memory, AbiDecoding::Strict)
 }
 test_yulgen! {
     abi_decode_component_string_26_calldata_function,
-    abi_functions::decode_component_bytes(26, AbiDecodeLocation::Calldata)
+    abi_functions::decode_component_bytes(26, AbiDecodeLocation::Calldata, AbiDecoding::Strict)
 }
 
 // data operations
 test_yulgen! {
     emit_event_no_indexed_operation,
-    data_operations::emit_event("MyEvent", &[(AbiType::Uint { size: 32 }, false), (AbiType::Address, false)], vec![expression! { 26 }, expression! { 0x42 }])
+    data_operations::emit_event("MyEvent", &[(AbiType::Uint { size: 32 }, false), (AbiType::Address, false)], false, false, vec![expression! { 26 }, expression! { 0x42 }])
 }
 test_yulgen! {
     emit_event_one_indexed_operation,
-    data_operations::emit_event("MyEvent", &[(AbiType::Uint { size: 32 }, true), (AbiType::Address, false)], vec![expression! { 26 }, expression! { 0x42 }])
+    data_operations::emit_event("MyEvent", &[(AbiType::Uint { size: 32 }, true), (AbiType::Address, false)], false, false, vec![expression! { 26 }, expression! { 0x42 }])
+}
+test_yulgen! {
+    emit_event_indexed_string_operation,
+    data_operations::emit_event("MyEvent", &[(AbiType::String { max_size: 26 }, true), (AbiType::Address, false)], false, false, vec![expression! { 0x100 }, expression! { 0x42 }])
+}
+test_yulgen! {
+    emit_event_anonymous_operation,
+    data_operations::emit_event("MyEvent", &[(AbiType::Uint { size: 32 }, true), (AbiType::Address, false)], true, false, vec![expression! { 26 }, expression! { 0x42 }])
+}
+test_yulgen! {
+    emit_event_packed_operation,
+    data_operations::emit_event("MyEvent", &[(AbiType::Uint { size: 2 }, true), (AbiType::String { max_size: 26 }, false), (AbiType::Address, false)], false, true, vec![expression! { 26 }, expression! { 0x100 }, expression! { 0x42 }])
 }
 test_yulgen! {
     sum_operation,
@@ -162,3 +197,67 @@ test_yulgen! {
     revert_string_error,
     revert_functions::error_revert(&AbiType::String { max_size: 3 })
 }
+
+/// Items that an edit doesn't touch keep their ids and the ids of their nodes.
+/// The Yul generated after each edit must be the same as the Yul generated from
+/// the edited source from scratch.
+fn assert_yul_survives_edits(versions: &[String]) {
+    fn compile(db: &mut fe_yulgen::Db, module: ModuleId) -> IndexMap<String, String> {
+        assert!(!module.diagnostics(db).iter().any(Diagnostic::is_error));
+        let lowered_module = fe_lowering::lower_main_module(db, module);
+        fe_yulgen::compile(db, lowered_module)
+    }
+
+    let mut db = fe_yulgen::Db::default();
+    let module = ModuleId::new_standalone(&mut db, "unrelated_edit.fe", &versions[0]);
+    let file = match module.data(&db).source {
+        ModuleSource::File(file) => file,
+        _ => unreachable!(),
+    };
+    compile(&mut db, module);
+    for version in &versions[1..] {
+        db.set_file_content(file, version.as_str().into());
+        let yul = compile(&mut db, module);
+
+        let mut fresh_db = fe_yulgen::Db::default();
+        let fresh_module = ModuleId::new_standalone(&mut fresh_db, "unrelated_edit.fe", version);
+        let expected = compile(&mut fresh_db, fresh_module);
+        assert_eq!(yul, expected, "Yul after edit to:\n{}", version);
+    }
+}
+
+#[test]
+fn yul_survives_unrelated_edit() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    let edited = src.replace("return 1", "return 100");
+    assert_yul_survives_edits(&[src.to_string(), edited.clone(), src.to_string(), edited]);
+}
+
+#[test]
+fn yul_survives_edit_in_middle() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    assert_yul_survives_edits(&[
+        src.to_string(),
+        src.replace("y: 1)", "y: 2)"),
+        src.replace("y: 1)", "y: 20)"),
+        src.to_string(),
+    ]);
+}
+
+#[test]
+fn yul_survives_reparse() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    assert_yul_survives_edits(&[src.to_string(), src.to_string()]);
+}
+
+#[test]
+fn yul_survives_comment_and_whitespace_edits() {
+    let src = test_files::fixture("lowering/unrelated_edit.fe");
+    assert_yul_survives_edits(&[
+        src.to_string(),
+        format!("# A new comment.\n\n{}", src),
+        src.replace("# Most of the tests", "# Most of our tests"),
+        src.replace("\n\nstruct Point", "\n\n\nstruct Point"),
+        src.to_string(),
+    ]);
+}